@@ -0,0 +1,265 @@
+//! For lining meshes and models up with the axes of the world.
+//!
+//! Every [`Alignment`] is computed from the bounding box of the vertices in world space, so
+//! positions of meshes are taken into account.
+//! Aligning only moves [`position`](crate::assets::Mesh::position)s, vertices stay untouched.
+//!
+//! [`fit_into_box`] additionally scales a model so files of any size show up the same size, which
+//! is what viewers and thumbnail renderers need.
+//!
+//! picoCAD's y-axis points down, so the bottom of a mesh is its largest y-coordinate and the
+//! ground is at `y = 0`.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::align::Alignment;
+//! use picocadrs::assets::{Axis, Mesh, Point3D};
+//! use picocadrs::point;
+//!
+//! let mut mesh = Mesh::plane();
+//! mesh.position = point!(3.0, -2.0, 0.5);
+//!
+//! mesh.align(Alignment::BottomToGround);
+//! mesh.align(Alignment::CenterX);
+//! mesh.align(Alignment::SnapMinTo(Axis::Z, 0.0));
+//!
+//! assert_eq!(mesh.position, point!(0.0, 0.0, 1.0));
+//! ```
+
+use crate::{
+    assets::{Axis, Mesh, Model, Point3D},
+    point,
+    spatial::Aabb,
+};
+
+/// Ways to line a bounding box up with the axes of the world.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Alignment {
+    /// Moves the bottom (largest y-coordinate) onto the ground at `y = 0`.
+    BottomToGround,
+    /// Moves the center onto `x = 0`.
+    CenterX,
+    /// Moves the center onto `y = 0`.
+    CenterY,
+    /// Moves the center onto `z = 0`.
+    CenterZ,
+    /// Moves the center onto the origin.
+    Center,
+    /// Moves the smallest coordinate on the axis to the given value.
+    SnapMinTo(Axis, f64),
+    /// Moves the largest coordinate on the axis to the given value.
+    SnapMaxTo(Axis, f64),
+}
+
+impl Alignment {
+    /// Returns how far a bounding box has to be moved to be aligned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::align::Alignment;
+    /// use picocadrs::assets::{Axis, Point3D};
+    /// use picocadrs::spatial::Aabb;
+    /// use picocadrs::point;
+    ///
+    /// let bounds = Aabb::new(point!(1.0, -2.0, 0.0), point!(3.0, -1.0, 4.0));
+    ///
+    /// assert_eq!(Alignment::BottomToGround.offset(&bounds), point!(0.0, 1.0, 0.0));
+    /// assert_eq!(Alignment::Center.offset(&bounds), point!(-2.0, 1.5, -2.0));
+    /// assert_eq!(
+    ///     Alignment::SnapMaxTo(Axis::Z, 1.0).offset(&bounds),
+    ///     point!(0.0, 0.0, -3.0)
+    /// );
+    /// ```
+    pub fn offset(&self, bounds: &Aabb) -> Point3D<f64> {
+        let center = bounds.center();
+        let mut offset = point!(0.0, 0.0, 0.0);
+
+        match self {
+            Alignment::BottomToGround => offset.y = -bounds.max.y,
+            Alignment::CenterX => offset.x = -center.x,
+            Alignment::CenterY => offset.y = -center.y,
+            Alignment::CenterZ => offset.z = -center.z,
+            Alignment::Center => offset = center * -1.0,
+            Alignment::SnapMinTo(axis, value) => {
+                *offset.axis_mut(*axis) = value - bounds.min.axis(*axis)
+            }
+            Alignment::SnapMaxTo(axis, value) => {
+                *offset.axis_mut(*axis) = value - bounds.max.axis(*axis)
+            }
+        }
+
+        offset
+    }
+}
+
+/// Returns the bounding box of the vertices of the mesh in world space, [`None`] if it has no
+/// vertices.
+pub fn world_bounds(mesh: &Mesh) -> Option<Aabb> {
+    Aabb::from_points(mesh.vertices.iter().map(|vertex| *vertex + mesh.position))
+}
+
+/// Moves the mesh so it is aligned and returns how far it was moved.
+///
+/// Meshes without vertices aren't moved.
+///
+/// It's recommended to use [`Mesh::align`].
+pub fn align_mesh(mesh: &mut Mesh, alignment: Alignment) -> Point3D<f64> {
+    let Some(bounds) = world_bounds(mesh) else {
+        return point!(0.0, 0.0, 0.0);
+    };
+
+    let offset = alignment.offset(&bounds);
+    mesh.position += offset;
+    offset
+}
+
+/// Moves every mesh of the model by the same amount so the model as a whole is aligned, and
+/// returns how far they were moved.
+///
+/// Meshes keep their places relative to each other.
+/// Models without vertices aren't moved.
+///
+/// It's recommended to use [`Model::align`].
+pub fn align_model(model: &mut Model, alignment: Alignment) -> Point3D<f64> {
+    let Some(bounds) = model
+        .meshes
+        .iter()
+        .filter_map(world_bounds)
+        .reduce(|a, b| a.union(&b))
+    else {
+        return point!(0.0, 0.0, 0.0);
+    };
+
+    let offset = alignment.offset(&bounds);
+    for mesh in model.meshes.iter_mut() {
+        mesh.position += offset;
+    }
+    offset
+}
+
+/// Scales the model uniformly so its longest side is `size` units long and moves its center onto
+/// the origin, then returns the factor it was scaled by.
+///
+/// Vertices and positions of meshes are both scaled, so meshes keep their places relative to each
+/// other.
+/// Models without vertices are left unchanged and models without any length are only moved, both
+/// return `1.0`.
+///
+/// It's recommended to use [`Model::fit_into_box`].
+pub fn fit_into_box(model: &mut Model, size: f64) -> f64 {
+    let Some(bounds) = model
+        .meshes
+        .iter()
+        .filter_map(world_bounds)
+        .reduce(|a, b| a.union(&b))
+    else {
+        return 1.0;
+    };
+
+    let extent = bounds.max - bounds.min;
+    let longest = extent.x.max(extent.y).max(extent.z);
+    let factor = if longest > 0.0 { size / longest } else { 1.0 };
+    let center = bounds.center();
+
+    for mesh in model.meshes.iter_mut() {
+        mesh.position = (mesh.position - center) * factor;
+        for vertex in mesh.vertices.iter_mut() {
+            *vertex = *vertex * factor;
+        }
+    }
+
+    factor
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn align_mesh_variants() {
+        let mut mesh = Mesh::plane();
+        mesh.vertices[0].y = -3.0;
+        mesh.position = point!(1.0, 2.0, 3.0);
+
+        assert_eq!(
+            mesh.align(Alignment::BottomToGround),
+            point!(0.0, -2.0, 0.0)
+        );
+        assert_eq!(mesh.align(Alignment::CenterY), point!(0.0, 1.5, 0.0));
+        assert_eq!(
+            mesh.align(Alignment::SnapMinTo(Axis::X, 2.0)),
+            point!(2.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            mesh.align(Alignment::SnapMaxTo(Axis::Y, 0.0)),
+            point!(0.0, -1.5, 0.0)
+        );
+        assert_eq!(mesh.position, point!(3.0, 0.0, 3.0));
+
+        mesh.align(Alignment::Center);
+        assert_eq!(mesh.position, point!(0.0, 1.5, 0.0));
+
+        let mut empty = Mesh::new("empty".to_string());
+        empty.position = point!(1.0, 1.0, 1.0);
+        assert_eq!(empty.align(Alignment::Center), point!(0.0, 0.0, 0.0));
+        assert_eq!(empty.position, point!(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn align_model_as_group() {
+        let mut right = Mesh::plane();
+        right.position = point!(4.0, -1.0, 0.0);
+
+        let mut model = Model {
+            meshes: vec![Mesh::plane(), right, Mesh::new("empty".to_string())],
+            ..Model::default()
+        };
+
+        assert_eq!(model.align(Alignment::CenterX), point!(-2.0, 0.0, 0.0));
+        assert_eq!(model.meshes[0].position, point!(-2.0, 0.0, 0.0));
+        assert_eq!(model.meshes[1].position, point!(2.0, -1.0, 0.0));
+        assert_eq!(model.meshes[2].position, point!(-2.0, 0.0, 0.0));
+
+        assert_eq!(
+            Model::default().align(Alignment::BottomToGround),
+            point!(0.0, 0.0, 0.0)
+        );
+    }
+    #[test]
+    fn align_fit_into_box() {
+        let mut right = Mesh::plane();
+        right.position = point!(6.0, -1.0, 0.0);
+
+        let mut model = Model {
+            meshes: vec![Mesh::plane(), right],
+            ..Model::default()
+        };
+
+        // the model spans 8 units along x.
+        assert_eq!(model.fit_into_box(2.0), 0.25);
+        assert_eq!(model.meshes[0].position, point!(-0.75, 0.125, 0.0));
+        assert_eq!(model.meshes[1].position, point!(0.75, -0.125, 0.0));
+        assert_eq!(model.meshes[1].vertices[2], point!(0.25, 0.0, 0.25));
+
+        let bounds = model
+            .meshes
+            .iter()
+            .filter_map(world_bounds)
+            .reduce(|a, b| a.union(&b))
+            .unwrap();
+        assert_eq!(bounds.min, point!(-1.0, -0.125, -0.25));
+        assert_eq!(bounds.max, point!(1.0, 0.125, 0.25));
+
+        let mut point = Model {
+            meshes: vec![Mesh::new("point".to_string())],
+            ..Model::default()
+        };
+        point.meshes[0].vertices.push(point!(1.0, 1.0, 1.0));
+        assert_eq!(point.fit_into_box(2.0), 1.0);
+        assert_eq!(point.meshes[0].position, point!(-1.0, -1.0, -1.0));
+
+        assert_eq!(Model::default().fit_into_box(2.0), 1.0);
+    }
+}
@@ -0,0 +1,241 @@
+//! For checking how evenly the texture is spread over the faces of a model.
+//!
+//! The texel density of a face is the amount of texture pixels along one unit of its surface,
+//! the square root of its [`uv_area`](crate::assets::Face::uv_area) in pixels divided by its
+//! [`world_area`](crate::assets::Face::world_area).
+//! A face with a density far above the rest of the model wastes texture space on detail nobody
+//! sees, a face far below it looks blurry and blocky next to its neighbours.
+//!
+//! [`DensityReport`] compares the density of every textured face to the median of the model and
+//! flags faces that are off by more than a given factor.
+//! Faces with [`no_texture`](crate::assets::Face::no_texture) and faces without area in 3D space
+//! are skipped.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Model, ProjectPreset};
+//!
+//! let mut model = Model::new_project("plane".to_string(), ProjectPreset::Plane);
+//! model.meshes.push(model.meshes[0].clone());
+//! model.meshes.push(model.meshes[0].clone());
+//!
+//! // the same plane, but squeezed onto a quarter of the pixels.
+//! for uv_map in model.meshes[2].faces[0].uv_maps.iter_mut() {
+//!     uv_map.coords = uv_map.coords * 0.25;
+//! }
+//!
+//! let report = model.texel_density_report();
+//!
+//! assert_eq!(report.median, 8.0);
+//! assert_eq!(report.flagged().len(), 1);
+//! assert_eq!(report.flagged()[0].face.mesh, 2);
+//! ```
+
+use crate::assets::{MeshFaceRef, Model};
+use std::fmt::{Display, Formatter};
+
+/// Factor by which the density of a face has to differ from the median to be flagged by
+/// [`DensityReport::flagged`].
+pub const DEFAULT_TOLERANCE: f64 = 2.0;
+
+/// Texel density of a single face.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FaceDensity {
+    /// The face this is about.
+    pub face: MeshFaceRef,
+    /// Area covered on the texture in pixels.
+    pub pixels: f64,
+    /// Area of the face in 3D space.
+    pub area: f64,
+    /// Pixels along one unit of the surface.
+    pub density: f64,
+    /// Density of the face divided by the median density of the model.
+    pub ratio: f64,
+}
+
+impl FaceDensity {
+    /// Returns `true` if the density of the face is more than `tolerance` times above or below
+    /// the median.
+    pub fn is_outlier(&self, tolerance: f64) -> bool {
+        self.ratio > tolerance || self.ratio < 1.0 / tolerance
+    }
+}
+
+impl Display for FaceDensity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mesh {}, face {}: {:.2} pixels per unit ({:.2}x the median)",
+            self.face.mesh, self.face.face, self.density, self.ratio
+        )
+    }
+}
+
+/// Texel densities of every textured face of a model.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DensityReport {
+    /// Density of every textured face with an area, in the order of their meshes and faces.
+    pub faces: Vec<FaceDensity>,
+    /// Median of all densities, `0.0` if there are no faces.
+    pub median: f64,
+}
+
+impl DensityReport {
+    /// Measures the density of every textured face of the model.
+    ///
+    /// It's recommended to use [`Model::texel_density_report`].
+    pub fn from_model(model: &Model) -> DensityReport {
+        let mut faces: Vec<FaceDensity> = vec![];
+
+        for (mesh_index, mesh) in model.meshes.iter().enumerate() {
+            for (face_index, face) in mesh.faces.iter().enumerate() {
+                if face.no_texture {
+                    continue;
+                }
+
+                let area = face.world_area(&mesh.vertices);
+                if area <= 0.0 {
+                    continue;
+                }
+
+                let pixels = face.uv_area() * 64.0;
+                faces.push(FaceDensity {
+                    face: MeshFaceRef::new(mesh_index, face_index),
+                    pixels,
+                    area,
+                    density: (pixels / area).sqrt(),
+                    ratio: 1.0,
+                });
+            }
+        }
+
+        let mut densities: Vec<f64> = faces.iter().map(|face| face.density).collect();
+        densities.sort_by(f64::total_cmp);
+        let median = match densities.len() {
+            0 => 0.0,
+            len if len % 2 == 1 => densities[len / 2],
+            len => (densities[len / 2 - 1] + densities[len / 2]) / 2.0,
+        };
+
+        for face in faces.iter_mut() {
+            face.ratio = if median > 0.0 {
+                face.density / median
+            } else if face.density > 0.0 {
+                f64::INFINITY
+            } else {
+                1.0
+            };
+        }
+
+        DensityReport { faces, median }
+    }
+
+    /// Returns the faces whose density is more than `tolerance` times above or below the median.
+    pub fn outliers(&self, tolerance: f64) -> Vec<&FaceDensity> {
+        self.faces
+            .iter()
+            .filter(|face| face.is_outlier(tolerance))
+            .collect()
+    }
+
+    /// Returns the faces whose density is off by more than [`DEFAULT_TOLERANCE`].
+    pub fn flagged(&self) -> Vec<&FaceDensity> {
+        self.outliers(DEFAULT_TOLERANCE)
+    }
+
+    /// Returns the density of the given face, [`None`] if it was skipped.
+    pub fn get(&self, face: MeshFaceRef) -> Option<&FaceDensity> {
+        self.faces.iter().find(|density| density.face == face)
+    }
+}
+
+impl Display for DensityReport {
+    /// Writes the median and one line for every [flagged](DensityReport::flagged) face.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "median: {:.2} pixels per unit", self.median)?;
+
+        for face in self.flagged() {
+            write!(f, "\n{}", face)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Face, Mesh, Point2D, Point3D, UVMap};
+    use crate::point;
+
+    /// A square of `size` units, mapped onto `pixels` by `pixels` pixels.
+    fn square(size: f64, pixels: f64) -> Mesh {
+        let mut mesh = Mesh::new("square".to_string());
+        mesh.vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(size, 0.0, 0.0),
+            point!(size, 0.0, size),
+            point!(0.0, 0.0, size),
+        ];
+
+        let uv = pixels / 8.0;
+        mesh.faces.push(Face {
+            uv_maps: vec![
+                UVMap::new(0, point!(0.0, 0.0)),
+                UVMap::new(1, point!(uv, 0.0)),
+                UVMap::new(2, point!(uv, uv)),
+                UVMap::new(3, point!(0.0, uv)),
+            ],
+            ..Face::default()
+        });
+
+        mesh
+    }
+
+    #[test]
+    fn density_from_model() {
+        let mut untextured = square(1.0, 64.0);
+        untextured.faces[0].no_texture = true;
+
+        let model = Model {
+            meshes: vec![
+                square(1.0, 8.0),
+                square(2.0, 16.0),
+                square(1.0, 32.0),
+                square(4.0, 8.0),
+                untextured,
+                square(0.0, 8.0),
+            ],
+            ..Model::default()
+        };
+
+        let report = model.texel_density_report();
+
+        assert_eq!(report.faces.len(), 4);
+        assert_eq!(report.median, 8.0);
+        assert_eq!(report.faces[2].pixels, 1024.0);
+        assert_eq!(report.faces[3].density, 2.0);
+        assert_eq!(report.get(MeshFaceRef::new(2, 0)).unwrap().ratio, 4.0);
+        assert!(report.get(MeshFaceRef::new(4, 0)).is_none());
+
+        let flagged: Vec<usize> = report.flagged().iter().map(|face| face.face.mesh).collect();
+        assert_eq!(flagged, vec![2, 3]);
+        assert!(report.outliers(5.0).is_empty());
+
+        assert_eq!(
+            report.to_string(),
+            "median: 8.00 pixels per unit
+mesh 2, face 0: 32.00 pixels per unit (4.00x the median)
+mesh 3, face 0: 2.00 pixels per unit (0.25x the median)"
+        );
+    }
+
+    #[test]
+    fn density_empty() {
+        let report = Model::default().texel_density_report();
+
+        assert_eq!(report, DensityReport::default());
+        assert!(report.flagged().is_empty());
+    }
+}
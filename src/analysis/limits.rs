@@ -0,0 +1,202 @@
+//! For checking models against what picoCAD can handle.
+//!
+//! picoCAD doesn't stop you from building big models, but it slows down noticeably once a model
+//! grows past a certain size and may become unusable or run out of memory.
+//! [`Limits`] describes these sizes, with [`Limits::V1`] as a profile for the original picoCAD.
+//!
+//! picoCAD doesn't document any limits, so the numbers of [`Limits::V1`] are conservative
+//! defaults rather than measured or enforced limits, and models above them may still work fine.
+//! Tools with other needs can build their own [`Limits`].
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::analysis::limits::{LimitViolation, Limits};
+//! use picocadrs::assets::{Mesh, Model, Point3D};
+//! use picocadrs::point;
+//!
+//! let mut model = Model::default();
+//! let mut mesh = Mesh::new("points".to_string());
+//! mesh.vertices = vec![point!(0.0, 0.0, 0.0); 2000];
+//! model.meshes.push(mesh);
+//!
+//! assert_eq!(
+//!     model.check_limits(&Limits::V1),
+//!     vec![LimitViolation::Vertices {
+//!         count: 2000,
+//!         limit: 1000
+//!     }]
+//! );
+//!
+//! let generous = Limits {
+//!     vertices: 4000,
+//!     ..Limits::V1
+//! };
+//! assert!(model.check_limits(&generous).is_empty());
+//! ```
+
+use crate::{
+    analysis::stats::{SAFE_FACE_COUNT, SAFE_VERTEX_COUNT},
+    assets::Model,
+};
+use std::fmt::{Display, Formatter};
+
+/// Biggest model a version of picoCAD can comfortably handle.
+///
+/// A model may have as many vertices, faces and meshes as given here, but not more.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Limits {
+    /// Amount of vertices across all meshes.
+    pub vertices: usize,
+    /// Amount of faces across all meshes.
+    pub faces: usize,
+    /// Amount of meshes.
+    pub meshes: usize,
+}
+
+impl Limits {
+    /// Conservative defaults for the original picoCAD.
+    ///
+    /// The vertex and face counts are the same as [`SAFE_VERTEX_COUNT`] and [`SAFE_FACE_COUNT`],
+    /// the mesh count is a round number that leaves plenty of room for typical models.
+    pub const V1: Limits = Limits {
+        vertices: SAFE_VERTEX_COUNT,
+        faces: SAFE_FACE_COUNT,
+        meshes: 64,
+    };
+
+    /// Returns every limit the model goes past, in the order vertices, faces, meshes.
+    ///
+    /// It's recommended to use [`Model::check_limits`].
+    pub fn check(&self, model: &Model) -> Vec<LimitViolation> {
+        let vertices = model.meshes.iter().map(|mesh| mesh.vertices.len()).sum();
+        let faces = model.meshes.iter().map(|mesh| mesh.faces.len()).sum();
+        let meshes = model.meshes.len();
+
+        let mut violations = vec![];
+
+        if vertices > self.vertices {
+            violations.push(LimitViolation::Vertices {
+                count: vertices,
+                limit: self.vertices,
+            });
+        }
+        if faces > self.faces {
+            violations.push(LimitViolation::Faces {
+                count: faces,
+                limit: self.faces,
+            });
+        }
+        if meshes > self.meshes {
+            violations.push(LimitViolation::Meshes {
+                count: meshes,
+                limit: self.meshes,
+            });
+        }
+
+        violations
+    }
+}
+
+impl Default for Limits {
+    /// Returns [`Limits::V1`].
+    fn default() -> Self {
+        Limits::V1
+    }
+}
+
+/// A limit a model goes past, returned by [`Model::check_limits`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum LimitViolation {
+    /// The model has too many vertices.
+    Vertices {
+        /// Amount of vertices the model has.
+        count: usize,
+        /// Amount of vertices it may have.
+        limit: usize,
+    },
+    /// The model has too many faces.
+    Faces {
+        /// Amount of faces the model has.
+        count: usize,
+        /// Amount of faces it may have.
+        limit: usize,
+    },
+    /// The model has too many meshes.
+    Meshes {
+        /// Amount of meshes the model has.
+        count: usize,
+        /// Amount of meshes it may have.
+        limit: usize,
+    },
+}
+
+impl LimitViolation {
+    /// Returns how far the model goes past the limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::analysis::limits::LimitViolation;
+    ///
+    /// let violation = LimitViolation::Faces {
+    ///     count: 620,
+    ///     limit: 500,
+    /// };
+    ///
+    /// assert_eq!(violation.excess(), 120);
+    /// ```
+    pub fn excess(&self) -> usize {
+        match *self {
+            LimitViolation::Vertices { count, limit }
+            | LimitViolation::Faces { count, limit }
+            | LimitViolation::Meshes { count, limit } => count.saturating_sub(limit),
+        }
+    }
+}
+
+impl Display for LimitViolation {
+    /// Writes the violation like `620 faces (limit 500)`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (count, limit, what) = match *self {
+            LimitViolation::Vertices { count, limit } => (count, limit, "vertices"),
+            LimitViolation::Faces { count, limit } => (count, limit, "faces"),
+            LimitViolation::Meshes { count, limit } => (count, limit, "meshes"),
+        };
+
+        write!(f, "{} {} (limit {})", count, what, limit)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Face, Mesh};
+
+    #[test]
+    fn limits_check() {
+        let mut model = Model::default();
+        assert!(model.check_limits(&Limits::default()).is_empty());
+
+        let mut mesh = Mesh::new("big".to_string());
+        mesh.faces = vec![Face::default(); 3];
+        model.meshes = vec![mesh; 3];
+
+        let limits = Limits {
+            vertices: 0,
+            faces: 8,
+            meshes: 2,
+        };
+        let violations = model.check_limits(&limits);
+
+        assert_eq!(
+            violations,
+            vec![
+                LimitViolation::Faces { count: 9, limit: 8 },
+                LimitViolation::Meshes { count: 3, limit: 2 },
+            ]
+        );
+        assert_eq!(violations[0].to_string(), "9 faces (limit 8)");
+        assert_eq!(violations[1].excess(), 1);
+    }
+}
@@ -0,0 +1,17 @@
+//! For analysing models without changing them.
+//!
+//! - _[`density`]:_ How many pixels of the texture each face gets for its size.
+//! - _[`limits`]:_ Checking models against the sizes picoCAD can handle.
+//! - _[`overlaps`]:_ Faces that share parts of the texture.
+//! - _[`stats`]:_ General statistics of a model, like the amount of vertices and faces.
+//! - _[`summary`]:_ A short readable overview of a model and its meshes.
+//! - _[`topology`]:_ How the faces of a mesh are connected through their edges.
+//! - _[`usage`]:_ Which faces sample which pixels of the texture.
+
+pub mod density;
+pub mod limits;
+pub mod overlaps;
+pub mod stats;
+pub mod summary;
+pub mod topology;
+pub mod usage;
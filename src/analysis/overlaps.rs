@@ -0,0 +1,86 @@
+//! For finding faces that share parts of the texture.
+//!
+//! Faces sample the texture through the polygon spanned by their uv-coordinates.
+//! If the polygons of two faces cover the same pixels, painting on one face changes the other as
+//! well.
+//! This is sometimes intended (like every side of a crate using the same texture) but often
+//! happens by accident and leads to textures bleeding between faces.
+//!
+//! Which pixels a face covers is decided by [`Face::uv_texels`](crate::assets::Face::uv_texels).
+//! Faces with [`no_texture`](crate::assets::Face) enabled don't sample the texture and are
+//! ignored.
+
+use crate::{
+    analysis::usage::TextureUsage,
+    assets::{Footer, MeshFaceRef, Model, Point2D},
+    point,
+};
+use std::collections::BTreeSet;
+
+/// Finds all pairs of textured faces that share at least one pixel of the texture.
+///
+/// It's recommended to use [`Model::uv_overlaps`].
+pub fn uv_overlaps(model: &Model) -> Vec<(MeshFaceRef, MeshFaceRef)> {
+    let usage = TextureUsage::from_model(model);
+    let mut pairs: BTreeSet<(MeshFaceRef, MeshFaceRef)> = BTreeSet::new();
+
+    for v in 0..Footer::HEIGHT {
+        for u in 0..Footer::WIDTH {
+            let owners = usage.faces(point!(u, v));
+
+            for (i, first) in owners.iter().enumerate() {
+                for second in owners.iter().skip(i + 1) {
+                    pairs.insert((*first.min(second), *first.max(second)));
+                }
+            }
+        }
+    }
+
+    pairs.into_iter().collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::Mesh;
+    use crate::fixtures::uv_face;
+
+    #[test]
+    fn overlaps_uv_overlaps() {
+        let mut first = Mesh::new("first".to_string());
+        first.faces = vec![
+            uv_face(point!(0.0, 0.0), point!(1.0, 1.0)),
+            uv_face(point!(1.0, 0.0), point!(2.0, 1.0)),
+            uv_face(point!(0.5, 0.5), point!(1.5, 1.5)),
+        ];
+
+        let mut second = Mesh::new("second".to_string());
+        second.faces = vec![
+            uv_face(point!(3.0, 3.0), point!(4.0, 4.0)),
+            uv_face(point!(1.75, 0.75), point!(2.0, 1.0)),
+        ];
+
+        let mut untextured = uv_face(point!(0.0, 0.0), point!(2.0, 2.0));
+        untextured.no_texture = true;
+        second.faces.push(untextured);
+
+        let model = Model {
+            meshes: vec![first, second],
+            ..Model::default()
+        };
+
+        assert_eq!(
+            model.uv_overlaps(),
+            vec![
+                (MeshFaceRef::new(0, 0), MeshFaceRef::new(0, 2)),
+                (MeshFaceRef::new(0, 1), MeshFaceRef::new(0, 2)),
+                (MeshFaceRef::new(0, 1), MeshFaceRef::new(1, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn overlaps_none() {
+        assert!(Model::default().uv_overlaps().is_empty());
+    }
+}
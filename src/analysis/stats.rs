@@ -0,0 +1,207 @@
+//! For summarizing a model in numbers.
+//!
+//! [`ModelStats`] holds counts of the different parts of a model as well as how much of the
+//! texture is in use.
+//! It also tells if a model grows bigger than picoCAD can comfortably handle.
+//! picoCAD doesn't stop you from building such models, but it slows down noticeably and may
+//! become unusable.
+//! To check models against other limits, see [`limits`](crate::analysis::limits).
+
+use crate::{
+    analysis::usage::TextureUsage,
+    assets::{Color, Footer, Model},
+};
+use std::collections::HashMap;
+
+/// Amount of vertices a model can have before picoCAD starts to slow down noticeably.
+pub const SAFE_VERTEX_COUNT: usize = 1000;
+/// Amount of faces a model can have before picoCAD starts to slow down noticeably.
+pub const SAFE_FACE_COUNT: usize = 500;
+/// Amount of pixels the texture has.
+const TEXEL_COUNT: usize = Footer::WIDTH * Footer::HEIGHT;
+
+/// Statistics of a model.
+///
+/// Can be created with [`Model::stats`].
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Model;
+///
+/// let stats = Model::default().stats();
+///
+/// assert_eq!(stats.meshes, 0);
+/// assert_eq!(stats.texture_usage, 0.0);
+/// assert!(!stats.exceeds_limits());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelStats {
+    /// Amount of meshes.
+    pub meshes: usize,
+    /// Amount of vertices across all meshes.
+    pub vertices: usize,
+    /// Amount of faces across all meshes.
+    pub faces: usize,
+    /// Amount of faces with 3 vertices.
+    pub triangles: usize,
+    /// Amount of faces with 4 vertices.
+    pub quads: usize,
+    /// Amount of faces with less than 3 or more than 4 vertices.
+    pub other_faces: usize,
+    /// Amount of faces that have a texture mapped onto them.
+    pub textured_faces: usize,
+    /// Amount of faces without texture for each color they use.
+    pub colored_faces: HashMap<Color, usize>,
+    /// Percentage (`0.0 - 100.0`) of the texture's pixels that are mapped onto at least one
+    /// textured face.
+    pub texture_usage: f64,
+}
+
+impl ModelStats {
+    /// Collects the statistics of the given model.
+    ///
+    /// It's recommended to use [`Model::stats`].
+    pub fn from_model(model: &Model) -> ModelStats {
+        let mut stats = ModelStats {
+            meshes: model.meshes.len(),
+            vertices: 0,
+            faces: 0,
+            triangles: 0,
+            quads: 0,
+            other_faces: 0,
+            textured_faces: 0,
+            colored_faces: HashMap::new(),
+            texture_usage: 0.0,
+        };
+
+        for mesh in model.meshes.iter() {
+            stats.vertices += mesh.vertices.len();
+            stats.faces += mesh.faces.len();
+
+            for face in mesh.faces.iter() {
+                match face.uv_maps.len() {
+                    3 => stats.triangles += 1,
+                    4 => stats.quads += 1,
+                    _ => stats.other_faces += 1,
+                }
+
+                if face.no_texture {
+                    *stats.colored_faces.entry(face.color).or_insert(0) += 1;
+                } else {
+                    stats.textured_faces += 1;
+                }
+            }
+        }
+
+        stats.texture_usage =
+            TextureUsage::from_model(model).used_count() as f64 / TEXEL_COUNT as f64 * 100.0;
+
+        stats
+    }
+
+    /// Checks if the model has more vertices than [`SAFE_VERTEX_COUNT`].
+    pub fn exceeds_vertex_limit(&self) -> bool {
+        self.vertices > SAFE_VERTEX_COUNT
+    }
+
+    /// Checks if the model has more faces than [`SAFE_FACE_COUNT`].
+    pub fn exceeds_face_limit(&self) -> bool {
+        self.faces > SAFE_FACE_COUNT
+    }
+
+    /// Checks if the model exceeds any of the limits picoCAD can comfortably handle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// let mut mesh = Mesh::new("points".to_string());
+    /// mesh.vertices = vec![point!(0.0, 0.0, 0.0); 2000];
+    /// model.meshes.push(mesh);
+    ///
+    /// let stats = model.stats();
+    ///
+    /// assert!(stats.exceeds_vertex_limit());
+    /// assert!(!stats.exceeds_face_limit());
+    /// assert!(stats.exceeds_limits());
+    /// ```
+    pub fn exceeds_limits(&self) -> bool {
+        self.exceeds_vertex_limit() || self.exceeds_face_limit()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Face, Mesh, Point2D, Point3D, UVMap};
+    use crate::point;
+
+    #[test]
+    fn stats_from_model() {
+        let mut mesh = Mesh::new("mesh".to_string());
+        mesh.vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(1.0, 0.0, 0.0),
+            point!(1.0, 1.0, 0.0),
+            point!(0.0, 1.0, 0.0),
+        ];
+
+        let quad = Face {
+            uv_maps: vec![
+                UVMap::new(0, point!(0.0, 0.0)),
+                UVMap::new(1, point!(2.0, 0.0)),
+                UVMap::new(2, point!(2.0, 1.5)),
+                UVMap::new(3, point!(0.0, 1.5)),
+            ],
+            ..Face::default()
+        };
+
+        let mut triangle = quad.clone();
+        triangle.uv_maps.pop();
+        triangle.no_texture = true;
+        triangle.color = Color::Red;
+
+        let mut line = triangle.clone();
+        line.uv_maps.pop();
+
+        mesh.faces = vec![quad.clone(), quad, triangle, line];
+
+        let model = Model {
+            meshes: vec![mesh.clone(), mesh],
+            ..Model::default()
+        };
+
+        let stats = model.stats();
+
+        assert_eq!(stats.meshes, 2);
+        assert_eq!(stats.vertices, 8);
+        assert_eq!(stats.faces, 8);
+        assert_eq!(stats.triangles, 2);
+        assert_eq!(stats.quads, 4);
+        assert_eq!(stats.other_faces, 2);
+        assert_eq!(stats.textured_faces, 4);
+        assert_eq!(stats.colored_faces.get(&Color::Red), Some(&4));
+        assert_eq!(stats.colored_faces.len(), 1);
+        assert_eq!(stats.texture_usage, 16.0 * 12.0 / 15360.0 * 100.0);
+        assert!(!stats.exceeds_limits());
+    }
+
+    #[test]
+    fn stats_limits() {
+        let mut mesh = Mesh::new("mesh".to_string());
+        mesh.faces = vec![Face::default(); SAFE_FACE_COUNT + 1];
+
+        let mut model = Model::default();
+        model.meshes.push(mesh);
+
+        let stats = model.stats();
+
+        assert!(stats.exceeds_face_limit());
+        assert!(!stats.exceeds_vertex_limit());
+        assert!(stats.exceeds_limits());
+    }
+}
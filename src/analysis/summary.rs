@@ -0,0 +1,219 @@
+//! For getting a quick overview of a model.
+//!
+//! The [`Display`] implementation of [`Model`] writes the whole project file and its [`Debug`]
+//! output lists every single vertex, neither of which is readable for anything but the smallest
+//! models.
+//! [`ModelSummary`] only keeps counts per mesh and writes them as a short report instead.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Mesh, Model};
+//!
+//! let mut model = Model::new_named("house".to_string());
+//! model.meshes.push(Mesh::plane());
+//!
+//! assert_eq!(
+//!     model.summary().to_string(),
+//!     "\"house\": 1 mesh, 4 vertices, 1 face, 1.67% of the texture used\n  \
+//!        mesh 0 \"plane\": 4 vertices, 1 face (1 textured)"
+//! );
+//! ```
+
+use crate::{
+    analysis::stats::ModelStats,
+    assets::{Color, Mesh, Model},
+};
+use std::fmt::{Display, Formatter};
+
+/// Short overview of a model.
+///
+/// Can be created with [`Model::summary`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelSummary {
+    /// Name of the project.
+    pub name: String,
+    /// Statistics of the whole model.
+    pub stats: ModelStats,
+    /// Overview of every mesh in the same order as [`meshes`](Model::meshes).
+    pub meshes: Vec<MeshSummary>,
+}
+
+impl ModelSummary {
+    /// Collects the summary of the given model.
+    ///
+    /// It's recommended to use [`Model::summary`].
+    pub fn from_model(model: &Model) -> ModelSummary {
+        ModelSummary {
+            name: model.header.name.clone(),
+            stats: model.stats(),
+            meshes: model.meshes.iter().map(MeshSummary::from_mesh).collect(),
+        }
+    }
+}
+
+impl Display for ModelSummary {
+    /// Writes one line for the model followed by an indented line for each mesh.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?}: {}, {}, {}, {:.2}% of the texture used",
+            self.name,
+            count(self.stats.meshes, "mesh", "meshes"),
+            count(self.stats.vertices, "vertex", "vertices"),
+            count(self.stats.faces, "face", "faces"),
+            self.stats.texture_usage
+        )?;
+
+        if self.stats.exceeds_limits() {
+            write!(f, " (too big for picoCAD)")?;
+        }
+
+        for (index, mesh) in self.meshes.iter().enumerate() {
+            write!(f, "\n  mesh {} {}", index, mesh)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Short overview of a single mesh.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MeshSummary {
+    /// Name of the mesh.
+    pub name: String,
+    /// Amount of vertices.
+    pub vertices: usize,
+    /// Amount of faces.
+    pub faces: usize,
+    /// Amount of faces that have a texture mapped onto them.
+    pub textured_faces: usize,
+    /// Colors of the faces without texture, each only once and ordered by their number.
+    pub colors: Vec<Color>,
+}
+
+impl MeshSummary {
+    /// Collects the summary of the given mesh.
+    pub fn from_mesh(mesh: &Mesh) -> MeshSummary {
+        let mut colors: Vec<Color> = mesh
+            .faces
+            .iter()
+            .filter(|face| face.no_texture)
+            .map(|face| face.color)
+            .collect();
+
+        colors.sort_by_key(|color| color.as_i32());
+        colors.dedup();
+
+        MeshSummary {
+            name: mesh.name.clone(),
+            vertices: mesh.vertices.len(),
+            faces: mesh.faces.len(),
+            textured_faces: mesh.faces.iter().filter(|face| !face.no_texture).count(),
+            colors,
+        }
+    }
+}
+
+impl Display for MeshSummary {
+    /// Writes the name and counts of the mesh, like
+    /// `"cube": 8 vertices, 6 faces (2 textured, colors 8, 12)`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?}: {}, {}",
+            self.name,
+            count(self.vertices, "vertex", "vertices"),
+            count(self.faces, "face", "faces")
+        )?;
+
+        let mut details: Vec<String> = vec![];
+
+        if self.textured_faces > 0 {
+            details.push(format!("{} textured", self.textured_faces));
+        }
+        if !self.colors.is_empty() {
+            let colors: Vec<String> = self
+                .colors
+                .iter()
+                .map(|color| color.as_i32().to_string())
+                .collect();
+            details.push(format!("colors {}", colors.join(", ")));
+        }
+
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes `amount` followed by the singular or plural form of a word.
+fn count(amount: usize, singular: &str, plural: &str) -> String {
+    if amount == 1 {
+        format!("{} {}", amount, singular)
+    } else {
+        format!("{} {}", amount, plural)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::analysis::stats::SAFE_FACE_COUNT;
+    use crate::assets::Face;
+
+    #[test]
+    fn summary_display() {
+        let mut mesh = Mesh::new("cube".to_string());
+        mesh.faces = vec![
+            Face {
+                no_texture: true,
+                color: Color::Pink,
+                ..Face::default()
+            },
+            Face {
+                no_texture: true,
+                color: Color::Red,
+                ..Face::default()
+            },
+            Face {
+                no_texture: true,
+                color: Color::Pink,
+                ..Face::default()
+            },
+        ];
+
+        let mut model = Model::default();
+        model.meshes.push(mesh);
+        model.meshes.push(Mesh::new("empty".to_string()));
+
+        let summary = model.summary();
+
+        assert_eq!(summary.meshes[0].colors, vec![Color::Red, Color::Pink]);
+        assert_eq!(
+            summary.to_string(),
+            "\"unnamed\": 2 meshes, 0 vertices, 3 faces, 0.00% of the texture used\n  \
+               mesh 0 \"cube\": 0 vertices, 3 faces (colors 8, 14)\n  \
+               mesh 1 \"empty\": 0 vertices, 0 faces"
+        );
+    }
+
+    #[test]
+    fn summary_limits() {
+        let mut mesh = Mesh::new("big".to_string());
+        mesh.faces = vec![Face::default(); SAFE_FACE_COUNT + 1];
+
+        let mut model = Model::default();
+        model.meshes.push(mesh);
+
+        assert!(model
+            .summary()
+            .to_string()
+            .lines()
+            .next()
+            .unwrap()
+            .ends_with("(too big for picoCAD)"));
+    }
+}
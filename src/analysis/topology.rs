@@ -0,0 +1,130 @@
+//! For inspecting how the faces of a mesh are connected.
+//!
+//! Two faces are connected if they share an [`Edge`], which is a pair of vertices that follow
+//! each other in both faces.
+//! In a closed (watertight) mesh every edge is used by exactly 2 faces.
+//! Edges used by a single face are on the border of a hole, edges used by more than 2 faces
+//! are non-manifold and usually a sign of overlapping or duplicated geometry.
+
+use crate::assets::Mesh;
+use std::collections::BTreeMap;
+
+/// Undirected connection between two vertices of a mesh.
+///
+/// The vertex indices are always stored with the smaller index first, so the same edge always
+/// compares equal no matter which face it was taken from.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::analysis::topology::Edge;
+///
+/// let edge = Edge::new(3, 1);
+///
+/// assert_eq!(edge, Edge::new(1, 3));
+/// assert_eq!(edge.vertices(), (1, 3));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Edge(usize, usize);
+
+impl Edge {
+    /// Creates the edge between the vertices with index `a` and `b`.
+    pub fn new(a: usize, b: usize) -> Edge {
+        Edge(a.min(b), a.max(b))
+    }
+
+    /// Returns the indices of both vertices with the smaller one first.
+    pub fn vertices(&self) -> (usize, usize) {
+        (self.0, self.1)
+    }
+}
+
+/// Maps every edge of the mesh to the indices of the faces using it.
+///
+/// It's recommended to use [`Mesh::edge_adjacency`].
+pub fn edge_adjacency(mesh: &Mesh) -> BTreeMap<Edge, Vec<usize>> {
+    let mut adjacency: BTreeMap<Edge, Vec<usize>> = BTreeMap::new();
+
+    for (face_index, face) in mesh.faces.iter().enumerate() {
+        let count = face.uv_maps.len();
+
+        if count < 2 {
+            continue;
+        }
+
+        for i in 0..count {
+            let a = face.uv_maps[i].vertex_index;
+            let b = face.uv_maps[(i + 1) % count].vertex_index;
+
+            // lines (2 vertices) would otherwise list their only edge twice.
+            if a == b || (count == 2 && i == 1) {
+                continue;
+            }
+
+            adjacency
+                .entry(Edge::new(a, b))
+                .or_default()
+                .push(face_index);
+        }
+    }
+
+    adjacency
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::Point3D;
+    use crate::fixtures::{cube, face};
+    use crate::point;
+
+    #[test]
+    fn topology_edge_adjacency() {
+        let mesh = cube();
+        let adjacency = mesh.edge_adjacency();
+
+        assert_eq!(adjacency.len(), 12);
+        assert!(adjacency.values().all(|faces| faces.len() == 2));
+        assert_eq!(adjacency[&Edge::new(0, 1)], vec![0, 2]);
+        assert_eq!(mesh.edges().len(), 12);
+    }
+
+    #[test]
+    fn topology_watertight() {
+        let mut mesh = cube();
+        assert!(mesh.is_watertight());
+        assert!(mesh.boundary_edges().is_empty());
+        assert!(mesh.non_manifold_edges().is_empty());
+
+        mesh.faces.remove(0);
+        assert!(!mesh.is_watertight());
+        assert_eq!(
+            mesh.boundary_edges(),
+            vec![
+                Edge::new(0, 1),
+                Edge::new(0, 3),
+                Edge::new(1, 2),
+                Edge::new(2, 3)
+            ]
+        );
+
+        let mut mesh = cube();
+        mesh.faces.push(face(&[0, 1, 6]));
+        assert!(!mesh.is_watertight());
+        assert_eq!(mesh.non_manifold_edges(), vec![Edge::new(0, 1)]);
+        assert_eq!(
+            mesh.boundary_edges(),
+            vec![Edge::new(0, 6), Edge::new(1, 6)]
+        );
+    }
+
+    #[test]
+    fn topology_lines_and_empty() {
+        let mut mesh = Mesh::new("line".to_string());
+        assert!(!mesh.is_watertight());
+
+        mesh.vertices = vec![point!(0.0, 0.0, 0.0); 2];
+        mesh.faces = vec![face(&[0, 1])];
+        assert_eq!(mesh.edge_adjacency()[&Edge::new(0, 1)], vec![0]);
+    }
+}
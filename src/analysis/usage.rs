@@ -0,0 +1,210 @@
+//! For finding out which faces sample which pixels of the texture.
+//!
+//! [`TextureUsage`] stores the faces sampling each pixel of the texture, using the same rules as
+//! [`Face::uv_texels`](crate::assets::Face::uv_texels).
+//! Pixels that aren't sampled by any face can be painted over or reused without changing how the
+//! model looks, which makes the map useful for finding free space before moving uv-coordinates
+//! around.
+//!
+//! Faces with [`no_texture`](crate::assets::Face) enabled don't sample the texture and are
+//! ignored.
+
+use crate::{
+    assets::{Footer, MeshFaceRef, Model, Point2D},
+    point,
+};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// The faces sampling each pixel of the texture of a model.
+///
+/// Can be created with [`Model::texture_usage_map`].
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Face, Mesh, MeshFaceRef, Model, Point2D, UVMap};
+/// use picocadrs::point;
+///
+/// let mut face = Face::default();
+/// face.uv_maps = vec![
+///     UVMap::new(0, point!(0.0, 0.0)),
+///     UVMap::new(1, point!(1.0, 0.0)),
+///     UVMap::new(2, point!(1.0, 1.0)),
+///     UVMap::new(3, point!(0.0, 1.0)),
+/// ];
+///
+/// let mut mesh = Mesh::new("mesh".to_string());
+/// mesh.faces.push(face);
+///
+/// let mut model = Model::default();
+/// model.meshes.push(mesh);
+///
+/// let usage = model.texture_usage_map();
+///
+/// assert_eq!(usage.owner(point!(3, 5)), Some(MeshFaceRef::new(0, 0)));
+/// assert_eq!(usage.owner(point!(8, 0)), None);
+/// assert_eq!(usage.used_count(), 64);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TextureUsage {
+    /// Faces sampling each pixel, line by line.
+    owners: Vec<Vec<MeshFaceRef>>,
+}
+
+impl TextureUsage {
+    /// Collects which faces of the model sample which pixels.
+    ///
+    /// It's recommended to use [`Model::texture_usage_map`].
+    pub fn from_model(model: &Model) -> TextureUsage {
+        let mut owners: Vec<Vec<MeshFaceRef>> = vec![vec![]; Footer::WIDTH * Footer::HEIGHT];
+
+        for (face, texels) in face_texels(model) {
+            for texel in texels {
+                owners[texel.v * Footer::WIDTH + texel.u].push(face);
+            }
+        }
+
+        TextureUsage { owners }
+    }
+
+    /// Returns all faces sampling the pixel at the given coordinates, in the order they appear in
+    /// the model.
+    ///
+    /// Pixels outside of the texture are never sampled.
+    pub fn faces(&self, coords: Point2D<usize>) -> &[MeshFaceRef] {
+        if coords.u >= Footer::WIDTH || coords.v >= Footer::HEIGHT {
+            return &[];
+        }
+
+        &self.owners[coords.v * Footer::WIDTH + coords.u]
+    }
+
+    /// Returns the first face sampling the pixel at the given coordinates, if there is any.
+    pub fn owner(&self, coords: Point2D<usize>) -> Option<MeshFaceRef> {
+        self.faces(coords).first().copied()
+    }
+
+    /// Checks if any face samples the pixel at the given coordinates.
+    pub fn is_used(&self, coords: Point2D<usize>) -> bool {
+        !self.faces(coords).is_empty()
+    }
+
+    /// Checks if more than one face samples the pixel at the given coordinates.
+    pub fn is_shared(&self, coords: Point2D<usize>) -> bool {
+        self.faces(coords).len() > 1
+    }
+
+    /// Amount of pixels sampled by at least one face.
+    pub fn used_count(&self) -> usize {
+        self.owners.iter().filter(|faces| !faces.is_empty()).count()
+    }
+
+    /// Returns the coordinates of all pixels not sampled by any face, line by line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Model;
+    ///
+    /// let usage = Model::default().texture_usage_map();
+    ///
+    /// assert_eq!(usage.unused_texels().len(), 128 * 120);
+    /// ```
+    pub fn unused_texels(&self) -> Vec<Point2D<usize>> {
+        self.owners
+            .iter()
+            .enumerate()
+            .filter(|(_, faces)| faces.is_empty())
+            .map(|(i, _)| point!(i % Footer::WIDTH, i / Footer::WIDTH))
+            .collect()
+    }
+
+    /// Returns the coordinates of all pixels sampled by the given face, line by line.
+    pub fn texels_of(&self, face: MeshFaceRef) -> Vec<Point2D<usize>> {
+        self.owners
+            .iter()
+            .enumerate()
+            .filter(|(_, faces)| faces.contains(&face))
+            .map(|(i, _)| point!(i % Footer::WIDTH, i / Footer::WIDTH))
+            .collect()
+    }
+}
+
+/// Returns the pixels sampled by each textured face of the model, in the order the faces appear
+/// in the model.
+///
+/// With the `rayon` feature the faces are processed in parallel.
+fn face_texels(model: &Model) -> Vec<(MeshFaceRef, Vec<Point2D<usize>>)> {
+    let faces: Vec<MeshFaceRef> = model
+        .meshes
+        .iter()
+        .enumerate()
+        .flat_map(|(mesh_index, mesh)| {
+            mesh.faces
+                .iter()
+                .enumerate()
+                .filter(|(_, face)| !face.no_texture)
+                .map(move |(face_index, _)| MeshFaceRef::new(mesh_index, face_index))
+        })
+        .collect();
+
+    #[cfg(feature = "rayon")]
+    let faces = faces.into_par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let faces = faces.into_iter();
+
+    faces
+        .map(|face_ref| {
+            let face = &model.meshes[face_ref.mesh].faces[face_ref.face];
+            (face_ref, face.uv_texels())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::Mesh;
+    use crate::fixtures::uv_face;
+
+    #[test]
+    fn usage_texture_usage_map() {
+        let mut first = Mesh::new("first".to_string());
+        first.faces = vec![
+            uv_face(point!(0.0, 0.0), point!(1.0, 1.0)),
+            uv_face(point!(0.5, 0.5), point!(1.5, 1.5)),
+        ];
+
+        let mut second = Mesh::new("second".to_string());
+        let mut untextured = uv_face(point!(2.0, 2.0), point!(3.0, 3.0));
+        untextured.no_texture = true;
+        second.faces = vec![untextured, uv_face(point!(15.5, 14.5), point!(17.0, 16.0))];
+
+        let model = Model {
+            meshes: vec![first, second],
+            ..Model::default()
+        };
+        let usage = model.texture_usage_map();
+
+        assert_eq!(usage.faces(point!(0, 0)), &[MeshFaceRef::new(0, 0)]);
+        assert_eq!(
+            usage.faces(point!(5, 5)),
+            &[MeshFaceRef::new(0, 0), MeshFaceRef::new(0, 1)]
+        );
+        assert!(usage.is_shared(point!(7, 7)));
+        assert!(!usage.is_shared(point!(8, 8)));
+        assert_eq!(usage.owner(point!(11, 11)), Some(MeshFaceRef::new(0, 1)));
+        assert!(!usage.is_used(point!(20, 20)));
+        assert_eq!(usage.owner(point!(127, 119)), Some(MeshFaceRef::new(1, 1)));
+        assert!(!usage.is_used(point!(128, 0)));
+
+        assert!(usage.texels_of(MeshFaceRef::new(1, 0)).is_empty());
+        assert_eq!(usage.texels_of(MeshFaceRef::new(1, 1)).len(), 4 * 4);
+        assert_eq!(usage.used_count(), 64 + 64 - 16 + 16);
+        assert_eq!(
+            usage.unused_texels().len(),
+            Footer::WIDTH * Footer::HEIGHT - usage.used_count()
+        );
+    }
+}
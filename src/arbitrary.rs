@@ -0,0 +1,285 @@
+//! Strategies for generating random models with [proptest](https://docs.rs/proptest).
+//!
+//! Only available with the `proptest` feature.
+//!
+//! There are two kinds of strategies:
+//!
+//! - _valid:_ [`model`], [`mesh`] and the strategies they are made of only generate values picoCAD
+//!   writes itself.
+//!   Coordinates lie on a grid of 1/16 units, so writing and parsing a generated model gives back
+//!   exactly the same model.
+//! - _edge cases:_ [`edge_case_mesh`] and [`edge_case_float`] generate numbers picoCAD would never
+//!   write, like tiny or huge values, negative zero or numbers with more digits than are written.
+//!   These don't survive being written unchanged, but should still settle after being written
+//!   once, see [`testing::assert_canonical_roundtrip`](crate::testing::assert_canonical_roundtrip).
+//!
+//! The texture of generated models uses every color all over it, up to the last pixel.
+//! [`Color`], [`Header`], [`Footer`], [`Mesh`] and [`Model`] also implement
+//! [`Arbitrary`], so they can be used with `any::<Model>()`.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::arbitrary;
+//! use picocadrs::assets::Model;
+//! use proptest::test_runner::TestRunner;
+//!
+//! let mut runner = TestRunner::default();
+//! runner
+//!     .run(&arbitrary::model(), |model| {
+//!         let written = model.to_string();
+//!         assert_eq!(written.parse::<Model>().unwrap(), model);
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! ```
+
+use crate::{
+    assets::{Color, Face, Footer, Header, Mesh, Model, Point2D, Point3D, Rotation, UVMap},
+    point,
+};
+use ::proptest::{
+    arbitrary::Arbitrary,
+    collection::vec,
+    prelude::*,
+    sample::{select, subsequence},
+    strategy::BoxedStrategy,
+};
+
+/// Characters names of generated meshes and models are made of.
+const NAME_CHARS: &[char] = &[
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', '_',
+];
+
+/// Numbers picoCAD would never write, but that a file could still hold.
+const EDGE_CASE_FLOATS: &[f64] = &[
+    0.0,
+    -0.0,
+    1e-9,
+    -1e-9,
+    0.00005,
+    0.99995,
+    1.0 / 3.0,
+    -2.0 / 3.0,
+    32767.9999,
+    -32768.0,
+    1e9,
+    -1e9,
+    f64::EPSILON,
+    f64::MIN_POSITIVE,
+];
+
+/// Amount of vertices generated meshes have at most.
+const MAX_VERTICES: usize = 12;
+
+/// Amount of faces generated meshes have at most.
+const MAX_FACES: usize = 8;
+
+/// Amount of meshes generated models have at most.
+const MAX_MESHES: usize = 4;
+
+/// Generates any of the 16 colors.
+pub fn color() -> impl Strategy<Value = Color> {
+    (0..16).prop_map(Color::from)
+}
+
+/// Generates names of 1 to 12 lowercase letters, digits and underscores.
+pub fn name() -> impl Strategy<Value = String> {
+    vec(select(NAME_CHARS), 1..=12).prop_map(String::from_iter)
+}
+
+/// Generates numbers between `-range` and `range` on a grid of 1/16 units.
+pub fn coordinate(range: u16) -> impl Strategy<Value = f64> + Clone {
+    let steps = range as i32 * 16;
+    (-steps..=steps).prop_map(|step| step as f64 / 16.0)
+}
+
+/// Generates numbers picoCAD would never write, or any finite number between `-1000.0` and
+/// `1000.0`.
+pub fn edge_case_float() -> impl Strategy<Value = f64> + Clone {
+    prop_oneof![select(EDGE_CASE_FLOATS), -1000.0..1000.0]
+}
+
+/// Generates headers with any name, zoom level, background and alpha color.
+pub fn header() -> impl Strategy<Value = Header> {
+    (name(), any::<u8>(), color(), color()).prop_map(|(name, zoom, background, alpha)| {
+        let mut header = Header::with(name, background, alpha);
+        header.zoom = zoom;
+        header
+    })
+}
+
+/// Generates textures with a random color in every pixel.
+pub fn footer() -> impl Strategy<Value = Footer> {
+    vec(color(), Footer::default().as_flat_slice().len()).prop_map(|colors| {
+        let mut footer = Footer::default();
+        footer.as_flat_mut_slice().copy_from_slice(&colors);
+        footer
+    })
+}
+
+/// Generates faces between 3 to 6 different vertices out of `vertex_count`, with any attributes
+/// and uv-coordinates inside of the texture.
+///
+/// `vertex_count` has to be at least 3.
+pub fn face(vertex_count: usize) -> impl Strategy<Value = Face> {
+    let corners = subsequence(
+        (0..vertex_count).collect::<Vec<usize>>(),
+        3..=vertex_count.min(6),
+    )
+    .prop_shuffle();
+    let uv = (0..=256u16, 0..=240u16).prop_map(|(u, v)| point!(u as f64 / 16.0, v as f64 / 16.0));
+
+    (corners, vec(uv, 6), color(), any::<[bool; 4]>()).prop_map(|(corners, uvs, color, flags)| {
+        Face {
+            double_sided: flags[0],
+            no_shading: flags[1],
+            render_priority: flags[2],
+            no_texture: flags[3],
+            color,
+            uv_maps: corners
+                .into_iter()
+                .zip(uvs)
+                .map(|(index, coords): (usize, Point2D<f64>)| UVMap::new(index, coords))
+                .collect(),
+            ..Face::default()
+        }
+    })
+}
+
+/// Generates meshes with 3 to 12 vertices and up to 8 faces between them.
+pub fn mesh() -> impl Strategy<Value = Mesh> {
+    mesh_with(coordinate(16), coordinate(1))
+}
+
+/// Generates meshes like [`mesh`] whose positions, rotations and vertices are made of
+/// [`edge_case_float`]s.
+pub fn edge_case_mesh() -> impl Strategy<Value = Mesh> {
+    mesh_with(edge_case_float(), edge_case_float())
+}
+
+/// Generates models with up to 4 [`mesh`]es and a random texture.
+pub fn model() -> impl Strategy<Value = Model> {
+    (header(), vec(mesh(), 0..=MAX_MESHES), footer()).prop_map(|(header, meshes, footer)| Model {
+        header,
+        meshes,
+        footer,
+    })
+}
+
+/// Generates meshes whose coordinates come from `coordinate` and rotations from `angle`.
+fn mesh_with<C, A>(coordinate: C, angle: A) -> impl Strategy<Value = Mesh>
+where
+    C: Strategy<Value = f64> + Clone,
+    A: Strategy<Value = f64> + Clone,
+{
+    let point = (coordinate.clone(), coordinate.clone(), coordinate)
+        .prop_map(|(x, y, z)| -> Point3D<f64> { point!(x, y, z) });
+    let rotation =
+        (angle.clone(), angle.clone(), angle).prop_map(|(x, y, z)| Rotation(point!(x, y, z)));
+
+    (
+        name(),
+        point.clone(),
+        rotation,
+        vec(point, 3..=MAX_VERTICES),
+    )
+        .prop_flat_map(|(name, position, rotation, vertices)| {
+            let faces = vec(face(vertices.len()), 0..=MAX_FACES);
+            (
+                Just(name),
+                Just(position),
+                Just(rotation),
+                Just(vertices),
+                faces,
+            )
+        })
+        .prop_map(|(name, position, rotation, vertices, faces)| {
+            let mut mesh = Mesh::new(name);
+            mesh.position = position;
+            mesh.rotation = rotation;
+            mesh.vertices = vertices;
+            mesh.faces = faces;
+            mesh
+        })
+}
+
+impl Arbitrary for Color {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Color>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        color().boxed()
+    }
+}
+
+impl Arbitrary for Header {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Header>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        header().boxed()
+    }
+}
+
+impl Arbitrary for Footer {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Footer>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        footer().boxed()
+    }
+}
+
+impl Arbitrary for Mesh {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Mesh>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        mesh().boxed()
+    }
+}
+
+impl Arbitrary for Model {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Model>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        model().boxed()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::testing;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn arbitrary_model_roundtrip(model in any::<Model>()) {
+            let written = model.to_string();
+
+            prop_assert_eq!(written.parse::<Model>().unwrap(), model);
+            testing::assert_roundtrip(&written);
+        }
+
+        #[test]
+        fn arbitrary_edge_case_mesh(mesh in edge_case_mesh()) {
+            let mut model = Model::default();
+            model.meshes.push(mesh);
+
+            testing::assert_canonical_roundtrip(&model.to_string());
+        }
+
+        #[test]
+        fn arbitrary_faces_valid(mesh in mesh()) {
+            for face in mesh.faces.iter() {
+                prop_assert!((3..=6).contains(&face.uv_maps.len()));
+                prop_assert!(face.corners(&mesh.vertices).is_some());
+            }
+        }
+    }
+}
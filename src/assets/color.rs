@@ -2,11 +2,18 @@
 //!
 //! Heavily relies on the pico-8 [color palette](https://pico-8.fandom.com/wiki/Palette).
 
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
 /// Represents a color in the pico-8 color-theme.
 /// picoCAD will only display the 16 official base colors.
 ///
 /// More information on pico8 colors can be found here: https://pico-8.fandom.com/wiki/Palette.
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum Color {
     Invalid,
     Black,
@@ -181,6 +188,48 @@ impl Color {
         }
     }
 
+    /// Returns how different two colors look.
+    ///
+    /// Uses a weighted euclidean distance in rgb space (the "redmean" approximation), which
+    /// follows human perception a lot closer than the plain distance while staying cheap.
+    /// Equal colors have a distance of `0.0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Color;
+    ///
+    /// assert_eq!(Color::Red.distance(&Color::Red), 0.0);
+    /// assert!(Color::Red.distance(&Color::Pink) < Color::Red.distance(&Color::Green));
+    /// ```
+    pub fn distance(&self, other: &Color) -> f64 {
+        rgb_distance(self.as_rgb(), other.as_rgb())
+    }
+
+    /// Returns the color of the palette that looks the closest to the given rgb values.
+    ///
+    /// Closeness is measured the same way as in [`distance`](Color::distance).
+    /// Never returns [`Invalid`](Color::Invalid).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Color;
+    ///
+    /// assert_eq!(Color::nearest_rgb(131, 118, 156), Color::Lavender);
+    /// assert_eq!(Color::nearest_rgb(250, 10, 70), Color::Red);
+    /// assert_eq!(Color::nearest_rgb(20, 20, 20), Color::Black);
+    /// ```
+    pub fn nearest_rgb(r: u8, g: u8, b: u8) -> Color {
+        (0..16)
+            .map(Color::from)
+            .min_by(|a, b_color| {
+                rgb_distance(a.as_rgb(), (r, g, b))
+                    .total_cmp(&rgb_distance(b_color.as_rgb(), (r, g, b)))
+            })
+            .unwrap_or(Color::Black)
+    }
+
     /// Returns the color picoCAD would use to replace `self` with if it was shadowed.
     ///
     /// Shadow of [`Invalid`](Color::Invalid) is still [`Invalid`](Color::Invalid).
@@ -235,6 +284,31 @@ impl Color {
             Color::Pink => Color::Red,
         }
     }
+
+    /// Mixes the rgb values of `a` and `b`, `t` being the share of `b` between `0.0` and `1.0`.
+    ///
+    /// Colors are mixed in linear light rather than on their sRGB values, which is how a pattern
+    /// of both colors looks from a distance.
+    /// A plain average of the values would come out too dark.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Color;
+    ///
+    /// assert_eq!(Color::blend(Color::Black, Color::Red, 0.0), (0, 0, 0));
+    /// assert_eq!(Color::blend(Color::Black, Color::Red, 1.0), Color::Red.as_rgb());
+    /// // half of 255 in linear light.
+    /// assert_eq!(Color::blend(Color::Black, Color::Red, 0.5).0, 188);
+    /// ```
+    pub fn blend(a: Color, b: Color, t: f64) -> (u8, u8, u8) {
+        let t = t.clamp(0.0, 1.0);
+        let (a, b) = (a.as_rgb(), b.as_rgb());
+        let mix =
+            |a: u8, b: u8| linear_to_srgb(srgb_to_linear(a) * (1.0 - t) + srgb_to_linear(b) * t);
+
+        (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+    }
 }
 
 impl From<char> for Color {
@@ -353,6 +427,191 @@ impl From<(u8, u8, u8)> for Color {
     }
 }
 
+/// Rgb values of the 16 secret pico-8 colors (`128 - 143`) in order.
+const SECRET_RGB: [(u8, u8, u8); 16] = [
+    (41, 24, 20),
+    (17, 29, 53),
+    (66, 33, 54),
+    (18, 83, 89),
+    (116, 47, 41),
+    (73, 51, 59),
+    (162, 136, 121),
+    (243, 239, 125),
+    (190, 18, 80),
+    (255, 108, 36),
+    (168, 231, 46),
+    (0, 181, 67),
+    (6, 90, 181),
+    (117, 70, 101),
+    (255, 110, 89),
+    (255, 157, 129),
+];
+
+/// A color of the full pico-8 palette, including the 16 secret colors.
+///
+/// The secret colors (`128 - 143`) can't be stored in the texture of a picoCAD project, which only
+/// knows the 16 base colors.
+/// They are used by pico-8 carts that swap the screen palette, so they matter when preparing
+/// textures for or exporting textures from such carts.
+/// More information can be found
+/// [here](https://pico-8.fandom.com/wiki/Palette#128..143:_Undocumented_extra_colors).
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Color, ExtendedColor};
+///
+/// let color = ExtendedColor::from(137);
+///
+/// assert_eq!(color, ExtendedColor::Secret(9));
+/// assert_eq!(color.as_i32(), 137);
+/// assert_eq!(color.as_rgb(), (255, 108, 36));
+/// assert_eq!(color.to_base(), Color::Orange);
+///
+/// assert_eq!(ExtendedColor::from(8), ExtendedColor::Base(Color::Red));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ExtendedColor {
+    /// One of the 16 base colors.
+    Base(Color),
+    /// One of the 16 secret colors, given as its index `0 - 15` (color `128 + index`).
+    /// Indices above 15 are treated as invalid.
+    Secret(u8),
+}
+
+impl ExtendedColor {
+    /// Returns the pico-8 number of the color, `0 - 15` for base colors and `128 - 143` for
+    /// secret colors.
+    ///
+    /// Invalid colors return `0`.
+    pub fn as_i32(&self) -> i32 {
+        match self {
+            ExtendedColor::Base(color) => color.as_i32(),
+            ExtendedColor::Secret(index) if *index < 16 => 128 + *index as i32,
+            ExtendedColor::Secret(_) => 0,
+        }
+    }
+
+    /// Returns the color as a rgb triplet.
+    ///
+    /// Invalid colors return `(0, 0, 0)`.
+    pub fn as_rgb(&self) -> (u8, u8, u8) {
+        match self {
+            ExtendedColor::Base(color) => color.as_rgb(),
+            ExtendedColor::Secret(index) => SECRET_RGB
+                .get(*index as usize)
+                .copied()
+                .unwrap_or((0, 0, 0)),
+        }
+    }
+
+    /// Returns the color as a hex string like [`Color::as_hex`].
+    pub fn as_hex(&self) -> String {
+        let (r, g, b) = self.as_rgb();
+        format!("{:02X}{:02X}{:02X}", r, g, b)
+    }
+
+    /// Checks if the color is one of the secret colors.
+    pub fn is_secret(&self) -> bool {
+        matches!(self, ExtendedColor::Secret(_))
+    }
+
+    /// Returns the base color that looks the closest to this color.
+    pub fn to_base(&self) -> Color {
+        match self {
+            ExtendedColor::Base(color) => *color,
+            ExtendedColor::Secret(_) => {
+                let (r, g, b) = self.as_rgb();
+                Color::nearest_rgb(r, g, b)
+            }
+        }
+    }
+
+    /// Returns the color that looks the closest to the given rgb values.
+    ///
+    /// Secret colors are only considered if `allow_secret` is true.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, ExtendedColor};
+    ///
+    /// assert_eq!(ExtendedColor::nearest_rgb(240, 235, 120, true), ExtendedColor::Secret(7));
+    /// assert_eq!(
+    ///     ExtendedColor::nearest_rgb(240, 235, 120, false),
+    ///     ExtendedColor::Base(Color::LightPeach)
+    /// );
+    /// ```
+    pub fn nearest_rgb(r: u8, g: u8, b: u8, allow_secret: bool) -> ExtendedColor {
+        let base = ExtendedColor::Base(Color::nearest_rgb(r, g, b));
+
+        if !allow_secret {
+            return base;
+        }
+
+        (0..16)
+            .map(ExtendedColor::Secret)
+            .chain([base])
+            .min_by(|a, b_color| {
+                rgb_distance(a.as_rgb(), (r, g, b))
+                    .total_cmp(&rgb_distance(b_color.as_rgb(), (r, g, b)))
+            })
+            .unwrap_or(base)
+    }
+}
+
+impl From<Color> for ExtendedColor {
+    fn from(value: Color) -> Self {
+        ExtendedColor::Base(value)
+    }
+}
+
+impl From<i32> for ExtendedColor {
+    /// Converts a pico-8 color number into [`ExtendedColor`].
+    ///
+    /// `0 - 15` are base colors, `128 - 143` secret colors.
+    /// Any other number becomes an [`Invalid`](Color::Invalid) base color.
+    fn from(value: i32) -> Self {
+        match value {
+            128..=143 => ExtendedColor::Secret((value - 128) as u8),
+            _ => ExtendedColor::Base(Color::from(value)),
+        }
+    }
+}
+
+/// Weighted euclidean distance between two rgb colors, see [`Color::distance`].
+pub(crate) fn rgb_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let mean_r = (a.0 as f64 + b.0 as f64) / 2.0;
+    let r = a.0 as f64 - b.0 as f64;
+    let g = a.1 as f64 - b.1 as f64;
+    let b = a.2 as f64 - b.2 as f64;
+
+    ((2.0 + mean_r / 256.0) * r * r + 4.0 * g * g + (2.0 + (255.0 - mean_r) / 256.0) * b * b).sqrt()
+}
+
+/// Converts an sRGB channel value into linear light between `0.0` and `1.0`.
+pub(crate) fn srgb_to_linear(value: u8) -> f64 {
+    let value = value as f64 / 255.0;
+
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Reverses [`srgb_to_linear`].
+pub(crate) fn linear_to_srgb(value: f64) -> u8 {
+    let value = value.clamp(0.0, 1.0);
+    let value = if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+
+    (value * 255.0).round() as u8
+}
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -406,9 +665,68 @@ pub mod tests {
         assert_eq!(Color::Invalid, Color::from((111, 111, 111)));
     }
 
+    #[test]
+    fn color_nearest_rgb() {
+        for i in 0..16 {
+            let (r, g, b) = Color::from(i).as_rgb();
+            assert_eq!(Color::nearest_rgb(r, g, b), Color::from(i));
+        }
+
+        assert_eq!(Color::nearest_rgb(255, 255, 255), Color::White);
+        assert_eq!(Color::nearest_rgb(100, 100, 100), Color::DarkGrey);
+        assert_eq!(Color::nearest_rgb(40, 170, 250), Color::Blue);
+    }
+
+    #[test]
+    fn color_distance() {
+        assert_eq!(Color::Blue.distance(&Color::Blue), 0.0);
+        assert_eq!(
+            Color::Blue.distance(&Color::Red),
+            Color::Red.distance(&Color::Blue)
+        );
+        assert!(Color::White.distance(&Color::LightPeach) < Color::White.distance(&Color::Black));
+    }
+
+    #[test]
+    fn extended_color_conversions() {
+        for value in (0..16).chain(128..144) {
+            assert_eq!(ExtendedColor::from(value).as_i32(), value);
+        }
+
+        assert_eq!(
+            ExtendedColor::from(144),
+            ExtendedColor::Base(Color::Invalid)
+        );
+        assert_eq!(ExtendedColor::Secret(16).as_i32(), 0);
+        assert_eq!(ExtendedColor::Secret(0).as_hex(), "291814");
+        assert!(ExtendedColor::Secret(3).is_secret());
+        assert_eq!(ExtendedColor::from(Color::Blue).to_base(), Color::Blue);
+        assert_eq!(ExtendedColor::Secret(8).to_base(), Color::DarkPurple);
+    }
+
     #[test]
     fn color_shadows() {
         assert_eq!(Color::Orange.shadow_transition(), Color::Brown);
         assert_eq!(Color::Orange.shadow(), Color::DarkPurple);
     }
+
+    #[test]
+    fn color_blend() {
+        for value in 0..=255 {
+            assert_eq!(linear_to_srgb(srgb_to_linear(value)), value);
+        }
+
+        assert_eq!(
+            Color::blend(Color::Black, Color::White, 0.5),
+            (188, 177, 170)
+        );
+        assert_eq!(
+            Color::blend(Color::Blue, Color::Blue, 0.3),
+            Color::Blue.as_rgb()
+        );
+        assert_eq!(
+            Color::blend(Color::Black, Color::White, 2.0),
+            Color::White.as_rgb()
+        );
+    }
 }
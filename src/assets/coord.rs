@@ -0,0 +1,136 @@
+//! For telling pixels of the texture and uv-coordinates apart.
+//!
+//! The texture can be addressed in two ways:
+//! - _texels:_ Whole pixels, `0 - 127` for `u` and `0 - 119` for `v`, see [`TexelCoord`].
+//! - _uv-coordinates:_ What faces store, where a single pixel is `0.125` wide, so the texture
+//!   spans `0.0 - 16.0` for `u` and `0.0 - 15.0` for `v`, see [`UvCoord`].
+//!
+//! Both can be written as [`Point2D`], and mixing them up silently reads the wrong pixel.
+//! [`Footer`](crate::assets::Footer) and [`UVMap`](crate::assets::UVMap) accept these types
+//! wherever coordinates are expected, converting between them always honors the size of a pixel.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{TexelCoord, UvCoord};
+//!
+//! assert_eq!(TexelCoord(8, 4).to_uv(), UvCoord(1.0, 0.5));
+//! assert_eq!(UvCoord(1.0, 0.5).to_texel(), Some(TexelCoord(8, 4)));
+//! assert_eq!(UvCoord(16.0, 0.5).to_texel(), None);
+//! ```
+
+use crate::assets::{mesh::UV_PIXEL_STEP, Footer, Point2D};
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+
+/// A pixel of the texture, `u` to the right and `v` downwards.
+/// `0, 0` is located in the top left corner.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct TexelCoord(pub usize, pub usize);
+
+impl TexelCoord {
+    /// Checks if the pixel is part of the texture, meaning `u < 128` and `v < 120`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::TexelCoord;
+    ///
+    /// assert!(TexelCoord(127, 119).in_bounds());
+    /// assert!(!TexelCoord(128, 0).in_bounds());
+    /// ```
+    pub fn in_bounds(&self) -> bool {
+        self.0 < Footer::WIDTH && self.1 < Footer::HEIGHT
+    }
+
+    /// Returns the uv-coordinates of the pixel.
+    /// Each pixel "owns" the region `0.0625` around these coordinates, see
+    /// [`UvCoord::to_texel`].
+    pub fn to_uv(self) -> UvCoord {
+        UvCoord(self.0 as f64 * UV_PIXEL_STEP, self.1 as f64 * UV_PIXEL_STEP)
+    }
+}
+
+impl From<Point2D<usize>> for TexelCoord {
+    fn from(value: Point2D<usize>) -> Self {
+        TexelCoord(value.u, value.v)
+    }
+}
+
+impl From<TexelCoord> for Point2D<usize> {
+    fn from(value: TexelCoord) -> Self {
+        Point2D::new(value.0, value.1)
+    }
+}
+
+/// uv-coordinates as stored in faces, `u` to the right and `v` downwards.
+/// `0.0, 0.0` is located in the top left corner of the texture, a pixel is `0.125` wide.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct UvCoord(pub f64, pub f64);
+
+impl UvCoord {
+    /// Returns the pixel these coordinates lie in, [`None`] if they are outside of the texture or
+    /// not a number.
+    ///
+    /// Each pixel "owns" a region of `0.125 x 0.125` centered on its
+    /// [uv-coordinates](TexelCoord::to_uv), so `u` is out of bounds if `-0.0625 > u` or
+    /// `u >= 15.9375` and `v` if `-0.0625 > v` or `v >= 14.9375`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{TexelCoord, UvCoord};
+    ///
+    /// assert_eq!(UvCoord(0.75, 0.5).to_texel(), Some(TexelCoord(6, 4)));
+    /// assert_eq!(UvCoord(-0.05, 0.0).to_texel(), Some(TexelCoord(0, 0)));
+    /// assert_eq!(UvCoord(-0.75, 0.5).to_texel(), None);
+    /// assert_eq!(UvCoord(f64::NAN, 0.5).to_texel(), None);
+    /// ```
+    pub fn to_texel(self) -> Option<TexelCoord> {
+        let half = UV_PIXEL_STEP / 2.0;
+        let max_u = Footer::WIDTH as f64 * UV_PIXEL_STEP - half;
+        let max_v = Footer::HEIGHT as f64 * UV_PIXEL_STEP - half;
+
+        if !(-half..max_u).contains(&self.0) || !(-half..max_v).contains(&self.1) {
+            return None;
+        }
+
+        Some(TexelCoord(
+            (self.0 / UV_PIXEL_STEP).round() as usize,
+            (self.1 / UV_PIXEL_STEP).round() as usize,
+        ))
+    }
+}
+
+impl From<Point2D<f64>> for UvCoord {
+    fn from(value: Point2D<f64>) -> Self {
+        UvCoord(value.u, value.v)
+    }
+}
+
+impl From<UvCoord> for Point2D<f64> {
+    fn from(value: UvCoord) -> Self {
+        Point2D::new(value.0, value.1)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::point;
+
+    #[test]
+    fn coord_conversions() {
+        for texel in [TexelCoord(0, 0), TexelCoord(127, 119), TexelCoord(31, 64)] {
+            assert_eq!(texel.to_uv().to_texel(), Some(texel));
+            assert_eq!(TexelCoord::from(Point2D::from(texel)), texel);
+        }
+
+        assert_eq!(UvCoord::from(point!(1.5, 2.0)), UvCoord(1.5, 2.0));
+        assert_eq!(Point2D::from(UvCoord(1.5, 2.0)), point!(1.5, 2.0));
+        assert_eq!(UvCoord(15.9, 14.9).to_texel(), Some(TexelCoord(127, 119)));
+        assert_eq!(UvCoord(0.0, 14.9375).to_texel(), None);
+        assert_eq!(UvCoord(f64::INFINITY, 0.0).to_texel(), None);
+        assert!(!TexelCoord(0, 120).in_bounds());
+    }
+}
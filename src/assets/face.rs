@@ -8,104 +8,1427 @@
 //! Which lies within a mesh.
 //!
 //! - _c (color):_ The color the mesh has if no texture is mapped onto it.
-//! Color index is given in decimal. For more information look at the [`color`](super::color) module or
-//! [this table](https://pico-8.fandom.com/wiki/Palette#0..15:_Official_base_colors).
-//! In this case `10` which represents yellow.
+//!   Color index is given in decimal. For more information look at the [`color`](super::color) module or
+//!   [this table](https://pico-8.fandom.com/wiki/Palette#0..15:_Official_base_colors).
+//!   In this case `10` which represents yellow.
 //!
 //! - _dbl (double-sided):_ if existent* the face will be rendered from both sides.
 //!
 //! - _noshade (no shading):_ if existent* the face will not show any shadows on it.
 //!
 //! - _notex (no texture):_ if existent* the face will not have textures mapped onto it and will
-//! just be the color of the _c_ field.
+//!   just be the color of the _c_ field.
 //!
 //! - _prio (render priority):_ if existent* the face will be rendered before any other face
-//! leading to it always being behind all other faces.
+//!   leading to it always being behind all other faces.
 //!
 //! - _table indices:_ In this case the values `3,2,1` at the start of the table.
-//! Indicate which vertices of the mesh this face lives within to use as corners.
-//! Indexing starts at 1, meaning this face uses the first three vertices of the mesh it is within.
-//! Order also matters as it tells picoCAD in which orders to draw edges.
-//! In the example of `3,2,1` it goes `3 -> 2 -> 1 -> 3`.
-//! This means that `4,3,2,1` is not the same face as `3,4,2,1`.
+//!   Indicate which vertices of the mesh this face lives within to use as corners.
+//!   Indexing starts at 1, meaning this face uses the first three vertices of the mesh it is within.
+//!   Order also matters as it tells picoCAD in which orders to draw edges.
+//!   In the example of `3,2,1` it goes `3 -> 2 -> 1 -> 3`.
+//!   This means that `4,3,2,1` is not the same face as `3,4,2,1`.
 //!
 //! - _uv:_ Represents the coordinates on the texture that are mapped to corners of the face.
-//! Always paired into 2 values.
-//! This is in relation to the _table indices_ as their positions determine which vertex gets which
-//! coordinates.
-//! In the example above the coordinates `1.25, 0` in the texture are mapped onto the corner that
-//! is at vertex with the index `3`.
-//! More information on how float coordinates work can be found in the docs of [`Footer`](super::Footer).
+//!   Always paired into 2 values.
+//!   This is in relation to the _table indices_ as their positions determine which vertex gets which
+//!   coordinates.
+//!   In the example above the coordinates `1.25, 0` in the texture are mapped onto the corner that
+//!   is at vertex with the index `3`.
+//!   More information on how float coordinates work can be found in the docs of [`Footer`](super::Footer).
 //!
 //! *: picoCAD doesn't actually check the value of these fields but only if they exist.
+//!
+//! Any other keys of the table are kept in [`Face::extras`] and written back unchanged.
+
+use crate::assets::{
+    mesh::{snap, UV_PIXEL_STEP},
+    Color, Footer, Point2D, Point3D, Rect, UvCoord,
+};
+use crate::error::PicoError;
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::lua_table::{self, LuaTable, LuaValue};
+use crate::number::{PicoFormat, PicoNumber};
+use crate::point;
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use rlua::{Table, Value};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// Represents uv-coordinates and the vertex they correspond to.
+///
+/// When building a face this helps with keeping index corresponding uv-coordinates together.
+/// Since internally these two are stored separately from each other, I think this also makes it
+/// easier to understand.
+///
+/// uv-mappings are stored inside the face of a mesh.
+///
+/// <br/>
+///
+/// Indexes are not the same they are in the project files.
+/// picoCAD uses indexes starting from 1 for referencing vertices.
+/// To make it more in line with standard programming rules they start from 0 here and only will be
+/// converted into the actual indexes when serializing.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UVMap {
+    pub vertex_index: usize,
+    pub coords: Point2D<f64>,
+}
+
+impl UVMap {
+    /// Creates a new `UVMap`.
+    /// `coords` can be given as [`UvCoord`] or [`Point2D<f64>`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{UVMap, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let map = UVMap::new(2, point!(2.0, 3.5));
+    ///
+    /// assert_eq!(map.vertex_index, 2);
+    /// assert_eq!(map.coords.u, 2.0);
+    /// assert_eq!(map.coords.v, 3.5);
+    /// ```
+    pub fn new(vertex_index: usize, coords: impl Into<UvCoord>) -> UVMap {
+        UVMap {
+            vertex_index,
+            coords: coords.into().into(),
+        }
+    }
+
+    /// Returns the uv-coordinates as [`UvCoord`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{TexelCoord, UVMap, UvCoord};
+    ///
+    /// let map = UVMap::new(0, UvCoord(1.0, 0.5));
+    ///
+    /// assert_eq!(map.uv(), UvCoord(1.0, 0.5));
+    /// assert_eq!(map.uv().to_texel(), Some(TexelCoord(8, 4)));
+    /// ```
+    pub fn uv(&self) -> UvCoord {
+        self.coords.into()
+    }
+}
+
+/// Represents the face of a mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Face {
+    /// If true, face will get rendered from both sides.
+    pub double_sided: bool,
+    /// If true, no shading will be applied to this face.
+    pub no_shading: bool,
+    /// If true, this face will be rendered first.
+    /// This means it will appear behind all other faces.
+    pub render_priority: bool,
+    /// If true, no texture will be rendered on this face.
+    pub no_texture: bool,
+    /// Color of the face. If `no_texture` is enabled this color will show.
+    pub color: Color,
+    /// uv-mappings of this face.
+    /// Tells picoCAD which vertices this face is between and where they are on the uv-map.
+    pub uv_maps: Vec<UVMap>,
+    /// Keys of the face table this crate doesn't know, like fields added by newer versions of
+    /// picoCAD or other tools.
+    /// These are written back unchanged.
+    pub extras: BTreeMap<String, LuaValue>,
+}
+
+impl Face {
+    /// Returns a [`FaceBuilder`] for putting a face together step by step.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Face, Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let face = Face::builder()
+    ///     .vertices([0, 1, 2, 3])
+    ///     .color(Color::Red)
+    ///     .double_sided(true)
+    ///     .uv_rect(Rect::new(point!(0, 0), point!(16, 8)))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(face.uv_maps[2].vertex_index, 2);
+    /// assert_eq!(face.uv_maps[2].coords, point!(2.0, 1.0));
+    /// assert!(face.double_sided);
+    /// ```
+    pub fn builder() -> FaceBuilder {
+        FaceBuilder::default()
+    }
+
+    /// Calculates the normal of the face using
+    /// [Newell's method](https://www.khronos.org/opengl/wiki/Calculating_a_Surface_Normal),
+    /// which also gives reasonable results for quads that are not completely planar.
+    ///
+    /// `vertices` are the vertices of the mesh this face lives in.
+    /// The normal points out of the side of the face picoCAD renders if the face is not
+    /// double-sided and is normalized to a length of `1.0`.
+    /// Since the y-axis in picoCAD points downwards, this is the side where the vertices appear
+    /// in clockwise order.
+    ///
+    /// Returns `0.0, 0.0, 0.0` if the face has less than 3 vertices, refers to vertices that don't
+    /// exist or has no area.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, Point3D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let vertices = vec![point!(0.0, 0.0, 0.0), point!(1.0, 0.0, 0.0), point!(0.0, 1.0, 0.0)];
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(0.0, 0.0)),
+    ///     UVMap::new(1, point!(0.0, 0.0)),
+    ///     UVMap::new(2, point!(0.0, 0.0)),
+    /// ];
+    ///
+    /// assert_eq!(face.normal(&vertices), point!(0.0, 0.0, -1.0));
+    /// ```
+    pub fn normal(&self, vertices: &[Point3D<f64>]) -> Point3D<f64> {
+        let corners = match self.corners(vertices) {
+            Some(corners) if corners.len() >= 3 => corners,
+            _ => return point!(0.0, 0.0, 0.0),
+        };
+
+        let mut normal = point!(0.0, 0.0, 0.0);
+
+        for (i, current) in corners.iter().enumerate() {
+            let next = corners[(i + 1) % corners.len()];
+
+            normal.x += (current.y - next.y) * (current.z + next.z);
+            normal.y += (current.z - next.z) * (current.x + next.x);
+            normal.z += (current.x - next.x) * (current.y + next.y);
+        }
+
+        let length = (normal.x * normal.x + normal.y * normal.y + normal.z * normal.z).sqrt();
+
+        if length == 0.0 {
+            point!(0.0, 0.0, 0.0)
+        } else {
+            // Newell's method follows the right-hand rule, so it has to be flipped.
+            point!(-normal.x / length, -normal.y / length, -normal.z / length)
+        }
+    }
+
+    /// Returns the area of the polygon spanned by the uv-coordinates of the face, in uv units.
+    ///
+    /// One uv unit is 8 pixels of the texture, so multiplying by `64.0` gives the area in pixels.
+    /// Faces with less than 3 uv-coordinates have no area.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(0.0, 0.0)),
+    ///     UVMap::new(1, point!(2.0, 0.0)),
+    ///     UVMap::new(2, point!(2.0, 1.5)),
+    ///     UVMap::new(3, point!(0.0, 1.5)),
+    /// ];
+    ///
+    /// assert_eq!(face.uv_area(), 3.0);
+    /// ```
+    pub fn uv_area(&self) -> f64 {
+        if self.uv_maps.len() < 3 {
+            return 0.0;
+        }
+
+        let mut doubled = 0.0;
+        for (i, current) in self.uv_maps.iter().enumerate() {
+            let next = &self.uv_maps[(i + 1) % self.uv_maps.len()];
+            doubled += current.coords.u * next.coords.v - next.coords.u * current.coords.v;
+        }
+
+        doubled.abs() / 2.0
+    }
+
+    /// Returns the area of the face in 3D space.
+    ///
+    /// `vertices` are the vertices of the mesh this face lives in.
+    /// Faces that aren't [planar](Face::is_planar) get the area of their outline projected along
+    /// their [`normal`](Face::normal).
+    ///
+    /// Returns `0.0` if the face has less than 3 vertices or refers to vertices that don't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, Point3D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let vertices = vec![point!(0.0, 0.0, 0.0), point!(2.0, 0.0, 0.0), point!(0.0, 0.0, 3.0)];
+    ///
+    /// let mut face = Face::default();
+    /// for i in 0..3 {
+    ///     face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+    /// }
+    ///
+    /// assert_eq!(face.world_area(&vertices), 3.0);
+    /// ```
+    pub fn world_area(&self, vertices: &[Point3D<f64>]) -> f64 {
+        let corners = match self.corners(vertices) {
+            Some(corners) if corners.len() >= 3 => corners,
+            _ => return 0.0,
+        };
+
+        let mut doubled = point!(0.0, 0.0, 0.0);
+        for (i, current) in corners.iter().enumerate() {
+            doubled += current.cross(corners[(i + 1) % corners.len()]);
+        }
+
+        doubled.length() / 2.0
+    }
+
+    /// Checks if all vertices of the face lie on the same plane.
+    ///
+    /// `vertices` are the vertices of the mesh this face lives in.
+    /// A vertex counts as being on the plane if its distance to it is not bigger than `tolerance`.
+    /// The plane goes through the center of the face and is oriented along its
+    /// [`normal`](Face::normal).
+    ///
+    /// Faces with 3 or fewer vertices are always planar.
+    /// Returns `false` if the face refers to vertices that don't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, Point3D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut vertices = vec![
+    ///     point!(0.0, 0.0, 0.0),
+    ///     point!(1.0, 0.0, 0.0),
+    ///     point!(1.0, 1.0, 0.0),
+    ///     point!(0.0, 1.0, 0.0),
+    /// ];
+    ///
+    /// let mut face = Face::default();
+    /// for i in 0..4 {
+    ///     face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+    /// }
+    ///
+    /// assert!(face.is_planar(&vertices, 0.001));
+    ///
+    /// vertices[2].z = 0.5;
+    /// assert!(!face.is_planar(&vertices, 0.001));
+    /// ```
+    pub fn is_planar(&self, vertices: &[Point3D<f64>], tolerance: f64) -> bool {
+        let corners = match self.corners(vertices) {
+            Some(corners) => corners,
+            None => return false,
+        };
+
+        if corners.len() <= 3 {
+            return true;
+        }
+
+        let normal = self.normal(vertices);
+        let count = corners.len() as f64;
+        let center = corners
+            .iter()
+            .fold(point!(0.0, 0.0, 0.0), |sum, corner| sum + *corner);
+        let center = point!(center.x / count, center.y / count, center.z / count);
+
+        corners.iter().all(|corner| {
+            let offset = *corner - center;
+            let distance = offset.x * normal.x + offset.y * normal.y + offset.z * normal.z;
+
+            distance.abs() <= tolerance
+        })
+    }
+
+    /// Returns where a ray starting at `origin` and travelling in `direction` hits this face.
+    ///
+    /// `vertices` are the vertices of the mesh this face lives in, `origin` has to be given in
+    /// the same space.
+    /// The face is split into triangles fanning out from its first corner, the closest hit in
+    /// front of `origin` is returned.
+    /// Rays hitting the back of a face only count if the face is
+    /// [`double_sided`](Face::double_sided), since picoCAD doesn't draw the back otherwise.
+    ///
+    /// Returns [`None`] if the ray misses, if `direction` has no length or if the face refers to
+    /// vertices that don't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, Point3D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let vertices = vec![
+    ///     point!(0.0, 0.0, 0.0),
+    ///     point!(0.0, 0.0, 2.0),
+    ///     point!(2.0, 0.0, 2.0),
+    ///     point!(2.0, 0.0, 0.0),
+    /// ];
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(0.0, 0.0)),
+    ///     UVMap::new(1, point!(0.0, 1.0)),
+    ///     UVMap::new(2, point!(1.0, 1.0)),
+    ///     UVMap::new(3, point!(1.0, 0.0)),
+    /// ];
+    ///
+    /// // the face looks up, which is towards negative y.
+    /// let hit = face
+    ///     .intersect_ray(&vertices, point!(1.5, -3.0, 0.5), point!(0.0, 1.0, 0.0))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(hit.distance, 3.0);
+    /// assert_eq!(hit.point, point!(1.5, 0.0, 0.5));
+    /// assert_eq!(hit.uv, point!(0.75, 0.25));
+    ///
+    /// // the back of the face can't be hit.
+    /// assert!(face
+    ///     .intersect_ray(&vertices, point!(1.5, 3.0, 0.5), point!(0.0, -1.0, 0.0))
+    ///     .is_none());
+    /// ```
+    pub fn intersect_ray(
+        &self,
+        vertices: &[Point3D<f64>],
+        origin: Point3D<f64>,
+        direction: Point3D<f64>,
+    ) -> Option<FaceHit> {
+        self.intersect_ray_sides(vertices, origin, direction, self.double_sided)
+    }
+
+    /// Same as [`intersect_ray`](Face::intersect_ray), but the back of the face can be hit if
+    /// `back` is true, no matter if the face is double-sided.
+    pub(crate) fn intersect_ray_sides(
+        &self,
+        vertices: &[Point3D<f64>],
+        origin: Point3D<f64>,
+        direction: Point3D<f64>,
+        back: bool,
+    ) -> Option<FaceHit> {
+        let corners = self.corners(vertices)?;
+        let direction = direction.normalized();
+
+        if corners.len() < 3 || direction == point!(0.0, 0.0, 0.0) {
+            return None;
+        }
+
+        if !back && direction.dot(self.normal(vertices)) >= 0.0 {
+            return None;
+        }
+
+        let mut closest: Option<FaceHit> = None;
+
+        for i in 1..corners.len() - 1 {
+            let triangle = [0, i, i + 1];
+            let Some((distance, weights)) =
+                intersect_triangle([corners[0], corners[i], corners[i + 1]], origin, direction)
+            else {
+                continue;
+            };
+
+            if closest
+                .as_ref()
+                .is_some_and(|closest| closest.distance <= distance)
+            {
+                continue;
+            }
+
+            let uv = triangle
+                .iter()
+                .zip(weights)
+                .fold(point!(0.0, 0.0), |uv, (corner, weight)| {
+                    uv + self.uv_maps[*corner].coords * weight
+                });
+
+            closest = Some(FaceHit {
+                distance,
+                point: origin + direction * distance,
+                uv,
+                triangle,
+                barycentric: weights,
+            });
+        }
+
+        closest
+    }
+
+    /// Returns the pixels of the texture that are mapped onto this face.
+    ///
+    /// A pixel at `u, v` covers the area from `u * 0.125` to `(u + 1) * 0.125` in uv-coordinates
+    /// (the same for `v`) and belongs to the face if its center lies inside the polygon spanned
+    /// by the faces uv-coordinates.
+    /// Pixels outside of the texture are never returned.
+    ///
+    /// Faces with less than 3 uv-coordinates don't cover any pixels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(0.0, 0.0)),
+    ///     UVMap::new(1, point!(0.25, 0.0)),
+    ///     UVMap::new(2, point!(0.25, 0.25)),
+    ///     UVMap::new(3, point!(0.0, 0.25)),
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     face.uv_texels(),
+    ///     vec![point!(0, 0), point!(1, 0), point!(0, 1), point!(1, 1)]
+    /// );
+    /// ```
+    pub fn uv_texels(&self) -> Vec<Point2D<usize>> {
+        if self.uv_maps.len() < 3 {
+            return vec![];
+        }
+
+        let polygon: Vec<Point2D<f64>> = self
+            .uv_maps
+            .iter()
+            .map(|uv_map| point!(uv_map.coords.u * 8.0, uv_map.coords.v * 8.0))
+            .collect();
+
+        let min_u = polygon.iter().map(|p| p.u).fold(f64::INFINITY, f64::min);
+        let max_u = polygon
+            .iter()
+            .map(|p| p.u)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let min_v = polygon.iter().map(|p| p.v).fold(f64::INFINITY, f64::min);
+        let max_v = polygon
+            .iter()
+            .map(|p| p.v)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        if !(min_u.is_finite() && max_u.is_finite() && min_v.is_finite() && max_v.is_finite()) {
+            return vec![];
+        }
+
+        let (width, height) = (Footer::WIDTH as f64, Footer::HEIGHT as f64);
+        let start_u = min_u.floor().clamp(0.0, width) as usize;
+        let end_u = max_u.ceil().clamp(0.0, width) as usize;
+        let start_v = min_v.floor().clamp(0.0, height) as usize;
+        let end_v = max_v.ceil().clamp(0.0, height) as usize;
+
+        let mut texels: Vec<Point2D<usize>> = vec![];
+
+        for v in start_v..end_v {
+            for u in start_u..end_u {
+                if polygon_contains(&polygon, point!(u as f64 + 0.5, v as f64 + 0.5)) {
+                    texels.push(point!(u, v));
+                }
+            }
+        }
+
+        texels
+    }
+
+    /// Counts how many pixels of each color of the texture are mapped onto this face.
+    ///
+    /// Pixels are sampled the same way as in [`uv_texels`](Face::uv_texels).
+    /// The texture is sampled even if [`no_texture`](Face::no_texture) is enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Face, Footer, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.set(point!(1, 1), Color::Red).unwrap();
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(0.0, 0.0)),
+    ///     UVMap::new(1, point!(0.25, 0.0)),
+    ///     UVMap::new(2, point!(0.25, 0.25)),
+    ///     UVMap::new(3, point!(0.0, 0.25)),
+    /// ];
+    ///
+    /// let colors = face.texture_colors(&footer);
+    ///
+    /// assert_eq!(colors.get(&Color::Black), Some(&3));
+    /// assert_eq!(colors.get(&Color::Red), Some(&1));
+    /// assert_eq!(colors.len(), 2);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn texture_colors(&self, footer: &Footer) -> HashMap<Color, usize> {
+        let mut colors: HashMap<Color, usize> = HashMap::new();
+
+        for texel in self.uv_texels() {
+            *colors.entry(footer.pixel(texel)).or_insert(0) += 1;
+        }
+
+        colors
+    }
+
+    /// Checks if every pixel of the texture mapped onto this face has the same color.
+    ///
+    /// Such faces look the same when they use [`no_texture`](Face::no_texture) with that color
+    /// instead, which takes up less space in the file.
+    /// Returns `false` for faces that don't cover any pixels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Face, Footer, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(0.0, 0.0)),
+    ///     UVMap::new(1, point!(0.25, 0.0)),
+    ///     UVMap::new(2, point!(0.25, 0.25)),
+    /// ];
+    ///
+    /// assert!(face.is_single_color(&footer));
+    ///
+    /// footer.set(point!(1, 0), Color::Red).unwrap();
+    /// assert!(!face.is_single_color(&footer));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn is_single_color(&self, footer: &Footer) -> bool {
+        self.texture_colors(footer).len() == 1
+    }
+
+    /// Reverses the order of the faces vertices, so it points in the opposite direction.
+    ///
+    /// Each vertex keeps its uv-coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![UVMap::new(0, point!(0.0, 0.0)), UVMap::new(1, point!(1.0, 0.0))];
+    /// face.flip();
+    ///
+    /// assert_eq!(
+    ///     face.uv_maps,
+    ///     vec![UVMap::new(1, point!(1.0, 0.0)), UVMap::new(0, point!(0.0, 0.0))]
+    /// );
+    /// ```
+    pub fn flip(&mut self) {
+        self.uv_maps.reverse();
+    }
+
+    /// Mirrors the uv-coordinates of the face horizontally, so the texture shows up mirrored on
+    /// it.
+    ///
+    /// Coordinates are mirrored inside the area they span on the texture, so the face still uses
+    /// the same part of the texture.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![UVMap::new(0, point!(1.0, 0.0)), UVMap::new(1, point!(3.0, 2.0))];
+    /// face.mirror_uvs();
+    ///
+    /// assert_eq!(face.uv_maps[0].coords, point!(3.0, 0.0));
+    /// assert_eq!(face.uv_maps[1].coords, point!(1.0, 2.0));
+    /// ```
+    pub fn mirror_uvs(&mut self) {
+        self.uv_mirror(UvAxis::U);
+    }
+
+    /// Moves the uv-coordinates of the face by `offset`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![UVMap::new(0, point!(1.0, 0.0))];
+    /// face.uv_translate(point!(0.5, 2.0));
+    ///
+    /// assert_eq!(face.uv_maps[0].coords, point!(1.5, 2.0));
+    /// ```
+    pub fn uv_translate(&mut self, offset: Point2D<f64>) {
+        for uv_map in self.uv_maps.iter_mut() {
+            uv_map.coords += offset;
+        }
+    }
+
+    /// Scales the uv-coordinates of the face by `factor`, moving them away from or towards
+    /// `pivot`.
+    ///
+    /// Factors above `1.0` make the face use a bigger part of the texture, so the texture looks
+    /// smaller on it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![UVMap::new(0, point!(1.0, 1.0)), UVMap::new(1, point!(2.0, 3.0))];
+    /// face.uv_scale(2.0, point!(1.0, 1.0));
+    ///
+    /// assert_eq!(face.uv_maps[0].coords, point!(1.0, 1.0));
+    /// assert_eq!(face.uv_maps[1].coords, point!(3.0, 5.0));
+    /// ```
+    pub fn uv_scale(&mut self, factor: f64, pivot: Point2D<f64>) {
+        for uv_map in self.uv_maps.iter_mut() {
+            uv_map.coords = pivot + (uv_map.coords - pivot) * factor;
+        }
+    }
+
+    /// Rotates the uv-coordinates of the face clockwise by 90 degrees `times` times, negative
+    /// values rotate counterclockwise.
+    ///
+    /// The rotated coordinates keep the top left corner of the area they span on the texture, so
+    /// coordinates aligned to pixels stay aligned.
+    /// The texture shows up rotated the other way on the face.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(1.0, 1.0)),
+    ///     UVMap::new(1, point!(3.0, 1.0)),
+    ///     UVMap::new(2, point!(3.0, 2.0)),
+    /// ];
+    /// face.uv_rotate_90(1);
+    ///
+    /// assert_eq!(face.uv_maps[0].coords, point!(2.0, 1.0));
+    /// assert_eq!(face.uv_maps[1].coords, point!(2.0, 3.0));
+    /// assert_eq!(face.uv_maps[2].coords, point!(1.0, 3.0));
+    /// ```
+    pub fn uv_rotate_90(&mut self, times: i32) {
+        let Some((min, _)) = self.uv_bounds() else {
+            return;
+        };
+
+        for _ in 0..times.rem_euclid(4) {
+            // clockwise on the texture, where v points down.
+            for uv_map in self.uv_maps.iter_mut() {
+                uv_map.coords = point!(-uv_map.coords.v, uv_map.coords.u);
+            }
+        }
+
+        if let Some((rotated_min, _)) = self.uv_bounds() {
+            self.uv_translate(min - rotated_min);
+        }
+    }
+
+    /// Mirrors the uv-coordinates of the face along `axis`, inside the area they span on the
+    /// texture.
+    ///
+    /// [`UvAxis::U`] mirrors the texture horizontally, [`UvAxis::V`] vertically.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, UVMap};
+    /// use picocadrs::assets::face::UvAxis;
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![UVMap::new(0, point!(1.0, 0.0)), UVMap::new(1, point!(3.0, 2.0))];
+    /// face.uv_mirror(UvAxis::V);
+    ///
+    /// assert_eq!(face.uv_maps[0].coords, point!(1.0, 2.0));
+    /// assert_eq!(face.uv_maps[1].coords, point!(3.0, 0.0));
+    /// ```
+    pub fn uv_mirror(&mut self, axis: UvAxis) {
+        let Some((min, max)) = self.uv_bounds() else {
+            return;
+        };
+
+        for uv_map in self.uv_maps.iter_mut() {
+            match axis {
+                UvAxis::U => uv_map.coords.u = min.u + max.u - uv_map.coords.u,
+                UvAxis::V => uv_map.coords.v = min.v + max.v - uv_map.coords.v,
+            }
+        }
+    }
+
+    /// Rounds the uv-coordinates of the face to the nearest pixel of the texture, so every corner
+    /// lands on a multiple of [`UV_PIXEL_STEP`].
+    ///
+    /// Corners between pixels make picoCAD sample pixels next to the intended ones, which shows
+    /// up as thin lines of the wrong color along the edges of the face.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![UVMap::new(0, point!(0.06, 1.2)), UVMap::new(1, point!(2.0, 0.3))];
+    /// face.snap_uv_to_pixels();
+    ///
+    /// assert_eq!(face.uv_maps[0].coords, point!(0.0, 1.25));
+    /// assert_eq!(face.uv_maps[1].coords, point!(2.0, 0.25));
+    /// ```
+    pub fn snap_uv_to_pixels(&mut self) {
+        for uv_map in self.uv_maps.iter_mut() {
+            uv_map.coords.u = snap(uv_map.coords.u, UV_PIXEL_STEP);
+            uv_map.coords.v = snap(uv_map.coords.v, UV_PIXEL_STEP);
+        }
+    }
+
+    /// Moves and scales the uv-coordinates of the face so they cover the pixels in `rect`, the
+    /// way `fit` decides.
+    ///
+    /// The area the coordinates span is what gets fitted, the shape of the face inside of it stays
+    /// the same.
+    /// Afterwards the coordinates are snapped to pixels (see
+    /// [`snap_uv_to_pixels`](Face::snap_uv_to_pixels)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, Rect, UVMap};
+    /// use picocadrs::assets::face::FitMode;
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(0.0, 0.0)),
+    ///     UVMap::new(1, point!(2.0, 0.0)),
+    ///     UVMap::new(2, point!(2.0, 1.0)),
+    /// ];
+    ///
+    /// // 16x16 pixels starting at pixel 8, 0, which is 2x2 in uv-coordinates.
+    /// let rect = Rect::new(point!(8, 0), point!(16, 16));
+    ///
+    /// face.align_uv_to_rect(rect, FitMode::Contain);
+    /// assert_eq!(face.uv_maps[0].coords, point!(1.0, 0.5));
+    /// assert_eq!(face.uv_maps[2].coords, point!(3.0, 1.5));
+    ///
+    /// face.align_uv_to_rect(rect, FitMode::Stretch);
+    /// assert_eq!(face.uv_maps[2].coords, point!(3.0, 2.0));
+    /// ```
+    pub fn align_uv_to_rect(&mut self, rect: Rect, fit: FitMode) {
+        let Some((min, max)) = self.uv_bounds() else {
+            return;
+        };
+
+        let (target_min, target_max) = rect.to_uv();
+        let target_size = target_max - target_min;
+        let size = max - min;
+
+        // faces without width or height keep them, dividing by their size wouldn't work.
+        let ratio = |target: f64, current: f64| {
+            if current > 0.0 {
+                target / current
+            } else {
+                1.0
+            }
+        };
+
+        let (scale, offset) = match fit {
+            FitMode::Stretch => (
+                point!(ratio(target_size.u, size.u), ratio(target_size.v, size.v)),
+                point!(0.0, 0.0),
+            ),
+            FitMode::Contain => {
+                let factor = match (size.u > 0.0, size.v > 0.0) {
+                    (true, true) => ratio(target_size.u, size.u).min(ratio(target_size.v, size.v)),
+                    (true, false) => ratio(target_size.u, size.u),
+                    (false, true) => ratio(target_size.v, size.v),
+                    (false, false) => 1.0,
+                };
+
+                (point!(factor, factor), (target_size - size * factor) * 0.5)
+            }
+            FitMode::Position => (point!(1.0, 1.0), point!(0.0, 0.0)),
+        };
+
+        for uv_map in self.uv_maps.iter_mut() {
+            let relative = uv_map.coords - min;
+            uv_map.coords = point!(
+                target_min.u + offset.u + relative.u * scale.u,
+                target_min.v + offset.v + relative.v * scale.v
+            );
+        }
+
+        self.snap_uv_to_pixels();
+    }
+
+    /// Copies the uv-coordinates of `other` onto this face, so both show the same part of the
+    /// texture.
+    ///
+    /// Only the coordinates are copied, each corner keeps its vertex.
+    /// `mapping` decides which corner of `other` each corner of this face takes its coordinates
+    /// from.
+    /// Returns `false` and leaves the face untouched if both faces have a different amount of
+    /// corners or the mapping doesn't fit them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Point2D, UVMap};
+    /// use picocadrs::assets::face::IndexMapping;
+    /// use picocadrs::point;
+    ///
+    /// let mut template = Face::default();
+    /// template.uv_maps = vec![
+    ///     UVMap::new(0, point!(0.0, 0.0)),
+    ///     UVMap::new(1, point!(1.0, 0.0)),
+    ///     UVMap::new(2, point!(1.0, 1.0)),
+    /// ];
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(3, point!(5.0, 5.0)),
+    ///     UVMap::new(4, point!(5.0, 5.0)),
+    ///     UVMap::new(5, point!(5.0, 5.0)),
+    /// ];
+    ///
+    /// assert!(face.copy_uv_from(&template, IndexMapping::Shifted(1)));
+    /// assert_eq!(face.uv_maps[0], UVMap::new(3, point!(1.0, 0.0)));
+    /// assert_eq!(face.uv_maps[2], UVMap::new(5, point!(0.0, 0.0)));
+    /// ```
+    pub fn copy_uv_from(&mut self, other: &Face, mapping: IndexMapping) -> bool {
+        let len = self.uv_maps.len();
+        if other.uv_maps.len() != len {
+            return false;
+        }
+
+        let sources: Vec<usize> = match mapping {
+            IndexMapping::Same => (0..len).collect(),
+            IndexMapping::Shifted(offset) => (0..len).map(|i| (i + offset) % len).collect(),
+            IndexMapping::Reversed => (0..len).rev().collect(),
+            IndexMapping::Custom(sources) => sources,
+        };
+
+        if sources.len() != len || sources.iter().any(|source| *source >= len) {
+            return false;
+        }
+
+        for (uv_map, source) in self.uv_maps.iter_mut().zip(sources) {
+            uv_map.coords = other.uv_maps[source].coords;
+        }
+
+        true
+    }
+
+    /// Returns the smallest and largest uv-coordinates of the face on each axis, or [`None`] if it
+    /// has none.
+    fn uv_bounds(&self) -> Option<(Point2D<f64>, Point2D<f64>)> {
+        let first = self.uv_maps.first()?.coords;
+
+        Some(
+            self.uv_maps
+                .iter()
+                .fold((first, first), |(min, max), uv_map| {
+                    (min.min(uv_map.coords), max.max(uv_map.coords))
+                }),
+        )
+    }
+
+    /// Converts the face into the lua table picoCAD stores it as.
+    ///
+    /// Vertex indices are stored starting from `1`, flags are only stored if they are set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Face, Point2D, UVMap};
+    /// use picocadrs::lua_table::LuaValue;
+    /// use picocadrs::point;
+    ///
+    /// let face = Face {
+    ///     uv_maps: vec![UVMap::new(0, point!(1.0, 2.0)), UVMap::new(1, point!(0.5, 0.0))],
+    ///     color: Color::Red,
+    ///     double_sided: true,
+    ///     ..Face::default()
+    /// };
+    ///
+    /// let table = face.to_lua_table();
+    ///
+    /// assert_eq!(table.sequence, vec![LuaValue::Integer(1), LuaValue::Integer(2)]);
+    /// assert_eq!(table.fields["c"], LuaValue::Integer(8));
+    /// assert_eq!(LuaValue::Table(table).to_string(), "{1,2,c=8,dbl=1,uv={1,2,0.5,0}}");
+    /// ```
+    pub fn to_lua_table(&self) -> LuaTable {
+        let mut table = LuaTable {
+            sequence: self
+                .uv_maps
+                .iter()
+                .map(|uv_map| LuaValue::Integer(uv_map.vertex_index as i64 + 1))
+                .collect(),
+            fields: self.extras.clone(),
+            other: vec![],
+        };
+
+        table.fields.insert(
+            "c".to_string(),
+            LuaValue::Integer(self.color.as_i32() as i64),
+        );
+
+        for (key, set) in [
+            ("dbl", self.double_sided),
+            ("noshade", self.no_shading),
+            ("notex", self.no_texture),
+            ("prio", self.render_priority),
+        ] {
+            if set {
+                table.fields.insert(key.to_string(), LuaValue::Integer(1));
+            }
+        }
 
-use crate::assets::{Color, Point2D};
-use crate::error::PicoError;
-use crate::point;
-use rlua::{Lua, Table, Value};
-use std::fmt::{Display, Formatter};
-use std::str::FromStr;
+        let uv = LuaTable {
+            sequence: self
+                .uv_maps
+                .iter()
+                .flat_map(|uv_map| {
+                    [
+                        LuaValue::from(PicoNumber(uv_map.coords.u)),
+                        LuaValue::from(PicoNumber(uv_map.coords.v)),
+                    ]
+                })
+                .collect(),
+            ..LuaTable::default()
+        };
+        table.fields.insert("uv".to_string(), LuaValue::Table(uv));
 
-/// Represents uv-coordinates and the vertex they correspond to.
-///
-/// When building a face this helps with keeping index corresponding uv-coordinates together.
-/// Since internally these two are stored separately from each other, I think this also makes it
-/// easier to understand.
-///
-/// uv-mappings are stored inside the face of a mesh.
-///
-/// <br/>
+        table
+    }
+
+    /// Creates a face from the lua table picoCAD stores it as.
+    ///
+    /// Parsing a face from a string reads the table and then uses this function.
+    /// Fails with [`PicoError::FaceField`] if a vertex index or uv-coordinate is not a number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Face};
+    /// use picocadrs::lua_table::LuaValue;
+    ///
+    /// let value: LuaValue = "{1,2,3, c=8, prio=1, uv={0,0,1,0,1,1} }".parse().unwrap();
+    /// let face = Face::from_lua_table(value.as_table().unwrap()).unwrap();
+    ///
+    /// assert_eq!(face.color, Color::Red);
+    /// assert!(face.render_priority);
+    /// assert_eq!(face.uv_maps[2].vertex_index, 2);
+    /// ```
+    pub fn from_lua_table(table: &LuaTable) -> Result<Face, PicoError> {
+        let mut face = Face {
+            color: Color::Invalid,
+            ..Face::default()
+        };
+
+        for value in table.sequence.iter() {
+            match value.as_i64() {
+                Some(index) if index >= 1 => face
+                    .uv_maps
+                    .push(UVMap::new(index as usize - 1, point!(0.0, 0.0))),
+                _ => return Err(PicoError::FaceField("vertex index".to_string())),
+            }
+        }
+
+        for (key, value) in table.fields.iter() {
+            match key.as_str() {
+                "dbl" => face.double_sided = true,
+                "noshade" => face.no_shading = true,
+                "notex" => face.no_texture = true,
+                "prio" => face.render_priority = true,
+                "c" => {
+                    face.color = match value {
+                        LuaValue::Integer(int) => Color::from(*int as i32),
+                        _ => Color::Invalid,
+                    }
+                }
+                "uv" => {
+                    if let Some(uv) = value.as_table() {
+                        let uv_chunks = uv
+                            .sequence
+                            .iter()
+                            .map(|value| value.as_f64())
+                            .collect::<Option<Vec<f64>>>()
+                            .ok_or(PicoError::FaceField("uv".to_string()))?;
+
+                        if uv_chunks.len() != face.uv_maps.len() * 2 {
+                            return Err(PicoError::FaceUVMapLength(
+                                face.uv_maps.len(),
+                                uv_chunks.len(),
+                            ));
+                        }
+
+                        for (i, chunk) in uv_chunks.chunks_exact(2).enumerate() {
+                            face.uv_maps[i].coords = point!(chunk[0], chunk[1]);
+                        }
+                    }
+                }
+                _ => {
+                    face.extras.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        Ok(face)
+    }
+
+    /// Returns if `flag` is set on the face.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, FaceFlag};
+    ///
+    /// let mut face = Face::default();
+    /// face.no_shading = true;
+    ///
+    /// assert!(face.flag(FaceFlag::NoShading));
+    /// assert!(!face.flag(FaceFlag::DoubleSided));
+    /// ```
+    pub fn flag(&self, flag: FaceFlag) -> bool {
+        match flag {
+            FaceFlag::DoubleSided => self.double_sided,
+            FaceFlag::NoShading => self.no_shading,
+            FaceFlag::RenderPriority => self.render_priority,
+            FaceFlag::NoTexture => self.no_texture,
+        }
+    }
+
+    /// Sets `flag` on the face to `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, FaceFlag};
+    ///
+    /// let mut face = Face::default();
+    /// face.set_flag(FaceFlag::RenderPriority, true);
+    ///
+    /// assert!(face.render_priority);
+    /// ```
+    pub fn set_flag(&mut self, flag: FaceFlag, value: bool) {
+        let field = match flag {
+            FaceFlag::DoubleSided => &mut self.double_sided,
+            FaceFlag::NoShading => &mut self.no_shading,
+            FaceFlag::RenderPriority => &mut self.render_priority,
+            FaceFlag::NoTexture => &mut self.no_texture,
+        };
+        *field = value;
+    }
+
+    /// Returns the positions of the vertices this face spans between in order.
+    /// Returns [`None`] if any of the vertices don't exist.
+    pub(crate) fn corners(&self, vertices: &[Point3D<f64>]) -> Option<Vec<Point3D<f64>>> {
+        self.uv_maps
+            .iter()
+            .map(|uv_map| vertices.get(uv_map.vertex_index).copied())
+            .collect()
+    }
+}
+
+/// An axis of the texture.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum UvAxis {
+    /// Horizontal axis, pointing right.
+    #[default]
+    U,
+    /// Vertical axis, pointing down.
+    V,
+}
+
+/// Which corner of another face each corner takes its uv-coordinates from, used by
+/// [`Face::copy_uv_from`].
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub enum IndexMapping {
+    /// Each corner takes the coordinates of the corner at the same position.
+    #[default]
+    Same,
+    /// Each corner takes the coordinates of the corner that many positions further along,
+    /// which rotates the texture on the face.
+    Shifted(usize),
+    /// Corners take the coordinates in reverse order, which mirrors the texture on the face.
+    Reversed,
+    /// Each corner takes the coordinates of the corner at the position given at its own position.
+    Custom(Vec<usize>),
+}
+
+/// How [`Face::align_uv_to_rect`] fits the uv-coordinates of a face into a rectangle.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum FitMode {
+    /// Scales each axis on its own to fill the whole rectangle, which may distort the texture.
+    #[default]
+    Stretch,
+    /// Scales both axes the same way, as big as possible while staying inside the rectangle, and
+    /// centers the result.
+    Contain,
+    /// Keeps the size and only moves the coordinates into the top left corner of the rectangle.
+    Position,
+}
+
+/// One of the on/off settings of a [`Face`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FaceFlag {
+    /// See [`Face::double_sided`].
+    DoubleSided,
+    /// See [`Face::no_shading`].
+    NoShading,
+    /// See [`Face::render_priority`].
+    RenderPriority,
+    /// See [`Face::no_texture`].
+    NoTexture,
+}
+
+/// Builds a [`Face`] without having to keep vertex indices and uv-coordinates in sync by hand.
 ///
-/// Indexes are not the same they are in the project files.
-/// picoCAD uses indexes starting from 1 for referencing vertices.
-/// To make it more in line with standard programming rules they start from 0 here and only will be
-/// converted into the actual indexes when serializing.
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct UVMap {
-    pub vertex_index: usize,
-    pub coords: Point2D<f64>,
+/// Created with [`Face::builder`].
+/// Everything that isn't set is the same as in [`Face::default`], corners without
+/// uv-coordinates are mapped to `0, 0`.
+#[derive(Debug, Clone, Default)]
+pub struct FaceBuilder {
+    face: Face,
+    vertices: Vec<usize>,
+    uvs: Option<Vec<Point2D<f64>>>,
+    uv_rect: Option<Rect>,
 }
 
-impl UVMap {
-    /// Creates a new `UVMap`.
+impl FaceBuilder {
+    /// Sets the indices of the vertices the face spans between, in the order they are connected.
+    /// Indices start from 0, like in [`UVMap`].
+    pub fn vertices<I: IntoIterator<Item = usize>>(mut self, vertices: I) -> Self {
+        self.vertices = vertices.into_iter().collect();
+        self
+    }
+
+    /// Sets the uv-coordinates of the corners, in the same order as
+    /// [`vertices`](FaceBuilder::vertices).
+    pub fn uvs<I: IntoIterator<Item = Point2D<f64>>>(mut self, uvs: I) -> Self {
+        self.uvs = Some(uvs.into_iter().collect());
+        self
+    }
+
+    /// Maps the face onto the pixels in `rect`.
+    ///
+    /// Quads get the corners of `rect` clockwise, starting in the top left.
+    /// Triangles get the top left, top right and bottom right corner.
+    /// Other faces get their corners spread evenly around an ellipse filling `rect`.
+    /// If [`uvs`](FaceBuilder::uvs) are set too, those are fitted into `rect` instead.
+    pub fn uv_rect(mut self, rect: Rect) -> Self {
+        self.uv_rect = Some(rect);
+        self
+    }
+
+    /// Sets the color of the face.
+    pub fn color(mut self, color: Color) -> Self {
+        self.face.color = color;
+        self
+    }
+
+    /// Sets if the face is rendered from both sides.
+    pub fn double_sided(mut self, double_sided: bool) -> Self {
+        self.face.double_sided = double_sided;
+        self
+    }
+
+    /// Sets if the face is drawn without shading.
+    pub fn no_shading(mut self, no_shading: bool) -> Self {
+        self.face.no_shading = no_shading;
+        self
+    }
+
+    /// Sets if the face is rendered before all other faces.
+    pub fn render_priority(mut self, render_priority: bool) -> Self {
+        self.face.render_priority = render_priority;
+        self
+    }
+
+    /// Sets if the face shows its color instead of the texture.
+    pub fn no_texture(mut self, no_texture: bool) -> Self {
+        self.face.no_texture = no_texture;
+        self
+    }
+
+    /// Adds a key to the [`extras`](Face::extras) of the face.
+    pub fn extra(mut self, key: String, value: LuaValue) -> Self {
+        self.face.extras.insert(key, value);
+        self
+    }
+
+    /// Puts the face together.
+    ///
+    /// Fails with [`PicoError::FaceUVMapLength`] if uv-coordinates were set for a different amount
+    /// of corners than there are vertices.
     ///
     /// # Example
     ///
     /// ```
-    /// use picocadrs::assets::{UVMap, Point2D};
+    /// use picocadrs::assets::{Face, Point2D};
     /// use picocadrs::point;
     ///
-    /// let map = UVMap::new(2, point!(2.0, 3.5));
+    /// let face = Face::builder()
+    ///     .vertices([2, 1, 0])
+    ///     .uvs([point!(0.0, 0.0), point!(1.0, 0.0), point!(1.0, 1.0)])
+    ///     .build()
+    ///     .unwrap();
     ///
-    /// assert_eq!(map.vertex_index, 2);
-    /// assert_eq!(map.coords.u, 2.0);
-    /// assert_eq!(map.coords.v, 3.5);
+    /// assert_eq!(face.to_string(), "{3,2,1, c=0, uv={0,0,1,0,1,1} }");
+    ///
+    /// assert!(Face::builder()
+    ///     .vertices([2, 1, 0])
+    ///     .uvs([point!(0.0, 0.0)])
+    ///     .build()
+    ///     .is_err());
     /// ```
-    pub fn new(vertex_index: usize, coords: Point2D<f64>) -> UVMap {
-        UVMap {
-            vertex_index,
-            coords,
+    pub fn build(self) -> Result<Face, PicoError> {
+        let FaceBuilder {
+            mut face,
+            vertices,
+            uvs,
+            uv_rect,
+        } = self;
+
+        let uvs = match uvs {
+            Some(uvs) if uvs.len() != vertices.len() => {
+                return Err(PicoError::FaceUVMapLength(uvs.len(), vertices.len()));
+            }
+            Some(uvs) => uvs,
+            None if uv_rect.is_some() => rect_corners(vertices.len()),
+            None => vec![point!(0.0, 0.0); vertices.len()],
+        };
+
+        face.uv_maps = vertices
+            .into_iter()
+            .zip(uvs)
+            .map(|(vertex_index, coords)| UVMap::new(vertex_index, coords))
+            .collect();
+
+        if let Some(rect) = uv_rect {
+            face.align_uv_to_rect(rect, FitMode::Stretch);
         }
+
+        Ok(face)
     }
 }
 
-/// Represents the face of a mesh.
-#[derive(Debug, Clone, PartialEq)]
-pub struct Face {
-    /// If true, face will get rendered from both sides.
-    pub double_sided: bool,
-    /// If true, no shading will be applied to this face.
-    pub no_shading: bool,
-    /// If true, this face will be rendered first.
-    /// This means it will appear behind all other faces.
-    pub render_priority: bool,
-    /// If true, no texture will be rendered on this face.
-    pub no_texture: bool,
-    /// Color of the face. If `no_texture` is enabled this color will show.
-    pub color: Color,
-    /// uv-mappings of this face.
-    /// Tells picoCAD which vertices this face is between and where they are on the uv-map.
-    pub uv_maps: Vec<UVMap>,
+/// Returns `count` corners spanning the square from `0, 0` to `1, 1`, used by
+/// [`FaceBuilder::uv_rect`].
+fn rect_corners(count: usize) -> Vec<Point2D<f64>> {
+    match count {
+        3 => vec![point!(0.0, 0.0), point!(1.0, 0.0), point!(1.0, 1.0)],
+        4 => vec![
+            point!(0.0, 0.0),
+            point!(1.0, 0.0),
+            point!(1.0, 1.0),
+            point!(0.0, 1.0),
+        ],
+        _ => (0..count)
+            .map(|i| {
+                // starts at the top and goes clockwise, since v points down.
+                let angle = core::f64::consts::TAU * i as f64 / count as f64;
+                point!(0.5 + libm::sin(angle) * 0.5, 0.5 - libm::cos(angle) * 0.5)
+            })
+            .collect(),
+    }
+}
+
+/// Where a ray hits a face, returned by [`Face::intersect_ray`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FaceHit {
+    /// Distance from the start of the ray to the hit.
+    pub distance: f64,
+    /// Position of the hit, in the same space as the vertices of the face.
+    pub point: Point3D<f64>,
+    /// uv-coordinates at the hit.
+    pub uv: Point2D<f64>,
+    /// Indices into [`uv_maps`](Face::uv_maps) of the corners of the triangle that was hit.
+    pub triangle: [usize; 3],
+    /// Weights of the corners of [`triangle`](FaceHit::triangle) at the hit, adding up to `1.0`.
+    pub barycentric: [f64; 3],
+}
+
+/// Returns the distance to where the ray hits the triangle and the weights of its corners there,
+/// using the Möller-Trumbore algorithm.
+fn intersect_triangle(
+    triangle: [Point3D<f64>; 3],
+    origin: Point3D<f64>,
+    direction: Point3D<f64>,
+) -> Option<(f64, [f64; 3])> {
+    const EPSILON: f64 = 1e-9;
+
+    let edge_1 = triangle[1] - triangle[0];
+    let edge_2 = triangle[2] - triangle[0];
+    let p = direction.cross(edge_2);
+    let determinant = edge_1.dot(p);
+
+    if determinant.abs() < EPSILON {
+        return None;
+    }
+
+    let offset = origin - triangle[0];
+    let u = offset.dot(p) / determinant;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = offset.cross(edge_1);
+    let v = direction.dot(q) / determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let distance = edge_2.dot(q) / determinant;
+    if distance <= EPSILON {
+        return None;
+    }
+
+    Some((distance, [1.0 - u - v, u, v]))
+}
+
+/// Checks if `point` lies inside of `polygon` using the even-odd rule.
+fn polygon_contains(polygon: &[Point2D<f64>], point: Point2D<f64>) -> bool {
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+
+    for current in polygon.iter() {
+        if (current.v > point.v) != (previous.v > point.v)
+            && point.u
+                < (previous.u - current.u) * (point.v - current.v) / (previous.v - current.v)
+                    + current.u
+        {
+            inside = !inside;
+        }
+
+        previous = *current;
+    }
+
+    inside
 }
 
 impl Default for Face {
@@ -133,18 +1456,19 @@ impl Default for Face {
             no_texture: false,
             color: Color::Black,
             uv_maps: vec![],
+            extras: BTreeMap::new(),
         }
     }
 }
 
 impl Display for Face {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let mut vertex_indices = String::new();
         let mut uvs = String::new();
 
         for uv_map in self.uv_maps.iter() {
             vertex_indices.push_str(format!("{},", uv_map.vertex_index + 1).as_str());
-            uvs.push_str(format!("{},", uv_map.coords).as_str());
+            uvs.push_str(format!("{},", PicoFormat(&uv_map.coords)).as_str());
         }
 
         let mut attributes = String::new();
@@ -165,6 +1489,10 @@ impl Display for Face {
             attributes.push_str("prio=1, ");
         }
 
+        for (key, value) in self.extras.iter() {
+            attributes.push_str(format!("{}, ", lua_table::field(key, value)).as_str());
+        }
+
         write!(
             f,
             "{{{ } c={ }, { }uv={{{ }}} }}",
@@ -176,6 +1504,7 @@ impl Display for Face {
     }
 }
 
+#[cfg(feature = "std")]
 impl TryFrom<Table<'_>> for Face {
     type Error = PicoError;
 
@@ -189,13 +1518,24 @@ impl TryFrom<Table<'_>> for Face {
         let mut no_shading: bool = false;
         let mut no_texture: bool = false;
         let mut render_priority: bool = false;
+        let mut extras: BTreeMap<String, LuaValue> = BTreeMap::new();
 
         for seq_value in value.clone().sequence_values::<usize>() {
-            uv_maps.push(UVMap::new(seq_value? - 1, point!(0.0, 0.0)));
+            let vertex_index = seq_value?
+                .checked_sub(1)
+                .ok_or(PicoError::FaceField("vertex index".to_string()))?;
+
+            uv_maps.push(UVMap::new(vertex_index, point!(0.0, 0.0)));
         }
 
-        for pair in value.pairs::<String, Value>() {
-            let (key, value) = pair.unwrap();
+        for pair in value.pairs::<Value, Value>() {
+            let (key, value) = pair?;
+
+            // integer keys are the vertex indices read above.
+            let key = match key {
+                Value::String(key) => key.to_str()?.to_string(),
+                _ => continue,
+            };
 
             match key.as_str() {
                 "dbl" => double_sided = true,
@@ -225,7 +1565,9 @@ impl TryFrom<Table<'_>> for Face {
                         }
                     }
                 }
-                _ => {}
+                _ => {
+                    extras.insert(key, LuaValue::try_from(value)?);
+                }
             }
         }
 
@@ -236,6 +1578,7 @@ impl TryFrom<Table<'_>> for Face {
             render_priority,
             uv_maps,
             color,
+            extras,
         })
     }
 }
@@ -266,19 +1609,7 @@ impl FromStr for Face {
     /// assert_eq!(face.uv_maps[1], UVMap::new(2, point!(1.25, 0.0)));
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut face = Ok(Face::default());
-
-        let lua = Lua::new();
-        lua.context(|ctx| {
-            let table_result: rlua::Result<Table> = ctx.load(s).eval();
-
-            face = match table_result {
-                Ok(table) => Face::try_from(table),
-                Err(err) => Err(PicoError::from(err)),
-            }
-        });
-
-        face
+        Face::from_lua_table(&crate::lua_table::parse_table(s)?)
     }
 }
 
@@ -287,6 +1618,16 @@ pub mod tests {
     use super::*;
     use crate::point;
 
+    fn quad_face() -> Face {
+        let mut face = Face::default();
+
+        for i in 0..4 {
+            face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+        }
+
+        face
+    }
+
     #[test]
     fn test_uvmap_new() {
         let map = UVMap::new(2, point!(2.0, 3.5));
@@ -345,4 +1686,289 @@ pub mod tests {
         assert!(face.render_priority);
         assert_eq!(face.uv_maps[1], UVMap::new(2, point!(1.25, 0.0)));
     }
+
+    #[test]
+    fn test_face_areas() {
+        let vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(0.0, 0.0, 2.0),
+            point!(2.0, 1.0, 2.0),
+            point!(2.0, 1.0, 0.0),
+        ];
+        let mut face = quad_face();
+
+        assert_eq!(face.uv_area(), 0.0);
+        assert!((face.world_area(&vertices) - 2.0 * 5.0f64.sqrt()).abs() < 1e-9);
+        assert_eq!(face.world_area(&vertices[..3]), 0.0);
+
+        face.uv_maps[1].coords = point!(1.0, 0.0);
+        face.uv_maps[2].coords = point!(1.0, 1.0);
+        // winding doesn't matter.
+        face.uv_maps.reverse();
+        assert_eq!(face.uv_area(), 0.5);
+
+        face.uv_maps.truncate(2);
+        assert_eq!(face.uv_area(), 0.0);
+    }
+
+    #[test]
+    fn test_face_normal() {
+        let vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(0.0, 0.0, 2.0),
+            point!(2.0, 0.0, 2.0),
+            point!(2.0, 0.0, 0.0),
+        ];
+
+        assert_eq!(quad_face().normal(&vertices), point!(0.0, -1.0, 0.0));
+
+        let mut reversed = quad_face();
+        reversed.uv_maps.reverse();
+        assert_eq!(reversed.normal(&vertices), point!(0.0, 1.0, 0.0));
+
+        assert_eq!(Face::default().normal(&vertices), point!(0.0, 0.0, 0.0));
+        assert_eq!(quad_face().normal(&vertices[..2]), point!(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_face_is_planar() {
+        let mut vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(1.0, 0.0, 0.0),
+            point!(1.0, 1.0, 0.0),
+            point!(0.0, 1.0, 0.0),
+        ];
+
+        assert!(quad_face().is_planar(&vertices, 0.0));
+
+        vertices[2].z = 0.01;
+        assert!(!quad_face().is_planar(&vertices, 0.001));
+        assert!(quad_face().is_planar(&vertices, 0.01));
+
+        assert!(!quad_face().is_planar(&vertices[..3], 0.1));
+    }
+
+    #[test]
+    fn test_face_flip() {
+        let vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(0.0, 0.0, 2.0),
+            point!(2.0, 0.0, 2.0),
+            point!(2.0, 0.0, 0.0),
+        ];
+
+        let mut face = quad_face();
+        face.uv_maps[0].coords = point!(1.0, 1.0);
+        face.flip();
+
+        assert_eq!(face.normal(&vertices), point!(0.0, 1.0, 0.0));
+        assert_eq!(face.uv_maps[3], UVMap::new(0, point!(1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_face_align_uv_to_rect() {
+        let mut face = quad_face();
+        face.uv_maps[1].coords = point!(0.5, 0.0);
+        face.uv_maps[2].coords = point!(0.5, 0.5);
+        face.uv_maps[3].coords = point!(0.0, 0.5);
+
+        let rect = Rect::new(point!(4, 8), point!(8, 16));
+
+        face.align_uv_to_rect(rect, FitMode::Position);
+        assert_eq!(face.uv_maps[0].coords, point!(0.5, 1.0));
+        assert_eq!(face.uv_maps[2].coords, point!(1.0, 1.5));
+
+        face.align_uv_to_rect(rect, FitMode::Contain);
+        assert_eq!(face.uv_maps[0].coords, point!(0.5, 1.5));
+        assert_eq!(face.uv_maps[2].coords, point!(1.5, 2.5));
+
+        face.align_uv_to_rect(rect, FitMode::Stretch);
+        assert_eq!(face.uv_maps[0].coords, point!(0.5, 1.0));
+        assert_eq!(face.uv_maps[2].coords, point!(1.5, 3.0));
+
+        // a face squashed into a line keeps being a line.
+        for uv_map in face.uv_maps.iter_mut() {
+            uv_map.coords.v = 0.3;
+        }
+        face.align_uv_to_rect(rect, FitMode::Contain);
+        assert!(face.uv_maps.iter().all(|uv_map| uv_map.coords.v == 2.0));
+        assert_eq!(face.uv_maps[2].coords.u, 1.5);
+
+        let mut empty = Face::default();
+        empty.align_uv_to_rect(rect, FitMode::Stretch);
+        assert!(empty.uv_maps.is_empty());
+    }
+
+    #[test]
+    fn test_face_uv_transforms() {
+        let mut face = quad_face();
+        face.uv_maps[1].coords = point!(1.0, 0.0);
+        face.uv_maps[2].coords = point!(1.0, 0.5);
+        face.uv_maps[3].coords = point!(0.0, 0.5);
+        let original = face.clone();
+
+        face.uv_rotate_90(4);
+        assert_eq!(face, original);
+        face.uv_rotate_90(-1);
+        assert_eq!(face.uv_maps[1].coords, point!(0.0, 0.0));
+        assert_eq!(face.uv_maps[2].coords, point!(0.5, 0.0));
+        face.uv_rotate_90(1);
+        assert_eq!(face, original);
+
+        face.uv_mirror(UvAxis::U);
+        face.uv_mirror(UvAxis::V);
+        face.uv_rotate_90(2);
+        assert_eq!(face, original);
+
+        face.uv_translate(point!(1.0, 1.0));
+        face.uv_scale(0.5, point!(1.0, 1.0));
+        assert_eq!(face.uv_maps[2].coords, point!(1.5, 1.25));
+        face.uv_scale(2.0, point!(1.0, 1.0));
+        face.uv_translate(point!(-1.0, -1.0));
+        assert_eq!(face, original);
+    }
+
+    #[test]
+    fn test_face_copy_uv_from() {
+        let mut template = quad_face();
+        for (i, uv_map) in template.uv_maps.iter_mut().enumerate() {
+            uv_map.coords = point!(i as f64, 0.0);
+        }
+
+        let mut face = quad_face();
+        assert!(face.copy_uv_from(&template, IndexMapping::Reversed));
+        assert_eq!(face.uv_maps[0].coords, point!(3.0, 0.0));
+        assert_eq!(face.uv_maps[0].vertex_index, 0);
+
+        assert!(face.copy_uv_from(&template, IndexMapping::Custom(vec![1, 1, 2, 0])));
+        assert_eq!(face.uv_maps[1].coords, point!(1.0, 0.0));
+        assert_eq!(face.uv_maps[3].coords, point!(0.0, 0.0));
+
+        let before = face.clone();
+        assert!(!face.copy_uv_from(&template, IndexMapping::Custom(vec![0, 1, 2, 4])));
+        assert!(!face.copy_uv_from(&template, IndexMapping::Custom(vec![0, 1])));
+        assert!(!face.copy_uv_from(&Face::default(), IndexMapping::Same));
+        assert_eq!(face, before);
+
+        assert!(face.copy_uv_from(&template, IndexMapping::Shifted(5)));
+        assert_eq!(face.uv_maps[3].coords, point!(0.0, 0.0));
+    }
+
+    #[test]
+    fn test_face_mirror_uvs() {
+        let mut face = quad_face();
+        face.uv_maps[0].coords = point!(1.0, 0.0);
+        face.uv_maps[1].coords = point!(2.0, 0.0);
+        face.uv_maps[2].coords = point!(2.0, 1.0);
+        face.uv_maps[3].coords = point!(1.5, 1.0);
+        face.mirror_uvs();
+
+        assert_eq!(face.uv_maps[0].coords, point!(2.0, 0.0));
+        assert_eq!(face.uv_maps[1].coords, point!(1.0, 0.0));
+        assert_eq!(face.uv_maps[2].coords, point!(1.0, 1.0));
+        assert_eq!(face.uv_maps[3].coords, point!(1.5, 1.0));
+
+        let mut empty = Face::default();
+        empty.mirror_uvs();
+        assert!(empty.uv_maps.is_empty());
+    }
+
+    #[test]
+    fn test_face_uv_texels() {
+        let mut face = quad_face();
+        face.uv_maps[0].coords = point!(1.0, 1.0);
+        face.uv_maps[1].coords = point!(2.0, 1.0);
+        face.uv_maps[2].coords = point!(2.0, 2.0);
+        face.uv_maps[3].coords = point!(1.0, 2.0);
+
+        let texels = face.uv_texels();
+        assert_eq!(texels.len(), 64);
+        assert_eq!(texels[0], point!(8, 8));
+        assert_eq!(texels[63], point!(15, 15));
+
+        // triangle covering half of the square, including the pixels centered on the diagonal.
+        face.uv_maps.remove(3);
+        assert_eq!(face.uv_texels().len(), 36);
+
+        // faces partly outside of the texture.
+        let mut face = quad_face();
+        face.uv_maps[0].coords = point!(-1.0, -1.0);
+        face.uv_maps[1].coords = point!(0.25, -1.0);
+        face.uv_maps[2].coords = point!(0.25, 0.25);
+        face.uv_maps[3].coords = point!(-1.0, 0.25);
+        assert_eq!(face.uv_texels().len(), 4);
+
+        face.uv_maps.truncate(2);
+        assert!(face.uv_texels().is_empty());
+    }
+
+    #[test]
+    fn test_face_texture_colors() {
+        let mut footer = Footer::default();
+        for u in 8..16 {
+            footer.set(point!(u, 8), Color::Blue).unwrap();
+        }
+
+        let mut face = quad_face();
+        face.uv_maps[0].coords = point!(1.0, 1.0);
+        face.uv_maps[1].coords = point!(2.0, 1.0);
+        face.uv_maps[2].coords = point!(2.0, 2.0);
+        face.uv_maps[3].coords = point!(1.0, 2.0);
+
+        let colors = face.texture_colors(&footer);
+        assert_eq!(colors.get(&Color::Blue), Some(&8));
+        assert_eq!(colors.get(&Color::Black), Some(&56));
+        assert!(!face.is_single_color(&footer));
+
+        face.uv_maps[2].coords = point!(2.0, 1.125);
+        face.uv_maps[3].coords = point!(1.0, 1.125);
+        assert!(face.is_single_color(&footer));
+
+        face.uv_maps.truncate(2);
+        assert!(face.texture_colors(&footer).is_empty());
+        assert!(!face.is_single_color(&footer));
+    }
+
+    #[test]
+    fn test_face_builder() {
+        let face = Face::builder()
+            .vertices([0, 1, 2, 3, 4])
+            .no_texture(true)
+            .no_shading(true)
+            .render_priority(true)
+            .extra("tag".to_string(), LuaValue::Boolean(true))
+            .uv_rect(Rect::new(point!(8, 8), point!(16, 16)))
+            .build()
+            .unwrap();
+
+        assert_eq!(face.uv_maps.len(), 5);
+        assert_eq!(face.uv_maps[0].coords.v, 1.0);
+        assert!(face
+            .uv_maps
+            .iter()
+            .all(|uv_map| (1.0..=3.0).contains(&uv_map.coords.u)
+                && (1.0..=3.0).contains(&uv_map.coords.v)));
+        assert!(face.no_texture && face.no_shading && face.render_priority);
+        assert_eq!(face.extras.len(), 1);
+
+        let face = Face::builder()
+            .vertices([0, 1, 2])
+            .uv_rect(Rect::new(point!(0, 8), point!(8, 8)))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            face.uv_maps
+                .iter()
+                .map(|uv_map| uv_map.coords)
+                .collect::<Vec<_>>(),
+            vec![point!(0.0, 1.0), point!(1.0, 1.0), point!(1.0, 2.0)]
+        );
+
+        assert!(matches!(
+            Face::builder().vertices([0, 1]).uvs([]).build(),
+            Err(PicoError::FaceUVMapLength(0, 2))
+        ));
+        assert_eq!(Face::builder().build().unwrap(), Face::default());
+    }
 }
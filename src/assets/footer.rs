@@ -15,14 +15,270 @@
 //! Any numbers above or below will still be mapped appropriately, but will not return good results
 //! in most cases but are not disallowed by picoCAD.
 
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
 use crate::{
-    assets::{Color, Point2D},
+    assets::{mesh::UV_PIXEL_STEP, Color, Point2D, TexelCoord, UvCoord},
     error::PicoError,
-    point,
+    pico8, point,
 };
-use std::fmt::{Display, Formatter};
-use std::ops::{Index, IndexMut};
-use std::str::FromStr;
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt::{Display, Formatter};
+#[cfg(feature = "index")]
+use core::ops::{Index, IndexMut};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// A rectangle of pixels in the texture.
+///
+/// Rectangles are given in pixels, [`Rect::from_uv`] and [`Rect::to_uv`] convert them from and to
+/// uv-coordinates, where a pixel is [`UV_PIXEL_STEP`] long.
+/// They may reach outside of the texture, [`Rect::intersection`] with [`Rect::TEXTURE`] clips
+/// them.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Point2D, Rect};
+/// use picocadrs::point;
+///
+/// let rect = Rect::new(point!(8, 0), point!(16, 8));
+///
+/// assert!(rect.contains(point!(8, 7)));
+/// assert!(!rect.contains(point!(24, 0)));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Rect {
+    /// Pixel in the top left corner.
+    pub position: Point2D<usize>,
+    /// Width and height in pixels.
+    pub size: Point2D<usize>,
+}
+
+impl Rect {
+    /// The whole texture, 128x120 pixels.
+    pub const TEXTURE: Rect = Rect {
+        position: Point2D { u: 0, v: 0 },
+        size: Point2D {
+            u: Footer::WIDTH,
+            v: Footer::HEIGHT,
+        },
+    };
+
+    /// Creates a new `Rect` from its top left corner and its size.
+    pub fn new(position: Point2D<usize>, size: Point2D<usize>) -> Rect {
+        Rect { position, size }
+    }
+
+    /// Returns the smallest rectangle containing all given pixels, [`None`] if there are none.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let rect = Rect::from_texels([point!(4, 2), point!(1, 5), point!(3, 3)]).unwrap();
+    ///
+    /// assert_eq!(rect, Rect::new(point!(1, 2), point!(4, 4)));
+    /// assert_eq!(Rect::from_texels([]), None);
+    /// ```
+    pub fn from_texels<I: IntoIterator<Item = Point2D<usize>>>(texels: I) -> Option<Rect> {
+        texels.into_iter().fold(None, |rect: Option<Rect>, texel| {
+            let single = Rect::new(texel, point!(1, 1));
+            Some(match rect {
+                Some(rect) => rect.union(&single),
+                None => single,
+            })
+        })
+    }
+
+    /// Returns the smallest rectangle covering every pixel the area between the uv-coordinates
+    /// `min` and `max` touches.
+    ///
+    /// Parts left of or above the texture are cut off.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// assert_eq!(
+    ///     Rect::from_uv(point!(1.0, 0.5), point!(2.0, 1.1)),
+    ///     Rect::new(point!(8, 4), point!(8, 5))
+    /// );
+    /// ```
+    pub fn from_uv(min: Point2D<f64>, max: Point2D<f64>) -> Rect {
+        let start = point!(
+            (min.u / UV_PIXEL_STEP).floor().max(0.0) as usize,
+            (min.v / UV_PIXEL_STEP).floor().max(0.0) as usize
+        );
+        let end = point!(
+            (max.u / UV_PIXEL_STEP).ceil().max(0.0) as usize,
+            (max.v / UV_PIXEL_STEP).ceil().max(0.0) as usize
+        );
+
+        Rect::new(
+            start,
+            point!(end.u.saturating_sub(start.u), end.v.saturating_sub(start.v)),
+        )
+    }
+
+    /// Returns the uv-coordinates of the top left and bottom right corner of the rectangle.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let rect = Rect::new(point!(8, 0), point!(16, 4));
+    ///
+    /// assert_eq!(rect.to_uv(), (point!(1.0, 0.0), point!(3.0, 0.5)));
+    /// ```
+    pub fn to_uv(&self) -> (Point2D<f64>, Point2D<f64>) {
+        let end = self.end();
+
+        (
+            point!(self.position.u as f64, self.position.v as f64) * UV_PIXEL_STEP,
+            point!(end.u as f64, end.v as f64) * UV_PIXEL_STEP,
+        )
+    }
+
+    /// Returns the pixel just past the bottom right corner of the rectangle.
+    pub fn end(&self) -> Point2D<usize> {
+        point!(
+            self.position.u.saturating_add(self.size.u),
+            self.position.v.saturating_add(self.size.v)
+        )
+    }
+
+    /// Returns the amount of pixels inside the rectangle, [`usize::MAX`] if there are more.
+    pub fn area(&self) -> usize {
+        self.size.u.saturating_mul(self.size.v)
+    }
+
+    /// Checks if the rectangle has no pixels.
+    pub fn is_empty(&self) -> bool {
+        self.size.u == 0 || self.size.v == 0
+    }
+
+    /// Checks if the pixel at the given coordinates is inside the rectangle.
+    pub fn contains(&self, coords: Point2D<usize>) -> bool {
+        coords.u >= self.position.u
+            && coords.v >= self.position.v
+            && coords.u - self.position.u < self.size.u
+            && coords.v - self.position.v < self.size.v
+    }
+
+    /// Checks if every pixel of `other` is inside the rectangle.
+    ///
+    /// Empty rectangles are inside of every rectangle.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        if other.is_empty() {
+            return true;
+        }
+
+        let (end, other_end) = (self.end(), other.end());
+        self.position.u <= other.position.u
+            && self.position.v <= other.position.v
+            && other_end.u <= end.u
+            && other_end.v <= end.v
+    }
+
+    /// Checks if both rectangles share at least one pixel.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Returns the pixels both rectangles share, [`None`] if there are none.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let a = Rect::new(point!(0, 0), point!(8, 8));
+    /// let b = Rect::new(point!(4, 6), point!(8, 8));
+    ///
+    /// assert_eq!(a.intersection(&b), Some(Rect::new(point!(4, 6), point!(4, 2))));
+    /// assert_eq!(a.intersection(&Rect::new(point!(8, 0), point!(1, 1))), None);
+    ///
+    /// // clipping to the texture.
+    /// let outside = Rect::new(point!(120, 100), point!(16, 32));
+    /// assert_eq!(
+    ///     outside.intersection(&Rect::TEXTURE),
+    ///     Some(Rect::new(point!(120, 100), point!(8, 20)))
+    /// );
+    /// ```
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let (end, other_end) = (self.end(), other.end());
+        let start = point!(
+            self.position.u.max(other.position.u),
+            self.position.v.max(other.position.v)
+        );
+        let end = point!(end.u.min(other_end.u), end.v.min(other_end.v));
+
+        (start.u < end.u && start.v < end.v)
+            .then(|| Rect::new(start, point!(end.u - start.u, end.v - start.v)))
+    }
+
+    /// Returns the smallest rectangle containing both rectangles.
+    ///
+    /// Empty rectangles are ignored, unless both are empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let a = Rect::new(point!(0, 0), point!(8, 8));
+    /// let b = Rect::new(point!(16, 4), point!(8, 8));
+    ///
+    /// assert_eq!(a.union(&b), Rect::new(point!(0, 0), point!(24, 12)));
+    /// ```
+    pub fn union(&self, other: &Rect) -> Rect {
+        if other.is_empty() {
+            return *self;
+        }
+        if self.is_empty() {
+            return *other;
+        }
+
+        let (end, other_end) = (self.end(), other.end());
+        let start = point!(
+            self.position.u.min(other.position.u),
+            self.position.v.min(other.position.v)
+        );
+        let end = point!(end.u.max(other_end.u), end.v.max(other_end.v));
+
+        Rect::new(start, point!(end.u - start.u, end.v - start.v))
+    }
+
+    /// Iterates over the coordinates of every pixel inside the rectangle, row after row.
+    ///
+    /// Unlike [`Footer::region_iter`] pixels outside of the texture aren't skipped.
+    pub fn texels(&self) -> impl Iterator<Item = Point2D<usize>> {
+        let (start, end) = (self.position, self.end());
+        (start.v..end.v).flat_map(move |v| (start.u..end.u).map(move |u| point!(u, v)))
+    }
+
+    /// Returns the range of columns covered by the rectangle, clipped to the texture.
+    fn columns(&self) -> (usize, usize) {
+        let start = self.position.u.min(Footer::WIDTH);
+        let end = self
+            .position
+            .u
+            .saturating_add(self.size.u)
+            .min(Footer::WIDTH);
+
+        (start, end)
+    }
+}
 
 /// Represents the bottom of a picoCAD file.
 ///
@@ -43,16 +299,23 @@ use std::str::FromStr;
 /// This means that the color at `u=1, v=0.25` is represented by the 9th character in the 3rd line.
 /// Since indexing by float numbers can be a bit annoying at times this struct has APIs for access
 /// via floats and whole numbers.
+/// Pixels are addressed with [`TexelCoord`] or [`Point2D<usize>`], uv-coordinates with [`UvCoord`] or
+/// [`Point2D<f64>`], see the [`coord`](crate::assets::coord) module.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Footer {
     data: Vec<Color>,
 }
 
 impl Footer {
+    /// Width of the texture in pixels.
+    pub const WIDTH: usize = 128;
+    /// Height of the texture in pixels.
+    pub const HEIGHT: usize = 120;
+
     /// Length the private `data` field should have, and the amount of pixels the texture has.
     ///
     /// `120 * 128 = 15360`.
-    const DATA_LENGHT: usize = 15360;
+    pub(crate) const DATA_LENGHT: usize = Footer::WIDTH * Footer::HEIGHT;
 
     /// Checks if every pixel in the texture has the same color.
     ///
@@ -86,11 +349,6 @@ impl Footer {
     ///
     /// `v` is out of bounds if `>= 120`.
     ///
-    /// <br/>
-    ///
-    /// Currently, no `get_mut` method as [`Color`] does not have any methods that take a mutable
-    /// reference of self.
-    ///
     /// # Example
     ///
     /// ```
@@ -106,12 +364,35 @@ impl Footer {
     ///     &Color::Lavender
     /// );
     /// ```
-    pub fn get(&self, coords: Point2D<usize>) -> Option<&Color> {
-        return if coords.u > 127 || coords.v > 119 {
-            None
-        } else {
-            Some(self.index(coords))
-        };
+    pub fn get(&self, coords: impl Into<TexelCoord>) -> Option<&Color> {
+        self.data_index(coords.into())
+            .map(|index| &self.data[index])
+    }
+
+    /// Get a mutable reference to the color at the given index in [`usize`].
+    /// This uses the actual pixel position in the texture.
+    /// `0, 0` is located in the top left corner.
+    ///
+    /// Returns [`None`] if coordinates are out of bounds, same as [`get`](Footer::get).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Point2D, Footer};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    ///
+    /// if let Some(color) = footer.get_mut(point!(3, 2)) {
+    ///     *color = Color::Lavender;
+    /// }
+    ///
+    /// assert_eq!(footer.get(point!(3, 2)), Some(&Color::Lavender));
+    /// assert_eq!(footer.get_mut(point!(128, 0)), None);
+    /// ```
+    pub fn get_mut(&mut self, coords: impl Into<TexelCoord>) -> Option<&mut Color> {
+        self.data_index(coords.into())
+            .map(|index| &mut self.data[index])
     }
 
     /// Sets the color at the given index in [`usize`].
@@ -144,12 +425,46 @@ impl Footer {
     ///     &Color::Lavender
     /// );
     /// ```
-    pub fn set(&mut self, coords: Point2D<usize>, value: Color) -> Result<(), PicoError> {
-        if coords.u > 127 || coords.v > 119 {
-            Err(PicoError::IndexUSIZE(coords, point!(128, 120)))
+    pub fn set(&mut self, coords: impl Into<TexelCoord>, value: Color) -> Result<(), PicoError> {
+        let coords = coords.into();
+        let color = self.get_mut(coords).ok_or(PicoError::IndexUSIZE(
+            coords.into(),
+            point!(Footer::WIDTH, Footer::HEIGHT),
+        ))?;
+
+        *color = value;
+        Ok(())
+    }
+
+    /// Returns a copy of the color at the given index in [`usize`].
+    /// This uses the actual pixel position in the texture.
+    /// `0, 0` is located in the top left corner.
+    ///
+    /// Returns [`Color::Invalid`] if coordinates are out of bounds, same as
+    /// [`read`](Footer::read).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Point2D, Footer};
+    /// use picocadrs::point;
+    ///
+    /// let footer = Footer::default();
+    ///
+    /// assert_eq!(footer.pixel(point!(127, 119)), Color::Black);
+    /// assert_eq!(footer.pixel(point!(127, 120)), Color::Invalid);
+    /// ```
+    pub fn pixel(&self, coords: impl Into<TexelCoord>) -> Color {
+        self.get(coords).copied().unwrap_or(Color::Invalid)
+    }
+
+    /// Position of the pixel at `coords` in the private `data` field, [`None`] if it is outside of
+    /// the texture.
+    fn data_index(&self, coords: TexelCoord) -> Option<usize> {
+        if coords.in_bounds() {
+            Some(coords.0 + coords.1 * Footer::WIDTH)
         } else {
-            self[coords] = value;
-            Ok(())
+            None
         }
     }
 
@@ -164,7 +479,7 @@ impl Footer {
     ///
     /// `v` is out of bounds if `-0.0625 > v` or `v >= 14.9375`.
     ///
-    /// This means each pixel "owns" a region of `0.125 x 0.125`.
+    /// This means each pixel "owns" a region of `0.125 x 0.125`, see [`UvCoord::to_texel`].
     ///
     /// # Example
     ///
@@ -180,15 +495,582 @@ impl Footer {
     /// assert_eq!(footer.read(point!(-0.75, 0.5)), Color::Invalid);
     /// assert_eq!(footer.read(point!(15.95, 0.5)), Color::Invalid);
     /// ```
-    pub fn read(&self, coords: Point2D<f64>) -> Color {
-        if -0.0625 > coords.u || coords.u >= 15.9375 || -0.0625 > coords.v || coords.v >= 14.9375 {
-            Color::Invalid
-        } else {
-            self[point!(
-                (coords.u * 8.0).round() as usize,
-                (coords.v * 8.0).round() as usize
-            )]
+    pub fn read(&self, coords: impl Into<UvCoord>) -> Color {
+        coords
+            .into()
+            .to_texel()
+            .map_or(Color::Invalid, |texel| self.pixel(texel))
+    }
+
+    /// Returns all pixels as a flat slice, row after row starting at `u=0, v=0`.
+    /// The pixel at `u, v` is located at index `u + v * 128`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.set(point!(2, 1), Color::Red).unwrap();
+    ///
+    /// assert_eq!(footer.as_flat_slice().len(), 128 * 120);
+    /// assert_eq!(footer.as_flat_slice()[2 + 128], Color::Red);
+    /// ```
+    pub fn as_flat_slice(&self) -> &[Color] {
+        &self.data
+    }
+
+    /// Returns all pixels as a flat mutable slice, see [`Footer::as_flat_slice`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.as_flat_mut_slice()[..128].fill(Color::Pink);
+    ///
+    /// assert_eq!(footer.pixel(point!(127, 0)), Color::Pink);
+    /// assert_eq!(footer.pixel(point!(0, 1)), Color::Black);
+    /// ```
+    pub fn as_flat_mut_slice(&mut self) -> &mut [Color] {
+        &mut self.data
+    }
+
+    /// Iterates over the 120 rows of the texture from top to bottom, each containing 128 pixels
+    /// from left to right.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.set(point!(5, 3), Color::Red).unwrap();
+    ///
+    /// assert_eq!(footer.rows().count(), 120);
+    /// assert_eq!(footer.rows().nth(3).unwrap()[5], Color::Red);
+    /// ```
+    pub fn rows(&self) -> impl Iterator<Item = &[Color]> {
+        self.data.chunks(Footer::WIDTH)
+    }
+
+    /// Iterates mutably over the rows of the texture, see [`Footer::rows`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// for row in footer.rows_mut().step_by(2) {
+    ///     row.fill(Color::White);
+    /// }
+    ///
+    /// assert_eq!(footer.pixel(point!(10, 2)), Color::White);
+    /// assert_eq!(footer.pixel(point!(10, 3)), Color::Black);
+    /// ```
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [Color]> {
+        self.data.chunks_mut(Footer::WIDTH)
+    }
+
+    /// Iterates over every pixel together with its coordinates, row after row.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D, TexelCoord};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.set(point!(100, 7), Color::Red).unwrap();
+    ///
+    /// let red: Vec<TexelCoord> = footer
+    ///     .pixels()
+    ///     .filter(|(_, color)| **color == Color::Red)
+    ///     .map(|(coords, _)| coords)
+    ///     .collect();
+    ///
+    /// assert_eq!(red, vec![TexelCoord(100, 7)]);
+    /// ```
+    pub fn pixels(&self) -> impl Iterator<Item = (TexelCoord, &Color)> {
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(index, color)| (texel_at(index), color))
+    }
+
+    /// Iterates mutably over every pixel together with its coordinates, see [`Footer::pixels`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// for (coords, color) in footer.pixels_mut() {
+    ///     if (coords.0 + coords.1) % 2 == 0 {
+    ///         *color = Color::White;
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(footer.pixel(point!(1, 1)), Color::White);
+    /// assert_eq!(footer.pixel(point!(1, 2)), Color::Black);
+    /// ```
+    pub fn pixels_mut(&mut self) -> impl Iterator<Item = (TexelCoord, &mut Color)> {
+        self.data
+            .iter_mut()
+            .enumerate()
+            .map(|(index, color)| (texel_at(index), color))
+    }
+
+    /// Iterates over the pixels inside of `rect` together with their coordinates, row after row.
+    ///
+    /// Parts of the rectangle outside of the texture are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D, Rect, TexelCoord};
+    /// use picocadrs::point;
+    ///
+    /// let footer = Footer::default();
+    /// let rect = Rect::new(point!(126, 118), point!(4, 4));
+    ///
+    /// assert_eq!(footer.region_iter(rect).count(), 4);
+    /// assert_eq!(
+    ///     footer.region_iter(rect).next(),
+    ///     Some((TexelCoord(126, 118), &Color::Black))
+    /// );
+    /// ```
+    pub fn region_iter(&self, rect: Rect) -> impl Iterator<Item = (TexelCoord, &Color)> {
+        let (start, end) = rect.columns();
+
+        self.rows()
+            .enumerate()
+            .skip(rect.position.v)
+            .take(rect.size.v)
+            .flat_map(move |(v, row)| {
+                row[start..end]
+                    .iter()
+                    .enumerate()
+                    .map(move |(du, color)| (TexelCoord(start + du, v), color))
+            })
+    }
+
+    /// Iterates mutably over the pixels inside of `rect` together with their coordinates, see
+    /// [`Footer::region_iter`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// for (_, color) in footer.region_iter_mut(Rect::new(point!(8, 8), point!(2, 2))) {
+    ///     *color = Color::Orange;
+    /// }
+    ///
+    /// assert_eq!(footer.pixel(point!(9, 9)), Color::Orange);
+    /// assert_eq!(footer.pixel(point!(10, 9)), Color::Black);
+    /// ```
+    pub fn region_iter_mut(
+        &mut self,
+        rect: Rect,
+    ) -> impl Iterator<Item = (TexelCoord, &mut Color)> {
+        let (start, end) = rect.columns();
+
+        self.rows_mut()
+            .enumerate()
+            .skip(rect.position.v)
+            .take(rect.size.v)
+            .flat_map(move |(v, row)| {
+                row[start..end]
+                    .iter_mut()
+                    .enumerate()
+                    .map(move |(du, color)| (TexelCoord(start + du, v), color))
+            })
+    }
+
+    /// Paints the border of every region in `color` and returns the amount of pixels that changed.
+    ///
+    /// A region is a group of connected pixels with the same color.
+    /// Pixels are on the border of their region if a pixel above, below, left or right of them has
+    /// a different color, so both regions next to each other get an outline.
+    /// The edges of the texture aren't borders and pixels already in `color` are left as they are.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.replace_color(Color::Black, Color::Red, Some(Rect::new(point!(0, 0), point!(3, 3))));
+    ///
+    /// assert_eq!(footer.outline_regions(Color::White), 11);
+    /// assert_eq!(footer.pixel(point!(1, 1)), Color::Red);
+    /// assert_eq!(footer.pixel(point!(2, 1)), Color::White);
+    /// assert_eq!(footer.pixel(point!(3, 1)), Color::White);
+    /// ```
+    pub fn outline_regions(&mut self, color: Color) -> usize {
+        let mut border: Vec<Point2D<usize>> = vec![];
+
+        for v in 0..Footer::HEIGHT {
+            for u in 0..Footer::WIDTH {
+                let own = self.pixel(point!(u, v));
+                if own == color {
+                    continue;
+                }
+
+                let neighbours = [
+                    (u > 0).then(|| point!(u - 1, v)),
+                    (u < 127).then(|| point!(u + 1, v)),
+                    (v > 0).then(|| point!(u, v - 1)),
+                    (v < 119).then(|| point!(u, v + 1)),
+                ];
+
+                if neighbours
+                    .iter()
+                    .flatten()
+                    .any(|neighbour| self.pixel(*neighbour) != own)
+                {
+                    border.push(point!(u, v));
+                }
+            }
+        }
+
+        for coords in border.iter() {
+            if let Some(pixel) = self.get_mut(*coords) {
+                *pixel = color;
+            }
+        }
+
+        border.len()
+    }
+
+    /// Moves every pixel `du` pixels to the right and `dv` pixels down, negative values move
+    /// them left and up.
+    ///
+    /// If `wrap` is true, pixels moved out of one side of the texture come back in on the other
+    /// side.
+    /// Otherwise they are lost and the space they leave behind is filled with black, like an empty
+    /// texture.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.set(point!(127, 0), Color::Red).unwrap();
+    ///
+    /// footer.shift(1, 2, true);
+    /// assert_eq!(footer.pixel(point!(0, 2)), Color::Red);
+    ///
+    /// footer.shift(-1, 0, false);
+    /// assert!(footer.is_solid());
+    /// ```
+    pub fn shift(&mut self, du: isize, dv: isize, wrap: bool) {
+        let mut data = vec![Color::Black; Footer::DATA_LENGHT];
+
+        for v in 0..Footer::HEIGHT {
+            for u in 0..Footer::WIDTH {
+                let mut target_u = u as isize + du;
+                let mut target_v = v as isize + dv;
+
+                if wrap {
+                    target_u = target_u.rem_euclid(Footer::WIDTH as isize);
+                    target_v = target_v.rem_euclid(Footer::HEIGHT as isize);
+                } else if !(0..Footer::WIDTH as isize).contains(&target_u)
+                    || !(0..Footer::HEIGHT as isize).contains(&target_v)
+                {
+                    continue;
+                }
+
+                data[target_u as usize + target_v as usize * Footer::WIDTH] =
+                    self.pixel(point!(u, v));
+            }
+        }
+
+        self.data = data;
+    }
+
+    /// Paints all 16 colors next to each other, each as 2x2 pixels in the order of their index,
+    /// starting with the top left pixel at `position`.
+    ///
+    /// Pixels outside of the texture are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.paint_palette_strip(point!(0, 10));
+    ///
+    /// assert_eq!(footer.pixel(point!(2, 11)), Color::DarkBlue);
+    /// assert_eq!(footer.pixel(point!(31, 10)), Color::LightPeach);
+    /// ```
+    pub fn paint_palette_strip(&mut self, position: Point2D<usize>) {
+        for index in 0..16 {
+            for (du, dv) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let _ = self.set(
+                    point!(position.u + 2 * index + du, position.v + dv),
+                    Color::from(index as i32),
+                );
+            }
+        }
+    }
+
+    /// Replaces every pixel in the color `from` with `to` and returns the amount of pixels that
+    /// changed.
+    ///
+    /// If `rect` is given only pixels inside of it are replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    ///
+    /// let rect = Rect::new(point!(120, 112), point!(16, 16));
+    /// assert_eq!(footer.replace_color(Color::Black, Color::Pink, Some(rect)), 64);
+    /// assert_eq!(footer.pixel(point!(127, 119)), Color::Pink);
+    ///
+    /// assert_eq!(footer.replace_color(Color::Pink, Color::Red, None), 64);
+    /// ```
+    pub fn replace_color(&mut self, from: Color, to: Color, rect: Option<Rect>) -> usize {
+        let mut changed = 0;
+
+        for (coords, pixel) in self.pixels_mut() {
+            if *pixel != from || rect.is_some_and(|rect| !rect.contains(coords.into())) {
+                continue;
+            }
+
+            *pixel = to;
+            if from != to {
+                changed += 1;
+            }
+        }
+
+        changed
+    }
+
+    /// Reads the content of the `__gfx__` section of a PICO-8 cart into a texture, using 120 rows
+    /// of the sprite sheet starting at `start_row`.
+    ///
+    /// Fails with [`PicoError::Pico8`] if a pixel isn't a hex digit.
+    /// More information can be found in the [`pico8`](crate::pico8) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let footer = Footer::from_p8_gfx("0000\n0e00\n", 0).unwrap();
+    ///
+    /// assert_eq!(footer.pixel(point!(1, 1)), Color::Pink);
+    /// assert!(Footer::from_p8_gfx("xyz", 0).is_err());
+    /// ```
+    pub fn from_p8_gfx(gfx: &str, start_row: usize) -> Result<Footer, PicoError> {
+        pico8::footer_from_gfx(gfx, start_row)
+    }
+
+    /// Reads the sprite sheet of a `.p8` cart into a texture, using 120 rows of it starting at
+    /// `start_row`.
+    ///
+    /// Fails with [`PicoError::Pico8`] if the cart has no `__gfx__` section or a pixel isn't a hex
+    /// digit.
+    /// More information can be found in the [`pico8`](crate::pico8) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let cart = "__lua__\n__gfx__\n0000\n0e00\n__map__\n";
+    /// let footer = Footer::from_p8_cart(cart, 1).unwrap();
+    ///
+    /// assert_eq!(footer.pixel(point!(1, 0)), Color::Pink);
+    /// ```
+    pub fn from_p8_cart(cart: &str, start_row: usize) -> Result<Footer, PicoError> {
+        pico8::footer_from_cart(cart, start_row)
+    }
+
+    /// Reads the sprite sheet of the `.p8` cart at `path` into a texture, using 120 rows of it
+    /// starting at `start_row`.
+    ///
+    /// Fails like [`Footer::from_p8_cart`] or with [`PicoError::IO`] if the file can't be read.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use picocadrs::assets::Footer;
+    ///
+    /// let footer = Footer::load_p8_cart("game.p8", 0).unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn load_p8_cart<P: AsRef<Path>>(path: P, start_row: usize) -> Result<Footer, PicoError> {
+        pico8::load_cart_footer(path, start_row)
+    }
+
+    /// Writes the texture as the `__gfx__` section of a PICO-8 cart, header included.
+    ///
+    /// The texture fills the top 120 rows of the sprite sheet, the 8 rows below it are black.
+    /// To keep the rest of an existing sprite sheet, use [`Footer::patch_p8_cart`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Footer;
+    ///
+    /// let gfx = Footer::default().to_p8_gfx();
+    ///
+    /// assert!(gfx.starts_with("__gfx__\n0000"));
+    /// assert_eq!(gfx.lines().count(), 1 + 128);
+    /// ```
+    pub fn to_p8_gfx(&self) -> String {
+        pico8::footer_to_gfx(self)
+    }
+
+    /// Copies the texture into the sprite sheet of the `.p8` cart at `path`, replacing 120 rows
+    /// starting at `start_row`, and leaves everything else in the cart as it is.
+    ///
+    /// Fails with [`PicoError::IO`] if the file can't be read or written.
+    /// More information can be found in [`pico8::patch_cart`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use picocadrs::assets::Model;
+    /// use std::ffi::OsString;
+    ///
+    /// let model = Model::load(OsString::from("ship")).unwrap();
+    /// model.footer.patch_p8_cart("game.p8", 0).unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn patch_p8_cart<P: AsRef<Path>>(
+        &self,
+        path: P,
+        start_row: usize,
+    ) -> Result<(), PicoError> {
+        pico8::patch_cart_file(path, self, start_row)
+    }
+
+    /// Packs the texture into bytes, two pixels per byte, line by line.
+    ///
+    /// Like in the memory of PICO-8 the left pixel of each pair is stored in the lower 4 bits, so
+    /// the result has `128 * 120 / 2 = 7680` bytes.
+    /// [`Invalid`](Color::Invalid) pixels are stored as black.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.set(point!(0, 0), Color::Red).unwrap();
+    /// footer.set(point!(1, 0), Color::Blue).unwrap();
+    ///
+    /// let bytes = footer.to_bytes();
+    ///
+    /// assert_eq!(bytes.len(), 7680);
+    /// assert_eq!(bytes[0], 0xc8);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.data
+            .chunks(2)
+            .map(|pair| (pair[0].as_i32() | (pair[1].as_i32() << 4)) as u8)
+            .collect()
+    }
+
+    /// Unpacks a texture from bytes created by [`Footer::to_bytes`].
+    ///
+    /// Fails with [`PicoError::ImageSize`] if there aren't exactly 7680 bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut bytes = vec![0; 7680];
+    /// bytes[64] = 0xe0;
+    ///
+    /// let footer = Footer::from_bytes(&bytes).unwrap();
+    ///
+    /// assert_eq!(footer.pixel(point!(1, 1)), Color::Pink);
+    /// assert!(Footer::from_bytes(&bytes[1..]).is_err());
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Footer, PicoError> {
+        if bytes.len() != Footer::DATA_LENGHT / 2 {
+            return Err(PicoError::ImageSize(bytes.len(), Footer::DATA_LENGHT / 2));
         }
+
+        let data = bytes
+            .iter()
+            .flat_map(|byte| [byte & 0x0f, byte >> 4])
+            .map(|nibble| Color::from(nibble as i32))
+            .collect();
+
+        Ok(Footer { data })
+    }
+
+    /// Encodes the [bytes](Footer::to_bytes) of the texture as base64, using the standard
+    /// alphabet.
+    ///
+    /// The result has 10240 characters, about two thirds of the roughly 15480 characters the
+    /// texture takes up in picoCAD files, and fits on one line, which makes it better suited for
+    /// sending textures around or storing them in JSON.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Footer;
+    ///
+    /// let base64 = Footer::default().to_base64();
+    ///
+    /// assert_eq!(base64.len(), 10240);
+    /// assert!(base64.starts_with("AAAA"));
+    /// ```
+    pub fn to_base64(&self) -> String {
+        encode_base64(&self.to_bytes())
+    }
+
+    /// Decodes a texture from base64 created by [`Footer::to_base64`].
+    ///
+    /// Whitespace and padding at the end are ignored.
+    /// Fails with [`PicoError::Base64`] if the text isn't valid base64 and with
+    /// [`PicoError::ImageSize`] if it doesn't hold exactly 7680 bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.set(point!(127, 119), Color::Yellow).unwrap();
+    ///
+    /// assert_eq!(Footer::from_base64(&footer.to_base64()).unwrap(), footer);
+    /// assert!(Footer::from_base64("not base64!").is_err());
+    /// ```
+    pub fn from_base64(s: &str) -> Result<Footer, PicoError> {
+        Footer::from_bytes(&decode_base64(s)?)
     }
 }
 
@@ -212,11 +1094,11 @@ impl Default for Footer {
 }
 
 impl Display for Footer {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let mut chars: String = self.data.iter().map(|c| c.as_char()).collect();
 
-        for line in (1..=120).rev() {
-            chars.insert(line * 128, '\n');
+        for line in (1..=Footer::HEIGHT).rev() {
+            chars.insert(line * Footer::WIDTH, '\n');
         }
 
         write!(f, "{}", chars)
@@ -243,6 +1125,8 @@ impl FromStr for Footer {
     }
 }
 
+/// Only available with the `index` feature, use [`Footer::get`] to not panic on bad coordinates.
+#[cfg(feature = "index")]
 impl Index<Point2D<usize>> for Footer {
     type Output = Color;
 
@@ -262,16 +1146,13 @@ impl Index<Point2D<usize>> for Footer {
     /// // assert_eq!(footer[point!(128, 119)], Color::Black);
     /// ```
     fn index(&self, index: Point2D<usize>) -> &Self::Output {
-        if index.u > 127 || index.v > 119 {
-            panic!("index out of range");
-        }
-
-        let data_index = index.u + index.v * 128;
-
-        self.data.get(data_index).unwrap()
+        self.get(index).expect("index out of range")
     }
 }
 
+/// Only available with the `index` feature, use [`Footer::get_mut`] to not panic on bad
+/// coordinates.
+#[cfg(feature = "index")]
 impl IndexMut<Point2D<usize>> for Footer {
     /// Panics if `u >= 128` or `v >= 120`.
     ///
@@ -281,22 +1162,82 @@ impl IndexMut<Point2D<usize>> for Footer {
     /// use picocadrs::assets::{Footer, Color, Point2D};
     /// use picocadrs::point;
     ///
-    /// let footer = Footer::default();
+    /// let mut footer = Footer::default();
     ///
-    /// assert_eq!(footer[point!(0, 0)], Color::Black);
-    /// assert_eq!(footer[point!(127, 119)], Color::Black);
-    /// // assert_eq!(footer[point!(127, 120)], Color::Black); These panic
-    /// // assert_eq!(footer[point!(128, 119)], Color::Black);
+    /// footer[point!(127, 119)] = Color::Red;
+    /// assert_eq!(footer[point!(127, 119)], Color::Red);
+    /// // footer[point!(128, 119)] = Color::Red; This panics
     /// ```
     fn index_mut(&mut self, index: Point2D<usize>) -> &mut Self::Output {
-        if index.u > 127 || index.v > 119 {
-            panic!("index out of range");
+        self.get_mut(index).expect("index out of range")
+    }
+}
+
+/// Returns the coordinates of the pixel at the given index of the flat pixel data.
+fn texel_at(index: usize) -> TexelCoord {
+    TexelCoord(index % Footer::WIDTH, index / Footer::WIDTH)
+}
+
+/// Characters of the standard base64 alphabet, in the order of their values.
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes as base64, padded with `=`.
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | ((*byte as u32) << (16 - 8 * i))
+        });
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[((group >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
         }
+    }
 
-        let data_index = index.u + index.v * 128;
+    encoded
+}
+
+/// Decodes base64 into bytes, ignoring whitespace and padding at the end.
+fn decode_base64(s: &str) -> Result<Vec<u8>, PicoError> {
+    let digits: Vec<u8> = s.bytes().filter(|c| !c.is_ascii_whitespace()).collect();
+    let trimmed = digits
+        .iter()
+        .rposition(|c| *c != b'=')
+        .map_or(0, |last| last + 1);
+    let (digits, padding) = digits.split_at(trimmed);
+
+    if padding.len() > 2 || digits.len() % 4 == 1 {
+        return Err(PicoError::Base64(format!(
+            "{} digits with {} padding",
+            digits.len(),
+            padding.len()
+        )));
+    }
 
-        self.data.get_mut(data_index).unwrap()
+    let mut bytes = Vec::with_capacity(digits.len() * 3 / 4);
+
+    for chunk in digits.chunks(4) {
+        let mut group = 0u32;
+        for (i, digit) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET
+                .iter()
+                .position(|c| c == digit)
+                .ok_or_else(|| PicoError::Base64(format!("invalid digit {:?}", *digit as char)))?;
+            group |= (value as u32) << (18 - 6 * i);
+        }
+
+        for i in 0..chunk.len() - 1 {
+            bytes.push((group >> (16 - 8 * i)) as u8);
+        }
     }
+
+    Ok(bytes)
 }
 
 #[cfg(test)]
@@ -304,6 +1245,72 @@ pub mod tests {
     use super::*;
     use crate::point;
 
+    #[test]
+    fn footer_bytes() {
+        let footer = TEST_FOOTER.parse::<Footer>().unwrap();
+        let bytes = footer.to_bytes();
+
+        assert_eq!(bytes.len(), 7680);
+        assert_eq!(Footer::from_bytes(&bytes).unwrap(), footer);
+        assert!(matches!(
+            Footer::from_bytes(&[0; 15360]),
+            Err(PicoError::ImageSize(15360, 7680))
+        ));
+    }
+
+    #[test]
+    fn footer_base64() {
+        assert_eq!(encode_base64(b"pico"), "cGljbw==");
+        assert_eq!(encode_base64(b"picoCAD"), "cGljb0NBRA==");
+        assert_eq!(encode_base64(b"cad"), "Y2Fk");
+        assert_eq!(decode_base64("cGljbw==").unwrap(), b"pico");
+        assert_eq!(decode_base64("cGlj\nbw").unwrap(), b"pico");
+        assert!(decode_base64("cGljb").is_err());
+        assert!(decode_base64("cG=ljbw").is_err());
+
+        let footer = TEST_FOOTER.parse::<Footer>().unwrap();
+        let encoded = footer.to_base64();
+        assert_eq!(Footer::from_base64(&encoded).unwrap(), footer);
+        assert!(matches!(
+            Footer::from_base64("AAAA"),
+            Err(PicoError::ImageSize(3, 7680))
+        ));
+    }
+
+    #[test]
+    fn footer_rect_math() {
+        let rect = Rect::new(point!(4, 4), point!(4, 2));
+
+        assert_eq!(rect.area(), 8);
+        assert_eq!(rect.end(), point!(8, 6));
+        assert_eq!(rect.texels().count(), 8);
+        assert_eq!(rect.texels().last(), Some(point!(7, 5)));
+
+        assert!(Rect::TEXTURE.contains_rect(&rect));
+        assert!(!rect.contains_rect(&Rect::TEXTURE));
+        assert!(rect.contains_rect(&Rect::new(point!(100, 100), point!(0, 0))));
+
+        // touching rectangles don't share pixels.
+        assert!(!rect.intersects(&Rect::new(point!(8, 4), point!(2, 2))));
+        assert!(rect.intersects(&Rect::new(point!(7, 5), point!(2, 2))));
+
+        let empty = Rect::new(point!(50, 50), point!(0, 3));
+        assert!(empty.is_empty());
+        assert_eq!(rect.union(&empty), rect);
+        assert_eq!(empty.union(&rect), rect);
+
+        assert_eq!(Rect::from_uv(rect.to_uv().0, rect.to_uv().1), rect);
+        assert_eq!(
+            Rect::from_uv(point!(-1.0, -1.0), point!(0.25, 0.25)),
+            Rect::new(point!(0, 0), point!(2, 2))
+        );
+        assert!(Rect::from_uv(point!(2.0, 2.0), point!(1.0, 1.0)).is_empty());
+
+        // huge uv rectangles saturate instead of overflowing.
+        let huge = Rect::from_uv(point!(0.0, 0.0), point!(f64::MAX, f64::MAX));
+        assert_eq!(huge.area(), usize::MAX);
+    }
+
     #[test]
     fn footer_parse() {
         let _footer = TEST_FOOTER.parse::<Footer>().unwrap();
@@ -336,14 +1343,50 @@ pub mod tests {
     }
 
     #[test]
-    fn footer_index() {
+    fn footer_pixel() {
         let footer = TEST_FOOTER.parse::<Footer>().unwrap();
 
-        assert_eq!(footer[point!(0, 0)], Color::Black);
-        assert_eq!(footer[point!(13, 4)], Color::from('e'));
-        assert_eq!(footer[point!(127, 119)], Color::Black);
-        // assert_eq!(footer[point!(127, 120)], Color::Black); These panic
-        // assert_eq!(footer[point!(128, 119)], Color::Black);
+        assert_eq!(footer.pixel(point!(0, 0)), Color::Black);
+        assert_eq!(footer.pixel(point!(13, 4)), Color::from('e'));
+        assert_eq!(footer.pixel(point!(127, 119)), Color::Black);
+        assert_eq!(footer.pixel(point!(127, 120)), Color::Invalid);
+        assert_eq!(footer.pixel(point!(128, 119)), Color::Invalid);
+        assert_eq!(footer.pixel(point!(usize::MAX, usize::MAX)), Color::Invalid);
+    }
+
+    #[test]
+    fn footer_get_mut() {
+        let mut footer = TEST_FOOTER.parse::<Footer>().unwrap();
+
+        *footer.get_mut(point!(127, 119)).unwrap() = Color::Red;
+
+        assert_eq!(footer.pixel(point!(127, 119)), Color::Red);
+        assert!(footer.get_mut(point!(128, 0)).is_none());
+        assert!(footer.get_mut(point!(0, 120)).is_none());
+    }
+
+    #[test]
+    fn footer_iterators() {
+        let mut footer = TEST_FOOTER.parse::<Footer>().unwrap();
+
+        for (coords, color) in footer.pixels() {
+            assert_eq!(footer.pixel(coords), *color);
+        }
+        for (v, row) in footer.rows().enumerate() {
+            assert_eq!(row.len(), 128);
+            assert_eq!(row[13], footer.pixel(point!(13, v)));
+        }
+
+        let rect = Rect::new(point!(10, 2), point!(5, 3));
+        let region: Vec<TexelCoord> = footer.region_iter(rect).map(|(coords, _)| coords).collect();
+        assert_eq!(region.len(), 15);
+        assert!(region.iter().all(|coords| rect.contains((*coords).into())));
+        assert_eq!(region[5], TexelCoord(10, 3));
+
+        let outside = Rect::new(point!(200, 0), point!(usize::MAX, usize::MAX));
+        assert_eq!(footer.region_iter(outside).count(), 0);
+        let huge = Rect::new(point!(0, 0), point!(usize::MAX, usize::MAX));
+        assert_eq!(footer.region_iter_mut(huge).count(), 128 * 120);
     }
 
     #[test]
@@ -372,13 +1415,70 @@ pub mod tests {
 
     #[test]
     fn footer_read() {
-        let mut footer = TEST_FOOTER.parse::<Footer>().unwrap();
+        let footer = TEST_FOOTER.parse::<Footer>().unwrap();
 
         assert_eq!(footer.read(point!(1.25, 0.75)), Color::from('8'));
         assert_eq!(footer.read(point!(-0.75, 0.5)), Color::Invalid);
         assert_eq!(footer.read(point!(15.95, 0.5)), Color::Invalid);
     }
 
+    #[test]
+    fn footer_outline_regions() {
+        let mut footer = Footer::default();
+        footer.replace_color(
+            Color::Black,
+            Color::Blue,
+            Some(Rect::new(point!(10, 10), point!(5, 4))),
+        );
+
+        // 14 pixels on the inside of the blue region, 18 around it.
+        assert_eq!(footer.outline_regions(Color::Yellow), 32);
+        assert_eq!(footer.pixel(point!(12, 11)), Color::Blue);
+        assert_eq!(footer.pixel(point!(10, 10)), Color::Yellow);
+        assert_eq!(footer.pixel(point!(9, 10)), Color::Yellow);
+        assert_eq!(footer.pixel(point!(9, 9)), Color::Black);
+
+        // the outline separates every region now, the pixels next to it are outlined again.
+        assert_eq!(footer.outline_regions(Color::Yellow), 6 + 22);
+    }
+
+    #[test]
+    fn footer_shift() {
+        let original = TEST_FOOTER.parse::<Footer>().unwrap();
+
+        let mut footer = original.clone();
+        footer.shift(-3, 125, true);
+        assert_eq!(footer.pixel(point!(125, 5)), original.pixel(point!(0, 0)));
+        footer.shift(3, -125, true);
+        assert_eq!(footer, original);
+
+        let mut footer = original.clone();
+        footer.shift(5, 1, false);
+        assert_eq!(footer.pixel(point!(4, 0)), Color::Black);
+        assert_eq!(footer.pixel(point!(13, 1)), original.pixel(point!(8, 0)));
+
+        footer.shift(0, 200, false);
+        assert!(footer.is_solid());
+    }
+
+    #[test]
+    fn footer_replace_color() {
+        let mut footer = TEST_FOOTER.parse::<Footer>().unwrap();
+        let greens = footer.data.iter().filter(|c| **c == Color::Green).count();
+
+        assert_eq!(footer.replace_color(Color::Green, Color::Green, None), 0);
+        assert_eq!(
+            footer.replace_color(
+                Color::Green,
+                Color::Red,
+                Some(Rect::new(point!(0, 0), point!(0, 0)))
+            ),
+            0
+        );
+        assert_eq!(footer.replace_color(Color::Green, Color::Red, None), greens);
+        assert!(!footer.data.contains(&Color::Green));
+    }
+
     const TEST_FOOTER: &str = r#"00000000eeee8888eeee8888aaaa9999aaaa9999bbbb3333bbbb3333ccccddddccccddddffffeeeeffffeeee7777666677776666555566665555666600000000
 00000000eeee8888eeee8888aaaa9999aaaa9999bbbb3333bbbb3333ccccddddccccddddffffeeeeffffeeee7777666677776666555566665555666600000000
 00000000eeee8888eeee8888aaaa9999aaaa9999bbbb3333bbbb3333ccccddddccccddddffffeeeeffffeeee7777666677776666555566665555666600000000
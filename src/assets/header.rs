@@ -8,22 +8,30 @@
 //!
 //! In order the different parts have the following function:
 //! - _identifier_: In order for a text-file to be recognized by picoCAD as a project the header has to start
-//! with `"picocad"`.
+//!   with `"picocad"`.
 //! - _project name_: While normally this is the same as the file-name it can actually differ and is what picoCAD
-//! displays at the bottom of the window.
-//! In this example `"my_project"`.
+//!   displays at the bottom of the window.
+//!   In this example `"my_project"`.
 //! - _zoom_: Level of zoom at last save.
-//! Changing this doesn't actually do anything since picoCAD will just use the zoom you currently
-//! have when loading a project.
-//! In this example `16`.
+//!   Changing this doesn't actually do anything since picoCAD will just use the zoom you currently
+//!   have when loading a project.
+//!   In this example `16`.
 //! - _background color_: The color of the background in a project.
-//! In this example `1` which represents dark-blue.
+//!   In this example `1` which represents dark-blue.
 //! - _alpha color_: The color that will be transparent when uv-mapped onto a face.
-//! In this example `0` which represents black.
+//!   In this example `0` which represents black.
+//!
+//! Some community tools append their own fields to the header, like
+//! `picocad;my_project;16;1;0;some_tool=1`.
+//! picoCAD ignores them, so they are kept as they are in [`Header::extensions`].
 
 use super::Color;
 use crate::error::PicoError;
-use std::{fmt::Display, str::FromStr};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Display, str::FromStr};
 
 /// Represents the header of a picoCAD project.
 /// A header consists of 5 parts seperated by semicolons and looks something like this:
@@ -32,18 +40,21 @@ use std::{fmt::Display, str::FromStr};
 ///
 /// In order the different parts have the following function:
 /// - _identifier_: In order for a text-file to be recognized by picoCAD as a project the header has to start
-/// with `"picocad"`.
+///   with `"picocad"`.
 /// - _project name_: While normally this is the same as the file-name it can actually differ and is what picoCAD
-/// displays at the bottom of the window.
-/// In this example `"my_project"`.
+///   displays at the bottom of the window.
+///   In this example `"my_project"`.
 /// - _zoom_: Level of zoom at last save.
-/// Changing this doesn't actually do anything since picoCAD will just use the zoom you currently
-/// have when loading a project.
-/// In this example `16`.
+///   Changing this doesn't actually do anything since picoCAD will just use the zoom you currently
+///   have when loading a project.
+///   In this example `16`.
 /// - _background color_: The color of the background in a project.
-/// In this example `1` which represents dark-blue.
+///   In this example `1` which represents dark-blue.
 /// - _alpha color_: The color that will be transparent when uv-mapped onto a face.
-/// In this example `0` which represents black.
+///   In this example `0` which represents black.
+///
+/// Any fields after those are kept in [`extensions`](Header::extensions) and written back
+/// unchanged.
 ///
 /// # Examples
 ///
@@ -61,6 +72,16 @@ use std::{fmt::Display, str::FromStr};
 ///
 /// assert_eq!("picocad;unnamed;16;1;4", header.to_string())
 /// ```
+///
+/// Extra fields survive a round trip.
+/// ```
+/// use picocadrs::assets::Header;
+///
+/// let header = "picocad;unnamed;16;1;4;author=me;v2".parse::<Header>().unwrap();
+///
+/// assert_eq!(header.extensions, vec!["author=me", "v2"]);
+/// assert_eq!("picocad;unnamed;16;1;4;author=me;v2", header.to_string());
+/// ```
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Header {
     identifier: String,
@@ -68,9 +89,32 @@ pub struct Header {
     pub name: String,
     pub background: Color,
     pub alpha: Color,
+    /// Fields after the alpha color that picoCAD doesn't know about, in order.
+    pub extensions: Vec<String>,
 }
 
 impl Header {
+    /// Creates a header with the given project name, background color and alpha color.
+    /// The zoom level is the same as in [`Header::default`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Header};
+    ///
+    /// let header = Header::with("my_project".to_string(), Color::Black, Color::Pink);
+    ///
+    /// assert_eq!("picocad;my_project;16;0;14", header.to_string());
+    /// ```
+    pub fn with(name: String, background: Color, alpha: Color) -> Header {
+        Header {
+            name,
+            background,
+            alpha,
+            ..Header::default()
+        }
+    }
+
     /// Returns the identifier of the header as a [`String`].
     /// In normal use this will always be `"picocad"`.
     ///
@@ -87,6 +131,28 @@ impl Header {
     pub fn identifier(&self) -> String {
         self.identifier.clone()
     }
+
+    /// Parses a header like [`from_str`](Header::from_str), but fails with
+    /// [`PicoError::HeaderLength`] if there are any fields after the alpha color instead of
+    /// keeping them in [`extensions`](Header::extensions).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use picocadrs::assets::Header;
+    ///
+    /// assert!(Header::parse_strict("picocad;unnamed;16;1;4").is_ok());
+    /// assert!(Header::parse_strict("picocad;unnamed;16;1;4;author=me").is_err());
+    /// ```
+    pub fn parse_strict(s: &str) -> Result<Header, PicoError> {
+        let header: Header = s.parse()?;
+
+        if !header.extensions.is_empty() {
+            return Err(PicoError::HeaderLength(5 + header.extensions.len()));
+        }
+
+        Ok(header)
+    }
 }
 
 impl Default for Header {
@@ -108,6 +174,7 @@ impl Default for Header {
             name: "unnamed".to_string(),
             background: Color::DarkBlue,
             alpha: Color::Black,
+            extensions: Vec::new(),
         }
     }
 }
@@ -116,11 +183,11 @@ impl FromStr for Header {
     type Err = PicoError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let fields: Vec<&str> = s.trim().splitn(5, ';').collect();
+        let fields: Vec<&str> = s.trim().split(';').collect();
 
-        if fields.len() != 5 {
+        if fields.len() < 5 {
             return Err(PicoError::HeaderLength(fields.len()));
-        } else if *fields.first().unwrap() != "picocad" {
+        } else if fields.first() != Some(&"picocad") {
             return Err(PicoError::Identifier);
         }
 
@@ -151,6 +218,7 @@ impl FromStr for Header {
             zoom,
             background,
             alpha,
+            extensions: fields[5..].iter().map(|field| field.to_string()).collect(),
         };
 
         Ok(header)
@@ -158,7 +226,7 @@ impl FromStr for Header {
 }
 
 impl Display for Header {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
             f,
             "picocad;{};{};{};{}",
@@ -166,7 +234,13 @@ impl Display for Header {
             self.zoom,
             self.background.as_i32(),
             self.alpha.as_i32()
-        )
+        )?;
+
+        for extension in self.extensions.iter() {
+            write!(f, ";{}", extension)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -191,4 +265,24 @@ pub mod tests {
 
         assert_eq!("picocad;unnamed;16;1;4", header.to_string())
     }
+
+    #[test]
+    fn header_extensions() {
+        let header = "picocad;unnamed;16;1;4;tool=x;;last"
+            .parse::<Header>()
+            .unwrap();
+
+        assert_eq!(header.alpha, Color::Brown);
+        assert_eq!(header.extensions, vec!["tool=x", "", "last"]);
+        assert_eq!("picocad;unnamed;16;1;4;tool=x;;last", header.to_string());
+
+        assert!(matches!(
+            Header::parse_strict("picocad;unnamed;16;1;4;tool=x"),
+            Err(PicoError::HeaderLength(6))
+        ));
+        assert!(matches!(
+            "picocad;unnamed;16;1".parse::<Header>(),
+            Err(PicoError::HeaderLength(4))
+        ));
+    }
 }
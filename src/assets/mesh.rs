@@ -2,30 +2,73 @@
 //!
 //! A mesh has 5 fields.
 //! - _name:_ Name of the mesh.
-//! To reduce file-space this can be shortened to a singular character which will not affect the
-//! render result.
+//!   To reduce file-space this can be shortened to a singular character which will not affect the
+//!   render result.
 //! - _pos (position):_ Anchor point of the mesh given as a point in 3-dimensional space.
-//! All Vertex positions are relative to this position.
+//!   All Vertex positions are relative to this position.
 //! - _rot (rotation):_ Shadow rotation of the mesh.
-//! More info in [`Rotation`].
+//!   More info in [`Rotation`].
 //! - _v (vertices):_ List of all the vertices relative to _position_ given as a point in
-//! 3-dimensional space.
+//!   3-dimensional space.
 //! - _f (faces):_ List of all faces the mesh has.
-//! More info on faces [`here`](crate::assets::face).
+//!   More info on faces [`here`](crate::assets::face).
+//!
+//! Any other keys of the table are kept in [`Mesh::extras`] and written back unchanged.
 //!
 //! This module also provides a wrapper struct for [`rotation`](Rotation) which implements some useful methods
 //! that only apply to rotation in picoCAD.
 
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+#[cfg(feature = "std")]
+use crate::{
+    align::{self, Alignment},
+    analysis::topology::{self, Edge},
+    assets::Footer,
+    geometry::{
+        components, decimate, extrude,
+        heightmap::{self, HeightSource},
+        lathe,
+        proportional::{self, Falloff},
+        split::{self, Plane},
+        subdivide,
+        sweep::{self, SweepOptions},
+        symmetry::{self, Side},
+        voxel, winding,
+    },
+};
 use crate::{
-    assets::{Face, Point3D},
+    assets::{face::IndexMapping, Axis, Color, Face, Point2D, Point3D, Rect, UVMap},
     error::PicoError,
+    hash::{self, ContentHash},
+    lua_table::{self, LuaTable, LuaValue},
+    number::{PicoFormat, PicoNumber},
     point,
 };
-use rlua::{Lua, Table, Value};
-use std::{
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
     fmt::{Display, Formatter},
     str::FromStr,
 };
+#[cfg(feature = "std")]
+use rlua::{Table, Value};
+
+/// Smallest step picoCAD moves vertices by while snapping to its grid.
+pub const GRID_STEP: f64 = 0.25;
+/// Size of a single pixel of the texture in uv-coordinates.
+pub const UV_PIXEL_STEP: f64 = 0.125;
+/// Top left pixel of the strip of colors [`Mesh::bake_vertex_colors`] maps faces onto.
+///
+/// Each of the 16 colors takes 2x2 pixels in the order of their index, so the strip covers the
+/// bottom right 32x2 pixels of the texture.
+/// It can be painted with [`Footer::paint_palette_strip`](crate::assets::Footer::paint_palette_strip).
+pub const VERTEX_COLOR_STRIP: Point2D<usize> = Point2D { u: 96, v: 118 };
 
 /// Wrapper type for [`Point3D<f64>`] representing a rotation in picoCAD.
 /// If you want to access the raw [`Point3D`] type that is wrapped you can access it using an index
@@ -152,8 +195,8 @@ impl Rotation {
     /// assert!(rot.equal_rotation(&actual_rot));
     /// ```
     pub fn equal_rotation(&self, other: &Rotation) -> bool {
-        let mut left = self.clone();
-        let mut right = other.clone();
+        let mut left = *self;
+        let mut right = *other;
 
         left.round();
         left.normalize();
@@ -183,6 +226,10 @@ pub struct Mesh {
     pub vertices: Vec<Point3D<f64>>,
     /// Faces of a mesh.
     pub faces: Vec<Face>,
+    /// Keys of the mesh table this crate doesn't know, like fields added by newer versions of
+    /// picoCAD or other tools.
+    /// These are written back unchanged.
+    pub extras: BTreeMap<String, LuaValue>,
 }
 
 impl Mesh {
@@ -211,172 +258,1634 @@ impl Mesh {
             rotation: Rotation(point!(0.0, 0.0, 0.0)),
             vertices: vec![],
             faces: vec![],
+            extras: BTreeMap::new(),
         }
     }
-}
-
-impl Display for Mesh {
-    fn fmt(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
-        let name: String = self.name.clone();
-        let pos: String = format!("{{{}}}", self.position);
-        let rot: String = format!("{{{}}}", self.rotation.0);
-
-        let mut v: String = String::new();
 
-        for (i, vertex) in self.vertices.iter().enumerate() {
-            v.push_str(format!("  {{{}}}", vertex).as_str());
-            if i + 1 < self.vertices.len() {
-                v.push_str(",\n");
-            }
+    /// Returns a [`MeshBuilder`] for a mesh with the given name, which checks that every face only
+    /// uses vertices the mesh has.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mesh = Mesh::builder("triangle".to_string())
+    ///     .position(point!(0.0, -1.0, 0.0))
+    ///     .vertices([
+    ///         point!(0.0, 0.0, 0.0),
+    ///         point!(1.0, 0.0, 0.0),
+    ///         point!(0.0, 1.0, 0.0),
+    ///     ])
+    ///     .face(Face::builder().vertices([0, 1, 2]).build().unwrap())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(mesh.faces.len(), 1);
+    ///
+    /// let broken = Mesh::builder("broken".to_string())
+    ///     .face(Face::builder().vertices([0, 1, 2]).build().unwrap())
+    ///     .build();
+    ///
+    /// assert!(broken.is_err());
+    /// ```
+    pub fn builder(name: String) -> MeshBuilder {
+        MeshBuilder {
+            mesh: Mesh::new(name),
         }
+    }
 
-        let mut f: String = String::new();
-
-        for (i, face) in self.faces.iter().enumerate() {
-            f.push_str(format!("  {}", face).as_str());
-            if i + 1 < self.faces.len() {
-                f.push_str(",\n");
-            }
+    /// Creates the plane picoCAD adds to new projects.
+    ///
+    /// It lies flat at the origin, spans 2 units on the `x` and `z` axis and consists of a single
+    /// double sided face in light grey, mapped onto the top left 16x16 pixels of the texture.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Mesh};
+    ///
+    /// let plane = Mesh::plane();
+    ///
+    /// assert_eq!(plane.name, "plane");
+    /// assert_eq!(plane.vertices.len(), 4);
+    /// assert_eq!(plane.faces[0].color, Color::LightGrey);
+    /// assert!(plane.faces[0].double_sided);
+    /// ```
+    pub fn plane() -> Mesh {
+        Mesh {
+            vertices: vec![
+                point!(-1.0, 0.0, -1.0),
+                point!(1.0, 0.0, -1.0),
+                point!(1.0, 0.0, 1.0),
+                point!(-1.0, 0.0, 1.0),
+            ],
+            faces: vec![Face {
+                double_sided: true,
+                color: Color::LightGrey,
+                uv_maps: vec![
+                    UVMap::new(3, point!(0.0, 0.0)),
+                    UVMap::new(2, point!(2.0, 0.0)),
+                    UVMap::new(1, point!(2.0, 2.0)),
+                    UVMap::new(0, point!(0.0, 2.0)),
+                ],
+                ..Face::default()
+            }],
+            ..Mesh::new("plane".to_string())
         }
-
-        write!(
-            formatter,
-            "{{\n name='{}', pos={}, rot={},\n v={{\n{}\n }},\n f={{\n{}\n }}\n}}",
-            name, pos, rot, v, f
-        )
     }
-}
 
-impl TryFrom<Table<'_>> for Mesh {
-    type Error = PicoError;
-
-    fn try_from(value: Table<'_>) -> Result<Self, Self::Error> {
-        let mut name = String::new();
-        let mut position: Point3D<f64> = point!(0.0, 0.0, 0.0);
-        let mut rotation = Rotation(point!(0.0, 0.0, 0.0));
-        let mut vertices: Vec<Point3D<f64>> = vec![];
-        let mut faces: Vec<Face> = vec![];
+    /// Builds a grid mesh out of the pixels of the texture, using their colors as heights.
+    ///
+    /// `region` is the top left pixel and the size of the part of the texture that is used, parts
+    /// of it outside of the texture are ignored.
+    /// Each pixel becomes a vertex, spaced `scale.x` and `scale.z` apart, raised by its height
+    /// times `scale.y`.
+    /// The grid is centered around the position of the mesh.
+    /// More information can be found in the [`heightmap`](crate::geometry::heightmap) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Footer, Mesh, Point2D, Point3D};
+    /// use picocadrs::geometry::heightmap::HeightSource;
+    /// use picocadrs::point;
+    ///
+    /// let mut footer = Footer::default();
+    /// footer.set(point!(1, 1), Color::White).unwrap();
+    ///
+    /// let mesh = Mesh::from_heightmap(
+    ///     &footer,
+    ///     (point!(0, 0), point!(3, 3)),
+    ///     point!(0.25, 1.0, 0.25),
+    ///     HeightSource::ColorIndex,
+    /// );
+    ///
+    /// assert_eq!(mesh.faces.len(), 4);
+    /// assert_eq!(mesh.vertices[4], point!(0.0, -7.0, 0.0));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_heightmap(
+        footer: &Footer,
+        region: (Point2D<usize>, Point2D<usize>),
+        scale: Point3D<f64>,
+        source: HeightSource,
+    ) -> Mesh {
+        heightmap::from_heightmap(footer, region, scale, source)
+    }
 
-        for pair in value.pairs::<String, Value>() {
-            let (key, value) = pair.unwrap();
+    /// Creates a round mesh by spinning `profile` around the `y`-axis, with `segments` faces
+    /// around the axis.
+    ///
+    /// `u` of each profile point is its distance from the axis, `v` its height.
+    /// Faces point outwards if the profile runs from the bottom to the top.
+    /// More information can be found in the [`lathe`](crate::geometry::lathe) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// // a cylinder without top and bottom.
+    /// let mesh = Mesh::lathe(&[point!(1.0, 1.0), point!(1.0, -1.0)], 8);
+    ///
+    /// assert_eq!(mesh.vertices.len(), 16);
+    /// assert_eq!(mesh.faces.len(), 8);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn lathe(profile: &[Point2D<f64>], segments: usize) -> Mesh {
+        lathe::lathe(profile, segments)
+    }
 
-            match key.as_str() {
-                "name" => {
-                    name = if let Value::String(string) = value {
-                        string.to_str()?.to_string()
-                    } else {
-                        return Err(PicoError::MeshField("name".to_string()));
-                    }
-                }
-                "pos" => {
-                    position = if let Value::Table(table) = value {
-                        Point3D::try_from(table)?
-                    } else {
-                        return Err(PicoError::MeshField("pos".to_string()));
-                    }
-                }
-                "rot" => {
-                    rotation = if let Value::Table(table) = value {
-                        Rotation(Point3D::try_from(table)?)
-                    } else {
-                        return Err(PicoError::MeshField("rot".to_string()));
-                    }
-                }
-                "v" => {
-                    if let Value::Table(table) = value {
-                        for point in table.sequence_values::<Table>() {
-                            vertices.push(Point3D::try_from(point?)?);
-                        }
-                    } else {
-                        return Err(PicoError::MeshField("rot".to_string()));
-                    };
-                }
-                "f" => {
-                    if let Value::Table(table) = value {
-                        for face in table.sequence_values::<Table>() {
-                            faces.push(Face::try_from(face?)?);
-                        }
-                    } else {
-                        return Err(PicoError::MeshField("rot".to_string()));
-                    }
-                }
-                _ => {}
-            }
-        }
+    /// Creates a mesh by moving `profile` along `path`, like a tube, rail or road.
+    ///
+    /// `u` of each profile point is its distance to the right of the path, `v` its height above
+    /// it.
+    /// More information can be found in the [`sweep`](crate::geometry::sweep) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point2D, Point3D};
+    /// use picocadrs::geometry::sweep::SweepOptions;
+    /// use picocadrs::point;
+    ///
+    /// // a rail along a straight path.
+    /// let rail = Mesh::sweep(
+    ///     &[point!(-0.5, 0.0), point!(0.0, 0.5), point!(0.5, 0.0)],
+    ///     &[point!(0.0, 0.0, 0.0), point!(0.0, 0.0, 2.0), point!(0.0, 0.0, 5.0)],
+    ///     SweepOptions::default(),
+    /// );
+    ///
+    /// assert_eq!(rail.vertices.len(), 9);
+    /// assert_eq!(rail.faces.len(), 4);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn sweep(profile: &[Point2D<f64>], path: &[Point3D<f64>], options: SweepOptions) -> Mesh {
+        sweep::sweep(profile, path, options)
+    }
 
-        Ok(Mesh {
-            name,
-            position,
-            rotation,
-            vertices,
-            faces,
-        })
+    /// Returns a blocky mesh of everything inside of this mesh or `other`, built from voxels with
+    /// edges `resolution` units long.
+    ///
+    /// The result keeps the name, position and rotation of this mesh.
+    /// More information can be found in the [`voxel`](crate::geometry::voxel) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point3D};
+    /// use picocadrs::template::MeshTemplate;
+    /// use picocadrs::point;
+    ///
+    /// let base = MeshTemplate::new("base".to_string()).cuboid(4, 1, 4).build();
+    /// let mut tower = MeshTemplate::new("tower".to_string()).cube(2).build();
+    /// tower.position = point!(0.0, -1.5, 0.0);
+    ///
+    /// let castle = base.union_voxel(&tower, 0.25);
+    ///
+    /// assert!(castle.vertices.contains(&point!(1.0, -2.5, 1.0)));
+    /// assert!(castle.vertices.contains(&point!(2.0, 0.5, 2.0)));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn union_voxel(&self, other: &Mesh, resolution: f64) -> Mesh {
+        voxel::union_voxel(self, other, resolution)
     }
-}
 
-impl FromStr for Mesh {
-    type Err = PicoError;
+    /// Returns the indices of all faces whose [`normal`](Face::normal) points towards `direction`.
+    ///
+    /// A face points towards a direction if the angle between its normal and the direction is
+    /// smaller than 90 degrees.
+    /// Faces without a valid normal are never included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh, Point2D, Point3D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("triangles".to_string());
+    /// mesh.vertices = vec![point!(0.0, 0.0, 0.0), point!(1.0, 0.0, 0.0), point!(0.0, 1.0, 0.0)];
+    ///
+    /// let mut front = Face::default();
+    /// for i in 0..3 {
+    ///     front.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+    /// }
+    /// let mut back = front.clone();
+    /// back.uv_maps.reverse();
+    ///
+    /// mesh.faces = vec![front, back];
+    ///
+    /// assert_eq!(mesh.faces_facing(point!(0.0, 0.0, -1.0)), vec![0]);
+    /// assert_eq!(mesh.faces_facing(point!(0.0, 0.0, 1.0)), vec![1]);
+    /// ```
+    pub fn faces_facing(&self, direction: Point3D<f64>) -> Vec<usize> {
+        self.faces
+            .iter()
+            .enumerate()
+            .filter(|(_, face)| {
+                let normal = face.normal(&self.vertices);
+                normal.x * direction.x + normal.y * direction.y + normal.z * direction.z > 0.0
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut mesh = Ok(Mesh::new("mesh".to_string()));
+    /// Mirrors the mesh along the given axis around its [`position`](Mesh::position).
+    ///
+    /// The vertices coordinates on that axis are negated and every face is
+    /// [`flip`](Face::flip)ped, so faces still point outwards after mirroring.
+    /// If `mirror_uvs` is true the uv-coordinates of every face are mirrored as well, which keeps
+    /// the texture on the mirrored faces readable instead of showing it mirrored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Axis, Mesh, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("wing".to_string());
+    /// mesh.vertices.push(point!(1.0, 2.0, 3.0));
+    ///
+    /// mesh.mirror(Axis::X, false);
+    ///
+    /// assert_eq!(mesh.vertices[0], point!(-1.0, 2.0, 3.0));
+    /// ```
+    pub fn mirror(&mut self, axis: Axis, mirror_uvs: bool) {
+        for vertex in self.vertices.iter_mut() {
+            *vertex.axis_mut(axis) = -vertex.axis(axis);
+        }
 
-        let lua = Lua::new();
-        lua.context(|ctx| {
-            let table_result: rlua::Result<Table> = ctx.load(s).eval();
+        for face in self.faces.iter_mut() {
+            face.flip();
 
-            mesh = match table_result {
-                Ok(table) => Mesh::try_from(table),
-                Err(err) => Err(PicoError::from(err)),
+            if mirror_uvs {
+                face.mirror_uvs();
             }
-        });
-
-        mesh
+        }
     }
-}
-
-#[cfg(test)]
-pub mod tests {
-    use super::*;
-    use crate::point;
 
-    #[test]
-    fn test_rot_round() {
-        let mut rot = Rotation(point!(0.2423, 0.9999, 0.34));
-        rot.round();
-
-        assert_eq!(rot, Rotation(point!(0.242, 1.0, 0.34)));
+    /// Returns every edge of the mesh once, sorted by vertex indices.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::analysis::topology::Edge;
+    /// use picocadrs::assets::{Face, Mesh, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("triangle".to_string());
+    /// let mut face = Face::default();
+    /// for i in 0..3 {
+    ///     face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+    /// }
+    /// mesh.faces.push(face);
+    ///
+    /// assert_eq!(mesh.edges(), vec![Edge::new(0, 1), Edge::new(0, 2), Edge::new(1, 2)]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn edges(&self) -> Vec<Edge> {
+        self.edge_adjacency().into_keys().collect()
     }
 
-    #[test]
-    fn test_rot_normalize() {
-        let mut rot = Rotation(point!(2.24, -1.21, 0.34));
-        rot.normalize();
-        rot.round();
-
-        assert_eq!(rot, Rotation(point!(0.24, 0.79, 0.34)));
+    /// Maps every edge of the mesh to the indices of the faces using it.
+    ///
+    /// Edges connecting a vertex to itself are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::analysis::topology::Edge;
+    /// use picocadrs::assets::{Face, Mesh, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("quad".to_string());
+    /// for indices in [[0, 1, 2], [2, 1, 3]] {
+    ///     let mut face = Face::default();
+    ///     for i in indices {
+    ///         face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+    ///     }
+    ///     mesh.faces.push(face);
+    /// }
+    ///
+    /// let adjacency = mesh.edge_adjacency();
+    ///
+    /// assert_eq!(adjacency[&Edge::new(1, 2)], vec![0, 1]);
+    /// assert_eq!(adjacency[&Edge::new(0, 1)], vec![0]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn edge_adjacency(&self) -> BTreeMap<Edge, Vec<usize>> {
+        topology::edge_adjacency(self)
     }
 
-    #[test]
-    fn test_rot_equal_rotation() {
-        let mut rot = Rotation(point!(0.9999, 1.0, 0.0));
-        rot.normalize();
-        rot.round();
+    /// Returns the edges that are only used by a single face, sorted by vertex indices.
+    ///
+    /// These edges outline the holes in the mesh.
+    #[cfg(feature = "std")]
+    pub fn boundary_edges(&self) -> Vec<Edge> {
+        self.edge_adjacency()
+            .into_iter()
+            .filter(|(_, faces)| faces.len() == 1)
+            .map(|(edge, _)| edge)
+            .collect()
+    }
 
-        assert_eq!(rot, Rotation(point!(1.0, 0.0, 0.0)));
+    /// Returns the edges that are used by more than 2 faces, sorted by vertex indices.
+    #[cfg(feature = "std")]
+    pub fn non_manifold_edges(&self) -> Vec<Edge> {
+        self.edge_adjacency()
+            .into_iter()
+            .filter(|(_, faces)| faces.len() > 2)
+            .map(|(edge, _)| edge)
+            .collect()
+    }
 
-        let mut rot = Rotation(point!(0.9999, 1.0, 0.0));
-        rot.round();
-        rot.normalize();
-        rot.round();
+    /// Checks if the mesh is closed, meaning every edge is used by exactly 2 faces.
+    ///
+    /// Meshes without any edges are not watertight.
+    #[cfg(feature = "std")]
+    pub fn is_watertight(&self) -> bool {
+        let adjacency = self.edge_adjacency();
 
-        assert_eq!(rot, Rotation(point!(0.0, 0.0, 0.0)));
+        !adjacency.is_empty() && adjacency.values().all(|faces| faces.len() == 2)
+    }
 
-        assert!(rot.equal_rotation(&Rotation(point!(0.0, 0.0, 0.0))));
+    /// Moves the face with index `face_index` by `distance` along its [`normal`](Face::normal)
+    /// and connects it to its old position with a wall of quads.
+    ///
+    /// Negative distances push the face into the mesh.
+    /// Returns `false` and leaves the mesh untouched if there is no such face or it has no valid
+    /// normal.
+    /// More information can be found in the [`extrude`](crate::geometry::extrude) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh, Point2D, Point3D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("plane".to_string());
+    /// mesh.vertices = vec![
+    ///     point!(0.0, 0.0, 0.0),
+    ///     point!(0.0, 0.0, 1.0),
+    ///     point!(1.0, 0.0, 1.0),
+    ///     point!(1.0, 0.0, 0.0),
+    /// ];
+    /// let mut face = Face::default();
+    /// for i in 0..4 {
+    ///     face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+    /// }
+    /// mesh.faces.push(face);
+    ///
+    /// assert!(mesh.extrude_face(0, 1.0));
+    ///
+    /// assert_eq!(mesh.vertices.len(), 8);
+    /// assert_eq!(mesh.faces.len(), 5);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn extrude_face(&mut self, face_index: usize, distance: f64) -> bool {
+        extrude::extrude_face(self, face_index, distance)
     }
 
-    #[test]
-    fn test_mesh_new() {
-        let mesh = Mesh::new("my_mesh".to_string());
+    /// Cuts the mesh in two along `plane`, returning the part in front of the plane first and the
+    /// part behind it second.
+    ///
+    /// The plane is given relative to the [`position`](Mesh::position) of the mesh, like its
+    /// vertices.
+    /// Both halves keep the name, position, rotation and extras of the mesh.
+    /// Faces lying on the plane end up in the front half.
+    /// More information can be found in the [`split`](crate::geometry::split) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Axis, Face, Mesh, Point2D, Point3D, UVMap};
+    /// use picocadrs::geometry::split::Plane;
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("plane".to_string());
+    /// mesh.vertices = vec![
+    ///     point!(0.0, 0.0, 0.0),
+    ///     point!(2.0, 0.0, 0.0),
+    ///     point!(2.0, 0.0, 2.0),
+    ///     point!(0.0, 0.0, 2.0),
+    /// ];
+    /// let mut face = Face::default();
+    /// for i in 0..4 {
+    ///     face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+    /// }
+    /// mesh.faces.push(face);
+    ///
+    /// let (front, back) = mesh.split_by_plane(Plane::axis(Axis::X, 1.0));
+    ///
+    /// assert_eq!(front.faces.len(), 1);
+    /// assert_eq!(back.faces.len(), 1);
+    /// assert!(front.vertices.contains(&point!(1.0, 0.0, 2.0)));
+    /// assert!(back.vertices.contains(&point!(1.0, 0.0, 2.0)));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn split_by_plane(&self, plane: Plane) -> (Mesh, Mesh) {
+        split::split_by_plane(self, plane)
+    }
+
+    /// Returns a new mesh for each part of the mesh that isn't connected to the rest, keeping
+    /// the uv-coordinates of every face.
+    ///
+    /// More information can be found in the [`components`] module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// let mut copy = mesh.faces[0].clone();
+    /// for uv_map in copy.uv_maps.iter_mut() {
+    ///     uv_map.vertex_index += 4;
+    /// }
+    /// mesh.vertices.extend(mesh.vertices.clone().into_iter().map(|v| v + point!(0.0, 2.0, 0.0)));
+    /// mesh.faces.push(copy);
+    ///
+    /// let parts = mesh.split_connected_components();
+    ///
+    /// assert_eq!(parts.len(), 2);
+    /// assert_eq!(parts[1].name, "plane_2");
+    /// assert_eq!(parts[1].vertices[0], point!(-1.0, 2.0, -1.0));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn split_connected_components(&self) -> Vec<Mesh> {
+        components::split_connected_components(self)
+    }
+
+    /// Moves the faces at `indices` into a new mesh and returns it, removing vertices only they
+    /// used from this mesh.
+    ///
+    /// More information can be found in the [`components`] module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Mesh;
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// let extracted = mesh.extract_faces(&[0]);
+    ///
+    /// assert_eq!(extracted, Mesh::plane());
+    /// assert!(mesh.faces.is_empty());
+    /// assert!(mesh.vertices.is_empty());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn extract_faces(&mut self, indices: &[usize]) -> Mesh {
+        components::extract_faces(self, indices)
+    }
+
+    /// Moves the mesh so its bounding box in world space is aligned, and returns how far it was
+    /// moved.
+    ///
+    /// More information can be found in the [`align`](crate::align) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::align::Alignment;
+    /// use picocadrs::assets::{Axis, Mesh, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::plane();
+    ///
+    /// assert_eq!(mesh.align(Alignment::SnapMinTo(Axis::X, 0.0)), point!(1.0, 0.0, 0.0));
+    /// assert_eq!(mesh.position, point!(1.0, 0.0, 0.0));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn align(&mut self, alignment: Alignment) -> Point3D<f64> {
+        align::align_mesh(self, alignment)
+    }
+
+    /// Splits every face into smaller ones `levels` times without changing the shape of the mesh.
+    ///
+    /// Triangles are split into 4 triangles, any other face into one quad per corner, so each
+    /// level multiplies the amount of faces by roughly 4.
+    /// More information can be found in the [`subdivide`](crate::geometry::subdivide) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh, Point2D, Point3D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("plane".to_string());
+    /// mesh.vertices = vec![
+    ///     point!(0.0, 0.0, 0.0),
+    ///     point!(1.0, 0.0, 0.0),
+    ///     point!(1.0, 0.0, 1.0),
+    ///     point!(0.0, 0.0, 1.0),
+    /// ];
+    /// let mut face = Face::default();
+    /// for i in 0..4 {
+    ///     face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+    /// }
+    /// mesh.faces.push(face);
+    ///
+    /// mesh.subdivide(2);
+    ///
+    /// assert_eq!(mesh.faces.len(), 16);
+    /// assert_eq!(mesh.vertices.len(), 25);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn subdivide(&mut self, levels: u32) {
+        subdivide::subdivide(self, levels)
+    }
+
+    /// Throws away the geometry on one side of the plane through the meshes
+    /// [`position`](Mesh::position) facing `axis` and replaces it with a mirrored copy of the
+    /// half on `side`.
+    ///
+    /// Vertices on the seam are welded, so both halves stay connected.
+    /// More information can be found in the [`symmetry`](crate::geometry::symmetry) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Axis, Face, Mesh, Point2D, Point3D, UVMap};
+    /// use picocadrs::geometry::symmetry::Side;
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("half".to_string());
+    /// mesh.vertices = vec![
+    ///     point!(0.0, 0.0, 0.0),
+    ///     point!(1.0, 0.0, 0.0),
+    ///     point!(1.0, 1.0, 0.0),
+    ///     point!(0.0, 1.0, 0.0),
+    /// ];
+    /// let mut face = Face::default();
+    /// for i in 0..4 {
+    ///     face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+    /// }
+    /// mesh.faces.push(face);
+    ///
+    /// mesh.symmetrize(Axis::X, Side::Positive);
+    ///
+    /// assert_eq!(mesh.faces.len(), 2);
+    /// assert_eq!(mesh.vertices.len(), 6);
+    /// assert!(mesh.vertices.contains(&point!(-1.0, 1.0, 0.0)));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn symmetrize(&mut self, axis: Axis, side: Side) {
+        symmetry::symmetrize(self, axis, side)
+    }
+
+    /// Mirrors the uv-coordinates of the faces on the negative side of the plane through the
+    /// position of the mesh that faces `axis` over the vertical line at `seam_u` on the texture,
+    /// and returns how many faces were changed.
+    ///
+    /// Mapping one side of a symmetric mesh onto the texture and calling this lets both sides share
+    /// the same pixels.
+    /// More information can be found in the [`symmetry`](crate::geometry::symmetry) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Axis, Face, Mesh, Point2D, Point3D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("wings".to_string());
+    /// mesh.vertices = vec![
+    ///     point!(-2.0, 0.0, 0.0),
+    ///     point!(0.0, 0.0, 0.0),
+    ///     point!(0.0, 0.0, 1.0),
+    ///     point!(-2.0, 0.0, 1.0),
+    /// ];
+    /// let mut face = Face::default();
+    /// for (i, u) in [6.0, 8.0, 8.0, 6.0].into_iter().enumerate() {
+    ///     face.uv_maps.push(UVMap::new(i, point!(u, 0.0)));
+    /// }
+    /// mesh.faces.push(face);
+    ///
+    /// assert_eq!(mesh.mirror_uvs(Axis::X, 8.0), 1);
+    /// assert_eq!(mesh.faces[0].uv_maps[0].coords, point!(10.0, 0.0));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn mirror_uvs(&mut self, axis: Axis, seam_u: f64) -> usize {
+        symmetry::mirror_uvs(self, axis, seam_u)
+    }
+
+    /// Flips faces until all faces connected to each other are oriented the same way and returns
+    /// the amount of faces that were flipped.
+    ///
+    /// If `outward` is true, closed parts of the mesh are additionally turned so their faces point
+    /// away from their inside.
+    /// More information can be found in the [`winding`](crate::geometry::winding) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::builder("strip".to_string())
+    ///     .vertices([
+    ///         point!(0.0, 0.0, 0.0),
+    ///         point!(1.0, 0.0, 0.0),
+    ///         point!(2.0, 0.0, 0.0),
+    ///         point!(0.0, 0.0, 1.0),
+    ///         point!(1.0, 0.0, 1.0),
+    ///         point!(2.0, 0.0, 1.0),
+    ///     ])
+    ///     .faces([
+    ///         Face::builder().vertices([3, 4, 1, 0]).build().unwrap(),
+    ///         Face::builder().vertices([2, 5, 4, 1]).build().unwrap(),
+    ///     ])
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(mesh.make_winding_consistent(false), 1);
+    /// assert_eq!(mesh.faces[0].normal(&mesh.vertices), mesh.faces[1].normal(&mesh.vertices));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn make_winding_consistent(&mut self, outward: bool) -> usize {
+        winding::make_winding_consistent(self, outward)
+    }
+
+    /// Transforms every vertex closer to `center` than `radius`, fading the effect out towards
+    /// the radius according to `falloff`, and returns the amount of vertices moved.
+    ///
+    /// `transform` returns where a vertex would end up if it was fully transformed.
+    /// More information can be found in the [`proportional`](crate::geometry::proportional)
+    /// module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point3D};
+    /// use picocadrs::geometry::proportional::Falloff;
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("bump".to_string());
+    /// mesh.vertices = vec![point!(0.0, 0.0, 0.0), point!(1.0, 0.0, 0.0)];
+    ///
+    /// mesh.transform_vertices_in_radius(point!(0.0, 0.0, 0.0), 1.0, Falloff::Smooth, |v| {
+    ///     v + point!(0.0, 0.0, 1.0)
+    /// });
+    ///
+    /// assert_eq!(mesh.vertices, vec![point!(0.0, 0.0, 1.0), point!(1.0, 0.0, 0.0)]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn transform_vertices_in_radius<F>(
+        &mut self,
+        center: Point3D<f64>,
+        radius: f64,
+        falloff: Falloff,
+        transform: F,
+    ) -> usize
+    where
+        F: Fn(Point3D<f64>) -> Point3D<f64>,
+    {
+        proportional::transform_vertices_in_radius(self, center, radius, falloff, transform)
+    }
+
+    /// Removes faces by collapsing edges until the mesh has at most `target_faces` faces.
+    ///
+    /// Vertices on uv seams and on the border of the mesh are never moved, and collapses that
+    /// would turn faces around are skipped, so the mesh may end up with more faces than
+    /// `target_faces` if no more edges can be collapsed.
+    /// Vertices that are no longer used are removed.
+    /// More information can be found in the [`decimate`](crate::geometry::decimate) module.
+    ///
+    /// Does nothing if a face refers to a vertex that doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh, Point2D, Point3D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("plane".to_string());
+    /// mesh.vertices = vec![
+    ///     point!(0.0, 0.0, 0.0),
+    ///     point!(1.0, 0.0, 0.0),
+    ///     point!(1.0, 0.0, 1.0),
+    ///     point!(0.0, 0.0, 1.0),
+    /// ];
+    /// let mut face = Face::default();
+    /// for i in 0..4 {
+    ///     face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+    /// }
+    /// mesh.faces.push(face);
+    ///
+    /// mesh.subdivide(2);
+    /// assert_eq!(mesh.faces.len(), 16);
+    ///
+    /// mesh.decimate(12);
+    /// assert!(mesh.faces.len() <= 12);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn decimate(&mut self, target_faces: usize) {
+        decimate::decimate(self, target_faces)
+    }
+
+    /// Converts the mesh into the lua table picoCAD stores it as.
+    ///
+    /// The table can be inspected or changed without a running lua instance and turned back into
+    /// a mesh using [`Mesh::from_lua_table`].
+    /// Its [`Display`] implementation (via [`LuaValue::Table`]) writes valid lua source code.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point3D};
+    /// use picocadrs::lua_table::LuaValue;
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("cube".to_string());
+    /// mesh.vertices.push(point!(0.5, 1.0, 0.0));
+    ///
+    /// let table = mesh.to_lua_table();
+    ///
+    /// assert_eq!(table.fields["name"].as_str(), Some("cube"));
+    /// assert_eq!(
+    ///     LuaValue::Table(table).to_string(),
+    ///     "{f={},name='cube',pos={0,0,0},rot={0,0,0},v={{0.5,1,0}}}"
+    /// );
+    /// ```
+    pub fn to_lua_table(&self) -> LuaTable {
+        let point = |point: Point3D<f64>| {
+            LuaValue::Table(LuaTable {
+                sequence: vec![
+                    LuaValue::from(PicoNumber(point.x)),
+                    LuaValue::from(PicoNumber(point.y)),
+                    LuaValue::from(PicoNumber(point.z)),
+                ],
+                ..LuaTable::default()
+            })
+        };
+
+        let mut fields = self.extras.clone();
+
+        fields.insert("name".to_string(), LuaValue::String(self.name.clone()));
+        fields.insert("pos".to_string(), point(self.position));
+        fields.insert("rot".to_string(), point(self.rotation.0));
+        fields.insert(
+            "v".to_string(),
+            LuaValue::Table(LuaTable {
+                sequence: self.vertices.iter().map(|vertex| point(*vertex)).collect(),
+                ..LuaTable::default()
+            }),
+        );
+        fields.insert(
+            "f".to_string(),
+            LuaValue::Table(LuaTable {
+                sequence: self
+                    .faces
+                    .iter()
+                    .map(|face| LuaValue::Table(face.to_lua_table()))
+                    .collect(),
+                ..LuaTable::default()
+            }),
+        );
+
+        LuaTable {
+            fields,
+            ..LuaTable::default()
+        }
+    }
+
+    /// Creates a mesh from the lua table picoCAD stores it as.
+    ///
+    /// Parsing a mesh from a string reads the table and then uses this function.
+    /// Unknown fields are kept in [`Mesh::extras`].
+    /// Fails with [`PicoError::MeshField`] if a field has the wrong type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point3D};
+    /// use picocadrs::lua_table::LuaValue;
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("cube".to_string());
+    /// mesh.vertices.push(point!(0.5, 1.0, 0.0));
+    ///
+    /// let mut table = mesh.to_lua_table();
+    /// table.fields.insert("name".to_string(), LuaValue::String("box".to_string()));
+    ///
+    /// let changed = Mesh::from_lua_table(&table).unwrap();
+    ///
+    /// assert_eq!(changed.name, "box");
+    /// assert_eq!(changed.vertices, mesh.vertices);
+    /// ```
+    pub fn from_lua_table(table: &LuaTable) -> Result<Mesh, PicoError> {
+        let point = |value: &LuaValue, field: &str| -> Result<Point3D<f64>, PicoError> {
+            if value.as_table().is_none() {
+                return Err(PicoError::MeshField(field.to_string()));
+            }
+
+            Point3D::try_from(value)
+        };
+
+        let mut mesh = Mesh::new(String::new());
+
+        for (key, value) in table.fields.iter() {
+            match key.as_str() {
+                "name" => {
+                    mesh.name = value
+                        .as_str()
+                        .ok_or(PicoError::MeshField("name".to_string()))?
+                        .to_string()
+                }
+                "pos" => mesh.position = point(value, "pos")?,
+                "rot" => mesh.rotation = Rotation(point(value, "rot")?),
+                "v" => {
+                    let vertices = value
+                        .as_table()
+                        .ok_or(PicoError::MeshField("v".to_string()))?;
+
+                    for vertex in vertices.sequence.iter() {
+                        mesh.vertices.push(point(vertex, "v")?);
+                    }
+                }
+                "f" => {
+                    let faces = value
+                        .as_table()
+                        .ok_or(PicoError::MeshField("f".to_string()))?;
+
+                    for face in faces.sequence.iter() {
+                        let face = face
+                            .as_table()
+                            .ok_or(PicoError::MeshField("f".to_string()))?;
+                        mesh.faces.push(Face::from_lua_table(face)?);
+                    }
+                }
+                _ => {
+                    mesh.extras.insert(key.clone(), value.clone());
+                }
+            }
+        }
+
+        Ok(mesh)
+    }
+
+    /// Rounds every vertex to the nearest multiple of `step` on each axis.
+    ///
+    /// Use [`GRID_STEP`] to match picoCAD's own snapping.
+    /// Does nothing if `step` is not a positive number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point3D};
+    /// use picocadrs::assets::mesh::GRID_STEP;
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("imported".to_string());
+    /// mesh.vertices.push(point!(0.2499999, -0.8, 1.1300001));
+    ///
+    /// mesh.snap_to_grid(GRID_STEP);
+    ///
+    /// assert_eq!(mesh.vertices[0], point!(0.25, -0.75, 1.25));
+    /// ```
+    pub fn snap_to_grid(&mut self, step: f64) {
+        for vertex in self.vertices.iter_mut() {
+            vertex.x = snap(vertex.x, step);
+            vertex.y = snap(vertex.y, step);
+            vertex.z = snap(vertex.z, step);
+        }
+    }
+
+    /// Rounds the uv-coordinates of every face to the nearest multiple of `step`.
+    ///
+    /// Use [`UV_PIXEL_STEP`] to align them with the pixels of the texture.
+    /// Does nothing if `step` is not a positive number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh, Point2D, UVMap};
+    /// use picocadrs::assets::mesh::UV_PIXEL_STEP;
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::new("imported".to_string());
+    /// let mut face = Face::default();
+    /// face.uv_maps.push(UVMap::new(0, point!(1.06, 2.2)));
+    /// mesh.faces.push(face);
+    ///
+    /// mesh.snap_uvs_to_grid(UV_PIXEL_STEP);
+    ///
+    /// assert_eq!(mesh.faces[0].uv_maps[0].coords, point!(1.0, 2.25));
+    /// ```
+    pub fn snap_uvs_to_grid(&mut self, step: f64) {
+        for uv_map in self
+            .faces
+            .iter_mut()
+            .flat_map(|face| face.uv_maps.iter_mut())
+        {
+            uv_map.coords.u = snap(uv_map.coords.u, step);
+            uv_map.coords.v = snap(uv_map.coords.v, step);
+        }
+    }
+
+    /// Rounds the uv-coordinates of every face to the nearest pixel of the texture.
+    ///
+    /// Same as calling [`Face::snap_uv_to_pixels`] on every face.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// mesh.faces[0].uv_maps[0].coords = point!(0.01, 1.99);
+    ///
+    /// mesh.snap_all_uvs();
+    ///
+    /// assert_eq!(mesh.faces[0].uv_maps[0].coords, point!(0.0, 2.0));
+    /// ```
+    pub fn snap_all_uvs(&mut self) {
+        for face in self.faces.iter_mut() {
+            face.snap_uv_to_pixels();
+        }
+    }
+
+    /// Gives every face a single color out of the colors of its vertices and returns the amount
+    /// of faces colored.
+    ///
+    /// `colors` holds the color of each vertex, by index.
+    /// Each face takes the color most of its corners have, if there is no such color the average
+    /// of its corners is used.
+    /// Corners without a color are ignored, faces without any colored corner are left as they
+    /// are.
+    ///
+    /// The color is set as the color of the face and its uv-coordinates are pointed at the
+    /// color in the strip at [`VERTEX_COLOR_STRIP`], so the face looks the same with or without
+    /// texture.
+    /// The strip has to be painted onto the texture for this to work, see
+    /// [`Footer::paint_palette_strip`](crate::assets::Footer::paint_palette_strip).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Mesh, Model, Point2D};
+    /// use picocadrs::assets::mesh::VERTEX_COLOR_STRIP;
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// let colors = [Color::Red, Color::Red, Color::Blue, Color::Invalid];
+    ///
+    /// assert_eq!(mesh.bake_vertex_colors(&colors), 1);
+    /// assert_eq!(mesh.faces[0].color, Color::Red);
+    ///
+    /// let mut model = Model::default();
+    /// model.footer.paint_palette_strip(VERTEX_COLOR_STRIP);
+    /// model.meshes.push(mesh);
+    ///
+    /// let face = &model.meshes[0].faces[0];
+    /// assert!(face
+    ///     .uv_texels()
+    ///     .iter()
+    ///     .all(|texel| model.footer.pixel(*texel) == Color::Red));
+    /// ```
+    pub fn bake_vertex_colors(&mut self, colors: &[Color]) -> usize {
+        // corners of the 2x2 pixels of a color, in pixels.
+        const BLOCK: [(f64, f64); 4] = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+
+        let mut colored = 0;
+
+        for face in self.faces.iter_mut() {
+            let corners: Vec<Color> = face
+                .uv_maps
+                .iter()
+                .filter_map(|uv_map| colors.get(uv_map.vertex_index).copied())
+                .filter(|color| *color != Color::Invalid)
+                .collect();
+
+            let Some(color) = dominant_color(&corners) else {
+                continue;
+            };
+
+            let origin = point!(
+                (VERTEX_COLOR_STRIP.u + 2 * color.as_i32() as usize) as f64,
+                VERTEX_COLOR_STRIP.v as f64
+            );
+            for (uv_map, (du, dv)) in face.uv_maps.iter_mut().zip(BLOCK.iter().cycle()) {
+                uv_map.coords = point!(origin.u + du, origin.v + dv) * UV_PIXEL_STEP;
+            }
+
+            face.color = color;
+            colored += 1;
+        }
+
+        colored
+    }
+
+    /// Copies the uv-coordinates of the face at `template_face_index` onto every face in
+    /// `target_indices` and returns the amount of faces that got them.
+    ///
+    /// Corners take the coordinates of the template corner at the same position, see
+    /// [`Face::copy_uv_from`] to pick other corners.
+    /// Faces with a different amount of corners than the template and indices without a face are
+    /// skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// mesh.faces.push(mesh.faces[0].clone());
+    /// mesh.faces.push(mesh.faces[0].clone());
+    /// mesh.faces[0].uv_translate(point!(4.0, 0.0));
+    ///
+    /// assert_eq!(mesh.propagate_uv(0, &[1, 2, 7]), 2);
+    /// assert_eq!(mesh.faces[2].uv_maps, mesh.faces[0].uv_maps);
+    /// ```
+    pub fn propagate_uv(&mut self, template_face_index: usize, target_indices: &[usize]) -> usize {
+        let Some(template) = self.faces.get(template_face_index).cloned() else {
+            return 0;
+        };
+
+        let mut changed = 0;
+
+        for index in target_indices {
+            if let Some(face) = self.faces.get_mut(*index) {
+                if face.copy_uv_from(&template, IndexMapping::Same) {
+                    changed += 1;
+                }
+            }
+        }
+
+        changed
+    }
+
+    /// Removes the vertex at `index` and returns it, [`None`] if there is no vertex at `index`.
+    ///
+    /// Faces using the vertex lose that corner and are removed if less than 3 corners are left.
+    /// Indices of the vertices after it are moved down by one, so the remaining faces keep their
+    /// shape.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::plane();
+    ///
+    /// assert_eq!(mesh.remove_vertex(0), Some(point!(-1.0, 0.0, -1.0)));
+    /// assert_eq!(mesh.faces[0].uv_maps.len(), 3);
+    /// assert_eq!(mesh.faces[0].uv_maps[0].vertex_index, 2);
+    ///
+    /// mesh.remove_vertex(0);
+    /// assert!(mesh.faces.is_empty());
+    /// assert_eq!(mesh.remove_vertex(2), None);
+    /// ```
+    pub fn remove_vertex(&mut self, index: usize) -> Option<Point3D<f64>> {
+        if index >= self.vertices.len() {
+            return None;
+        }
+
+        for face in self.faces.iter_mut() {
+            face.uv_maps.retain(|uv_map| uv_map.vertex_index != index);
+
+            for uv_map in face.uv_maps.iter_mut() {
+                if uv_map.vertex_index > index {
+                    uv_map.vertex_index -= 1;
+                }
+            }
+        }
+
+        self.faces.retain(|face| face.uv_maps.len() >= 3);
+        Some(self.vertices.remove(index))
+    }
+
+    /// Removes the face at `index` and returns it, [`None`] if there is no face at `index`.
+    ///
+    /// Vertices are kept, even if no other face uses them, see [`Mesh::remove_unused_vertices`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Mesh;
+    ///
+    /// let mut mesh = Mesh::plane();
+    ///
+    /// assert!(mesh.remove_face(0).is_some());
+    /// assert!(mesh.remove_face(0).is_none());
+    /// assert_eq!(mesh.vertices.len(), 4);
+    /// ```
+    pub fn remove_face(&mut self, index: usize) -> Option<Face> {
+        (index < self.faces.len()).then(|| self.faces.remove(index))
+    }
+
+    /// Removes every vertex that isn't used by a face and returns the amount of removed vertices.
+    ///
+    /// Faces are updated to use the new indices of their vertices.
+    /// Indices that didn't point at a vertex before still don't afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// mesh.vertices.insert(0, point!(5.0, 5.0, 5.0));
+    /// for uv_map in mesh.faces[0].uv_maps.iter_mut() {
+    ///     uv_map.vertex_index += 1;
+    /// }
+    ///
+    /// assert_eq!(mesh.remove_unused_vertices(), 1);
+    /// assert_eq!(mesh, Mesh::plane());
+    /// ```
+    pub fn remove_unused_vertices(&mut self) -> usize {
+        let mut used = vec![false; self.vertices.len()];
+        for uv_map in self.faces.iter().flat_map(|face| face.uv_maps.iter()) {
+            if let Some(used) = used.get_mut(uv_map.vertex_index) {
+                *used = true;
+            }
+        }
+
+        // new index of every vertex, or the amount of vertices removed before it for unused ones.
+        let mut indices: Vec<usize> = Vec::with_capacity(self.vertices.len());
+        let mut removed = 0;
+        for is_used in used.iter() {
+            indices.push(indices.len() - removed);
+            if !is_used {
+                removed += 1;
+            }
+        }
+
+        for uv_map in self
+            .faces
+            .iter_mut()
+            .flat_map(|face| face.uv_maps.iter_mut())
+        {
+            uv_map.vertex_index = match indices.get(uv_map.vertex_index) {
+                Some(index) => *index,
+                None => uv_map.vertex_index - removed,
+            };
+        }
+
+        let mut is_used = used.iter();
+        self.vertices.retain(|_| *is_used.next().unwrap_or(&true));
+
+        removed
+    }
+
+    /// Keeps only the faces for which `predicate` returns true and returns the amount of removed
+    /// faces.
+    ///
+    /// Vertices are kept, even if no face uses them anymore, see
+    /// [`Mesh::remove_unused_vertices`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Mesh};
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// mesh.faces.push(mesh.faces[0].clone());
+    /// mesh.faces[1].color = Color::Red;
+    ///
+    /// assert_eq!(mesh.retain_faces(|face| face.color != Color::Red), 1);
+    /// assert_eq!(mesh.faces.len(), 1);
+    /// ```
+    pub fn retain_faces<F: FnMut(&Face) -> bool>(&mut self, mut predicate: F) -> usize {
+        let before = self.faces.len();
+        self.faces.retain(|face| predicate(face));
+
+        before - self.faces.len()
+    }
+
+    /// Adds the face to the mesh after checking that it has at least 3 corners and only uses
+    /// vertices the mesh has.
+    ///
+    /// Fails with [`PicoError::FaceUVMapLength`] for faces with less than 3 corners and
+    /// [`PicoError::VertexIndex`] for the first vertex the mesh doesn't have, leaving the mesh
+    /// unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh};
+    ///
+    /// let mut mesh = Mesh::plane();
+    ///
+    /// assert!(mesh
+    ///     .add_face_checked(Face::builder().vertices([0, 1, 2]).build().unwrap())
+    ///     .is_ok());
+    /// assert!(mesh
+    ///     .add_face_checked(Face::builder().vertices([0, 1, 4]).build().unwrap())
+    ///     .is_err());
+    /// assert!(mesh
+    ///     .add_face_checked(Face::builder().vertices([0, 1]).build().unwrap())
+    ///     .is_err());
+    /// assert_eq!(mesh.faces.len(), 2);
+    /// ```
+    pub fn add_face_checked(&mut self, face: Face) -> Result<(), PicoError> {
+        check_face(&face, self.vertices.len())?;
+        self.faces.push(face);
+
+        Ok(())
+    }
+
+    /// Adds a textured quad using the given vertices, with its corners mapped clockwise onto the
+    /// corners of `uv_rect` starting in the top left.
+    ///
+    /// Fails like [`Mesh::add_face_checked`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Mesh, Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// mesh.faces.clear();
+    ///
+    /// mesh.add_quad([3, 2, 1, 0], Rect::new(point!(0, 0), point!(16, 16)), Color::LightGrey)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(mesh.faces[0].uv_maps, Mesh::plane().faces[0].uv_maps);
+    /// ```
+    pub fn add_quad(
+        &mut self,
+        vertices: [usize; 4],
+        uv_rect: Rect,
+        color: Color,
+    ) -> Result<(), PicoError> {
+        self.add_face_checked(
+            Face::builder()
+                .vertices(vertices)
+                .uv_rect(uv_rect)
+                .color(color)
+                .build()?,
+        )
+    }
+
+    /// Adds a textured triangle using the given vertices, with its corners mapped clockwise onto
+    /// the top left, top right and bottom right corner of `uv_rect`.
+    ///
+    /// Fails like [`Mesh::add_face_checked`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Mesh, Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// let rect = Rect::new(point!(0, 0), point!(8, 8));
+    ///
+    /// assert!(mesh.add_tri([0, 1, 2], rect, Color::Red).is_ok());
+    /// assert!(mesh.add_tri([0, 1, 7], rect, Color::Red).is_err());
+    /// assert_eq!(mesh.faces[1].color, Color::Red);
+    /// ```
+    pub fn add_tri(
+        &mut self,
+        vertices: [usize; 3],
+        uv_rect: Rect,
+        color: Color,
+    ) -> Result<(), PicoError> {
+        self.add_face_checked(
+            Face::builder()
+                .vertices(vertices)
+                .uv_rect(uv_rect)
+                .color(color)
+                .build()?,
+        )
+    }
+
+    /// Returns a stable hash of the mesh as it is saved, name and placement included.
+    ///
+    /// More information can be found in the [`hash`](crate::hash) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Mesh;
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// assert_eq!(mesh.content_hash(), Mesh::plane().content_hash());
+    ///
+    /// mesh.name = "floor".to_string();
+    /// assert_ne!(mesh.content_hash(), Mesh::plane().content_hash());
+    /// ```
+    pub fn content_hash(&self) -> ContentHash {
+        hash::mesh_hash(self)
+    }
+
+    /// Returns a stable hash of the vertices and faces of the mesh.
+    ///
+    /// Meshes that only differ in name, position, rotation or [`extras`](Mesh::extras) get the
+    /// same hash, which helps finding copies of the same mesh.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut mesh = Mesh::plane();
+    /// mesh.name = "floor".to_string();
+    /// mesh.position = point!(2.0, 0.0, 0.0);
+    ///
+    /// assert_eq!(mesh.shape_hash(), Mesh::plane().shape_hash());
+    /// ```
+    pub fn shape_hash(&self) -> ContentHash {
+        hash::shape_hash(self)
+    }
+}
+
+/// Returns the color most of `colors` have, or the color closest to their average if no color is
+/// the most common.
+fn dominant_color(colors: &[Color]) -> Option<Color> {
+    let mut counts: BTreeMap<i32, usize> = BTreeMap::new();
+    for color in colors {
+        *counts.entry(color.as_i32()).or_insert(0) += 1;
+    }
+
+    let most = *counts.values().max()?;
+    let mut dominant = counts.iter().filter(|(_, count)| **count == most);
+
+    if let (Some((color, _)), None) = (dominant.next(), dominant.next()) {
+        return Some(Color::from(*color));
+    }
+
+    let (r, g, b) = colors.iter().fold((0, 0, 0), |(r, g, b), color| {
+        let (cr, cg, cb) = color.as_rgb();
+        (r + cr as usize, g + cg as usize, b + cb as usize)
+    });
+    let len = colors.len();
+
+    Some(Color::nearest_rgb(
+        (r / len) as u8,
+        (g / len) as u8,
+        (b / len) as u8,
+    ))
+}
+
+/// Builds a [`Mesh`] and checks it before handing it out.
+///
+/// Created with [`Mesh::builder`].
+/// Everything that isn't set is the same as in [`Mesh::new`].
+#[derive(Debug, Clone)]
+pub struct MeshBuilder {
+    mesh: Mesh,
+}
+
+impl MeshBuilder {
+    /// Sets the position of the mesh.
+    pub fn position(mut self, position: Point3D<f64>) -> Self {
+        self.mesh.position = position;
+        self
+    }
+
+    /// Sets the shadow rotation of the mesh.
+    pub fn rotation(mut self, rotation: Point3D<f64>) -> Self {
+        self.mesh.rotation = Rotation(rotation);
+        self
+    }
+
+    /// Adds a single vertex after the ones added before.
+    pub fn vertex(mut self, vertex: Point3D<f64>) -> Self {
+        self.mesh.vertices.push(vertex);
+        self
+    }
+
+    /// Adds vertices after the ones added before.
+    pub fn vertices<I: IntoIterator<Item = Point3D<f64>>>(mut self, vertices: I) -> Self {
+        self.mesh.vertices.extend(vertices);
+        self
+    }
+
+    /// Adds a single face after the ones added before.
+    pub fn face(mut self, face: Face) -> Self {
+        self.mesh.faces.push(face);
+        self
+    }
+
+    /// Adds faces after the ones added before.
+    pub fn faces<I: IntoIterator<Item = Face>>(mut self, faces: I) -> Self {
+        self.mesh.faces.extend(faces);
+        self
+    }
+
+    /// Adds a key to the [`extras`](Mesh::extras) of the mesh.
+    pub fn extra(mut self, key: String, value: LuaValue) -> Self {
+        self.mesh.extras.insert(key, value);
+        self
+    }
+
+    /// Puts the mesh together.
+    ///
+    /// Fails with [`PicoError::VertexIndex`] for the first face that uses a vertex the mesh
+    /// doesn't have and [`PicoError::FaceUVMapLength`] for faces with less than 3 corners.
+    pub fn build(self) -> Result<Mesh, PicoError> {
+        for face in self.mesh.faces.iter() {
+            check_face(face, self.mesh.vertices.len())?;
+        }
+
+        Ok(self.mesh)
+    }
+}
+
+/// Checks that the face has at least 3 corners and only uses the first `vertex_count` vertices.
+fn check_face(face: &Face, vertex_count: usize) -> Result<(), PicoError> {
+    if face.uv_maps.len() < 3 {
+        return Err(PicoError::FaceUVMapLength(face.uv_maps.len(), 3));
+    }
+
+    match face
+        .uv_maps
+        .iter()
+        .find(|uv_map| uv_map.vertex_index >= vertex_count)
+    {
+        Some(uv_map) => Err(PicoError::VertexIndex(uv_map.vertex_index, vertex_count)),
+        None => Ok(()),
+    }
+}
+
+/// Rounds `value` to the nearest multiple of `step`.
+/// Returns `value` unchanged if `step` is not a positive number.
+pub(crate) fn snap(value: f64, step: f64) -> f64 {
+    if step > 0.0 && step.is_finite() {
+        (value / step).round() * step
+    } else {
+        value
+    }
+}
+
+impl Display for Mesh {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        let name: String = self.name.clone();
+        let pos: String = format!("{{{}}}", PicoFormat(&self.position));
+        let rot: String = format!("{{{}}}", PicoFormat(&self.rotation.0));
+
+        let mut v: String = String::new();
+
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            v.push_str(format!("  {{{}}}", PicoFormat(vertex)).as_str());
+            if i + 1 < self.vertices.len() {
+                v.push_str(",\n");
+            }
+        }
+
+        let mut f: String = String::new();
+
+        for (i, face) in self.faces.iter().enumerate() {
+            f.push_str(format!("  {}", face).as_str());
+            if i + 1 < self.faces.len() {
+                f.push_str(",\n");
+            }
+        }
+
+        let mut extras: String = String::new();
+
+        for (key, value) in self.extras.iter() {
+            extras.push_str(format!(" {},", lua_table::field(key, value)).as_str());
+        }
+
+        write!(
+            formatter,
+            "{{\n name='{}', pos={}, rot={},{}\n v={{\n{}\n }},\n f={{\n{}\n }}\n}}",
+            name, pos, rot, extras, v, f
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<Table<'_>> for Mesh {
+    type Error = PicoError;
+
+    fn try_from(value: Table<'_>) -> Result<Self, Self::Error> {
+        let mut name = String::new();
+        let mut position: Point3D<f64> = point!(0.0, 0.0, 0.0);
+        let mut rotation = Rotation(point!(0.0, 0.0, 0.0));
+        let mut vertices: Vec<Point3D<f64>> = vec![];
+        let mut faces: Vec<Face> = vec![];
+        let mut extras: BTreeMap<String, LuaValue> = BTreeMap::new();
+
+        for pair in value.pairs::<Value, Value>() {
+            let (key, value) = pair?;
+
+            let key = match key {
+                Value::String(key) => key.to_str()?.to_string(),
+                _ => continue,
+            };
+
+            match key.as_str() {
+                "name" => {
+                    name = if let Value::String(string) = value {
+                        string.to_str()?.to_string()
+                    } else {
+                        return Err(PicoError::MeshField("name".to_string()));
+                    }
+                }
+                "pos" => {
+                    position = if let Value::Table(table) = value {
+                        Point3D::try_from(table)?
+                    } else {
+                        return Err(PicoError::MeshField("pos".to_string()));
+                    }
+                }
+                "rot" => {
+                    rotation = if let Value::Table(table) = value {
+                        Rotation(Point3D::try_from(table)?)
+                    } else {
+                        return Err(PicoError::MeshField("rot".to_string()));
+                    }
+                }
+                "v" => {
+                    if let Value::Table(table) = value {
+                        for point in table.sequence_values::<Table>() {
+                            vertices.push(Point3D::try_from(point?)?);
+                        }
+                    } else {
+                        return Err(PicoError::MeshField("rot".to_string()));
+                    };
+                }
+                "f" => {
+                    if let Value::Table(table) = value {
+                        for face in table.sequence_values::<Table>() {
+                            faces.push(Face::try_from(face?)?);
+                        }
+                    } else {
+                        return Err(PicoError::MeshField("rot".to_string()));
+                    }
+                }
+                _ => {
+                    extras.insert(key, LuaValue::try_from(value)?);
+                }
+            }
+        }
+
+        Ok(Mesh {
+            name,
+            position,
+            rotation,
+            vertices,
+            faces,
+            extras,
+        })
+    }
+}
+
+impl FromStr for Mesh {
+    type Err = PicoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Mesh::from_lua_table(&crate::lua_table::parse_table(s)?)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::Point2D;
+    use crate::point;
+
+    #[test]
+    fn test_rot_round() {
+        let mut rot = Rotation(point!(0.2423, 0.9999, 0.34));
+        rot.round();
+
+        assert_eq!(rot, Rotation(point!(0.242, 1.0, 0.34)));
+    }
+
+    #[test]
+    fn test_rot_normalize() {
+        let mut rot = Rotation(point!(2.24, -1.21, 0.34));
+        rot.normalize();
+        rot.round();
+
+        assert_eq!(rot, Rotation(point!(0.24, 0.79, 0.34)));
+    }
+
+    #[test]
+    fn test_rot_equal_rotation() {
+        let mut rot = Rotation(point!(0.9999, 1.0, 0.0));
+        rot.normalize();
+        rot.round();
+
+        assert_eq!(rot, Rotation(point!(1.0, 0.0, 0.0)));
+
+        let mut rot = Rotation(point!(0.9999, 1.0, 0.0));
+        rot.round();
+        rot.normalize();
+        rot.round();
+
+        assert_eq!(rot, Rotation(point!(0.0, 0.0, 0.0)));
+
+        assert!(rot.equal_rotation(&Rotation(point!(0.0, 0.0, 0.0))));
+    }
+
+    #[test]
+    fn test_mesh_new() {
+        let mesh = Mesh::new("my_mesh".to_string());
 
         assert_eq!(mesh.name, "my_mesh");
         assert_eq!(mesh.position, point!(0.0, 0.0, 0.0));
@@ -385,6 +1894,267 @@ pub mod tests {
         assert!(mesh.vertices.is_empty());
     }
 
+    #[test]
+    fn test_mesh_faces_facing() {
+        let mesh = TEST_MESH.parse::<Mesh>().unwrap();
+
+        assert_eq!(mesh.faces_facing(point!(0.0, 0.0, -1.0)), vec![0]);
+        assert_eq!(mesh.faces_facing(point!(0.0, 0.0, 1.0)), vec![1]);
+        assert_eq!(mesh.faces_facing(point!(1.0, 1.0, 0.0)), vec![4, 5]);
+    }
+
+    #[test]
+    fn test_mesh_mirror() {
+        let original = TEST_MESH.parse::<Mesh>().unwrap();
+        let mut mesh = original.clone();
+        mesh.mirror(Axis::Y, false);
+
+        assert_eq!(mesh.vertices[0], point!(-0.5, 0.5, -0.5));
+        assert_eq!(mesh.vertices[2], point!(0.5, -0.5, -0.5));
+
+        // faces of a mirrored cube still point outwards.
+        assert_eq!(mesh.faces_facing(point!(0.0, 0.0, -1.0)), vec![0]);
+        assert_eq!(mesh.faces_facing(point!(0.0, 0.0, 1.0)), vec![1]);
+        assert_eq!(mesh.faces[0].uv_maps[0].coords, point!(5.5, 1.5));
+
+        mesh.mirror(Axis::Y, true);
+        assert_eq!(mesh.vertices, original.vertices);
+        assert_eq!(mesh.faces[0].uv_maps[0].coords, point!(6.5, 0.5));
+    }
+
+    #[test]
+    fn test_mesh_bake_vertex_colors() {
+        assert_eq!(dominant_color(&[]), None);
+        assert_eq!(
+            dominant_color(&[Color::Green, Color::Red, Color::Green]),
+            Some(Color::Green)
+        );
+        // black and white average out to the color closest to grey.
+        assert_eq!(
+            dominant_color(&[Color::Black, Color::White]),
+            Some(Color::Lavender)
+        );
+
+        let mut mesh = Mesh::plane();
+        let triangle = Face {
+            uv_maps: vec![
+                UVMap::new(0, point!(0.0, 0.0)),
+                UVMap::new(1, point!(0.0, 0.0)),
+                UVMap::new(2, point!(0.0, 0.0)),
+            ],
+            ..Face::default()
+        };
+        mesh.faces.push(triangle);
+        let untouched = mesh.faces[0].clone();
+
+        assert_eq!(mesh.bake_vertex_colors(&[]), 0);
+        assert_eq!(mesh.bake_vertex_colors(&[Color::Invalid; 4]), 0);
+        assert_eq!(mesh.faces[0], untouched);
+
+        let colors = [Color::Pink, Color::Pink, Color::Green, Color::Green];
+        assert_eq!(mesh.bake_vertex_colors(&colors), 2);
+        assert_eq!(mesh.faces[1].color, Color::Pink);
+        assert_eq!(mesh.faces[1].uv_maps[0].coords, point!(15.5, 14.75));
+        assert_eq!(mesh.faces[1].uv_maps[2].coords, point!(15.75, 15.0));
+
+        let mut footer = Footer::default();
+        footer.paint_palette_strip(VERTEX_COLOR_STRIP);
+        for face in mesh.faces.iter() {
+            let texels = face.uv_texels();
+
+            assert!(!texels.is_empty());
+            assert!(texels
+                .iter()
+                .all(|texel| footer.pixel(*texel) == face.color));
+        }
+    }
+
+    #[test]
+    fn test_mesh_snap_to_grid() {
+        let mut mesh: Mesh = TEST_MESH.parse().unwrap();
+        mesh.vertices[0] = point!(-0.51, -0.4999, 0.6);
+        mesh.faces[0].uv_maps[0].coords = point!(5.44, 0.56);
+
+        mesh.snap_to_grid(GRID_STEP);
+        mesh.snap_uvs_to_grid(UV_PIXEL_STEP);
+
+        assert_eq!(mesh.vertices[0], point!(-0.5, -0.5, 0.5));
+        assert_eq!(mesh.vertices[1], point!(0.5, -0.5, -0.5));
+        assert_eq!(mesh.faces[0].uv_maps[0].coords, point!(5.5, 0.5));
+
+        mesh.snap_to_grid(0.0);
+        mesh.snap_to_grid(f64::NAN);
+        assert_eq!(mesh.vertices[0], point!(-0.5, -0.5, 0.5));
+    }
+
+    #[test]
+    fn test_mesh_extras() {
+        let source =
+            TEST_MESH.replace("rot={0,-0.5,0},", "rot={0,-0.5,0}, layer=2, tags={'prop'},");
+        let source = source.replacen("c=11,", "c=11, glow=true,", 1);
+
+        let mesh: Mesh = source.parse().unwrap();
+
+        assert_eq!(mesh.extras.get("layer"), Some(&LuaValue::Integer(2)));
+        assert_eq!(mesh.extras.len(), 2);
+        assert_eq!(
+            mesh.faces[0].extras.get("glow"),
+            Some(&LuaValue::Boolean(true))
+        );
+        assert!(mesh.faces[1].extras.is_empty());
+
+        assert_eq!(mesh.to_string().parse::<Mesh>().unwrap(), mesh);
+        assert!(mesh.to_string().contains("layer=2, tags={'prop'},"));
+    }
+
+    #[test]
+    fn test_mesh_extras_keys() {
+        let source = TEST_MESH.replace("rot={0,-0.5,0},", "rot={0,-0.5,0}, ['my-key']=1,");
+        let source = source.replacen("c=11,", "c=11, ['it\\'s']='x',", 1);
+
+        let mesh: Mesh = source.parse().unwrap();
+
+        assert_eq!(mesh.extras.get("my-key"), Some(&LuaValue::Integer(1)));
+        assert_eq!(
+            mesh.faces[0].extras.get("it's"),
+            Some(&LuaValue::String("x".to_string()))
+        );
+
+        let written = mesh.to_string();
+
+        assert!(written.contains("['my-key']=1,"));
+        assert!(written.contains("['it\\'s']='x',"));
+        assert_eq!(written.parse::<Mesh>().unwrap(), mesh);
+    }
+
+    #[test]
+    fn test_mesh_lua_table() {
+        let source =
+            TEST_MESH.replace("rot={0,-0.5,0},", "rot={0,-0.5,0}, layer=2, tags={'prop'},");
+        let mesh: Mesh = source.parse().unwrap();
+
+        let table = mesh.to_lua_table();
+        assert_eq!(Mesh::from_lua_table(&table).unwrap(), mesh);
+
+        // the table written as lua source parses into the same mesh.
+        let written = LuaValue::Table(table.clone()).to_string();
+        assert_eq!(written.parse::<Mesh>().unwrap(), mesh);
+
+        // tables parsed from the file work too.
+        let parsed: LuaValue = source.parse().unwrap();
+        assert_eq!(
+            Mesh::from_lua_table(parsed.as_table().unwrap()).unwrap(),
+            mesh
+        );
+
+        let mut broken = table;
+        broken
+            .fields
+            .insert("pos".to_string(), LuaValue::String("0,0,0".to_string()));
+        assert!(matches!(
+            Mesh::from_lua_table(&broken),
+            Err(PicoError::MeshField(_))
+        ));
+    }
+
+    #[test]
+    fn test_mesh_builder() {
+        let mesh = Mesh::builder("plane".to_string())
+            .vertices(Mesh::plane().vertices)
+            .face(
+                Face::builder()
+                    .vertices([3, 2, 1, 0])
+                    .color(Color::LightGrey)
+                    .double_sided(true)
+                    .uv_rect(Rect::new(point!(0, 0), point!(16, 16)))
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(mesh, Mesh::plane());
+
+        let broken = Mesh::builder("broken".to_string())
+            .vertex(point!(0.0, 0.0, 0.0))
+            .faces([
+                Face::builder().vertices([0, 0, 0]).build().unwrap(),
+                Face::builder().vertices([0, 4, 1]).build().unwrap(),
+            ])
+            .build();
+
+        assert!(matches!(broken, Err(PicoError::VertexIndex(4, 1))));
+    }
+
+    #[test]
+    fn test_mesh_removal() {
+        let mut mesh = Mesh::builder("strip".to_string())
+            .vertices([
+                point!(0.0, 0.0, 0.0),
+                point!(1.0, 0.0, 0.0),
+                point!(2.0, 0.0, 0.0),
+                point!(0.0, 0.0, 1.0),
+                point!(1.0, 0.0, 1.0),
+                point!(2.0, 0.0, 1.0),
+            ])
+            .faces([
+                Face::builder().vertices([0, 1, 4, 3]).build().unwrap(),
+                Face::builder().vertices([1, 2, 5, 4]).build().unwrap(),
+            ])
+            .build()
+            .unwrap();
+
+        assert_eq!(mesh.remove_vertex(6), None);
+        assert_eq!(mesh.remove_vertex(2), Some(point!(2.0, 0.0, 0.0)));
+
+        let indices: Vec<Vec<usize>> = mesh
+            .faces
+            .iter()
+            .map(|face| face.uv_maps.iter().map(|uv| uv.vertex_index).collect())
+            .collect();
+        assert_eq!(indices, vec![vec![0, 1, 3, 2], vec![1, 4, 3]]);
+
+        assert!(mesh.remove_face(0).is_some());
+        assert_eq!(mesh.remove_unused_vertices(), 2);
+        assert_eq!(mesh.vertices.len(), 3);
+        assert_eq!(mesh.faces[0].uv_maps[1].vertex_index, 2);
+        assert_eq!(mesh.remove_unused_vertices(), 0);
+
+        mesh.faces[0].uv_maps[0].vertex_index = 9;
+        mesh.vertices.insert(0, point!(5.0, 5.0, 5.0));
+        assert_eq!(mesh.remove_unused_vertices(), 2);
+        assert_eq!(mesh.faces[0].uv_maps[0].vertex_index, 7);
+
+        assert_eq!(mesh.retain_faces(|face| face.uv_maps.len() == 4), 1);
+        assert!(mesh.faces.is_empty());
+    }
+
+    #[test]
+    fn test_mesh_add_face_checked() {
+        let mut mesh = Mesh::plane();
+        let rect = Rect::new(point!(0, 0), point!(4, 4));
+
+        assert!(matches!(
+            mesh.add_face_checked(Face::default()),
+            Err(PicoError::FaceUVMapLength(0, 3))
+        ));
+        assert!(matches!(
+            mesh.add_quad([0, 1, 2, 5], rect, Color::Red),
+            Err(PicoError::VertexIndex(5, 4))
+        ));
+        assert_eq!(mesh.faces.len(), 1);
+
+        mesh.add_tri([2, 1, 0], rect, Color::Red).unwrap();
+        assert_eq!(
+            mesh.faces[1].uv_maps,
+            vec![
+                UVMap::new(2, point!(0.0, 0.0)),
+                UVMap::new(1, point!(0.5, 0.0)),
+                UVMap::new(0, point!(0.5, 0.5)),
+            ]
+        );
+    }
+
     #[test]
     fn test_mesh_parse() {
         assert_eq!(TEST_MESH, TEST_MESH.parse::<Mesh>().unwrap().to_string());
@@ -1,15 +1,21 @@
 pub mod color;
+pub mod coord;
 pub mod face;
 pub mod footer;
 pub mod header;
 pub mod mesh;
 pub mod model;
+#[cfg(feature = "std")]
+pub mod palette;
 pub mod point;
 
-pub use color::Color;
-pub use face::{Face, UVMap};
-pub use footer::Footer;
+pub use color::{Color, ExtendedColor};
+pub use coord::{TexelCoord, UvCoord};
+pub use face::{Face, FaceBuilder, FaceFlag, FaceHit, UVMap};
+pub use footer::{Footer, Rect};
 pub use header::Header;
-pub use mesh::{Mesh, Rotation};
-pub use model::Model;
-pub use point::{Point2D, Point3D};
+pub use mesh::{Mesh, MeshBuilder, Rotation};
+pub use model::{MeshFaceRef, Model, ProjectPreset};
+#[cfg(feature = "std")]
+pub use palette::{DitherPattern, Palette};
+pub use point::{Axis, Point2D, Point3D};
@@ -3,30 +3,82 @@
 //!
 //! A picoCAD file consists of 3 main parts.
 //! - _[`header`](crate::assets::header):_ Contains general settings of the project,
-//! like background color or name.
-//! Each component is seperated by `;`.
-//! Its end is indicated by a newline (`\n`) character, meaning this is always the first line of the
-//! file.
+//!   like background color or name.
+//!   Each component is seperated by `;`.
+//!   Its end is indicated by a newline (`\n`) character, meaning this is always the first line of the
+//!   file.
 //! - _[`meshes`](crate::assets::mesh):_ This is a [`lua table`](https://www.lua.org/pil/2.5.html)
-//! holding a list of meshes.
-//! The order these are in does not matter.
-//! Each mesh itself is also represented as a [`lua table`](https://www.lua.org/pil/2.5.html).
-//! Aside from the lua table's closing bracket the end of this section is indicated by a `%`
+//!   holding a list of meshes.
+//!   The order these are in does not matter.
+//!   Each mesh itself is also represented as a [`lua table`](https://www.lua.org/pil/2.5.html).
+//!   Aside from the lua table's closing bracket the end of this section is indicated by a `%`
 //! - _[`footer`](crate::assets::footer):_ Holds the texture used for uv mapping.
 
+#[cfg(feature = "svg")]
+use crate::export::svg;
+#[cfg(feature = "vox")]
+use crate::vox::{self, VoxOptions};
+#[cfg(feature = "std")]
 use crate::{
-    assets::{Footer, Header, Mesh},
+    align::{self, Alignment},
+    analysis::{
+        density::DensityReport,
+        limits::{LimitViolation, Limits},
+        overlaps,
+        stats::ModelStats,
+        summary::ModelSummary,
+        usage::TextureUsage,
+    },
+    assets::{mesh::snap, Axis, Color, Point3D},
+    atlas,
+    budget::{TextureAssignment, TextureBudget},
+    camera::CameraParams,
+    diff::{self, ModelDiff},
+    duplicate::{self, DuplicateOptions},
+    export::{
+        image::{self, TemplateImage},
+        obj,
+        slice::{self, Polyline},
+    },
+    files::{self, WriteOptions},
+    geometry::{components, split::Plane},
+    library::{self, Asset, ImportOptions},
+    merge::{self, MergeConflict},
+    occlusion::{self, AoOptions},
+    optimize::{self, OptimizeOptions, OptimizeReport},
+    paths::Paths,
+    raycast::{self, RayHit},
+    recolor::{self, RemapReport},
+    select::MeshSelection,
+    shading::{self, LightingReference, LightingReport},
+    spatial::Bvh,
+    visibility,
+};
+use crate::{
+    assets::{Face, FaceFlag, Footer, Header, Mesh},
     error::PicoError,
-    paths::projects_path,
+    hash::{self, ContentHash},
+    lenient::{self, ParseWarning},
+    metadata::{self, Metadata},
 };
-use rlua::{Lua, Table};
-use std::ffi::OsString;
-use std::{
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
     fmt::{Display, Formatter},
-    io::Write,
-    path::PathBuf,
     str::FromStr,
 };
+#[cfg(feature = "std")]
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 /// A picoCAD model.
 ///
@@ -35,8 +87,8 @@ use std::{
 ///
 /// - The [`Header`] contains general settings of the project, like background color or name.
 /// - After the header there is a list of [`meshes`](Mesh) that combined define the 3-dimensional structure of
-/// the model.
-/// This part also takes care of uv-mapping.
+///   the model.
+///   This part also takes care of uv-mapping.
 /// - At the end is the [`Footer`] which holds the texture used for uv-mapping.
 ///
 /// <br/>
@@ -53,74 +105,1634 @@ pub struct Model {
     pub footer: Footer,
 }
 
-impl Model {
-    /// Loads a model from an absolute path.
+/// Identifies a face inside of a [`Model`] by the index of its mesh and the index of the face
+/// inside that mesh.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::MeshFaceRef;
+///
+/// let face_ref = MeshFaceRef::new(1, 3);
+///
+/// assert_eq!(face_ref.mesh, 1);
+/// assert_eq!(face_ref.face, 3);
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct MeshFaceRef {
+    /// Index of the mesh in [`Model::meshes`].
+    pub mesh: usize,
+    /// Index of the face in [`Mesh::faces`].
+    pub face: usize,
+}
+
+impl MeshFaceRef {
+    /// Creates a new reference to the face with index `face` in the mesh with index `mesh`.
+    pub fn new(mesh: usize, face: usize) -> MeshFaceRef {
+        MeshFaceRef { mesh, face }
+    }
+}
+
+/// The kinds of projects picoCAD can start new projects as.
+///
+/// Used by [`Model::new_project`].
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum ProjectPreset {
+    /// A project without any meshes.
+    #[default]
+    Empty,
+    /// A project holding the plane picoCAD adds to new projects, see [`Mesh::plane`].
+    Plane,
+}
+
+impl Model {
+    /// Creates a new model without meshes with the given project name.
+    /// Header and footer are otherwise the same as in [`Model::default`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Model;
+    ///
+    /// let model = Model::new_named("my_project".to_string());
+    ///
+    /// assert_eq!(model.header.name, "my_project");
+    /// assert!(model.meshes.is_empty());
+    /// ```
+    pub fn new_named(name: String) -> Model {
+        let mut model = Model::default();
+        model.header.name = name;
+        model
+    }
+
+    /// Creates a new project with the given name the same way picoCAD does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model, ProjectPreset};
+    ///
+    /// let model = Model::new_project("my_project".to_string(), ProjectPreset::Plane);
+    ///
+    /// assert_eq!(model.header.name, "my_project");
+    /// assert_eq!(model.meshes, vec![Mesh::plane()]);
+    /// ```
+    pub fn new_project(name: String, preset: ProjectPreset) -> Model {
+        let mut model = Model::new_named(name);
+
+        match preset {
+            ProjectPreset::Empty => {}
+            ProjectPreset::Plane => model.meshes.push(Mesh::plane()),
+        }
+
+        model
+    }
+
+    /// Returns a stable hash of the file the model is saved as.
+    ///
+    /// Models only get the same hash if picoCAD would store them the same way, which makes it a
+    /// cheap way to check if a file changed.
+    /// More information can be found in the [`hash`](crate::hash) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    ///
+    /// let model = Model::new_project("house".to_string(), ProjectPreset::Plane);
+    /// let saved = model.content_hash();
+    ///
+    /// assert_eq!(model.clone().content_hash(), saved);
+    /// assert_ne!(Model::new_named("house".to_string()).content_hash(), saved);
+    /// ```
+    pub fn content_hash(&self) -> ContentHash {
+        hash::content_hash(self)
+    }
+
+    /// Returns the [`content_hash`](Mesh::content_hash) of every mesh in the same order as
+    /// [`meshes`](Model::meshes).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model, ProjectPreset};
+    ///
+    /// let model = Model::new_project("house".to_string(), ProjectPreset::Plane);
+    ///
+    /// assert_eq!(model.mesh_hashes(), vec![Mesh::plane().content_hash()]);
+    /// ```
+    pub fn mesh_hashes(&self) -> Vec<ContentHash> {
+        self.meshes.iter().map(Mesh::content_hash).collect()
+    }
+
+    /// Parses a model from a project file, skipping meshes and faces that can't be read instead of
+    /// failing.
+    ///
+    /// Everything that was skipped or repaired is returned as a [`ParseWarning`] together with
+    /// where it is located in the file.
+    /// More information can be found in the [`lenient`](crate::lenient) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Footer, Model};
+    ///
+    /// let file = format!(
+    ///     "picocad;salvaged;16;1;0\n{{\n{{ name='cube', v={{}}, f={{ {{1,2,3}} }} }}\n}}%\n{}",
+    ///     Footer::default()
+    /// );
+    ///
+    /// let (model, warnings) = Model::parse_lenient(&file);
+    ///
+    /// assert_eq!(model.meshes[0].name, "cube");
+    /// assert!(model.meshes[0].faces.is_empty());
+    /// assert_eq!(warnings[0].to_string(), "line 3, mesh 0, face 0: vertex index 0 out of range (mesh has 0 vertices)");
+    /// ```
+    pub fn parse_lenient(s: &str) -> (Model, Vec<ParseWarning>) {
+        lenient::parse_lenient(s)
+    }
+
+    /// Returns the author, license, tags and tool version stored in the header.
+    /// More information can be found in the [`metadata`](crate::metadata) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Model;
+    ///
+    /// let model: Model = format!(
+    ///     "picocad;house;16;1;0;meta.license=CC0\n{{\n}}%\n{}",
+    ///     picocadrs::assets::Footer::default()
+    /// )
+    /// .parse()
+    /// .unwrap();
+    ///
+    /// assert_eq!(model.metadata().license.as_deref(), Some("CC0"));
+    /// assert!(Model::default().metadata().is_empty());
+    /// ```
+    pub fn metadata(&self) -> Metadata {
+        metadata::metadata(self)
+    }
+
+    /// Replaces the metadata stored in the header, other header extensions are kept.
+    /// More information can be found in the [`metadata`](crate::metadata) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Model;
+    /// use picocadrs::metadata::Metadata;
+    ///
+    /// let mut model = Model::default();
+    /// model.set_metadata(&Metadata {
+    ///     license: Some("CC0".to_string()),
+    ///     ..Metadata::default()
+    /// });
+    ///
+    /// assert_eq!(model.header.to_string(), "picocad;unnamed;16;1;0;meta.license=CC0");
+    /// ```
+    pub fn set_metadata(&mut self, metadata: &Metadata) {
+        metadata::set_metadata(self, metadata)
+    }
+
+    /// Tags the face with index `face` of the mesh with index `mesh`, storing the tag in the
+    /// [`extras`](Face::extras) of the face.
+    /// More information can be found in the [`metadata`](crate::metadata) module.
+    ///
+    /// Returns [`PicoError::MeshIndex`] or [`PicoError::FaceIndex`] if the face doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    ///
+    /// let mut model = Model::new_project("house".to_string(), ProjectPreset::Plane);
+    /// model.tag_face(0, 0, "emissive").unwrap();
+    ///
+    /// assert_eq!(
+    ///     model.meshes[0].faces[0].extras["tags"].to_string(),
+    ///     "{'emissive'}"
+    /// );
+    /// assert!(model.tag_face(0, 1, "emissive").is_err());
+    /// ```
+    pub fn tag_face(&mut self, mesh: usize, face: usize, tag: &str) -> Result<(), PicoError> {
+        metadata::tag_face(self, mesh, face, tag)
+    }
+
+    /// Removes `tag` from the face with index `face` of the mesh with index `mesh`.
+    /// Returns `true` if the face was tagged with it.
+    /// More information can be found in the [`metadata`](crate::metadata) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    ///
+    /// let mut model = Model::new_project("house".to_string(), ProjectPreset::Plane);
+    /// model.tag_face(0, 0, "emissive").unwrap();
+    ///
+    /// assert!(model.untag_face(0, 0, "emissive"));
+    /// assert!(!model.untag_face(0, 0, "emissive"));
+    /// assert!(model.meshes[0].faces[0].extras.is_empty());
+    /// ```
+    pub fn untag_face(&mut self, mesh: usize, face: usize, tag: &str) -> bool {
+        metadata::untag_face(self, mesh, face, tag)
+    }
+
+    /// Returns the faces tagged with `tag`, sorted by mesh and face index.
+    /// More information can be found in the [`metadata`](crate::metadata) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{MeshFaceRef, Model, ProjectPreset};
+    ///
+    /// let mut model = Model::new_project("house".to_string(), ProjectPreset::Plane);
+    /// model.tag_face(0, 0, "window").unwrap();
+    ///
+    /// assert_eq!(model.faces_with_tag("window"), vec![MeshFaceRef::new(0, 0)]);
+    /// assert!(model.faces_with_tag("door").is_empty());
+    /// ```
+    pub fn faces_with_tag(&self, tag: &str) -> Vec<MeshFaceRef> {
+        metadata::faces_with_tag(self, tag)
+    }
+
+    /// Returns every face tag stored in the model and the faces tagged with it.
+    /// More information can be found in the [`metadata`](crate::metadata) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    ///
+    /// let mut model = Model::new_project("house".to_string(), ProjectPreset::Plane);
+    /// model.tag_face(0, 0, "window").unwrap();
+    /// model.tag_face(0, 0, "glass").unwrap();
+    ///
+    /// let tags: Vec<String> = model.face_tags().into_keys().collect();
+    /// assert_eq!(tags, vec!["glass", "window"]);
+    /// ```
+    pub fn face_tags(&self) -> BTreeMap<String, Vec<MeshFaceRef>> {
+        metadata::face_tags(self)
+    }
+
+    /// Calls `f` with every face of every mesh, in order of meshes and faces.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Model, ProjectPreset};
+    ///
+    /// let mut model = Model::new_project("house".to_string(), ProjectPreset::Plane);
+    /// model.for_each_face_mut(|face| face.color = Color::Red);
+    ///
+    /// assert!(model.meshes[0].faces.iter().all(|face| face.color == Color::Red));
+    /// ```
+    pub fn for_each_face_mut<F: FnMut(&mut Face)>(&mut self, mut f: F) {
+        for mesh in self.meshes.iter_mut() {
+            for face in mesh.faces.iter_mut() {
+                f(face);
+            }
+        }
+    }
+
+    /// Sets `flag` on every face of every mesh to `value`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{FaceFlag, Model, ProjectPreset};
+    ///
+    /// let mut model = Model::new_project("house".to_string(), ProjectPreset::Plane);
+    /// model.set_all(FaceFlag::NoShading, true);
+    ///
+    /// assert!(model.meshes[0].faces.iter().all(|face| face.no_shading));
+    /// ```
+    pub fn set_all(&mut self, flag: FaceFlag, value: bool) {
+        self.for_each_face_mut(|face| face.set_flag(flag, value));
+    }
+
+    /// Makes every face of every mesh render from both sides.
+    ///
+    /// Same as [`set_all`](Model::set_all) with [`FaceFlag::DoubleSided`].
+    pub fn make_all_double_sided(&mut self) {
+        self.set_all(FaceFlag::DoubleSided, true);
+    }
+
+    /// Removes the [`render_priority`](Face::render_priority) flag from every face of every mesh.
+    ///
+    /// Same as [`set_all`](Model::set_all) with [`FaceFlag::RenderPriority`].
+    pub fn clear_render_priority(&mut self) {
+        self.set_all(FaceFlag::RenderPriority, false);
+    }
+}
+
+#[cfg(feature = "std")]
+impl Model {
+    /// Loads a model from an absolute path.
+    ///
+    /// It's recommended to use [`load`](Model::load).
+    pub fn load_from_path(path: OsString) -> Result<Model, PicoError> {
+        let file_string = std::fs::read_to_string(path)?;
+
+        file_string.parse::<Model>()
+    }
+
+    /// Converts a [MagicaVoxel](https://ephtracy.github.io/) model into a model with a mesh for
+    /// each model in the file.
+    ///
+    /// Fails with [`PicoError::Vox`] if `bytes` aren't a valid `.vox` file.
+    /// More information can be found in the [`vox`](crate::vox) module.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use picocadrs::assets::Model;
+    /// use picocadrs::vox::VoxOptions;
+    ///
+    /// let bytes = std::fs::read("tree.vox").unwrap();
+    /// let model = Model::from_vox(&bytes, VoxOptions::default()).unwrap();
+    ///
+    /// println!("{}", model.stats().faces);
+    /// ```
+    #[cfg(feature = "vox")]
+    pub fn from_vox(bytes: &[u8], options: VoxOptions) -> Result<Model, PicoError> {
+        vox::from_vox(bytes, options)
+    }
+
+    /// Loads a model from a given file-name.
+    ///
+    /// Returns an error if the users home directory can't be found ([`PicoError::NoHomeDirectory`])
+    /// or if file doesn't exist [`PicoError::IO`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::ffi::OsString;
+    /// use picocadrs::assets::Model;
+    ///
+    /// // Requires a valid picoCAD project file in projects folder called "test.txt"
+    /// let model = Model::load(OsString::from("test.txt")).unwrap();
+    ///
+    /// assert_eq!(model.header.name, "test");
+    /// ```
+    pub fn load(file_name: OsString) -> Result<Model, PicoError> {
+        Model::load_with_paths(file_name, &Paths::default())
+    }
+
+    /// Loads a model from a given file-name inside the project folder picked by `paths`.
+    ///
+    /// Returns an error if no project folder can be found ([`PicoError::NoHomeDirectory`])
+    /// or if file doesn't exist [`PicoError::IO`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::ffi::OsString;
+    /// use picocadrs::assets::Model;
+    /// use picocadrs::paths::Paths;
+    ///
+    /// let paths = Paths::with_projects_dir("/media/usb/picocad");
+    /// let model = Model::load_with_paths(OsString::from("test"), &paths).unwrap();
+    /// ```
+    pub fn load_with_paths(file_name: OsString, paths: &Paths) -> Result<Model, PicoError> {
+        let mut file_name = file_name;
+        file_name.push(".txt");
+
+        let dir = paths.projects_dir().ok_or(PicoError::NoHomeDirectory)?;
+        Model::load_from_path(dir.join(file_name).into_os_string())
+    }
+
+    /// Writes the model to the project file named after the value in [`self.header.name`](Header).
+    ///
+    /// This means if that field contains the string `my_model` this will be written to
+    /// `{result from` [`projects_path`](crate::paths::projects_path)`}/my_model.txt`.
+    ///
+    /// Returns [`PicoError::ProjectName`] if the name contains a path separator or `..`, so the
+    /// file would end up outside of the project folder, and errors if files can't be written to.
+    ///
+    /// Contents of the file will be overwritten.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use picocadrs::assets::Model;
+    /// use std::ffi::OsString;
+    ///
+    /// let mut model = Model::default();
+    /// model.header.name = "model_write_example".to_string();
+    /// model.write().unwrap();
+    ///
+    /// let read_model = Model::load(OsString::from("model_write_example")).unwrap();
+    ///
+    /// assert_eq!(model, read_model);
+    /// ```
+    pub fn write(&self) -> Result<(), PicoError> {
+        self.write_with_paths(&Paths::default())
+    }
+
+    /// Writes the model to the project file named after the value in [`self.header.name`](Header)
+    /// inside the project folder picked by `paths`.
+    ///
+    /// Returns [`PicoError::ProjectName`] if the name contains a path separator or `..`,
+    /// [`PicoError::NoHomeDirectory`] if no project folder can be found and errors if files can't
+    /// be written to.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use picocadrs::assets::Model;
+    /// use picocadrs::paths::Paths;
+    ///
+    /// let paths = Paths::with_projects_dir("/media/usb/picocad");
+    /// Model::default().write_with_paths(&paths).unwrap();
+    /// ```
+    pub fn write_with_paths(&self, paths: &Paths) -> Result<(), PicoError> {
+        let path = self.project_file(paths)?;
+
+        let mut file = std::fs::File::create(path)?;
+        file.write_all(self.to_string().as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Writes the model to a file without risking a partially written file, for example when the
+    /// program crashes while writing.
+    ///
+    /// Where the model is written to and if a backup of the previous version is kept is decided
+    /// by `options`.
+    /// Returns the path of the backup if one was created.
+    /// More information can be found at [`files::write_safe`].
+    ///
+    /// If no path is given, returns [`PicoError::ProjectName`] if the name contains a path
+    /// separator or `..` and [`PicoError::NoHomeDirectory`] if the users home directory can't be
+    /// found.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use picocadrs::assets::Model;
+    /// use picocadrs::files::WriteOptions;
+    ///
+    /// let mut model = Model::default();
+    /// model.header.name = "safe".to_string();
+    ///
+    /// let backup = model
+    ///     .write_safe(WriteOptions {
+    ///         backup: true,
+    ///         ..WriteOptions::default()
+    ///     })
+    ///     .unwrap();
+    ///
+    /// if let Some(backup) = backup {
+    ///     println!("previous version kept at {}", backup.display());
+    /// }
+    /// ```
+    pub fn write_safe(&self, options: WriteOptions) -> Result<Option<PathBuf>, PicoError> {
+        let path = match options.path {
+            Some(path) => path,
+            None => self.project_file(&Paths::default())?,
+        };
+
+        files::write_safe(&path, self.to_string().as_bytes(), options.backup)
+    }
+
+    /// Returns the path of the project file named after the model inside the project folder
+    /// picked by `paths`.
+    ///
+    /// Returns [`PicoError::ProjectName`] if the name would end up outside of the folder and
+    /// [`PicoError::NoHomeDirectory`] if there is no project folder.
+    fn project_file(&self, paths: &Paths) -> Result<PathBuf, PicoError> {
+        files::check_name(&self.header.name)?;
+
+        let mut path = paths.projects_dir().ok_or(PicoError::NoHomeDirectory)?;
+        path.push(self.header.name.clone());
+        path.set_extension("txt");
+
+        Ok(path)
+    }
+
+    /// Exports every mesh of the model into its own Wavefront OBJ file inside of `dir`.
+    ///
+    /// All meshes share a single MTL file and the texture as a PNG file.
+    /// A `manifest.json` that maps each mesh name to its file and position is written as well,
+    /// so engine pipelines can import each mesh as an individual prop.
+    /// More information on the exported files can be found in the [`obj`](crate::export::obj)
+    /// module.
+    ///
+    /// Returns errors if files can't be written to.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use picocadrs::assets::Model;
+    /// use std::ffi::OsString;
+    ///
+    /// let model = Model::load(OsString::from("props")).unwrap();
+    /// model.export_obj_per_mesh("exports/props").unwrap();
+    /// ```
+    pub fn export_obj_per_mesh<P: AsRef<Path>>(&self, dir: P) -> Result<(), PicoError> {
+        obj::export_per_mesh(self, dir.as_ref())
+    }
+
+    /// Exports the model into the file at `path` in the format belonging to its extension, like
+    /// `png` for the texture or `svg` for a rendered view.
+    ///
+    /// More information on the available formats can be found in the
+    /// [`exporter`](crate::export::exporter) module.
+    ///
+    /// Returns [`PicoError::UnknownFormat`] if no exporter handles the extension of `path`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use picocadrs::assets::Model;
+    ///
+    /// Model::default().export("texture.png").unwrap();
+    /// ```
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> Result<(), PicoError> {
+        files::export(self, path)
+    }
+
+    /// Collects statistics of the model, like the amount of vertices and faces or how much of
+    /// the texture is used.
+    ///
+    /// More information can be found in the [`stats`](crate::analysis::stats) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model};
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(Mesh::new("empty".to_string()));
+    ///
+    /// let stats = model.stats();
+    ///
+    /// assert_eq!(stats.meshes, 1);
+    /// assert_eq!(stats.faces, 0);
+    /// ```
+    pub fn stats(&self) -> ModelStats {
+        ModelStats::from_model(self)
+    }
+
+    /// Returns every limit of the given profile the model goes past, an empty list if picoCAD
+    /// can comfortably handle it.
+    ///
+    /// More information can be found in the [`limits`](crate::analysis::limits) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::analysis::limits::Limits;
+    /// use picocadrs::assets::{Mesh, Model};
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes = vec![Mesh::plane(); 100];
+    ///
+    /// assert_eq!(model.check_limits(&Limits::V1).len(), 1);
+    ///
+    /// let generous = Limits {
+    ///     meshes: 128,
+    ///     ..Limits::V1
+    /// };
+    /// assert!(model.check_limits(&generous).is_empty());
+    /// ```
+    pub fn check_limits(&self, limits: &Limits) -> Vec<LimitViolation> {
+        limits.check(self)
+    }
+
+    /// Measures how many pixels of the texture each face gets for its size, flagging faces far
+    /// above or below the rest of the model.
+    ///
+    /// More information can be found in the [`density`](crate::analysis::density) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    ///
+    /// let model = Model::new_project("plane".to_string(), ProjectPreset::Plane);
+    /// let report = model.texel_density_report();
+    ///
+    /// assert_eq!(report.faces[0].density, 8.0);
+    /// assert!(report.flagged().is_empty());
+    /// ```
+    pub fn texel_density_report(&self) -> DensityReport {
+        DensityReport::from_model(self)
+    }
+
+    /// Returns a short overview of the model and its meshes, whose [`Display`] implementation
+    /// writes a readable report instead of the whole project file.
+    ///
+    /// More information can be found in the [`summary`](crate::analysis::summary) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model};
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(Mesh::new("empty".to_string()));
+    ///
+    /// println!("{}", model.summary());
+    ///
+    /// assert_eq!(model.summary().meshes[0].name, "empty");
+    /// ```
+    pub fn summary(&self) -> ModelSummary {
+        ModelSummary::from_model(self)
+    }
+
+    /// Compares the model to `other` and returns everything that changed, treating `self` as the
+    /// old and `other` as the new version.
+    ///
+    /// Coordinates that moved less than [`DEFAULT_TOLERANCE`](diff::DEFAULT_TOLERANCE) are
+    /// treated as unchanged.
+    /// More information can be found in the [`diff`](crate::diff) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Model};
+    ///
+    /// let old = Model::default();
+    /// let mut new = old.clone();
+    /// new.header.background = Color::Black;
+    ///
+    /// let diff = old.diff(&new);
+    ///
+    /// assert!(diff.header.is_some());
+    /// assert_eq!(diff.to_string(), "header: background 1 -> 0");
+    /// ```
+    pub fn diff(&self, other: &Model) -> ModelDiff {
+        diff::diff(self, other, diff::DEFAULT_TOLERANCE)
+    }
+
+    /// Merges the changes `ours` and `theirs` made to `base` into a new model.
+    ///
+    /// Returns every conflict found if both sides changed the same mesh, pixel or the header in
+    /// different ways.
+    /// More information can be found in the [`merge`](crate::merge) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Model, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let base = Model::default();
+    ///
+    /// let mut ours = base.clone();
+    /// ours.footer.set(point!(0, 0), Color::Red).unwrap();
+    ///
+    /// let mut theirs = base.clone();
+    /// theirs.footer.set(point!(0, 0), Color::Blue).unwrap();
+    ///
+    /// let conflicts = Model::merge3(&base, &ours, &theirs).unwrap_err();
+    /// assert_eq!(conflicts.len(), 1);
+    ///
+    /// theirs.footer.set(point!(0, 0), Color::Red).unwrap();
+    /// assert_eq!(Model::merge3(&base, &ours, &theirs), Ok(ours));
+    /// ```
+    pub fn merge3(base: &Model, ours: &Model, theirs: &Model) -> Result<Model, Vec<MergeConflict>> {
+        merge::merge3(base, ours, theirs)
+    }
+
+    /// Finds all pairs of textured faces whose uv-coordinates share at least one pixel of the
+    /// texture.
+    ///
+    /// Each pair is only reported once with the smaller reference first, pairs are sorted.
+    /// Faces that intentionally use the same part of the texture are reported as well.
+    /// More information can be found in the [`overlaps`](crate::analysis::overlaps) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh, MeshFaceRef, Model, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(0.0, 0.0)),
+    ///     UVMap::new(1, point!(1.0, 0.0)),
+    ///     UVMap::new(2, point!(1.0, 1.0)),
+    /// ];
+    ///
+    /// let mut mesh = Mesh::new("mesh".to_string());
+    /// mesh.faces = vec![face.clone(), face];
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(mesh);
+    ///
+    /// assert_eq!(
+    ///     model.uv_overlaps(),
+    ///     vec![(MeshFaceRef::new(0, 0), MeshFaceRef::new(0, 1))]
+    /// );
+    /// ```
+    pub fn uv_overlaps(&self) -> Vec<(MeshFaceRef, MeshFaceRef)> {
+        overlaps::uv_overlaps(self)
+    }
+
+    /// Maps each pixel of the texture to the textured faces sampling it.
+    ///
+    /// Useful for finding unused parts of the texture.
+    /// More information can be found in the [`usage`](crate::analysis::usage) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Face, Mesh, MeshFaceRef, Model, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(0.0, 0.0)),
+    ///     UVMap::new(1, point!(2.0, 0.0)),
+    ///     UVMap::new(2, point!(0.0, 2.0)),
+    /// ];
+    ///
+    /// let mut mesh = Mesh::new("mesh".to_string());
+    /// mesh.faces.push(face);
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(mesh);
+    ///
+    /// let usage = model.texture_usage_map();
+    ///
+    /// assert_eq!(usage.owner(point!(0, 0)), Some(MeshFaceRef::new(0, 0)));
+    /// assert!(!usage.is_used(point!(15, 15)));
+    /// ```
+    pub fn texture_usage_map(&self) -> TextureUsage {
+        TextureUsage::from_model(self)
+    }
+
+    /// Gives every mesh its own region of the texture, as big as the area it uses right now, and
+    /// moves its uv-coordinates and pixels there.
+    ///
+    /// Returns the assignment, so later changes can be checked against it with
+    /// [`TextureAssignment::violations`].
+    /// Returns [`PicoError::TextureFull`] and leaves the model untouched if the regions don't fit
+    /// on the texture.
+    /// More information can be found in the [`budget`](crate::budget) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model, Point2D, Rect};
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(Mesh::plane());
+    /// model.meshes.push(Mesh::plane());
+    ///
+    /// let assignment = model.assign_texture_regions().unwrap();
+    ///
+    /// assert_eq!(assignment.region(1), Some(Rect::new(point!(16, 0), point!(16, 16))));
+    /// assert_eq!(model.meshes[1].faces[0].uv_maps[0].coords, point!(2.0, 0.0));
+    /// ```
+    pub fn assign_texture_regions(&mut self) -> Result<TextureAssignment, PicoError> {
+        let assignment = TextureBudget::from_model(self).assign()?;
+        assignment.apply(self)?;
+
+        Ok(assignment)
+    }
+
+    /// Paints every pixel of the texture that isn't sampled by a textured face in the given color
+    /// and returns the amount of pixels painted.
+    ///
+    /// More information can be found in the [`atlas`](crate::atlas) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Model, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// model.footer.set(point!(3, 4), Color::Red).unwrap();
+    ///
+    /// assert_eq!(model.clear_unused_texture(Color::Black), 128 * 120);
+    /// assert!(model.footer.is_solid());
+    /// ```
+    pub fn clear_unused_texture(&mut self, color: Color) -> usize {
+        atlas::clear_unused_texture(self, color)
+    }
+
+    /// Turns every textured face whose pixels are at least `tolerance` percent the same color into
+    /// a face without texture in that color, and returns the amount of faces turned.
+    ///
+    /// The pixels those faces sampled can be reused afterwards.
+    /// More information can be found in the [`atlas`](crate::atlas) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Model, ProjectPreset};
+    ///
+    /// let mut model = Model::new_project("wall".to_string(), ProjectPreset::Plane);
+    /// model.footer.as_flat_mut_slice().fill(Color::Brown);
+    ///
+    /// assert_eq!(model.auto_notex(90.0), 1);
+    /// assert!(model.meshes[0].faces[0].no_texture);
+    /// assert_eq!(model.meshes[0].faces[0].color, Color::Brown);
+    /// assert_eq!(model.texture_usage_map().used_count(), 0);
+    /// ```
+    pub fn auto_notex(&mut self, tolerance: f64) -> usize {
+        atlas::auto_notex(self, tolerance)
+    }
+
+    /// Moves the uv islands of the model up and to the left as far as possible, so the unused
+    /// part of the texture ends up in one piece.
+    /// Returns the amount of islands moved.
+    ///
+    /// Pixels that aren't sampled by any face afterwards are black.
+    /// More information can be found in the [`atlas`](crate::atlas) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Face, Mesh, Model, Point2D, UVMap};
+    /// use picocadrs::point;
+    ///
+    /// let mut face = Face::default();
+    /// face.uv_maps = vec![
+    ///     UVMap::new(0, point!(8.0, 8.0)),
+    ///     UVMap::new(1, point!(9.0, 8.0)),
+    ///     UVMap::new(2, point!(9.0, 9.0)),
+    /// ];
+    ///
+    /// let mut mesh = Mesh::new("mesh".to_string());
+    /// mesh.faces.push(face);
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(mesh);
+    /// model.footer.set(point!(71, 64), Color::Red).unwrap();
+    ///
+    /// assert_eq!(model.compact_texture(), 1);
+    /// assert_eq!(model.meshes[0].faces[0].uv_maps[0].coords, point!(0.0, 0.0));
+    /// assert_eq!(model.footer.pixel(point!(7, 0)), Color::Red);
+    /// ```
+    pub fn compact_texture(&mut self) -> usize {
+        atlas::compact_texture(self)
+    }
+
+    /// Builds a [`Bvh`] over the faces of the model, for running many ray casts or other
+    /// queries against it.
+    ///
+    /// More information can be found in the [`spatial`](crate::spatial) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, Point3D, ProjectPreset};
+    /// use picocadrs::point;
+    ///
+    /// let model = Model::new_project("pick".to_string(), ProjectPreset::Plane);
+    /// let bvh = model.bvh();
+    ///
+    /// assert_eq!(bvh.len(), 1);
+    /// assert!(bvh
+    ///     .raycast(&model, point!(0.0, -1.0, 0.0), point!(0.0, 1.0, 0.0))
+    ///     .is_some());
+    /// ```
+    pub fn bvh(&self) -> Bvh<MeshFaceRef> {
+        Bvh::from_model(self)
+    }
+
+    /// Returns the closest face hit by a ray starting at `origin` and travelling in `direction`,
+    /// both given in world space.
+    ///
+    /// More information can be found in the [`raycast`] module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{MeshFaceRef, Model, Point2D, Point3D, ProjectPreset};
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::new_project("pick".to_string(), ProjectPreset::Plane);
+    /// model.meshes[0].position = point!(0.0, 1.0, 0.0);
+    ///
+    /// let hit = model
+    ///     .raycast(point!(0.5, -1.0, 0.5), point!(0.0, 1.0, 0.0))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(hit.face, MeshFaceRef::new(0, 0));
+    /// assert_eq!(hit.hit.distance, 2.0);
+    /// assert_eq!(hit.hit.point, point!(0.5, 1.0, 0.5));
+    /// assert_eq!(hit.hit.uv, point!(1.5, 0.5));
+    /// ```
+    pub fn raycast(&self, origin: Point3D<f64>, direction: Point3D<f64>) -> Option<RayHit> {
+        raycast::raycast(self, origin, direction)
+    }
+
+    /// Returns every face that is visible from none of the `cameras`, sorted by mesh and face
+    /// index.
+    ///
+    /// More information can be found in the [`visibility`] module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    /// use picocadrs::camera::CameraParams;
+    ///
+    /// let model = Model::new_project("plane".to_string(), ProjectPreset::Plane);
+    ///
+    /// assert!(model.hidden_faces(&[CameraParams::default()]).is_empty());
+    /// assert_eq!(model.hidden_faces(&[]).len(), 1);
+    /// ```
+    pub fn hidden_faces(&self, cameras: &[CameraParams]) -> Vec<MeshFaceRef> {
+        visibility::hidden_faces(self, cameras)
+    }
+
+    /// Removes every face that is visible from none of the `cameras` and returns the amount of
+    /// removed faces.
+    ///
+    /// More information can be found in the [`visibility`] module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    /// use picocadrs::camera::CameraParams;
+    ///
+    /// let mut model = Model::new_project("plane".to_string(), ProjectPreset::Plane);
+    ///
+    /// assert_eq!(model.remove_hidden_faces(&[CameraParams::default()]), 0);
+    /// assert_eq!(model.meshes[0].faces.len(), 1);
+    /// ```
+    pub fn remove_hidden_faces(&mut self, cameras: &[CameraParams]) -> usize {
+        visibility::remove_hidden_faces(self, cameras)
+    }
+
+    /// Cuts the mesh at `mesh_index` out of the model together with the part of the texture it
+    /// uses, so it can be added to other models.
+    ///
+    /// Returns [`None`] if there is no mesh at that index.
+    /// More information can be found in the [`library`] module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    ///
+    /// let model = Model::new_project("props".to_string(), ProjectPreset::Plane);
+    /// let asset = model.extract_asset(0).unwrap();
+    ///
+    /// assert_eq!(asset.name, "plane");
+    /// assert_eq!(asset.source, "props");
+    /// assert!(model.extract_asset(1).is_none());
+    /// ```
+    pub fn extract_asset(&self, mesh_index: usize) -> Option<Asset> {
+        library::extract(self, mesh_index)
+    }
+
+    /// Adds the mesh of `asset` to the model and returns its index.
+    ///
+    /// The pixels of the asset are copied into free space of the texture and the uv-coordinates
+    /// of the mesh are moved along.
+    /// Returns [`PicoError::TextureFull`] if there is not enough free space.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    ///
+    /// let props = Model::new_project("props".to_string(), ProjectPreset::Plane);
+    /// let plane = props.extract_asset(0).unwrap();
+    ///
+    /// let mut model = Model::new_project("scene".to_string(), ProjectPreset::Plane);
+    /// let index = model.insert_asset(&plane).unwrap();
+    ///
+    /// assert_eq!(index, 1);
+    /// assert_ne!(model.meshes[0].faces[0].uv_maps, model.meshes[1].faces[0].uv_maps);
+    /// ```
+    pub fn insert_asset(&mut self, asset: &Asset) -> Result<usize, PicoError> {
+        library::instantiate(self, asset)
+    }
+
+    /// Copies the first mesh called `mesh_name` of `other` into this model together with the
+    /// pixels of the texture it uses and returns its index.
+    ///
+    /// The pixels are placed into free space of the texture and the uv-coordinates of the copied
+    /// mesh are rewritten to match.
+    /// Returns [`PicoError::MeshNotFound`] if `other` has no such mesh and
+    /// [`PicoError::TextureFull`] if there is not enough free space.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, Point3D, ProjectPreset};
+    /// use picocadrs::library::ImportOptions;
+    /// use picocadrs::point;
+    ///
+    /// let props = Model::new_project("props".to_string(), ProjectPreset::Plane);
+    /// let mut model = Model::new_named("scene".to_string());
+    ///
+    /// let options = ImportOptions {
+    ///     name: Some("floor".to_string()),
+    ///     position: Some(point!(0.0, 1.0, 0.0)),
+    ///     ..ImportOptions::default()
+    /// };
+    /// let index = model.import_mesh_from(&props, "plane", options).unwrap();
+    ///
+    /// assert_eq!(model.meshes[index].name, "floor");
+    /// assert!(model
+    ///     .import_mesh_from(&props, "tree", ImportOptions::default())
+    ///     .is_err());
+    /// ```
+    pub fn import_mesh_from(
+        &mut self,
+        other: &Model,
+        mesh_name: &str,
+        options: ImportOptions,
+    ) -> Result<usize, PicoError> {
+        library::import_mesh(self, other, mesh_name, options)
+    }
+
+    /// Adds `count` copies of the first mesh called `mesh_name` in a line, each one `offset` away
+    /// from the one before, and returns their indices.
+    ///
+    /// Returns [`PicoError::MeshNotFound`] if there is no such mesh and
+    /// [`PicoError::TextureFull`] if the texture is duplicated and there is not enough free space.
+    /// More information can be found in the [`duplicate`](crate::duplicate) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, Point3D, ProjectPreset};
+    /// use picocadrs::duplicate::DuplicateOptions;
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::new_project("fence".to_string(), ProjectPreset::Plane);
+    ///
+    /// model
+    ///     .array_duplicate("plane", 4, point!(0.0, 0.0, 2.0), DuplicateOptions::default())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(model.meshes.len(), 5);
+    /// assert_eq!(model.meshes[4].position, point!(0.0, 0.0, 8.0));
+    /// ```
+    pub fn array_duplicate(
+        &mut self,
+        mesh_name: &str,
+        count: usize,
+        offset: Point3D<f64>,
+        options: DuplicateOptions,
+    ) -> Result<Vec<usize>, PicoError> {
+        duplicate::array_duplicate(self, mesh_name, count, offset, options)
+    }
+
+    /// Places the first mesh called `mesh_name` and `count - 1` copies of it evenly on a circle
+    /// of `radius` around its position and `axis`, and returns the indices of the copies.
+    ///
+    /// Returns [`PicoError::MeshNotFound`] if there is no such mesh and
+    /// [`PicoError::TextureFull`] if the texture is duplicated and there is not enough free space.
+    /// More information can be found in the [`duplicate`](crate::duplicate) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Axis, Model, Point3D, ProjectPreset};
+    /// use picocadrs::duplicate::DuplicateOptions;
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::new_project("pillars".to_string(), ProjectPreset::Plane);
+    ///
+    /// model
+    ///     .radial_duplicate("plane", 2, Axis::Y, 3.0, DuplicateOptions::default())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(model.meshes[0].position, point!(0.0, 0.0, 3.0));
+    /// assert_eq!(model.meshes[1].position.z.round(), -3.0);
+    /// ```
+    pub fn radial_duplicate(
+        &mut self,
+        mesh_name: &str,
+        count: usize,
+        axis: Axis,
+        radius: f64,
+        options: DuplicateOptions,
+    ) -> Result<Vec<usize>, PicoError> {
+        duplicate::radial_duplicate(self, mesh_name, count, axis, radius, options)
+    }
+
+    /// Shrinks the size of the model when written to a file and reports how much was saved.
+    ///
+    /// Which changes are made is decided by `options`.
+    /// More information can be found in the [`optimize`](crate::optimize) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model};
+    /// use picocadrs::optimize::OptimizeOptions;
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(Mesh::new("cube".to_string()));
+    ///
+    /// let report = model.optimize(OptimizeOptions::default());
+    ///
+    /// assert_eq!(model.meshes[0].name, "a");
+    /// assert_eq!(report.renamed_meshes, 1);
+    /// assert_eq!(report.bytes_saved(), 3);
+    /// ```
+    pub fn optimize(&mut self, options: OptimizeOptions) -> OptimizeReport {
+        optimize::optimize(self, options)
+    }
+
+    /// Brings the model into a canonical form, so the written file diffs cleanly when shared on
+    /// forums or in version control.
+    ///
+    /// Resets the zoom level, trims names, rounds coordinates and sorts meshes by name.
+    /// More information can be found at [`optimize::normalize_for_sharing`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model};
+    ///
+    /// let mut model = Model::default();
+    /// model.header.zoom = 4;
+    /// model.meshes.push(Mesh::new("wheel ".to_string()));
+    /// model.meshes.push(Mesh::new("body".to_string()));
+    ///
+    /// model.normalize_for_sharing().unwrap();
+    ///
+    /// assert_eq!(model.header.zoom, 16);
+    /// assert_eq!(model.meshes[0].name, "body");
+    /// assert_eq!(model.meshes[1].name, "wheel");
+    /// ```
+    pub fn normalize_for_sharing(&mut self) -> Result<(), PicoError> {
+        optimize::normalize_for_sharing(self)
+    }
+
+    /// Rounds the position and vertices of every mesh to the nearest multiple of `step` on each
+    /// axis.
+    ///
+    /// See [`Mesh::snap_to_grid`] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model, Point3D};
+    /// use picocadrs::assets::mesh::GRID_STEP;
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// let mut mesh = Mesh::new("imported".to_string());
+    /// mesh.position = point!(1.0000002, 0.0, -0.26);
+    /// mesh.vertices.push(point!(0.1, 0.2, 0.3));
+    /// model.meshes.push(mesh);
+    ///
+    /// model.snap_to_grid(GRID_STEP);
+    ///
+    /// assert_eq!(model.meshes[0].position, point!(1.0, 0.0, -0.25));
+    /// assert_eq!(model.meshes[0].vertices[0], point!(0.0, 0.25, 0.25));
+    /// ```
+    pub fn snap_to_grid(&mut self, step: f64) {
+        for mesh in self.meshes.iter_mut() {
+            mesh.position.x = snap(mesh.position.x, step);
+            mesh.position.y = snap(mesh.position.y, step);
+            mesh.position.z = snap(mesh.position.z, step);
+            mesh.snap_to_grid(step);
+        }
+    }
+
+    /// Rounds the uv-coordinates of every face to the nearest multiple of `step`.
     ///
-    /// It's recommended to use [`load`](Model::load).
-    pub fn load_from_path(path: OsString) -> Result<Model, PicoError> {
-        let file_string = std::fs::read_to_string(path)?;
+    /// See [`Mesh::snap_uvs_to_grid`] for more information.
+    pub fn snap_uvs_to_grid(&mut self, step: f64) {
+        for mesh in self.meshes.iter_mut() {
+            mesh.snap_uvs_to_grid(step);
+        }
+    }
 
-        file_string.parse::<Model>()
+    /// Draws the texture scaled up by `scale` with the uv-map of every face outlined and labeled
+    /// on top of it, to be used as a guide when painting textures in other programs.
+    ///
+    /// More information can be found at [`texture_template`](image::texture_template).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    ///
+    /// let model = Model::new_project("plane".to_string(), ProjectPreset::Plane);
+    /// let image = model.texture_template_image(4);
+    ///
+    /// assert_eq!(image.width, 128 * 4);
+    /// assert_eq!(image.pixels.len(), 128 * 4 * 120 * 4);
+    /// ```
+    pub fn texture_template_image(&self, scale: u32) -> TemplateImage {
+        image::texture_template(self, scale)
     }
 
-    /// Loads a model from a given file-name.
+    /// Renders the model as an SVG document.
     ///
-    /// Returns an error if the users home directory can't be found ([`PicoError::NoHomeDirectory`])
-    /// or if file doesn't exist [`PicoError::IO`].
+    /// More information can be found in the [`svg`](crate::export::svg) module.
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// use std::ffi::OsString;
+    /// ```
     /// use picocadrs::assets::Model;
+    /// use picocadrs::export::svg::SvgOptions;
     ///
-    /// // Requires a valid picoCAD project file in projects folder called "test.txt"
-    /// let model = Model::load(OsString::from("test.txt")).unwrap();
+    /// let svg = Model::default().render_svg(&SvgOptions::default());
     ///
-    /// assert_eq!(model.header.name, "test");
+    /// assert!(svg.starts_with("<svg"));
     /// ```
-    pub fn load(file_name: OsString) -> Result<Model, PicoError> {
-        if let Some(mut projects_path) = projects_path() {
-            projects_path.push(file_name);
-            projects_path.push(".txt");
-            Model::load_from_path(projects_path)
-        } else {
-            Err(PicoError::NoHomeDirectory)
-        }
+    #[cfg(feature = "svg")]
+    pub fn render_svg(&self, options: &svg::SvgOptions) -> String {
+        svg::render_svg(self, options)
     }
 
-    /// Writes the model to the project file named after the value in [`self.header.name`](Header).
+    /// Renders the edges of every face as an SVG document, seen through `camera`.
     ///
-    /// This means if that field contains the string `my_model` this will be written to
-    /// `{result from` [`projects_path`]`}/my_model.txt`.
+    /// More information can be found in the [`svg`](crate::export::svg) module.
     ///
-    /// Returns errors if files can't be written to.
+    /// # Example
     ///
-    /// Contents of the file will be overwritten.
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    /// use picocadrs::camera::CameraParams;
+    ///
+    /// let model = Model::new_project("plane".to_string(), ProjectPreset::Plane);
+    /// let svg = model.wireframe_svg(&CameraParams::default());
+    ///
+    /// assert_eq!(svg.matches("<line").count(), 4);
+    /// ```
+    #[cfg(feature = "svg")]
+    pub fn wireframe_svg(&self, camera: &CameraParams) -> String {
+        svg::wireframe_svg(self, camera)
+    }
+
+    /// Renders the uv-map of every face on top of a grid the size of the texture as an SVG
+    /// document, to be used as a guide when painting textures in other programs.
+    ///
+    /// More information can be found in the [`svg`](crate::export::svg) module.
     ///
     /// # Example
     ///
-    /// ```no_run
-    /// use picocadrs::assets::Model;
-    /// use std::ffi::OsString;
+    /// ```
+    /// use picocadrs::assets::{Model, ProjectPreset};
+    ///
+    /// let model = Model::new_project("plane".to_string(), ProjectPreset::Plane);
+    /// let svg = model.uv_layout_svg();
+    ///
+    /// assert_eq!(svg.matches("<polygon").count(), 1);
+    /// assert!(svg.contains(">plane 0</text>"));
+    /// ```
+    #[cfg(feature = "svg")]
+    pub fn uv_layout_svg(&self) -> String {
+        svg::uv_layout_svg(self)
+    }
+
+    /// Returns the outlines where the plane cuts through the faces of the model.
+    ///
+    /// More information can be found in the [`slice`](crate::export::slice) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Axis, Model, ProjectPreset};
+    /// use picocadrs::geometry::split::Plane;
+    ///
+    /// let model = Model::new_project("plane".to_string(), ProjectPreset::Plane);
+    ///
+    /// assert!(model.slice(Plane::axis(Axis::X, 100.0)).is_empty());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn slice(&self, plane: Plane) -> Vec<Polyline> {
+        slice::slice(self, plane)
+    }
+
+    /// Selects every mesh of the model, so they can be narrowed down and edited in bulk.
+    ///
+    /// More information can be found in the [`select`](crate::select) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Face, Mesh, Model};
     ///
     /// let mut model = Model::default();
-    /// model.header.name = "model_write_example".to_string();
-    /// model.write().unwrap();
+    /// let mut mesh = Mesh::new("cube".to_string());
+    /// mesh.faces.push(Face::default());
+    /// model.meshes.push(mesh);
     ///
-    /// let read_model = Model::load(OsString::from("model_write_example")).unwrap();
+    /// for face in model.select().faces().double_sided(false) {
+    ///     face.color = Color::Pink;
+    /// }
     ///
-    /// assert_eq!(model, read_model);
+    /// assert_eq!(model.meshes[0].faces[0].color, Color::Pink);
     /// ```
-    pub fn write(&self) -> Result<(), PicoError> {
-        let mut path = PathBuf::from(projects_path().unwrap());
-        path.push(self.header.name.clone());
-        path.set_extension("txt");
+    pub fn select(&mut self) -> MeshSelection<'_> {
+        MeshSelection::new(self)
+    }
 
-        let mut file = std::fs::File::create(path)?;
-        file.write_all(self.to_string().as_bytes())?;
+    /// Replaces the colors of faces, the header and the texture according to `mapping` and reports
+    /// how many places of each color changed.
+    ///
+    /// Colors missing from `mapping` are left untouched.
+    /// More information can be found in the [`recolor`](crate::recolor) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use picocadrs::assets::{Color, Face, Mesh, Model};
+    ///
+    /// let mut mesh = Mesh::new("enemy".to_string());
+    /// mesh.faces.push(Face {
+    ///     color: Color::Red,
+    ///     ..Face::default()
+    /// });
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(mesh);
+    ///
+    /// let report = model.remap_colors(&HashMap::from([(Color::Red, Color::Blue)]));
+    ///
+    /// assert_eq!(report.total(Color::Red), 1);
+    /// assert_eq!(model.meshes[0].faces[0].color, Color::Blue);
+    /// ```
+    pub fn remap_colors(&mut self, mapping: &HashMap<Color, Color>) -> RemapReport {
+        recolor::remap_colors(self, mapping)
+    }
 
-        Ok(())
+    /// Reports what [`remap_colors`](Model::remap_colors) would change without changing the
+    /// model.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use picocadrs::assets::{Color, Model};
+    ///
+    /// let model = Model::default();
+    /// let report = model.remap_colors_dry_run(&HashMap::from([(Color::DarkBlue, Color::Black)]));
+    ///
+    /// assert_eq!(report.header[&Color::DarkBlue], 1);
+    /// assert_eq!(model.header.background, Color::DarkBlue);
+    /// ```
+    pub fn remap_colors_dry_run(&self, mapping: &HashMap<Color, Color>) -> RemapReport {
+        recolor::count_remap(self, mapping)
+    }
+
+    /// Darkens the color of every face as if lit by light travelling in the direction `light_dir`
+    /// and returns the amount of faces whose color changed.
+    ///
+    /// Shaded faces are marked with [`no_shading`](crate::assets::Face::no_shading), so picoCAD
+    /// doesn't shade them again.
+    /// More information can be found in the [`shading`](crate::shading) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Model, Point3D, ProjectPreset};
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::new_project("shaded".to_string(), ProjectPreset::Plane);
+    ///
+    /// assert_eq!(model.bake_shading(point!(1.0, 0.0, 0.0)), 1);
+    /// assert_eq!(model.meshes[0].faces[0].color, Color::DarkGrey);
+    /// assert!(model.meshes[0].faces[0].no_shading);
+    /// ```
+    pub fn bake_shading(&mut self, light_dir: Point3D<f64>) -> usize {
+        shading::bake_shading(self, light_dir)
+    }
+
+    /// Replaces every mesh with one mesh for each of its parts that aren't connected to each
+    /// other and returns the amount of meshes that were added.
+    ///
+    /// More information can be found in the [`components`](crate::geometry::components) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model};
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(Mesh::plane());
+    ///
+    /// assert_eq!(model.explode_components(), 0);
+    /// assert_eq!(model.meshes[0], Mesh::plane());
+    /// ```
+    pub fn explode_components(&mut self) -> usize {
+        components::explode_components(self)
+    }
+
+    /// Joins the meshes at `indices` into a single mesh called `name`, which takes the place of
+    /// the first of them, and returns its index.
+    ///
+    /// More information can be found in the [`components`](crate::geometry::components) module.
+    ///
+    /// Returns [`PicoError::MeshIndex`] if any index is out of range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model};
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes = vec![Mesh::plane(), Mesh::plane(), Mesh::plane()];
+    ///
+    /// assert_eq!(model.join_meshes(&[2, 0], "floor".to_string()).unwrap(), 0);
+    /// assert_eq!(model.meshes.len(), 2);
+    /// assert_eq!(model.meshes[0].faces.len(), 2);
+    /// assert!(model.join_meshes(&[5], "none".to_string()).is_err());
+    /// ```
+    pub fn join_meshes(&mut self, indices: &[usize], name: String) -> Result<usize, PicoError> {
+        components::join_meshes(self, indices, name)
+    }
+
+    /// Replaces every mesh with a single mesh called `name`, moving the vertices by the
+    /// positions of their meshes and merging vertices at the same place.
+    /// Returns the amount of vertices that were merged.
+    ///
+    /// More information can be found in the [`components`](crate::geometry::components) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes = vec![Mesh::plane(), Mesh::plane()];
+    /// model.meshes[1].position = point!(0.0, -1.0, 0.0);
+    ///
+    /// assert_eq!(model.flatten_to_single_mesh("planes".to_string()), 0);
+    /// assert_eq!(model.meshes.len(), 1);
+    /// assert_eq!(model.meshes[0].vertices[4], point!(-1.0, -1.0, -1.0));
+    /// ```
+    pub fn flatten_to_single_mesh(&mut self, name: String) -> usize {
+        components::flatten(self, name)
+    }
+
+    /// Moves every mesh by the same amount so the bounding box of the whole model is aligned,
+    /// and returns how far they were moved.
+    ///
+    /// More information can be found in the [`align`](crate::align) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::align::Alignment;
+    /// use picocadrs::assets::{Mesh, Model, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes = vec![Mesh::plane(), Mesh::plane()];
+    /// model.meshes[1].position = point!(0.0, -2.0, 0.0);
+    ///
+    /// model.align(Alignment::CenterY);
+    ///
+    /// assert_eq!(model.meshes[0].position, point!(0.0, 1.0, 0.0));
+    /// assert_eq!(model.meshes[1].position, point!(0.0, -1.0, 0.0));
+    /// ```
+    pub fn align(&mut self, alignment: Alignment) -> Point3D<f64> {
+        align::align_model(self, alignment)
+    }
+
+    /// Scales the whole model uniformly so it fits into a cube with edges `size` units long
+    /// centered on the origin, and returns the factor it was scaled by.
+    ///
+    /// More information can be found in the [`align`](crate::align) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes = vec![Mesh::plane()];
+    /// model.meshes[0].position = point!(3.0, 0.0, 3.0);
+    ///
+    /// assert_eq!(model.fit_into_box(1.0), 0.5);
+    /// assert_eq!(model.meshes[0].position, point!(0.0, 0.0, 0.0));
+    /// assert_eq!(model.meshes[0].vertices[0], point!(-0.5, 0.0, -0.5));
+    /// ```
+    pub fn fit_into_box(&mut self, size: f64) -> f64 {
+        align::fit_into_box(self, size)
+    }
+
+    /// Gives every mesh the same rotation, so picoCAD shades them all with light from the same
+    /// direction.
+    ///
+    /// More information can be found in the [`shading`](crate::shading) module.
+    ///
+    /// Returns [`PicoError::MeshNotFound`] if the reference is a mesh that doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model, Point3D, Rotation};
+    /// use picocadrs::point;
+    /// use picocadrs::shading::LightingReference;
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes = vec![Mesh::plane(), Mesh::plane()];
+    /// model.meshes[1].rotation = Rotation(point!(0.0, 0.25, 0.0));
+    ///
+    /// let report = model
+    ///     .unify_lighting(&LightingReference::Mesh("plane".to_string()))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(report.changed, vec![1]);
+    /// assert_eq!(model.meshes[1].rotation, model.meshes[0].rotation);
+    /// ```
+    pub fn unify_lighting(
+        &mut self,
+        reference: &LightingReference,
+    ) -> Result<LightingReport, PicoError> {
+        shading::unify_lighting(self, reference)
+    }
+
+    /// Darkens the texture or the face colors of the model where light is blocked by nearby faces
+    /// and returns the amount of pixels or faces that changed.
+    ///
+    /// Each point is tested with `samples` rays.
+    /// More information can be found in the [`occlusion`](crate::occlusion) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Mesh, Model, Point2D, Point3D};
+    /// use picocadrs::occlusion::AoOptions;
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// model.header.alpha = Color::Invalid;
+    /// model.footer.set(point!(4, 4), Color::White).unwrap();
+    /// model.meshes.push(Mesh::plane());
+    /// model.meshes.push(Mesh::plane());
+    /// model.meshes[1].position = point!(0.0, -0.25, 0.0);
+    /// model.meshes[1].faces[0].no_texture = true;
+    ///
+    /// model.bake_ao(16, AoOptions::default());
+    ///
+    /// assert_eq!(model.footer.pixel(point!(4, 4)), Color::White.shadow());
+    /// ```
+    pub fn bake_ao(&mut self, samples: usize, options: AoOptions) -> usize {
+        occlusion::bake_ao(self, samples, options)
+    }
+
+    /// Returns a copy of the texture where the pixels of each face are darkened as if lit by light
+    /// travelling in the direction `light_dir`.
+    ///
+    /// Useful to render textured faces the way picoCAD would show them.
+    /// More information can be found in the [`shading`](crate::shading) module.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Model, Point2D, Point3D, ProjectPreset};
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::new_project("shaded".to_string(), ProjectPreset::Plane);
+    /// model.header.alpha = Color::Invalid;
+    /// model.footer.set(point!(0, 0), Color::White).unwrap();
+    ///
+    /// let footer = model.shaded_texture(point!(1.0, 0.0, 0.0));
+    ///
+    /// assert_eq!(footer.pixel(point!(0, 0)), Color::Lavender);
+    /// ```
+    pub fn shaded_texture(&self, light_dir: Point3D<f64>) -> Footer {
+        shading::shaded_texture(self, light_dir)
+    }
+
+    /// Mirrors the whole model along the given axis around the origin.
+    ///
+    /// Every mesh is [`mirror`](Mesh::mirror)ed and its position is mirrored as well, so meshes
+    /// keep their place relative to each other.
+    /// If `mirror_uvs` is true the uv-coordinates of every face are mirrored too.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Axis, Mesh, Model, Point3D};
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// let mut mesh = Mesh::new("wing".to_string());
+    /// mesh.position = point!(2.0, 0.0, 0.0);
+    /// mesh.vertices.push(point!(1.0, 0.0, 0.0));
+    /// model.meshes.push(mesh);
+    ///
+    /// model.mirror(Axis::X, false);
+    ///
+    /// assert_eq!(model.meshes[0].position, point!(-2.0, 0.0, 0.0));
+    /// assert_eq!(model.meshes[0].vertices[0], point!(-1.0, 0.0, 0.0));
+    /// ```
+    pub fn mirror(&mut self, axis: Axis, mirror_uvs: bool) {
+        for mesh in self.meshes.iter_mut() {
+            *mesh.position.axis_mut(axis) = -mesh.position.axis(axis);
+            mesh.mirror(axis, mirror_uvs);
+        }
     }
 }
 
@@ -148,7 +1760,7 @@ impl Default for Model {
 }
 
 impl Display for Model {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let mut meshes = String::new();
 
         for mesh in self.meshes.iter() {
@@ -175,39 +1787,14 @@ impl FromStr for Model {
         let header: Header = header_str.parse()?;
         let footer: Footer = footer_str.parse()?;
 
-        let mut meshes: Vec<Mesh> = vec![];
-        let mut lua_result: Result<(), PicoError> = Ok(());
-
-        // We would be fucked without '?' LUL
-        let lua = Lua::new();
-        lua.context(|ctx| match ctx.load(meshes_str).eval::<Table>() {
-            Ok(meshes_table) => {
-                for mesh_table_result in meshes_table.sequence_values::<Table>() {
-                    match mesh_table_result {
-                        Ok(mesh_table) => {
-                            let mesh_result = Mesh::try_from(mesh_table);
-
-                            match mesh_result {
-                                Ok(mesh) => meshes.push(mesh),
-                                Err(parse_error) => {
-                                    lua_result = Err(parse_error);
-                                    return;
-                                }
-                            }
-                        }
-                        Err(lua_err) => {
-                            lua_result = Err(PicoError::from(lua_err));
-                            return;
-                        }
-                    }
-                }
-            }
-            Err(lua_err) => {
-                lua_result = Err(PicoError::from(lua_err));
-            }
-        });
-
-        lua_result?;
+        let meshes = crate::lua_table::parse_table(meshes_str)?
+            .sequence
+            .iter()
+            .map(|mesh| match mesh {
+                crate::lua_table::LuaValue::Table(table) => Mesh::from_lua_table(table),
+                _ => Err(PicoError::MeshTable),
+            })
+            .collect::<Result<Vec<Mesh>, PicoError>>()?;
 
         Ok(Model {
             header,
@@ -242,7 +1829,10 @@ fn seperate_model(model: &str) -> Result<(&str, &str, &str), PicoError> {
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::assets::Point3D;
+    use crate::lua_table::LuaValue;
     use crate::paths::projects_path;
+    use crate::point;
 
     #[test]
     fn test_model_parse() {
@@ -254,6 +1844,40 @@ pub mod tests {
         assert_eq!(TEST_FILE, TEST_FILE.parse::<Model>().unwrap().to_string())
     }
 
+    #[test]
+    fn test_model_display_numbers() {
+        // numbers at the precision and range picoCAD writes, plus extras it doesn't know about.
+        let file = format!(
+            "picocad;numbers;16;1;0
+{{
+{{
+ name='cube', pos={{-1.5,0.0625,2}}, rot={{0,-0.1667,0.0833}}, big=1000000.0, small=0.0000001,
+ v={{
+  {{-0.3333,0.6667,-1.125}},
+  {{32767.9999,-32768,0.0001}},
+  {{1,0,-1}}
+ }},
+ f={{
+  {{1,2,3, c=3, whole=2.0, uv={{0.1875,14.9375,-0.0625,0,1,1}} }}
+ }}
+}}
+}}%
+{}",
+            Footer::default()
+        );
+
+        let mut model = file.parse::<Model>().unwrap();
+        assert_eq!(model.to_string(), file);
+        assert_eq!(model.meshes[0].extras["big"], LuaValue::Number(1e6));
+        assert_eq!(
+            model.meshes[0].faces[0].extras["whole"],
+            LuaValue::Number(2.0)
+        );
+
+        model.meshes[0].position = point!(0.1 + 0.2, 1.0 / 3.0, -1e-9);
+        assert!(model.to_string().contains("pos={0.3,0.3333,0}"));
+    }
+
     #[test]
     fn test_model_default() {
         let model = Model::default();
@@ -263,14 +1887,92 @@ pub mod tests {
         assert!(model.meshes.is_empty());
     }
 
+    #[test]
+    fn test_model_new_project() {
+        let model = Model::new_project("new".to_string(), ProjectPreset::Plane);
+        let expected = format!(
+            "picocad;new;16;1;0
+{{
+{{
+ name='plane', pos={{0,0,0}}, rot={{0,0,0}},
+ v={{
+  {{-1,0,-1}},
+  {{1,0,-1}},
+  {{1,0,1}},
+  {{-1,0,1}}
+ }},
+ f={{
+  {{4,3,2,1, c=6, dbl=1, uv={{0,0,2,0,2,2,0,2}} }}
+ }}
+}}
+}}%
+{}",
+            Footer::default()
+        );
+
+        assert_eq!(model.to_string(), expected);
+        assert_eq!(model, expected.parse::<Model>().unwrap());
+        assert_eq!(
+            Model::new_project("new".to_string(), ProjectPreset::Empty),
+            Model::new_named("new".to_string())
+        );
+    }
+
+    #[test]
+    fn test_model_mirror() {
+        let original = TEST_FILE.parse::<Model>().unwrap();
+        let mut model = original.clone();
+
+        model.mirror(Axis::Z, false);
+        assert_eq!(model.meshes[0].position, point!(0.0, 0.0, -1.0));
+        assert_eq!(model.meshes[0].vertices[0], point!(-1.0, 0.0, 1.0));
+
+        model.mirror(Axis::Z, false);
+        assert_eq!(model, original);
+    }
+
+    #[test]
+    fn test_model_face_flags() {
+        let mut model = TEST_FILE.parse::<Model>().unwrap();
+        model.meshes[0].faces[0].render_priority = true;
+
+        model.make_all_double_sided();
+        model.clear_render_priority();
+        model.set_all(FaceFlag::NoTexture, true);
+
+        for face in model.meshes.iter().flat_map(|mesh| mesh.faces.iter()) {
+            assert!(face.double_sided && face.no_texture);
+            assert!(!face.render_priority);
+        }
+
+        let mut count = 0;
+        model.for_each_face_mut(|face| {
+            face.set_flag(FaceFlag::DoubleSided, !face.flag(FaceFlag::DoubleSided));
+            count += 1;
+        });
+        assert_eq!(
+            count,
+            model
+                .meshes
+                .iter()
+                .map(|mesh| mesh.faces.len())
+                .sum::<usize>()
+        );
+        assert!(!model.meshes[0].faces[0].double_sided);
+    }
+
     /// Requires a file called `test3.txt` with the contents of [`TEST_FILE`]
     #[test]
     #[ignore]
     fn test_model_load() {
-        let mut path: OsString = projects_path().unwrap();
-        path.push("test3.txt");
+        let path = PathBuf::from(projects_path().unwrap()).join("test3.txt");
 
-        assert_eq!(TEST_FILE, Model::load_from_path(path).unwrap().to_string());
+        assert_eq!(
+            TEST_FILE,
+            Model::load_from_path(path.into_os_string())
+                .unwrap()
+                .to_string()
+        );
 
         assert_eq!(
             TEST_FILE,
@@ -278,6 +1980,27 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_model_write_name_outside() {
+        let dir = std::env::temp_dir().join(format!(
+            "picocadrs_model_write_name_outside_{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut model = Model::default();
+        model.header.name = "../escaped".to_string();
+
+        let written = model.write_with_paths(&Paths::with_projects_dir(&dir));
+        let safe = model.write_safe(WriteOptions::default());
+        let escaped = dir.join("../escaped.txt").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(written, Err(PicoError::ProjectName(name)) if name == "../escaped"));
+        assert!(matches!(safe, Err(PicoError::ProjectName(_))));
+        assert!(!escaped);
+    }
+
     #[test]
     #[ignore]
     fn test_model_write() {
@@ -0,0 +1,332 @@
+//! For turning arbitrary colors into colors picoCAD can display.
+//!
+//! A [`Palette`] is a set of [`Color`]s that other colors get mapped onto.
+//! By default it holds all 16 pico-8 colors, but it can be limited to fewer colors, for example
+//! to keep the alpha color out of an imported texture.
+//!
+//! Colors between two palette colors can be approximated by a [`DitherPattern`] of both, see
+//! [`Palette::closest_blend`].
+
+use crate::{
+    assets::{color::rgb_distance, Color, Footer, Point2D},
+    error::PicoError,
+    point,
+};
+
+/// Patterns two colors can be mixed in, returned by [`Palette::closest_blend`].
+///
+/// Every pattern repeats every 2 pixels in both directions.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum DitherPattern {
+    /// Only the first color.
+    #[default]
+    Solid,
+    /// One out of every 4 pixels uses the second color.
+    Quarter,
+    /// Both colors alternate like on a checkerboard.
+    Checker,
+}
+
+impl DitherPattern {
+    /// Returns the share of pixels using the second color.
+    pub fn fraction(&self) -> f64 {
+        match self {
+            DitherPattern::Solid => 0.0,
+            DitherPattern::Quarter => 0.25,
+            DitherPattern::Checker => 0.5,
+        }
+    }
+
+    /// Returns the color the pixel at `pixel` has when filling an area with `a` and `b` in this
+    /// pattern.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, DitherPattern, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let pattern = DitherPattern::Checker;
+    ///
+    /// assert_eq!(pattern.color_at(Color::Red, Color::Blue, point!(0, 0)), Color::Red);
+    /// assert_eq!(pattern.color_at(Color::Red, Color::Blue, point!(1, 0)), Color::Blue);
+    /// assert_eq!(pattern.color_at(Color::Red, Color::Blue, point!(1, 1)), Color::Red);
+    /// ```
+    pub fn color_at(&self, a: Color, b: Color, pixel: Point2D<usize>) -> Color {
+        let second = match self {
+            DitherPattern::Solid => false,
+            DitherPattern::Quarter => pixel.u % 2 == 1 && pixel.v % 2 == 1,
+            DitherPattern::Checker => (pixel.u + pixel.v) % 2 == 1,
+        };
+
+        if second {
+            b
+        } else {
+            a
+        }
+    }
+}
+
+/// A set of colors used for mapping arbitrary rgb values onto pico-8 colors.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Color, Palette};
+///
+/// let palette = Palette::new(vec![Color::Black, Color::White]);
+///
+/// assert_eq!(palette.nearest(200, 200, 200), Color::White);
+/// assert_eq!(palette.nearest(60, 60, 60), Color::Black);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Palette {
+    colors: Vec<Color>,
+}
+
+impl Palette {
+    /// Creates a palette holding the given colors.
+    ///
+    /// [`Invalid`](Color::Invalid) colors and duplicates are removed.
+    /// An empty palette maps every color to [`Black`](Color::Black).
+    pub fn new(colors: Vec<Color>) -> Palette {
+        let mut unique: Vec<Color> = vec![];
+
+        for color in colors {
+            if color != Color::Invalid && !unique.contains(&color) {
+                unique.push(color);
+            }
+        }
+
+        Palette { colors: unique }
+    }
+
+    /// Returns the colors of the palette.
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    /// Returns a copy of the palette without the given color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Palette};
+    ///
+    /// let palette = Palette::default().without(Color::Black);
+    ///
+    /// assert_eq!(palette.colors().len(), 15);
+    /// assert_eq!(palette.nearest(0, 0, 0), Color::DarkBlue);
+    /// ```
+    pub fn without(&self, color: Color) -> Palette {
+        Palette {
+            colors: self
+                .colors
+                .iter()
+                .copied()
+                .filter(|c| *c != color)
+                .collect(),
+        }
+    }
+
+    /// Returns the color of the palette that looks the closest to the given rgb values.
+    ///
+    /// Closeness is measured the same way as in [`Color::distance`].
+    pub fn nearest(&self, r: u8, g: u8, b: u8) -> Color {
+        self.colors
+            .iter()
+            .copied()
+            .min_by(|a, b_color| {
+                rgb_distance(a.as_rgb(), (r, g, b))
+                    .total_cmp(&rgb_distance(b_color.as_rgb(), (r, g, b)))
+            })
+            .unwrap_or(Color::Black)
+    }
+
+    /// Returns the two colors of the palette and the pattern to mix them in that look the closest
+    /// to the given rgb values from a distance.
+    ///
+    /// Mixes are compared the way they look, see [`Color::blend`], and closeness is measured the
+    /// same way as in [`Color::distance`].
+    /// The first color is always the one covering most of the pattern.
+    /// If a single color is the closest, it is returned twice with [`DitherPattern::Solid`].
+    /// An empty palette returns [`Black`](Color::Black) twice.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, DitherPattern, Palette};
+    ///
+    /// let palette = Palette::new(vec![Color::Black, Color::White]);
+    ///
+    /// assert_eq!(
+    ///     palette.closest_blend(185, 180, 175),
+    ///     (Color::Black, Color::White, DitherPattern::Checker)
+    /// );
+    /// assert_eq!(
+    ///     palette.closest_blend(255, 241, 232),
+    ///     (Color::White, Color::White, DitherPattern::Solid)
+    /// );
+    /// ```
+    pub fn closest_blend(&self, r: u8, g: u8, b: u8) -> (Color, Color, DitherPattern) {
+        let mut closest = (Color::Black, Color::Black, DitherPattern::Solid);
+        let mut closest_distance = f64::INFINITY;
+
+        for first in self.colors.iter().copied() {
+            let patterns = self.colors.iter().copied().flat_map(|second| {
+                [DitherPattern::Quarter, DitherPattern::Checker]
+                    .into_iter()
+                    .map(move |pattern| (first, second, pattern))
+            });
+
+            for (a, b_color, pattern) in
+                core::iter::once((first, first, DitherPattern::Solid)).chain(patterns)
+            {
+                if a == b_color && pattern != DitherPattern::Solid {
+                    continue;
+                }
+
+                let distance =
+                    rgb_distance(Color::blend(a, b_color, pattern.fraction()), (r, g, b));
+                if distance < closest_distance {
+                    closest = (a, b_color, pattern);
+                    closest_distance = distance;
+                }
+            }
+        }
+
+        closest
+    }
+
+    /// Maps an rgba image onto the colors of the palette and stores it as a texture.
+    ///
+    /// `rgba` holds 4 bytes for every pixel, row by row, like
+    /// [`image::write_png`](crate::export::image::write_png) writes them.
+    /// Only the top left 128x120 pixels are used, if the image is smaller the rest of the texture
+    /// stays black.
+    /// The alpha channel is ignored.
+    ///
+    /// Returns [`PicoError::ImageSize`] if `rgba` doesn't hold exactly `width * height` pixels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Palette, Point2D};
+    /// use picocadrs::point;
+    ///
+    /// let image: Vec<u8> = vec![250, 10, 70, 255, 40, 170, 250, 255];
+    /// let footer = Palette::default().map_image(2, 1, &image).unwrap();
+    ///
+    /// assert_eq!(footer.pixel(point!(0, 0)), Color::Red);
+    /// assert_eq!(footer.pixel(point!(1, 0)), Color::Blue);
+    /// assert_eq!(footer.pixel(point!(2, 0)), Color::Black);
+    /// ```
+    pub fn map_image(&self, width: usize, height: usize, rgba: &[u8]) -> Result<Footer, PicoError> {
+        if rgba.len() != width * height * 4 {
+            return Err(PicoError::ImageSize(rgba.len(), width * height * 4));
+        }
+
+        let mut footer = Footer::default();
+
+        for v in 0..height.min(Footer::HEIGHT) {
+            for u in 0..width.min(Footer::WIDTH) {
+                let i = (v * width + u) * 4;
+                if let Some(pixel) = footer.get_mut(point!(u, v)) {
+                    *pixel = self.nearest(rgba[i], rgba[i + 1], rgba[i + 2]);
+                }
+            }
+        }
+
+        Ok(footer)
+    }
+}
+
+impl Default for Palette {
+    /// Creates a palette holding all 16 pico-8 colors.
+    fn default() -> Self {
+        Palette::new((0..16).map(Color::from).collect())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_new() {
+        let palette = Palette::new(vec![Color::Red, Color::Invalid, Color::Red, Color::Blue]);
+
+        assert_eq!(palette.colors(), &[Color::Red, Color::Blue]);
+        assert_eq!(Palette::new(vec![]).nearest(255, 255, 255), Color::Black);
+        assert_eq!(Palette::default().colors().len(), 16);
+    }
+
+    #[test]
+    fn palette_map_image() {
+        let palette = Palette::new(vec![Color::DarkBlue, Color::Yellow]);
+        let mut image: Vec<u8> = vec![];
+
+        for v in 0..130 {
+            for _ in 0..140 {
+                if v % 2 == 0 {
+                    image.extend_from_slice(&[250, 250, 0, 255]);
+                } else {
+                    image.extend_from_slice(&[0, 0, 50, 0]);
+                }
+            }
+        }
+
+        let footer = palette.map_image(140, 130, &image).unwrap();
+
+        assert_eq!(footer.pixel(point!(127, 0)), Color::Yellow);
+        assert_eq!(footer.pixel(point!(127, 119)), Color::DarkBlue);
+
+        assert!(matches!(
+            palette.map_image(2, 2, &image),
+            Err(PicoError::ImageSize(_, 16))
+        ));
+    }
+
+    #[test]
+    fn palette_closest_blend() {
+        let palette = Palette::new(vec![Color::Black, Color::White, Color::Red]);
+
+        assert_eq!(
+            palette.closest_blend(0, 0, 0),
+            (Color::Black, Color::Black, DitherPattern::Solid)
+        );
+        assert_eq!(
+            palette.closest_blend(135, 127, 122),
+            (Color::Black, Color::White, DitherPattern::Quarter)
+        );
+        assert_eq!(
+            palette.closest_blend(227, 216, 208),
+            (Color::White, Color::Black, DitherPattern::Quarter)
+        );
+        assert_eq!(
+            Palette::new(vec![]).closest_blend(255, 255, 255),
+            (Color::Black, Color::Black, DitherPattern::Solid)
+        );
+    }
+
+    #[test]
+    fn palette_dither_pattern() {
+        let pixels = [point!(0, 0), point!(1, 0), point!(0, 1), point!(1, 1)];
+        let count = |pattern: DitherPattern| {
+            pixels
+                .iter()
+                .filter(|pixel| {
+                    pattern.color_at(Color::Black, Color::White, **pixel) == Color::White
+                })
+                .count() as f64
+        };
+
+        for pattern in [
+            DitherPattern::Solid,
+            DitherPattern::Quarter,
+            DitherPattern::Checker,
+        ] {
+            assert_eq!(count(pattern) / 4.0, pattern.fraction());
+        }
+    }
+}
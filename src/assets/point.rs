@@ -1,13 +1,21 @@
 //! For different kind of coordinates.
 //!
 //! This module houses the structs [`Point2D`] and [`Point3D`] that describe points in either 2- or
-//! 3-dimensional space.
+//! 3-dimensional space, as well as [`Axis`] to refer to one of the axes of 3-dimensional space.
 
+#[cfg(feature = "std")]
+use crate::camera::{CameraParams, Projection, NEAR_PLANE};
 use crate::error::PicoError;
-use rlua::{Lua, Table};
-use std::fmt::{Display, Formatter};
-use std::ops::{Add, Sub};
-use std::str::FromStr;
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use crate::lua_table::LuaValue;
+use crate::number::FormatNumber;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use rlua::Table;
 
 /// Represents a 2-dimensional point in space.
 /// In this crates context used for uv-mapping.
@@ -33,7 +41,7 @@ use std::str::FromStr;
 /// assert_eq!(point + point, point!(2, 4));
 /// assert_eq!(point - point, point!(0, 0));
 /// ```
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Point2D<T> {
     pub u: T,
     pub v: T,
@@ -152,12 +160,212 @@ impl<T: Sub<Output = T>> Sub for Point2D<T> {
     }
 }
 
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point2D<T> {
+    type Output = Point2D<T>;
+
+    /// Multiplies both coordinates with a scalar.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    ///
+    /// assert_eq!(Point2D::new(1, -4) * 3, Point2D::new(3, -12));
+    /// ```
+    fn mul(self, rhs: T) -> Self::Output {
+        Point2D {
+            u: self.u * rhs,
+            v: self.v * rhs,
+        }
+    }
+}
+
+impl<T: Copy + Div<Output = T>> Div<T> for Point2D<T> {
+    type Output = Point2D<T>;
+
+    /// Divides both coordinates by a scalar.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    ///
+    /// assert_eq!(Point2D::new(1.0, -4.0) / 2.0, Point2D::new(0.5, -2.0));
+    /// ```
+    fn div(self, rhs: T) -> Self::Output {
+        Point2D {
+            u: self.u / rhs,
+            v: self.v / rhs,
+        }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2D<T> {
+    type Output = Point2D<T>;
+
+    /// Negates both coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    ///
+    /// assert_eq!(-Point2D::new(1, -4), Point2D::new(-1, 4));
+    /// ```
+    fn neg(self) -> Self::Output {
+        Point2D {
+            u: -self.u,
+            v: -self.v,
+        }
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point2D<T> {
+    /// Adds another point onto this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    ///
+    /// let mut point = Point2D::new(1, 4);
+    /// point += Point2D::new(2, 1);
+    ///
+    /// assert_eq!(point, Point2D::new(3, 5));
+    /// ```
+    fn add_assign(&mut self, rhs: Self) {
+        self.u += rhs.u;
+        self.v += rhs.v;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point2D<T> {
+    /// Subtracts another point from this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    ///
+    /// let mut point = Point2D::new(1, 4);
+    /// point -= Point2D::new(2, 1);
+    ///
+    /// assert_eq!(point, Point2D::new(-1, 3));
+    /// ```
+    fn sub_assign(&mut self, rhs: Self) {
+        self.u -= rhs.u;
+        self.v -= rhs.v;
+    }
+}
+
+impl<T: Copy + PartialOrd> Point2D<T> {
+    /// Returns the smaller value of both points on each coordinate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    ///
+    /// assert_eq!(Point2D::new(1, 4).min(Point2D::new(2, 1)), Point2D::new(1, 1));
+    /// ```
+    pub fn min(&self, other: Point2D<T>) -> Point2D<T> {
+        Point2D {
+            u: if other.u < self.u { other.u } else { self.u },
+            v: if other.v < self.v { other.v } else { self.v },
+        }
+    }
+
+    /// Returns the bigger value of both points on each coordinate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    ///
+    /// assert_eq!(Point2D::new(1, 4).max(Point2D::new(2, 1)), Point2D::new(2, 4));
+    /// ```
+    pub fn max(&self, other: Point2D<T>) -> Point2D<T> {
+        Point2D {
+            u: if other.u > self.u { other.u } else { self.u },
+            v: if other.v > self.v { other.v } else { self.v },
+        }
+    }
+}
+
+impl Point2D<f64> {
+    /// Returns the dot product of two vectors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    /// use picocadrs::point;
+    ///
+    /// assert_eq!(point!(1.0, 2.0).dot(point!(4.0, -5.0)), -6.0);
+    /// ```
+    pub fn dot(&self, other: Point2D<f64>) -> f64 {
+        self.u * other.u + self.v * other.v
+    }
+
+    /// Returns the length of the vector.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    /// use picocadrs::point;
+    ///
+    /// assert_eq!(point!(3.0, -4.0).length(), 5.0);
+    /// ```
+    pub fn length(&self) -> f64 {
+        self.dot(*self).sqrt()
+    }
+
+    /// Returns the distance between two points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    /// use picocadrs::point;
+    ///
+    /// assert_eq!(point!(1.0, 1.0).distance(point!(4.0, 5.0)), 5.0);
+    /// ```
+    pub fn distance(&self, other: Point2D<f64>) -> f64 {
+        (other - *self).length()
+    }
+
+    /// Interpolates linearly between two points.
+    /// `t = 0.0` returns this point and `t = 1.0` returns `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    /// use picocadrs::point;
+    ///
+    /// assert_eq!(point!(0.0, 2.0).lerp(point!(4.0, 4.0), 0.25), point!(1.0, 2.5));
+    /// ```
+    pub fn lerp(&self, other: Point2D<f64>, t: f64) -> Point2D<f64> {
+        *self + (other - *self) * t
+    }
+}
+
 impl<T: Display> Display for Point2D<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{},{}", self.u, self.v)
     }
 }
 
+impl<T: FormatNumber> FormatNumber for Point2D<T> {
+    fn fmt_number(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.u.fmt_number(f)?;
+        write!(f, ",")?;
+        self.v.fmt_number(f)
+    }
+}
+
+#[cfg(feature = "std")]
 impl TryFrom<Table<'_>> for Point2D<f64> {
     type Error = PicoError;
 
@@ -181,6 +389,18 @@ impl TryFrom<Table<'_>> for Point2D<f64> {
     }
 }
 
+impl TryFrom<&LuaValue> for Point2D<f64> {
+    type Error = PicoError;
+
+    /// Tries to create a [`Point2D`] from a [`LuaValue`].
+    /// Only succeeds if the value is a table holding 2 numbers.
+    fn try_from(value: &LuaValue) -> Result<Self, Self::Error> {
+        let coords = lua_numbers(value, 2)?;
+
+        Ok(Point2D::new(coords[0], coords[1]))
+    }
+}
+
 impl FromStr for Point2D<f64> {
     type Err = PicoError;
 
@@ -198,19 +418,7 @@ impl FromStr for Point2D<f64> {
     /// )
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut point = Ok(Point2D::new(0.0, 0.0));
-
-        let lua = Lua::new();
-        lua.context(|ctx| {
-            let table_result: rlua::Result<Table> = ctx.load(s).eval();
-
-            point = match table_result {
-                Ok(table) => Point2D::try_from(table),
-                Err(err) => Err(PicoError::from(err)),
-            }
-        });
-
-        point
+        Point2D::try_from(&crate::lua_table::parse(s)?)
     }
 }
 
@@ -239,7 +447,7 @@ impl FromStr for Point2D<f64> {
 /// assert_eq!(point + point, point!(2, 4, 6));
 /// assert_eq!(point - point, point!(0, 0, 0));
 /// ```
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Point3D<T> {
     pub x: T,
     pub y: T,
@@ -366,12 +574,168 @@ impl<T: Sub<Output = T>> Sub for Point3D<T> {
     }
 }
 
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point3D<T> {
+    type Output = Point3D<T>;
+
+    /// Multiplies every coordinate with a scalar.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    ///
+    /// assert_eq!(Point3D::new(1, -4, 2) * 3, Point3D::new(3, -12, 6));
+    /// ```
+    fn mul(self, rhs: T) -> Self::Output {
+        Point3D {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+        }
+    }
+}
+
+impl<T: Copy + Div<Output = T>> Div<T> for Point3D<T> {
+    type Output = Point3D<T>;
+
+    /// Divides every coordinate by a scalar.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    ///
+    /// assert_eq!(Point3D::new(1.0, -4.0, 3.0) / 2.0, Point3D::new(0.5, -2.0, 1.5));
+    /// ```
+    fn div(self, rhs: T) -> Self::Output {
+        Point3D {
+            x: self.x / rhs,
+            y: self.y / rhs,
+            z: self.z / rhs,
+        }
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3D<T> {
+    type Output = Point3D<T>;
+
+    /// Negates every coordinate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    ///
+    /// assert_eq!(-Point3D::new(1, -4, 0), Point3D::new(-1, 4, 0));
+    /// ```
+    fn neg(self) -> Self::Output {
+        Point3D {
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point3D<T> {
+    /// Adds another point onto this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    ///
+    /// let mut point = Point3D::new(1, 4, -2);
+    /// point += Point3D::new(2, -2, 3);
+    ///
+    /// assert_eq!(point, Point3D::new(3, 2, 1));
+    /// ```
+    fn add_assign(&mut self, rhs: Self) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+        self.z += rhs.z;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point3D<T> {
+    /// Subtracts another point from this one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    ///
+    /// let mut point = Point3D::new(1, 4, 2);
+    /// point -= Point3D::new(2, 1, -4);
+    ///
+    /// assert_eq!(point, Point3D::new(-1, 3, 6));
+    /// ```
+    fn sub_assign(&mut self, rhs: Self) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+        self.z -= rhs.z;
+    }
+}
+
+impl<T: Copy + PartialOrd> Point3D<T> {
+    /// Returns the smaller value of both points on each coordinate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    ///
+    /// assert_eq!(
+    ///     Point3D::new(1, 4, 0).min(Point3D::new(2, 1, 0)),
+    ///     Point3D::new(1, 1, 0)
+    /// );
+    /// ```
+    pub fn min(&self, other: Point3D<T>) -> Point3D<T> {
+        Point3D {
+            x: if other.x < self.x { other.x } else { self.x },
+            y: if other.y < self.y { other.y } else { self.y },
+            z: if other.z < self.z { other.z } else { self.z },
+        }
+    }
+
+    /// Returns the bigger value of both points on each coordinate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    ///
+    /// assert_eq!(
+    ///     Point3D::new(1, 4, 0).max(Point3D::new(2, 1, 0)),
+    ///     Point3D::new(2, 4, 0)
+    /// );
+    /// ```
+    pub fn max(&self, other: Point3D<T>) -> Point3D<T> {
+        Point3D {
+            x: if other.x > self.x { other.x } else { self.x },
+            y: if other.y > self.y { other.y } else { self.y },
+            z: if other.z > self.z { other.z } else { self.z },
+        }
+    }
+}
+
 impl<T: Display> Display for Point3D<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{},{},{}", self.x, self.y, self.z)
     }
 }
 
+impl<T: FormatNumber> FormatNumber for Point3D<T> {
+    fn fmt_number(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.x.fmt_number(f)?;
+        write!(f, ",")?;
+        self.y.fmt_number(f)?;
+        write!(f, ",")?;
+        self.z.fmt_number(f)
+    }
+}
+
+#[cfg(feature = "std")]
 impl TryFrom<Table<'_>> for Point3D<f64> {
     type Error = PicoError;
 
@@ -395,6 +759,18 @@ impl TryFrom<Table<'_>> for Point3D<f64> {
     }
 }
 
+impl TryFrom<&LuaValue> for Point3D<f64> {
+    type Error = PicoError;
+
+    /// Tries to create a [`Point3D`] from a [`LuaValue`].
+    /// Only succeeds if the value is a table holding 3 numbers.
+    fn try_from(value: &LuaValue) -> Result<Self, Self::Error> {
+        let coords = lua_numbers(value, 3)?;
+
+        Ok(Point3D::new(coords[0], coords[1], coords[2]))
+    }
+}
+
 impl FromStr for Point3D<f64> {
     type Err = PicoError;
 
@@ -412,19 +788,248 @@ impl FromStr for Point3D<f64> {
     /// )
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut point = Ok(Point3D::new(0.0, 0.0, 0.0));
+        Point3D::try_from(&crate::lua_table::parse(s)?)
+    }
+}
 
-        let lua = Lua::new();
-        lua.context(|ctx| {
-            let table_result: rlua::Result<Table> = ctx.load(s).eval();
+/// Returns the numbers of a table holding exactly `count` numbers.
+fn lua_numbers(value: &LuaValue, count: usize) -> Result<Vec<f64>, PicoError> {
+    let table = value.as_table().ok_or(PicoError::TableLength(0, count))?;
 
-            point = match table_result {
-                Ok(table) => Point3D::try_from(table),
-                Err(err) => Err(PicoError::from(err)),
-            }
-        });
+    if table.sequence.len() != count {
+        return Err(PicoError::TableLength(table.sequence.len(), count));
+    }
+
+    table
+        .sequence
+        .iter()
+        .enumerate()
+        .map(|(i, value)| value.as_f64().ok_or(PicoError::TableNumber(i)))
+        .collect()
+}
+
+/// One of the three axes of 3-dimensional space.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Axis, Point3D};
+/// use picocadrs::point;
+///
+/// let mut point = point!(1.0, 2.0, 3.0);
+///
+/// assert_eq!(point.axis(Axis::Y), 2.0);
+///
+/// *point.axis_mut(Axis::Z) = -1.0;
+/// assert_eq!(point, point!(1.0, 2.0, -1.0));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
 
-        point
+impl<T: Copy> Point3D<T> {
+    /// Returns the coordinate of the point on the given axis.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Axis, Point3D};
+    ///
+    /// let point = Point3D::new(1, 2, 3);
+    ///
+    /// assert_eq!(point.axis(Axis::X), 1);
+    /// assert_eq!(point.axis(Axis::Z), 3);
+    /// ```
+    pub fn axis(&self, axis: Axis) -> T {
+        match axis {
+            Axis::X => self.x,
+            Axis::Y => self.y,
+            Axis::Z => self.z,
+        }
+    }
+}
+
+impl<T> Point3D<T> {
+    /// Returns a mutable reference to the coordinate of the point on the given axis.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Axis, Point3D};
+    ///
+    /// let mut point = Point3D::new(1, 2, 3);
+    /// *point.axis_mut(Axis::Y) = 5;
+    ///
+    /// assert_eq!(point, Point3D::new(1, 5, 3));
+    /// ```
+    pub fn axis_mut(&mut self, axis: Axis) -> &mut T {
+        match axis {
+            Axis::X => &mut self.x,
+            Axis::Y => &mut self.y,
+            Axis::Z => &mut self.z,
+        }
+    }
+}
+
+impl Point3D<f64> {
+    /// Returns the dot product of two vectors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    /// use picocadrs::point;
+    ///
+    /// assert_eq!(point!(1.0, 2.0, 3.0).dot(point!(4.0, -5.0, 6.0)), 12.0);
+    /// ```
+    pub fn dot(&self, other: Point3D<f64>) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Returns the cross product of two vectors.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    /// use picocadrs::point;
+    ///
+    /// assert_eq!(
+    ///     point!(1.0, 0.0, 0.0).cross(point!(0.0, 1.0, 0.0)),
+    ///     point!(0.0, 0.0, 1.0)
+    /// );
+    /// ```
+    pub fn cross(&self, other: Point3D<f64>) -> Point3D<f64> {
+        Point3D::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// Returns the length of the vector.
+    pub fn length(&self) -> f64 {
+        self.dot(*self).sqrt()
+    }
+
+    /// Returns the distance between two points.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    /// use picocadrs::point;
+    ///
+    /// assert_eq!(point!(1.0, 1.0, 1.0).distance(point!(3.0, 4.0, -5.0)), 7.0);
+    /// ```
+    pub fn distance(&self, other: Point3D<f64>) -> f64 {
+        (other - *self).length()
+    }
+
+    /// Interpolates linearly between two points.
+    /// `t = 0.0` returns this point and `t = 1.0` returns `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    /// use picocadrs::point;
+    ///
+    /// assert_eq!(
+    ///     point!(0.0, 2.0, -1.0).lerp(point!(4.0, 4.0, 1.0), 0.25),
+    ///     point!(1.0, 2.5, -0.5)
+    /// );
+    /// ```
+    pub fn lerp(&self, other: Point3D<f64>, t: f64) -> Point3D<f64> {
+        *self + (other - *self) * t
+    }
+
+    /// Returns the vector scaled to a length of 1.
+    /// A vector with length 0 is returned unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    /// use picocadrs::point;
+    ///
+    /// assert_eq!(point!(0.0, 3.0, -4.0).normalized(), point!(0.0, 0.6, -0.8));
+    /// ```
+    pub fn normalized(&self) -> Point3D<f64> {
+        let length = self.length();
+
+        if length == 0.0 {
+            *self
+        } else {
+            Point3D::new(self.x / length, self.y / length, self.z / length)
+        }
+    }
+
+    /// Projects the point onto the view of the given camera.
+    ///
+    /// `x` and `y` of the result are the position in the view, where the center of the view is
+    /// at `0, 0` and its top and bottom edge are at `y = -1` and `y = 1`.
+    /// Like in picoCAD the `y`-axis points down.
+    /// How far the view reaches horizontally depends on its aspect ratio.
+    /// `z` is the distance of the point in front of the camera along its viewing direction.
+    ///
+    /// Returns [`None`] if the camera uses a perspective projection and the point is not in front
+    /// of the camera (see [`NEAR_PLANE`](crate::camera::NEAR_PLANE)).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    /// use picocadrs::camera::{CameraParams, Projection};
+    /// use picocadrs::point;
+    ///
+    /// let camera = CameraParams {
+    ///     yaw: 0.0,
+    ///     pitch: 0.0,
+    ///     distance: 4.0,
+    ///     fov: 90f64.to_radians(),
+    ///     ..CameraParams::default()
+    /// };
+    ///
+    /// let projected = point!(2.0, 0.0, 0.0).project(&camera).unwrap();
+    /// assert!((projected.x - 0.5).abs() < 1e-9);
+    /// assert!((projected.z - 4.0).abs() < 1e-9);
+    ///
+    /// // behind the camera
+    /// assert!(point!(0.0, 0.0, 5.0).project(&camera).is_none());
+    ///
+    /// let camera = CameraParams {
+    ///     projection: Projection::Orthographic,
+    ///     ..camera
+    /// };
+    /// let projected = point!(2.0, 0.0, 0.0).project(&camera).unwrap();
+    /// assert!((projected.x - 0.5).abs() < 1e-9);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn project(&self, camera: &CameraParams) -> Option<Point3D<f64>> {
+        let relative = *self - camera.position();
+        let depth = relative.dot(camera.forward());
+        let half_height = (camera.fov / 2.0).tan();
+
+        let scale = match camera.projection {
+            Projection::Perspective => {
+                if depth < NEAR_PLANE {
+                    return None;
+                }
+
+                depth * half_height
+            }
+            Projection::Orthographic => camera.distance * half_height,
+        };
+
+        Some(Point3D::new(
+            relative.dot(camera.right()) / scale,
+            relative.dot(camera.down()) / scale,
+            depth,
+        ))
     }
 }
 
@@ -497,6 +1102,29 @@ pub mod tests {
         assert_eq!(p1 - p2, Point2D::new(-1, 3));
     }
 
+    #[test]
+    fn test_uv_arithmetic() {
+        let mut point = Point2D::new(1.0, -2.0);
+
+        assert_eq!(point * 2.0, Point2D::new(2.0, -4.0));
+        assert_eq!(point / 2.0, Point2D::new(0.5, -1.0));
+        assert_eq!(-point, Point2D::new(-1.0, 2.0));
+
+        point += Point2D::new(2.0, 6.0);
+        assert_eq!(point, Point2D::new(3.0, 4.0));
+        point -= Point2D::new(3.0, 0.0);
+        assert_eq!(point, Point2D::new(0.0, 4.0));
+
+        assert_eq!(point.length(), 4.0);
+        assert_eq!(point.distance(Point2D::new(3.0, 0.0)), 5.0);
+        assert_eq!(
+            point.lerp(Point2D::new(2.0, 0.0), 0.5),
+            Point2D::new(1.0, 2.0)
+        );
+        assert_eq!(point.min(Point2D::new(1.0, 1.0)), Point2D::new(0.0, 1.0));
+        assert_eq!(point.max(Point2D::new(1.0, 1.0)), Point2D::new(1.0, 4.0));
+    }
+
     #[test]
     fn test_uv_macro() {
         assert_eq!(point!(2, 3), Point2D::new(2, 3));
@@ -567,6 +1195,34 @@ pub mod tests {
         assert_eq!(p1 - p2, Point3D::new(-1, 3, 6));
     }
 
+    #[test]
+    fn test_xyz_arithmetic() {
+        let mut point = Point3D::new(1.0, -2.0, 0.5);
+
+        assert_eq!(point * 2.0, Point3D::new(2.0, -4.0, 1.0));
+        assert_eq!(point / 2.0, Point3D::new(0.5, -1.0, 0.25));
+        assert_eq!(-point, Point3D::new(-1.0, 2.0, -0.5));
+
+        point += Point3D::new(2.0, 6.0, -0.5);
+        assert_eq!(point, Point3D::new(3.0, 4.0, 0.0));
+        point -= Point3D::new(3.0, 0.0, 0.0);
+        assert_eq!(point, Point3D::new(0.0, 4.0, 0.0));
+
+        assert_eq!(point.distance(Point3D::new(3.0, 0.0, 0.0)), 5.0);
+        assert_eq!(
+            point.lerp(Point3D::new(2.0, 0.0, 2.0), 0.5),
+            Point3D::new(1.0, 2.0, 1.0)
+        );
+        assert_eq!(
+            point.min(Point3D::new(1.0, 1.0, -1.0)),
+            Point3D::new(0.0, 1.0, -1.0)
+        );
+        assert_eq!(
+            point.max(Point3D::new(1.0, 1.0, -1.0)),
+            Point3D::new(1.0, 4.0, 0.0)
+        );
+    }
+
     #[test]
     fn test_xyz_macro() {
         assert_eq!(point!(2, 3, -1), Point3D::new(2, 3, -1));
@@ -596,4 +1252,16 @@ pub mod tests {
             "{0,-1.5,2.2}".parse::<Point3D<f64>>().unwrap().to_string()
         )
     }
+
+    #[test]
+    fn test_xyz_axis() {
+        let mut point = Point3D::new(2, 3, -1);
+
+        assert_eq!(point.axis(Axis::X), 2);
+        assert_eq!(point.axis(Axis::Y), 3);
+        assert_eq!(point.axis(Axis::Z), -1);
+
+        *point.axis_mut(Axis::X) = 4;
+        assert_eq!(point, Point3D::new(4, 3, -1));
+    }
 }
@@ -0,0 +1,370 @@
+//! For reclaiming unused space on the texture.
+//!
+//! The texture of a model only has 128x120 pixels, which fills up quickly.
+//! Which pixels are in use is decided by
+//! [`Model::texture_usage_map`](crate::assets::Model::texture_usage_map).
+//!
+//! - [`clear_unused_texture`] paints every pixel not sampled by any face in a single color,
+//!   removing leftovers of earlier uv-maps.
+//! - [`compact_texture`] moves uv islands up and to the left, so the free space ends up in one
+//!   piece at the bottom and right of the texture.
+//! - [`auto_notex`] turns faces that show (almost) a single color into faces without texture in
+//!   that color, so their pixels aren't needed anymore.
+//!
+//! A uv island is a group of textured faces whose pixels touch or overlap.
+//! Islands are always moved as a whole and by full pixels, so every face keeps showing exactly
+//! the same pixels.
+
+use crate::{
+    analysis::usage::TextureUsage,
+    assets::{Color, Footer, MeshFaceRef, Model, Point2D},
+    point,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Textured faces whose pixels touch or overlap.
+#[derive(Debug, Clone)]
+struct Island {
+    /// Faces belonging to the island.
+    faces: Vec<MeshFaceRef>,
+    /// Pixels sampled by the faces of the island.
+    texels: Vec<Point2D<usize>>,
+    /// True if a face of the island reaches outside of the texture.
+    /// Moving such an island could pull pixels from outside of the texture onto other islands.
+    fixed: bool,
+}
+
+/// Paints every pixel of the texture that isn't sampled by a textured face in `color` and returns
+/// the amount of pixels painted.
+///
+/// It's recommended to use [`Model::clear_unused_texture`].
+pub fn clear_unused_texture(model: &mut Model, color: Color) -> usize {
+    let unused = model.texture_usage_map().unused_texels();
+
+    for texel in unused.iter() {
+        if let Some(pixel) = model.footer.get_mut(*texel) {
+            *pixel = color;
+        }
+    }
+
+    unused.len()
+}
+
+/// Turns every textured face whose pixels are at least `tolerance` percent the same color into a
+/// face with [`no_texture`](crate::assets::Face::no_texture) enabled in that color, and returns
+/// the amount of faces turned.
+///
+/// Faces that don't sample any pixels are left alone, as well as faces whose most common color
+/// is the [alpha color](crate::assets::Header::alpha), since picoCAD shows those pixels as
+/// transparent.
+/// Pixels that were only sampled by turned faces are free afterwards, they can be painted over
+/// with [`clear_unused_texture`] or filled by [`compact_texture`].
+/// A `tolerance` of `100.0` only turns faces that show a single color.
+///
+/// It's recommended to use [`Model::auto_notex`].
+pub fn auto_notex(model: &mut Model, tolerance: f64) -> usize {
+    let mut turned = 0;
+
+    for mesh in model.meshes.iter_mut() {
+        for face in mesh.faces.iter_mut().filter(|face| !face.no_texture) {
+            let texels = face.uv_texels();
+
+            let mut histogram: HashMap<Color, usize> = HashMap::new();
+            for texel in texels.iter() {
+                *histogram.entry(model.footer.pixel(*texel)).or_default() += 1;
+            }
+
+            // the most common color, the one with the lower index on ties.
+            let Some((color, count)) = histogram
+                .into_iter()
+                .max_by_key(|(color, count)| (*count, -color.as_i32()))
+            else {
+                continue;
+            };
+
+            if color == model.header.alpha {
+                continue;
+            }
+
+            if count as f64 * 100.0 >= tolerance * texels.len() as f64 {
+                face.no_texture = true;
+                face.color = color;
+                turned += 1;
+            }
+        }
+    }
+
+    turned
+}
+
+/// Moves the uv islands of the model up and to the left as far as possible and returns the amount
+/// of islands moved.
+///
+/// It's recommended to use [`Model::compact_texture`].
+pub fn compact_texture(model: &mut Model) -> usize {
+    let usage = model.texture_usage_map();
+    let islands = islands(model, &usage);
+
+    let mut occupied = vec![false; Footer::WIDTH * Footer::HEIGHT];
+    for texel in islands.iter().flat_map(|island| island.texels.iter()) {
+        occupied[texel.v * Footer::WIDTH + texel.u] = true;
+    }
+
+    let mut footer = Footer::default();
+    let mut moved = 0;
+
+    for island in islands.iter() {
+        for texel in island.texels.iter() {
+            occupied[texel.v * Footer::WIDTH + texel.u] = false;
+        }
+
+        // the current position of the island is always free, so a spot is always found.
+        let (du, dv) = if island.fixed {
+            (0, 0)
+        } else {
+            free_spot(&occupied, &island.texels).unwrap_or((0, 0))
+        };
+
+        for texel in island.texels.iter() {
+            let target = point!(
+                (texel.u as isize + du) as usize,
+                (texel.v as isize + dv) as usize
+            );
+
+            occupied[target.v * Footer::WIDTH + target.u] = true;
+            if let Some(pixel) = footer.get_mut(target) {
+                *pixel = model.footer.pixel(*texel);
+            }
+        }
+
+        if (du, dv) == (0, 0) {
+            continue;
+        }
+
+        moved += 1;
+        for face_ref in island.faces.iter() {
+            let face = &mut model.meshes[face_ref.mesh].faces[face_ref.face];
+
+            for uv_map in face.uv_maps.iter_mut() {
+                uv_map.coords.u += du as f64 / 8.0;
+                uv_map.coords.v += dv as f64 / 8.0;
+            }
+        }
+    }
+
+    model.footer = footer;
+    moved
+}
+
+/// Groups the textured faces of the model into islands, sorted by their first pixel line by line.
+fn islands(model: &Model, usage: &TextureUsage) -> Vec<Island> {
+    let mut parents: BTreeMap<MeshFaceRef, MeshFaceRef> = BTreeMap::new();
+
+    for v in 0..Footer::HEIGHT {
+        for u in 0..Footer::WIDTH {
+            let Some(first) = usage.owner(point!(u, v)) else {
+                continue;
+            };
+
+            parents.entry(first).or_insert(first);
+
+            let neighbours = usage
+                .faces(point!(u, v))
+                .iter()
+                .chain(usage.faces(point!(u + 1, v)).first())
+                .chain(usage.faces(point!(u, v + 1)).first());
+
+            for face in neighbours {
+                parents.entry(*face).or_insert(*face);
+                union(&mut parents, first, *face);
+            }
+        }
+    }
+
+    let mut roots: BTreeMap<MeshFaceRef, usize> = BTreeMap::new();
+    let mut islands: Vec<Island> = vec![];
+
+    for v in 0..Footer::HEIGHT {
+        for u in 0..Footer::WIDTH {
+            let Some(face) = usage.owner(point!(u, v)) else {
+                continue;
+            };
+
+            let root = find(&mut parents, face);
+            let index = *roots.entry(root).or_insert_with(|| {
+                islands.push(Island {
+                    faces: vec![],
+                    texels: vec![],
+                    fixed: false,
+                });
+                islands.len() - 1
+            });
+
+            islands[index].texels.push(point!(u, v));
+        }
+    }
+
+    for face_ref in parents.keys().copied().collect::<Vec<MeshFaceRef>>() {
+        let island = &mut islands[roots[&find(&mut parents, face_ref)]];
+        let face = &model.meshes[face_ref.mesh].faces[face_ref.face];
+
+        island.faces.push(face_ref);
+        island.fixed |= face.uv_maps.iter().any(|uv_map| {
+            let (u, v) = (uv_map.coords.u * 8.0, uv_map.coords.v * 8.0);
+            !(0.0..=Footer::WIDTH as f64).contains(&u)
+                || !(0.0..=Footer::HEIGHT as f64).contains(&v)
+        });
+    }
+
+    islands
+}
+
+/// Returns the root of the set the face belongs to.
+fn find(parents: &mut BTreeMap<MeshFaceRef, MeshFaceRef>, face: MeshFaceRef) -> MeshFaceRef {
+    let parent = parents[&face];
+
+    if parent == face {
+        return face;
+    }
+
+    let root = find(parents, parent);
+    parents.insert(face, root);
+    root
+}
+
+/// Merges the sets both faces belong to.
+fn union(parents: &mut BTreeMap<MeshFaceRef, MeshFaceRef>, a: MeshFaceRef, b: MeshFaceRef) {
+    let a = find(parents, a);
+    let b = find(parents, b);
+
+    parents.insert(a.max(b), a.min(b));
+}
+
+/// Finds the topmost, then leftmost offset at which none of the pixels are occupied.
+pub(crate) fn free_spot(occupied: &[bool], texels: &[Point2D<usize>]) -> Option<(isize, isize)> {
+    let min_u = texels.iter().map(|texel| texel.u).min()?;
+    let max_u = texels.iter().map(|texel| texel.u).max()?;
+    let min_v = texels.iter().map(|texel| texel.v).min()?;
+    let max_v = texels.iter().map(|texel| texel.v).max()?;
+
+    for v in 0..Footer::HEIGHT - (max_v - min_v) {
+        for u in 0..Footer::WIDTH - (max_u - min_u) {
+            let fits = texels.iter().all(|texel| {
+                let target = (texel.v - min_v + v) * Footer::WIDTH + texel.u - min_u + u;
+                !occupied[target]
+            });
+
+            if fits {
+                return Some((u as isize - min_u as isize, v as isize - min_v as isize));
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Face, Mesh, ProjectPreset};
+    use crate::fixtures::uv_face;
+
+    fn model(faces: Vec<Face>) -> Model {
+        let mut mesh = Mesh::new("mesh".to_string());
+        mesh.faces = faces;
+
+        Model {
+            meshes: vec![mesh],
+            ..Model::default()
+        }
+    }
+
+    #[test]
+    fn atlas_clear_unused_texture() {
+        let mut model = model(vec![uv_face(point!(0.0, 0.0), point!(1.0, 1.0))]);
+        model.footer.set(point!(0, 0), Color::Red).unwrap();
+        model.footer.set(point!(8, 0), Color::Red).unwrap();
+
+        assert_eq!(model.clear_unused_texture(Color::Blue), 128 * 120 - 64);
+        assert_eq!(model.footer.pixel(point!(0, 0)), Color::Red);
+        assert_eq!(model.footer.pixel(point!(7, 7)), Color::Black);
+        assert_eq!(model.footer.pixel(point!(8, 0)), Color::Blue);
+    }
+
+    #[test]
+    fn atlas_compact_texture() {
+        let mut model = model(vec![
+            // first island, 2 faces next to each other.
+            uv_face(point!(4.0, 4.0), point!(5.0, 5.0)),
+            uv_face(point!(5.0, 4.0), point!(6.0, 5.0)),
+            // second island, 2 faces using the same pixels.
+            uv_face(point!(10.0, 10.0), point!(11.0, 11.0)),
+            uv_face(point!(10.0, 10.0), point!(11.0, 11.0)),
+            // reaches outside of the texture and stays in place.
+            uv_face(point!(15.5, 14.0), point!(16.5, 15.0)),
+        ]);
+        model.footer.set(point!(32, 32), Color::Red).unwrap();
+        model.footer.set(point!(87, 87), Color::Green).unwrap();
+        model.footer.set(point!(127, 119), Color::Blue).unwrap();
+        model.footer.set(point!(0, 0), Color::Pink).unwrap();
+
+        assert_eq!(model.compact_texture(), 2);
+
+        let faces = &model.meshes[0].faces;
+        assert_eq!(faces[0], uv_face(point!(0.0, 0.0), point!(1.0, 1.0)));
+        assert_eq!(faces[1], uv_face(point!(1.0, 0.0), point!(2.0, 1.0)));
+        assert_eq!(faces[2], uv_face(point!(2.0, 0.0), point!(3.0, 1.0)));
+        assert_eq!(faces[3], uv_face(point!(2.0, 0.0), point!(3.0, 1.0)));
+        assert_eq!(faces[4], uv_face(point!(15.5, 14.0), point!(16.5, 15.0)));
+
+        assert_eq!(model.footer.pixel(point!(0, 0)), Color::Red);
+        assert_eq!(model.footer.pixel(point!(23, 7)), Color::Green);
+        assert_eq!(model.footer.pixel(point!(127, 119)), Color::Blue);
+        assert_eq!(model.footer.pixel(point!(32, 32)), Color::Black);
+        assert_eq!(model.texture_usage_map().used_count(), 3 * 64 + 4 * 8);
+
+        // nothing left to move.
+        assert_eq!(model.compact_texture(), 0);
+    }
+    #[test]
+    fn atlas_auto_notex() {
+        let mut model = model(vec![
+            uv_face(point!(0.0, 0.0), point!(1.0, 1.0)),
+            uv_face(point!(1.0, 0.0), point!(2.0, 1.0)),
+            uv_face(point!(2.0, 0.0), point!(2.0, 1.0)),
+        ]);
+        model.meshes[0].faces[1].no_texture = true;
+        for v in 0..8 {
+            for u in 0..8 {
+                model.footer.set(point!(u, v), Color::Green).unwrap();
+            }
+        }
+        model.footer.set(point!(0, 0), Color::Red).unwrap();
+
+        // 63 of 64 pixels are green.
+        assert_eq!(model.auto_notex(100.0), 0);
+        assert_eq!(model.auto_notex(98.0), 1);
+
+        let face = &model.meshes[0].faces[0];
+        assert!(face.no_texture);
+        assert_eq!(face.color, Color::Green);
+        assert_eq!(model.texture_usage_map().used_count(), 0);
+
+        // faces without pixels stay textured.
+        assert!(!model.meshes[0].faces[2].no_texture);
+    }
+
+    #[test]
+    fn atlas_auto_notex_alpha() {
+        let mut model = Model::new_project("plane".to_string(), ProjectPreset::Plane);
+        assert_eq!(model.header.alpha, Color::Black);
+
+        // the texture is all black, which picoCAD shows as transparent.
+        assert_eq!(model.auto_notex(100.0), 0);
+        assert!(!model.meshes[0].faces[0].no_texture);
+
+        model.header.alpha = Color::Red;
+        assert_eq!(model.auto_notex(100.0), 1);
+        assert_eq!(model.meshes[0].faces[0].color, Color::Black);
+    }
+}
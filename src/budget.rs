@@ -0,0 +1,363 @@
+//! For splitting the texture between meshes.
+//!
+//! All meshes of a model share one texture of 128x120 pixels.
+//! When multiple people work on the same model it helps to decide up front which part of the
+//! texture belongs to which mesh, so nobody paints over pixels someone else uses.
+//!
+//! A [`TextureBudget`] collects how many pixels each mesh needs and turns them into a
+//! [`TextureAssignment`], where every mesh gets its own [`Rect`] that doesn't overlap with any
+//! other.
+//! Rectangles are packed in rows, tallest first, starting at the top left of the texture.
+//!
+//! The assignment can then be used to:
+//!
+//! - move the uv-coordinates and pixels of each mesh into its region, see
+//!   [`TextureAssignment::apply`].
+//! - check that a model still keeps to it, see [`TextureAssignment::violations`].
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Point2D, Rect};
+//! use picocadrs::budget::TextureBudget;
+//! use picocadrs::point;
+//!
+//! let mut budget = TextureBudget::new();
+//! budget.request(0, point!(64, 32));
+//! budget.request(1, point!(64, 64));
+//! budget.request(2, point!(128, 16));
+//!
+//! let assignment = budget.assign().unwrap();
+//!
+//! assert_eq!(assignment.region(1), Some(Rect::new(point!(0, 0), point!(64, 64))));
+//! assert_eq!(assignment.region(0), Some(Rect::new(point!(64, 0), point!(64, 32))));
+//! assert_eq!(assignment.region(2), Some(Rect::new(point!(0, 64), point!(128, 16))));
+//! assert_eq!(assignment.owner(point!(100, 10)), Some(0));
+//! ```
+
+use crate::{
+    assets::{Footer, MeshFaceRef, Model, Point2D, Rect},
+    error::PicoError,
+    point,
+};
+use std::collections::BTreeMap;
+
+/// The amount of texture each mesh of a model needs, in pixels.
+///
+/// Meshes are identified by their index in [`Model::meshes`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TextureBudget {
+    /// Width and height requested by each mesh.
+    pub requests: BTreeMap<usize, Point2D<usize>>,
+}
+
+impl TextureBudget {
+    /// Creates a budget without any requests.
+    pub fn new() -> TextureBudget {
+        TextureBudget::default()
+    }
+
+    /// Creates a budget where each mesh requests the size of the area its textured faces
+    /// currently use.
+    ///
+    /// Meshes without textured faces don't request anything.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model, Point2D};
+    /// use picocadrs::budget::TextureBudget;
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(Mesh::plane());
+    ///
+    /// let budget = TextureBudget::from_model(&model);
+    ///
+    /// assert_eq!(budget.requests[&0], point!(16, 16));
+    /// ```
+    pub fn from_model(model: &Model) -> TextureBudget {
+        let mut budget = TextureBudget::new();
+
+        for mesh_index in 0..model.meshes.len() {
+            if let Some((min, max)) = mesh_texel_bounds(model, mesh_index) {
+                budget.request(mesh_index, point!(max.u - min.u + 1, max.v - min.v + 1));
+            }
+        }
+
+        budget
+    }
+
+    /// Requests `size` pixels for the mesh at `mesh_index`, replacing any earlier request of it.
+    pub fn request(&mut self, mesh_index: usize, size: Point2D<usize>) -> &mut TextureBudget {
+        self.requests.insert(mesh_index, size);
+        self
+    }
+
+    /// Assigns every request a region of the texture, without any two overlapping.
+    ///
+    /// Requests without any area don't get a region.
+    /// Returns [`PicoError::TextureFull`] with the index of the first mesh that doesn't fit.
+    pub fn assign(&self) -> Result<TextureAssignment, PicoError> {
+        let mut requests: Vec<(usize, Point2D<usize>)> = self
+            .requests
+            .iter()
+            .filter(|(_, size)| size.u > 0 && size.v > 0)
+            .map(|(index, size)| (*index, *size))
+            .collect();
+
+        // tallest first, so each row wastes as little height as possible.
+        requests.sort_by(|(a_index, a), (b_index, b)| {
+            b.v.cmp(&a.v).then(b.u.cmp(&a.u)).then(a_index.cmp(b_index))
+        });
+
+        let mut regions: BTreeMap<usize, Rect> = BTreeMap::new();
+        let (mut u, mut v, mut row_height) = (0, 0, 0);
+
+        for (mesh_index, size) in requests {
+            if u + size.u > Footer::WIDTH {
+                u = 0;
+                v += row_height;
+                row_height = 0;
+            }
+
+            let region = Rect::new(point!(u, v), size);
+            if !Rect::TEXTURE.contains_rect(&region) {
+                return Err(PicoError::TextureFull(mesh_index.to_string()));
+            }
+
+            regions.insert(mesh_index, region);
+            u += size.u;
+            row_height = row_height.max(size.v);
+        }
+
+        Ok(TextureAssignment { regions })
+    }
+}
+
+/// The region of the texture each mesh is allowed to use, created by [`TextureBudget::assign`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TextureAssignment {
+    /// Region of each mesh, by index in [`Model::meshes`].
+    pub regions: BTreeMap<usize, Rect>,
+}
+
+impl TextureAssignment {
+    /// Returns the region of the mesh at `mesh_index`.
+    pub fn region(&self, mesh_index: usize) -> Option<Rect> {
+        self.regions.get(&mesh_index).copied()
+    }
+
+    /// Returns the index of the mesh whose region contains the pixel at `coords`.
+    pub fn owner(&self, coords: Point2D<usize>) -> Option<usize> {
+        self.regions
+            .iter()
+            .find(|(_, rect)| rect.contains(coords))
+            .map(|(index, _)| *index)
+    }
+
+    /// Returns every textured face of `model` that samples pixels outside the region of its
+    /// mesh.
+    ///
+    /// Faces of meshes without a region always sample outside of it.
+    pub fn violations(&self, model: &Model) -> Vec<MeshFaceRef> {
+        let mut violations = vec![];
+
+        for (mesh_index, mesh) in model.meshes.iter().enumerate() {
+            let region = self.region(mesh_index);
+
+            for (face_index, face) in mesh.faces.iter().enumerate() {
+                if face.no_texture {
+                    continue;
+                }
+
+                let inside = face
+                    .uv_texels()
+                    .iter()
+                    .all(|texel| region.is_some_and(|rect| rect.contains(*texel)));
+
+                if !inside {
+                    violations.push(MeshFaceRef::new(mesh_index, face_index));
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// Moves the uv-coordinates of each mesh with a region into the top left corner of it,
+    /// together with the pixels they use, and returns the amount of meshes moved.
+    ///
+    /// Meshes are moved by full pixels, so every face keeps showing the same pixels.
+    /// Pixels of meshes without a region stay where they are, all other pixels are cleared to
+    /// black.
+    /// Returns [`PicoError::TextureFull`] and leaves the model untouched if the textured faces of
+    /// a mesh use more space than its region has or a region reaches outside of the texture.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Mesh, Model, Point2D, Rect};
+    /// use picocadrs::budget::TextureAssignment;
+    /// use picocadrs::point;
+    ///
+    /// let mut model = Model::default();
+    /// model.meshes.push(Mesh::plane());
+    /// model.footer.set(point!(0, 0), Color::Red).unwrap();
+    ///
+    /// let mut assignment = TextureAssignment::default();
+    /// assignment.regions.insert(0, Rect::new(point!(32, 8), point!(16, 16)));
+    ///
+    /// assert_eq!(assignment.apply(&mut model).unwrap(), 1);
+    /// assert_eq!(model.meshes[0].faces[0].uv_maps[0].coords, point!(4.0, 1.0));
+    /// assert_eq!(model.footer.pixel(point!(32, 8)), Color::Red);
+    /// assert!(assignment.violations(&model).is_empty());
+    /// ```
+    pub fn apply(&self, model: &mut Model) -> Result<usize, PicoError> {
+        let mut offsets: BTreeMap<usize, (isize, isize)> = BTreeMap::new();
+
+        for (mesh_index, rect) in self.regions.iter() {
+            let Some((min, max)) = mesh_texel_bounds(model, *mesh_index) else {
+                continue;
+            };
+
+            if max.u - min.u + 1 > rect.size.u
+                || max.v - min.v + 1 > rect.size.v
+                || rect.position.u + rect.size.u > Footer::WIDTH
+                || rect.position.v + rect.size.v > Footer::HEIGHT
+            {
+                return Err(PicoError::TextureFull(mesh_index.to_string()));
+            }
+
+            offsets.insert(
+                *mesh_index,
+                (
+                    rect.position.u as isize - min.u as isize,
+                    rect.position.v as isize - min.v as isize,
+                ),
+            );
+        }
+
+        let mut footer = Footer::default();
+
+        // meshes that stay in place are copied first, so moved meshes can take over their pixels.
+        let mut meshes: Vec<usize> = (0..model.meshes.len()).collect();
+        meshes.sort_by_key(|index| offsets.contains_key(index));
+
+        for mesh_index in meshes {
+            let (du, dv) = offsets.get(&mesh_index).copied().unwrap_or((0, 0));
+
+            for face in model.meshes[mesh_index]
+                .faces
+                .iter()
+                .filter(|face| !face.no_texture)
+            {
+                for texel in face.uv_texels() {
+                    let target = point!(
+                        (texel.u as isize + du) as usize,
+                        (texel.v as isize + dv) as usize
+                    );
+                    if let Some(pixel) = footer.get_mut(target) {
+                        *pixel = model.footer.pixel(texel);
+                    }
+                }
+            }
+        }
+
+        let mut moved = 0;
+
+        for (mesh_index, (du, dv)) in offsets {
+            if (du, dv) == (0, 0) {
+                continue;
+            }
+
+            moved += 1;
+            for face in model.meshes[mesh_index].faces.iter_mut() {
+                face.uv_translate(point!(du as f64, dv as f64) * (1.0 / 8.0));
+            }
+        }
+
+        model.footer = footer;
+        Ok(moved)
+    }
+}
+
+/// Returns the smallest and largest coordinates of the pixels the textured faces of a mesh use.
+fn mesh_texel_bounds(model: &Model, mesh_index: usize) -> Option<(Point2D<usize>, Point2D<usize>)> {
+    let texels: Vec<Point2D<usize>> = model.meshes[mesh_index]
+        .faces
+        .iter()
+        .filter(|face| !face.no_texture)
+        .flat_map(|face| face.uv_texels())
+        .collect();
+
+    let first = *texels.first()?;
+
+    Some(texels.iter().fold((first, first), |(min, max), texel| {
+        (min.min(*texel), max.max(*texel))
+    }))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Color, Mesh};
+
+    #[test]
+    fn budget_assign_rows() {
+        let mut budget = TextureBudget::new();
+        budget
+            .request(3, point!(100, 20))
+            .request(1, point!(40, 20))
+            .request(2, point!(10, 0));
+
+        let assignment = budget.assign().unwrap();
+
+        assert_eq!(assignment.regions.len(), 2);
+        assert_eq!(
+            assignment.region(1),
+            Some(Rect::new(point!(0, 20), point!(40, 20)))
+        );
+        assert_eq!(assignment.owner(point!(99, 19)), Some(3));
+        assert_eq!(assignment.owner(point!(100, 0)), None);
+
+        budget.request(4, point!(129, 1));
+        assert!(matches!(
+            budget.assign(),
+            Err(PicoError::TextureFull(mesh)) if mesh == "4"
+        ));
+    }
+
+    #[test]
+    fn budget_model() {
+        let mut model = Model::default();
+        model.meshes.push(Mesh::plane());
+        model.meshes.push(Mesh::plane());
+        model.meshes.push(Mesh::plane());
+        model.meshes[2].faces[0].no_texture = true;
+        model.footer.set(point!(5, 5), Color::Green).unwrap();
+
+        // both planes sample the same pixels.
+        assert_eq!(TextureAssignment::default().violations(&model).len(), 2);
+
+        let assignment = model.assign_texture_regions().unwrap();
+
+        assert_eq!(
+            assignment.region(1),
+            Some(Rect::new(point!(16, 0), point!(16, 16)))
+        );
+        assert!(assignment.region(2).is_none());
+        assert!(assignment.violations(&model).is_empty());
+        assert_eq!(model.footer.pixel(point!(5, 5)), Color::Green);
+        assert_eq!(model.footer.pixel(point!(21, 5)), Color::Green);
+
+        // a region too small for its mesh.
+        let mut small = TextureAssignment::default();
+        small
+            .regions
+            .insert(0, Rect::new(point!(0, 0), point!(8, 8)));
+        let before = model.clone();
+        assert!(small.apply(&mut model).is_err());
+        assert_eq!(model, before);
+    }
+}
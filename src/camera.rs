@@ -0,0 +1,154 @@
+//! For looking at models from arbitrary angles.
+//!
+//! [`CameraParams`] describes a camera orbiting around a point, like the preview in picoCAD.
+//! Points are projected onto the view of a camera with [`Point3D::project`].
+
+use crate::assets::Point3D;
+use crate::point;
+
+/// Distance in front of a perspective camera below which points can't be projected.
+pub const NEAR_PLANE: f64 = 0.01;
+
+/// The way points are projected onto the view of a camera.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Projection {
+    /// Objects further away appear smaller.
+    #[default]
+    Perspective,
+    /// Objects keep their size no matter how far away they are.
+    /// The visible area is as big as the one a perspective camera sees at its target.
+    Orthographic,
+}
+
+/// Camera looking at a point from a given direction.
+///
+/// The direction is given as angles in radians.
+/// `yaw` turns the camera around the y-axis, starting on the positive z-axis.
+/// `pitch` moves the camera above (positive) or below (negative) the point it looks at.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Point3D;
+/// use picocadrs::camera::CameraParams;
+/// use picocadrs::point;
+///
+/// let camera = CameraParams {
+///     yaw: 0.0,
+///     pitch: 0.0,
+///     distance: 5.0,
+///     ..CameraParams::default()
+/// };
+///
+/// assert_eq!(camera.position(), point!(0.0, 0.0, 5.0));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CameraParams {
+    /// Point the camera looks at.
+    pub target: Point3D<f64>,
+    /// Rotation around the y-axis in radians.
+    pub yaw: f64,
+    /// Angle above the target in radians.
+    pub pitch: f64,
+    /// Distance between the camera and its target.
+    pub distance: f64,
+    /// Vertical field of view in radians.
+    pub fov: f64,
+    /// How points are projected.
+    pub projection: Projection,
+}
+
+impl CameraParams {
+    /// Returns the position of the camera.
+    pub fn position(&self) -> Point3D<f64> {
+        // picoCADs y-axis points down, so looking from above means a negative y.
+        point!(
+            self.target.x + self.distance * self.pitch.cos() * self.yaw.sin(),
+            self.target.y - self.distance * self.pitch.sin(),
+            self.target.z + self.distance * self.pitch.cos() * self.yaw.cos()
+        )
+    }
+
+    /// Returns the direction the camera is looking in as a vector of length 1.
+    pub fn forward(&self) -> Point3D<f64> {
+        (self.target - self.position()).normalized()
+    }
+
+    /// Returns the direction pointing to the right side of the view as a vector of length 1.
+    pub fn right(&self) -> Point3D<f64> {
+        point!(0.0, -1.0, 0.0).cross(self.forward()).normalized()
+    }
+
+    /// Returns the direction pointing to the bottom of the view as a vector of length 1.
+    pub fn down(&self) -> Point3D<f64> {
+        self.right().cross(self.forward())
+    }
+
+    /// Returns the direction from `point` towards the camera as a vector of length 1.
+    ///
+    /// For orthographic cameras this is the same for every point.
+    pub fn direction_from(&self, point: Point3D<f64>) -> Point3D<f64> {
+        match self.projection {
+            Projection::Perspective => (self.position() - point).normalized(),
+            Projection::Orthographic => {
+                let forward = self.forward();
+                point!(-forward.x, -forward.y, -forward.z)
+            }
+        }
+    }
+}
+
+impl Default for CameraParams {
+    /// Creates a perspective camera looking at the origin slightly from above and from the side.
+    fn default() -> Self {
+        CameraParams {
+            target: point!(0.0, 0.0, 0.0),
+            yaw: 0.6,
+            pitch: 0.5,
+            distance: 8.0,
+            fov: 60f64.to_radians(),
+            projection: Projection::Perspective,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn camera_position() {
+        let camera = CameraParams {
+            yaw: 0.0,
+            pitch: std::f64::consts::FRAC_PI_2,
+            distance: 2.0,
+            ..CameraParams::default()
+        };
+
+        let position = camera.position();
+        assert!((position.y + 2.0).abs() < 1e-9);
+        assert!(position.z.abs() < 1e-9);
+    }
+
+    #[test]
+    fn camera_axes() {
+        let camera = CameraParams {
+            yaw: 0.0,
+            pitch: 0.0,
+            ..CameraParams::default()
+        };
+
+        assert_eq!(camera.forward(), point!(0.0, 0.0, -1.0));
+        assert_eq!(camera.right(), point!(1.0, 0.0, 0.0));
+        assert_eq!(camera.down(), point!(0.0, 1.0, 0.0));
+
+        let ortho = CameraParams {
+            projection: Projection::Orthographic,
+            ..camera
+        };
+        assert_eq!(
+            ortho.direction_from(point!(3.0, 1.0, 0.0)),
+            point!(0.0, 0.0, 1.0)
+        );
+    }
+}
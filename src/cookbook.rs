@@ -0,0 +1,207 @@
+//! Small recipes for common tasks that take multiple steps.
+//!
+//! Every function in here is built only from the public API of this crate, so besides being
+//! useful on their own they double as examples of how the different parts of the crate fit
+//! together.
+//! Feel free to copy and adjust them if they don't do exactly what you need.
+//!
+//! There is no recipe for importing an OBJ file and fitting the texture onto it yet, since this
+//! crate can only write OBJ files, not read them.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Axis, Mesh, Model, Point3D};
+//! use picocadrs::cookbook;
+//! use picocadrs::point;
+//!
+//! let mut model = Model::default();
+//! let mut arm = Mesh::new("arm".to_string());
+//! arm.position = point!(1.0, 0.0, 0.0);
+//! model.meshes.push(arm);
+//!
+//! let index = cookbook::add_mirrored_copy(&mut model, 0, Axis::X).unwrap();
+//!
+//! assert_eq!(model.meshes[index].name, "arm_mirrored");
+//! assert_eq!(model.meshes[index].position, point!(-1.0, 0.0, 0.0));
+//! ```
+
+use crate::{
+    assets::{Axis, Mesh, Model, Point3D},
+    error::PicoError,
+    export::image::{write_png, PngFormat},
+    optimize::{OptimizeOptions, OptimizeReport},
+};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// Returns a copy of `mesh` mirrored along `axis` around its position.
+///
+/// The uv-coordinates of the copy are mirrored too, so textures (like text or decals) stay
+/// readable on the mirrored side.
+/// The copy is named after the original with `_mirrored` appended.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Axis, Mesh, Point3D};
+/// use picocadrs::cookbook::mirrored_copy;
+/// use picocadrs::point;
+///
+/// let mut mesh = Mesh::new("leg".to_string());
+/// mesh.vertices.push(point!(0.5, 1.0, 0.0));
+///
+/// let copy = mirrored_copy(&mesh, Axis::X);
+///
+/// assert_eq!(copy.name, "leg_mirrored");
+/// assert_eq!(copy.vertices[0], point!(-0.5, 1.0, 0.0));
+/// assert_eq!(mesh.vertices[0], point!(0.5, 1.0, 0.0));
+/// ```
+pub fn mirrored_copy(mesh: &Mesh, axis: Axis) -> Mesh {
+    let mut copy = mesh.clone();
+    copy.name = format!("{}_mirrored", mesh.name);
+    copy.mirror(axis, true);
+
+    copy
+}
+
+/// Adds a [`mirrored_copy`] of the mesh at `mesh_index` to the model, placed on the opposite side
+/// of the models origin.
+///
+/// This is the usual way to build symmetric models: model one side, then mirror it over.
+/// Returns the index of the new mesh or [`None`] if there is no mesh at `mesh_index`.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Axis, Mesh, Model, Point3D};
+/// use picocadrs::cookbook::add_mirrored_copy;
+/// use picocadrs::point;
+///
+/// let mut model = Model::default();
+/// let mut ear = Mesh::new("ear".to_string());
+/// ear.position = point!(0.0, -1.0, 2.0);
+/// model.meshes.push(ear);
+///
+/// assert_eq!(add_mirrored_copy(&mut model, 0, Axis::Z), Some(1));
+/// assert_eq!(model.meshes[1].position, point!(0.0, -1.0, -2.0));
+///
+/// assert_eq!(add_mirrored_copy(&mut model, 5, Axis::Z), None);
+/// ```
+pub fn add_mirrored_copy(model: &mut Model, mesh_index: usize, axis: Axis) -> Option<usize> {
+    let mut copy = mirrored_copy(model.meshes.get(mesh_index)?, axis);
+    *copy.position.axis_mut(axis) = -copy.position.axis(axis);
+
+    model.meshes.push(copy);
+
+    Some(model.meshes.len() - 1)
+}
+
+/// Makes every face of the mesh that points towards `direction` double-sided and returns how
+/// many faces were changed.
+///
+/// Useful for thin geometry like leaves or flags that should be visible from the side facing
+/// the camera and from behind.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Face, Mesh, Point2D, Point3D, UVMap};
+/// use picocadrs::cookbook::double_side_faces_facing;
+/// use picocadrs::point;
+///
+/// let mut mesh = Mesh::new("flag".to_string());
+/// mesh.vertices = vec![point!(0.0, 0.0, 0.0), point!(1.0, 0.0, 0.0), point!(0.0, 1.0, 0.0)];
+///
+/// let mut face = Face::default();
+/// for i in 0..3 {
+///     face.uv_maps.push(UVMap::new(i, point!(0.0, 0.0)));
+/// }
+/// mesh.faces.push(face);
+///
+/// assert_eq!(double_side_faces_facing(&mut mesh, point!(0.0, 0.0, -1.0)), 1);
+/// assert!(mesh.faces[0].double_sided);
+/// ```
+pub fn double_side_faces_facing(mesh: &mut Mesh, direction: Point3D<f64>) -> usize {
+    let mut changed = 0;
+
+    for index in mesh.faces_facing(direction) {
+        let face = &mut mesh.faces[index];
+
+        if !face.double_sided {
+            face.double_sided = true;
+            changed += 1;
+        }
+    }
+
+    changed
+}
+
+/// Saves the texture of the model as an indexed PNG file at `path`.
+///
+/// The models alpha color is transparent and the palette of the image holds exactly the 16
+/// pico-8 colors, so the file can be edited in pixel-art programs and still maps back onto the
+/// palette.
+///
+/// # Example
+///
+/// ```no_run
+/// use picocadrs::assets::Model;
+/// use picocadrs::cookbook::save_texture;
+/// use std::ffi::OsString;
+///
+/// let model = Model::load(OsString::from("house")).unwrap();
+/// save_texture(&model, "house_texture.png").unwrap();
+/// ```
+pub fn save_texture<P: AsRef<Path>>(model: &Model, path: P) -> Result<(), PicoError> {
+    let file = BufWriter::new(File::create(path)?);
+
+    write_png(
+        &model.footer,
+        Some(model.header.alpha),
+        PngFormat::Indexed,
+        file,
+    )
+}
+
+/// Prepares the model for sharing: shrinks the file as much as possible without changing how the
+/// model looks, then brings it into the canonical form of [`Model::normalize_for_sharing`].
+///
+/// Mesh names are kept, since people opening a shared file want to know what each mesh is.
+/// The returned report covers both steps.
+///
+/// Returns [`PicoError::Identifier`] without changing anything if the identifier of the header is
+/// not `picocad`.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Mesh, Model, ProjectPreset};
+/// use picocadrs::cookbook::optimize_for_sharing;
+///
+/// let mut model = Model::new_project("shared".to_string(), ProjectPreset::Plane);
+/// let copy = model.meshes[0].faces[0].clone();
+/// model.meshes[0].faces.push(copy);
+/// model.meshes.push(Mesh::new("antenna".to_string()));
+///
+/// let report = optimize_for_sharing(&mut model).unwrap();
+///
+/// assert_eq!(report.removed_faces, 1);
+/// assert!(report.bytes_saved() > 0);
+/// assert_eq!(model.meshes[0].name, "antenna");
+/// assert_eq!(model.meshes[1].name, "plane");
+/// ```
+pub fn optimize_for_sharing(model: &mut Model) -> Result<OptimizeReport, PicoError> {
+    let mut result = model.clone();
+    let mut report = result.optimize(OptimizeOptions {
+        shorten_names: false,
+        ..OptimizeOptions::default()
+    });
+
+    result.normalize_for_sharing()?;
+    report.bytes_after = result.to_string().len();
+
+    *model = result;
+    Ok(report)
+}
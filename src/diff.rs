@@ -0,0 +1,614 @@
+//! For finding the differences between two versions of a model.
+//!
+//! [`diff`] compares an old and a new model and collects everything that changed in a
+//! [`ModelDiff`], whose [`Display`] implementation prints a readable summary.
+//!
+//! Meshes are matched by name, the first mesh called `"cube"` in the old model belongs to the
+//! first mesh called `"cube"` in the new model and so on.
+//! Renaming a mesh therefore shows up as removing the old and adding a new one.
+//! Vertices and faces of matched meshes are compared by their index.
+//!
+//! Vertex positions, mesh positions and uv-coordinates are only reported as changed if they moved
+//! further than a tolerance.
+//! [`Model::diff`] uses [`DEFAULT_TOLERANCE`], which ignores the rounding done by
+//! [`optimize`](crate::optimize).
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Color, Mesh, Model, Point2D, Point3D};
+//! use picocadrs::point;
+//!
+//! let mut old = Model::default();
+//! old.meshes.push(Mesh::new("cube".to_string()));
+//!
+//! let mut new = old.clone();
+//! new.meshes[0].position = point!(1.0, 0.0, 0.0);
+//! new.meshes.push(Mesh::new("plane".to_string()));
+//! new.footer.set(point!(0, 0), Color::Red).unwrap();
+//!
+//! let diff = old.diff(&new);
+//!
+//! assert_eq!(diff.added_meshes, vec![1]);
+//! assert_eq!(diff.modified_meshes[0].position, Some((point!(0.0, 0.0, 0.0), point!(1.0, 0.0, 0.0))));
+//! assert_eq!(diff.pixels.len(), 1);
+//!
+//! assert_eq!(
+//!     diff.to_string(),
+//!     "added mesh 1\n\
+//!      modified mesh 0 \"cube\":\n  \
+//!        position 0,0,0 -> 1,0,0\n\
+//!      texture: 1 pixel changed"
+//! );
+//! ```
+
+use crate::{
+    assets::{Color, Face, Footer, Header, Mesh, Model, Point2D, Point3D, Rotation},
+    point,
+};
+use std::fmt::{Display, Formatter};
+
+/// Tolerance used by [`Model::diff`].
+///
+/// Coordinates that moved less than this are treated as unchanged.
+pub const DEFAULT_TOLERANCE: f64 = 0.001;
+
+/// Everything that changed between two models.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelDiff {
+    /// The old and new header, if they differ.
+    pub header: Option<(Header, Header)>,
+    /// Indices of meshes in the new model that have no counterpart in the old one.
+    pub added_meshes: Vec<usize>,
+    /// Indices of meshes in the old model that have no counterpart in the new one.
+    pub removed_meshes: Vec<usize>,
+    /// Meshes found in both models that changed or ended up at another index.
+    pub modified_meshes: Vec<MeshDiff>,
+    /// Pixels of the texture that changed.
+    pub pixels: Vec<PixelDiff>,
+}
+
+impl ModelDiff {
+    /// Returns `true` if both models are the same.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Model;
+    ///
+    /// let model = Model::default();
+    ///
+    /// assert!(model.diff(&model).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.header.is_none()
+            && self.added_meshes.is_empty()
+            && self.removed_meshes.is_empty()
+            && self.modified_meshes.is_empty()
+            && self.pixels.is_empty()
+    }
+}
+
+impl Display for ModelDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut lines: Vec<String> = vec![];
+
+        if let Some((old, new)) = &self.header {
+            let mut changes: Vec<String> = vec![];
+
+            if old.name != new.name {
+                changes.push(format!("name {:?} -> {:?}", old.name, new.name));
+            }
+            if old.zoom != new.zoom {
+                changes.push(format!("zoom {} -> {}", old.zoom, new.zoom));
+            }
+            if old.background != new.background {
+                changes.push(format!(
+                    "background {} -> {}",
+                    old.background.as_i32(),
+                    new.background.as_i32()
+                ));
+            }
+            if old.alpha != new.alpha {
+                changes.push(format!(
+                    "alpha {} -> {}",
+                    old.alpha.as_i32(),
+                    new.alpha.as_i32()
+                ));
+            }
+            if old.extensions != new.extensions {
+                changes.push(format!(
+                    "extensions {:?} -> {:?}",
+                    old.extensions, new.extensions
+                ));
+            }
+
+            lines.push(format!("header: {}", changes.join(", ")));
+        }
+
+        for index in self.added_meshes.iter() {
+            lines.push(format!("added mesh {}", index));
+        }
+
+        for index in self.removed_meshes.iter() {
+            lines.push(format!("removed mesh {}", index));
+        }
+
+        for mesh in self.modified_meshes.iter() {
+            lines.push(mesh.to_string());
+        }
+
+        if !self.pixels.is_empty() {
+            lines.push(format!(
+                "texture: {} pixel{} changed",
+                self.pixels.len(),
+                if self.pixels.len() == 1 { "" } else { "s" }
+            ));
+        }
+
+        if lines.is_empty() {
+            write!(f, "no changes")
+        } else {
+            write!(f, "{}", lines.join("\n"))
+        }
+    }
+}
+
+/// Changes of a mesh found in both models.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshDiff {
+    /// Name of the mesh.
+    pub name: String,
+    /// Index of the mesh in the old model.
+    pub old_index: usize,
+    /// Index of the mesh in the new model.
+    pub new_index: usize,
+    /// The old and new position, if the mesh moved.
+    pub position: Option<(Point3D<f64>, Point3D<f64>)>,
+    /// The old and new rotation, if the mesh was rotated.
+    pub rotation: Option<(Rotation, Rotation)>,
+    /// Vertices that moved, were added or removed.
+    pub vertices: Vec<VertexDiff>,
+    /// Faces that changed, were added or removed.
+    pub faces: Vec<FaceDiff>,
+    /// `true` if the [`extras`](Mesh::extras) of the mesh changed.
+    pub extras: bool,
+}
+
+impl MeshDiff {
+    /// Returns `true` if nothing about the mesh changed.
+    pub fn is_empty(&self) -> bool {
+        self.position.is_none()
+            && self.rotation.is_none()
+            && self.vertices.is_empty()
+            && self.faces.is_empty()
+            && !self.extras
+    }
+}
+
+impl Display for MeshDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "modified mesh {} {:?}", self.old_index, self.name)?;
+        if self.old_index != self.new_index {
+            write!(f, " (now {})", self.new_index)?;
+        }
+        write!(f, ":")?;
+
+        if let Some((old, new)) = self.position {
+            write!(f, "\n  position {} -> {}", old, new)?;
+        }
+        if let Some((old, new)) = self.rotation {
+            write!(f, "\n  rotation {} -> {}", old.0, new.0)?;
+        }
+        for vertex in self.vertices.iter() {
+            write!(f, "\n  {}", vertex)?;
+        }
+        for face in self.faces.iter() {
+            write!(f, "\n  {}", face)?;
+        }
+        if self.extras {
+            write!(f, "\n  extras changed")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A vertex that moved, was added or removed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct VertexDiff {
+    /// Index of the vertex in its mesh.
+    pub index: usize,
+    /// Position in the old model, [`None`] if the vertex was added.
+    pub old: Option<Point3D<f64>>,
+    /// Position in the new model, [`None`] if the vertex was removed.
+    pub new: Option<Point3D<f64>>,
+}
+
+impl Display for VertexDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.old, self.new) {
+            (Some(old), Some(new)) => write!(f, "vertex {} moved {} -> {}", self.index, old, new),
+            (None, Some(new)) => write!(f, "vertex {} added {}", self.index, new),
+            (Some(_), None) => write!(f, "vertex {} removed", self.index),
+            (None, None) => write!(f, "vertex {} unchanged", self.index),
+        }
+    }
+}
+
+/// A face that changed, was added or removed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FaceDiff {
+    /// Index of the face in its mesh.
+    pub index: usize,
+    /// What changed.
+    pub changes: Vec<FaceChange>,
+}
+
+impl Display for FaceDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let changes: Vec<String> = self.changes.iter().map(|c| c.to_string()).collect();
+        write!(f, "face {}: {}", self.index, changes.join(", "))
+    }
+}
+
+/// A single change of a face.
+///
+/// Flags hold their new value.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FaceChange {
+    /// The face only exists in the new model.
+    Added,
+    /// The face only exists in the old model.
+    Removed,
+    /// The old and new color.
+    Color(Color, Color),
+    /// See [`Face::double_sided`].
+    DoubleSided(bool),
+    /// See [`Face::no_shading`].
+    NoShading(bool),
+    /// See [`Face::render_priority`].
+    RenderPriority(bool),
+    /// See [`Face::no_texture`].
+    NoTexture(bool),
+    /// The face uses other vertices or uses them in another order.
+    Vertices,
+    /// A uv-coordinate of the face moved.
+    UVs,
+    /// The [`extras`](Face::extras) of the face changed.
+    Extras,
+}
+
+impl Display for FaceChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let on_off = |flag: bool| if flag { "on" } else { "off" };
+
+        match self {
+            FaceChange::Added => write!(f, "added"),
+            FaceChange::Removed => write!(f, "removed"),
+            FaceChange::Color(old, new) => {
+                write!(f, "color {} -> {}", old.as_i32(), new.as_i32())
+            }
+            FaceChange::DoubleSided(flag) => write!(f, "double sided {}", on_off(*flag)),
+            FaceChange::NoShading(flag) => write!(f, "no shading {}", on_off(*flag)),
+            FaceChange::RenderPriority(flag) => write!(f, "render priority {}", on_off(*flag)),
+            FaceChange::NoTexture(flag) => write!(f, "no texture {}", on_off(*flag)),
+            FaceChange::Vertices => write!(f, "vertices changed"),
+            FaceChange::UVs => write!(f, "uvs changed"),
+            FaceChange::Extras => write!(f, "extras changed"),
+        }
+    }
+}
+
+/// A pixel of the texture that changed color.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PixelDiff {
+    /// Position of the pixel.
+    pub coords: Point2D<usize>,
+    /// Color in the old model.
+    pub old: Color,
+    /// Color in the new model.
+    pub new: Color,
+}
+
+/// Compares `old` to `new` and returns everything that changed.
+/// Coordinates that moved less than `tolerance` are treated as unchanged.
+///
+/// It's recommended to use [`Model::diff`].
+pub fn diff(old: &Model, new: &Model, tolerance: f64) -> ModelDiff {
+    let mut result = ModelDiff::default();
+
+    if old.header != new.header {
+        result.header = Some((old.header.clone(), new.header.clone()));
+    }
+
+    let (matches, added, removed) = match_meshes(&old.meshes, &new.meshes);
+    result.added_meshes = added;
+    result.removed_meshes = removed;
+
+    for (old_index, new_index) in matches {
+        let mesh = diff_mesh(
+            &old.meshes[old_index],
+            &new.meshes[new_index],
+            tolerance,
+            (old_index, new_index),
+        );
+
+        if !mesh.is_empty() || old_index != new_index {
+            result.modified_meshes.push(mesh);
+        }
+    }
+
+    for v in 0..Footer::HEIGHT {
+        for u in 0..Footer::WIDTH {
+            let coords = point!(u, v);
+
+            if old.footer.pixel(coords) != new.footer.pixel(coords) {
+                result.pixels.push(PixelDiff {
+                    coords,
+                    old: old.footer.pixel(coords),
+                    new: new.footer.pixel(coords),
+                });
+            }
+        }
+    }
+
+    result
+}
+
+/// Pairs meshes of two models by their name.
+///
+/// Returns the pairs of indices sorted by the new index, the indices of new meshes without a pair
+/// and the indices of old meshes without a pair.
+pub(crate) fn match_meshes(
+    old: &[Mesh],
+    new: &[Mesh],
+) -> (Vec<(usize, usize)>, Vec<usize>, Vec<usize>) {
+    let mut matched = vec![false; old.len()];
+    let mut matches: Vec<(usize, usize)> = vec![];
+    let mut added: Vec<usize> = vec![];
+
+    for (new_index, mesh) in new.iter().enumerate() {
+        let old_index = old
+            .iter()
+            .enumerate()
+            .position(|(i, other)| !matched[i] && other.name == mesh.name);
+
+        match old_index {
+            Some(old_index) => {
+                matched[old_index] = true;
+                matches.push((old_index, new_index));
+            }
+            None => added.push(new_index),
+        }
+    }
+
+    let removed = (0..old.len()).filter(|i| !matched[*i]).collect();
+
+    (matches, added, removed)
+}
+
+/// Compares two versions of a mesh.
+fn diff_mesh(old: &Mesh, new: &Mesh, tolerance: f64, indices: (usize, usize)) -> MeshDiff {
+    let moved = |a: Point3D<f64>, b: Point3D<f64>| (a - b).length() > tolerance;
+
+    let mut result = MeshDiff {
+        name: old.name.clone(),
+        old_index: indices.0,
+        new_index: indices.1,
+        extras: old.extras != new.extras,
+        ..MeshDiff::default()
+    };
+
+    if moved(old.position, new.position) {
+        result.position = Some((old.position, new.position));
+    }
+    if moved(old.rotation.0, new.rotation.0) {
+        result.rotation = Some((old.rotation, new.rotation));
+    }
+
+    for index in 0..old.vertices.len().max(new.vertices.len()) {
+        let vertex = VertexDiff {
+            index,
+            old: old.vertices.get(index).copied(),
+            new: new.vertices.get(index).copied(),
+        };
+
+        let changed = match (vertex.old, vertex.new) {
+            (Some(a), Some(b)) => moved(a, b),
+            _ => true,
+        };
+
+        if changed {
+            result.vertices.push(vertex);
+        }
+    }
+
+    for index in 0..old.faces.len().max(new.faces.len()) {
+        let changes = match (old.faces.get(index), new.faces.get(index)) {
+            (Some(a), Some(b)) => diff_face(a, b, tolerance),
+            (None, _) => vec![FaceChange::Added],
+            (_, None) => vec![FaceChange::Removed],
+        };
+
+        if !changes.is_empty() {
+            result.faces.push(FaceDiff { index, changes });
+        }
+    }
+
+    result
+}
+
+/// Compares two versions of a face.
+fn diff_face(old: &Face, new: &Face, tolerance: f64) -> Vec<FaceChange> {
+    let mut changes: Vec<FaceChange> = vec![];
+
+    if old.color != new.color {
+        changes.push(FaceChange::Color(old.color, new.color));
+    }
+    if old.double_sided != new.double_sided {
+        changes.push(FaceChange::DoubleSided(new.double_sided));
+    }
+    if old.no_shading != new.no_shading {
+        changes.push(FaceChange::NoShading(new.no_shading));
+    }
+    if old.render_priority != new.render_priority {
+        changes.push(FaceChange::RenderPriority(new.render_priority));
+    }
+    if old.no_texture != new.no_texture {
+        changes.push(FaceChange::NoTexture(new.no_texture));
+    }
+
+    let vertices_changed = old.uv_maps.len() != new.uv_maps.len()
+        || old
+            .uv_maps
+            .iter()
+            .zip(new.uv_maps.iter())
+            .any(|(a, b)| a.vertex_index != b.vertex_index);
+
+    if vertices_changed {
+        changes.push(FaceChange::Vertices);
+    } else if old
+        .uv_maps
+        .iter()
+        .zip(new.uv_maps.iter())
+        .any(|(a, b)| (a.coords - b.coords).length() > tolerance)
+    {
+        changes.push(FaceChange::UVs);
+    }
+
+    if old.extras != new.extras {
+        changes.push(FaceChange::Extras);
+    }
+
+    changes
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::UVMap;
+
+    fn triangle() -> Mesh {
+        let mut mesh = Mesh::new("triangle".to_string());
+        mesh.vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(1.0, 0.0, 0.0),
+            point!(0.0, 0.0, 1.0),
+        ];
+        mesh.faces.push(Face {
+            uv_maps: (0..3).map(|i| UVMap::new(i, point!(0.0, 0.0))).collect(),
+            ..Face::default()
+        });
+
+        mesh
+    }
+
+    #[test]
+    fn diff_meshes_matched_by_name() {
+        let mut old = Model::default();
+        old.meshes.push(triangle());
+        old.meshes.push(Mesh::new("plane".to_string()));
+        old.meshes.push(triangle());
+
+        let mut new = old.clone();
+        new.meshes.remove(1);
+        new.meshes.push(Mesh::new("cube".to_string()));
+
+        let diff = old.diff(&new);
+
+        assert_eq!(diff.removed_meshes, vec![1]);
+        assert_eq!(diff.added_meshes, vec![2]);
+
+        // the second triangle moved up in the list, but didn't change.
+        assert_eq!(diff.modified_meshes.len(), 1);
+        assert_eq!(diff.modified_meshes[0].old_index, 2);
+        assert_eq!(diff.modified_meshes[0].new_index, 1);
+        assert!(diff.modified_meshes[0].is_empty());
+    }
+
+    #[test]
+    fn diff_vertices_and_faces() {
+        let mut old = Model::default();
+        old.meshes.push(triangle());
+
+        let mut new = old.clone();
+        let mesh = &mut new.meshes[0];
+        mesh.vertices[0] = point!(0.0004, 0.0, 0.0);
+        mesh.vertices[1] = point!(2.0, 0.0, 0.0);
+        mesh.vertices.push(point!(1.0, 1.0, 1.0));
+        mesh.faces[0].color = Color::Red;
+        mesh.faces[0].double_sided = true;
+        mesh.faces[0].uv_maps[2].coords = point!(1.0, 1.0);
+        mesh.faces.push(Face::default());
+
+        let diff = old.diff(&new);
+        let mesh = &diff.modified_meshes[0];
+
+        assert_eq!(
+            mesh.vertices,
+            vec![
+                VertexDiff {
+                    index: 1,
+                    old: Some(point!(1.0, 0.0, 0.0)),
+                    new: Some(point!(2.0, 0.0, 0.0)),
+                },
+                VertexDiff {
+                    index: 3,
+                    old: None,
+                    new: Some(point!(1.0, 1.0, 1.0)),
+                },
+            ]
+        );
+        assert_eq!(
+            mesh.faces[0].changes,
+            vec![
+                FaceChange::Color(Color::Black, Color::Red),
+                FaceChange::DoubleSided(true),
+                FaceChange::UVs,
+            ]
+        );
+        assert_eq!(mesh.faces[1].changes, vec![FaceChange::Added]);
+
+        assert_eq!(
+            diff.to_string(),
+            "modified mesh 0 \"triangle\":\n  \
+             vertex 1 moved 1,0,0 -> 2,0,0\n  \
+             vertex 3 added 1,1,1\n  \
+             face 0: color 0 -> 8, double sided on, uvs changed\n  \
+             face 1: added"
+        );
+    }
+
+    #[test]
+    fn diff_header_and_texture() {
+        let old = Model::default();
+        let mut new = old.clone();
+        new.header.name = "renamed".to_string();
+        new.footer.set(point!(127, 119), Color::Red).unwrap();
+        new.footer.set(point!(0, 1), Color::Blue).unwrap();
+
+        let diff = old.diff(&new);
+
+        assert_eq!(
+            diff.pixels,
+            vec![
+                PixelDiff {
+                    coords: point!(0, 1),
+                    old: Color::Black,
+                    new: Color::Blue,
+                },
+                PixelDiff {
+                    coords: point!(127, 119),
+                    old: Color::Black,
+                    new: Color::Red,
+                },
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "header: name \"unnamed\" -> \"renamed\"\ntexture: 2 pixels changed"
+        );
+        assert_eq!(old.diff(&old).to_string(), "no changes");
+    }
+}
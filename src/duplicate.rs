@@ -0,0 +1,320 @@
+//! For filling a model with evenly spaced copies of a mesh, like fences, pillars or the spokes of
+//! a wheel.
+//!
+//! - [`array_duplicate`] places copies in a line, each one `offset` away from the one before.
+//! - [`radial_duplicate`] places copies on a circle around an axis, turning each one so they all
+//!   face away from the center the same way.
+//!
+//! [`DuplicateOptions`] decide how copies are named and whether they share the texture pixels of
+//! the original or get their own copy of them, which can then be painted differently.
+//! Copies are appended to the end of the model.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Model, Point3D, ProjectPreset};
+//! use picocadrs::duplicate::DuplicateOptions;
+//! use picocadrs::point;
+//!
+//! let mut model = Model::new_project("fence".to_string(), ProjectPreset::Plane);
+//!
+//! let copies = model
+//!     .array_duplicate("plane", 3, point!(2.0, 0.0, 0.0), DuplicateOptions::default())
+//!     .unwrap();
+//!
+//! assert_eq!(copies, vec![1, 2, 3]);
+//! assert_eq!(model.meshes[3].position, point!(6.0, 0.0, 0.0));
+//! assert_eq!(model.meshes[3].name, "plane_3");
+//! ```
+
+use crate::{
+    assets::{Axis, Mesh, Model, Point3D},
+    error::PicoError,
+    library,
+};
+use std::f64::consts::TAU;
+
+/// What copies do with the pixels of the texture the original mesh uses.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum TextureMode {
+    /// Copies use the same pixels as the original.
+    #[default]
+    Shared,
+    /// Every copy gets its own copy of the pixels, placed into free space of the texture like
+    /// [`Model::insert_asset`] does.
+    Duplicated,
+}
+
+/// Settings used by [`array_duplicate`] and [`radial_duplicate`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DuplicateOptions {
+    /// If true, copies are named after the original with their number appended, like `name_1`,
+    /// `name_2` and so on.
+    /// Otherwise they keep the name of the original.
+    pub numbered_names: bool,
+    /// What copies do with the pixels of the texture the original uses.
+    pub texture: TextureMode,
+}
+
+impl Default for DuplicateOptions {
+    /// Numbered names and a shared texture.
+    fn default() -> Self {
+        DuplicateOptions {
+            numbered_names: true,
+            texture: TextureMode::Shared,
+        }
+    }
+}
+
+/// Adds `count` copies of the first mesh called `mesh_name`, the first one `offset` away from
+/// the original and every following one `offset` away from the one before, and returns their
+/// indices.
+///
+/// Returns [`PicoError::MeshNotFound`] if there is no such mesh and [`PicoError::TextureFull`] if
+/// the texture is duplicated and there is not enough free space, leaving the model unchanged.
+///
+/// It's recommended to use [`Model::array_duplicate`].
+pub fn array_duplicate(
+    model: &mut Model,
+    mesh_name: &str,
+    count: usize,
+    offset: Point3D<f64>,
+    options: DuplicateOptions,
+) -> Result<Vec<usize>, PicoError> {
+    duplicate(model, mesh_name, count, options, |number, mesh| {
+        mesh.position += offset * number as f64;
+    })
+}
+
+/// Turns the first mesh called `mesh_name` into the first of `count` evenly spaced items on a
+/// circle of `radius` around its position, adds the other `count - 1` as copies and returns
+/// their indices.
+///
+/// The circle lies flat on the plane of the other two axes, perpendicular to `axis`.
+/// The original is moved `radius` along the axis after `axis` (y for x, z for y and x for z),
+/// every copy is placed a bit further around the circle and turned around `axis` by the same
+/// angle, so each one faces the center the same way.
+/// A `count` of 0 leaves the model unchanged.
+///
+/// Returns [`PicoError::MeshNotFound`] if there is no such mesh and [`PicoError::TextureFull`] if
+/// the texture is duplicated and there is not enough free space, leaving the model unchanged.
+///
+/// It's recommended to use [`Model::radial_duplicate`].
+pub fn radial_duplicate(
+    model: &mut Model,
+    mesh_name: &str,
+    count: usize,
+    axis: Axis,
+    radius: f64,
+    options: DuplicateOptions,
+) -> Result<Vec<usize>, PicoError> {
+    if count == 0 {
+        return Ok(vec![]);
+    }
+
+    let (first, second) = match axis {
+        Axis::X => (Axis::Y, Axis::Z),
+        Axis::Y => (Axis::Z, Axis::X),
+        Axis::Z => (Axis::X, Axis::Y),
+    };
+
+    let mut result = model.clone();
+    let center = result
+        .meshes
+        .iter()
+        .find(|mesh| mesh.name == mesh_name)
+        .ok_or_else(|| PicoError::MeshNotFound(mesh_name.to_string()))?
+        .position;
+
+    let place = move |number: usize, mesh: &mut Mesh| {
+        let angle = TAU * number as f64 / count as f64;
+        let (sin, cos) = angle.sin_cos();
+
+        for vertex in mesh.vertices.iter_mut() {
+            let (a, b) = (vertex.axis(first), vertex.axis(second));
+            *vertex.axis_mut(first) = a * cos - b * sin;
+            *vertex.axis_mut(second) = a * sin + b * cos;
+        }
+
+        mesh.position = center;
+        *mesh.position.axis_mut(first) += radius * cos;
+        *mesh.position.axis_mut(second) += radius * sin;
+    };
+
+    if let Some(original) = result.meshes.iter_mut().find(|mesh| mesh.name == mesh_name) {
+        place(0, original);
+    }
+    let copies = duplicate(
+        &mut result,
+        mesh_name,
+        count.saturating_sub(1),
+        options,
+        place,
+    )?;
+
+    *model = result;
+    Ok(copies)
+}
+
+/// Adds `count` copies of the first mesh called `mesh_name`, each one changed by `place` with
+/// its number starting at 1, and returns their indices.
+///
+/// The model is only changed if every copy could be added.
+fn duplicate<F>(
+    model: &mut Model,
+    mesh_name: &str,
+    count: usize,
+    options: DuplicateOptions,
+    place: F,
+) -> Result<Vec<usize>, PicoError>
+where
+    F: Fn(usize, &mut Mesh),
+{
+    let index = model
+        .meshes
+        .iter()
+        .position(|mesh| mesh.name == mesh_name)
+        .ok_or_else(|| PicoError::MeshNotFound(mesh_name.to_string()))?;
+
+    let mut result = model.clone();
+    let mut copies = Vec::with_capacity(count);
+
+    let asset = match options.texture {
+        TextureMode::Shared => None,
+        TextureMode::Duplicated => library::extract(model, index),
+    };
+
+    for number in 1..=count {
+        let mut copy = model.meshes[index].clone();
+        if options.numbered_names {
+            copy.name = format!("{}_{}", copy.name, number);
+        }
+
+        match &asset {
+            Some(asset) => {
+                let mut asset = asset.clone();
+                asset.mesh.name = copy.name;
+                place(number, &mut asset.mesh);
+                copies.push(library::instantiate(&mut result, &asset)?);
+            }
+            None => {
+                place(number, &mut copy);
+                result.meshes.push(copy);
+                copies.push(result.meshes.len() - 1);
+            }
+        }
+    }
+
+    *model = result;
+    Ok(copies)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::ProjectPreset;
+    use crate::point;
+
+    fn plane_model() -> Model {
+        Model::new_project("copies".to_string(), ProjectPreset::Plane)
+    }
+
+    #[test]
+    fn duplicate_array() {
+        let mut model = plane_model();
+        model.meshes[0].position = point!(0.0, -1.0, 0.0);
+
+        let options = DuplicateOptions {
+            numbered_names: false,
+            ..DuplicateOptions::default()
+        };
+        let copies = model
+            .array_duplicate("plane", 2, point!(0.0, 0.0, 1.5), options)
+            .unwrap();
+
+        assert_eq!(copies, vec![1, 2]);
+        assert_eq!(model.meshes[1].position, point!(0.0, -1.0, 1.5));
+        assert_eq!(model.meshes[2].position, point!(0.0, -1.0, 3.0));
+        assert_eq!(model.meshes[2].name, "plane");
+        assert_eq!(model.meshes[2].faces, model.meshes[0].faces);
+
+        assert!(model
+            .array_duplicate("plane", 0, point!(1.0, 0.0, 0.0), options)
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            model.array_duplicate("tree", 1, point!(1.0, 0.0, 0.0), options),
+            Err(PicoError::MeshNotFound(name)) if name == "tree"
+        ));
+    }
+
+    #[test]
+    fn duplicate_radial() {
+        let mut model = plane_model();
+        model.meshes[0].position = point!(1.0, 0.0, 0.0);
+        model.meshes[0].vertices = vec![point!(0.0, 0.0, 1.0), point!(0.0, 0.0, 2.0)];
+        model.meshes[0].faces.clear();
+
+        let copies = model
+            .radial_duplicate("plane", 4, Axis::Y, 2.0, DuplicateOptions::default())
+            .unwrap();
+
+        assert_eq!(copies, vec![1, 2, 3]);
+
+        let round = |point: Point3D<f64>| point!(point.x.round(), point.y, point.z.round());
+        let positions: Vec<Point3D<f64>> = model
+            .meshes
+            .iter()
+            .map(|mesh| round(mesh.position))
+            .collect();
+        assert_eq!(
+            positions,
+            vec![
+                point!(1.0, 0.0, 2.0),
+                point!(3.0, 0.0, 0.0),
+                point!(1.0, 0.0, -2.0),
+                point!(-1.0, 0.0, 0.0)
+            ]
+        );
+
+        // every copy still points away from the center.
+        assert_eq!(model.meshes[0].vertices[1], point!(0.0, 0.0, 2.0));
+        assert_eq!(round(model.meshes[1].vertices[1]), point!(2.0, 0.0, 0.0));
+        assert_eq!(round(model.meshes[3].vertices[1]), point!(-2.0, 0.0, 0.0));
+        assert_eq!(model.meshes[3].name, "plane_3");
+
+        let before = model.clone();
+        assert!(model
+            .radial_duplicate("tree", 4, Axis::X, 1.0, DuplicateOptions::default())
+            .is_err());
+        assert_eq!(model, before);
+    }
+
+    #[test]
+    fn duplicate_texture() {
+        let mut model = plane_model();
+        let options = DuplicateOptions {
+            texture: TextureMode::Duplicated,
+            ..DuplicateOptions::default()
+        };
+
+        let copies = model
+            .array_duplicate("plane", 1, point!(2.0, 0.0, 0.0), options)
+            .unwrap();
+
+        let original = &model.meshes[0].faces[0];
+        let copy = &model.meshes[copies[0]].faces[0];
+        assert_ne!(original.uv_maps, copy.uv_maps);
+        for (a, b) in original.uv_texels().into_iter().zip(copy.uv_texels()) {
+            assert_eq!(model.footer.pixel(a), model.footer.pixel(b));
+        }
+
+        // there is no room for this many copies of the texture.
+        let before = model.clone();
+        assert!(matches!(
+            model.array_duplicate("plane", 100, point!(2.0, 0.0, 0.0), options),
+            Err(PicoError::TextureFull(_))
+        ));
+        assert_eq!(model, before);
+    }
+}
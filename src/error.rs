@@ -1,4 +1,6 @@
 use crate::assets::Point2D;
+use alloc::string::String;
+#[cfg(feature = "std")]
 use rlua::Error as LuaError;
 use thiserror::Error;
 
@@ -16,18 +18,66 @@ pub enum PicoError {
     FaceUVMapLength(usize, usize),
     #[error("found {0} table elements (expected {1})")]
     TableLength(usize, usize),
+    #[error("table element {0} is not a number")]
+    TableNumber(usize),
     #[error("could not parse mesh field {0}")]
     MeshField(String),
+    #[error("could not parse face field {0}")]
+    FaceField(String),
     #[error("could not parse meshes from file")]
     MeshTable,
     #[error("could not split file properly ({0})")]
     Split(String),
     #[error("couldn't get home directory")]
     NoHomeDirectory,
+    #[cfg(feature = "std")]
     #[error("invalid vertex index")]
     Lua(#[from] LuaError),
+    #[cfg(feature = "std")]
     #[error("io error")]
     IO(#[from] std::io::Error),
+    #[cfg(feature = "std")]
+    #[error("png encoding error")]
+    Png(#[from] png::EncodingError),
+    #[cfg(feature = "watch")]
+    #[error("file watcher error")]
+    Watch(#[from] notify::Error),
+    #[error("no project named {0} in registry")]
+    UnknownProject(String),
+    #[error("project {0} is not associated with a file")]
+    NoProjectPath(String),
+    #[error("project {0} does not exist")]
+    ProjectNotFound(String),
+    #[error("project {0} already exists")]
+    ProjectExists(String),
+    #[error("{0:?} can't be used as a project name")]
+    ProjectName(String),
+    #[error("vertex index {0} out of range (mesh has {1} vertices)")]
+    VertexIndex(usize, usize),
+    #[error("mesh index {0} out of range (model has {1} meshes)")]
+    MeshIndex(usize, usize),
+    #[error("face index {0} out of range (mesh has {1} faces)")]
+    FaceIndex(usize, usize),
+    #[error("lua value of type {0} can't be stored")]
+    LuaValue(String),
+    #[error("could not parse lua table: {0}")]
+    LuaSyntax(String),
+    #[error("image data has {0} bytes (expected {1})")]
+    ImageSize(usize, usize),
+    #[error("could not parse asset: {0}")]
+    Asset(String),
+    #[error("no free texture space for mesh {0}")]
+    TextureFull(String),
+    #[error("no mesh named {0}")]
+    MeshNotFound(String),
     #[error("index out of range: {0:?} (expected < {1:?})")]
     IndexUSIZE(Point2D<usize>, Point2D<usize>),
+    #[error("could not read vox file: {0}")]
+    Vox(String),
+    #[error("could not read pico-8 cart: {0}")]
+    Pico8(String),
+    #[error("no exporter for file extension {0:?}")]
+    UnknownFormat(String),
+    #[error("could not decode base64: {0}")]
+    Base64(String),
 }
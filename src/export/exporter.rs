@@ -0,0 +1,314 @@
+//! A common interface for every format models can be exported to.
+//!
+//! Each format implements [`ModelExporter`] and is registered in an [`ExporterRegistry`] under
+//! the file extensions it handles.
+//! The registry picks the exporter by the extension of the path a model is exported to, so tools
+//! only need one call no matter the format, see [`files::export`](crate::files::export).
+//!
+//! Formats from other crates can be added with [`ExporterRegistry::register`].
+//! Exporters registered later take precedence, so built-in formats can be replaced as well.
+//!
+//! Built-in exporters, as registered by [`ExporterRegistry::default`]:
+//!
+//! - [`ProjectExporter`]: `txt`, the picoCAD project file itself.
+//! - [`PngExporter`]: `png`, the texture of the model.
+//! - `SvgExporter`: `svg`, a rendered view of the model (requires the `svg` feature).
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::Model;
+//! use picocadrs::error::PicoError;
+//! use picocadrs::export::exporter::{ExporterRegistry, ModelExporter};
+//! use std::path::Path;
+//!
+//! struct NameExporter;
+//!
+//! impl ModelExporter for NameExporter {
+//!     fn name(&self) -> &str {
+//!         "name"
+//!     }
+//!
+//!     fn extensions(&self) -> &[&str] {
+//!         &["name"]
+//!     }
+//!
+//!     fn export(&self, model: &Model, path: &Path) -> Result<(), PicoError> {
+//!         std::fs::write(path, &model.header.name)?;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut registry = ExporterRegistry::default();
+//! registry.register(NameExporter);
+//!
+//! assert_eq!(registry.find("NAME").unwrap().name(), "name");
+//! assert_eq!(registry.find("png").unwrap().name(), "png");
+//! assert!(registry.find("fbx").is_none());
+//! ```
+
+use super::image::{write_png, PngFormat};
+#[cfg(feature = "svg")]
+use super::svg::{render_svg, SvgOptions};
+use crate::{assets::Model, error::PicoError};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// A format models can be exported to.
+pub trait ModelExporter: Send + Sync {
+    /// Returns a short name of the format, used to tell exporters apart in user interfaces.
+    fn name(&self) -> &str;
+
+    /// Returns the file extensions this exporter handles, without the leading dot.
+    fn extensions(&self) -> &[&str];
+
+    /// Writes the model into the file at `path`.
+    fn export(&self, model: &Model, path: &Path) -> Result<(), PicoError>;
+}
+
+/// Writes models as picoCAD project files.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct ProjectExporter;
+
+impl ModelExporter for ProjectExporter {
+    fn name(&self) -> &str {
+        "picoCAD"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["txt"]
+    }
+
+    fn export(&self, model: &Model, path: &Path) -> Result<(), PicoError> {
+        std::fs::write(path, model.to_string())?;
+        Ok(())
+    }
+}
+
+/// Writes the texture of models as PNG images, with the alpha color of the model being
+/// transparent.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct PngExporter {
+    /// Kind of PNG image written.
+    pub format: PngFormat,
+}
+
+impl ModelExporter for PngExporter {
+    fn name(&self) -> &str {
+        "png"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["png"]
+    }
+
+    fn export(&self, model: &Model, path: &Path) -> Result<(), PicoError> {
+        write_png(
+            &model.footer,
+            Some(model.header.alpha),
+            self.format,
+            BufWriter::new(File::create(path)?),
+        )
+    }
+}
+
+/// Writes rendered views of models as SVG images.
+///
+/// Only available with the `svg` feature.
+#[cfg(feature = "svg")]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct SvgExporter {
+    /// Settings the model is rendered with.
+    pub options: SvgOptions,
+}
+
+#[cfg(feature = "svg")]
+impl ModelExporter for SvgExporter {
+    fn name(&self) -> &str {
+        "svg"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["svg"]
+    }
+
+    fn export(&self, model: &Model, path: &Path) -> Result<(), PicoError> {
+        std::fs::write(path, render_svg(model, &self.options))?;
+        Ok(())
+    }
+}
+
+/// A collection of [`ModelExporter`]s looked up by file extension.
+///
+/// [`ExporterRegistry::new`] creates an empty registry, [`ExporterRegistry::default`] one
+/// holding every built-in exporter.
+pub struct ExporterRegistry {
+    exporters: Vec<Box<dyn ModelExporter>>,
+}
+
+impl ExporterRegistry {
+    /// Creates a registry without any exporters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::export::exporter::ExporterRegistry;
+    ///
+    /// assert_eq!(ExporterRegistry::new().len(), 0);
+    /// ```
+    pub fn new() -> ExporterRegistry {
+        ExporterRegistry { exporters: vec![] }
+    }
+
+    /// Adds an exporter to the registry.
+    ///
+    /// If an extension is handled by multiple exporters, the one registered last is used.
+    pub fn register<E: ModelExporter + 'static>(&mut self, exporter: E) -> &mut Self {
+        self.exporters.push(Box::new(exporter));
+        self
+    }
+
+    /// Returns the exporter used for files with the given extension.
+    /// Extensions are compared ignoring case and may start with a dot.
+    ///
+    /// Returns [`None`] if no exporter handles that extension.
+    pub fn find(&self, extension: &str) -> Option<&dyn ModelExporter> {
+        let extension = extension.trim_start_matches('.');
+
+        self.exporters
+            .iter()
+            .rev()
+            .find(|exporter| {
+                exporter
+                    .extensions()
+                    .iter()
+                    .any(|handled| handled.eq_ignore_ascii_case(extension))
+            })
+            .map(|exporter| exporter.as_ref())
+    }
+
+    /// Writes the model into the file at `path` using the exporter for its extension.
+    ///
+    /// Returns [`PicoError::UnknownFormat`] if the path has no extension or no exporter handles
+    /// it.
+    pub fn export<P: AsRef<Path>>(&self, model: &Model, path: P) -> Result<(), PicoError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        self.find(extension.as_str())
+            .ok_or(PicoError::UnknownFormat(extension))?
+            .export(model, path)
+    }
+
+    /// Returns an iterator over every registered exporter in the order they were registered.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn ModelExporter> {
+        self.exporters.iter().map(|exporter| exporter.as_ref())
+    }
+
+    /// Returns the amount of registered exporters.
+    pub fn len(&self) -> usize {
+        self.exporters.len()
+    }
+
+    /// Returns `true` if no exporters are registered.
+    pub fn is_empty(&self) -> bool {
+        self.exporters.is_empty()
+    }
+}
+
+impl Default for ExporterRegistry {
+    /// Returns a registry holding every built-in exporter with its default settings.
+    fn default() -> Self {
+        let mut registry = ExporterRegistry::new();
+        registry.register(ProjectExporter);
+        registry.register(PngExporter::default());
+        #[cfg(feature = "svg")]
+        registry.register(SvgExporter::default());
+
+        registry
+    }
+}
+
+impl std::fmt::Debug for ExporterRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.exporters.iter().map(|exporter| exporter.name()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    struct UpperExporter;
+
+    impl ModelExporter for UpperExporter {
+        fn name(&self) -> &str {
+            "upper"
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt", "up"]
+        }
+
+        fn export(&self, model: &Model, path: &Path) -> Result<(), PicoError> {
+            std::fs::write(path, model.to_string().to_uppercase())?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn exporter_find() {
+        let mut registry = ExporterRegistry::default();
+
+        assert_eq!(registry.find("txt").unwrap().name(), "picoCAD");
+        assert_eq!(registry.find(".PNG").unwrap().name(), "png");
+        assert!(registry.find("").is_none());
+
+        registry.register(UpperExporter);
+        assert_eq!(registry.find("txt").unwrap().name(), "upper");
+        assert_eq!(registry.find("up").unwrap().name(), "upper");
+        assert_eq!(
+            registry.iter().last().map(|exporter| exporter.name()),
+            Some("upper")
+        );
+    }
+
+    #[test]
+    fn exporter_export() {
+        let dir = std::env::temp_dir().join("picocadrs_exporter_export");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let registry = ExporterRegistry::default();
+        let model = Model::default();
+
+        registry.export(&model, dir.join("model.txt")).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.join("model.txt")).unwrap(),
+            model.to_string()
+        );
+
+        registry.export(&model, dir.join("texture.png")).unwrap();
+        assert_eq!(
+            &std::fs::read(dir.join("texture.png")).unwrap()[1..4],
+            b"PNG"
+        );
+
+        assert!(matches!(
+            registry.export(&model, dir.join("model.fbx")),
+            Err(PicoError::UnknownFormat(extension)) if extension == "fbx"
+        ));
+        assert!(matches!(
+            registry.export(&model, dir.join("model")),
+            Err(PicoError::UnknownFormat(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
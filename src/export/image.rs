@@ -0,0 +1,560 @@
+//! For exporting the texture of a model as an image.
+//!
+//! Textures are written as 128x120 pixel PNG files, one pixel for every character in the
+//! [`Footer`].
+//! They can either be written as regular RGBA images or as indexed images whose palette consists
+//! of exactly the 16 pico-8 colors (see [`PngFormat`]).
+//!
+//! [`texture_template`] creates an upscaled image of the texture with the uv-map of every face
+//! drawn on top, which helps painting textures in other programs.
+
+use super::MESH_COLORS;
+use crate::{
+    assets::{Color, ExtendedColor, Footer, Model, Point2D},
+    error::PicoError,
+    point,
+};
+use std::io::Write;
+
+/// The different ways the texture can be stored in a PNG file.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum PngFormat {
+    /// Every pixel is stored as its rgb values and an alpha channel.
+    #[default]
+    Rgba,
+    /// Every pixel is stored as the index of its color in a palette holding the 16 pico-8
+    /// colors.
+    /// The index of a color in the palette is the same as its [`as_i32`](Color::as_i32)
+    /// representation, so palette semantics are preserved for other tools.
+    ///
+    /// Pixels only take up 4 bits each, which keeps the files tiny.
+    Indexed,
+}
+
+/// Writes the texture stored in `footer` as a PNG image into `writer`.
+///
+/// Every pixel that has the color `alpha` will be fully transparent, the same way picoCAD renders
+/// the texture on faces.
+/// Pass [`None`] to keep every pixel opaque.
+///
+/// `format` decides how pixels are stored in the image.
+/// [`Invalid`](Color::Invalid) colors are stored as black.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Color, Footer};
+/// use picocadrs::export::image::{write_png, PngFormat};
+///
+/// let mut buffer: Vec<u8> = vec![];
+/// write_png(&Footer::default(), Some(Color::Black), PngFormat::Indexed, &mut buffer).unwrap();
+///
+/// // PNG signature
+/// assert_eq!(&buffer[1..4], b"PNG");
+/// ```
+pub fn write_png<W: Write>(
+    footer: &Footer,
+    alpha: Option<Color>,
+    format: PngFormat,
+    writer: W,
+) -> Result<(), PicoError> {
+    let mut encoder = png::Encoder::new(writer, Footer::WIDTH as u32, Footer::HEIGHT as u32);
+
+    let data = match format {
+        PngFormat::Rgba => {
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+
+            rgba_data(footer, alpha)
+        }
+        PngFormat::Indexed => {
+            encoder.set_color(png::ColorType::Indexed);
+            encoder.set_depth(png::BitDepth::Four);
+            encoder.set_palette(
+                (0..16)
+                    .flat_map(|i| {
+                        let (r, g, b) = Color::from(i).as_rgb();
+                        [r, g, b]
+                    })
+                    .collect::<Vec<u8>>(),
+            );
+
+            if let Some(alpha) = alpha {
+                let mut transparency = vec![255; alpha.as_i32() as usize + 1];
+                transparency[alpha.as_i32() as usize] = 0;
+                encoder.set_trns(transparency);
+            }
+
+            indexed_data(footer)
+        }
+    };
+
+    let mut png_writer = encoder.write_header()?;
+    png_writer.write_image_data(&data)?;
+
+    Ok(())
+}
+
+/// Writes a texture using the full pico-8 palette as a RGBA PNG image into `writer`.
+///
+/// `pixels` holds the 128x120 pixels of the texture row by row, like the ones returned by
+/// [`Quantizer::quantize_extended`](crate::texture::Quantizer::quantize_extended).
+/// If `allow_secret` is false, secret colors are replaced by the base color looking the closest
+/// to them (see [`ExtendedColor::to_base`]).
+/// Every pixel that has the color `alpha` will be fully transparent.
+///
+/// Returns [`PicoError::ImageSize`] if `pixels` doesn't hold exactly 128x120 pixels.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::ExtendedColor;
+/// use picocadrs::export::image::write_extended_png;
+///
+/// let pixels = vec![ExtendedColor::Secret(0); 128 * 120];
+///
+/// let mut buffer: Vec<u8> = vec![];
+/// write_extended_png(&pixels, None, true, &mut buffer).unwrap();
+///
+/// assert_eq!(&buffer[1..4], b"PNG");
+/// assert!(write_extended_png(&pixels[1..], None, true, &mut buffer).is_err());
+/// ```
+pub fn write_extended_png<W: Write>(
+    pixels: &[ExtendedColor],
+    alpha: Option<ExtendedColor>,
+    allow_secret: bool,
+    writer: W,
+) -> Result<(), PicoError> {
+    let expected = Footer::WIDTH * Footer::HEIGHT;
+
+    if pixels.len() != expected {
+        return Err(PicoError::ImageSize(pixels.len(), expected));
+    }
+
+    let mut encoder = png::Encoder::new(writer, Footer::WIDTH as u32, Footer::HEIGHT as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut data: Vec<u8> = Vec::with_capacity(expected * 4);
+
+    for pixel in pixels.iter() {
+        let a = if Some(*pixel) == alpha { 0 } else { 255 };
+        let (r, g, b) = if allow_secret {
+            pixel.as_rgb()
+        } else {
+            pixel.to_base().as_rgb()
+        };
+
+        data.extend_from_slice(&[r, g, b, a]);
+    }
+
+    let mut png_writer = encoder.write_header()?;
+    png_writer.write_image_data(&data)?;
+
+    Ok(())
+}
+
+/// An image of the texture with the uv-map of every face drawn on top, created by
+/// [`texture_template`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TemplateImage {
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    /// Color of every pixel, row by row.
+    pub pixels: Vec<Color>,
+}
+
+impl TemplateImage {
+    /// Returns the color of the pixel at `coords`, or [`None`] if it is outside of the image.
+    pub fn get(&self, coords: Point2D<u32>) -> Option<Color> {
+        if coords.u >= self.width || coords.v >= self.height {
+            return None;
+        }
+
+        self.pixels
+            .get((coords.v * self.width + coords.u) as usize)
+            .copied()
+    }
+
+    /// Writes the image as a RGBA PNG image into `writer`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Model;
+    ///
+    /// let mut buffer: Vec<u8> = vec![];
+    /// Model::default()
+    ///     .texture_template_image(2)
+    ///     .write_png(&mut buffer)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(&buffer[1..4], b"PNG");
+    /// ```
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), PicoError> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let data: Vec<u8> = self
+            .pixels
+            .iter()
+            .flat_map(|color| {
+                let (r, g, b) = color.as_rgb();
+                [r, g, b, 255]
+            })
+            .collect();
+
+        let mut png_writer = encoder.write_header()?;
+        png_writer.write_image_data(&data)?;
+
+        Ok(())
+    }
+
+    /// Sets the pixel at the given position if it is inside of the image.
+    fn set(&mut self, u: i64, v: i64, color: Color) {
+        if u >= 0 && v >= 0 && u < self.width as i64 && v < self.height as i64 {
+            self.pixels[(v * self.width as i64 + u) as usize] = color;
+        }
+    }
+
+    /// Draws a line between two points.
+    /// Only the part inside of the image is drawn, so broken uv-coordinates far outside of the
+    /// texture don't take forever.
+    fn line(&mut self, from: Point2D<f64>, to: Point2D<f64>, color: Color) {
+        let Some((from, to)) = self.clip(from, to) else {
+            return;
+        };
+
+        let steps = (to - from).length().ceil().max(1.0) as usize;
+
+        for step in 0..=steps {
+            let point = from.lerp(to, step as f64 / steps as f64);
+            self.set(point.u.floor() as i64, point.v.floor() as i64, color);
+        }
+    }
+
+    /// Cuts the line between two points down to the part inside of the image (Liang-Barsky).
+    /// Returns [`None`] if no part of it is inside or a coordinate is not finite.
+    fn clip(&self, from: Point2D<f64>, to: Point2D<f64>) -> Option<(Point2D<f64>, Point2D<f64>)> {
+        let delta = to - from;
+        let mut start: f64 = 0.0;
+        let mut end: f64 = 1.0;
+
+        for (p, q) in [
+            (-delta.u, from.u),
+            (delta.u, self.width as f64 - from.u),
+            (-delta.v, from.v),
+            (delta.v, self.height as f64 - from.v),
+        ] {
+            if !p.is_finite() || !q.is_finite() {
+                return None;
+            }
+
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else if p < 0.0 {
+                start = start.max(q / p);
+            } else {
+                end = end.min(q / p);
+            }
+        }
+
+        if start > end {
+            return None;
+        }
+
+        Some((from.lerp(to, start), from.lerp(to, end)))
+    }
+
+    /// Writes `text` centered on `center`, with every pixel of the font being `size` pixels big.
+    /// Only digits and `.` are drawn, other characters are left blank.
+    /// Nothing is written if `center` is outside of the image.
+    fn text(&mut self, text: &str, center: Point2D<f64>, size: i64, color: Color) {
+        if !(0.0..self.width as f64).contains(&center.u)
+            || !(0.0..self.height as f64).contains(&center.v)
+        {
+            return;
+        }
+
+        let width = text.chars().count() as i64 * 4 - 1;
+        let left = center.u.floor() as i64 - width * size / 2;
+        let top = center.v.floor() as i64 - 5 * size / 2;
+
+        for (i, character) in text.chars().enumerate() {
+            let glyph = match character {
+                '0'..='9' => DIGITS[character as usize - '0' as usize],
+                '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+                _ => continue,
+            };
+
+            for (row, bits) in glyph.iter().enumerate() {
+                for column in 0..3 {
+                    if bits & (0b100 >> column) == 0 {
+                        continue;
+                    }
+
+                    for du in 0..size {
+                        for dv in 0..size {
+                            self.set(
+                                left + (i as i64 * 4 + column) * size + du,
+                                top + row as i64 * size + dv,
+                                color,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 3x5 pixel font for the digits `0` to `9`, one row per entry with the leftmost pixel in the
+/// highest bit.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b011, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Draws the texture of the model scaled up by `scale` and outlines the uv-map of every face on
+/// top of it, so textures can be painted in other programs.
+///
+/// Faces are outlined in a color picked by the index of their mesh and labeled with
+/// `mesh.face`, the index of their mesh and their index inside of it.
+/// Labels grow with `scale`, a `scale` of `0` is treated as `1`.
+///
+/// It's recommended to use [`Model::texture_template_image`].
+pub fn texture_template(model: &Model, scale: u32) -> TemplateImage {
+    let scale = scale.max(1);
+    let mut image = TemplateImage {
+        width: Footer::WIDTH as u32 * scale,
+        height: Footer::HEIGHT as u32 * scale,
+        pixels: vec![],
+    };
+
+    for v in 0..image.height {
+        for u in 0..image.width {
+            image.pixels.push(
+                model
+                    .footer
+                    .pixel(point!((u / scale) as usize, (v / scale) as usize)),
+            );
+        }
+    }
+
+    // uv units are 8 pixels wide.
+    let pixels_per_unit = 8.0 * scale as f64;
+    let font_size = (scale as i64 / 4).max(1);
+
+    for (mesh_index, mesh) in model.meshes.iter().enumerate() {
+        let color = MESH_COLORS[mesh_index % MESH_COLORS.len()];
+
+        for (face_index, face) in mesh.faces.iter().enumerate() {
+            if face.uv_maps.is_empty() {
+                continue;
+            }
+
+            let points: Vec<Point2D<f64>> = face
+                .uv_maps
+                .iter()
+                .map(|uv_map| uv_map.coords * pixels_per_unit)
+                .collect();
+
+            for (i, point) in points.iter().enumerate() {
+                image.line(*point, points[(i + 1) % points.len()], color);
+            }
+
+            let center = points
+                .iter()
+                .fold(point!(0.0, 0.0), |sum, point| sum + *point)
+                / points.len() as f64;
+
+            image.text(
+                &format!("{}.{}", mesh_index, face_index),
+                center,
+                font_size,
+                color,
+            );
+        }
+    }
+
+    image
+}
+
+/// Returns the texture as rgba values, 4 bytes per pixel.
+fn rgba_data(footer: &Footer, alpha: Option<Color>) -> Vec<u8> {
+    let mut data: Vec<u8> = Vec::with_capacity(Footer::WIDTH * Footer::HEIGHT * 4);
+
+    for v in 0..Footer::HEIGHT {
+        for u in 0..Footer::WIDTH {
+            let color = footer.pixel(point!(u, v));
+            let (r, g, b) = color.as_rgb();
+            let a = if Some(color) == alpha { 0 } else { 255 };
+
+            data.extend_from_slice(&[r, g, b, a]);
+        }
+    }
+
+    data
+}
+
+/// Returns the texture as palette indices, 2 pixels per byte with the left pixel in the high
+/// nibble.
+fn indexed_data(footer: &Footer) -> Vec<u8> {
+    let mut data: Vec<u8> = Vec::with_capacity(Footer::WIDTH * Footer::HEIGHT / 2);
+
+    for v in 0..Footer::HEIGHT {
+        for u in (0..Footer::WIDTH).step_by(2) {
+            let left = footer.pixel(point!(u, v)).as_i32() as u8;
+            let right = footer.pixel(point!(u + 1, v)).as_i32() as u8;
+
+            data.push(left << 4 | right);
+        }
+    }
+
+    data
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Face, Mesh, UVMap};
+
+    fn decode(buffer: &[u8]) -> (png::OutputInfo, Vec<u8>, png::Info<'static>) {
+        let decoder = png::Decoder::new(buffer);
+        let mut reader = decoder.read_info().unwrap();
+        let mut data = vec![0; reader.output_buffer_size()];
+        let output_info = reader.next_frame(&mut data).unwrap();
+
+        (output_info, data, reader.info().clone().to_owned())
+    }
+
+    #[test]
+    fn image_write_png_rgba() {
+        let mut footer = Footer::default();
+        footer.set(point!(1, 0), Color::Red).unwrap();
+
+        let mut buffer: Vec<u8> = vec![];
+        write_png(&footer, Some(Color::Black), PngFormat::Rgba, &mut buffer).unwrap();
+
+        let (output_info, data, _) = decode(&buffer);
+
+        assert_eq!(output_info.width, Footer::WIDTH as u32);
+        assert_eq!(output_info.height, Footer::HEIGHT as u32);
+        assert_eq!(output_info.color_type, png::ColorType::Rgba);
+        assert_eq!(&data[0..8], &[0, 0, 0, 0, 255, 0, 77, 255]);
+    }
+
+    #[test]
+    fn image_write_png_indexed() {
+        let mut footer = Footer::default();
+        footer.set(point!(1, 0), Color::Red).unwrap();
+        footer.set(point!(2, 0), Color::LightPeach).unwrap();
+
+        let mut buffer: Vec<u8> = vec![];
+        write_png(
+            &footer,
+            Some(Color::DarkBlue),
+            PngFormat::Indexed,
+            &mut buffer,
+        )
+        .unwrap();
+
+        let (output_info, data, info) = decode(&buffer);
+
+        assert_eq!(output_info.width, Footer::WIDTH as u32);
+        assert_eq!(output_info.height, Footer::HEIGHT as u32);
+        assert_eq!(output_info.color_type, png::ColorType::Indexed);
+        assert_eq!(output_info.bit_depth, png::BitDepth::Four);
+        assert_eq!(&data[0..2], &[0x08, 0xf0]);
+
+        let palette = info.palette.unwrap();
+        assert_eq!(palette.len(), 48);
+        assert_eq!(&palette[39..42], &[131, 118, 156]);
+        assert_eq!(info.trns.unwrap().as_ref(), &[255, 0]);
+    }
+
+    #[test]
+    fn image_texture_template() {
+        let face = Face {
+            uv_maps: vec![
+                UVMap::new(0, point!(1.0, 1.0)),
+                UVMap::new(1, point!(3.0, 1.0)),
+                UVMap::new(2, point!(3.0, 3.0)),
+                UVMap::new(3, point!(1.0, 3.0)),
+            ],
+            ..Face::default()
+        };
+        let mut mesh = Mesh::new("mesh".to_string());
+        mesh.faces.push(face);
+
+        let mut model = Model::default();
+        model.footer.set(point!(0, 0), Color::Yellow).unwrap();
+        model.meshes.push(mesh);
+
+        let image = model.texture_template_image(4);
+
+        assert_eq!((image.width, image.height), (512, 480));
+        assert_eq!(image.get(point!(3, 3)), Some(Color::Yellow));
+        assert_eq!(image.get(point!(4, 4)), Some(Color::Black));
+        assert_eq!(image.get(point!(512, 0)), None);
+
+        // corners and edges of the outline.
+        assert_eq!(image.get(point!(32, 32)), Some(Color::Red));
+        assert_eq!(image.get(point!(60, 32)), Some(Color::Red));
+        assert_eq!(image.get(point!(96, 70)), Some(Color::Red));
+        assert_eq!(image.get(point!(50, 50)), Some(Color::Black));
+
+        // "0.0" centered on the face, the top row of the first zero.
+        assert_eq!(image.get(point!(59, 62)), Some(Color::Red));
+        assert_eq!(image.get(point!(61, 62)), Some(Color::Red));
+        assert_eq!(image.get(point!(60, 63)), Some(Color::Black));
+    }
+
+    #[test]
+    fn image_indexed_smaller_than_rgba() {
+        let mut rgba: Vec<u8> = vec![];
+        let mut indexed: Vec<u8> = vec![];
+
+        write_png(&Footer::default(), None, PngFormat::Rgba, &mut rgba).unwrap();
+        write_png(&Footer::default(), None, PngFormat::Indexed, &mut indexed).unwrap();
+
+        assert!(indexed.len() < rgba.len());
+    }
+
+    #[test]
+    fn image_write_extended_png() {
+        let mut pixels = vec![ExtendedColor::Base(Color::Black); 128 * 120];
+        pixels[1] = ExtendedColor::Secret(8);
+
+        let mut buffer: Vec<u8> = vec![];
+        write_extended_png(
+            &pixels,
+            Some(ExtendedColor::Base(Color::Black)),
+            true,
+            &mut buffer,
+        )
+        .unwrap();
+
+        let (_, data, _) = decode(&buffer);
+        assert_eq!(&data[0..8], &[0, 0, 0, 0, 190, 18, 80, 255]);
+
+        let mut buffer: Vec<u8> = vec![];
+        write_extended_png(&pixels, None, false, &mut buffer).unwrap();
+
+        let (_, data, _) = decode(&buffer);
+        assert_eq!(&data[0..8], &[0, 0, 0, 255, 126, 37, 83, 255]);
+    }
+}
@@ -0,0 +1,29 @@
+//! For exporting models into formats other programs understand.
+//!
+//! - _[`exporter`]:_ A common interface for all formats, picking the format by file extension.
+//! - _[`obj`]:_ Wavefront OBJ files, including the material library and texture they reference.
+//! - _[`image`]:_ The texture of a model as an image file, optionally with the uv-maps drawn on
+//!   top.
+//! - _[`slice`]:_ Outlines of a model cut by a plane as SVG or DXF drawings.
+//! - _`svg`:_ Rendered views of a model as SVG images (requires the `svg` feature).
+
+use crate::assets::Color;
+
+pub mod exporter;
+pub mod image;
+pub mod obj;
+pub mod slice;
+#[cfg(feature = "svg")]
+pub mod svg;
+
+/// Colors faces are outlined with in uv guides, picked by the index of their mesh.
+pub(crate) const MESH_COLORS: [Color; 8] = [
+    Color::Red,
+    Color::Blue,
+    Color::DarkGreen,
+    Color::Orange,
+    Color::DarkPurple,
+    Color::Pink,
+    Color::Brown,
+    Color::Green,
+];
@@ -0,0 +1,432 @@
+//! For exporting models as [Wavefront OBJ](https://en.wikipedia.org/wiki/Wavefront_.obj_file)
+//! files.
+//!
+//! <br/>
+//!
+//! picoCAD uses a coordinate system where the y-axis points downwards.
+//! Exported vertices have their y-axis flipped so models stand upright in other programs.
+//! This also turns the clockwise vertex order of picoCAD faces into the counter-clockwise order
+//! OBJ files expect, so faces keep pointing in the same direction.
+//!
+//! Textured faces use the material `texture` which maps the texture exported as a PNG file.
+//! Faces with [`no_texture`](crate::assets::Face) enabled use a material named `color_<index>`
+//! with the faces color instead.
+
+use crate::{
+    assets::{Color, Mesh, Model},
+    error::PicoError,
+    export::image::{write_png, PngFormat},
+};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write as FmtWrite;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Name of the material used by textured faces.
+pub const TEXTURE_MATERIAL: &str = "texture";
+/// Name of the manifest file written by [`export_per_mesh`].
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Returns the name of the material used by faces with the given color and no texture.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Color;
+/// use picocadrs::export::obj::color_material;
+///
+/// assert_eq!(color_material(Color::Lavender), "color_13");
+/// ```
+pub fn color_material(color: Color) -> String {
+    format!("color_{}", color.as_i32())
+}
+
+/// Serializes a single mesh into the contents of an OBJ file.
+///
+/// Vertex coordinates are written relative to the meshes [`position`](Mesh::position).
+/// `material_library` is the file name of the MTL file referenced by the OBJ file.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Face, Mesh, Point2D, Point3D, UVMap};
+/// use picocadrs::export::obj::mesh_to_obj;
+/// use picocadrs::point;
+///
+/// let mut mesh = Mesh::new("tri".to_string());
+/// mesh.vertices = vec![point!(0.0, 0.0, 0.0), point!(1.0, 0.0, 0.0), point!(0.0, 1.0, 0.0)];
+///
+/// let mut face = Face::default();
+/// face.uv_maps = vec![
+///     UVMap::new(0, point!(0.0, 0.0)),
+///     UVMap::new(1, point!(8.0, 0.0)),
+///     UVMap::new(2, point!(0.0, 7.5)),
+/// ];
+/// mesh.faces.push(face);
+///
+/// let obj = mesh_to_obj(&mesh, "model.mtl");
+///
+/// assert!(obj.contains("mtllib model.mtl\n"));
+/// assert!(obj.contains("v 0 -1 0\n"));
+/// assert!(obj.contains("vt 0.5 1\n"));
+/// assert!(obj.contains("f 1/1 2/2 3/3\n"));
+/// ```
+pub fn mesh_to_obj(mesh: &Mesh, material_library: &str) -> String {
+    let mut obj = String::new();
+    let mut faces = String::new();
+    let mut uv_count: usize = 0;
+    let mut current_material: Option<String> = None;
+
+    let _ = writeln!(obj, "# picoCAD mesh '{}'", mesh.name);
+    let _ = writeln!(obj, "mtllib {}", material_library);
+    let _ = writeln!(obj, "o {}", mesh.name);
+
+    for vertex in mesh.vertices.iter() {
+        let _ = writeln!(obj, "v {} {} {}", vertex.x, -vertex.y + 0.0, vertex.z);
+    }
+
+    for face in mesh.faces.iter() {
+        let material = if face.no_texture {
+            color_material(face.color)
+        } else {
+            TEXTURE_MATERIAL.to_string()
+        };
+
+        if current_material.as_ref() != Some(&material) {
+            let _ = writeln!(faces, "usemtl {}", material);
+            current_material = Some(material);
+        }
+
+        faces.push('f');
+
+        for uv_map in face.uv_maps.iter() {
+            if face.no_texture {
+                let _ = write!(faces, " {}", uv_map.vertex_index + 1);
+            } else {
+                uv_count += 1;
+                let _ = write!(faces, " {}/{}", uv_map.vertex_index + 1, uv_count);
+                let _ = writeln!(
+                    obj,
+                    "vt {} {}",
+                    uv_map.coords.u / 16.0,
+                    1.0 - uv_map.coords.v / 15.0
+                );
+            }
+        }
+
+        faces.push('\n');
+    }
+
+    obj.push_str(faces.as_str());
+    obj
+}
+
+/// Creates the contents of a MTL file containing every material used by the models meshes.
+///
+/// `texture` is the file name of the image the `texture` material maps.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Model;
+/// use picocadrs::export::obj::material_library;
+///
+/// let mtl = material_library(&Model::default(), "model.png");
+///
+/// assert!(mtl.contains("newmtl texture\n"));
+/// assert!(mtl.contains("map_Kd model.png\n"));
+/// ```
+pub fn material_library(model: &Model, texture: &str) -> String {
+    let mut mtl = String::new();
+
+    let _ = writeln!(mtl, "# picoCAD model '{}'", model.header.name);
+    let _ = writeln!(mtl, "newmtl {}", TEXTURE_MATERIAL);
+    let _ = writeln!(mtl, "Kd 1 1 1");
+    let _ = writeln!(mtl, "map_Kd {}", texture);
+
+    let colors: BTreeSet<i32> = model
+        .meshes
+        .iter()
+        .flat_map(|mesh| mesh.faces.iter())
+        .filter(|face| face.no_texture)
+        .map(|face| face.color.as_i32())
+        .collect();
+
+    for color in colors {
+        let (r, g, b) = Color::from(color).as_rgb();
+
+        let _ = writeln!(mtl, "newmtl {}", color_material(Color::from(color)));
+        let _ = writeln!(
+            mtl,
+            "Kd {} {} {}",
+            r as f64 / 255.0,
+            g as f64 / 255.0,
+            b as f64 / 255.0
+        );
+    }
+
+    mtl
+}
+
+/// Writes every mesh of `model` into its own OBJ file inside of `dir`.
+///
+/// All meshes share one MTL file and the texture exported as a PNG file, both named after the
+/// model.
+/// A [`manifest`](MANIFEST_FILE) is written alongside that maps each mesh to its file and
+/// position.
+/// `dir` will be created if it doesn't exist, files that already exist will be overwritten.
+///
+/// It's recommended to use [`Model::export_obj_per_mesh`].
+pub fn export_per_mesh(model: &Model, dir: &Path) -> Result<(), PicoError> {
+    std::fs::create_dir_all(dir)?;
+
+    let base_name = file_stem(&model.header.name);
+    let mtl_name = format!("{}.mtl", base_name);
+    let texture_name = format!("{}.png", base_name);
+
+    let texture = BufWriter::new(File::create(dir.join(&texture_name))?);
+    write_png(
+        &model.footer,
+        Some(model.header.alpha),
+        PngFormat::Rgba,
+        texture,
+    )?;
+
+    std::fs::write(
+        dir.join(&mtl_name),
+        material_library(model, texture_name.as_str()),
+    )?;
+
+    let mut used_names: HashSet<String> = HashSet::new();
+
+    let mut manifest = String::new();
+    let _ = writeln!(manifest, "{{");
+    let _ = writeln!(
+        manifest,
+        "  \"model\": {},",
+        json_string(&model.header.name)
+    );
+    let _ = writeln!(
+        manifest,
+        "  \"material_library\": {},",
+        json_string(&mtl_name)
+    );
+    let _ = writeln!(manifest, "  \"texture\": {},", json_string(&texture_name));
+    let _ = writeln!(manifest, "  \"meshes\": [");
+
+    for (i, mesh) in model.meshes.iter().enumerate() {
+        let stem = file_stem(&mesh.name);
+        let mut unique = stem.clone();
+        let mut suffix = 2;
+
+        while !used_names.insert(unique.clone()) {
+            unique = format!("{}_{}", stem, suffix);
+            suffix += 1;
+        }
+
+        let obj_name = format!("{}.obj", unique);
+        let mut file = BufWriter::new(File::create(dir.join(&obj_name))?);
+        file.write_all(mesh_to_obj(mesh, mtl_name.as_str()).as_bytes())?;
+        file.flush()?;
+
+        let _ = write!(
+            manifest,
+            "    {{ \"name\": {}, \"file\": {}, \"position\": [{}, {}, {}] }}",
+            json_string(&mesh.name),
+            json_string(&obj_name),
+            mesh.position.x,
+            -mesh.position.y + 0.0,
+            mesh.position.z
+        );
+
+        manifest.push_str(if i + 1 < model.meshes.len() {
+            ",\n"
+        } else {
+            "\n"
+        });
+    }
+
+    let _ = writeln!(manifest, "  ]");
+    let _ = writeln!(manifest, "}}");
+
+    std::fs::write(dir.join(MANIFEST_FILE), manifest)?;
+
+    Ok(())
+}
+
+/// Turns a name into something that can safely be used as a file name.
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if stem.is_empty() {
+        "unnamed".to_string()
+    } else {
+        stem
+    }
+}
+
+/// Quotes and escapes a string for use in a JSON document.
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Face, Point2D, Point3D, UVMap};
+    use crate::point;
+
+    fn test_mesh(name: &str) -> Mesh {
+        let mut mesh = Mesh::new(name.to_string());
+        mesh.position = point!(1.0, 2.0, 3.0);
+        mesh.vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(1.0, 0.0, 0.0),
+            point!(1.0, 0.0, 1.0),
+            point!(0.0, 0.0, 1.0),
+        ];
+
+        let textured = Face {
+            uv_maps: vec![
+                UVMap::new(0, point!(0.0, 0.0)),
+                UVMap::new(1, point!(16.0, 0.0)),
+                UVMap::new(2, point!(16.0, 15.0)),
+            ],
+            ..Face::default()
+        };
+
+        let colored = Face {
+            no_texture: true,
+            color: Color::Red,
+            uv_maps: vec![
+                UVMap::new(0, point!(0.0, 0.0)),
+                UVMap::new(2, point!(0.0, 0.0)),
+                UVMap::new(3, point!(0.0, 0.0)),
+            ],
+            ..Face::default()
+        };
+
+        mesh.faces = vec![textured, colored];
+        mesh
+    }
+
+    #[test]
+    fn obj_mesh_to_obj() {
+        let obj = mesh_to_obj(&test_mesh("plane"), "test.mtl");
+
+        assert_eq!(
+            obj,
+            "# picoCAD mesh 'plane'\n\
+            mtllib test.mtl\n\
+            o plane\n\
+            v 0 0 0\n\
+            v 1 0 0\n\
+            v 1 0 1\n\
+            v 0 0 1\n\
+            vt 0 1\n\
+            vt 1 1\n\
+            vt 1 0\n\
+            usemtl texture\n\
+            f 1/1 2/2 3/3\n\
+            usemtl color_8\n\
+            f 1 3 4\n"
+        );
+    }
+
+    #[test]
+    fn obj_material_library() {
+        let mut model = Model::default();
+        model.meshes.push(test_mesh("plane"));
+
+        let mtl = material_library(&model, "unnamed.png");
+
+        assert!(mtl.contains("newmtl texture\nKd 1 1 1\nmap_Kd unnamed.png\n"));
+        assert!(mtl.contains("newmtl color_8\nKd 1 0 0.30196078431372547\n"));
+        assert!(!mtl.contains("color_0"));
+    }
+
+    #[test]
+    fn obj_file_stem() {
+        assert_eq!(file_stem("plane"), "plane");
+        assert_eq!(file_stem("my mesh/1"), "my_mesh_1");
+        assert_eq!(file_stem(""), "unnamed");
+    }
+
+    #[test]
+    fn obj_json_string() {
+        assert_eq!(json_string("plane"), r#""plane""#);
+        assert_eq!(json_string("a\"b\\c\n"), r#""a\"b\\c\n""#);
+    }
+
+    #[test]
+    fn obj_export_per_mesh() {
+        let mut model = Model::default();
+        model.header.name = "props".to_string();
+        model.meshes.push(test_mesh("barrel"));
+        model.meshes.push(test_mesh("barrel"));
+        model.meshes.push(test_mesh("crate"));
+        model.meshes[2].position = point!(0.0, 0.0, 0.0);
+
+        // unique per process, so parallel test runs don't write into the same folder.
+        let dir = std::env::temp_dir().join(format!(
+            "picocadrs_obj_export_per_mesh_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        let result = export_per_mesh(&model, &dir);
+        let files = std::fs::read_dir(&dir).map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect::<Vec<String>>()
+        });
+        let manifest = std::fs::read_to_string(dir.join(MANIFEST_FILE));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        result.unwrap();
+        let files = files.unwrap();
+        for file in [
+            "props.mtl",
+            "props.png",
+            "barrel.obj",
+            "barrel_2.obj",
+            "crate.obj",
+        ] {
+            assert!(files.iter().any(|name| name == file), "missing {}", file);
+        }
+
+        let manifest = manifest.unwrap();
+        assert!(manifest
+            .contains(r#"{ "name": "barrel", "file": "barrel_2.obj", "position": [1, -2, 3] }"#));
+        assert!(
+            manifest.contains(r#"{ "name": "crate", "file": "crate.obj", "position": [0, 0, 0] }"#)
+        );
+    }
+}
@@ -0,0 +1,455 @@
+//! For cutting models with a plane and exporting the outlines of the cut.
+//!
+//! Every face is intersected with the [`Plane`], the resulting line segments are joined into
+//! [`Polyline`]s wherever their ends meet.
+//! Closed meshes give closed outlines, open meshes or faces missing from a mesh give open ones.
+//! Faces lying exactly on the plane are left out, they don't cross it.
+//!
+//! The outlines are flattened onto the plane, see [`slice`] for the direction of its axes.
+//! They can be written as SVG images with [`to_svg`] (requires the `svg` feature) or as DXF
+//! drawings with [`to_dxf`], for laser cutters or blueprint-style top, front and side views.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::Axis;
+//! use picocadrs::export::slice;
+//! use picocadrs::geometry::split::Plane;
+//!
+//! let model = picocadrs::model! {
+//!     mesh "box" { cube(2); }
+//! };
+//! let outlines = model.slice(Plane::axis(Axis::Y, 0.0));
+//!
+//! assert_eq!(outlines.len(), 1);
+//! assert!(outlines[0].closed);
+//! assert!(slice::to_dxf(&outlines).contains("POLYLINE"));
+//! ```
+
+use crate::{
+    assets::{Model, Point2D, Point3D},
+    geometry::split::Plane,
+    point,
+};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Distance to the plane below which a vertex counts as lying on it.
+const PLANE_EPSILON: f64 = 1e-9;
+
+/// Distance below which the ends of two segments are joined.
+const JOIN_EPSILON: f64 = 1e-6;
+
+/// A line through a list of points in the coordinates of a plane.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Polyline {
+    /// Points the line goes through in order.
+    pub points: Vec<Point2D<f64>>,
+    /// If true, the last point connects back to the first one.
+    pub closed: bool,
+}
+
+impl Polyline {
+    /// Returns the length of the line, including the part closing it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point2D;
+    /// use picocadrs::export::slice::Polyline;
+    /// use picocadrs::point;
+    ///
+    /// let mut line = Polyline {
+    ///     points: vec![point!(0.0, 0.0), point!(1.0, 0.0), point!(1.0, 1.0)],
+    ///     closed: false,
+    /// };
+    /// assert_eq!(line.length(), 2.0);
+    ///
+    /// line.closed = true;
+    /// assert_eq!(line.length(), 2.0 + 2.0f64.sqrt());
+    /// ```
+    pub fn length(&self) -> f64 {
+        let mut length: f64 = self
+            .points
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).length())
+            .sum();
+
+        if self.closed && self.points.len() > 2 {
+            length += (self.points[0] - self.points[self.points.len() - 1]).length();
+        }
+
+        length
+    }
+}
+
+/// Returns the outlines where the plane cuts through the faces of the model.
+///
+/// The points are given in coordinates on the plane, as seen from its front:
+/// the `y`-axis of the outlines follows the `y`-axis of the model (pointing down) if the plane
+/// isn't lying flat, otherwise it follows the `z`-axis.
+/// For a plane created with [`Plane::axis`] at `x`, `y` or `z` this gives a side, top or front
+/// view.
+///
+/// It's recommended to use [`Model::slice`].
+pub fn slice(model: &Model, plane: Plane) -> Vec<Polyline> {
+    let (u, v) = plane_axes(plane.normal);
+    let project = |point: Point3D<f64>| -> Point2D<f64> {
+        let offset = point - plane.point;
+        point!(offset.dot(u), offset.dot(v))
+    };
+
+    let mut segments = vec![];
+    for mesh in model.meshes.iter() {
+        for face in mesh.faces.iter() {
+            let Some(corners) = face.corners(&mesh.vertices) else {
+                continue;
+            };
+            let corners: Vec<Point3D<f64>> = corners
+                .into_iter()
+                .map(|corner| corner + mesh.position)
+                .collect();
+
+            segments.extend(
+                cut_face(&corners, plane)
+                    .into_iter()
+                    .map(|(start, end)| (project(start), project(end))),
+            );
+        }
+    }
+
+    join_segments(segments)
+}
+
+/// Writes the outlines into an SVG image, `scale` pixels per unit, with a margin of one unit
+/// around them.
+///
+/// Only available with the `svg` feature.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Point2D;
+/// use picocadrs::export::slice::{self, Polyline};
+/// use picocadrs::point;
+///
+/// let line = Polyline {
+///     points: vec![point!(0.0, 0.0), point!(2.0, 0.0), point!(2.0, 1.0)],
+///     closed: true,
+/// };
+/// let svg = slice::to_svg(&[line], 10.0);
+///
+/// assert!(svg.starts_with("<svg"));
+/// assert!(svg.contains("width=\"40\" height=\"30\""));
+/// assert_eq!(svg.matches("<polygon").count(), 1);
+/// ```
+#[cfg(feature = "svg")]
+pub fn to_svg(outlines: &[Polyline], scale: f64) -> String {
+    let (min, max) = bounds(outlines);
+    let width = (max.u - min.u + 2.0) * scale;
+    let height = (max.v - min.v + 2.0) * scale;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+        width.ceil(),
+        height.ceil()
+    );
+
+    for outline in outlines {
+        let points: Vec<String> = outline
+            .points
+            .iter()
+            .map(|point| {
+                format!(
+                    "{},{}",
+                    (point.u - min.u + 1.0) * scale,
+                    (point.v - min.v + 1.0) * scale
+                )
+            })
+            .collect();
+
+        let _ = writeln!(
+            svg,
+            "<{} points=\"{}\" fill=\"none\" stroke=\"#000000\" stroke-width=\"1\"/>",
+            if outline.closed {
+                "polygon"
+            } else {
+                "polyline"
+            },
+            points.join(" ")
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Writes the outlines into an ASCII DXF drawing, with one `POLYLINE` entity per outline.
+///
+/// Units are the same as in picoCAD.
+/// DXF drawings have their `y`-axis pointing up, so the outlines get flipped vertically.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Point2D;
+/// use picocadrs::export::slice::{self, Polyline};
+/// use picocadrs::point;
+///
+/// let line = Polyline {
+///     points: vec![point!(0.0, 0.0), point!(1.0, 2.0)],
+///     closed: false,
+/// };
+/// let dxf = slice::to_dxf(&[line]);
+///
+/// assert_eq!(dxf.matches("VERTEX").count(), 2);
+/// assert!(dxf.ends_with("EOF\n"));
+/// ```
+pub fn to_dxf(outlines: &[Polyline]) -> String {
+    let mut dxf = String::from("0\nSECTION\n2\nENTITIES\n");
+
+    for outline in outlines {
+        let _ = write!(
+            dxf,
+            "0\nPOLYLINE\n8\n0\n66\n1\n70\n{}\n10\n0.0\n20\n0.0\n30\n0.0\n",
+            u8::from(outline.closed)
+        );
+        for point in outline.points.iter() {
+            let _ = write!(
+                dxf,
+                "0\nVERTEX\n8\n0\n10\n{:?}\n20\n{:?}\n30\n0.0\n",
+                point.u,
+                // adding 0.0 turns -0.0 into 0.0.
+                -point.v + 0.0
+            );
+        }
+        dxf.push_str("0\nSEQEND\n8\n0\n");
+    }
+
+    dxf.push_str("0\nENDSEC\n0\nEOF\n");
+    dxf
+}
+
+/// Returns the horizontal and vertical axis of a plane facing `normal`.
+fn plane_axes(normal: Point3D<f64>) -> (Point3D<f64>, Point3D<f64>) {
+    let normal = normal.normalized();
+    let down = if normal.y.abs() > normal.x.abs().max(normal.z.abs()) {
+        point!(0.0, 0.0, 1.0)
+    } else {
+        point!(0.0, 1.0, 0.0)
+    };
+
+    let u = down.cross(normal).normalized();
+    (u, normal.cross(u))
+}
+
+/// Returns the segments where the plane cuts through the face with the given corners.
+fn cut_face(corners: &[Point3D<f64>], plane: Plane) -> Vec<(Point3D<f64>, Point3D<f64>)> {
+    let distances: Vec<f64> = corners
+        .iter()
+        .map(|corner| {
+            let distance = plane.distance(*corner);
+            if distance.abs() < PLANE_EPSILON {
+                0.0
+            } else {
+                distance
+            }
+        })
+        .collect();
+
+    // corners on the plane count as in front of it, so each crossing is only found once.
+    let mut crossings = vec![];
+    for i in 0..corners.len() {
+        let j = (i + 1) % corners.len();
+        let (a, b) = (distances[i], distances[j]);
+
+        if (a >= 0.0) != (b >= 0.0) {
+            let t = a / (a - b);
+            crossings.push(corners[i] + (corners[j] - corners[i]) * t);
+        } else if a == 0.0 && b == 0.0 {
+            // an edge on the plane is only kept if the face is on its back.
+            let behind = distances.iter().any(|distance| *distance < 0.0);
+            if behind {
+                return vec![(corners[i], corners[j])];
+            }
+        }
+    }
+
+    if crossings.len() < 2 {
+        return vec![];
+    }
+
+    // faces that aren't convex can cross the plane more than twice.
+    let direction = crossings[1] - crossings[0];
+    crossings.sort_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)));
+    crossings
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .filter(|(start, end)| (*end - *start).length() > PLANE_EPSILON)
+        .collect()
+}
+
+/// Joins segments sharing ends into polylines.
+fn join_segments(segments: Vec<(Point2D<f64>, Point2D<f64>)>) -> Vec<Polyline> {
+    let key = |point: Point2D<f64>| {
+        (
+            (point.u / JOIN_EPSILON).round() as i64,
+            (point.v / JOIN_EPSILON).round() as i64,
+        )
+    };
+
+    let mut ends: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (index, (start, end)) in segments.iter().enumerate() {
+        ends.entry(key(*start)).or_default().push(index);
+        ends.entry(key(*end)).or_default().push(index);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut outlines = vec![];
+
+    // start at loose ends first, so open lines aren't started in their middle.
+    let mut starts: Vec<usize> = (0..segments.len()).collect();
+    starts.sort_by_key(|index| {
+        let (start, end) = segments[*index];
+        ends[&key(start)].len() != 1 && ends[&key(end)].len() != 1
+    });
+
+    for first in starts {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+
+        let (mut start, mut end) = segments[first];
+        if ends[&key(end)].len() == 1 {
+            (start, end) = (end, start);
+        }
+
+        let mut points = vec![start, end];
+        let mut closed = false;
+        loop {
+            let current = *points.last().unwrap();
+            let next = ends[&key(current)]
+                .iter()
+                .copied()
+                .find(|index| !used[*index]);
+
+            let Some(next) = next else {
+                break;
+            };
+            used[next] = true;
+
+            let (a, b) = segments[next];
+            let point = if key(a) == key(current) { b } else { a };
+
+            if key(point) == key(points[0]) {
+                closed = true;
+                break;
+            }
+            points.push(point);
+        }
+
+        outlines.push(Polyline { points, closed });
+    }
+
+    outlines
+}
+
+/// Returns the smallest and largest coordinates of all outlines.
+#[cfg(feature = "svg")]
+fn bounds(outlines: &[Polyline]) -> (Point2D<f64>, Point2D<f64>) {
+    let mut points = outlines.iter().flat_map(|outline| outline.points.iter());
+    let Some(first) = points.next() else {
+        return (point!(0.0, 0.0), point!(0.0, 0.0));
+    };
+
+    points.fold((*first, *first), |(min, max), point| {
+        (
+            point!(min.u.min(point.u), min.v.min(point.v)),
+            point!(max.u.max(point.u), max.v.max(point.v)),
+        )
+    })
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Axis, Mesh};
+    use crate::template::MeshTemplate;
+
+    fn cube_model(position: Point3D<f64>) -> Model {
+        let mut model = Model::default();
+        let mut mesh: Mesh = MeshTemplate::new("cube".to_string()).cube(2).build();
+        mesh.position = position;
+        model.meshes.push(mesh);
+        model
+    }
+
+    #[test]
+    fn slice_plane_axes() {
+        let (u, v) = plane_axes(point!(0.0, 0.0, 1.0));
+        assert_eq!((u, v), (point!(1.0, 0.0, 0.0), point!(0.0, 1.0, 0.0)));
+
+        let (u, v) = plane_axes(point!(0.0, -2.0, 0.0));
+        assert_eq!((u, v), (point!(1.0, 0.0, 0.0), point!(0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn slice_closed_outline() {
+        let outlines = cube_model(point!(1.0, 0.0, 0.0)).slice(Plane::axis(Axis::Z, 0.5));
+
+        assert_eq!(outlines.len(), 1);
+        assert!(outlines[0].closed);
+        assert_eq!(outlines[0].points.len(), 4);
+        assert!((outlines[0].length() - 8.0).abs() < 1e-9);
+        for point in outlines[0].points.iter() {
+            assert!(point.u.abs() < 1e-9 || (point.u - 2.0).abs() < 1e-9);
+            assert!((point.v.abs() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn slice_open_outline() {
+        let mut model = cube_model(point!(0.0, 0.0, 0.0));
+        // remove a side of the cube, so the outline around it is broken open.
+        let mesh = &mut model.meshes[0];
+        let side = mesh
+            .faces
+            .iter()
+            .position(|face| {
+                let corners = face.corners(&mesh.vertices).unwrap();
+                corners.iter().any(|corner| corner.y != corners[0].y)
+            })
+            .unwrap();
+        mesh.faces.remove(side);
+
+        let outlines = model.slice(Plane::axis(Axis::Y, 0.25));
+        let total: usize = outlines.iter().map(|outline| outline.points.len()).sum();
+
+        assert!(outlines.iter().any(|outline| !outline.closed));
+        assert!((outlines.iter().map(Polyline::length).sum::<f64>() - 6.0).abs() < 1e-9);
+        assert!(total <= 5);
+    }
+
+    #[test]
+    fn slice_missing_plane() {
+        let outlines = cube_model(point!(0.0, 0.0, 0.0)).slice(Plane::axis(Axis::X, 3.0));
+        assert!(outlines.is_empty());
+
+        assert_eq!(
+            to_dxf(&outlines),
+            "0\nSECTION\n2\nENTITIES\n0\nENDSEC\n0\nEOF\n"
+        );
+    }
+
+    #[test]
+    fn slice_dxf_flips() {
+        let dxf = to_dxf(&[Polyline {
+            points: vec![point!(1.5, 2.0)],
+            closed: true,
+        }]);
+
+        assert!(dxf.contains("70\n1\n"));
+        assert!(dxf.contains("10\n1.5\n20\n-2.0\n"));
+    }
+}
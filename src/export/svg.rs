@@ -0,0 +1,666 @@
+//! For rendering models as SVG images.
+//!
+//! Models are viewed through a [`CameraParams`] and drawn using the painter's algorithm: faces
+//! are sorted by the depth of their center and drawn from back to front.
+//! Faces with [`render_priority`](crate::assets::Face::render_priority) are drawn before every
+//! other face, so they end up behind them like in picoCAD.
+//!
+//! SVG can't map the texture onto faces, so textured faces are filled with the color they show
+//! the most (see [`Face::texture_colors`](crate::assets::Face::texture_colors)).
+//! Faces only showing the alpha color are invisible and not drawn at all.
+//!
+//! Besides rendering a model the way picoCAD shows it, there are two kinds of guide sheets:
+//!
+//! - [`wireframe_svg`] only draws the edges of every face.
+//! - [`uv_layout_svg`] draws the uv-map of every face on top of a grid the size of the texture,
+//!   to paint textures in other programs.
+//!
+//! Only available with the `svg` feature, which is enabled by default.
+
+use super::MESH_COLORS;
+use crate::assets::{Color, Face, Footer, Mesh, MeshFaceRef, Model, Point2D, Point3D};
+use crate::camera::CameraParams;
+use crate::point;
+use crate::shading::Shade;
+use crate::visibility;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+/// Size of a pixel of the texture in images created by [`uv_layout_svg`].
+pub const UV_LAYOUT_SCALE: u32 = 8;
+
+/// Settings used by [`render_svg`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SvgOptions {
+    /// Width of the image in pixels.
+    pub width: u32,
+    /// Height of the image in pixels.
+    pub height: u32,
+    /// Camera the model is viewed through.
+    pub camera: CameraParams,
+    /// If true, faces that are not [`double_sided`](Face::double_sided) are skipped when
+    /// pointing away from the camera.
+    pub backface_culling: bool,
+    /// If true, faces pointing away from the camera get darker using [`Color::shadow`] and
+    /// [`Color::shadow_transition`], as if the light came from the camera.
+    /// See the [`shading`](crate::shading) module.
+    /// Faces with [`no_shading`](Face::no_shading) are never shaded.
+    pub shading: bool,
+    /// Color of the outline drawn around each face, [`None`] draws no outlines.
+    pub outline: Option<Color>,
+    /// If true, the image is filled with the background color of the model first.
+    pub background: bool,
+    /// If true, faces completely covered by other faces are left out of the image.
+    /// Makes images of big models smaller, but takes longer to render.
+    /// See the [`visibility`](crate::visibility) module.
+    pub cull_hidden_faces: bool,
+}
+
+impl Default for SvgOptions {
+    /// Renders a 256x240 image (twice the size of the texture) with the default [`CameraParams`],
+    /// backface culling, shading and background, but without outlines or culling hidden faces.
+    fn default() -> Self {
+        SvgOptions {
+            width: 256,
+            height: 240,
+            camera: CameraParams::default(),
+            backface_culling: true,
+            shading: true,
+            outline: None,
+            background: true,
+            cull_hidden_faces: false,
+        }
+    }
+}
+
+/// A face projected onto the image.
+struct ProjectedFace {
+    points: Vec<Point2D<f64>>,
+    depth: f64,
+    priority: bool,
+    color: Color,
+}
+
+/// Renders the model as a complete SVG document.
+///
+/// It's recommended to use [`Model::render_svg`].
+pub fn render_svg(model: &Model, options: &SvgOptions) -> String {
+    let hidden: BTreeSet<MeshFaceRef> = if options.cull_hidden_faces {
+        visibility::hidden_faces(model, &[options.camera])
+            .into_iter()
+            .collect()
+    } else {
+        BTreeSet::new()
+    };
+
+    let faces: Vec<(&Mesh, &Face)> = model
+        .meshes
+        .iter()
+        .enumerate()
+        .flat_map(|(mesh_index, mesh)| {
+            mesh.faces
+                .iter()
+                .enumerate()
+                .map(move |(face_index, face)| {
+                    (MeshFaceRef::new(mesh_index, face_index), mesh, face)
+                })
+        })
+        .filter(|(face_ref, _, _)| !hidden.contains(face_ref))
+        .map(|(_, mesh, face)| (mesh, face))
+        .collect();
+
+    #[cfg(feature = "rayon")]
+    let faces = faces.into_par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let faces = faces.into_iter();
+
+    let mut faces: Vec<ProjectedFace> = faces
+        .filter_map(|(mesh, face)| project_face(model, mesh, face, options))
+        .collect();
+
+    // faces with render priority first, then the farthest.
+    faces.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then(b.depth.total_cmp(&a.depth))
+    });
+
+    let mut svg = svg_start(options.width, options.height);
+
+    if options.background {
+        let _ = writeln!(
+            svg,
+            "<rect width=\"100%\" height=\"100%\" fill=\"#{}\"/>",
+            model.header.background.as_hex()
+        );
+    }
+
+    let outline = match options.outline {
+        Some(color) => format!(" stroke=\"#{}\" stroke-linejoin=\"round\"", color.as_hex()),
+        None => String::new(),
+    };
+
+    for face in faces.iter() {
+        let points: Vec<String> = face
+            .points
+            .iter()
+            .map(|p| format!("{:.2},{:.2}", p.u, p.v))
+            .collect();
+
+        let _ = writeln!(
+            svg,
+            "<polygon points=\"{}\" fill=\"#{}\"{}/>",
+            points.join(" "),
+            face.color.as_hex(),
+            outline
+        );
+    }
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/// Projects a face onto the image.
+/// Returns [`None`] if the face should not be drawn.
+fn project_face(
+    model: &Model,
+    mesh: &Mesh,
+    face: &Face,
+    options: &SvgOptions,
+) -> Option<ProjectedFace> {
+    if face.uv_maps.len() < 3 {
+        return None;
+    }
+
+    let corners: Vec<Point3D<f64>> = face
+        .uv_maps
+        .iter()
+        .map(|uv_map| {
+            mesh.vertices
+                .get(uv_map.vertex_index)
+                .map(|vertex| *vertex + mesh.position)
+        })
+        .collect::<Option<Vec<Point3D<f64>>>>()?;
+
+    let camera = options.camera;
+    let count = corners.len() as f64;
+    let center = point!(
+        corners.iter().map(|c| c.x).sum::<f64>() / count,
+        corners.iter().map(|c| c.y).sum::<f64>() / count,
+        corners.iter().map(|c| c.z).sum::<f64>() / count
+    );
+
+    let mut facing = face
+        .normal(&mesh.vertices)
+        .dot(camera.direction_from(center));
+
+    if facing <= 0.0 {
+        if options.backface_culling && !face.double_sided {
+            return None;
+        }
+
+        // the back of double-sided faces is lit like its front.
+        facing = -facing;
+    }
+
+    let mut color = if face.no_texture {
+        face.color
+    } else {
+        let colors = face.texture_colors(&model.footer);
+        let (color, _) = colors
+            .into_iter()
+            .filter(|(color, _)| *color != model.header.alpha)
+            .max_by_key(|(color, count)| (*count, -color.as_i32()))?;
+        color
+    };
+
+    if options.shading && !face.no_shading {
+        color = Shade::from_intensity(facing).apply(color);
+    }
+
+    let points = corners
+        .iter()
+        .map(|corner| project_point(*corner, &camera, options.width, options.height))
+        .collect::<Option<Vec<Point2D<f64>>>>()?;
+
+    Some(ProjectedFace {
+        points,
+        depth: (center - camera.position()).dot(camera.forward()),
+        priority: face.render_priority,
+        color,
+    })
+}
+
+/// Projects a point onto an image of the given size.
+/// Returns [`None`] if the point is behind the camera.
+fn project_point(
+    point: Point3D<f64>,
+    camera: &CameraParams,
+    width: u32,
+    height: u32,
+) -> Option<Point2D<f64>> {
+    let projected = point.project(camera)?;
+    let half_width = width as f64 / 2.0;
+    let half_height = height as f64 / 2.0;
+
+    Some(point!(
+        half_width + projected.x * half_height,
+        half_height + projected.y * half_height
+    ))
+}
+
+/// Renders the edges of every face of the model as lines, without filling faces or hiding the
+/// ones in the back.
+/// Edges shared by multiple faces of a mesh are only drawn once.
+///
+/// The image has the same size as with [`SvgOptions::default`].
+///
+/// It's recommended to use [`Model::wireframe_svg`].
+pub fn wireframe_svg(model: &Model, camera: &CameraParams) -> String {
+    let SvgOptions { width, height, .. } = SvgOptions::default();
+    let mut svg = svg_start(width, height);
+
+    let _ = writeln!(
+        svg,
+        "<rect width=\"100%\" height=\"100%\" fill=\"#{}\"/>",
+        Color::White.as_hex()
+    );
+    let _ = writeln!(
+        svg,
+        "<g stroke=\"#{}\" stroke-linecap=\"round\">",
+        Color::Black.as_hex()
+    );
+
+    for mesh in model.meshes.iter() {
+        let mut edges: BTreeSet<(usize, usize)> = BTreeSet::new();
+
+        for face in mesh.faces.iter() {
+            let count = face.uv_maps.len();
+
+            for (i, uv_map) in face.uv_maps.iter().enumerate() {
+                let a = uv_map.vertex_index;
+                let b = face.uv_maps[(i + 1) % count].vertex_index;
+
+                if a != b {
+                    edges.insert((a.min(b), a.max(b)));
+                }
+            }
+        }
+
+        for (a, b) in edges {
+            let (Some(a), Some(b)) = (mesh.vertices.get(a), mesh.vertices.get(b)) else {
+                continue;
+            };
+            let a = project_point(*a + mesh.position, camera, width, height);
+            let b = project_point(*b + mesh.position, camera, width, height);
+
+            if let (Some(a), Some(b)) = (a, b) {
+                let _ = writeln!(
+                    svg,
+                    "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\"/>",
+                    a.u, a.v, b.u, b.v
+                );
+            }
+        }
+    }
+
+    svg.push_str("</g>\n</svg>\n");
+
+    svg
+}
+
+/// Renders the uv-map of every face on top of a grid the size of the texture, so textures can be
+/// painted in other programs.
+///
+/// Every pixel of the texture is [`UV_LAYOUT_SCALE`] units wide and high.
+/// Thin lines mark pixels, thicker lines mark full uv units (8 pixels).
+/// Faces are outlined in a color picked by the index of their mesh and labeled with the name of
+/// their mesh and their index.
+///
+/// It's recommended to use [`Model::uv_layout_svg`].
+pub fn uv_layout_svg(model: &Model) -> String {
+    let scale = UV_LAYOUT_SCALE as f64;
+    let (width, height) = (
+        Footer::WIDTH as u32 * UV_LAYOUT_SCALE,
+        Footer::HEIGHT as u32 * UV_LAYOUT_SCALE,
+    );
+    let mut svg = svg_start(width, height);
+
+    let _ = writeln!(
+        svg,
+        "<rect width=\"100%\" height=\"100%\" fill=\"#{}\"/>",
+        Color::White.as_hex()
+    );
+
+    let grids = [(128, 1, Color::LightGrey, 1), (16, 8, Color::DarkGrey, 2)];
+
+    for (count, step, grid_color, stroke_width) in grids {
+        let _ = writeln!(
+            svg,
+            "<g stroke=\"#{}\" stroke-width=\"{}\">",
+            grid_color.as_hex(),
+            stroke_width
+        );
+        for i in 0..=count {
+            let position = (i * step * UV_LAYOUT_SCALE) as f64;
+
+            if position <= width as f64 {
+                let _ = writeln!(
+                    svg,
+                    "<line x1=\"{0}\" y1=\"0\" x2=\"{0}\" y2=\"{1}\"/>",
+                    position, height
+                );
+            }
+            if position <= height as f64 {
+                let _ = writeln!(
+                    svg,
+                    "<line x1=\"0\" y1=\"{0}\" x2=\"{1}\" y2=\"{0}\"/>",
+                    position, width
+                );
+            }
+        }
+        svg.push_str("</g>\n");
+    }
+
+    for (mesh_index, mesh) in model.meshes.iter().enumerate() {
+        let color = MESH_COLORS[mesh_index % MESH_COLORS.len()];
+
+        let _ = writeln!(
+            svg,
+            "<g stroke=\"#{0}\" fill=\"#{0}\" fill-opacity=\"0.2\" stroke-width=\"2\">",
+            color.as_hex()
+        );
+
+        for (face_index, face) in mesh.faces.iter().enumerate() {
+            if face.uv_maps.is_empty() {
+                continue;
+            }
+
+            // uv units are 8 pixels wide.
+            let points: Vec<Point2D<f64>> = face
+                .uv_maps
+                .iter()
+                .map(|uv_map| uv_map.coords * 8.0 * scale)
+                .collect();
+            let center = points
+                .iter()
+                .fold(point!(0.0, 0.0), |sum, point| sum + *point)
+                / points.len() as f64;
+            let points: Vec<String> = points
+                .iter()
+                .map(|p| format!("{:.2},{:.2}", p.u, p.v))
+                .collect();
+
+            let _ = writeln!(
+                svg,
+                "<polygon points=\"{}\"/>\n<text x=\"{:.2}\" y=\"{:.2}\" stroke=\"none\" fill-opacity=\"1\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"middle\">{} {}</text>",
+                points.join(" "),
+                center.u,
+                center.v,
+                UV_LAYOUT_SCALE * 2,
+                escape_xml(&mesh.name),
+                face_index
+            );
+        }
+
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+
+    svg
+}
+
+/// Returns the opening tag of an SVG document of the given size.
+fn svg_start(width: u32, height: u32) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+        width, height
+    )
+}
+
+/// Escapes characters that have a meaning in XML.
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::UVMap;
+    use crate::camera::Projection;
+
+    /// Model holding a unit cube with a differently colored, untextured face on each side.
+    fn cube_model() -> Model {
+        let mut mesh = Mesh::new("cube".to_string());
+        mesh.vertices = vec![
+            point!(-0.5, -0.5, -0.5),
+            point!(0.5, -0.5, -0.5),
+            point!(0.5, 0.5, -0.5),
+            point!(-0.5, 0.5, -0.5),
+            point!(-0.5, -0.5, 0.5),
+            point!(0.5, -0.5, 0.5),
+            point!(0.5, 0.5, 0.5),
+            point!(-0.5, 0.5, 0.5),
+        ];
+
+        let faces = [
+            ([0, 1, 2, 3], Color::Red),
+            ([5, 4, 7, 6], Color::Green),
+            ([4, 5, 1, 0], Color::Blue),
+            ([4, 0, 3, 7], Color::Yellow),
+            ([1, 5, 6, 2], Color::Pink),
+            ([3, 2, 6, 7], Color::Orange),
+        ];
+
+        for (indices, color) in faces {
+            mesh.faces.push(Face {
+                uv_maps: indices
+                    .iter()
+                    .map(|i| UVMap::new(*i, point!(0.0, 0.0)))
+                    .collect(),
+                no_texture: true,
+                color,
+                ..Face::default()
+            });
+        }
+
+        Model {
+            meshes: vec![mesh],
+            ..Model::default()
+        }
+    }
+
+    fn front_options() -> SvgOptions {
+        SvgOptions {
+            camera: CameraParams {
+                yaw: 0.0,
+                pitch: 0.0,
+                ..CameraParams::default()
+            },
+            shading: false,
+            background: false,
+            ..SvgOptions::default()
+        }
+    }
+
+    #[test]
+    fn svg_wireframe() {
+        let model = cube_model();
+        let svg = model.wireframe_svg(&front_options().camera);
+
+        // 12 edges of the cube, each shared by 2 faces.
+        assert_eq!(svg.matches("<line").count(), 12);
+        assert!(!svg.contains("<polygon"));
+    }
+
+    #[test]
+    fn svg_uv_layout() {
+        let mut model = cube_model();
+        model.meshes[0].name = "<cube>".to_string();
+        model.meshes[0].faces[0].uv_maps = vec![
+            UVMap::new(0, point!(1.0, 1.0)),
+            UVMap::new(1, point!(2.0, 1.0)),
+            UVMap::new(2, point!(2.0, 2.0)),
+        ];
+        model.meshes[0].faces.truncate(1);
+
+        let svg = model.uv_layout_svg();
+
+        assert!(svg.contains("width=\"1024\" height=\"960\""));
+        assert!(svg.contains("<polygon points=\"64.00,64.00 128.00,64.00 128.00,128.00\"/>"));
+        assert!(svg.contains(">&lt;cube&gt; 0</text>"));
+        assert!(svg.contains(&format!("stroke=\"#{}\"", Color::Red.as_hex())));
+    }
+
+    #[test]
+    fn svg_backface_culling() {
+        let model = cube_model();
+        let svg = model.render_svg(&front_options());
+
+        // only the face pointing towards +z is visible from the front.
+        assert_eq!(svg.matches("<polygon").count(), 1);
+        assert!(svg.contains(&Color::Green.as_hex()));
+
+        let svg = model.render_svg(&SvgOptions {
+            backface_culling: false,
+            ..front_options()
+        });
+        assert_eq!(svg.matches("<polygon").count(), 6);
+
+        let mut model = model;
+        for face in model.meshes[0].faces.iter_mut() {
+            face.double_sided = true;
+        }
+        let svg = model.render_svg(&front_options());
+        assert_eq!(svg.matches("<polygon").count(), 6);
+    }
+
+    #[test]
+    fn svg_cull_hidden_faces() {
+        let mut model = cube_model();
+        for face in model.meshes[0].faces.iter_mut() {
+            face.double_sided = true;
+        }
+
+        let svg = model.render_svg(&SvgOptions {
+            cull_hidden_faces: true,
+            ..front_options()
+        });
+
+        assert_eq!(svg.matches("<polygon").count(), 1);
+        assert!(svg.contains(&Color::Green.as_hex()));
+    }
+
+    #[test]
+    fn svg_depth_sorting() {
+        let mut model = cube_model();
+        for face in model.meshes[0].faces.iter_mut() {
+            face.double_sided = true;
+        }
+
+        let svg = model.render_svg(&front_options());
+        let back = svg.find(&Color::Red.as_hex()).unwrap();
+        let front = svg.find(&Color::Green.as_hex()).unwrap();
+
+        assert!(back < front);
+
+        model.meshes[0].faces[1].render_priority = true;
+        let svg = model.render_svg(&front_options());
+        let back = svg.find(&Color::Red.as_hex()).unwrap();
+        let front = svg.find(&Color::Green.as_hex()).unwrap();
+
+        assert!(back > front);
+    }
+
+    #[test]
+    fn svg_shading() {
+        let model = cube_model();
+        let svg = model.render_svg(&SvgOptions {
+            camera: CameraParams {
+                yaw: 1.2,
+                pitch: 0.0,
+                ..CameraParams::default()
+            },
+            ..front_options()
+        });
+
+        assert!(svg.contains(&Color::Green.as_hex()));
+        assert!(svg.contains(&Color::Pink.as_hex()));
+
+        let svg = model.render_svg(&SvgOptions {
+            camera: CameraParams {
+                yaw: 1.2,
+                pitch: 0.0,
+                ..CameraParams::default()
+            },
+            shading: true,
+            ..front_options()
+        });
+
+        // the face looking at +z is only seen from a steep angle.
+        assert!(!svg.contains(&Color::Green.as_hex()));
+        assert!(svg.contains(&Color::DarkBlue.as_hex()));
+        assert!(svg.contains(&Color::Pink.as_hex()));
+    }
+
+    #[test]
+    fn svg_textured_faces() {
+        let mut model = cube_model();
+        model.header.alpha = Color::Black;
+
+        let face = &mut model.meshes[0].faces[1];
+        face.no_texture = false;
+        face.uv_maps[0].coords = point!(0.0, 0.0);
+        face.uv_maps[1].coords = point!(1.0, 0.0);
+        face.uv_maps[2].coords = point!(1.0, 1.0);
+        face.uv_maps[3].coords = point!(0.0, 1.0);
+
+        // only shows the alpha color.
+        let svg = model.render_svg(&front_options());
+        assert_eq!(svg.matches("<polygon").count(), 0);
+
+        model.footer.set(point!(2, 2), Color::Lavender).unwrap();
+        let svg = model.render_svg(&front_options());
+        assert!(svg.contains(&Color::Lavender.as_hex()));
+    }
+
+    #[test]
+    fn svg_orthographic() {
+        let model = cube_model();
+        let svg = model.render_svg(&SvgOptions {
+            camera: CameraParams {
+                yaw: 0.0,
+                pitch: 0.0,
+                distance: 100.0,
+                fov: 90f64.to_radians(),
+                projection: Projection::Orthographic,
+                ..CameraParams::default()
+            },
+            width: 200,
+            height: 200,
+            ..front_options()
+        });
+
+        // the view is 200 units high, so the cube is exactly 1 pixel wide.
+        assert!(svg.contains("points=\"100.50,99.50 99.50,99.50 99.50,100.50 100.50,100.50\""));
+    }
+
+    #[test]
+    fn svg_document() {
+        let svg = Model::default().render_svg(&SvgOptions {
+            outline: Some(Color::White),
+            ..SvgOptions::default()
+        });
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"256\""));
+        assert!(svg.contains("<rect"));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
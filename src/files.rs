@@ -0,0 +1,890 @@
+//! For working with whole folders of picoCAD projects.
+//!
+//! [`ProjectDir`] wraps a folder holding project files (by default the one picoCAD uses, see
+//! [`projects_path`]) and takes care of file names, so batch tools only have to deal with
+//! project names.
+//! A project named `house` is stored as `house.txt`.
+//! With the `watch` feature a folder can also be watched for changes, see `ProjectDir::watch`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use picocadrs::files::ProjectDir;
+//!
+//! let projects = ProjectDir::open_default().unwrap();
+//!
+//! for (name, model) in projects.iter().unwrap() {
+//!     match model {
+//!         Ok(model) => println!("{}: {} meshes", name, model.meshes.len()),
+//!         Err(error) => println!("{}: {}", name, error),
+//!     }
+//! }
+//! ```
+
+use crate::{
+    assets::Model, error::PicoError, export::exporter::ExporterRegistry, paths::projects_path,
+};
+#[cfg(feature = "watch")]
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "watch")]
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "watch")]
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+#[cfg(feature = "watch")]
+use std::time::{Duration, Instant};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Extension of picoCAD project files.
+pub const PROJECT_EXTENSION: &str = "txt";
+/// Extension of backups created by [`ProjectDir::backup`].
+pub const BACKUP_EXTENSION: &str = "bak";
+/// Time a project file has to stay untouched before a [`ProjectWatcher`] reports a change.
+#[cfg(feature = "watch")]
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Name of a project and the result of loading it.
+pub type LoadedProject = (String, Result<Model, PicoError>);
+
+/// A folder holding picoCAD project files.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ProjectDir {
+    path: PathBuf,
+}
+
+impl ProjectDir {
+    /// Wraps the folder at `path`.
+    ///
+    /// The folder doesn't have to exist until it is used.
+    pub fn new<P: AsRef<Path>>(path: P) -> ProjectDir {
+        ProjectDir {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Wraps the folder picoCAD stores its projects in.
+    ///
+    /// Returns [`PicoError::NoHomeDirectory`] if the home directory of the user can't be found.
+    pub fn open_default() -> Result<ProjectDir, PicoError> {
+        projects_path()
+            .map(ProjectDir::new)
+            .ok_or(PicoError::NoHomeDirectory)
+    }
+
+    /// Returns the path of the folder.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the path of the file the project called `name` is stored in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::files::ProjectDir;
+    /// use std::path::Path;
+    ///
+    /// let projects = ProjectDir::new("projects");
+    ///
+    /// assert_eq!(projects.project_path("house"), Path::new("projects/house.txt"));
+    /// ```
+    pub fn project_path(&self, name: &str) -> PathBuf {
+        self.path.join(format!("{}.{}", name, PROJECT_EXTENSION))
+    }
+
+    /// Checks if there is a project called `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.project_path(name).is_file()
+    }
+
+    /// Returns the names of all projects in the folder in alphabetical order.
+    ///
+    /// Returns an error if the folder can't be read.
+    pub fn names(&self) -> Result<Vec<String>, PicoError> {
+        let mut names: Vec<String> = vec![];
+
+        for entry in std::fs::read_dir(&self.path)? {
+            let path = entry?.path();
+
+            if !path.is_file()
+                || path.extension().and_then(|e| e.to_str()) != Some(PROJECT_EXTENSION)
+            {
+                continue;
+            }
+
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                names.push(name.to_string());
+            }
+        }
+
+        names.sort();
+
+        Ok(names)
+    }
+
+    /// Returns an iterator over all projects in the folder in alphabetical order.
+    ///
+    /// Each project is only loaded once the iterator reaches it, so a single broken file doesn't
+    /// stop the iteration.
+    /// Returns an error if the folder can't be read.
+    pub fn iter(&self) -> Result<Projects, PicoError> {
+        Ok(Projects {
+            dir: self.clone(),
+            names: self.names()?.into_iter(),
+        })
+    }
+
+    /// Loads all projects in the folder at once, spreading the work over all cores.
+    ///
+    /// Returns each projects name and the result of loading it in alphabetical order, like
+    /// [`iter`](ProjectDir::iter) does.
+    /// Returns an error if the folder can't be read.
+    #[cfg(feature = "rayon")]
+    pub fn par_load_all(&self) -> Result<Vec<LoadedProject>, PicoError> {
+        Ok(self
+            .names()?
+            .into_par_iter()
+            .map(|name| {
+                let model = Model::load_from_path(self.project_path(&name).into_os_string());
+                (name, model)
+            })
+            .collect())
+    }
+
+    /// Loads the project called `name`.
+    ///
+    /// Returns [`None`] if there is no such project.
+    pub fn find(&self, name: &str) -> Option<Result<Model, PicoError>> {
+        if !self.contains(name) {
+            return None;
+        }
+
+        Some(Model::load_from_path(
+            self.project_path(name).into_os_string(),
+        ))
+    }
+
+    /// Copies the project called `name` into a backup file next to it and returns the path of the
+    /// backup.
+    ///
+    /// Backups are called `{name}.bak`.
+    /// If that file exists already a number is added (`{name}.1.bak`, `{name}.2.bak`, ...), so
+    /// earlier backups are never overwritten.
+    ///
+    /// Returns [`PicoError::ProjectName`] if `name` isn't a valid project name (see
+    /// [`ProjectDir::save_as`]) and [`PicoError::ProjectNotFound`] if there is no such project.
+    pub fn backup(&self, name: &str) -> Result<PathBuf, PicoError> {
+        check_name(name)?;
+        if !self.contains(name) {
+            return Err(PicoError::ProjectNotFound(name.to_string()));
+        }
+
+        let mut backup = self.path.join(format!("{}.{}", name, BACKUP_EXTENSION));
+        let mut index = 1;
+
+        while backup.exists() {
+            backup = self
+                .path
+                .join(format!("{}.{}.{}", name, index, BACKUP_EXTENSION));
+            index += 1;
+        }
+
+        std::fs::copy(self.project_path(name), &backup)?;
+
+        Ok(backup)
+    }
+
+    /// Saves `model` as the project called `name` and returns the path of the written file.
+    ///
+    /// The name stored in the [`header`](crate::assets::Header) of the saved file is set to
+    /// `name`, so picoCAD saves changes back into the same file.
+    /// `model` itself is left untouched.
+    ///
+    /// If there already is a project called `name` it is only replaced if `overwrite` is true,
+    /// otherwise [`PicoError::ProjectExists`] is returned.
+    /// The file is written with [`write_safe`], so a replaced project is never left half written.
+    ///
+    /// Returns [`PicoError::ProjectName`] if `name` contains a path separator or `..`, since the
+    /// project would end up outside of the folder.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use picocadrs::assets::Model;
+    /// use picocadrs::files::ProjectDir;
+    ///
+    /// let projects = ProjectDir::open_default().unwrap();
+    ///
+    /// projects.save_as(&Model::default(), "empty", false).unwrap();
+    /// assert!(projects.save_as(&Model::default(), "empty", false).is_err());
+    /// ```
+    pub fn save_as(
+        &self,
+        model: &Model,
+        name: &str,
+        overwrite: bool,
+    ) -> Result<PathBuf, PicoError> {
+        check_name(name)?;
+
+        let path = self.project_path(name);
+        if !overwrite && path.exists() {
+            return Err(PicoError::ProjectExists(name.to_string()));
+        }
+
+        let mut model = model.clone();
+        model.header.name = name.to_string();
+        write_safe(&path, model.to_string().as_bytes(), false)?;
+
+        Ok(path)
+    }
+
+    /// Starts watching the folder for projects being created, modified or deleted.
+    ///
+    /// Changes are only reported once a project file stayed untouched for
+    /// [`DEFAULT_DEBOUNCE`], so half written files and the temporary files used while saving
+    /// never show up.
+    /// Returns an error if the folder can't be read or watched.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use picocadrs::files::{ProjectDir, ProjectEvent};
+    ///
+    /// let projects = ProjectDir::open_default().unwrap();
+    ///
+    /// for event in projects.watch().unwrap() {
+    ///     if let ProjectEvent::Modified(project) = event {
+    ///         match project.load() {
+    ///             Ok(model) => println!("reloaded {}", model.header.name),
+    ///             Err(error) => println!("{}: {}", project.name, error),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "watch")]
+    pub fn watch(&self) -> Result<ProjectWatcher, PicoError> {
+        let known = self.names()?.into_iter().collect();
+        let (sender, receiver) = mpsc::channel();
+
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(&self.path, RecursiveMode::NonRecursive)?;
+
+        Ok(ProjectWatcher {
+            dir: self.clone(),
+            debounce: DEFAULT_DEBOUNCE,
+            _watcher: watcher,
+            receiver,
+            known,
+            pending: BTreeMap::new(),
+            ready: VecDeque::new(),
+        })
+    }
+}
+
+/// Loads all projects in the folder at `dir` in parallel.
+///
+/// It's recommended to use [`ProjectDir::par_load_all`].
+///
+/// # Example
+///
+/// ```no_run
+/// use picocadrs::files;
+///
+/// for (name, model) in files::par_load_all("community_models").unwrap() {
+///     if let Ok(model) = model {
+///         println!("{}: {} meshes", name, model.meshes.len());
+///     }
+/// }
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_load_all<P: AsRef<Path>>(dir: P) -> Result<Vec<LoadedProject>, PicoError> {
+    ProjectDir::new(dir).par_load_all()
+}
+
+/// Watches the folder at `dir` for projects being created, modified or deleted.
+///
+/// It's recommended to use [`ProjectDir::watch`].
+///
+/// # Example
+///
+/// ```no_run
+/// use picocadrs::files;
+///
+/// for event in files::watch("community_models").unwrap() {
+///     println!("{:?}", event);
+/// }
+/// ```
+#[cfg(feature = "watch")]
+pub fn watch<P: AsRef<Path>>(dir: P) -> Result<ProjectWatcher, PicoError> {
+    ProjectDir::new(dir).watch()
+}
+
+/// Settings used by [`write_safe`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct WriteOptions {
+    /// File to write to.
+    /// [`None`] writes to the project file in picoCADs project folder named after the model
+    /// (see [`Model::write`]).
+    pub path: Option<PathBuf>,
+    /// If true and the file exists already, the previous version is kept as
+    /// `{name}.{timestamp}.bak` next to it.
+    /// The timestamp is given in milliseconds since the unix epoch.
+    pub backup: bool,
+}
+
+/// Exports the model into the file at `path` in the format belonging to its extension.
+///
+/// Uses the built-in exporters, see the [`exporter`](crate::export::exporter) module.
+/// To use other formats, [`register`](ExporterRegistry::register) them in an
+/// [`ExporterRegistry`] and export through that instead.
+///
+/// Returns [`PicoError::UnknownFormat`] if no exporter handles the extension of `path`.
+/// It's recommended to use [`Model::export`].
+///
+/// # Example
+///
+/// ```no_run
+/// use picocadrs::assets::Model;
+/// use picocadrs::files;
+///
+/// let model = Model::default();
+///
+/// files::export(&model, "unnamed.png").unwrap();
+/// files::export(&model, "unnamed.svg").unwrap();
+/// ```
+pub fn export<P: AsRef<Path>>(model: &Model, path: P) -> Result<(), PicoError> {
+    ExporterRegistry::default().export(model, path)
+}
+
+/// Writes `contents` into the file at `path` without ever leaving a partially written file
+/// behind.
+///
+/// The contents are first written into a temporary file next to `path`, which is flushed to disk
+/// and then renamed to `path` in a single step.
+/// On unix the folder is flushed to disk as well afterwards, so the rename survives a crash.
+/// If anything fails along the way the original file stays untouched and the temporary file is
+/// removed.
+///
+/// Returns the path of the backup if one was created.
+/// It's recommended to use [`Model::write_safe`].
+pub fn write_safe(
+    path: &Path,
+    contents: &[u8],
+    backup: bool,
+) -> Result<Option<PathBuf>, PicoError> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("project");
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let temp_path = dir.join(format!(".{}.tmp", file_name));
+
+    let result = write_synced(&temp_path, contents).and_then(|_| {
+        let backup_path = if backup && path.is_file() {
+            Some(backup_timestamped(path, dir)?)
+        } else {
+            None
+        };
+
+        std::fs::rename(&temp_path, path)?;
+        Ok(backup_path)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    let backup_path = result?;
+
+    sync_dir(dir)?;
+
+    Ok(backup_path)
+}
+
+/// Copies the file at `path` to `{stem}.{timestamp}.bak` in `dir` and returns the path of the
+/// copy.
+fn backup_timestamped(path: &Path, dir: &Path) -> Result<PathBuf, PicoError> {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("project");
+    let mut timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or(0);
+
+    let mut backup_path = dir.join(format!("{}.{}.{}", stem, timestamp, BACKUP_EXTENSION));
+    while backup_path.exists() {
+        timestamp += 1;
+        backup_path = dir.join(format!("{}.{}.{}", stem, timestamp, BACKUP_EXTENSION));
+    }
+
+    std::fs::copy(path, &backup_path)?;
+
+    Ok(backup_path)
+}
+
+/// Checks that `name` can be used as the name of a project file without leaving its folder.
+pub(crate) fn check_name(name: &str) -> Result<(), PicoError> {
+    // both kinds of separators are rejected everywhere, so names work on every system.
+    if name.contains(['/', '\\']) || name.contains("..") {
+        return Err(PicoError::ProjectName(name.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Writes `contents` into a new file at `path` and waits until it is stored on disk.
+fn write_synced(path: &Path, contents: &[u8]) -> Result<(), PicoError> {
+    let mut file = File::create(path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    Ok(())
+}
+
+/// Waits until the entries of the folder at `dir` are stored on disk, so a file renamed into it
+/// isn't lost in a crash.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> Result<(), PicoError> {
+    File::open(dir)?.sync_all()?;
+
+    Ok(())
+}
+
+/// Folders can only be opened like files on unix, elsewhere renames are left to the system.
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> Result<(), PicoError> {
+    Ok(())
+}
+
+/// Iterator over the projects of a [`ProjectDir`] returning each projects name and the result of
+/// loading it.
+///
+/// Created by [`ProjectDir::iter`].
+#[derive(Debug)]
+pub struct Projects {
+    dir: ProjectDir,
+    names: std::vec::IntoIter<String>,
+}
+
+impl Iterator for Projects {
+    type Item = LoadedProject;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let name = self.names.next()?;
+        let model = Model::load_from_path(self.dir.project_path(&name).into_os_string());
+
+        Some((name, model))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.names.size_hint()
+    }
+}
+
+/// A project reported by a [`ProjectWatcher`].
+#[cfg(feature = "watch")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WatchedProject {
+    /// Name of the project.
+    pub name: String,
+    /// Path of the project file.
+    pub path: PathBuf,
+}
+
+#[cfg(feature = "watch")]
+impl WatchedProject {
+    /// Loads the current version of the project.
+    ///
+    /// Models are only parsed when this is called, so viewers can skip events they don't care
+    /// about for free.
+    pub fn load(&self) -> Result<Model, PicoError> {
+        Model::load_from_path(self.path.clone().into_os_string())
+    }
+}
+
+/// Change to a project reported by a [`ProjectWatcher`].
+#[cfg(feature = "watch")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ProjectEvent {
+    /// A new project file appeared.
+    Created(WatchedProject),
+    /// An existing project file was saved.
+    Modified(WatchedProject),
+    /// A project file was removed.
+    Deleted(WatchedProject),
+}
+
+#[cfg(feature = "watch")]
+impl ProjectEvent {
+    /// Returns the project the event is about.
+    pub fn project(&self) -> &WatchedProject {
+        match self {
+            ProjectEvent::Created(project)
+            | ProjectEvent::Modified(project)
+            | ProjectEvent::Deleted(project) => project,
+        }
+    }
+}
+
+/// Iterator over changes to the projects of a [`ProjectDir`].
+///
+/// Calling [`next`](Iterator::next) blocks until the next change, use
+/// [`next_timeout`](ProjectWatcher::next_timeout) to wait for a limited time instead.
+/// The iterator only ends if the underlying watcher stops.
+///
+/// Every change to a file restarts a timer and a project is only reported once its timer ran out.
+/// What is reported is decided by comparing the file with what existed before, so saving by
+/// writing into a temporary file and renaming it shows up as a single
+/// [`Modified`](ProjectEvent::Modified) event and a file created and removed again before the
+/// timer ran out isn't reported at all.
+///
+/// Created by [`ProjectDir::watch`].
+#[cfg(feature = "watch")]
+#[derive(Debug)]
+pub struct ProjectWatcher {
+    dir: ProjectDir,
+    debounce: Duration,
+    _watcher: RecommendedWatcher,
+    receiver: Receiver<notify::Result<notify::Event>>,
+    known: HashSet<String>,
+    pending: BTreeMap<String, Instant>,
+    ready: VecDeque<ProjectEvent>,
+}
+
+#[cfg(feature = "watch")]
+impl ProjectWatcher {
+    /// Sets how long a project file has to stay untouched before a change is reported.
+    pub fn with_debounce(mut self, debounce: Duration) -> ProjectWatcher {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Returns the watched folder.
+    pub fn dir(&self) -> &ProjectDir {
+        &self.dir
+    }
+
+    /// Waits up to `timeout` for the next change.
+    ///
+    /// Returns [`None`] if nothing changed in time or the watcher stopped.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<ProjectEvent> {
+        self.next_before(Some(Instant::now() + timeout))
+    }
+
+    /// Waits for the next change until `deadline`, or forever if there is none.
+    fn next_before(&mut self, deadline: Option<Instant>) -> Option<ProjectEvent> {
+        loop {
+            if let Some(event) = self.ready.pop_front() {
+                return Some(event);
+            }
+
+            let now = Instant::now();
+            self.flush(now);
+            if !self.ready.is_empty() {
+                continue;
+            }
+
+            let wake = self
+                .pending
+                .values()
+                .map(|touched| *touched + self.debounce)
+                .chain(deadline)
+                .min();
+
+            let received = match wake {
+                Some(wake) => self
+                    .receiver
+                    .recv_timeout(wake.saturating_duration_since(now)),
+                None => self
+                    .receiver
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+            };
+
+            match received {
+                Ok(Ok(event)) => self.touch(&event.paths),
+                // errors of single events don't stop the watcher.
+                Ok(Err(_)) => {}
+                Err(RecvTimeoutError::Timeout) => {
+                    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                        self.flush(Instant::now());
+                        return self.ready.pop_front();
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.flush_all();
+                    return self.ready.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Restarts the timers of all projects in `paths`.
+    fn touch(&mut self, paths: &[PathBuf]) {
+        let now = Instant::now();
+
+        for path in paths {
+            if path.extension().and_then(|e| e.to_str()) != Some(PROJECT_EXTENSION) {
+                continue;
+            }
+
+            if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
+                self.pending.insert(name.to_string(), now);
+            }
+        }
+    }
+
+    /// Reports all projects whose timer ran out at `now`.
+    fn flush(&mut self, now: Instant) {
+        let due: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, touched)| now.duration_since(**touched) >= self.debounce)
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in due {
+            self.pending.remove(&name);
+            self.report(name);
+        }
+    }
+
+    /// Reports all projects that changed, regardless of their timer.
+    fn flush_all(&mut self) {
+        for name in std::mem::take(&mut self.pending).into_keys() {
+            self.report(name);
+        }
+    }
+
+    /// Compares the project file called `name` with what existed before and queues the event.
+    fn report(&mut self, name: String) {
+        let project = WatchedProject {
+            path: self.dir.project_path(&name),
+            name,
+        };
+
+        let event = match (project.path.is_file(), self.known.contains(&project.name)) {
+            (true, true) => ProjectEvent::Modified(project),
+            (true, false) => {
+                self.known.insert(project.name.clone());
+                ProjectEvent::Created(project)
+            }
+            (false, true) => {
+                self.known.remove(&project.name);
+                ProjectEvent::Deleted(project)
+            }
+            (false, false) => return,
+        };
+
+        self.ready.push_back(event);
+    }
+}
+
+#[cfg(feature = "watch")]
+impl Iterator for ProjectWatcher {
+    type Item = ProjectEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_before(None)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> ProjectDir {
+        let path = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        ProjectDir::new(path)
+    }
+
+    #[test]
+    fn files_save_as_and_find() {
+        let dir = test_dir("picocadrs_files_save_as_and_find");
+
+        let mut model = Model::default();
+        model.header.name = "original".to_string();
+
+        let path = dir.save_as(&model, "copy", false).unwrap();
+        assert_eq!(path, dir.project_path("copy"));
+        assert_eq!(model.header.name, "original");
+
+        assert!(matches!(
+            dir.save_as(&model, "copy", false),
+            Err(PicoError::ProjectExists(name)) if name == "copy"
+        ));
+        assert!(dir.save_as(&model, "copy", true).is_ok());
+
+        for name in ["../copy", "a/b", "a\\b", ".."] {
+            assert!(matches!(
+                dir.save_as(&model, name, true),
+                Err(PicoError::ProjectName(invalid)) if invalid == name
+            ));
+        }
+
+        let loaded = dir.find("copy").unwrap().unwrap();
+        assert_eq!(loaded.header.name, "copy");
+        assert!(dir.find("missing").is_none());
+
+        std::fs::remove_dir_all(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn files_iter() {
+        let dir = test_dir("picocadrs_files_iter");
+
+        dir.save_as(&Model::default(), "b", false).unwrap();
+        dir.save_as(&Model::default(), "a", false).unwrap();
+        std::fs::write(dir.project_path("broken"), "not a project").unwrap();
+        std::fs::write(dir.path().join("notes.md"), "").unwrap();
+
+        assert_eq!(dir.names().unwrap(), vec!["a", "b", "broken"]);
+
+        let projects: Vec<(String, Result<Model, PicoError>)> = dir.iter().unwrap().collect();
+        assert_eq!(projects.len(), 3);
+        assert_eq!(projects[0].0, "a");
+        assert!(projects[1].1.is_ok());
+        assert!(projects[2].1.is_err());
+
+        std::fs::remove_dir_all(dir.path()).unwrap();
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn files_par_load_all() {
+        let dir = test_dir("picocadrs_files_par_load_all");
+
+        for name in ["c", "a", "b"] {
+            dir.save_as(&Model::default(), name, false).unwrap();
+        }
+        std::fs::write(dir.project_path("broken"), "not a project").unwrap();
+
+        let loaded = par_load_all(dir.path()).unwrap();
+        let iterated: Vec<LoadedProject> = dir.iter().unwrap().collect();
+
+        assert_eq!(loaded.len(), iterated.len());
+        for ((name, model), (other_name, other_model)) in loaded.iter().zip(iterated.iter()) {
+            assert_eq!(name, other_name);
+            assert_eq!(model.as_ref().ok(), other_model.as_ref().ok());
+        }
+        assert!(loaded[2].1.is_err());
+
+        std::fs::remove_dir_all(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn files_write_safe() {
+        let dir = test_dir("picocadrs_files_write_safe");
+        let path = dir.project_path("house");
+
+        assert_eq!(write_safe(&path, b"first", true).unwrap(), None);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+
+        let backup = write_safe(&path, b"second", true).unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "first");
+        assert_eq!(backup.extension().unwrap(), BACKUP_EXTENSION);
+
+        let other_backup = write_safe(&path, b"third", true).unwrap().unwrap();
+        assert_ne!(backup, other_backup);
+
+        assert_eq!(write_safe(&path, b"fourth", false).unwrap(), None);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "fourth");
+
+        // no temporary files are left behind.
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 3);
+
+        std::fs::remove_dir_all(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn files_write_safe_cleanup() {
+        let dir = test_dir("picocadrs_files_write_safe_cleanup");
+        let path = dir.project_path("folder");
+        std::fs::create_dir(&path).unwrap();
+
+        // a folder can't be replaced by a file, so the rename fails.
+        assert!(write_safe(&path, b"contents", true).is_err());
+        assert!(path.is_dir());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        std::fs::remove_dir_all(dir.path()).unwrap();
+    }
+
+    #[test]
+    fn files_backup() {
+        let dir = test_dir("picocadrs_files_backup");
+
+        assert!(matches!(
+            dir.backup("house"),
+            Err(PicoError::ProjectNotFound(_))
+        ));
+
+        dir.save_as(&Model::default(), "house", false).unwrap();
+
+        let first = dir.backup("house").unwrap();
+        let second = dir.backup("house").unwrap();
+
+        assert_eq!(first, dir.path().join("house.bak"));
+        assert_eq!(second, dir.path().join("house.1.bak"));
+        assert_eq!(
+            std::fs::read_to_string(second).unwrap(),
+            std::fs::read_to_string(dir.project_path("house")).unwrap()
+        );
+        assert_eq!(dir.names().unwrap(), vec!["house"]);
+
+        std::fs::remove_dir_all(dir.path()).unwrap();
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn files_watch() {
+        let dir = test_dir("picocadrs_files_watch");
+        dir.save_as(&Model::default(), "old", false).unwrap();
+
+        let mut watcher = dir
+            .watch()
+            .unwrap()
+            .with_debounce(Duration::from_millis(50));
+        let timeout = Duration::from_secs(5);
+
+        let path = dir.save_as(&Model::default(), "new", false).unwrap();
+        let event = watcher.next_timeout(timeout).unwrap();
+        assert_eq!(
+            event,
+            ProjectEvent::Created(WatchedProject {
+                name: "new".to_string(),
+                path: path.clone(),
+            })
+        );
+        assert!(event.project().load().is_ok());
+
+        // saving through a temporary file is a single change.
+        write_safe(&path, Model::default().to_string().as_bytes(), false).unwrap();
+        assert!(matches!(
+            watcher.next_timeout(timeout),
+            Some(ProjectEvent::Modified(project)) if project.name == "new"
+        ));
+
+        std::fs::remove_file(dir.project_path("old")).unwrap();
+        assert!(matches!(
+            watcher.next_timeout(timeout),
+            Some(ProjectEvent::Deleted(project)) if project.name == "old"
+        ));
+
+        std::fs::write(dir.path().join("notes.md"), "").unwrap();
+        assert!(watcher.next_timeout(Duration::from_millis(200)).is_none());
+
+        std::fs::remove_dir_all(dir.path()).unwrap();
+    }
+}
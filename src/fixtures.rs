@@ -0,0 +1,80 @@
+//! Geometry shared by the tests of different modules.
+
+use crate::{
+    assets::{Face, Mesh, Point2D, Point3D, UVMap},
+    point,
+};
+use alloc::{string::ToString, vec};
+
+/// A face using the vertices at `indices`, with every uv-coordinate at the origin.
+pub fn face(indices: &[usize]) -> Face {
+    Face {
+        uv_maps: indices
+            .iter()
+            .map(|i| UVMap::new(*i, point!(0.0, 0.0)))
+            .collect(),
+        ..Face::default()
+    }
+}
+
+/// A face using the vertices `0` to `3`, mapped onto the uv rectangle from `min` to `max`.
+pub fn uv_face(min: Point2D<f64>, max: Point2D<f64>) -> Face {
+    Face {
+        uv_maps: vec![
+            UVMap::new(0, point!(min.u, min.v)),
+            UVMap::new(1, point!(max.u, min.v)),
+            UVMap::new(2, point!(max.u, max.v)),
+            UVMap::new(3, point!(min.u, max.v)),
+        ],
+        ..Face::default()
+    }
+}
+
+/// A cube from `-1` to `1` with all faces pointing outwards.
+///
+/// The faces point towards `-y`, `+y`, `-z`, `+x`, `+z` and `-x`, in this order.
+pub fn cube() -> Mesh {
+    let mut mesh = Mesh::new("cube".to_string());
+    mesh.vertices = vec![
+        point!(-1.0, -1.0, -1.0),
+        point!(1.0, -1.0, -1.0),
+        point!(1.0, -1.0, 1.0),
+        point!(-1.0, -1.0, 1.0),
+        point!(-1.0, 1.0, -1.0),
+        point!(1.0, 1.0, -1.0),
+        point!(1.0, 1.0, 1.0),
+        point!(-1.0, 1.0, 1.0),
+    ];
+    mesh.faces = vec![
+        face(&[3, 2, 1, 0]),
+        face(&[4, 5, 6, 7]),
+        face(&[0, 1, 5, 4]),
+        face(&[1, 2, 6, 5]),
+        face(&[2, 3, 7, 6]),
+        face(&[3, 0, 4, 7]),
+    ];
+
+    mesh
+}
+
+/// Checks if every face of the mesh points away from the center of its vertices.
+/// Only meaningful for convex meshes.
+pub fn points_outward(mesh: &Mesh) -> bool {
+    let center = center(&mesh.vertices);
+
+    mesh.faces.iter().all(|face| {
+        let corners = face.corners(&mesh.vertices).unwrap();
+
+        face.normal(&mesh.vertices)
+            .dot(self::center(&corners) - center)
+            > 0.0
+    })
+}
+
+/// Average of the points.
+fn center(points: &[Point3D<f64>]) -> Point3D<f64> {
+    points
+        .iter()
+        .fold(point!(0.0, 0.0, 0.0), |sum, point| sum + *point)
+        / points.len() as f64
+}
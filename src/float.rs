@@ -0,0 +1,40 @@
+//! Float functions that `core` doesn't provide without the standard library.
+//!
+//! Importing [`Float`] makes the methods available under the same names as in `std`, so code
+//! doesn't have to care which one it's built with.
+
+/// Methods of [`f64`] only available with the standard library, implemented using `libm`.
+pub(crate) trait Float {
+    fn sqrt(self) -> f64;
+    fn round(self) -> f64;
+    fn floor(self) -> f64;
+    fn ceil(self) -> f64;
+    fn fract(self) -> f64;
+    fn powf(self, n: f64) -> f64;
+}
+
+impl Float for f64 {
+    fn sqrt(self) -> f64 {
+        libm::sqrt(self)
+    }
+
+    fn round(self) -> f64 {
+        libm::round(self)
+    }
+
+    fn floor(self) -> f64 {
+        libm::floor(self)
+    }
+
+    fn ceil(self) -> f64 {
+        libm::ceil(self)
+    }
+
+    fn fract(self) -> f64 {
+        self - libm::trunc(self)
+    }
+
+    fn powf(self, n: f64) -> f64 {
+        libm::pow(self, n)
+    }
+}
@@ -0,0 +1,323 @@
+//! For generating random meshes from a seed.
+//!
+//! Every generator takes a seed and always returns the same mesh for the same seed and
+//! parameters, so a whole field of rocks can be described by a list of numbers.
+//! The randomness comes from [`Rng`], which can be used on its own for other procedural
+//! tools too.
+//!
+//! - [`rock`]: A lumpy stone with a flat bottom.
+//! - [`tree`]: A trunk with layered cones on top, shaped by [`TreeParams`].
+//! - [`asteroid`]: A lumpy ball with dents.
+//! - [`terrain`](terrain()): A landscape, with its texture colored by altitude, see the
+//!   [`terrain`](mod@terrain) module.
+//!
+//! Generated meshes are centered around their position, trees stand on it.
+//! Their faces are colored instead of textured, so they don't take up any space on the texture and
+//! can be added to any model as they are.
+//! To paint them, turn off [`no_texture`](crate::assets::Face::no_texture) on their faces, which
+//! are already wrapped around the texture like in [`Mesh::lathe`].
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Mesh, Model};
+//! use picocadrs::gen;
+//!
+//! let mut model = Model::default();
+//! for seed in 0..3 {
+//!     model.meshes.push(gen::rock(seed, 1));
+//! }
+//!
+//! assert_eq!(model.meshes[0], gen::rock(0, 1));
+//! assert_ne!(model.meshes[0], model.meshes[1]);
+//! assert!(model.meshes[0].is_watertight());
+//! ```
+
+pub mod rng;
+pub mod terrain;
+
+pub use terrain::{
+    altitude_bands, bake_altitude_bands, noise, noise_terrain, terrain, NoiseParams,
+};
+
+use crate::{
+    assets::{Color, Mesh, Point2D, Point3D},
+    point,
+};
+use rng::Rng;
+use std::f64::consts::{PI, TAU};
+
+/// Shape of the trees created by [`tree`].
+///
+/// Heights are measured from the ground up, radii from the middle of the tree.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TreeParams {
+    /// Height of the trunk up to where the crown starts.
+    pub trunk_height: f64,
+    /// Radius of the trunk.
+    pub trunk_radius: f64,
+    /// Height of the crown on top of the trunk.
+    pub crown_height: f64,
+    /// Radius of the lowest and widest layer of the crown.
+    pub crown_radius: f64,
+    /// Amount of cones stacked on top of each other to form the crown.
+    pub layers: usize,
+    /// Amount of faces around the trunk and each layer of the crown, at least 3.
+    pub segments: usize,
+}
+
+impl Default for TreeParams {
+    /// Returns the parameters of a small pine tree.
+    fn default() -> Self {
+        TreeParams {
+            trunk_height: 1.0,
+            trunk_radius: 0.15,
+            crown_height: 2.0,
+            crown_radius: 1.0,
+            layers: 3,
+            segments: 6,
+        }
+    }
+}
+
+/// Generates a rock about 2 units wide, with more faces the higher `detail` is.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::gen;
+///
+/// let small = gen::rock(4, 0);
+/// let big = gen::rock(4, 2);
+///
+/// assert!(small.faces.len() < big.faces.len());
+/// assert!(small.faces.iter().all(|face| face.no_texture));
+/// ```
+pub fn rock(seed: u64, detail: u32) -> Mesh {
+    let mut rng = Rng::new(seed);
+    let detail = detail as usize;
+
+    let mut mesh = sphere(5 + detail * 2, 3 + detail);
+    mesh.name = "rock".to_string();
+
+    roughen(&mut mesh, &mut rng, 0.25);
+    for vertex in mesh.vertices.iter_mut() {
+        // the y-axis points down, so this flattens the bottom half.
+        vertex.y *= if vertex.y > 0.0 { 0.3 } else { 0.7 };
+    }
+
+    paint(
+        &mut mesh,
+        &mut rng,
+        &[Color::DarkGrey, Color::DarkGrey, Color::LightGrey],
+    );
+    mesh
+}
+
+/// Generates a tree standing on its position.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Color;
+/// use picocadrs::gen::{self, TreeParams};
+///
+/// let params = TreeParams {
+///     layers: 2,
+///     ..TreeParams::default()
+/// };
+/// let tree = gen::tree(1, params);
+///
+/// assert_eq!(tree.faces.len(), 6 * 3 + 2 * 6 * 2);
+/// assert_eq!(tree.faces[0].color, Color::Brown);
+///
+/// // the top of the tree, since the y-axis points down.
+/// let top = tree.vertices.iter().map(|vertex| vertex.y).fold(0.0, f64::min);
+/// assert!((top + 3.0).abs() < 1e-9);
+/// ```
+pub fn tree(seed: u64, params: TreeParams) -> Mesh {
+    let mut rng = Rng::new(seed);
+    let mut mesh = Mesh::new("tree".to_string());
+
+    let mut trunk = Mesh::lathe(
+        &[
+            point!(0.0, 0.0),
+            point!(params.trunk_radius, 0.0),
+            point!(params.trunk_radius, -params.trunk_height),
+            point!(0.0, -params.trunk_height),
+        ],
+        params.segments,
+    );
+    paint(&mut trunk, &mut rng, &[Color::Brown]);
+    append(&mut mesh, trunk);
+
+    let step = params.crown_height / (params.layers + 1) as f64;
+    for layer in 0..params.layers {
+        let bottom = -params.trunk_height - step * layer as f64;
+        let shrink = 1.0 - 0.6 * layer as f64 / params.layers as f64;
+        let radius = params.crown_radius * shrink * rng.range(0.85, 1.15);
+
+        let mut cone = Mesh::lathe(
+            &[
+                point!(0.0, bottom),
+                point!(radius, bottom),
+                point!(0.0, bottom - step * 2.0),
+            ],
+            params.segments,
+        );
+
+        let (sin, cos) = rng.range(0.0, TAU).sin_cos();
+        for vertex in cone.vertices.iter_mut() {
+            *vertex = point!(
+                vertex.x * cos - vertex.z * sin,
+                vertex.y,
+                vertex.x * sin + vertex.z * cos
+            );
+        }
+
+        paint(&mut cone, &mut rng, &[Color::Green, Color::DarkGreen]);
+        append(&mut mesh, cone);
+    }
+
+    mesh
+}
+
+/// Generates an asteroid about `radius` units in radius, with more faces and dents the higher
+/// `detail` is.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::gen;
+///
+/// let asteroid = gen::asteroid(9, 3.0, 1);
+///
+/// assert!(asteroid.is_watertight());
+/// assert!(asteroid
+///     .vertices
+///     .iter()
+///     .all(|vertex| vertex.length() < 3.0 * 1.5));
+/// ```
+pub fn asteroid(seed: u64, radius: f64, detail: u32) -> Mesh {
+    let mut rng = Rng::new(seed);
+    let detail = detail as usize;
+
+    let mut mesh = sphere(6 + detail * 2, 4 + detail);
+    mesh.name = "asteroid".to_string();
+
+    roughen(&mut mesh, &mut rng, 0.35);
+    for _ in 0..=detail {
+        let index = rng.index(mesh.vertices.len());
+        mesh.vertices[index] = mesh.vertices[index] * 0.7;
+    }
+    for vertex in mesh.vertices.iter_mut() {
+        *vertex = *vertex * radius;
+    }
+
+    paint(
+        &mut mesh,
+        &mut rng,
+        &[Color::DarkGrey, Color::Brown, Color::DarkPurple],
+    );
+    mesh
+}
+
+/// Creates a closed ball with a radius of 1 with `segments` faces around the `y`-axis and `rings`
+/// faces from the bottom to the top.
+fn sphere(segments: usize, rings: usize) -> Mesh {
+    let profile: Vec<Point2D<f64>> = (0..=rings)
+        .map(|ring| {
+            if ring == 0 || ring == rings {
+                // exactly on the axis, so the poles are single vertices.
+                return point!(0.0, if ring == 0 { 1.0 } else { -1.0 });
+            }
+
+            let angle = PI * ring as f64 / rings as f64;
+            point!(angle.sin(), angle.cos())
+        })
+        .collect();
+
+    Mesh::lathe(&profile, segments)
+}
+
+/// Moves every vertex closer to or further away from the position of the mesh by up to `amount`
+/// times its distance.
+fn roughen(mesh: &mut Mesh, rng: &mut Rng, amount: f64) {
+    for vertex in mesh.vertices.iter_mut() {
+        *vertex = *vertex * rng.range(1.0 - amount, 1.0 + amount);
+    }
+}
+
+/// Gives every face a random color out of `colors` and turns off its texture.
+fn paint(mesh: &mut Mesh, rng: &mut Rng, colors: &[Color]) {
+    for face in mesh.faces.iter_mut() {
+        face.color = *rng.pick(colors).unwrap_or(&Color::LightGrey);
+        face.no_texture = true;
+    }
+}
+
+/// Adds the vertices and faces of `shape` to `mesh`.
+fn append(mesh: &mut Mesh, shape: Mesh) {
+    let offset = mesh.vertices.len();
+
+    mesh.vertices.extend(shape.vertices);
+    mesh.faces.extend(shape.faces.into_iter().map(|mut face| {
+        for uv_map in face.uv_maps.iter_mut() {
+            uv_map.vertex_index += offset;
+        }
+        face
+    }));
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::fixtures::points_outward;
+
+    #[test]
+    fn gen_deterministic() {
+        for seed in [0, 1, u64::MAX] {
+            assert_eq!(rock(seed, 1), rock(seed, 1));
+            assert_eq!(asteroid(seed, 2.0, 1), asteroid(seed, 2.0, 1));
+            assert_eq!(
+                tree(seed, TreeParams::default()),
+                tree(seed, TreeParams::default())
+            );
+        }
+
+        assert_ne!(rock(0, 1).vertices, rock(1, 1).vertices);
+        assert_ne!(asteroid(0, 1.0, 0).vertices, asteroid(1, 1.0, 0).vertices);
+    }
+
+    #[test]
+    fn gen_closed_shapes() {
+        for detail in 0..3 {
+            let rock = rock(5, detail);
+            let asteroid = asteroid(5, 1.0, detail);
+
+            assert!(rock.is_watertight());
+            assert!(asteroid.is_watertight());
+            assert!(points_outward(&rock));
+            assert!(points_outward(&asteroid));
+            assert!(rock.faces.iter().all(|face| face.no_texture));
+        }
+    }
+
+    #[test]
+    fn gen_tree() {
+        let tree = tree(3, TreeParams::default());
+
+        assert_eq!(tree.faces.len(), 6 * 3 + 3 * 6 * 2);
+        assert!(tree.faces[..18]
+            .iter()
+            .all(|face| face.color == Color::Brown));
+        assert!(tree.faces[18..]
+            .iter()
+            .all(|face| face.color == Color::Green || face.color == Color::DarkGreen));
+        assert!(tree
+            .faces
+            .iter()
+            .flat_map(|face| face.uv_maps.iter())
+            .all(|uv_map| uv_map.vertex_index < tree.vertices.len()));
+    }
+}
@@ -0,0 +1,103 @@
+//! For deterministic random numbers.
+//!
+//! [`Rng`] is a small [SplitMix64](https://prng.di.unimi.it/splitmix64.c) generator.
+//! The same seed always produces the same numbers, on every platform and in every version of this
+//! crate, so generated models can be shared by their seed alone.
+//! It isn't suited for anything security related.
+
+/// Deterministic random number generator, see the [`rng`](crate::gen::rng) module.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::gen::rng::Rng;
+///
+/// let mut a = Rng::new(7);
+/// let mut b = Rng::new(7);
+///
+/// assert_eq!(a.next_u64(), b.next_u64());
+///
+/// let value = a.range(-1.0, 1.0);
+/// assert!((-1.0..1.0).contains(&value));
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator starting from the given seed.
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Returns the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number in `0.0..1.0`.
+    pub fn next_f64(&mut self) -> f64 {
+        // the upper 53 bits fill the mantissa of a f64 exactly.
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a random number in `min..max`.
+    pub fn range(&mut self, min: f64, max: f64) -> f64 {
+        min + self.next_f64() * (max - min)
+    }
+
+    /// Returns a random index in `0..len`, or `0` if `len` is `0`.
+    pub fn index(&mut self, len: usize) -> usize {
+        if len == 0 {
+            return 0;
+        }
+
+        (self.next_u64() % len as u64) as usize
+    }
+
+    /// Returns a random element of `items`, or `None` if it is empty.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+
+        items.get(self.index(items.len()))
+    }
+
+    /// Returns `true` with the given chance (`0.0 - 1.0`).
+    pub fn chance(&mut self, chance: f64) -> bool {
+        self.next_f64() < chance
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_deterministic() {
+        // reference values of SplitMix64 seeded with 0.
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+
+        let mut rng = Rng::new(42);
+        for _ in 0..1000 {
+            let value = rng.next_f64();
+            assert!((0.0..1.0).contains(&value));
+            assert!(rng.index(3) < 3);
+        }
+
+        assert_eq!(rng.index(0), 0);
+        assert_eq!(rng.pick::<u8>(&[]), None);
+        assert_eq!(rng.pick(&[5]), Some(&5));
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+    }
+}
@@ -0,0 +1,378 @@
+//! For generating landscapes.
+//!
+//! [`terrain`] builds a grid of `width` by `depth` cells, each 1 unit wide, whose height is given
+//! by a function of the grid coordinates.
+//! [`noise_terrain`] uses [`noise`] as that function, which creates rolling hills out of a seed and
+//! [`NoiseParams`].
+//!
+//! Every cell of the grid is mapped onto its own pixel of the texture, the cell in column `x` and
+//! row `z` onto the pixel at `x, z`.
+//! [`bake_altitude_bands`] paints these pixels depending on how high their cell lies, so the
+//! terrain gets colored like a map without drawing a texture by hand.
+//! Since the texture only has 128x120 pixels, grids are never bigger than that.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Color, Model, Point2D};
+//! use picocadrs::gen::{self, NoiseParams};
+//! use picocadrs::point;
+//!
+//! let params = NoiseParams {
+//!     seed: 12,
+//!     ..NoiseParams::default()
+//! };
+//!
+//! let mut model = Model::default();
+//! let terrain = gen::noise_terrain(32, 32, &params);
+//! gen::bake_altitude_bands(&terrain, &mut model.footer, &gen::altitude_bands(params.height));
+//! model.meshes.push(terrain);
+//!
+//! assert_eq!(model.meshes[0].faces.len(), 32 * 32);
+//! assert_ne!(model.footer.pixel(point!(0, 0)), Color::Black);
+//! ```
+
+use crate::{
+    assets::{Color, Face, Footer, Mesh, Point2D, Point3D, UVMap},
+    gen::rng::Rng,
+    point,
+};
+
+/// Shape of the hills created by [`noise`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NoiseParams {
+    /// Seed of the random heights.
+    pub seed: u64,
+    /// Distance in cells between the biggest hills.
+    pub scale: f64,
+    /// Amount of layers of noise added on top of each other, each with half the scale of the
+    /// previous one.
+    /// More octaves add smaller bumps to the hills.
+    pub octaves: u32,
+    /// How much each octave contributes compared to the previous one, usually `0.0 - 1.0`.
+    pub persistence: f64,
+    /// Highest possible height.
+    pub height: f64,
+}
+
+impl Default for NoiseParams {
+    /// Returns the parameters of gentle hills at most 2 units high.
+    fn default() -> Self {
+        NoiseParams {
+            seed: 0,
+            scale: 8.0,
+            octaves: 3,
+            persistence: 0.5,
+            height: 2.0,
+        }
+    }
+}
+
+/// Returns the height (`0.0 - params.height`) of smooth random hills at the given grid
+/// coordinates.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::gen::{noise, NoiseParams};
+///
+/// let params = NoiseParams::default();
+/// let height = noise(&params, 3.5, 7.0);
+///
+/// assert!((0.0..=params.height).contains(&height));
+/// assert_eq!(height, noise(&params, 3.5, 7.0));
+/// ```
+pub fn noise(params: &NoiseParams, x: f64, z: f64) -> f64 {
+    let mut sum = 0.0;
+    let mut total = 0.0;
+    let mut amplitude = 1.0;
+    let mut scale = params.scale.max(f64::EPSILON);
+
+    for octave in 0..params.octaves.max(1) {
+        sum += value_noise(params.seed, octave, x / scale, z / scale) * amplitude;
+        total += amplitude;
+
+        amplitude *= params.persistence;
+        scale /= 2.0;
+    }
+
+    if total <= 0.0 {
+        return 0.0;
+    }
+
+    (sum / total).clamp(0.0, 1.0) * params.height
+}
+
+/// Builds a grid of `width` by `depth` cells, raising every corner by `height(x, z)`.
+///
+/// `x` goes from `0` to `width` and `z` from `0` to `depth`, so corners between the same cells
+/// always get the same height.
+/// The grid is centered around the position of the mesh and limited to 128x120 cells.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Point3D;
+/// use picocadrs::gen;
+/// use picocadrs::point;
+///
+/// let ramp = gen::terrain(4, 2, |x, _| x * 0.5);
+///
+/// assert_eq!(ramp.vertices.len(), 5 * 3);
+/// assert_eq!(ramp.faces.len(), 4 * 2);
+/// // the y-axis points down, so raised corners get negative values.
+/// assert_eq!(ramp.vertices[4], point!(2.0, -2.0, -1.0));
+/// ```
+pub fn terrain<F: Fn(f64, f64) -> f64>(width: usize, depth: usize, height: F) -> Mesh {
+    let width = width.min(Footer::WIDTH);
+    let depth = depth.min(Footer::HEIGHT);
+
+    let mut mesh = Mesh::new("terrain".to_string());
+
+    if width == 0 || depth == 0 {
+        return mesh;
+    }
+
+    // centers the grid around the position of the mesh.
+    let offset_x = width as f64 / 2.0;
+    let offset_z = depth as f64 / 2.0;
+
+    for z in 0..=depth {
+        for x in 0..=width {
+            mesh.vertices.push(point!(
+                x as f64 - offset_x,
+                -height(x as f64, z as f64),
+                z as f64 - offset_z
+            ));
+        }
+    }
+
+    for z in 0..depth {
+        for x in 0..width {
+            let corners = [(x, z), (x, z + 1), (x + 1, z + 1), (x + 1, z)];
+
+            // the corners of the cell are mapped onto the corners of its pixel.
+            mesh.faces.push(Face {
+                uv_maps: corners
+                    .iter()
+                    .map(|(u, v)| {
+                        UVMap::new(
+                            v * (width + 1) + u,
+                            point!(*u as f64 / 8.0, *v as f64 / 8.0),
+                        )
+                    })
+                    .collect(),
+                color: Color::Green,
+                ..Face::default()
+            });
+        }
+    }
+
+    mesh
+}
+
+/// Builds a grid like [`terrain`], using [`noise`] for the heights.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::gen::{self, NoiseParams};
+///
+/// let params = NoiseParams::default();
+///
+/// assert_eq!(gen::noise_terrain(16, 8, &params), gen::noise_terrain(16, 8, &params));
+/// ```
+pub fn noise_terrain(width: usize, depth: usize, params: &NoiseParams) -> Mesh {
+    terrain(width, depth, |x, z| noise(params, x, z))
+}
+
+/// Returns bands for terrain up to `height` units high, going from water over sand, grass,
+/// forest and rock up to snow.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Color;
+/// use picocadrs::gen;
+///
+/// let bands = gen::altitude_bands(10.0);
+///
+/// assert_eq!(bands[0], (0.0, Color::DarkBlue));
+/// assert_eq!(bands.last(), Some(&(9.0, Color::White)));
+/// ```
+pub fn altitude_bands(height: f64) -> Vec<(f64, Color)> {
+    [
+        (0.0, Color::DarkBlue),
+        (0.2, Color::Yellow),
+        (0.3, Color::Green),
+        (0.55, Color::DarkGreen),
+        (0.75, Color::DarkGrey),
+        (0.9, Color::White),
+    ]
+    .iter()
+    .map(|(fraction, color)| (fraction * height, *color))
+    .collect()
+}
+
+/// Paints the pixels mapped onto each face of `mesh` in the color of the band its altitude falls
+/// into.
+///
+/// `bands` are pairs of the lowest altitude of a band and its color, sorted from low to high.
+/// The altitude of a face is the average height of its corners above the position of the mesh.
+/// Faces below the lowest band get its color too.
+///
+/// Meant for meshes from [`terrain`], but works with any mesh whose faces don't share pixels.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Color, Footer, Point2D};
+/// use picocadrs::gen;
+/// use picocadrs::point;
+///
+/// let ramp = gen::terrain(4, 1, |x, _| x);
+/// let mut footer = Footer::default();
+///
+/// gen::bake_altitude_bands(&ramp, &mut footer, &[(0.0, Color::Blue), (2.0, Color::White)]);
+///
+/// assert_eq!(footer.pixel(point!(1, 0)), Color::Blue);
+/// assert_eq!(footer.pixel(point!(2, 0)), Color::White);
+/// ```
+pub fn bake_altitude_bands(mesh: &Mesh, footer: &mut Footer, bands: &[(f64, Color)]) {
+    let Some(first) = bands.first() else {
+        return;
+    };
+
+    for face in mesh.faces.iter() {
+        let Some(corners) = face.corners(&mesh.vertices) else {
+            continue;
+        };
+        if corners.is_empty() {
+            continue;
+        }
+
+        let center = corners
+            .iter()
+            .fold(point!(0.0, 0.0, 0.0), |sum, corner| sum + *corner)
+            / corners.len() as f64;
+        let altitude = -center.y;
+
+        let color = bands
+            .iter()
+            .take_while(|(start, _)| *start <= altitude)
+            .last()
+            .unwrap_or(first)
+            .1;
+
+        for texel in face.uv_texels() {
+            let _ = footer.set(texel, color);
+        }
+    }
+}
+
+/// Returns smoothly interpolated random values (`0.0 - 1.0`) between the corners of a grid.
+fn value_noise(seed: u64, octave: u32, x: f64, z: f64) -> f64 {
+    let (x0, z0) = (x.floor(), z.floor());
+    let (tx, tz) = (smoothstep(x - x0), smoothstep(z - z0));
+    let (x0, z0) = (x0 as i64, z0 as i64);
+
+    let corner = |x: i64, z: i64| lattice(seed, octave, x, z);
+
+    let top = lerp(corner(x0, z0), corner(x0 + 1, z0), tx);
+    let bottom = lerp(corner(x0, z0 + 1), corner(x0 + 1, z0 + 1), tx);
+
+    lerp(top, bottom, tz)
+}
+
+/// Returns the random value (`0.0 - 1.0`) at a corner of the grid.
+fn lattice(seed: u64, octave: u32, x: i64, z: i64) -> f64 {
+    let mut rng = Rng::new(seed);
+    let hash = rng.next_u64()
+        ^ (x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+        ^ (z as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f)
+        ^ (octave as u64).wrapping_mul(0x1656_67b1_9e37_79f9);
+
+    Rng::new(hash).next_f64()
+}
+
+fn smoothstep(t: f64) -> f64 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn terrain_grid() {
+        let mesh = terrain(3, 2, |x, z| x + z * 10.0);
+
+        assert_eq!(mesh.vertices.len(), 4 * 3);
+        assert_eq!(mesh.faces.len(), 3 * 2);
+        assert_eq!(mesh.vertices[0], point!(-1.5, 0.0, -1.0));
+        assert_eq!(mesh.vertices[11], point!(1.5, -23.0, 1.0));
+
+        for (index, face) in mesh.faces.iter().enumerate() {
+            // faces point up.
+            assert!(face.normal(&mesh.vertices).y < 0.0);
+            assert_eq!(face.uv_texels(), vec![point!(index % 3, index / 3)]);
+        }
+
+        assert_eq!(terrain(200, 200, |_, _| 0.0).faces.len(), 128 * 120);
+        assert!(terrain(0, 5, |_, _| 0.0).vertices.is_empty());
+    }
+
+    #[test]
+    fn terrain_noise() {
+        let params = NoiseParams {
+            seed: 3,
+            height: 5.0,
+            ..NoiseParams::default()
+        };
+
+        let mut lowest = f64::INFINITY;
+        let mut highest = f64::NEG_INFINITY;
+        for z in 0..64 {
+            for x in 0..64 {
+                let height = noise(&params, x as f64 * 0.5, z as f64 * 0.5);
+                lowest = lowest.min(height);
+                highest = highest.max(height);
+
+                // neighbouring points are close to each other.
+                assert!(
+                    (height - noise(&params, x as f64 * 0.5 + 0.01, z as f64 * 0.5)).abs() < 0.1
+                );
+            }
+        }
+
+        assert!(lowest >= 0.0);
+        assert!(highest <= 5.0);
+        assert!(highest - lowest > 1.0);
+
+        let other = NoiseParams { seed: 4, ..params };
+        assert_ne!(noise(&params, 1.5, 2.5), noise(&other, 1.5, 2.5));
+    }
+
+    #[test]
+    fn terrain_bands() {
+        let mesh = terrain(3, 1, |x, _| x * 2.0);
+        let mut footer = Footer::default();
+        footer.set(point!(3, 0), Color::Pink).unwrap();
+
+        bake_altitude_bands(
+            &mesh,
+            &mut footer,
+            &[(2.0, Color::Blue), (3.0, Color::Green), (5.0, Color::White)],
+        );
+
+        assert_eq!(footer.pixel(point!(0, 0)), Color::Blue);
+        assert_eq!(footer.pixel(point!(1, 0)), Color::Green);
+        assert_eq!(footer.pixel(point!(2, 0)), Color::White);
+        assert_eq!(footer.pixel(point!(3, 0)), Color::Pink);
+        assert_eq!(footer.pixel(point!(0, 1)), Color::Black);
+    }
+}
@@ -0,0 +1,591 @@
+//! For separating meshes into the parts that aren't connected to each other, and joining meshes
+//! back together.
+//!
+//! [`extract_faces`] separates any selection of faces instead, [`flatten`] joins every mesh of a
+//! model into one.
+//! Two faces belong to the same part if they share a vertex, directly or through other faces.
+//! Imported models often arrive as one big mesh holding many separate pieces, which is hard to
+//! work with in picoCAD's editor.
+//! [`split_connected_components`] turns each piece into its own mesh and [`join_meshes`] does the
+//! opposite.
+//!
+//! Faces keep their uv-coordinates and attributes, so the texture stays in place.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Mesh, Model, Point3D};
+//! use picocadrs::point;
+//!
+//! let mut far = Mesh::plane();
+//! far.position = point!(4.0, 0.0, 0.0);
+//!
+//! let mut model = Model::default();
+//! model.meshes = vec![Mesh::plane(), far];
+//!
+//! let joined = model.join_meshes(&[0, 1], "planes".to_string()).unwrap();
+//! assert_eq!(model.meshes.len(), 1);
+//! assert_eq!(model.meshes[joined].faces.len(), 2);
+//!
+//! assert_eq!(model.explode_components(), 1);
+//! assert_eq!(model.meshes.len(), 2);
+//! assert_eq!(model.meshes[1].name, "planes_2");
+//! ```
+
+use crate::{
+    assets::{Face, Mesh, Model},
+    error::PicoError,
+};
+use std::collections::BTreeMap;
+
+/// Returns the indices of the faces of each connected part of the mesh, ordered by their first
+/// face.
+///
+/// Faces without corners or with corners referring to vertices that don't exist are left out.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Mesh;
+/// use picocadrs::geometry::components::connected_components;
+///
+/// let mut mesh = Mesh::plane();
+/// mesh.faces.push(mesh.faces[0].clone());
+///
+/// // both faces use the same vertices.
+/// assert_eq!(connected_components(&mesh), vec![vec![0, 1]]);
+/// ```
+pub fn connected_components(mesh: &Mesh) -> Vec<Vec<usize>> {
+    let mut parents: Vec<usize> = (0..mesh.vertices.len()).collect();
+
+    for face in mesh.faces.iter() {
+        if !is_valid(mesh, face_indices(face)) {
+            continue;
+        }
+
+        let mut indices = face_indices(face);
+        let first = indices.next().unwrap_or_default();
+        for index in indices {
+            let (a, b) = (root(&mut parents, first), root(&mut parents, index));
+            parents[b] = a;
+        }
+    }
+
+    let mut components: Vec<Vec<usize>> = vec![];
+    let mut by_root: BTreeMap<usize, usize> = BTreeMap::new();
+
+    for (face_index, face) in mesh.faces.iter().enumerate() {
+        if !is_valid(mesh, face_indices(face)) {
+            continue;
+        }
+
+        let first = face.uv_maps[0].vertex_index;
+        let component = *by_root.entry(root(&mut parents, first)).or_insert_with(|| {
+            components.push(vec![]);
+            components.len() - 1
+        });
+        components[component].push(face_index);
+    }
+
+    components
+}
+
+/// Returns a new mesh for each connected part of the mesh.
+///
+/// The meshes keep the position, rotation and extras of the original mesh and only hold the
+/// vertices their faces use, in their original order.
+/// If there are multiple parts, they are named after the mesh with their number appended, like
+/// `name_1`, `name_2` and so on.
+/// Faces without corners or with corners referring to vertices that don't exist are left out.
+///
+/// It's recommended to use [`Mesh::split_connected_components`].
+pub fn split_connected_components(mesh: &Mesh) -> Vec<Mesh> {
+    let components = connected_components(mesh);
+    let count = components.len();
+
+    components
+        .into_iter()
+        .enumerate()
+        .map(|(number, faces)| {
+            let mut part = sub_mesh(mesh, &faces);
+            if count > 1 {
+                part.name = format!("{}_{}", mesh.name, number + 1);
+            }
+
+            part
+        })
+        .collect()
+}
+
+/// Moves the faces at `indices` out of the mesh into a new one and returns it.
+///
+/// The new mesh keeps the name, position, rotation and extras of the mesh and only holds the
+/// vertices its faces use, in their original order.
+/// Vertices only the moved faces used are removed from the mesh, vertices shared with faces
+/// that stay are copied.
+/// Indices without a face, duplicates and faces referring to vertices that don't exist are
+/// ignored, those faces stay in the mesh.
+///
+/// It's recommended to use [`Mesh::extract_faces`].
+pub fn extract_faces(mesh: &mut Mesh, indices: &[usize]) -> Mesh {
+    let mut indices: Vec<usize> = indices
+        .iter()
+        .copied()
+        .filter(|index| {
+            mesh.faces
+                .get(*index)
+                .is_some_and(|face| is_valid(mesh, face_indices(face)))
+        })
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+
+    let extracted = sub_mesh(mesh, &indices);
+
+    let mut moved_vertices = vec![false; mesh.vertices.len()];
+    for index in indices.iter().rev() {
+        for vertex in face_indices(&mesh.faces.remove(*index)) {
+            moved_vertices[vertex] = true;
+        }
+    }
+    for vertex in mesh.faces.iter().flat_map(face_indices) {
+        if let Some(moved) = moved_vertices.get_mut(vertex) {
+            *moved = false;
+        }
+    }
+
+    // new index of every vertex, vertices that are removed don't need one.
+    let mut new_indices: Vec<usize> = Vec::with_capacity(moved_vertices.len());
+    let mut removed = 0;
+    for moved in moved_vertices.iter() {
+        new_indices.push(new_indices.len() - removed);
+        if *moved {
+            removed += 1;
+        }
+    }
+
+    for uv_map in mesh
+        .faces
+        .iter_mut()
+        .flat_map(|face| face.uv_maps.iter_mut())
+    {
+        uv_map.vertex_index = match new_indices.get(uv_map.vertex_index) {
+            Some(index) => *index,
+            None => uv_map.vertex_index - removed,
+        };
+    }
+
+    let mut moved = moved_vertices.iter();
+    mesh.vertices.retain(|_| !*moved.next().unwrap_or(&false));
+
+    extracted
+}
+
+/// Replaces every mesh of the model with its connected parts, see
+/// [`split_connected_components`], and returns the amount of meshes that were added.
+///
+/// The parts take the place of the mesh they came from, so the order of the meshes stays the
+/// same otherwise.
+/// Meshes without any valid faces are left as they are.
+///
+/// It's recommended to use [`Model::explode_components`].
+pub fn explode_components(model: &mut Model) -> usize {
+    let before = model.meshes.len();
+    let meshes = std::mem::take(&mut model.meshes);
+
+    for mesh in meshes {
+        let parts = split_connected_components(&mesh);
+
+        if parts.is_empty() {
+            model.meshes.push(mesh);
+        } else {
+            model.meshes.extend(parts);
+        }
+    }
+
+    model.meshes.len() - before
+}
+
+/// Joins the meshes at `indices` into a single mesh called `name` and returns its index.
+///
+/// The joined mesh takes the place of the first of the meshes in the model and keeps its
+/// position and rotation.
+/// Vertices of the other meshes are moved so they stay at the same place in the scene.
+/// Duplicate indices are ignored.
+/// If `indices` is empty, an empty mesh is added at the end of the model.
+///
+/// Returns [`PicoError::MeshIndex`] if any index is out of range, without changing the model.
+///
+/// It's recommended to use [`Model::join_meshes`].
+pub fn join_meshes(model: &mut Model, indices: &[usize], name: String) -> Result<usize, PicoError> {
+    if let Some(index) = indices.iter().find(|index| **index >= model.meshes.len()) {
+        return Err(PicoError::MeshIndex(*index, model.meshes.len()));
+    }
+
+    let mut indices = indices.to_vec();
+    indices.sort_unstable();
+    indices.dedup();
+
+    let Some(first) = indices.first().copied() else {
+        model.meshes.push(Mesh::new(name));
+        return Ok(model.meshes.len() - 1);
+    };
+
+    let mut joined = model.meshes[first].clone();
+    joined.name = name;
+
+    for index in indices.iter().skip(1) {
+        let mesh = &model.meshes[*index];
+        let offset = joined.vertices.len();
+        let shift = mesh.position - joined.position;
+
+        joined
+            .vertices
+            .extend(mesh.vertices.iter().map(|vertex| *vertex + shift));
+        joined
+            .faces
+            .extend(mesh.faces.iter().cloned().map(|mut face| {
+                for uv_map in face.uv_maps.iter_mut() {
+                    uv_map.vertex_index += offset;
+                }
+                face
+            }));
+    }
+
+    for index in indices.iter().skip(1).rev() {
+        model.meshes.remove(*index);
+    }
+    model.meshes[first] = joined;
+
+    Ok(first)
+}
+
+/// Replaces every mesh of the model with a single mesh called `name` and returns the amount of
+/// vertices that were merged.
+///
+/// Positions of the meshes are added to their vertices and the new mesh sits at the origin.
+/// Vertices at exactly the same place are merged into one, faces keep their uv-coordinates and
+/// attributes.
+/// Faces referring to vertices that don't exist are left out.
+/// A model without meshes gets an empty one.
+///
+/// It's recommended to use [`Model::flatten_to_single_mesh`].
+pub fn flatten(model: &mut Model, name: String) -> usize {
+    let mut flat = Mesh::new(name);
+    let mut lookup: BTreeMap<[u64; 3], usize> = BTreeMap::new();
+    let mut vertex_count = 0;
+
+    for mesh in std::mem::take(&mut model.meshes) {
+        vertex_count += mesh.vertices.len();
+
+        let indices: Vec<usize> = mesh
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let vertex = *vertex + mesh.position;
+                // adding `0.0` turns `-0.0` into `0.0`, so both are merged.
+                let key = [
+                    (vertex.x + 0.0).to_bits(),
+                    (vertex.y + 0.0).to_bits(),
+                    (vertex.z + 0.0).to_bits(),
+                ];
+
+                *lookup.entry(key).or_insert_with(|| {
+                    flat.vertices.push(vertex);
+                    flat.vertices.len() - 1
+                })
+            })
+            .collect();
+
+        for face in mesh.faces.iter() {
+            if !is_valid(&mesh, face_indices(face)) {
+                continue;
+            }
+
+            let mut face = face.clone();
+            for uv_map in face.uv_maps.iter_mut() {
+                uv_map.vertex_index = indices[uv_map.vertex_index];
+            }
+            flat.faces.push(face);
+        }
+    }
+
+    model.meshes.push(flat);
+    vertex_count - model.meshes[0].vertices.len()
+}
+
+/// Returns a copy of the mesh holding only the faces at `faces` and the vertices they use.
+///
+/// Every corner of the faces has to refer to a vertex that exists.
+fn sub_mesh(mesh: &Mesh, faces: &[usize]) -> Mesh {
+    let mut used: Vec<usize> = faces
+        .iter()
+        .flat_map(|face| face_indices(&mesh.faces[*face]))
+        .collect();
+    used.sort_unstable();
+    used.dedup();
+
+    let remap: BTreeMap<usize, usize> = used
+        .iter()
+        .enumerate()
+        .map(|(new, old)| (*old, new))
+        .collect();
+
+    let mut part = mesh.clone();
+    part.vertices = used.iter().map(|index| mesh.vertices[*index]).collect();
+    part.faces = faces
+        .iter()
+        .map(|index| {
+            let mut face = mesh.faces[*index].clone();
+            for uv_map in face.uv_maps.iter_mut() {
+                uv_map.vertex_index = remap[&uv_map.vertex_index];
+            }
+            face
+        })
+        .collect();
+
+    part
+}
+
+/// Returns the vertex indices of the corners of the face.
+fn face_indices(face: &Face) -> impl Iterator<Item = usize> + '_ {
+    face.uv_maps.iter().map(|uv_map| uv_map.vertex_index)
+}
+
+/// Checks if the face has corners and all of them refer to vertices that exist.
+fn is_valid(mesh: &Mesh, mut indices: impl Iterator<Item = usize>) -> bool {
+    let mut any = false;
+    let all = indices.all(|index| {
+        any = true;
+        index < mesh.vertices.len()
+    });
+
+    any && all
+}
+
+/// Returns the representative of the set `index` belongs to, flattening the path to it.
+fn root(parents: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parents[root] != root {
+        root = parents[root];
+    }
+
+    let mut current = index;
+    while parents[current] != root {
+        let next = parents[current];
+        parents[current] = root;
+        current = next;
+    }
+
+    root
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Color, Point2D, Point3D, UVMap};
+    use crate::point;
+
+    /// Two separate triangles with a quad attached to the second one and an unused vertex.
+    fn islands() -> Mesh {
+        let mut mesh = Mesh::new("islands".to_string());
+        mesh.position = point!(1.0, 0.0, 0.0);
+        mesh.vertices = vec![
+            point!(10.0, 0.0, 0.0),
+            point!(0.0, 0.0, 0.0),
+            point!(1.0, 0.0, 0.0),
+            point!(0.0, 0.0, 1.0),
+            point!(11.0, 0.0, 0.0),
+            point!(10.0, 0.0, 1.0),
+            point!(99.0, 0.0, 0.0),
+            point!(11.0, 0.0, 1.0),
+        ];
+
+        let face = |indices: &[usize]| Face {
+            uv_maps: indices
+                .iter()
+                .map(|i| UVMap::new(*i, point!(*i as f64, 0.0)))
+                .collect(),
+            ..Face::default()
+        };
+
+        mesh.faces = vec![
+            face(&[0, 4, 5]),
+            face(&[1, 2, 3]),
+            face(&[4, 7, 5]),
+            face(&[]),
+        ];
+
+        mesh
+    }
+
+    #[test]
+    fn components_connected() {
+        assert_eq!(connected_components(&islands()), vec![vec![0, 2], vec![1]]);
+
+        let mut broken = islands();
+        broken.faces[1].uv_maps[0].vertex_index = 20;
+        assert_eq!(connected_components(&broken), vec![vec![0, 2]]);
+    }
+
+    #[test]
+    fn components_split() {
+        let parts = islands().split_connected_components();
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].name, "islands_1");
+        assert_eq!(parts[1].name, "islands_2");
+        assert_eq!(parts[0].position, point!(1.0, 0.0, 0.0));
+
+        assert_eq!(parts[0].vertices.len(), 4);
+        assert_eq!(parts[0].vertices[3], point!(11.0, 0.0, 1.0));
+        assert_eq!(
+            parts[0].faces[1].corners(&parts[0].vertices).unwrap(),
+            vec![
+                point!(11.0, 0.0, 0.0),
+                point!(11.0, 0.0, 1.0),
+                point!(10.0, 0.0, 1.0)
+            ]
+        );
+        // uvs stay with their corners.
+        assert_eq!(parts[0].faces[1].uv_maps[1].coords, point!(7.0, 0.0));
+        assert_eq!(parts[1].vertices.len(), 3);
+
+        let single = Mesh::plane().split_connected_components();
+        assert_eq!(single, vec![Mesh::plane()]);
+    }
+
+    #[test]
+    fn components_explode_join() {
+        let mut model = Model {
+            meshes: vec![Mesh::plane(), islands(), Mesh::new("empty".to_string())],
+            ..Model::default()
+        };
+
+        assert_eq!(model.explode_components(), 1);
+        let names: Vec<&str> = model.meshes.iter().map(|mesh| mesh.name.as_str()).collect();
+        assert_eq!(names, vec!["plane", "islands_1", "islands_2", "empty"]);
+
+        let joined = model
+            .join_meshes(&[2, 1, 2], "islands".to_string())
+            .unwrap();
+        assert_eq!(joined, 1);
+        assert_eq!(model.meshes.len(), 3);
+        assert_eq!(model.meshes[1].faces.len(), 3);
+        assert_eq!(model.meshes[1].vertices.len(), 7);
+        assert_eq!(model.meshes[2].name, "empty");
+
+        assert!(matches!(
+            model.join_meshes(&[0, 3], "none".to_string()),
+            Err(PicoError::MeshIndex(3, 3))
+        ));
+        assert_eq!(model.meshes.len(), 3);
+
+        assert_eq!(model.join_meshes(&[], "new".to_string()).unwrap(), 3);
+    }
+
+    #[test]
+    fn components_join_positions() {
+        let mut far = Mesh::plane();
+        far.position = point!(0.0, -2.0, 3.0);
+
+        let mut model = Model {
+            meshes: vec![Mesh::plane(), far],
+            ..Model::default()
+        };
+        model.meshes[0].position = point!(1.0, 0.0, 0.0);
+
+        model.join_meshes(&[0, 1], "planes".to_string()).unwrap();
+
+        let mesh = &model.meshes[0];
+        assert_eq!(mesh.position, point!(1.0, 0.0, 0.0));
+        assert_eq!(mesh.vertices[4], point!(-2.0, -2.0, 2.0));
+    }
+
+    #[test]
+    fn components_extract_faces() {
+        let mut mesh = islands();
+        let extracted = mesh.extract_faces(&[2, 7, 2]);
+
+        assert_eq!(extracted.name, "islands");
+        assert_eq!(extracted.position, point!(1.0, 0.0, 0.0));
+        assert_eq!(extracted.faces.len(), 1);
+        assert_eq!(
+            extracted.faces[0].corners(&extracted.vertices).unwrap(),
+            vec![
+                point!(11.0, 0.0, 0.0),
+                point!(11.0, 0.0, 1.0),
+                point!(10.0, 0.0, 1.0)
+            ]
+        );
+        assert_eq!(extracted.faces[0].uv_maps[1].coords, point!(7.0, 0.0));
+
+        // only the vertex the other faces don't use is removed.
+        assert_eq!(mesh.faces.len(), 3);
+        assert_eq!(mesh.vertices.len(), 7);
+        assert!(!mesh.vertices.contains(&point!(11.0, 0.0, 1.0)));
+        assert!(mesh.vertices.contains(&point!(99.0, 0.0, 0.0)));
+        assert_eq!(
+            mesh.faces[0].corners(&mesh.vertices).unwrap(),
+            vec![
+                point!(10.0, 0.0, 0.0),
+                point!(11.0, 0.0, 0.0),
+                point!(10.0, 0.0, 1.0)
+            ]
+        );
+        assert_eq!(
+            mesh.faces[1].corners(&mesh.vertices).unwrap(),
+            vec![
+                point!(0.0, 0.0, 0.0),
+                point!(1.0, 0.0, 0.0),
+                point!(0.0, 0.0, 1.0)
+            ]
+        );
+
+        let empty = mesh.extract_faces(&[]);
+        assert!(empty.faces.is_empty());
+        assert!(empty.vertices.is_empty());
+        assert_eq!(mesh.faces.len(), 3);
+    }
+
+    #[test]
+    fn components_flatten() {
+        let mut moved = Mesh::plane();
+        moved.position = point!(2.0, 0.0, 0.0);
+        moved.faces[0].color = Color::Red;
+        moved.faces[0].double_sided = true;
+
+        let mut broken = Mesh::plane();
+        broken.faces[0].uv_maps[0].vertex_index = 9;
+
+        let mut model = Model {
+            meshes: vec![Mesh::plane(), moved, broken],
+            ..Model::default()
+        };
+
+        // the moved plane shares an edge with the first one, the broken plane lies on it.
+        assert_eq!(model.flatten_to_single_mesh("flat".to_string()), 6);
+        assert_eq!(model.meshes.len(), 1);
+
+        let flat = &model.meshes[0];
+        assert_eq!(flat.name, "flat");
+        assert_eq!(flat.position, point!(0.0, 0.0, 0.0));
+        assert_eq!(flat.vertices.len(), 6);
+        assert_eq!(flat.faces.len(), 2);
+        assert_eq!(flat.faces[1].color, Color::Red);
+        assert!(flat.faces[1].double_sided);
+        assert_eq!(flat.vertices[4], point!(3.0, 0.0, -1.0));
+        assert_eq!(
+            flat.faces[1].uv_maps,
+            Mesh::plane().faces[0]
+                .uv_maps
+                .iter()
+                .map(|uv_map| UVMap::new([1, 4, 5, 2][uv_map.vertex_index], uv_map.coords))
+                .collect::<Vec<UVMap>>()
+        );
+
+        let mut empty = Model::default();
+        assert_eq!(empty.flatten_to_single_mesh("flat".to_string()), 0);
+        assert_eq!(empty.meshes, vec![Mesh::new("flat".to_string())]);
+    }
+}
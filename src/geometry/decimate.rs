@@ -0,0 +1,335 @@
+//! For reducing the amount of faces of a mesh.
+//!
+//! Faces are removed by repeatedly collapsing an edge, moving one of its vertices onto the other
+//! one.
+//! Triangles using that edge disappear, quads turn into triangles.
+//! The edge picked next is always the one that changes the shape of the mesh the least, measured
+//! by the squared distance of the kept vertex to the faces around both vertices (the quadric error
+//! metric).
+//!
+//! Vertices on uv seams are never moved, so the texture stays in place.
+//! A vertex is on a seam if one of its edges is used by only one face, by more than 2 faces or by
+//! 2 faces that map the edge onto different parts of the texture.
+//! Edges between a textured and an untextured face are seams too, edges between 2 untextured faces
+//! never are.
+//! Collapses that would flip a face around are skipped as well.
+
+use crate::{
+    analysis::topology::Edge,
+    assets::{Face, Mesh, Point2D, Point3D},
+    point,
+};
+use std::collections::HashSet;
+
+/// Error quadric of a vertex, the sum of the squared plane equations of its faces.
+type Quadric = [[f64; 4]; 4];
+
+/// Removes faces from the mesh until it has at most `target_faces` faces.
+///
+/// It's recommended to use [`Mesh::decimate`].
+pub fn decimate(mesh: &mut Mesh, target_faces: usize) {
+    if mesh.faces.len() <= target_faces
+        || mesh.faces.iter().any(|face| {
+            face.uv_maps
+                .iter()
+                .any(|uv_map| uv_map.vertex_index >= mesh.vertices.len())
+        })
+    {
+        return;
+    }
+
+    let mut quadrics: Vec<Quadric> = vec![[[0.0; 4]; 4]; mesh.vertices.len()];
+    for face in mesh.faces.iter() {
+        let normal = face.normal(&mesh.vertices);
+        let Some(first) = face.uv_maps.first() else {
+            continue;
+        };
+
+        let plane = [
+            normal.x,
+            normal.y,
+            normal.z,
+            -normal.dot(mesh.vertices[first.vertex_index]),
+        ];
+
+        for uv_map in face.uv_maps.iter() {
+            let quadric = &mut quadrics[uv_map.vertex_index];
+            for (row, p_row) in quadric.iter_mut().zip(plane) {
+                for (value, p_column) in row.iter_mut().zip(plane) {
+                    *value += p_row * p_column;
+                }
+            }
+        }
+    }
+
+    while mesh.faces.len() > target_faces {
+        let Some((keep, remove)) = best_collapse(mesh, &quadrics) else {
+            break;
+        };
+
+        collapse(mesh, keep, remove);
+
+        let removed = quadrics[remove];
+        for (row, removed_row) in quadrics[keep].iter_mut().zip(removed) {
+            for (value, removed_value) in row.iter_mut().zip(removed_row) {
+                *value += removed_value;
+            }
+        }
+    }
+
+    mesh.remove_unused_vertices();
+}
+
+/// Returns the vertex to keep and the vertex to remove of the cheapest edge that can be
+/// collapsed.
+fn best_collapse(mesh: &Mesh, quadrics: &[Quadric]) -> Option<(usize, usize)> {
+    let adjacency = mesh.edge_adjacency();
+    let mut seam_vertices: HashSet<usize> = HashSet::new();
+
+    for (edge, faces) in adjacency.iter() {
+        if is_seam(mesh, *edge, faces) {
+            let (a, b) = edge.vertices();
+            seam_vertices.insert(a);
+            seam_vertices.insert(b);
+        }
+    }
+
+    let mut best: Option<(f64, usize, usize)> = None;
+
+    for edge in adjacency.keys() {
+        let (a, b) = edge.vertices();
+
+        for (keep, remove) in [(a, b), (b, a)] {
+            if seam_vertices.contains(&remove) {
+                continue;
+            }
+
+            let cost = error(&quadrics[keep], &quadrics[remove], mesh.vertices[keep]);
+
+            if best.is_some_and(|(best_cost, _, _)| best_cost <= cost) {
+                continue;
+            }
+            if flips_faces(mesh, keep, remove) {
+                continue;
+            }
+
+            best = Some((cost, keep, remove));
+        }
+    }
+
+    best.map(|(_, keep, remove)| (keep, remove))
+}
+
+/// Checks if the edge is on a uv seam or on the border of the mesh.
+fn is_seam(mesh: &Mesh, edge: Edge, faces: &[usize]) -> bool {
+    if faces.len() != 2 {
+        return true;
+    }
+
+    let (a, b) = edge.vertices();
+    let first = &mesh.faces[faces[0]];
+    let second = &mesh.faces[faces[1]];
+
+    if first.no_texture && second.no_texture {
+        return false;
+    }
+    if first.no_texture != second.no_texture {
+        return true;
+    }
+
+    uv_of(first, a) != uv_of(second, a) || uv_of(first, b) != uv_of(second, b)
+}
+
+/// Returns the uv-coordinates of the vertex with the given index in the face.
+fn uv_of(face: &Face, vertex_index: usize) -> Option<Point2D<f64>> {
+    face.uv_maps
+        .iter()
+        .find(|uv_map| uv_map.vertex_index == vertex_index)
+        .map(|uv_map| uv_map.coords)
+}
+
+/// Returns the error of moving both vertices with the given quadrics onto `position`.
+fn error(a: &Quadric, b: &Quadric, position: Point3D<f64>) -> f64 {
+    let v = [position.x, position.y, position.z, 1.0];
+    let mut error = 0.0;
+
+    for row in 0..4 {
+        for column in 0..4 {
+            error += v[row] * (a[row][column] + b[row][column]) * v[column];
+        }
+    }
+
+    error
+}
+
+/// Checks if moving `remove` onto `keep` turns any face around.
+fn flips_faces(mesh: &Mesh, keep: usize, remove: usize) -> bool {
+    let mut moved = mesh.vertices.clone();
+    moved[remove] = mesh.vertices[keep];
+
+    mesh.faces
+        .iter()
+        .filter(|face| {
+            face.uv_maps
+                .iter()
+                .any(|uv_map| uv_map.vertex_index == remove)
+        })
+        .any(|face| {
+            let before = face.normal(&mesh.vertices);
+            let mut after_face = face.clone();
+            if uv_of(face, keep).is_some() {
+                after_face
+                    .uv_maps
+                    .retain(|uv_map| uv_map.vertex_index != remove);
+            }
+            let after = after_face.normal(&moved);
+            let none = point!(0.0, 0.0, 0.0);
+
+            // faces that lose their area are removed anyway.
+            before != none && after != none && before.dot(after) <= 0.0
+        })
+}
+
+/// Moves the vertex `remove` onto `keep`, removing faces that end up with less than 3 corners or
+/// without any area.
+fn collapse(mesh: &mut Mesh, keep: usize, remove: usize) {
+    // uv-coordinates of `keep` around `remove`, taken from a textured face using the edge.
+    let keep_uv: Option<Point2D<f64>> = mesh
+        .faces
+        .iter()
+        .filter(|face| !face.no_texture && uv_of(face, remove).is_some())
+        .find_map(|face| uv_of(face, keep));
+
+    let mut faces: Vec<Face> = Vec::with_capacity(mesh.faces.len());
+
+    for mut face in std::mem::take(&mut mesh.faces) {
+        if uv_of(&face, remove).is_none() {
+            faces.push(face);
+            continue;
+        }
+
+        if uv_of(&face, keep).is_some() {
+            face.uv_maps.retain(|uv_map| uv_map.vertex_index != remove);
+        } else {
+            for uv_map in face.uv_maps.iter_mut() {
+                if uv_map.vertex_index == remove {
+                    uv_map.vertex_index = keep;
+
+                    if !face.no_texture {
+                        if let Some(coords) = keep_uv {
+                            uv_map.coords = coords;
+                        }
+                    }
+                }
+            }
+        }
+
+        if face.uv_maps.len() >= 3 && face.normal(&mesh.vertices) != point!(0.0, 0.0, 0.0) {
+            faces.push(face);
+        }
+    }
+
+    mesh.faces = faces;
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::UVMap;
+
+    /// A flat grid of `size` x `size` quads with one continuous uv map.
+    fn grid(size: usize) -> Mesh {
+        let mut mesh = Mesh::new("grid".to_string());
+
+        for z in 0..=size {
+            for x in 0..=size {
+                mesh.vertices.push(point!(x as f64, 0.0, z as f64));
+            }
+        }
+
+        for z in 0..size {
+            for x in 0..size {
+                let corner = z * (size + 1) + x;
+                let indices = [corner, corner + 1, corner + size + 2, corner + size + 1];
+
+                mesh.faces.push(Face {
+                    uv_maps: indices
+                        .iter()
+                        .map(|i| {
+                            let vertex = mesh.vertices[*i];
+                            UVMap::new(*i, point!(vertex.x, vertex.z))
+                        })
+                        .collect(),
+                    ..Face::default()
+                });
+            }
+        }
+
+        mesh
+    }
+
+    #[test]
+    fn decimate_grid() {
+        let mut mesh = grid(4);
+        let normal = mesh.faces[0].normal(&mesh.vertices);
+
+        mesh.decimate(12);
+
+        assert!(mesh.faces.len() <= 12);
+        assert!(mesh.vertices.len() < 25);
+
+        for face in mesh.faces.iter() {
+            assert!(face.uv_maps.len() >= 3);
+            assert_eq!(face.normal(&mesh.vertices), normal);
+
+            // the texture stays in place.
+            for uv_map in face.uv_maps.iter() {
+                let vertex = mesh.vertices[uv_map.vertex_index];
+                assert_eq!(uv_map.coords, point!(vertex.x, vertex.z));
+            }
+        }
+
+        // only the border of the grid is left once nothing can be collapsed anymore.
+        mesh.decimate(0);
+
+        assert!(!mesh.faces.is_empty());
+        assert!(mesh.vertices.len() <= 16);
+        assert!(mesh
+            .vertices
+            .iter()
+            .all(|vertex| [0.0, 4.0].contains(&vertex.x) || [0.0, 4.0].contains(&vertex.z)));
+    }
+
+    #[test]
+    fn decimate_respects_seams() {
+        let mut mesh = grid(4);
+        // gives every face its own part of the texture.
+        for (i, face) in mesh.faces.iter_mut().enumerate() {
+            for uv_map in face.uv_maps.iter_mut() {
+                uv_map.coords.u += i as f64 * 4.0;
+            }
+        }
+
+        let before = mesh.clone();
+        mesh.decimate(1);
+
+        assert_eq!(mesh, before);
+
+        // untextured faces don't have seams.
+        for face in mesh.faces.iter_mut() {
+            face.no_texture = true;
+        }
+        mesh.decimate(12);
+
+        assert!(mesh.faces.len() <= 12);
+    }
+
+    #[test]
+    fn decimate_target_reached() {
+        let mut mesh = grid(2);
+        mesh.decimate(4);
+
+        assert_eq!(mesh.faces.len(), 4);
+        assert_eq!(mesh.vertices.len(), 9);
+    }
+}
@@ -0,0 +1,140 @@
+//! For pulling faces out of a mesh.
+//!
+//! Extruding a face moves a copy of it along its [`normal`](crate::assets::Face::normal) and
+//! connects the copy to the edges of the original face with a wall of quads.
+//! The original face is replaced by the moved copy, so the mesh stays closed if it was closed
+//! before.
+//!
+//! Walls always point away from the extruded face, even when extruding inwards with a negative
+//! distance.
+//! They take over the look of the extruded face (color, shading and texture settings) and map
+//! the part of the texture along their edge, stretched by the distance extruded.
+
+use crate::{
+    assets::{Face, Mesh, Point2D, Point3D, UVMap},
+    point,
+};
+
+/// Extrudes the face with index `face_index` by `distance` along its normal.
+///
+/// Returns `false` and leaves the mesh untouched if there is no such face, it refers to vertices
+/// that don't exist or has no area.
+///
+/// It's recommended to use [`Mesh::extrude_face`].
+pub fn extrude_face(mesh: &mut Mesh, face_index: usize, distance: f64) -> bool {
+    let Some(face) = mesh.faces.get(face_index) else {
+        return false;
+    };
+    let Some(corners) = face.corners(&mesh.vertices) else {
+        return false;
+    };
+
+    let normal = face.normal(&mesh.vertices);
+    if normal == point!(0.0, 0.0, 0.0) {
+        return false;
+    }
+
+    let face = face.clone();
+    let offset = normal * distance;
+    let center = corners
+        .iter()
+        .fold(point!(0.0, 0.0, 0.0), |sum, corner| sum + *corner)
+        / corners.len() as f64;
+
+    // index of the moved copy of each corner.
+    let first_moved = mesh.vertices.len();
+    mesh.vertices
+        .extend(corners.iter().map(|corner| *corner + offset));
+
+    let count = face.uv_maps.len();
+    let mut walls: Vec<Face> = vec![];
+
+    for (i, current) in face.uv_maps.iter().enumerate() {
+        let next = &face.uv_maps[(i + 1) % count];
+        let stretch = point!(0.0, distance.abs());
+
+        let mut wall = Face {
+            uv_maps: vec![
+                UVMap::new(current.vertex_index, current.coords),
+                UVMap::new(next.vertex_index, next.coords),
+                UVMap::new(first_moved + (i + 1) % count, next.coords + stretch),
+                UVMap::new(first_moved + i, current.coords + stretch),
+            ],
+            ..face.clone()
+        };
+
+        let edge_center = (corners[i] + corners[(i + 1) % count]) / 2.0;
+        let outwards: Point3D<f64> = edge_center - center;
+        if wall.normal(&mesh.vertices).dot(outwards) < 0.0 {
+            wall.uv_maps.reverse();
+        }
+
+        walls.push(wall);
+    }
+
+    for (i, uv_map) in mesh.faces[face_index].uv_maps.iter_mut().enumerate() {
+        uv_map.vertex_index = first_moved + i;
+    }
+    mesh.faces.extend(walls);
+
+    true
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    fn square() -> Mesh {
+        let mut mesh = Mesh::new("square".to_string());
+        mesh.vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(0.0, 0.0, 1.0),
+            point!(1.0, 0.0, 1.0),
+            point!(1.0, 0.0, 0.0),
+        ];
+        mesh.faces.push(Face {
+            uv_maps: (0..4).map(|i| UVMap::new(i, point!(0.0, 0.0))).collect(),
+            ..Face::default()
+        });
+
+        mesh
+    }
+
+    #[test]
+    fn extrude_walls_point_outwards() {
+        for distance in [2.0, -2.0] {
+            let mut mesh = square();
+            let normal = mesh.faces[0].normal(&mesh.vertices);
+
+            assert!(mesh.extrude_face(0, distance));
+
+            assert_eq!(mesh.vertices.len(), 8);
+            assert_eq!(mesh.faces.len(), 5);
+            assert_eq!(mesh.faces[0].normal(&mesh.vertices), normal);
+            assert_eq!(mesh.vertices[4], mesh.vertices[0] + normal * distance);
+
+            for wall in mesh.faces.iter().skip(1) {
+                let wall_normal = wall.normal(&mesh.vertices);
+                let corners = wall.corners(&mesh.vertices).unwrap();
+                let wall_center = corners
+                    .iter()
+                    .fold(point!(0.0, 0.0, 0.0), |sum, corner| sum + *corner)
+                    / 4.0;
+
+                assert!(wall_normal.dot(wall_center - point!(0.5, 0.0, 0.5)) > 0.0);
+                assert!(wall_normal.y.abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn extrude_invalid_face() {
+        let mut mesh = square();
+
+        assert!(!mesh.extrude_face(1, 1.0));
+
+        mesh.faces[0].uv_maps.truncate(2);
+        assert!(!mesh.extrude_face(0, 1.0));
+        assert_eq!(mesh.vertices.len(), 4);
+    }
+}
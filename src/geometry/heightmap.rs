@@ -0,0 +1,151 @@
+//! For building terrain and reliefs out of the texture.
+//!
+//! Every pixel of a region of the texture becomes a vertex of a flat grid, which is then pushed up
+//! depending on the pixel's color.
+//! Neighbouring vertices are connected by quads whose uv-coordinates point back at the pixels they
+//! were built from, so the texture shows up on top of the mesh.
+//!
+//! How high a pixel ends up is decided by [`HeightSource`].
+
+use crate::{
+    assets::{Color, Face, Footer, Mesh, Point2D, Point3D, UVMap},
+    point,
+};
+
+/// The ways the height of a pixel can be read from its color.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum HeightSource {
+    /// The index of the color in the pico-8 palette, from `0` (black) to `15` (peach).
+    ColorIndex,
+    /// The perceived brightness of the color, from `0.0` (black) to a bit below `1.0` (white).
+    #[default]
+    Luminance,
+}
+
+impl HeightSource {
+    /// Returns the height of the given color.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Color;
+    /// use picocadrs::geometry::heightmap::HeightSource;
+    ///
+    /// assert_eq!(HeightSource::ColorIndex.height(Color::Red), 8.0);
+    /// assert_eq!(HeightSource::Luminance.height(Color::Black), 0.0);
+    /// assert!(HeightSource::Luminance.height(Color::White) > 0.95);
+    /// ```
+    pub fn height(&self, color: Color) -> f64 {
+        match self {
+            HeightSource::ColorIndex => color.as_i32().max(0) as f64,
+            HeightSource::Luminance => {
+                let (r, g, b) = color.as_rgb();
+                (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64) / 255.0
+            }
+        }
+    }
+}
+
+/// Builds a grid mesh out of the pixels of the texture inside `region`.
+///
+/// It's recommended to use [`Mesh::from_heightmap`].
+pub fn from_heightmap(
+    footer: &Footer,
+    region: (Point2D<usize>, Point2D<usize>),
+    scale: Point3D<f64>,
+    source: HeightSource,
+) -> Mesh {
+    let (start, size) = region;
+    let width = size.u.min(128usize.saturating_sub(start.u));
+    let depth = size.v.min(120usize.saturating_sub(start.v));
+
+    let mut mesh = Mesh::new("heightmap".to_string());
+
+    // centers the grid around the position of the mesh.
+    let offset_x = (width.max(1) - 1) as f64 / 2.0;
+    let offset_z = (depth.max(1) - 1) as f64 / 2.0;
+
+    for v in 0..depth {
+        for u in 0..width {
+            let color = footer.pixel(point!(start.u + u, start.v + v));
+
+            // the y-axis points down, so higher pixels need smaller values.
+            mesh.vertices.push(point!(
+                (u as f64 - offset_x) * scale.x,
+                -source.height(color) * scale.y,
+                (v as f64 - offset_z) * scale.z
+            ));
+        }
+    }
+
+    for v in 0..depth.saturating_sub(1) {
+        for u in 0..width.saturating_sub(1) {
+            let corners = [(u, v), (u, v + 1), (u + 1, v + 1), (u + 1, v)];
+
+            mesh.faces.push(Face {
+                uv_maps: corners
+                    .iter()
+                    .map(|(u, v)| {
+                        UVMap::new(
+                            v * width + u,
+                            point!((start.u + u) as f64 / 8.0, (start.v + v) as f64 / 8.0),
+                        )
+                    })
+                    .collect(),
+                ..Face::default()
+            });
+        }
+    }
+
+    mesh
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn heightmap_grid() {
+        let mut footer = Footer::default();
+        footer.set(point!(11, 21), Color::Red).unwrap();
+
+        let mesh = Mesh::from_heightmap(
+            &footer,
+            (point!(10, 20), point!(3, 4)),
+            point!(0.25, 0.1, 0.5),
+            HeightSource::ColorIndex,
+        );
+
+        assert_eq!(mesh.vertices.len(), 3 * 4);
+        assert_eq!(mesh.faces.len(), 2 * 3);
+
+        assert_eq!(mesh.vertices[0], point!(-0.25, 0.0, -0.75));
+        assert_eq!(mesh.vertices[11], point!(0.25, 0.0, 0.75));
+        assert!((mesh.vertices[4].y + 0.8).abs() < 1e-9);
+
+        for face in mesh.faces.iter() {
+            // faces point up.
+            assert!(face.normal(&mesh.vertices).y < 0.0);
+
+            for uv_map in face.uv_maps.iter() {
+                let u = uv_map.vertex_index % 3 + 10;
+                let v = uv_map.vertex_index / 3 + 20;
+
+                assert_eq!(footer.read(uv_map.coords), footer.pixel(point!(u, v)));
+            }
+        }
+    }
+
+    #[test]
+    fn heightmap_clamped_region() {
+        let mesh = Mesh::from_heightmap(
+            &Footer::default(),
+            (point!(126, 119), point!(8, 8)),
+            point!(1.0, 1.0, 1.0),
+            HeightSource::Luminance,
+        );
+
+        assert_eq!(mesh.vertices.len(), 2);
+        assert!(mesh.faces.is_empty());
+    }
+}
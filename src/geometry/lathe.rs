@@ -0,0 +1,152 @@
+//! For building round meshes by spinning a profile around the `y`-axis.
+//!
+//! The profile is a line of points, where `u` is the distance from the axis and `v` the height
+//! along it.
+//! It is copied `segments` times around the axis and neighbouring copies are connected with quads.
+//! Points on the axis (`u = 0`) are only added once, turning the quads touching them into
+//! triangles, so closed shapes like spheres or cones don't end up with faces without area.
+//!
+//! Since the `y`-axis points down in picoCAD, faces point outwards if the profile runs from the
+//! bottom to the top, i.e. from bigger to smaller `v`.
+//! Running it the other way around creates a mesh that is only visible from the inside.
+//!
+//! The texture is wrapped around the mesh once, going from the left to the right edge of the
+//! texture around the axis and from the top to the bottom along the profile.
+
+use crate::{
+    assets::{Face, Mesh, Point2D, Point3D, UVMap},
+    point,
+};
+use std::f64::consts::TAU;
+
+/// Spins `profile` around the `y`-axis, creating a mesh with `segments` faces around the axis.
+///
+/// Returns an empty mesh if the profile has less than 2 points or `segments` is smaller than 3.
+///
+/// It's recommended to use [`Mesh::lathe`].
+pub fn lathe(profile: &[Point2D<f64>], segments: usize) -> Mesh {
+    let mut mesh = Mesh::new("lathe".to_string());
+
+    if profile.len() < 2 || segments < 3 {
+        return mesh;
+    }
+
+    // index of the vertex of each profile point in each segment.
+    let mut indices: Vec<Vec<usize>> = vec![];
+
+    for point in profile.iter() {
+        if point.u == 0.0 {
+            mesh.vertices.push(point!(0.0, point.v, 0.0));
+            indices.push(vec![mesh.vertices.len() - 1; segments]);
+            continue;
+        }
+
+        let mut ring: Vec<usize> = vec![];
+        for segment in 0..segments {
+            let angle = TAU * segment as f64 / segments as f64;
+
+            mesh.vertices.push(point!(
+                point.u * angle.cos(),
+                point.v,
+                point.u * angle.sin()
+            ));
+            ring.push(mesh.vertices.len() - 1);
+        }
+        indices.push(ring);
+    }
+
+    let rows = profile.len() - 1;
+
+    for row in 0..rows {
+        for segment in 0..segments {
+            let next = (segment + 1) % segments;
+            let corners = [
+                (indices[row][segment], segment, row),
+                (indices[row + 1][segment], segment, row + 1),
+                (indices[row + 1][next], segment + 1, row + 1),
+                (indices[row][next], segment + 1, row),
+            ];
+
+            let mut uv_maps: Vec<UVMap> = vec![];
+            for (vertex_index, u, v) in corners {
+                if uv_maps
+                    .iter()
+                    .any(|uv_map| uv_map.vertex_index == vertex_index)
+                {
+                    continue;
+                }
+
+                uv_maps.push(UVMap::new(
+                    vertex_index,
+                    point!(
+                        16.0 * u as f64 / segments as f64,
+                        15.0 * v as f64 / rows as f64
+                    ),
+                ));
+            }
+
+            if uv_maps.len() >= 3 {
+                mesh.faces.push(Face {
+                    uv_maps,
+                    ..Face::default()
+                });
+            }
+        }
+    }
+
+    mesh
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn lathe_cylinder() {
+        let mesh = Mesh::lathe(&[point!(1.0, 1.0), point!(1.0, -1.0)], 8);
+
+        assert_eq!(mesh.vertices.len(), 16);
+        assert_eq!(mesh.faces.len(), 8);
+
+        for face in mesh.faces.iter() {
+            let corners = face.corners(&mesh.vertices).unwrap();
+            let center = corners
+                .iter()
+                .fold(point!(0.0, 0.0, 0.0), |sum, corner| sum + *corner)
+                / corners.len() as f64;
+
+            // faces point away from the axis.
+            assert!(
+                face.normal(&mesh.vertices)
+                    .dot(point!(center.x, 0.0, center.z))
+                    > 0.0
+            );
+        }
+    }
+
+    #[test]
+    fn lathe_cone() {
+        let mesh = Mesh::lathe(&[point!(0.0, 1.0), point!(1.0, 1.0), point!(0.0, -1.0)], 6);
+
+        assert_eq!(mesh.vertices.len(), 2 + 6);
+        assert_eq!(mesh.faces.len(), 12);
+        assert!(mesh.faces.iter().all(|face| face.uv_maps.len() == 3));
+        assert!(mesh
+            .faces
+            .iter()
+            .all(|face| face.normal(&mesh.vertices) != point!(0.0, 0.0, 0.0)));
+
+        // the bottom points down, the sides up and outwards.
+        assert!(mesh.faces[0].normal(&mesh.vertices).y > 0.0);
+        assert!(mesh.faces[6].normal(&mesh.vertices).y < 0.0);
+        assert_eq!(mesh.vertices[0], Point3D::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn lathe_invalid() {
+        assert!(Mesh::lathe(&[point!(1.0, 0.0)], 8).faces.is_empty());
+        assert!(Mesh::lathe(&[point!(1.0, 0.0), point!(1.0, 1.0)], 2)
+            .vertices
+            .is_empty());
+    }
+}
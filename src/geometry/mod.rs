@@ -0,0 +1,27 @@
+//! For changing the shape of meshes.
+//!
+//! - _[`components`]:_ Splitting meshes into their unconnected parts and joining meshes.
+//! - _[`decimate`]:_ Reducing the amount of faces by collapsing edges.
+//! - _[`extrude`]:_ Pulling faces out of a mesh.
+//! - _[`heightmap`]:_ Building grid meshes whose height comes from the texture.
+//! - _[`lathe`]:_ Building round meshes by spinning a profile around an axis.
+//! - _[`proportional`]:_ Moving vertices around a point with a smooth falloff.
+//! - _[`split`]:_ Cutting meshes in two along a [`Plane`](split::Plane).
+//! - _[`subdivide`]:_ Splitting faces into smaller ones to add resolution.
+//! - _[`sweep`]:_ Building meshes by moving a profile along a path.
+//! - _[`symmetry`]:_ Mirroring one half of a mesh onto the other.
+//! - _[`voxel`]:_ Combining meshes by turning them into voxels.
+//! - _[`winding`]:_ Orienting all faces of a mesh the same way.
+
+pub mod components;
+pub mod decimate;
+pub mod extrude;
+pub mod heightmap;
+pub mod lathe;
+pub mod proportional;
+pub mod split;
+pub mod subdivide;
+pub mod sweep;
+pub mod symmetry;
+pub mod voxel;
+pub mod winding;
@@ -0,0 +1,154 @@
+//! For moving groups of vertices smoothly, also known as proportional editing or soft selection.
+//!
+//! Every vertex closer to a center than a radius is transformed, but only partly: the vertex at
+//! the center gets the full transformation, vertices further away get less of it and vertices at
+//! the radius aren't moved at all.
+//! How fast the effect fades is decided by the [`Falloff`].
+//!
+//! This makes organic changes like bulges or bends possible without moving every vertex by hand.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Mesh, Point3D};
+//! use picocadrs::geometry::proportional::Falloff;
+//! use picocadrs::point;
+//!
+//! let mut mesh = Mesh::new("hill".to_string());
+//! mesh.vertices = vec![
+//!     point!(0.0, 0.0, 0.0),
+//!     point!(1.0, 0.0, 0.0),
+//!     point!(2.0, 0.0, 0.0),
+//! ];
+//!
+//! // pull the vertices up, the y-axis points down in picoCAD.
+//! let moved = mesh.transform_vertices_in_radius(point!(0.0, 0.0, 0.0), 2.0, Falloff::Linear, |v| {
+//!     v + point!(0.0, -1.0, 0.0)
+//! });
+//!
+//! assert_eq!(moved, 2);
+//! assert_eq!(mesh.vertices[0], point!(0.0, -1.0, 0.0));
+//! assert_eq!(mesh.vertices[1], point!(1.0, -0.5, 0.0));
+//! assert_eq!(mesh.vertices[2], point!(2.0, 0.0, 0.0));
+//! ```
+
+use crate::assets::{Mesh, Point3D};
+
+/// How the strength of a transformation fades between the center and the radius.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum Falloff {
+    /// Strength fades evenly with the distance.
+    Linear,
+    /// Strength fades slowly near the center and the radius and fast in between, which avoids
+    /// visible creases where the effect starts.
+    #[default]
+    Smooth,
+    /// Every vertex in the radius is fully transformed.
+    Constant,
+}
+
+impl Falloff {
+    /// Returns how strongly a vertex at `distance` from the center is transformed, from `0.0` to
+    /// `1.0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::geometry::proportional::Falloff;
+    ///
+    /// assert_eq!(Falloff::Linear.weight(0.5, 2.0), 0.75);
+    /// assert_eq!(Falloff::Smooth.weight(1.0, 2.0), 0.5);
+    /// assert_eq!(Falloff::Constant.weight(1.9, 2.0), 1.0);
+    /// assert_eq!(Falloff::Linear.weight(3.0, 2.0), 0.0);
+    /// ```
+    pub fn weight(&self, distance: f64, radius: f64) -> f64 {
+        if radius <= 0.0 || distance >= radius {
+            return 0.0;
+        }
+
+        let t = 1.0 - distance / radius;
+
+        match self {
+            Falloff::Linear => t,
+            Falloff::Smooth => t * t * (3.0 - 2.0 * t),
+            Falloff::Constant => 1.0,
+        }
+    }
+}
+
+/// Moves every vertex closer to `center` than `radius` towards where `transform` puts it,
+/// weighted by `falloff`, and returns the amount of vertices moved.
+///
+/// `center` is relative to the [`position`](Mesh::position) of the mesh, like the vertices.
+///
+/// It's recommended to use [`Mesh::transform_vertices_in_radius`].
+pub fn transform_vertices_in_radius<F>(
+    mesh: &mut Mesh,
+    center: Point3D<f64>,
+    radius: f64,
+    falloff: Falloff,
+    transform: F,
+) -> usize
+where
+    F: Fn(Point3D<f64>) -> Point3D<f64>,
+{
+    let mut moved = 0;
+
+    for vertex in mesh.vertices.iter_mut() {
+        let weight = falloff.weight((*vertex - center).length(), radius);
+        if weight <= 0.0 {
+            continue;
+        }
+
+        let target = transform(*vertex);
+        *vertex = *vertex + (target - *vertex) * weight;
+        moved += 1;
+    }
+
+    moved
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::point;
+
+    #[test]
+    fn proportional_falloff() {
+        for falloff in [Falloff::Linear, Falloff::Smooth, Falloff::Constant] {
+            assert_eq!(falloff.weight(0.0, 1.0), 1.0);
+            assert_eq!(falloff.weight(1.0, 1.0), 0.0);
+            assert_eq!(falloff.weight(0.0, 0.0), 0.0);
+        }
+
+        assert_eq!(Falloff::Smooth.weight(0.25, 1.0), 0.84375);
+    }
+
+    #[test]
+    fn proportional_scale() {
+        let mut mesh = Mesh::new("ball".to_string());
+        mesh.vertices = vec![
+            point!(1.0, 0.0, 0.0),
+            point!(0.0, 0.0, -1.0),
+            point!(4.0, 0.0, 0.0),
+        ];
+
+        // bulge out around the origin.
+        let moved = mesh.transform_vertices_in_radius(
+            point!(0.0, 0.0, 0.0),
+            2.0,
+            Falloff::Constant,
+            |vertex| vertex * 2.0,
+        );
+
+        assert_eq!(moved, 2);
+        assert_eq!(
+            mesh.vertices,
+            vec![
+                point!(2.0, 0.0, 0.0),
+                point!(0.0, 0.0, -2.0),
+                point!(4.0, 0.0, 0.0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! For cutting meshes in two along a plane.
+//!
+//! Every face is clipped against the [`Plane`]: parts in front of the plane end up in one mesh,
+//! parts behind it in the other.
+//! Edges crossing the plane get a new vertex where they intersect it, the uv-coordinates of that
+//! vertex are interpolated between both ends of the edge, so the texture stays in place.
+//! Faces sharing an edge also share the new vertex, so the halves don't get any cracks.
+//!
+//! This is mostly useful to cut large models in half to fit them into the size limits of a cart.
+
+use crate::{
+    analysis::topology::Edge,
+    assets::{Axis, Face, Mesh, Point2D, Point3D, UVMap},
+    point,
+};
+use std::collections::HashMap;
+
+/// Distance to the plane below which a vertex counts as lying on it.
+const PLANE_EPSILON: f64 = 1e-9;
+
+/// An infinite plane in 3-dimensional space.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Axis, Point3D};
+/// use picocadrs::geometry::split::Plane;
+/// use picocadrs::point;
+///
+/// let plane = Plane::axis(Axis::Y, 1.0);
+///
+/// assert_eq!(plane.distance(point!(5.0, 3.0, -2.0)), 2.0);
+/// assert_eq!(plane.distance(point!(0.0, 0.0, 0.0)), -1.0);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Plane {
+    /// Any point on the plane.
+    pub point: Point3D<f64>,
+    /// Direction the front of the plane faces.
+    /// Doesn't need to be normalized.
+    pub normal: Point3D<f64>,
+}
+
+impl Plane {
+    /// Creates the plane going through `point` whose front faces `normal`.
+    pub fn new(point: Point3D<f64>, normal: Point3D<f64>) -> Plane {
+        Plane { point, normal }
+    }
+
+    /// Creates the plane where the coordinate on `axis` is `offset`.
+    /// Its front faces the positive direction of that axis.
+    pub fn axis(axis: Axis, offset: f64) -> Plane {
+        let mut point = point!(0.0, 0.0, 0.0);
+        let mut normal = point!(0.0, 0.0, 0.0);
+        *point.axis_mut(axis) = offset;
+        *normal.axis_mut(axis) = 1.0;
+
+        Plane { point, normal }
+    }
+
+    /// Returns the signed distance of `point` to the plane.
+    /// The distance is positive in front of the plane and negative behind it.
+    ///
+    /// Returns `0.0` if the normal of the plane has no length.
+    pub fn distance(&self, point: Point3D<f64>) -> f64 {
+        (point - self.point).dot(self.normal.normalized())
+    }
+}
+
+/// Splits the mesh into the part in front of and the part behind the plane.
+///
+/// It's recommended to use [`Mesh::split_by_plane`].
+pub fn split_by_plane(mesh: &Mesh, plane: Plane) -> (Mesh, Mesh) {
+    let distances: Vec<f64> = mesh
+        .vertices
+        .iter()
+        .map(|vertex| plane.distance(*vertex))
+        .collect();
+
+    let mut front = Half::new(mesh);
+    let mut back = Half::new(mesh);
+
+    for face in mesh.faces.iter() {
+        if face
+            .uv_maps
+            .iter()
+            .any(|uv_map| uv_map.vertex_index >= mesh.vertices.len())
+        {
+            continue;
+        }
+
+        let face_distances: Vec<f64> = face
+            .uv_maps
+            .iter()
+            .map(|uv_map| distances[uv_map.vertex_index])
+            .collect();
+
+        let in_front = face_distances.iter().any(|d| *d > PLANE_EPSILON);
+        let behind = face_distances.iter().any(|d| *d < -PLANE_EPSILON);
+
+        // faces lying on the plane go to the front.
+        if in_front || !behind {
+            front.clip(mesh, face, &distances, 1.0);
+        }
+        if behind {
+            back.clip(mesh, face, &distances, -1.0);
+        }
+    }
+
+    (front.mesh, back.mesh)
+}
+
+/// One of the halves of a mesh while it's being built.
+struct Half {
+    mesh: Mesh,
+    /// Maps indices of vertices in the original mesh onto the ones in this half.
+    vertices: HashMap<usize, usize>,
+    /// Maps edges of the original mesh onto the vertex created where they cross the plane.
+    intersections: HashMap<Edge, usize>,
+}
+
+impl Half {
+    /// Creates an empty half of the mesh that keeps its name, position, rotation and extras.
+    fn new(mesh: &Mesh) -> Half {
+        Half {
+            mesh: Mesh {
+                vertices: vec![],
+                faces: vec![],
+                ..mesh.clone()
+            },
+            vertices: HashMap::new(),
+            intersections: HashMap::new(),
+        }
+    }
+
+    /// Adds the part of `face` on the given side of the plane.
+    /// `side` is `1.0` for the front and `-1.0` for the back.
+    fn clip(&mut self, mesh: &Mesh, face: &Face, distances: &[f64], side: f64) {
+        let count = face.uv_maps.len();
+        let mut uv_maps: Vec<UVMap> = vec![];
+
+        for (i, a) in face.uv_maps.iter().enumerate() {
+            let b = face.uv_maps[(i + 1) % count];
+            let distance_a = distances[a.vertex_index] * side;
+            let distance_b = distances[b.vertex_index] * side;
+
+            if distance_a >= -PLANE_EPSILON {
+                uv_maps.push(UVMap::new(self.vertex(mesh, a.vertex_index), a.coords));
+            }
+
+            let crosses = (distance_a > PLANE_EPSILON && distance_b < -PLANE_EPSILON)
+                || (distance_a < -PLANE_EPSILON && distance_b > PLANE_EPSILON);
+
+            if crosses {
+                // always interpolate from the smaller index, so both faces using this edge get
+                // the exact same vertex.
+                let (from, to) = if a.vertex_index < b.vertex_index {
+                    (*a, b)
+                } else {
+                    (b, *a)
+                };
+                let t = distances[from.vertex_index]
+                    / (distances[from.vertex_index] - distances[to.vertex_index]);
+
+                let vertex = self.intersection(mesh, from.vertex_index, to.vertex_index, t);
+                let coords = point!(
+                    from.coords.u + (to.coords.u - from.coords.u) * t,
+                    from.coords.v + (to.coords.v - from.coords.v) * t
+                );
+
+                uv_maps.push(UVMap::new(vertex, coords));
+            }
+        }
+
+        if uv_maps.len() >= 3 {
+            self.mesh.faces.push(Face {
+                uv_maps,
+                ..face.clone()
+            });
+        }
+    }
+
+    /// Returns the index of a vertex of the original mesh in this half, adding it if needed.
+    fn vertex(&mut self, mesh: &Mesh, index: usize) -> usize {
+        *self.vertices.entry(index).or_insert_with(|| {
+            self.mesh.vertices.push(mesh.vertices[index]);
+            self.mesh.vertices.len() - 1
+        })
+    }
+
+    /// Returns the index of the vertex where the edge from `from` to `to` crosses the plane,
+    /// adding it if needed.
+    fn intersection(&mut self, mesh: &Mesh, from: usize, to: usize, t: f64) -> usize {
+        *self
+            .intersections
+            .entry(Edge::new(from, to))
+            .or_insert_with(|| {
+                let a = mesh.vertices[from];
+                let b = mesh.vertices[to];
+
+                self.mesh.vertices.push(point!(
+                    a.x + (b.x - a.x) * t,
+                    a.y + (b.y - a.y) * t,
+                    a.z + (b.z - a.z) * t
+                ));
+                self.mesh.vertices.len() - 1
+            })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// Two quads next to each other, spanning from `x = 0` to `x = 2`.
+    fn strip() -> Mesh {
+        let mut mesh = Mesh::new("strip".to_string());
+        mesh.position = point!(1.0, 2.0, 3.0);
+        mesh.vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(1.0, 0.0, 0.0),
+            point!(2.0, 0.0, 0.0),
+            point!(0.0, 1.0, 0.0),
+            point!(1.0, 1.0, 0.0),
+            point!(2.0, 1.0, 0.0),
+        ];
+
+        for indices in [[0, 1, 4, 3], [1, 2, 5, 4]] {
+            mesh.faces.push(Face {
+                uv_maps: indices
+                    .iter()
+                    .map(|i| {
+                        let vertex = mesh.vertices[*i];
+                        UVMap::new(*i, point!(vertex.x * 2.0, vertex.y * 2.0))
+                    })
+                    .collect(),
+                ..Face::default()
+            });
+        }
+
+        mesh
+    }
+
+    #[test]
+    fn split_plane_distance() {
+        let plane = Plane::new(point!(0.0, 0.0, 0.0), point!(0.0, 0.0, 2.0));
+
+        assert_eq!(plane.distance(point!(1.0, 1.0, 3.0)), 3.0);
+        assert_eq!(
+            Plane::axis(Axis::X, 0.5).distance(point!(0.0, 7.0, 7.0)),
+            -0.5
+        );
+    }
+
+    #[test]
+    fn split_through_faces() {
+        let (front, back) = strip().split_by_plane(Plane::axis(Axis::X, 0.5));
+
+        assert_eq!(front.name, "strip");
+        assert_eq!(front.position, point!(1.0, 2.0, 3.0));
+        assert_eq!(front.faces.len(), 2);
+        assert_eq!(back.faces.len(), 1);
+
+        // 4 original vertices and 2 on the cut.
+        assert_eq!(front.vertices.len(), 6);
+        assert_eq!(back.vertices.len(), 4);
+        assert!(front.vertices.contains(&point!(0.5, 0.0, 0.0)));
+        assert!(back.vertices.contains(&point!(0.5, 1.0, 0.0)));
+
+        let cut = back.faces[0]
+            .uv_maps
+            .iter()
+            .find(|uv_map| back.vertices[uv_map.vertex_index] == point!(0.5, 1.0, 0.0))
+            .unwrap();
+        assert_eq!(cut.coords, point!(1.0, 2.0));
+    }
+
+    #[test]
+    fn split_along_edges() {
+        let (front, back) = strip().split_by_plane(Plane::axis(Axis::X, 1.0));
+
+        assert_eq!(front.faces.len(), 1);
+        assert_eq!(back.faces.len(), 1);
+        assert_eq!(front.vertices.len(), 4);
+        assert_eq!(back.vertices.len(), 4);
+        assert_eq!(front.faces[0].uv_maps[0].coords, point!(2.0, 0.0));
+    }
+
+    #[test]
+    fn split_shared_intersections() {
+        let mut mesh = strip();
+        // cut diagonally through both faces and the edge they share.
+        let plane = Plane::new(point!(1.0, 0.5, 0.0), point!(0.0, 1.0, 0.0));
+        mesh.faces[1].uv_maps.rotate_left(2);
+
+        let (front, back) = mesh.split_by_plane(plane);
+
+        assert_eq!(front.faces.len(), 2);
+        assert_eq!(back.faces.len(), 2);
+        // 3 vertices in front and 3 on the cut.
+        assert_eq!(front.vertices.len(), 6);
+        assert_eq!(back.vertices.len(), 6);
+    }
+
+    #[test]
+    fn split_plane_faces() {
+        let (front, back) = strip().split_by_plane(Plane::axis(Axis::Z, 0.0));
+
+        assert_eq!(front.faces.len(), 2);
+        assert!(back.faces.is_empty());
+        assert!(back.vertices.is_empty());
+    }
+}
@@ -0,0 +1,212 @@
+//! For adding resolution to meshes by splitting their faces.
+//!
+//! Each level of subdivision splits every face into smaller ones without moving any vertex, so the
+//! shape of the mesh stays exactly the same:
+//! - Triangles are split into 4 triangles by connecting the middles of their edges.
+//! - Every other face gets a new vertex in its center and is split into one quad per corner,
+//!   connecting the center with the middles of the edges next to that corner.
+//!
+//! Middles of edges are shared between the faces using the edge, so subdivided meshes don't get
+//! any cracks.
+//! The uv-coordinates of new corners are interpolated, so the texture stays in place.
+
+use crate::{
+    analysis::topology::Edge,
+    assets::{Face, Mesh, Point2D, Point3D, UVMap},
+    point,
+};
+use std::collections::HashMap;
+
+/// Subdivides every face of the mesh `levels` times.
+///
+/// It's recommended to use [`Mesh::subdivide`].
+pub fn subdivide(mesh: &mut Mesh, levels: u32) {
+    for _ in 0..levels {
+        subdivide_once(mesh);
+    }
+}
+
+/// Splits every face of the mesh once.
+fn subdivide_once(mesh: &mut Mesh) {
+    let mut middles: HashMap<Edge, usize> = HashMap::new();
+    let mut faces: Vec<Face> = Vec::with_capacity(mesh.faces.len() * 4);
+
+    for face in std::mem::take(&mut mesh.faces) {
+        let count = face.uv_maps.len();
+
+        if count < 3
+            || face
+                .uv_maps
+                .iter()
+                .any(|uv_map| uv_map.vertex_index >= mesh.vertices.len())
+        {
+            faces.push(face);
+            continue;
+        }
+
+        // middle of the edge from each corner to the next.
+        let edge_middles: Vec<UVMap> = (0..count)
+            .map(|i| {
+                let a = face.uv_maps[i];
+                let b = face.uv_maps[(i + 1) % count];
+                let vertex = *middles
+                    .entry(Edge::new(a.vertex_index, b.vertex_index))
+                    .or_insert_with(|| {
+                        let from = mesh.vertices[a.vertex_index];
+                        let to = mesh.vertices[b.vertex_index];
+
+                        mesh.vertices.push(point!(
+                            (from.x + to.x) / 2.0,
+                            (from.y + to.y) / 2.0,
+                            (from.z + to.z) / 2.0
+                        ));
+                        mesh.vertices.len() - 1
+                    });
+
+                UVMap::new(
+                    vertex,
+                    point!(
+                        (a.coords.u + b.coords.u) / 2.0,
+                        (a.coords.v + b.coords.v) / 2.0
+                    ),
+                )
+            })
+            .collect();
+
+        let split = |uv_maps: Vec<UVMap>| Face {
+            uv_maps,
+            ..face.clone()
+        };
+
+        if count == 3 {
+            let corners = &face.uv_maps;
+
+            faces.push(split(vec![corners[0], edge_middles[0], edge_middles[2]]));
+            faces.push(split(vec![edge_middles[0], corners[1], edge_middles[1]]));
+            faces.push(split(vec![edge_middles[2], edge_middles[1], corners[2]]));
+            faces.push(split(edge_middles));
+            continue;
+        }
+
+        let mut center_vertex = point!(0.0, 0.0, 0.0);
+        let mut center_coords = point!(0.0, 0.0);
+        for uv_map in face.uv_maps.iter() {
+            center_vertex += mesh.vertices[uv_map.vertex_index];
+            center_coords += uv_map.coords;
+        }
+
+        let n = count as f64;
+        mesh.vertices.push(point!(
+            center_vertex.x / n,
+            center_vertex.y / n,
+            center_vertex.z / n
+        ));
+        let center = UVMap::new(
+            mesh.vertices.len() - 1,
+            point!(center_coords.u / n, center_coords.v / n),
+        );
+
+        for i in 0..count {
+            faces.push(split(vec![
+                face.uv_maps[i],
+                edge_middles[i],
+                center,
+                edge_middles[(i + count - 1) % count],
+            ]));
+        }
+    }
+
+    mesh.faces = faces;
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::Color;
+
+    fn face(indices: &[usize], vertices: &[Point3D<f64>]) -> Face {
+        Face {
+            uv_maps: indices
+                .iter()
+                .map(|i| UVMap::new(*i, point!(vertices[*i].x * 4.0, vertices[*i].y * 4.0)))
+                .collect(),
+            color: Color::Red,
+            ..Face::default()
+        }
+    }
+
+    #[test]
+    fn subdivide_triangle() {
+        let mut mesh = Mesh::new("triangle".to_string());
+        mesh.vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(2.0, 0.0, 0.0),
+            point!(0.0, 2.0, 0.0),
+        ];
+        mesh.faces.push(face(&[0, 1, 2], &mesh.vertices));
+        let normal = mesh.faces[0].normal(&mesh.vertices);
+
+        mesh.subdivide(1);
+
+        assert_eq!(mesh.faces.len(), 4);
+        assert_eq!(mesh.vertices.len(), 6);
+        assert!(mesh.vertices.contains(&point!(1.0, 1.0, 0.0)));
+        for face in mesh.faces.iter() {
+            assert_eq!(face.uv_maps.len(), 3);
+            assert_eq!(face.color, Color::Red);
+            assert_eq!(face.normal(&mesh.vertices), normal);
+
+            for uv_map in face.uv_maps.iter() {
+                let vertex = mesh.vertices[uv_map.vertex_index];
+                assert_eq!(uv_map.coords, point!(vertex.x * 4.0, vertex.y * 4.0));
+            }
+        }
+    }
+
+    #[test]
+    fn subdivide_quads() {
+        let mut mesh = Mesh::new("strip".to_string());
+        mesh.vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(1.0, 0.0, 0.0),
+            point!(2.0, 0.0, 0.0),
+            point!(0.0, 1.0, 0.0),
+            point!(1.0, 1.0, 0.0),
+            point!(2.0, 1.0, 0.0),
+        ];
+        mesh.faces.push(face(&[0, 1, 4, 3], &mesh.vertices));
+        mesh.faces.push(face(&[1, 2, 5, 4], &mesh.vertices));
+        let normal = mesh.faces[0].normal(&mesh.vertices);
+
+        mesh.subdivide(1);
+
+        assert_eq!(mesh.faces.len(), 8);
+        // 6 original, 7 edge middles (one shared) and 2 centers.
+        assert_eq!(mesh.vertices.len(), 15);
+        for face in mesh.faces.iter() {
+            assert_eq!(face.normal(&mesh.vertices), normal);
+
+            for uv_map in face.uv_maps.iter() {
+                let vertex = mesh.vertices[uv_map.vertex_index];
+                assert_eq!(uv_map.coords, point!(vertex.x * 4.0, vertex.y * 4.0));
+            }
+        }
+
+        mesh.subdivide(2);
+        assert_eq!(mesh.faces.len(), 128);
+    }
+
+    #[test]
+    fn subdivide_keeps_broken_faces() {
+        let mut mesh = Mesh::new("broken".to_string());
+        mesh.faces.push(Face {
+            uv_maps: vec![UVMap::new(0, point!(0.0, 0.0)); 3],
+            ..Face::default()
+        });
+
+        mesh.subdivide(1);
+
+        assert_eq!(mesh.faces.len(), 1);
+        assert!(mesh.vertices.is_empty());
+    }
+}
@@ -0,0 +1,338 @@
+//! For building meshes by moving a cross-section along a path, like tubes, rails or roads.
+//!
+//! The profile is a line of points across the path: looking along the path, `u` points to the
+//! right and `v` up.
+//! Since the `y`-axis points down in picoCAD, up is `-y`, unless the path itself runs straight
+//! up or down.
+//! Faces point to the left of the direction the profile runs in, so a flat profile from `-u` to
+//! `u` faces up and a closed profile running clockwise makes a tube facing outwards.
+//!
+//! Where the path bends, the profile is stretched onto the plane halfway between both directions
+//! (a miter), so the mesh keeps its width on both sides of the bend.
+//! The sharper the bend, the more the profile is stretched.
+//! Bends that would stretch it more than [`SweepOptions::miter_limit`] are cut off instead (a
+//! bevel): the profile is placed once facing the incoming and once facing the outgoing direction
+//! and the gap between both is closed with faces.
+//!
+//! The texture is wrapped around the mesh once, going from the left to the right edge of the
+//! texture along the profile and from the top to the bottom along the path.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Mesh, Point2D, Point3D};
+//! use picocadrs::geometry::sweep::SweepOptions;
+//! use picocadrs::point;
+//!
+//! // a road with a 90 degree bend.
+//! let road = Mesh::sweep(
+//!     &[point!(-1.0, 0.0), point!(1.0, 0.0)],
+//!     &[point!(0.0, 0.0, 0.0), point!(0.0, 0.0, 4.0), point!(4.0, 0.0, 4.0)],
+//!     SweepOptions::default(),
+//! );
+//!
+//! assert_eq!(road.faces.len(), 2);
+//! assert!(road.vertices.contains(&point!(-1.0, 0.0, 5.0)));
+//! assert!(road.vertices.contains(&point!(1.0, 0.0, 3.0)));
+//! ```
+
+use crate::{
+    assets::{Face, Mesh, Point2D, Point3D, UVMap},
+    point,
+};
+
+/// Lengths below this are treated as zero.
+const EPSILON: f64 = 1e-9;
+
+/// Settings used by [`sweep`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SweepOptions {
+    /// If true, the last point of the profile is connected to the first one, making tubes.
+    pub closed_profile: bool,
+    /// If true, the last point of the path is connected to the first one, making rings.
+    pub closed_path: bool,
+    /// How much the profile may be stretched in bends before they are cut off, as a factor of
+    /// its width.
+    /// `1.0` cuts off every bend, `2.0` keeps bends up to 120 degrees.
+    pub miter_limit: f64,
+}
+
+impl Default for SweepOptions {
+    /// Open profile and path, bends up to 120 degrees are mitered.
+    fn default() -> Self {
+        SweepOptions {
+            closed_profile: false,
+            closed_path: false,
+            miter_limit: 2.0,
+        }
+    }
+}
+
+/// A copy of the profile placed somewhere along the path.
+struct Ring {
+    /// Indices of the vertices of every profile point.
+    vertices: Vec<usize>,
+    /// Distance along the path from its start.
+    distance: f64,
+}
+
+/// Moves `profile` along `path`, creating a mesh with a face between every two neighbouring
+/// profile points on every part of the path.
+///
+/// Points of the path that are the same as the one before are ignored.
+/// Returns an empty mesh if the profile or the path have less than 2 points.
+///
+/// It's recommended to use [`Mesh::sweep`].
+pub fn sweep(profile: &[Point2D<f64>], path: &[Point3D<f64>], options: SweepOptions) -> Mesh {
+    let mut mesh = Mesh::new("sweep".to_string());
+
+    let mut points: Vec<Point3D<f64>> = vec![];
+    for point in path.iter() {
+        if points.last() != Some(point) {
+            points.push(*point);
+        }
+    }
+    if options.closed_path && points.len() > 2 && points.first() == points.last() {
+        points.pop();
+    }
+
+    if profile.len() < 2 || points.len() < 2 {
+        return mesh;
+    }
+
+    let closed_path = options.closed_path && points.len() > 2;
+    let count = points.len();
+    let direction = |index: usize| (points[(index + 1) % count] - points[index]).normalized();
+
+    let mut rings: Vec<Ring> = vec![];
+    let mut distance = 0.0;
+
+    for (index, point) in points.iter().enumerate() {
+        let incoming = (index > 0 || closed_path).then(|| direction((index + count - 1) % count));
+        let outgoing = (index + 1 < count || closed_path).then(|| direction(index));
+
+        let mut place = |direction: Point3D<f64>, plane: Point3D<f64>| {
+            let (side, up) = frame(direction);
+            let vertices = profile
+                .iter()
+                .map(|corner| {
+                    let offset = side * corner.u + up * corner.v;
+                    let along = -offset.dot(plane) / direction.dot(plane);
+
+                    mesh.vertices.push(*point + offset + direction * along);
+                    mesh.vertices.len() - 1
+                })
+                .collect();
+
+            rings.push(Ring { vertices, distance });
+        };
+
+        match (incoming, outgoing) {
+            (Some(incoming), Some(outgoing)) => {
+                let bisector = incoming + outgoing;
+                let stretch = if bisector.length() > EPSILON {
+                    1.0 / incoming.dot(bisector.normalized())
+                } else {
+                    f64::INFINITY
+                };
+
+                if stretch <= options.miter_limit {
+                    place(incoming, bisector.normalized());
+                } else {
+                    place(incoming, incoming);
+                    place(outgoing, outgoing);
+                }
+            }
+            (Some(direction), None) | (None, Some(direction)) => place(direction, direction),
+            (None, None) => {}
+        }
+
+        if index + 1 < count || closed_path {
+            distance += (points[(index + 1) % count] - *point).length();
+        }
+    }
+
+    let total = distance;
+    let edges = if options.closed_profile && profile.len() > 2 {
+        profile.len()
+    } else {
+        profile.len() - 1
+    };
+    let strips = if closed_path {
+        rings.len()
+    } else {
+        rings.len() - 1
+    };
+
+    for strip in 0..strips {
+        let (first, second) = (&rings[strip], &rings[(strip + 1) % rings.len()]);
+        let start = first.distance / total * 15.0;
+        let end = if strip + 1 == rings.len() {
+            15.0
+        } else {
+            second.distance / total * 15.0
+        };
+
+        for edge in 0..edges {
+            let next = (edge + 1) % profile.len();
+            let left = 16.0 * edge as f64 / edges as f64;
+            let right = 16.0 * (edge + 1) as f64 / edges as f64;
+
+            mesh.faces.push(Face {
+                uv_maps: vec![
+                    UVMap::new(first.vertices[edge], point!(left, start)),
+                    UVMap::new(second.vertices[edge], point!(left, end)),
+                    UVMap::new(second.vertices[next], point!(right, end)),
+                    UVMap::new(first.vertices[next], point!(right, start)),
+                ],
+                ..Face::default()
+            });
+        }
+    }
+
+    mesh
+}
+
+/// Returns the directions `u` and `v` of the profile point in, when looking along `direction`.
+fn frame(direction: Point3D<f64>) -> (Point3D<f64>, Point3D<f64>) {
+    let mut side = direction.cross(point!(0.0, -1.0, 0.0));
+    if side.length() < EPSILON {
+        side = direction.cross(point!(0.0, 0.0, 1.0));
+    }
+    let side = side.normalized();
+
+    (side, side.cross(direction))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    /// A square of width 2 around the path, running clockwise.
+    fn square() -> Vec<Point2D<f64>> {
+        vec![
+            point!(-1.0, 1.0),
+            point!(1.0, 1.0),
+            point!(1.0, -1.0),
+            point!(-1.0, -1.0),
+        ]
+    }
+
+    #[test]
+    fn sweep_road() {
+        let road = Mesh::sweep(
+            &[point!(-1.0, 0.0), point!(1.0, 0.0)],
+            &[
+                point!(0.0, 0.0, 0.0),
+                point!(0.0, 0.0, 0.0),
+                point!(0.0, 0.0, 4.0),
+                point!(4.0, 0.0, 4.0),
+            ],
+            SweepOptions::default(),
+        );
+
+        assert_eq!(road.vertices.len(), 6);
+        assert_eq!(road.vertices[0], point!(-1.0, 0.0, 0.0));
+        assert_eq!(road.faces.len(), 2);
+        assert!(road
+            .faces
+            .iter()
+            .all(|face| face.normal(&road.vertices).y < 0.0));
+
+        assert_eq!(road.faces[0].uv_maps[2].coords, point!(16.0, 7.5));
+        assert_eq!(road.faces[1].uv_maps[2].coords, point!(16.0, 15.0));
+    }
+
+    #[test]
+    fn sweep_bevel() {
+        let path = [
+            point!(0.0, 0.0, 0.0),
+            point!(0.0, 0.0, 4.0),
+            point!(1.0, 0.0, 0.0),
+        ];
+        let profile = [point!(-1.0, 0.0), point!(1.0, 0.0)];
+
+        let beveled = Mesh::sweep(&profile, &path, SweepOptions::default());
+        assert_eq!(beveled.vertices.len(), 8);
+        assert_eq!(beveled.faces.len(), 3);
+        assert!(beveled.vertices.contains(&point!(-1.0, 0.0, 4.0)));
+
+        let mitered = Mesh::sweep(
+            &profile,
+            &path,
+            SweepOptions {
+                miter_limit: 100.0,
+                ..SweepOptions::default()
+            },
+        );
+        assert_eq!(mitered.vertices.len(), 6);
+    }
+
+    #[test]
+    fn sweep_tube() {
+        let tube = Mesh::sweep(
+            &square(),
+            &[point!(0.0, 0.0, 0.0), point!(0.0, 0.0, 3.0)],
+            SweepOptions {
+                closed_profile: true,
+                ..SweepOptions::default()
+            },
+        );
+
+        assert_eq!(tube.vertices.len(), 8);
+        assert_eq!(tube.faces.len(), 4);
+
+        for face in tube.faces.iter() {
+            let corners = face.corners(&tube.vertices).unwrap();
+            let center = corners
+                .iter()
+                .fold(point!(0.0, 0.0, 0.0), |sum, corner| sum + *corner)
+                / corners.len() as f64;
+
+            assert!(
+                face.normal(&tube.vertices)
+                    .dot(point!(center.x, center.y, 0.0))
+                    > 0.0
+            );
+        }
+    }
+
+    #[test]
+    fn sweep_ring() {
+        let ring = Mesh::sweep(
+            &square(),
+            &[
+                point!(0.0, 0.0, 0.0),
+                point!(4.0, 0.0, 0.0),
+                point!(4.0, 0.0, 4.0),
+                point!(0.0, 0.0, 4.0),
+                point!(0.0, 0.0, 0.0),
+            ],
+            SweepOptions {
+                closed_profile: true,
+                closed_path: true,
+                ..SweepOptions::default()
+            },
+        );
+
+        assert_eq!(ring.vertices.len(), 16);
+        assert_eq!(ring.faces.len(), 16);
+        assert!(ring.is_watertight());
+    }
+
+    #[test]
+    fn sweep_invalid() {
+        let path = [point!(0.0, 0.0, 0.0), point!(0.0, 0.0, 0.0)];
+
+        assert!(Mesh::sweep(&square(), &path, SweepOptions::default())
+            .vertices
+            .is_empty());
+        assert!(Mesh::sweep(
+            &[point!(0.0, 0.0)],
+            &[point!(0.0, 0.0, 0.0), point!(1.0, 0.0, 0.0)],
+            SweepOptions::default()
+        )
+        .faces
+        .is_empty());
+    }
+}
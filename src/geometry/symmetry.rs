@@ -0,0 +1,243 @@
+//! For making meshes symmetric.
+//!
+//! [`symmetrize`] cuts the mesh in half along the plane through its
+//! [`position`](crate::assets::Mesh::position) that faces the given [`Axis`], throws away one
+//! half and replaces it with a mirrored copy of the other.
+//! Vertices on the seam are shared by both halves instead of being duplicated, so the result has
+//! no cracks along the seam.
+//!
+//! Mirrored faces keep the uv-coordinates of the faces they are copied from, so both halves show
+//! the same part of the texture.
+//! Faces lying on the seam itself are kept once and not mirrored.
+//!
+//! [`mirror_uvs`] goes the other way for models that are already symmetric: it flips the
+//! uv-coordinates of the faces on the negative side over a vertical line on the texture, so both
+//! sides can share one half of it.
+//! Only that half needs to be painted, which is how characters are usually textured to save
+//! space.
+
+use crate::{
+    assets::{Axis, Mesh, Point2D, Point3D},
+    geometry::split::{self, Plane},
+    point,
+};
+use std::collections::HashMap;
+
+/// Distance to the seam below which a vertex is welded onto it.
+pub const SEAM_TOLERANCE: f64 = 1e-6;
+
+/// One side of a plane facing an [`Axis`].
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum Side {
+    /// Where coordinates on the axis are positive.
+    #[default]
+    Positive,
+    /// Where coordinates on the axis are negative.
+    Negative,
+}
+
+/// Keeps the half of the mesh on `side` of the seam and mirrors it onto the other side.
+///
+/// It's recommended to use [`Mesh::symmetrize`].
+pub fn symmetrize(mesh: &mut Mesh, axis: Axis, side: Side) {
+    let mut normal = point!(0.0, 0.0, 0.0);
+    *normal.axis_mut(axis) = match side {
+        Side::Positive => 1.0,
+        Side::Negative => -1.0,
+    };
+
+    // faces lying on the plane end up in front of it, so they are kept on either side.
+    let (mut half, _) = split::split_by_plane(mesh, Plane::new(point!(0.0, 0.0, 0.0), normal));
+
+    for vertex in half.vertices.iter_mut() {
+        if vertex.axis(axis).abs() <= SEAM_TOLERANCE {
+            *vertex.axis_mut(axis) = 0.0;
+        }
+    }
+
+    let on_seam = |vertex: &Point3D<f64>| vertex.axis(axis) == 0.0;
+
+    // maps vertices of the half onto their mirrored copies.
+    let mut mirrored: HashMap<usize, usize> = HashMap::new();
+    let mut vertices = half.vertices.clone();
+
+    for (index, vertex) in half.vertices.iter().enumerate() {
+        if on_seam(vertex) {
+            mirrored.insert(index, index);
+        } else {
+            let mut copy = *vertex;
+            *copy.axis_mut(axis) = -vertex.axis(axis);
+
+            vertices.push(copy);
+            mirrored.insert(index, vertices.len() - 1);
+        }
+    }
+
+    let mut faces = half.faces.clone();
+
+    for face in half.faces.iter() {
+        if face
+            .uv_maps
+            .iter()
+            .all(|uv_map| on_seam(&half.vertices[uv_map.vertex_index]))
+        {
+            continue;
+        }
+
+        let mut copy = face.clone();
+        for uv_map in copy.uv_maps.iter_mut() {
+            uv_map.vertex_index = mirrored[&uv_map.vertex_index];
+        }
+        copy.flip();
+
+        faces.push(copy);
+    }
+
+    half.vertices = vertices;
+    half.faces = faces;
+    *mesh = half;
+}
+
+/// Mirrors the uv-coordinates of every face on the negative side of the seam over the vertical
+/// line at `seam_u` on the texture and returns how many faces were changed.
+///
+/// A face is on the negative side if none of its corners are in front of the seam and at least
+/// one is behind it, so faces crossing or lying on the seam keep their coordinates.
+///
+/// It's recommended to use [`Mesh::mirror_uvs`].
+pub fn mirror_uvs(mesh: &mut Mesh, axis: Axis, seam_u: f64) -> usize {
+    let mut changed = 0;
+
+    for face in mesh.faces.iter_mut() {
+        let Some(corners) = face.corners(&mesh.vertices) else {
+            continue;
+        };
+
+        let negative = corners
+            .iter()
+            .all(|corner| corner.axis(axis) <= SEAM_TOLERANCE)
+            && corners
+                .iter()
+                .any(|corner| corner.axis(axis) < -SEAM_TOLERANCE);
+        if !negative {
+            continue;
+        }
+
+        for uv_map in face.uv_maps.iter_mut() {
+            uv_map.coords = point!(2.0 * seam_u - uv_map.coords.u, uv_map.coords.v);
+        }
+        changed += 1;
+    }
+
+    changed
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Face, Point2D, UVMap};
+
+    /// A strip of two quads from `x = -1` to `x = 2`, the seam at `x = 0` cuts the first quad.
+    fn strip() -> Mesh {
+        let mut mesh = Mesh::new("strip".to_string());
+        mesh.vertices = vec![
+            point!(-1.0, 0.0, 0.0),
+            point!(1.0, 0.0, 0.0),
+            point!(2.0, 0.0, 0.0),
+            point!(-1.0, 1.0, 0.0),
+            point!(1.0, 1.0, 0.0),
+            point!(2.0, 1.0, 0.0),
+        ];
+
+        for indices in [[0, 1, 4, 3], [1, 2, 5, 4]] {
+            mesh.faces.push(Face {
+                uv_maps: indices
+                    .iter()
+                    .map(|i| UVMap::new(*i, point!(0.0, 0.0)))
+                    .collect(),
+                ..Face::default()
+            });
+        }
+
+        mesh
+    }
+
+    #[test]
+    fn symmetry_positive() {
+        let mut mesh = strip();
+        mesh.symmetrize(Axis::X, Side::Positive);
+
+        // 2 seam vertices shared, 4 vertices on each side.
+        assert_eq!(mesh.vertices.len(), 10);
+        assert_eq!(mesh.faces.len(), 4);
+        assert!(mesh.vertices.contains(&point!(-2.0, 1.0, 0.0)));
+        assert_eq!(
+            mesh.vertices
+                .iter()
+                .filter(|vertex| vertex.x == 0.0)
+                .count(),
+            2
+        );
+        assert!(mesh.non_manifold_edges().is_empty());
+
+        for vertex in mesh.vertices.iter() {
+            assert!(mesh
+                .vertices
+                .contains(&point!(-vertex.x, vertex.y, vertex.z)));
+        }
+
+        // faces parallel to the mirror axis keep facing the same way.
+        let normal = mesh.faces[0].normal(&mesh.vertices);
+        assert_eq!(mesh.faces[2].normal(&mesh.vertices), normal);
+    }
+
+    #[test]
+    fn symmetry_negative() {
+        let mut mesh = strip();
+        mesh.symmetrize(Axis::X, Side::Negative);
+
+        assert_eq!(mesh.vertices.len(), 6);
+        assert_eq!(mesh.faces.len(), 2);
+        assert!(mesh.vertices.contains(&point!(1.0, 1.0, 0.0)));
+        assert!(mesh.vertices.iter().all(|vertex| vertex.x != 2.0));
+    }
+
+    #[test]
+    fn symmetry_face_on_seam() {
+        let mut mesh = strip();
+        mesh.symmetrize(Axis::Z, Side::Positive);
+
+        assert_eq!(mesh.vertices.len(), 6);
+        assert_eq!(mesh.faces.len(), 2);
+    }
+    #[test]
+    fn symmetry_mirror_uvs() {
+        let mut mesh = strip();
+        mesh.vertices[1].x = 0.0;
+        mesh.vertices[4].x = 0.0;
+        for (uv_map, u) in mesh.faces[0].uv_maps.iter_mut().zip([0.0, 4.0, 4.0, 0.0]) {
+            uv_map.coords = point!(u, 1.0);
+        }
+        let right = mesh.faces[1].clone();
+
+        assert_eq!(mesh.mirror_uvs(Axis::X, 4.0), 1);
+        let coords: Vec<Point2D<f64>> = mesh.faces[0]
+            .uv_maps
+            .iter()
+            .map(|uv_map| uv_map.coords)
+            .collect();
+        assert_eq!(
+            coords,
+            vec![
+                point!(8.0, 1.0),
+                point!(4.0, 1.0),
+                point!(4.0, 1.0),
+                point!(8.0, 1.0)
+            ]
+        );
+        assert_eq!(mesh.faces[1], right);
+
+        // the strip lies on the seam of the z-axis.
+        assert_eq!(mesh.mirror_uvs(Axis::Z, 4.0), 0);
+    }
+}
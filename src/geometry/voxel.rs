@@ -0,0 +1,373 @@
+//! For combining meshes by turning them into voxels first.
+//!
+//! Exact boolean operations on meshes break easily on touching faces, open meshes or rounding
+//! errors.
+//! [`union_voxel`] avoids all of that by filling a grid of cubes with everything inside either
+//! mesh and building a new mesh from the outside of the grid, like [`vox`](crate::vox) does with
+//! imported models.
+//! The result is blocky, which suits the look of picoCAD anyway.
+//!
+//! A voxel is inside a mesh if its center is: a ray from the center crosses the faces of the mesh
+//! an odd number of times.
+//! Meshes should therefore be closed, see [`Mesh::is_watertight`].
+//!
+//! Neighbouring sides of voxels are merged into as few quads as possible, as long as they come
+//! from the same mesh or both meshes look the same.
+//! Quads take the color and flags of the first face of the mesh their voxels come from and are
+//! mapped onto the texture by their position, one uv unit per picoCAD unit.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Mesh, Point3D};
+//! use picocadrs::template::MeshTemplate;
+//! use picocadrs::point;
+//!
+//! let left = MeshTemplate::new("left".to_string()).cube(2).build();
+//! let mut right = left.clone();
+//! right.position = point!(1.0, 0.0, 0.0);
+//!
+//! let union = left.union_voxel(&right, 0.5);
+//!
+//! // both cubes became one box.
+//! assert_eq!(union.faces.len(), 6);
+//! assert!(union.vertices.contains(&point!(2.0, 1.0, 1.0)));
+//! assert!(union.is_watertight());
+//! ```
+
+use crate::{
+    assets::{Face, Mesh, Point2D, Point3D, UVMap},
+    point,
+};
+use std::collections::HashMap;
+
+/// Moves rays off the grid a tiny bit so they don't run exactly along edges of faces.
+const JITTER: [f64; 2] = [1.3e-7, 0.7e-7];
+
+/// Cells between two corners of the world, in multiples of the voxel size.
+struct Grid {
+    /// Index of the first cell on each axis.
+    origin: [i64; 3],
+    size: [usize; 3],
+    resolution: f64,
+    /// Index of the mesh each voxel comes from, [`None`] if it is empty.
+    voxels: Vec<Option<usize>>,
+}
+
+impl Grid {
+    /// Returns the mesh the voxel at `position` comes from, [`None`] if it is empty or outside of
+    /// the grid.
+    fn get(&self, position: [i64; 3]) -> Option<usize> {
+        if (0..3).any(|axis| position[axis] < 0 || position[axis] >= self.size[axis] as i64) {
+            return None;
+        }
+
+        let [x, y, z] = position.map(|value| value as usize);
+        self.voxels[x + self.size[0] * (y + self.size[1] * z)]
+    }
+
+    /// Returns the corner of the grid at `corner` in world space.
+    fn corner(&self, corner: [usize; 3]) -> Point3D<f64> {
+        let [x, y, z] = [0, 1, 2]
+            .map(|axis| (self.origin[axis] + corner[axis] as i64) as f64 * self.resolution);
+        point!(x, y, z)
+    }
+}
+
+/// Returns a mesh of everything inside of `a` or `b`, built from voxels with edges `resolution`
+/// units long.
+///
+/// The result keeps the name, position and rotation of `a`, positions of both meshes are taken
+/// into account.
+/// Voxels are lined up with the origin of the world, so a `resolution` of
+/// [`GRID_STEP`](crate::assets::mesh::GRID_STEP) puts every vertex onto picoCAD's grid.
+/// The amount of voxels grows with the cube of the size of the meshes divided by `resolution`, so
+/// small values get slow quickly.
+///
+/// Returns a mesh without vertices if both meshes have none or `resolution` isn't a positive
+/// number.
+///
+/// It's recommended to use [`Mesh::union_voxel`].
+pub fn union_voxel(a: &Mesh, b: &Mesh, resolution: f64) -> Mesh {
+    let mut mesh = Mesh {
+        vertices: vec![],
+        faces: vec![],
+        ..a.clone()
+    };
+
+    if !(resolution > 0.0 && resolution.is_finite()) {
+        return mesh;
+    }
+
+    let sources: Vec<Vec<Point3D<f64>>> = [a, b]
+        .iter()
+        .map(|mesh| {
+            mesh.vertices
+                .iter()
+                .map(|vertex| *vertex + mesh.position)
+                .collect()
+        })
+        .collect();
+
+    let Some((min, max)) = sources
+        .iter()
+        .flatten()
+        .map(|vertex| (*vertex, *vertex))
+        .reduce(|(min, max), (a, b)| (min.min(a), max.max(b)))
+    else {
+        return mesh;
+    };
+
+    let origin = [min.x, min.y, min.z].map(|value| (value / resolution).floor() as i64);
+    let end = [max.x, max.y, max.z].map(|value| (value / resolution).ceil() as i64);
+    let size = [0, 1, 2].map(|axis| (end[axis] - origin[axis]).max(0) as usize);
+
+    let mut grid = Grid {
+        origin,
+        size,
+        resolution,
+        voxels: vec![None; size[0] * size[1] * size[2]],
+    };
+
+    let templates = [a, b].map(|mesh| Face {
+        uv_maps: vec![],
+        ..mesh.faces.first().cloned().unwrap_or_default()
+    });
+    // voxels of meshes whose faces look the same can be merged.
+    let second = usize::from(templates[0] != templates[1]);
+
+    for ((index, source), vertices) in [(0, a), (second, b)].iter().zip(sources.iter()) {
+        fill(&mut grid, *index, &source.faces, vertices);
+    }
+
+    build(&grid, &templates, &mut mesh);
+
+    mesh
+}
+
+/// Marks the empty voxels of the grid whose center is inside the faces with `source`.
+///
+/// Each row of voxels along the `x`-axis is checked with a single ray, counting the faces it
+/// crosses before each center.
+fn fill(grid: &mut Grid, source: usize, faces: &[Face], vertices: &[Point3D<f64>]) {
+    let resolution = grid.resolution;
+    let start = (grid.origin[0] - 1) as f64 * resolution;
+
+    for z in 0..grid.size[2] {
+        for y in 0..grid.size[1] {
+            let ray = point!(
+                start,
+                ((grid.origin[1] + y as i64) as f64 + 0.5 + JITTER[0]) * resolution,
+                ((grid.origin[2] + z as i64) as f64 + 0.5 + JITTER[1]) * resolution
+            );
+
+            let mut hits: Vec<f64> = faces
+                .iter()
+                .filter_map(|face| {
+                    face.intersect_ray_sides(vertices, ray, point!(1.0, 0.0, 0.0), true)
+                })
+                .map(|hit| hit.distance)
+                .collect();
+            hits.sort_by(f64::total_cmp);
+
+            for x in 0..grid.size[0] {
+                let center = (x as f64 + 1.5) * resolution;
+                let crossed = hits.iter().take_while(|hit| **hit < center).count();
+
+                let voxel = &mut grid.voxels[x + grid.size[0] * (y + grid.size[1] * z)];
+                if crossed % 2 == 1 && voxel.is_none() {
+                    *voxel = Some(source);
+                }
+            }
+        }
+    }
+}
+
+/// Adds the outside of the grid to `mesh`, merging sides of voxels from the same source into
+/// quads that look like the face in `templates` of their source.
+fn build(grid: &Grid, templates: &[Face; 2], mesh: &mut Mesh) {
+    // index of the vertex at each corner of the grid.
+    let mut corners: HashMap<[usize; 3], usize> = HashMap::new();
+
+    for axis in 0..3 {
+        let (u_axis, v_axis) = ((axis + 1) % 3, (axis + 2) % 3);
+        let (width, height) = (grid.size[u_axis], grid.size[v_axis]);
+
+        for direction in [-1, 1] {
+            let mut normal = [0.0; 3];
+            normal[axis] = direction as f64;
+            let normal = point!(normal[0], normal[1], normal[2]);
+
+            for layer in 0..grid.size[axis] {
+                // source of each visible side in this layer.
+                let mut mask: Vec<Option<usize>> = vec![None; width * height];
+
+                for v in 0..height {
+                    for u in 0..width {
+                        let mut position = [0i64; 3];
+                        position[axis] = layer as i64;
+                        position[u_axis] = u as i64;
+                        position[v_axis] = v as i64;
+
+                        let mut neighbour = position;
+                        neighbour[axis] += direction;
+
+                        if grid.get(neighbour).is_none() {
+                            mask[u + v * width] = grid.get(position);
+                        }
+                    }
+                }
+
+                for v in 0..height {
+                    let mut u = 0;
+                    while u < width {
+                        let Some(source) = mask[u + v * width] else {
+                            u += 1;
+                            continue;
+                        };
+
+                        let mut quad_width = 1;
+                        while u + quad_width < width
+                            && mask[u + quad_width + v * width] == Some(source)
+                        {
+                            quad_width += 1;
+                        }
+
+                        let mut quad_height = 1;
+                        while v + quad_height < height
+                            && (u..u + quad_width)
+                                .all(|cell| mask[cell + (v + quad_height) * width] == Some(source))
+                        {
+                            quad_height += 1;
+                        }
+
+                        for row in v..v + quad_height {
+                            for cell in u..u + quad_width {
+                                mask[cell + row * width] = None;
+                            }
+                        }
+
+                        let plane = layer + usize::from(direction > 0);
+                        let uv_maps = [
+                            (u, v),
+                            (u + quad_width, v),
+                            (u + quad_width, v + quad_height),
+                            (u, v + quad_height),
+                        ]
+                        .map(|(corner_u, corner_v)| {
+                            let mut corner = [0; 3];
+                            corner[axis] = plane;
+                            corner[u_axis] = corner_u;
+                            corner[v_axis] = corner_v;
+
+                            let index = *corners.entry(corner).or_insert_with(|| {
+                                mesh.vertices.push(grid.corner(corner) - mesh.position);
+                                mesh.vertices.len() - 1
+                            });
+
+                            let coords = point!(
+                                corner_u as f64 * grid.resolution,
+                                corner_v as f64 * grid.resolution
+                            );
+                            UVMap::new(index, coords)
+                        });
+
+                        let mut face = Face {
+                            uv_maps: uv_maps.to_vec(),
+                            ..templates[source].clone()
+                        };
+                        if face.normal(&mesh.vertices).dot(normal) < 0.0 {
+                            face.flip();
+                        }
+                        mesh.faces.push(face);
+
+                        u += quad_width;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::Color;
+    use crate::template::MeshTemplate;
+
+    fn cube(size: f64, position: Point3D<f64>) -> Mesh {
+        let mut cube = MeshTemplate::new("cube".to_string()).cube(size).build();
+        cube.position = position;
+        cube
+    }
+
+    #[test]
+    fn voxel_union_overlapping() {
+        let a = cube(2.0, point!(1.0, 0.0, 0.0));
+        let b = cube(2.0, point!(2.0, 0.0, 0.0));
+
+        let union = a.union_voxel(&b, 0.25);
+
+        assert_eq!(union.position, point!(1.0, 0.0, 0.0));
+        assert_eq!(union.faces.len(), 6);
+        assert_eq!(union.vertices.len(), 8);
+        assert!(union.is_watertight());
+        assert!(union.vertices.contains(&point!(-1.0, -1.0, -1.0)));
+        assert!(union.vertices.contains(&point!(2.0, 1.0, 1.0)));
+
+        for face in union.faces.iter() {
+            let corners = face.corners(&union.vertices).unwrap();
+            let center = corners
+                .iter()
+                .fold(point!(0.0, 0.0, 0.0), |sum, corner| sum + *corner)
+                / 4.0;
+
+            assert!(
+                face.normal(&union.vertices)
+                    .dot(center - point!(0.5, 0.0, 0.0))
+                    > 0.0
+            );
+        }
+    }
+
+    #[test]
+    fn voxel_union_sources() {
+        let mut a = cube(2.0, point!(0.0, 0.0, 0.0));
+        let mut b = cube(2.0, point!(4.0, 0.0, 0.0));
+        for face in a.faces.iter_mut() {
+            face.color = Color::Red;
+        }
+        for face in b.faces.iter_mut() {
+            face.color = Color::Blue;
+        }
+
+        let union = a.union_voxel(&b, 1.0);
+
+        assert_eq!(union.faces.len(), 12);
+        assert_eq!(
+            union
+                .faces
+                .iter()
+                .filter(|face| face.color == Color::Blue)
+                .count(),
+            6
+        );
+    }
+
+    #[test]
+    fn voxel_union_invalid() {
+        let a = cube(2.0, point!(0.0, 0.0, 0.0));
+
+        assert!(a.union_voxel(&a, 0.0).vertices.is_empty());
+        assert!(a.union_voxel(&a, f64::NAN).vertices.is_empty());
+        assert!(Mesh::new("empty".to_string())
+            .union_voxel(&Mesh::new("empty".to_string()), 1.0)
+            .faces
+            .is_empty());
+
+        // a mesh with itself stays the same.
+        let union = a.union_voxel(&a, 0.5);
+        assert_eq!(union.faces.len(), 6);
+        assert_eq!(union.name, "cube");
+    }
+}
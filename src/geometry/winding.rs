@@ -0,0 +1,195 @@
+//! For making all faces of a mesh point the same way.
+//!
+//! Which side of a face picoCAD renders depends on the order of its vertices, see
+//! [`Face::normal`].
+//! Two faces sharing an edge are oriented the same way if they walk along that edge in opposite
+//! directions.
+//! Imported or mirrored meshes often mix both orders, which makes parts of them disappear unless
+//! every face is double-sided.
+//!
+//! [`make_winding_consistent`] starts at the first face of every connected part of the mesh and
+//! flips its neighbours until all faces agree with it.
+//! Optionally each part is then turned around as a whole if its faces point inwards, measured by
+//! the sign of the volume they enclose.
+//! This only gives meaningful results for closed parts, open ones keep the orientation of their
+//! first face.
+//!
+//! Across non-manifold edges, used by more than 2 faces, the first neighbour reached decides.
+
+use crate::{
+    analysis::topology::{self, Edge},
+    assets::{Face, Mesh, Point3D},
+    point,
+};
+use std::collections::VecDeque;
+
+/// Flips faces until all faces connected to each other are oriented the same way and returns the
+/// amount of faces that were flipped.
+///
+/// If `outward` is true, closed parts of the mesh are additionally turned so their faces point
+/// away from their inside.
+///
+/// It's recommended to use [`Mesh::make_winding_consistent`].
+pub fn make_winding_consistent(mesh: &mut Mesh, outward: bool) -> usize {
+    let adjacency = topology::edge_adjacency(mesh);
+    let mut flipped: Vec<Option<bool>> = vec![None; mesh.faces.len()];
+
+    for seed in 0..mesh.faces.len() {
+        if flipped[seed].is_some() {
+            continue;
+        }
+
+        flipped[seed] = Some(false);
+        let mut part = vec![seed];
+        let mut queue = VecDeque::from([seed]);
+
+        while let Some(current) = queue.pop_front() {
+            let face = &mesh.faces[current];
+
+            for (a, b) in directed_edges(face) {
+                for neighbour in adjacency[&Edge::new(a, b)].iter() {
+                    if flipped[*neighbour].is_some() {
+                        continue;
+                    }
+
+                    // neighbours walking along the edge in the same direction point the other way.
+                    let same_direction =
+                        directed_edges(&mesh.faces[*neighbour]).any(|edge| edge == (a, b));
+
+                    flipped[*neighbour] = Some(flipped[current] != Some(same_direction));
+                    part.push(*neighbour);
+                    queue.push_back(*neighbour);
+                }
+            }
+        }
+
+        if outward && signed_volume(mesh, &part, &flipped) > 0.0 {
+            for index in part {
+                flipped[index] = flipped[index].map(|flip| !flip);
+            }
+        }
+    }
+
+    let mut count = 0;
+
+    for (face, flip) in mesh.faces.iter_mut().zip(flipped) {
+        if flip == Some(true) {
+            face.flip();
+            count += 1;
+        }
+    }
+
+    count
+}
+
+/// Iterates over the edges of the face in the order of its vertices, skipping edges between the
+/// same vertex.
+fn directed_edges(face: &Face) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let count = face.uv_maps.len();
+
+    (0..count)
+        .filter(move |_| count >= 3)
+        .map(move |i| {
+            (
+                face.uv_maps[i].vertex_index,
+                face.uv_maps[(i + 1) % count].vertex_index,
+            )
+        })
+        .filter(|(a, b)| a != b)
+}
+
+/// Returns six times the volume enclosed by the faces in `part` as if they were flipped according
+/// to `flipped`.
+///
+/// The volume is negative if the faces point outwards, since the visible side of a face in
+/// picoCAD is the one where its vertices appear in clockwise order.
+fn signed_volume(mesh: &Mesh, part: &[usize], flipped: &[Option<bool>]) -> f64 {
+    let corners: Vec<Vec<Point3D<f64>>> = part
+        .iter()
+        .filter_map(|index| {
+            let face = &mesh.faces[*index];
+            let mut corners: Vec<Point3D<f64>> = face
+                .uv_maps
+                .iter()
+                .map(|uv_map| mesh.vertices.get(uv_map.vertex_index).copied())
+                .collect::<Option<_>>()?;
+
+            if flipped[*index] == Some(true) {
+                corners.reverse();
+            }
+
+            Some(corners)
+        })
+        .collect();
+
+    // measuring from the center keeps the result stable for parts far away from the origin.
+    let all: Vec<&Point3D<f64>> = corners.iter().flatten().collect();
+    if all.is_empty() {
+        return 0.0;
+    }
+    let center = all
+        .iter()
+        .fold(point!(0.0, 0.0, 0.0), |sum, corner| sum + **corner)
+        / all.len() as f64;
+
+    corners
+        .iter()
+        .filter(|corners| corners.len() >= 3)
+        .map(|corners| {
+            let first = corners[0] - center;
+
+            corners[1..]
+                .windows(2)
+                .map(|pair| first.dot((pair[0] - center).cross(pair[1] - center)))
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::fixtures::{cube, points_outward};
+
+    #[test]
+    fn winding_cube() {
+        let mut mesh = cube();
+        assert!(points_outward(&mesh));
+        assert_eq!(mesh.make_winding_consistent(true), 0);
+
+        mesh.faces[1].flip();
+        mesh.faces[4].flip();
+        assert_eq!(mesh.make_winding_consistent(false), 2);
+        assert!(points_outward(&mesh));
+
+        for face in mesh.faces.iter_mut() {
+            face.flip();
+        }
+        assert_eq!(mesh.make_winding_consistent(false), 0);
+        assert_eq!(mesh.make_winding_consistent(true), 6);
+        assert!(points_outward(&mesh));
+    }
+
+    #[test]
+    fn winding_separate_parts() {
+        let mut mesh = cube();
+        let offset = mesh.vertices.len();
+
+        for vertex in cube().vertices {
+            mesh.vertices.push(vertex + point!(10.0, 0.0, 0.0));
+        }
+        for mut face in cube().faces {
+            for uv_map in face.uv_maps.iter_mut() {
+                uv_map.vertex_index += offset;
+            }
+            face.flip();
+            mesh.faces.push(face);
+        }
+
+        assert_eq!(mesh.make_winding_consistent(true), 6);
+        assert!(mesh.faces[6..]
+            .iter()
+            .zip(cube().faces)
+            .all(|(a, b)| { a.uv_maps[0].vertex_index == b.uv_maps[0].vertex_index + offset }));
+    }
+}
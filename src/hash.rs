@@ -0,0 +1,166 @@
+//! For telling models and meshes apart without comparing them field by field.
+//!
+//! Hashes are calculated over the text a model or mesh is saved as, so two values get the same
+//! hash exactly if picoCAD would store them the same way.
+//! Differences picoCAD can't store, like `0.1 + 0.2` and `0.3`, don't change the hash.
+//!
+//! Unlike the hashes of [`core::hash::Hash`], which may change between versions of rust, the
+//! hashes returned here are stable, so they can be stored next to files to find changes later.
+//! They use 64-bit [FNV-1a](http://www.isthe.com/chongo/tech/comp/fnv/index.html), which is fast
+//! but not meant to protect against deliberate collisions.
+//!
+//! - [`content_hash`] hashes a whole model, header and texture included.
+//! - [`mesh_hash`] hashes a single mesh, including its name and placement.
+//! - [`shape_hash`] only hashes the vertices and faces of a mesh, so the same prop is found in
+//!   different files even if it was renamed or moved around.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Mesh, Model, Point3D};
+//! use picocadrs::point;
+//!
+//! let mut model = Model::default();
+//! model.meshes.push(Mesh::plane());
+//! model.meshes.push(Mesh::plane());
+//! model.meshes[1].name = "floor".to_string();
+//! model.meshes[1].position = point!(0.0, 2.0, 0.0);
+//!
+//! let before = model.content_hash();
+//! assert_ne!(model.meshes[0].content_hash(), model.meshes[1].content_hash());
+//! assert_eq!(model.meshes[0].shape_hash(), model.meshes[1].shape_hash());
+//!
+//! model.meshes[1].position = point!(0.0, 1.0 + 1.0, 0.0);
+//! assert_eq!(model.content_hash(), before);
+//! ```
+
+use crate::assets::{Mesh, Model};
+use core::fmt::{Display, Formatter, Write};
+
+/// Starting value of a FNV-1a hash.
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+/// Value the hash is multiplied with after each byte.
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Stable hash of a model or mesh.
+///
+/// Displayed as 16 hexadecimal digits.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::hash::ContentHash;
+///
+/// assert_eq!(ContentHash(255).to_string(), "00000000000000ff");
+/// ```
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ContentHash(pub u64);
+
+impl Display for ContentHash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// Hashes everything written into it.
+struct Hasher(u64);
+
+impl Hasher {
+    fn new() -> Hasher {
+        Hasher(FNV_OFFSET)
+    }
+
+    fn finish(&self) -> ContentHash {
+        ContentHash(self.0)
+    }
+}
+
+impl Write for Hasher {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for byte in s.bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the hash of the file `model` is saved as.
+///
+/// It's recommended to use [`Model::content_hash`].
+pub fn content_hash(model: &Model) -> ContentHash {
+    let mut hasher = Hasher::new();
+    let _ = write!(hasher, "{}", model);
+    hasher.finish()
+}
+
+/// Returns the hash of the table `mesh` is saved as.
+///
+/// It's recommended to use [`Mesh::content_hash`].
+pub fn mesh_hash(mesh: &Mesh) -> ContentHash {
+    let mut hasher = Hasher::new();
+    let _ = write!(hasher, "{}", mesh);
+    hasher.finish()
+}
+
+/// Returns the hash of the vertices and faces of `mesh`.
+///
+/// Name, position, rotation and [`extras`](Mesh::extras) are left out.
+///
+/// It's recommended to use [`Mesh::shape_hash`].
+pub fn shape_hash(mesh: &Mesh) -> ContentHash {
+    let mut hasher = Hasher::new();
+
+    for vertex in mesh.vertices.iter() {
+        let _ = write!(hasher, "{{{}}},", vertex);
+    }
+    // separates vertices from faces, so moving an entry between them changes the hash.
+    let _ = hasher.write_str(";");
+    for face in mesh.faces.iter() {
+        let _ = write!(hasher, "{},", face);
+    }
+
+    hasher.finish()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Color, Point3D};
+    use crate::point;
+
+    #[test]
+    fn hash_known_values() {
+        // reference values of 64-bit FNV-1a.
+        let hash = |s: &str| {
+            let mut hasher = Hasher::new();
+            hasher.write_str(s).unwrap();
+            hasher.finish()
+        };
+
+        assert_eq!(hash(""), ContentHash(0xcbf29ce484222325));
+        assert_eq!(hash("a"), ContentHash(0xaf63dc4c8601ec8c));
+    }
+
+    #[test]
+    fn hash_changes() {
+        let mut model = Model::default();
+        model.meshes.push(Mesh::plane());
+        let hash = model.content_hash();
+        let shape = model.meshes[0].shape_hash();
+
+        model.meshes[0].faces[0].color = Color::Red;
+        assert_ne!(model.content_hash(), hash);
+        assert_ne!(model.meshes[0].shape_hash(), shape);
+
+        model.meshes[0].faces[0].color = Color::LightGrey;
+        model.meshes[0].rotation.0 = point!(0.25, 0.0, 0.0);
+        assert_ne!(model.content_hash(), hash);
+        assert_eq!(model.meshes[0].shape_hash(), shape);
+
+        model.meshes[0].rotation.0 = point!(0.0, 0.0, 0.0);
+        assert_eq!(model.content_hash(), hash);
+        assert_eq!(model.mesh_hashes(), vec![Mesh::plane().content_hash()]);
+    }
+}
@@ -0,0 +1,386 @@
+//! For salvaging models out of slightly corrupted project files.
+//!
+//! Parsing a [`Model`] normally fails as soon as a single part of the file can't be read.
+//! [`parse_lenient`] instead skips everything it can't make sense of and reports it as a
+//! [`ParseWarning`], keeping the rest of the model:
+//!
+//! - a broken header is replaced by [`Header::default`]
+//! - meshes that aren't valid lua tables or have broken fields are skipped
+//! - faces that can't be read or use vertices the mesh doesn't have are skipped
+//! - a footer that is too short is filled up with black, a footer that is too long is cut off
+//!
+//! Mesh tables are read with the parser of [`lua_table`](crate::lua_table), like
+//! parsing a [`Model`] does.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Footer, Model};
+//!
+//! let file = format!(
+//!     "picocad;broken;16;1;0\n{{\n{{\n name='ok', pos={{0,0,0}}, rot={{0,0,0}},\n v={{}},\n f={{}}\n}},\n{{ name=42 }}\n}}%\n{}",
+//!     Footer::default()
+//! );
+//!
+//! assert!(file.parse::<Model>().is_err());
+//!
+//! let (model, warnings) = Model::parse_lenient(&file);
+//!
+//! assert_eq!(model.meshes.len(), 1);
+//! assert_eq!(model.meshes[0].name, "ok");
+//! assert_eq!(warnings.len(), 1);
+//! assert_eq!(warnings[0].line, 8);
+//! assert_eq!(warnings[0].mesh, Some(1));
+//! ```
+
+use crate::{
+    assets::{Color, Face, Footer, Header, Mesh, Model, Point2D},
+    error::PicoError,
+    lua_table::{self, LuaValue},
+    point,
+};
+use alloc::{string::ToString, vec, vec::Vec};
+use core::fmt::{Display, Formatter};
+
+/// A part of a file that was skipped or repaired by [`parse_lenient`].
+#[derive(Debug)]
+pub struct ParseWarning {
+    /// Line of the file the skipped part starts at, counting from 1.
+    /// Warnings about faces point to the line their mesh starts at.
+    pub line: usize,
+    /// Index of the mesh table in the file the warning belongs to, counting skipped meshes.
+    pub mesh: Option<usize>,
+    /// Index of the face table in its mesh the warning belongs to, counting skipped faces.
+    pub face: Option<usize>,
+    /// Why the part was skipped or repaired.
+    pub error: PicoError,
+}
+
+impl ParseWarning {
+    fn new(line: usize, mesh: Option<usize>, face: Option<usize>, error: PicoError) -> Self {
+        ParseWarning {
+            line,
+            mesh,
+            face,
+            error,
+        }
+    }
+}
+
+impl Display for ParseWarning {
+    /// Writes the location and the error, like `line 9, mesh 1, face 0: could not parse ...`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "line {}", self.line)?;
+
+        if let Some(mesh) = self.mesh {
+            write!(f, ", mesh {}", mesh)?;
+        }
+
+        if let Some(face) = self.face {
+            write!(f, ", face {}", face)?;
+        }
+
+        write!(f, ": {}", self.error)
+    }
+}
+
+/// Parses as much of a model as possible, skipping the parts that can't be read.
+///
+/// Never fails, in the worst case an empty model is returned together with the reasons.
+///
+/// It's recommended to use [`Model::parse_lenient`].
+pub fn parse_lenient(s: &str) -> (Model, Vec<ParseWarning>) {
+    let mut warnings: Vec<ParseWarning> = vec![];
+
+    let (header_str, rest) = s.split_once('\n').unwrap_or((s, ""));
+
+    let header = header_str
+        .trim_end_matches('\r')
+        .parse()
+        .unwrap_or_else(|error| {
+            warnings.push(ParseWarning::new(1, None, None, error));
+            Header::default()
+        });
+
+    let (meshes_str, footer_str) = match rest.rsplit_once('%') {
+        Some(split) => split,
+        None => {
+            warnings.push(ParseWarning::new(
+                line_of(s, s.len()),
+                None,
+                None,
+                PicoError::Split(r#"seperate meshes from footer with '%'"#.to_string()),
+            ));
+            (rest, "")
+        }
+    };
+
+    // meshes start on the second line of the file.
+    let meshes_offset = s.len() - rest.len();
+    let mut meshes: Vec<Mesh> = vec![];
+
+    for (index, chunk) in mesh_chunks(meshes_str).into_iter().enumerate() {
+        let line = line_of(s, meshes_offset + chunk.start);
+
+        match chunk.source {
+            Some(source) => {
+                if let Some(mesh) = parse_mesh(source, line, index, &mut warnings) {
+                    meshes.push(mesh);
+                }
+            }
+            None => warnings.push(ParseWarning::new(
+                line,
+                Some(index),
+                None,
+                PicoError::LuaSyntax("mesh table is never closed".to_string()),
+            )),
+        }
+    }
+
+    let footer_line = line_of(s, s.len() - footer_str.trim_start().len());
+    let footer = footer_str.parse().unwrap_or_else(|error| {
+        warnings.push(ParseWarning::new(footer_line, None, None, error));
+        repair_footer(footer_str)
+    });
+
+    (
+        Model {
+            header,
+            meshes,
+            footer,
+        },
+        warnings,
+    )
+}
+
+/// Source of a single mesh table inside the meshes part of a file.
+struct MeshChunk<'a> {
+    /// Byte offset of the opening brace in the meshes part.
+    start: usize,
+    /// Source of the table including its braces, [`None`] if it is never closed.
+    source: Option<&'a str>,
+}
+
+/// Splits the outer table of the meshes part into the sources of its inner tables.
+///
+/// Only braces are counted, while skipping strings and comments, so a mesh that is broken on the
+/// inside doesn't affect the meshes around it.
+fn mesh_chunks(meshes: &str) -> Vec<MeshChunk<'_>> {
+    let bytes = meshes.as_bytes();
+    let mut chunks: Vec<MeshChunk> = vec![];
+    let mut depth: usize = 0;
+    let mut start: usize = 0;
+    let mut i: usize = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'\'' | b'"') => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote && bytes[i] != b'\n' {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'{' => {
+                depth += 1;
+                if depth == 2 {
+                    start = i;
+                }
+            }
+            b'}' => {
+                if depth == 2 {
+                    chunks.push(MeshChunk {
+                        start,
+                        source: Some(&meshes[start..=i]),
+                    });
+                }
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+
+        i += 1;
+    }
+
+    if depth >= 2 {
+        chunks.push(MeshChunk {
+            start,
+            source: None,
+        });
+    }
+
+    chunks
+}
+
+/// Parses a single mesh table, skipping faces that can't be read.
+fn parse_mesh(
+    source: &str,
+    line: usize,
+    index: usize,
+    warnings: &mut Vec<ParseWarning>,
+) -> Option<Mesh> {
+    let table = match lua_table::parse(source) {
+        Ok(LuaValue::Table(table)) => table,
+        Ok(_) => unreachable!("mesh chunks start with a brace"),
+        Err(error) => {
+            warnings.push(ParseWarning::new(line, Some(index), None, error));
+            return None;
+        }
+    };
+
+    let mut table = table;
+    let faces = table.fields.remove("f");
+
+    let mut mesh = match Mesh::from_lua_table(&table) {
+        Ok(mesh) => mesh,
+        Err(error) => {
+            warnings.push(ParseWarning::new(line, Some(index), None, error));
+            return None;
+        }
+    };
+
+    let faces = match faces {
+        Some(LuaValue::Table(faces)) => faces,
+        Some(_) => {
+            warnings.push(ParseWarning::new(
+                line,
+                Some(index),
+                None,
+                PicoError::MeshField("f".to_string()),
+            ));
+            return Some(mesh);
+        }
+        None => return Some(mesh),
+    };
+
+    for (face_index, face) in faces.sequence.iter().enumerate() {
+        let face = face
+            .as_table()
+            .ok_or(PicoError::MeshField("f".to_string()))
+            .and_then(Face::from_lua_table)
+            .and_then(|face| {
+                match face
+                    .uv_maps
+                    .iter()
+                    .find(|uv_map| uv_map.vertex_index >= mesh.vertices.len())
+                {
+                    Some(uv_map) => Err(PicoError::VertexIndex(
+                        uv_map.vertex_index,
+                        mesh.vertices.len(),
+                    )),
+                    None => Ok(face),
+                }
+            });
+
+        match face {
+            Ok(face) => mesh.faces.push(face),
+            Err(error) => warnings.push(ParseWarning::new(
+                line,
+                Some(index),
+                Some(face_index),
+                error,
+            )),
+        }
+    }
+
+    Some(mesh)
+}
+
+/// Reads the pixels of a footer with the wrong length, filling missing pixels with black.
+fn repair_footer(footer: &str) -> Footer {
+    let mut repaired = Footer::default();
+
+    for (i, color) in footer
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '\n' | '\r'))
+        .map(Color::from)
+        .take(Footer::DATA_LENGHT)
+        .enumerate()
+    {
+        if let Some(pixel) = repaired.get_mut(point!(i % Footer::WIDTH, i / Footer::WIDTH)) {
+            *pixel = color;
+        }
+    }
+
+    repaired
+}
+
+/// Returns the line of the byte at `offset` in `s`, counting from 1.
+fn line_of(s: &str, offset: usize) -> usize {
+    s[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use alloc::{format, string::String};
+
+    fn file(meshes: &str) -> String {
+        format!(
+            "picocad;test;16;1;0\n{{\n{}\n}}%\n{}",
+            meshes,
+            Footer::default()
+        )
+    }
+
+    #[test]
+    fn parse_lenient_skips_faces() {
+        let (model, warnings) = parse_lenient(&file(
+            "{\n name='quad', pos={0,0,0}, rot={0,0,0},\n v={\n  {0,0,0},\n  {1,0,0},\n  {1,0,1}\n },\n f={\n  {1,2,3, c=5, uv={0,0,1,0,1,1} },\n  {1,2,9, c=5, uv={0,0,1,0,1,1} },\n  {3,2,1, c=1, uv={0,0,1,0,1,1} },\n  {1,2,3, c=5, uv={0,0} }\n }\n}",
+        ));
+
+        assert_eq!(model.meshes.len(), 1);
+        assert_eq!(model.meshes[0].faces.len(), 2);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].line, 3);
+        assert_eq!(warnings[0].face, Some(1));
+        assert!(matches!(warnings[0].error, PicoError::VertexIndex(8, 3)));
+        assert_eq!(warnings[1].face, Some(3));
+        assert_eq!(
+            warnings[1].to_string(),
+            "line 3, mesh 0, face 3: found 3 uv-coordinates (expected 2)"
+        );
+    }
+
+    #[test]
+    fn parse_lenient_skips_meshes() {
+        let (model, warnings) = parse_lenient(&file(
+            "{ name='a', v={}, f={} },\n{ name='b', pos={0,0} },\n{ name='-- }' },\n{ name='c', v={}, f={} }",
+        ));
+
+        let names: Vec<&str> = model.meshes.iter().map(|mesh| mesh.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "-- }", "c"]);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 4);
+        assert_eq!(warnings[0].mesh, Some(1));
+    }
+
+    #[test]
+    fn parse_lenient_repairs_header_and_footer() {
+        let (model, warnings) = parse_lenient("picocad;broken;x;1;0\n{\n{ name='a' }\n}%\n11");
+
+        assert_eq!(model.header, Header::default());
+        assert_eq!(model.meshes[0].name, "a");
+        assert_eq!(model.footer.pixel(point!(1, 0)), Color::DarkBlue);
+        assert_eq!(model.footer.pixel(point!(2, 0)), Color::Black);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].line, 1);
+        assert_eq!(warnings[1].line, 5);
+    }
+
+    #[test]
+    fn parse_lenient_valid_file() {
+        let model = Model::default();
+        let (parsed, warnings) = parse_lenient(&model.to_string());
+
+        assert!(warnings.is_empty());
+        assert_eq!(parsed.to_string(), model.to_string());
+    }
+}
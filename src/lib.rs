@@ -56,7 +56,105 @@
 //! println!("Double sided: {}", face.double_sided); // "Double sided: false"
 //! println!("No texture: {}", face.no_texture);     // "No texture: true"
 //! ```
+//!
+//! # Features
+//!
+//! - _std (default):_ Everything that needs the standard library, like reading and writing files,
+//!   converting values of a lua instance, exporting and analysing models.
+//!   Without it the crate is `no_std` and only needs `alloc`.
+//!   [`assets`] are always de-/serialized using a small parser for lua tables
+//!   (see [`lua_table::parse`]), which never runs the file as code.
+//! - _svg (default):_ Rendering models as SVG images, see [`export::svg`].
+//! - _index:_ Indexing [`Footer`](assets::Footer) with `footer[point!(u, v)]`, which panics if the
+//!   pixel is outside of the texture.
+//!   Without it only the non-panicking `get`, `get_mut`, `pixel` and `set` are available, so
+//!   corrupted project files can't bring down a program using this crate.
+//! - _proptest:_ Strategies for generating random models with
+//!   [proptest](https://docs.rs/proptest), see `arbitrary`.
+//! - _rayon:_ Spreads heavy work over all cores, like loading whole folders of projects with
+//!   `files::par_load_all`, rendering and finding which faces use which parts of the texture.
+//! - _scene:_ Building models out of nested groups of meshes with their own transforms, see
+//!   `scene`.
+//! - _vox:_ Importing [MagicaVoxel](https://ephtracy.github.io/) models, see `vox`.
+//! - _watch:_ Watching folders of projects for changes, for viewers that reload models whenever
+//!   they are saved in picoCAD, see `files::watch`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(test)]
+pub(crate) mod fixtures;
+#[cfg(not(feature = "std"))]
+pub(crate) mod float;
 
+#[cfg(feature = "std")]
+pub mod align;
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
 pub mod assets;
+#[cfg(feature = "std")]
+pub mod atlas;
+#[cfg(feature = "std")]
+pub mod budget;
+#[cfg(feature = "std")]
+pub mod camera;
+#[cfg(feature = "std")]
+pub mod cookbook;
+#[cfg(feature = "std")]
+pub mod diff;
+#[cfg(feature = "std")]
+pub mod duplicate;
 pub mod error;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "std")]
+pub mod files;
+#[cfg(feature = "std")]
+pub mod gen;
+#[cfg(feature = "std")]
+pub mod geometry;
+pub mod hash;
+pub mod lenient;
+#[cfg(feature = "std")]
+pub mod library;
+pub mod lua_table;
+#[cfg(feature = "std")]
+pub mod merge;
+pub mod metadata;
+pub mod number;
+#[cfg(feature = "std")]
+pub mod occlusion;
+#[cfg(feature = "std")]
+pub mod optimize;
+#[cfg(feature = "std")]
 pub mod paths;
+pub mod pico8;
+#[cfg(feature = "std")]
+pub mod project;
+#[cfg(feature = "std")]
+pub mod raycast;
+#[cfg(feature = "std")]
+pub mod recolor;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "scene")]
+pub mod scene;
+#[cfg(feature = "std")]
+pub mod select;
+#[cfg(feature = "std")]
+pub mod shading;
+#[cfg(feature = "std")]
+pub mod spatial;
+#[cfg(feature = "std")]
+pub mod template;
+#[cfg(feature = "std")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod texture;
+#[cfg(feature = "std")]
+pub mod visibility;
+#[cfg(feature = "vox")]
+pub mod vox;
@@ -0,0 +1,716 @@
+//! For sharing single meshes between projects.
+//!
+//! An [`Asset`] is a mesh cut out of a project together with the part of the texture its faces
+//! use, so it can be placed into any other project without losing its texture.
+//!
+//! - [`extract`] turns a mesh of a model into an asset.
+//! - [`instantiate`] adds an asset to a model, copying its pixels into free space of the texture
+//!   and moving its uv-coordinates along.
+//! - [`import_mesh`] does both at once to copy a mesh from one model into another.
+//! - [`Library`] collects assets, either by scanning a folder of projects or by loading assets
+//!   saved earlier.
+//!
+//! Assets are saved in small text files next to each other (see [`ASSET_EXTENSION`]), similar to
+//! picoCAD projects:
+//!
+//! ```text
+//! asset;barrel;props
+//! {
+//!  name='barrel', pos={0,0,0}, rot={0,0,0},
+//!  ...
+//! }
+//! %
+//! 3;2
+//! 88.
+//! 899
+//! ```
+//!
+//! The first line holds the name of the asset and the project it was taken from, followed by the
+//! mesh table and the size of the texture part.
+//! Each pixel of the texture part is written as a hex digit like in the footer of a project,
+//! pixels no face uses are written as `.`.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Mesh, Model};
+//! use picocadrs::library::Asset;
+//!
+//! let mut props = Model::new_named("props".to_string());
+//! props.meshes.push(Mesh::plane());
+//!
+//! let asset = props.extract_asset(0).unwrap();
+//! assert_eq!(asset.source, "props");
+//!
+//! let mut scene = Model::new_named("scene".to_string());
+//! let index = scene.insert_asset(&asset).unwrap();
+//!
+//! assert_eq!(scene.meshes[index].name, "plane");
+//! assert_eq!(asset.to_string().parse::<Asset>().unwrap(), asset);
+//! ```
+
+use crate::{
+    assets::{Color, Footer, Mesh, Model, Point2D, Point3D, Rect},
+    atlas,
+    error::PicoError,
+    files::ProjectDir,
+    point,
+};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Extension of files assets are saved in.
+pub const ASSET_EXTENSION: &str = "asset";
+/// First field of the first line of an asset file.
+pub const ASSET_IDENTIFIER: &str = "asset";
+
+/// Settings used by [`import_mesh`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportOptions {
+    /// New name of the copied mesh, [`None`] keeps its name.
+    pub name: Option<String>,
+    /// New position of the copied mesh, [`None`] keeps its position.
+    pub position: Option<Point3D<f64>>,
+    /// If true and the texture has no space left, it is compacted (see
+    /// [`Model::compact_texture`]) before trying again.
+    pub compact: bool,
+}
+
+/// A mesh together with the part of the texture it uses.
+///
+/// The uv-coordinates of the mesh are relative to the top left pixel of the texture part.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    /// Name of the asset, the same as the name of its mesh when extracted.
+    pub name: String,
+    /// Name of the project the asset was extracted from.
+    pub source: String,
+    /// The mesh itself.
+    pub mesh: Mesh,
+    /// Width of the texture part in pixels.
+    pub width: usize,
+    /// Height of the texture part in pixels.
+    pub height: usize,
+    /// Pixels of the texture part line by line, [`None`] for pixels no face uses.
+    pub pixels: Vec<Option<Color>>,
+}
+
+impl Asset {
+    /// Returns the color of the pixel at the given coordinates of the texture part.
+    ///
+    /// Returns [`None`] if no face uses the pixel or if it is outside of the texture part.
+    pub fn pixel(&self, coords: Point2D<usize>) -> Option<Color> {
+        if coords.u >= self.width || coords.v >= self.height {
+            return None;
+        }
+
+        self.pixels
+            .get(coords.v * self.width + coords.u)
+            .copied()
+            .flatten()
+    }
+
+    /// Returns the coordinates of all pixels of the texture part that are used, line by line.
+    pub fn used_pixels(&self) -> Vec<Point2D<usize>> {
+        self.pixels
+            .iter()
+            .enumerate()
+            .filter(|(_, pixel)| pixel.is_some())
+            .map(|(i, _)| point!(i % self.width, i / self.width))
+            .collect()
+    }
+
+    /// Checks if both assets hold the same mesh geometry and texture, regardless of their names,
+    /// where they came from and where the mesh is placed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model};
+    ///
+    /// let mut model = Model::new_named("props".to_string());
+    /// model.meshes.push(Mesh::plane());
+    /// model.meshes.push(Mesh::plane());
+    /// model.meshes[1].name = "floor".to_string();
+    ///
+    /// let plane = model.extract_asset(0).unwrap();
+    /// let floor = model.extract_asset(1).unwrap();
+    ///
+    /// assert!(plane.same_content(&floor));
+    /// ```
+    pub fn same_content(&self, other: &Asset) -> bool {
+        self.mesh.shape_hash() == other.mesh.shape_hash()
+            && self.width == other.width
+            && self.height == other.height
+            && self.pixels == other.pixels
+    }
+
+    /// Loads an asset from the file at `path`.
+    ///
+    /// Returns an error if the file can't be read or isn't an asset.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Asset, PicoError> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Writes the asset into the file at `path`, replacing it if it exists already.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), PicoError> {
+        std::fs::write(path, self.to_string())?;
+        Ok(())
+    }
+}
+
+impl Display for Asset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{};{};{}\n{}\n%\n{};{}",
+            ASSET_IDENTIFIER, self.name, self.source, self.mesh, self.width, self.height
+        )?;
+
+        for row in self.pixels.chunks(self.width.max(1)) {
+            let line: String = row
+                .iter()
+                .map(|pixel| pixel.map_or('.', |color| color.as_char()))
+                .collect();
+
+            write!(f, "\n{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Asset {
+    type Err = PicoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first_line, rest) = s
+            .split_once('\n')
+            .ok_or(PicoError::Asset("missing mesh".to_string()))?;
+
+        let fields: Vec<&str> = first_line.trim_end_matches('\r').split(';').collect();
+        if fields.len() != 3 || fields[0] != ASSET_IDENTIFIER {
+            return Err(PicoError::Asset(format!(
+                "invalid first line {}",
+                first_line
+            )));
+        }
+
+        let (mesh_str, texture_str) = rest
+            .rsplit_once('%')
+            .ok_or(PicoError::Asset("missing texture".to_string()))?;
+        let mesh: Mesh = mesh_str.trim().parse()?;
+
+        let mut lines = texture_str
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty());
+
+        let size = lines
+            .next()
+            .ok_or(PicoError::Asset("missing texture size".to_string()))?;
+        let (width, height) = size
+            .split_once(';')
+            .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+            .ok_or(PicoError::Asset(format!("invalid texture size {}", size)))?;
+
+        let mut pixels: Vec<Option<Color>> = Vec::with_capacity(width * height);
+        for _ in 0..height {
+            let line = lines
+                .next()
+                .ok_or(PicoError::Asset("missing texture line".to_string()))?;
+
+            if line.chars().count() != width {
+                return Err(PicoError::Asset(format!("invalid texture line {}", line)));
+            }
+
+            pixels.extend(line.chars().map(|c| match c {
+                '.' => None,
+                _ => Some(Color::from(c)),
+            }));
+        }
+
+        Ok(Asset {
+            name: fields[1].to_string(),
+            source: fields[2].to_string(),
+            mesh,
+            width,
+            height,
+            pixels,
+        })
+    }
+}
+
+/// A collection of assets.
+///
+/// # Example
+///
+/// ```no_run
+/// use picocadrs::assets::Model;
+/// use picocadrs::library::Library;
+///
+/// let library = Library::scan("community_models").unwrap();
+/// library.save("assets").unwrap();
+///
+/// let mut model = Model::new_named("forest".to_string());
+/// let tree = Library::load("assets").unwrap().get("tree").unwrap().clone();
+///
+/// for _ in 0..3 {
+///     model.insert_asset(&tree).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Library {
+    /// Assets of the library in the order they were added.
+    pub assets: Vec<Asset>,
+}
+
+impl Library {
+    /// Creates an empty library.
+    pub fn new() -> Library {
+        Library::default()
+    }
+
+    /// Extracts every mesh of every project in the folder at `dir`.
+    ///
+    /// Projects are read in alphabetical order and projects that can't be loaded are skipped.
+    /// Meshes that were already found in an earlier project are only added once (see
+    /// [`Library::insert`]).
+    /// Returns an error if the folder can't be read.
+    pub fn scan<P: AsRef<Path>>(dir: P) -> Result<Library, PicoError> {
+        let mut library = Library::new();
+
+        for (_, model) in ProjectDir::new(dir).iter()? {
+            let Ok(model) = model else {
+                continue;
+            };
+
+            for mesh_index in 0..model.meshes.len() {
+                if let Some(asset) = extract(&model, mesh_index) {
+                    library.insert(asset);
+                }
+            }
+        }
+
+        Ok(library)
+    }
+
+    /// Loads every asset file in the folder at `dir` in alphabetical order.
+    ///
+    /// Returns an error if the folder or any asset file can't be read.
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<Library, PicoError> {
+        let mut paths: Vec<PathBuf> = vec![];
+
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path.is_file() && path.extension().and_then(|e| e.to_str()) == Some(ASSET_EXTENSION)
+            {
+                paths.push(path);
+            }
+        }
+
+        paths.sort();
+
+        Ok(Library {
+            assets: paths
+                .iter()
+                .map(Asset::load)
+                .collect::<Result<Vec<Asset>, PicoError>>()?,
+        })
+    }
+
+    /// Writes every asset into its own file inside of the folder at `dir` and returns the paths
+    /// of the written files.
+    ///
+    /// Files are called `{source}_{name}.asset`, with a number added if multiple assets share
+    /// the same name and source.
+    /// The folder is created if it doesn't exist.
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> Result<Vec<PathBuf>, PicoError> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+
+        let mut paths: Vec<PathBuf> = vec![];
+
+        for asset in self.assets.iter() {
+            let stem = if asset.source.is_empty() {
+                asset.name.clone()
+            } else {
+                format!("{}_{}", asset.source, asset.name)
+            };
+
+            let mut path = dir.join(format!("{}.{}", stem, ASSET_EXTENSION));
+            let mut index = 1;
+            while paths.contains(&path) {
+                path = dir.join(format!("{}.{}.{}", stem, index, ASSET_EXTENSION));
+                index += 1;
+            }
+
+            asset.write(&path)?;
+            paths.push(path);
+        }
+
+        Ok(paths)
+    }
+
+    /// Adds `asset` to the library.
+    ///
+    /// Returns `false` and leaves the library untouched if it already holds an asset with the
+    /// [`same content`](Asset::same_content).
+    pub fn insert(&mut self, asset: Asset) -> bool {
+        if self.assets.iter().any(|other| other.same_content(&asset)) {
+            return false;
+        }
+
+        self.assets.push(asset);
+        true
+    }
+
+    /// Returns the first asset called `name`.
+    pub fn get(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+
+    /// Returns an iterator over all assets.
+    pub fn iter(&self) -> impl Iterator<Item = &Asset> {
+        self.assets.iter()
+    }
+
+    /// Amount of assets in the library.
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    /// Returns `true` if the library holds no assets.
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+}
+
+/// Turns the mesh at `mesh_index` into an asset, using the name of the model as its source.
+///
+/// Only pixels sampled by textured faces of the mesh are part of the asset, see
+/// [`Face::uv_texels`](crate::assets::Face::uv_texels).
+/// Returns [`None`] if there is no mesh at that index.
+///
+/// It's recommended to use [`Model::extract_asset`].
+pub fn extract(model: &Model, mesh_index: usize) -> Option<Asset> {
+    let mut mesh = model.meshes.get(mesh_index)?.clone();
+
+    let texels: Vec<Point2D<usize>> = mesh
+        .faces
+        .iter()
+        .filter(|face| !face.no_texture)
+        .flat_map(|face| face.uv_texels())
+        .collect();
+
+    let (mut width, mut height) = (0, 0);
+    let mut pixels: Vec<Option<Color>> = vec![];
+
+    if let Some(bounds) = Rect::from_texels(texels.iter().copied()) {
+        let min = bounds.position;
+        (width, height) = (bounds.size.u, bounds.size.v);
+        pixels = vec![None; width * height];
+
+        for texel in texels {
+            pixels[(texel.v - min.v) * width + texel.u - min.u] = Some(model.footer.pixel(texel));
+        }
+
+        move_uvs(&mut mesh, -(min.u as f64), -(min.v as f64));
+    }
+
+    Some(Asset {
+        name: mesh.name.clone(),
+        source: model.header.name.clone(),
+        mesh,
+        width,
+        height,
+        pixels,
+    })
+}
+
+/// Adds the mesh of `asset` to the model and returns its index.
+///
+/// The pixels of the asset are copied into the topmost, then leftmost free space of the texture,
+/// where no face of the model samples any pixel, and the uv-coordinates of the mesh are moved
+/// along.
+/// Returns [`PicoError::TextureFull`] if there is no space large enough.
+///
+/// It's recommended to use [`Model::insert_asset`].
+pub fn instantiate(model: &mut Model, asset: &Asset) -> Result<usize, PicoError> {
+    let mut mesh = asset.mesh.clone();
+    let used = asset.used_pixels();
+
+    if !used.is_empty() {
+        if asset.width > Footer::WIDTH || asset.height > Footer::HEIGHT {
+            return Err(PicoError::TextureFull(asset.name.clone()));
+        }
+
+        let usage = model.texture_usage_map();
+        let mut occupied = vec![false; Footer::WIDTH * Footer::HEIGHT];
+        for v in 0..Footer::HEIGHT {
+            for u in 0..Footer::WIDTH {
+                occupied[v * Footer::WIDTH + u] = usage.is_used(point!(u, v));
+            }
+        }
+
+        let (du, dv) = atlas::free_spot(&occupied, &used)
+            .ok_or_else(|| PicoError::TextureFull(asset.name.clone()))?;
+
+        for pixel in used {
+            if let Some(color) = asset.pixel(pixel) {
+                let target = point!(pixel.u + du as usize, pixel.v + dv as usize);
+
+                if let Some(target) = model.footer.get_mut(target) {
+                    *target = color;
+                }
+            }
+        }
+
+        move_uvs(&mut mesh, du as f64, dv as f64);
+    }
+
+    model.meshes.push(mesh);
+    Ok(model.meshes.len() - 1)
+}
+
+/// Copies the first mesh called `mesh_name` of `other` into the model and returns its index.
+///
+/// Only the pixels sampled by the mesh are copied, see [`instantiate`] for how they are placed.
+/// Returns [`PicoError::MeshNotFound`] if `other` has no such mesh and
+/// [`PicoError::TextureFull`] if there is not enough free space on the texture.
+///
+/// It's recommended to use [`Model::import_mesh_from`].
+pub fn import_mesh(
+    model: &mut Model,
+    other: &Model,
+    mesh_name: &str,
+    options: ImportOptions,
+) -> Result<usize, PicoError> {
+    let mut asset = other
+        .meshes
+        .iter()
+        .position(|mesh| mesh.name == mesh_name)
+        .and_then(|mesh_index| extract(other, mesh_index))
+        .ok_or_else(|| PicoError::MeshNotFound(mesh_name.to_string()))?;
+
+    if let Some(name) = options.name {
+        asset.mesh.name = name.clone();
+        asset.name = name;
+    }
+    if let Some(position) = options.position {
+        asset.mesh.position = position;
+    }
+
+    match instantiate(model, &asset) {
+        Err(PicoError::TextureFull(_)) if options.compact => {
+            atlas::compact_texture(model);
+            instantiate(model, &asset)
+        }
+        result => result,
+    }
+}
+
+/// Moves all uv-coordinates of the mesh by the given amount of pixels.
+fn move_uvs(mesh: &mut Mesh, du: f64, dv: f64) {
+    for uv_map in mesh
+        .faces
+        .iter_mut()
+        .flat_map(|face| face.uv_maps.iter_mut())
+    {
+        uv_map.coords.u += du / 8.0;
+        uv_map.coords.v += dv / 8.0;
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Face, UVMap};
+
+    /// A model with a single mesh whose face uses the 2x2 pixels starting at `(u, v)`.
+    fn prop(name: &str, u: f64, v: f64, color: Color) -> Model {
+        let mut mesh = Mesh::plane();
+        mesh.name = name.to_string();
+        mesh.faces = vec![Face {
+            uv_maps: (0..4)
+                .zip([(0.0, 0.0), (0.25, 0.0), (0.25, 0.25), (0.0, 0.25)])
+                .map(|(i, (du, dv))| UVMap::new(i, point!(u + du, v + dv)))
+                .collect(),
+            ..Face::default()
+        }];
+
+        let mut model = Model::new_named("props".to_string());
+        for texel in mesh.faces[0].uv_texels() {
+            model.footer.set(texel, color).unwrap();
+        }
+        model.meshes.push(mesh);
+
+        model
+    }
+
+    #[test]
+    fn library_extract_and_instantiate() {
+        let model = prop("barrel", 2.0, 1.0, Color::Orange);
+        let asset = model.extract_asset(0).unwrap();
+
+        assert_eq!((asset.width, asset.height), (2, 2));
+        assert_eq!(asset.pixel(point!(1, 1)), Some(Color::Orange));
+        assert_eq!(
+            asset.mesh.faces[0].uv_maps[0].coords,
+            point!(0.0, 0.0),
+            "uv-coordinates are relative to the texture part"
+        );
+        assert!(model.extract_asset(1).is_none());
+
+        // the top left corner is taken, so the asset is placed right next to it.
+        let mut scene = prop("crate", 0.0, 0.0, Color::Brown);
+        let index = scene.insert_asset(&asset).unwrap();
+
+        assert_eq!(index, 1);
+        assert_eq!(
+            scene.meshes[1].faces[0].uv_maps[0].coords,
+            point!(0.25, 0.0)
+        );
+        assert_eq!(scene.footer.pixel(point!(1, 1)), Color::Brown);
+        assert_eq!(scene.footer.pixel(point!(2, 0)), Color::Orange);
+        assert_eq!(scene.footer.pixel(point!(3, 1)), Color::Orange);
+    }
+
+    #[test]
+    fn library_texture_full() {
+        let mut full = prop("wall", 0.0, 0.0, Color::Red);
+        full.meshes[0].faces[0].uv_maps = vec![
+            UVMap::new(0, point!(0.0, 0.0)),
+            UVMap::new(1, point!(16.0, 0.0)),
+            UVMap::new(2, point!(16.0, 15.0)),
+            UVMap::new(3, point!(0.0, 15.0)),
+        ];
+
+        let asset = prop("barrel", 0.0, 0.0, Color::Orange)
+            .extract_asset(0)
+            .unwrap();
+
+        assert!(matches!(
+            full.insert_asset(&asset),
+            Err(PicoError::TextureFull(name)) if name == "barrel"
+        ));
+        assert_eq!(full.meshes.len(), 1);
+    }
+
+    #[test]
+    fn library_import_mesh() {
+        let props = prop("barrel", 3.0, 3.0, Color::Orange);
+        let mut scene = prop("crate", 0.0, 0.0, Color::Brown);
+
+        let index = scene
+            .import_mesh_from(
+                &props,
+                "barrel",
+                ImportOptions {
+                    name: Some("barrel_2".to_string()),
+                    position: Some(point!(1.0, 0.0, 1.0)),
+                    ..ImportOptions::default()
+                },
+            )
+            .unwrap();
+
+        let mesh = &scene.meshes[index];
+        assert_eq!(mesh.name, "barrel_2");
+        assert_eq!(mesh.position, point!(1.0, 0.0, 1.0));
+        assert_eq!(mesh.faces[0].uv_maps[0].coords, point!(0.25, 0.0));
+        assert_eq!(scene.footer.pixel(point!(2, 1)), Color::Orange);
+        // pixels the mesh doesn't use stay behind.
+        assert_eq!(scene.footer.pixel(point!(4, 0)), Color::Black);
+
+        assert!(matches!(
+            scene.import_mesh_from(&props, "tree", ImportOptions::default()),
+            Err(PicoError::MeshNotFound(name)) if name == "tree"
+        ));
+    }
+
+    #[test]
+    fn library_import_mesh_compact() {
+        // only the first and last line of the texture are free, so nothing taller fits.
+        let mut scene = prop("wall", 0.0, 0.0, Color::Brown);
+        scene.meshes[0].faces[0].uv_maps = vec![
+            UVMap::new(0, point!(0.0, 0.125)),
+            UVMap::new(1, point!(16.0, 0.125)),
+            UVMap::new(2, point!(16.0, 14.875)),
+            UVMap::new(3, point!(0.0, 14.875)),
+        ];
+
+        let props = prop("barrel", 0.0, 0.0, Color::Orange);
+
+        assert!(matches!(
+            scene.import_mesh_from(&props, "barrel", ImportOptions::default()),
+            Err(PicoError::TextureFull(_))
+        ));
+
+        let options = ImportOptions {
+            compact: true,
+            ..ImportOptions::default()
+        };
+        assert_eq!(
+            scene.import_mesh_from(&props, "barrel", options).unwrap(),
+            1
+        );
+        assert_eq!(
+            scene.meshes[1].faces[0].uv_maps[0].coords,
+            point!(0.0, 14.75)
+        );
+    }
+
+    #[test]
+    fn library_asset_file() {
+        let asset = prop("barrel", 0.5, 0.0, Color::Orange)
+            .extract_asset(0)
+            .unwrap();
+        let text = asset.to_string();
+
+        assert!(text.starts_with("asset;barrel;props\n{"));
+        assert!(text.ends_with("%\n2;2\n99\n99"));
+        assert_eq!(text.parse::<Asset>().unwrap(), asset);
+
+        assert!(matches!(
+            "picocad;barrel;props\n{}\n%\n0;0".parse::<Asset>(),
+            Err(PicoError::Asset(_))
+        ));
+        assert!("asset;barrel;props\n{}\n%\n2;1\n9"
+            .parse::<Asset>()
+            .is_err());
+    }
+
+    #[test]
+    fn library_scan_save_load() {
+        let dir = std::env::temp_dir().join("picocadrs_library_scan_save_load");
+        let _ = std::fs::remove_dir_all(&dir);
+        let projects = ProjectDir::new(dir.join("projects"));
+        std::fs::create_dir_all(projects.path()).unwrap();
+
+        projects
+            .save_as(&prop("barrel", 0.0, 0.0, Color::Orange), "a", false)
+            .unwrap();
+        projects
+            .save_as(&prop("barrel", 4.0, 4.0, Color::Orange), "b", false)
+            .unwrap();
+        projects
+            .save_as(&prop("tree", 0.0, 0.0, Color::Green), "c", false)
+            .unwrap();
+        std::fs::write(projects.project_path("broken"), "not a project").unwrap();
+
+        let library = Library::scan(projects.path()).unwrap();
+
+        // the barrel of "b" only uses other pixels, so it is the same asset.
+        assert_eq!(library.len(), 2);
+        assert_eq!(library.get("barrel").unwrap().source, "a");
+        assert_eq!(library.get("tree").unwrap().source, "c");
+
+        let paths = library.save(dir.join("assets")).unwrap();
+        assert_eq!(paths[0], dir.join("assets").join("a_barrel.asset"));
+        assert_eq!(Library::load(dir.join("assets")).unwrap(), library);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,623 @@
+//! Typed representation of lua values that doesn't depend on a running lua instance.
+//!
+//! picoCAD stores meshes and faces as lua tables.
+//! [`LuaValue`] can hold any value found in these tables (apart from functions and the like),
+//! so it can be kept around after the lua instance used for parsing is gone.
+//! Its [`Display`] implementation writes the value back as lua source code.
+//!
+//! Meshes and faces can be converted into and from [`LuaTable`]s (see
+//! [`Mesh::to_lua_table`](crate::assets::Mesh::to_lua_table)), which allows inspecting or
+//! transforming the raw tables of a project without dealing with the lifetimes of a lua instance.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::lua_table::{LuaTable, LuaValue};
+//!
+//! let mut table = LuaTable::default();
+//! table.sequence.push(LuaValue::Integer(1));
+//! table.fields.insert("name".to_string(), LuaValue::String("cube".to_string()));
+//!
+//! assert_eq!(LuaValue::Table(table).to_string(), "{1,name='cube'}");
+//! ```
+
+use crate::error::PicoError;
+#[cfg(not(feature = "std"))]
+use crate::float::Float;
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+#[cfg(feature = "std")]
+use rlua::Value;
+
+/// Maximum depth tables can be nested in.
+/// picoCAD itself never goes deeper than 4, the limit keeps broken or malicious files from
+/// overflowing the stack, including tables that contain themselves.
+pub const MAX_DEPTH: usize = 64;
+
+/// A lua value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LuaValue {
+    Nil,
+    Boolean(bool),
+    Integer(i64),
+    Number(f64),
+    String(String),
+    Table(LuaTable),
+}
+
+/// A lua table split into its sequence (the values at keys `1, 2, 3, ...`) and its other fields.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LuaTable {
+    /// Values stored at keys `1` to `n` in order.
+    pub sequence: Vec<LuaValue>,
+    /// Values stored at string keys.
+    pub fields: BTreeMap<String, LuaValue>,
+    /// Values stored at any other key, like numbers that are not part of the sequence.
+    pub other: Vec<(LuaValue, LuaValue)>,
+}
+
+impl LuaValue {
+    /// Returns the value as a [`f64`] if it is a number.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::lua_table::LuaValue;
+    ///
+    /// assert_eq!(LuaValue::Integer(2).as_f64(), Some(2.0));
+    /// assert_eq!(LuaValue::Number(0.5).as_f64(), Some(0.5));
+    /// assert_eq!(LuaValue::Nil.as_f64(), None);
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            LuaValue::Integer(integer) => Some(*integer as f64),
+            LuaValue::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an [`i64`] if it is a number without a fractional part.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            LuaValue::Integer(integer) => Some(*integer),
+            LuaValue::Number(number) if number.fract() == 0.0 => Some(*number as i64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a string slice if it is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            LuaValue::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a [`LuaTable`] if it is a table.
+    pub fn as_table(&self) -> Option<&LuaTable> {
+        match self {
+            LuaValue::Table(table) => Some(table),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl TryFrom<Value<'_>> for LuaValue {
+    type Error = PicoError;
+
+    /// Converts a value of a running lua instance.
+    ///
+    /// Fails with [`PicoError::LuaValue`] for values that can't be written as lua source code,
+    /// like functions.
+    fn try_from(value: Value<'_>) -> Result<Self, Self::Error> {
+        LuaValue::from_lua(value, 0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl LuaValue {
+    /// Converts a value of a running lua instance that is nested `depth` tables deep.
+    ///
+    /// Fails with [`PicoError::LuaSyntax`] once tables are nested deeper than [`MAX_DEPTH`].
+    fn from_lua(value: Value<'_>, depth: usize) -> Result<Self, PicoError> {
+        Ok(match value {
+            Value::Nil => LuaValue::Nil,
+            Value::Boolean(boolean) => LuaValue::Boolean(boolean),
+            Value::Integer(integer) => LuaValue::Integer(integer),
+            Value::Number(number) => LuaValue::Number(number),
+            Value::String(string) => LuaValue::String(string.to_str()?.to_string()),
+            Value::Table(table) => {
+                if depth >= MAX_DEPTH {
+                    return Err(PicoError::LuaSyntax("tables nested too deeply".to_string()));
+                }
+
+                let mut lua_table = LuaTable::default();
+                let length = table.len()?;
+
+                for i in 1..=length {
+                    lua_table
+                        .sequence
+                        .push(LuaValue::from_lua(table.get::<_, Value>(i)?, depth + 1)?);
+                }
+
+                for pair in table.pairs::<Value, Value>() {
+                    let (key, value) = pair?;
+
+                    match key {
+                        Value::Integer(i) if i >= 1 && i <= length => {}
+                        Value::String(key) => {
+                            lua_table.fields.insert(
+                                key.to_str()?.to_string(),
+                                LuaValue::from_lua(value, depth + 1)?,
+                            );
+                        }
+                        key => lua_table.other.push((
+                            LuaValue::from_lua(key, depth + 1)?,
+                            LuaValue::from_lua(value, depth + 1)?,
+                        )),
+                    }
+                }
+
+                LuaValue::Table(lua_table)
+            }
+            other => return Err(PicoError::LuaValue(other.type_name().to_string())),
+        })
+    }
+}
+
+impl FromStr for LuaValue {
+    type Err = PicoError;
+
+    /// Reads a lua value written as a literal using [`parse`].
+    ///
+    /// The string is never run as lua code, so reading a project file can't execute commands,
+    /// touch files or loop forever.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::lua_table::LuaValue;
+    ///
+    /// let value: LuaValue = "{1, 2, name='cube'}".parse().unwrap();
+    /// let table = value.as_table().unwrap();
+    ///
+    /// assert_eq!(table.sequence.len(), 2);
+    /// assert_eq!(table.fields["name"].as_str(), Some("cube"));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s)
+    }
+}
+
+impl Display for LuaValue {
+    /// Writes the value as a lua literal.
+    ///
+    /// Numbers are written without losing precision, whole floats keep their `.0` so they are
+    /// read back as [`LuaValue::Number`].
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LuaValue::Nil => write!(f, "nil"),
+            LuaValue::Boolean(boolean) => write!(f, "{}", boolean),
+            LuaValue::Integer(integer) => write!(f, "{}", integer),
+            LuaValue::Number(number) if number.fract() == 0.0 => write!(f, "{:.1}", number),
+            LuaValue::Number(number) => write!(f, "{}", number),
+            LuaValue::String(string) => write!(f, "'{}'", escape(string)),
+            LuaValue::Table(table) => write!(f, "{}", table),
+        }
+    }
+}
+
+impl Display for LuaTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut entries: Vec<String> = self.sequence.iter().map(|v| v.to_string()).collect();
+
+        for (key, value) in self.fields.iter() {
+            entries.push(field(key, value));
+        }
+
+        for (key, value) in self.other.iter() {
+            entries.push(format!("[{}]={}", key, value));
+        }
+
+        write!(f, "{{{}}}", entries.join(","))
+    }
+}
+
+/// Parses a lua value written as a literal, like the tables picoCAD stores its meshes in.
+///
+/// This doesn't need a lua instance and only understands `nil`, booleans, numbers (also negative and hexadecimal ones), strings, tables and comments.
+/// Any other expression, like `1 + 1` or a variable, fails with [`PicoError::LuaSyntax`].
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::lua_table::{parse, LuaValue};
+///
+/// let value = parse("{ name='cube', pos={0,-0.5,0x10}, [2]=true }").unwrap();
+/// let table = value.as_table().unwrap();
+///
+/// assert_eq!(table.fields["name"], LuaValue::String("cube".to_string()));
+/// assert_eq!(
+///     table.fields["pos"].as_table().unwrap().sequence,
+///     vec![LuaValue::Integer(0), LuaValue::Number(-0.5), LuaValue::Integer(16)]
+/// );
+/// assert_eq!(table.other, vec![(LuaValue::Integer(2), LuaValue::Boolean(true))]);
+///
+/// assert!(parse("{1 + 1}").is_err());
+/// ```
+pub fn parse(source: &str) -> Result<LuaValue, PicoError> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        position: 0,
+        depth: 0,
+    };
+
+    let value = parser.value()?;
+    parser.skip_whitespace();
+
+    if parser.position < parser.chars.len() {
+        return Err(parser.error("expected end of input"));
+    }
+
+    Ok(value)
+}
+
+/// Parses a lua table written as a literal using [`parse`].
+pub(crate) fn parse_table(source: &str) -> Result<LuaTable, PicoError> {
+    match parse(source)? {
+        LuaValue::Table(table) => Ok(table),
+        _ => Err(PicoError::LuaSyntax("expected a table".to_string())),
+    }
+}
+
+/// Reads lua literals character by character.
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+    /// Amount of tables the parser is currently inside of.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn error(&self, message: &str) -> PicoError {
+        PicoError::LuaSyntax(format!("{} at character {}", message, self.position))
+    }
+
+    /// Skips whitespace and comments.
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => self.position += 1,
+                Some('-') if self.chars.get(self.position + 1) == Some(&'-') => {
+                    while !matches!(self.peek(), Some('\n') | None) {
+                        self.position += 1;
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Skips whitespace and consumes `expected` if it comes next.
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self) -> Result<LuaValue, PicoError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('{') => {
+                if self.depth >= MAX_DEPTH {
+                    return Err(self.error("tables nested too deeply"));
+                }
+
+                self.depth += 1;
+                let table = self.table();
+                self.depth -= 1;
+
+                table
+            }
+            Some('\'') | Some('"') => Ok(LuaValue::String(self.string()?)),
+            Some(c) if c == '-' || c == '.' || c.is_ascii_digit() => self.number(),
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => match self.name().as_str() {
+                "nil" => Ok(LuaValue::Nil),
+                "true" => Ok(LuaValue::Boolean(true)),
+                "false" => Ok(LuaValue::Boolean(false)),
+                _ => Err(self.error("expected a literal")),
+            },
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    fn name(&mut self) -> String {
+        let start = self.position;
+
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '_') {
+            self.position += 1;
+        }
+
+        self.chars[start..self.position].iter().collect()
+    }
+
+    fn number(&mut self) -> Result<LuaValue, PicoError> {
+        let negative = self.peek() == Some('-');
+        if negative {
+            self.position += 1;
+            self.skip_whitespace();
+        }
+
+        let start = self.position;
+        while matches!(
+            self.peek(),
+            Some(c) if c.is_ascii_alphanumeric() || c == '.'
+                || (matches!(c, '+' | '-') && matches!(self.chars[self.position - 1], 'e' | 'E'))
+        ) {
+            self.position += 1;
+        }
+        let literal: String = self.chars[start..self.position].iter().collect();
+
+        let value = if let Some(hex) = literal
+            .strip_prefix("0x")
+            .or_else(|| literal.strip_prefix("0X"))
+        {
+            i64::from_str_radix(hex, 16)
+                .map(LuaValue::Integer)
+                .map_err(|_| self.error("invalid number"))?
+        } else if let Ok(integer) = literal.parse::<i64>() {
+            LuaValue::Integer(integer)
+        } else {
+            literal
+                .parse::<f64>()
+                .map(LuaValue::Number)
+                .map_err(|_| self.error("invalid number"))?
+        };
+
+        Ok(match (negative, value) {
+            (true, LuaValue::Integer(integer)) => LuaValue::Integer(-integer),
+            (true, LuaValue::Number(number)) => LuaValue::Number(-number),
+            (_, value) => value,
+        })
+    }
+
+    fn string(&mut self) -> Result<String, PicoError> {
+        let quote = self.chars[self.position];
+        self.position += 1;
+        let mut string = String::new();
+
+        loop {
+            match self.peek() {
+                None | Some('\n') => return Err(self.error("unfinished string")),
+                Some(c) if c == quote => {
+                    self.position += 1;
+                    return Ok(string);
+                }
+                Some('\\') => {
+                    self.position += 1;
+
+                    let escaped = match self.peek() {
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some(c @ ('\\' | '\'' | '"')) => c,
+                        _ => return Err(self.error("unknown escape sequence")),
+                    };
+
+                    string.push(escaped);
+                    self.position += 1;
+                }
+                Some(c) => {
+                    string.push(c);
+                    self.position += 1;
+                }
+            }
+        }
+    }
+
+    fn table(&mut self) -> Result<LuaValue, PicoError> {
+        self.position += 1;
+        let mut table = LuaTable::default();
+
+        loop {
+            if self.eat('}') {
+                return Ok(LuaValue::Table(table));
+            }
+
+            self.skip_whitespace();
+            let start = self.position;
+
+            if self.eat('[') {
+                let key = self.value()?;
+                if !self.eat(']') || !self.eat('=') {
+                    return Err(self.error("expected ']='"));
+                }
+                let value = self.value()?;
+
+                match (key, value) {
+                    (_, LuaValue::Nil) => {}
+                    (LuaValue::String(key), value) => {
+                        table.fields.insert(key, value);
+                    }
+                    (key, value) => table.other.push((key, value)),
+                }
+            } else if matches!(self.peek(), Some(c) if c.is_ascii_alphabetic() || c == '_') {
+                let name = self.name();
+
+                if self.eat('=') {
+                    let value = self.value()?;
+                    if value != LuaValue::Nil {
+                        table.fields.insert(name, value);
+                    }
+                } else {
+                    // not a key, so it has to be a value like `true`.
+                    self.position = start;
+                    let value = self.value()?;
+                    table.sequence.push(value);
+                }
+            } else {
+                let value = self.value()?;
+                table.sequence.push(value);
+            }
+
+            if !self.eat(',') && !self.eat(';') {
+                if self.eat('}') {
+                    return Ok(LuaValue::Table(table));
+                }
+
+                return Err(self.error("expected ',' or '}'"));
+            }
+        }
+    }
+}
+
+/// Escapes a string so it can be put between single quotes.
+fn escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+
+    for c in string.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\'' => escaped.push_str("\\'"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Writes a field of a table as `key=value`, or as `['key']=value` if the key isn't an identifier.
+pub(crate) fn field(key: &str, value: &LuaValue) -> String {
+    if is_identifier(key) {
+        format!("{}={}", key, value)
+    } else {
+        format!("['{}']={}", escape(key), value)
+    }
+}
+
+/// Checks if `key` can be written as `key=value` instead of `['key']=value`.
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use rlua::Lua;
+
+    /// Evaluates `source` with lua, to compare the literal parser against.
+    fn eval(source: &str) -> LuaValue {
+        Lua::new()
+            .context(|ctx| LuaValue::try_from(ctx.load(source).eval::<Value>().unwrap()))
+            .unwrap()
+    }
+
+    #[test]
+    fn lua_value_round_trip() {
+        let source = "{1,2.5,'it\\'s',true,nil_key=false,name='a',['with space']={},[10]=3}";
+        let value = eval(source);
+
+        if let LuaValue::Table(table) = &value {
+            assert_eq!(table.sequence.len(), 4);
+            assert_eq!(table.fields.len(), 3);
+            assert_eq!(
+                table.other,
+                vec![(LuaValue::Integer(10), LuaValue::Integer(3))]
+            );
+        } else {
+            panic!("expected a table");
+        }
+
+        assert_eq!(super::parse(&value.to_string()).unwrap(), value);
+    }
+
+    #[test]
+    fn lua_value_numbers_exact() {
+        for number in [1e6, 2.0, -0.5, 0.1 + 0.2, 1e-7, 123456.789] {
+            let value = LuaValue::Number(number);
+
+            assert_eq!(super::parse(&value.to_string()).unwrap(), value);
+            assert_eq!(eval(&value.to_string()), value);
+        }
+
+        assert_eq!(LuaValue::Number(1e6).to_string(), "1000000.0");
+        assert_eq!(LuaValue::Integer(2).to_string(), "2");
+    }
+
+    #[test]
+    fn lua_parse_matches_lua() {
+        let sources = [
+            "{1,2.5,'it\\'s',true,nil_key=false,name='a',['with space']={},[10]=3}",
+            "{ -1, - 2, 0.5e1, 1E-2, .5, 0x1F, \"double \\\"quoted\\\"\" } -- comment",
+            "{\n a={ {1,2}, {3,4}; }, b=nil, [1.5]='x', t=true,\n}",
+            "'string'",
+            "-3.75",
+        ];
+
+        for source in sources {
+            assert_eq!(super::parse(source).unwrap(), eval(source), "{}", source);
+        }
+    }
+
+    #[test]
+    fn lua_parse_errors() {
+        for source in [
+            "{1 + 1}", "{a}", "{1,", "'open", "{[1]2}", "{} {}", "x", "{'\\q'}",
+        ] {
+            assert!(
+                matches!(super::parse(source), Err(PicoError::LuaSyntax(_))),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn lua_parse_never_runs_code() {
+        for source in [
+            "os.execute('touch pwned')",
+            "{os.execute('touch pwned')}",
+            "{io.open('pwned', 'w')}",
+            "(function() while true do end end)()",
+            "{(function() while true do end end)()}",
+        ] {
+            assert!(
+                matches!(source.parse::<LuaValue>(), Err(PicoError::LuaSyntax(_))),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    fn lua_value_functions() {
+        let result = Lua::new()
+            .context(|ctx| LuaValue::try_from(ctx.load("function() end").eval::<Value>().unwrap()));
+
+        assert!(matches!(result, Err(PicoError::LuaValue(_))));
+    }
+}
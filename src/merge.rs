@@ -0,0 +1,337 @@
+//! For combining changes two people made to the same model.
+//!
+//! [`merge3`] takes the version both started from (`base`) and the two changed versions (`ours`
+//! and `theirs`) and applies the changes of both to `base`.
+//! The header, every mesh and every pixel of the texture are merged on their own:
+//!
+//! - If only one side changed it, that change is kept.
+//! - If both sides made the same change, it is kept once.
+//! - If both sides changed it in different ways, a [`MergeConflict`] is reported.
+//!
+//! Meshes are matched the same way as in [`diff`](crate::diff), by their name.
+//! Changing a mesh counts as a change to the whole mesh, so two people moving different vertices
+//! of the same mesh conflict, while two people editing different meshes don't.
+//! Removing a mesh conflicts with any change the other side made to it.
+//!
+//! Meshes added by either side are kept, unless both added exactly the same mesh.
+//! The merged model lists its meshes in the order of `ours`, followed by the meshes only added by
+//! `theirs`.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Color, Mesh, Model, Point2D, Point3D};
+//! use picocadrs::point;
+//!
+//! let mut base = Model::default();
+//! base.meshes.push(Mesh::new("cube".to_string()));
+//!
+//! let mut ours = base.clone();
+//! ours.meshes[0].position = point!(1.0, 0.0, 0.0);
+//!
+//! let mut theirs = base.clone();
+//! theirs.meshes.push(Mesh::new("plane".to_string()));
+//! theirs.footer.set(point!(0, 0), Color::Red).unwrap();
+//!
+//! let merged = Model::merge3(&base, &ours, &theirs).unwrap();
+//!
+//! assert_eq!(merged.meshes.len(), 2);
+//! assert_eq!(merged.meshes[0].position, point!(1.0, 0.0, 0.0));
+//! assert_eq!(merged.meshes[1].name, "plane");
+//! assert_eq!(merged.footer.pixel(point!(0, 0)), Color::Red);
+//! ```
+
+use crate::{
+    assets::{Color, Footer, Header, Mesh, Model, Point2D},
+    diff::match_meshes,
+    point,
+};
+use std::fmt::{Display, Formatter};
+
+/// A part of the model both sides changed in different ways.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MergeConflict {
+    /// Both sides changed the header.
+    Header {
+        /// Header of `ours`.
+        ours: Header,
+        /// Header of `theirs`.
+        theirs: Header,
+    },
+    /// Both sides changed or removed the same mesh.
+    Mesh {
+        /// Name of the mesh.
+        name: String,
+        /// Index of the mesh in `base`.
+        base: usize,
+        /// Index of the mesh in `ours`, [`None`] if it was removed.
+        ours: Option<usize>,
+        /// Index of the mesh in `theirs`, [`None`] if it was removed.
+        theirs: Option<usize>,
+    },
+    /// Both sides painted the same pixel in different colors.
+    Pixel {
+        /// Position of the pixel.
+        coords: Point2D<usize>,
+        /// Color in `base`.
+        base: Color,
+        /// Color in `ours`.
+        ours: Color,
+        /// Color in `theirs`.
+        theirs: Color,
+    },
+}
+
+impl Display for MergeConflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let side = |index: &Option<usize>| match index {
+            Some(index) => format!("changed (mesh {})", index),
+            None => "removed".to_string(),
+        };
+
+        match self {
+            MergeConflict::Header { ours, theirs } => {
+                write!(f, "header changed on both sides: {} vs {}", ours, theirs)
+            }
+            MergeConflict::Mesh {
+                name,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "mesh {} {:?}: ours {}, theirs {}",
+                base,
+                name,
+                side(ours),
+                side(theirs)
+            ),
+            MergeConflict::Pixel {
+                coords,
+                base,
+                ours,
+                theirs,
+            } => write!(
+                f,
+                "pixel {}: {} changed to {} by ours and {} by theirs",
+                coords,
+                base.as_i32(),
+                ours.as_i32(),
+                theirs.as_i32()
+            ),
+        }
+    }
+}
+
+/// Applies the changes of `ours` and `theirs` to `base`.
+/// Returns every conflict found if both sides changed the same part of the model in different
+/// ways.
+///
+/// It's recommended to use [`Model::merge3`].
+pub fn merge3(base: &Model, ours: &Model, theirs: &Model) -> Result<Model, Vec<MergeConflict>> {
+    let mut conflicts: Vec<MergeConflict> = vec![];
+
+    let header = match pick(&base.header, &ours.header, &theirs.header) {
+        Some(header) => header.clone(),
+        None => {
+            conflicts.push(MergeConflict::Header {
+                ours: ours.header.clone(),
+                theirs: theirs.header.clone(),
+            });
+            base.header.clone()
+        }
+    };
+
+    let (ours_matches, ours_added, _) = match_meshes(&base.meshes, &ours.meshes);
+    let (theirs_matches, theirs_added, _) = match_meshes(&base.meshes, &theirs.meshes);
+    let counterpart = |matches: &[(usize, usize)], index: usize| {
+        matches
+            .iter()
+            .find(|(base_index, _)| *base_index == index)
+            .map(|(_, other_index)| *other_index)
+    };
+
+    // merged version of every mesh of base, None if it was removed.
+    let mut merged: Vec<Option<&Mesh>> = vec![];
+
+    for (index, mesh) in base.meshes.iter().enumerate() {
+        let ours_index = counterpart(&ours_matches, index);
+        let theirs_index = counterpart(&theirs_matches, index);
+
+        let result = pick(
+            Some(mesh),
+            ours_index.map(|i| &ours.meshes[i]),
+            theirs_index.map(|i| &theirs.meshes[i]),
+        );
+
+        match result {
+            Some(result) => merged.push(result),
+            None => {
+                conflicts.push(MergeConflict::Mesh {
+                    name: mesh.name.clone(),
+                    base: index,
+                    ours: ours_index,
+                    theirs: theirs_index,
+                });
+                merged.push(None);
+            }
+        }
+    }
+
+    let mut meshes: Vec<Mesh> = vec![];
+
+    // meshes removed by ours are either removed or conflicting, so ours decides the order.
+    for (index, mesh) in ours.meshes.iter().enumerate() {
+        match ours_matches
+            .iter()
+            .find(|(_, ours_index)| *ours_index == index)
+        {
+            Some((base_index, _)) => {
+                if let Some(mesh) = merged[*base_index] {
+                    meshes.push(mesh.clone());
+                }
+            }
+            None => meshes.push(mesh.clone()),
+        }
+    }
+
+    for index in theirs_added {
+        let mesh = &theirs.meshes[index];
+
+        if !ours_added.iter().any(|i| ours.meshes[*i] == *mesh) {
+            meshes.push(mesh.clone());
+        }
+    }
+
+    let mut footer = base.footer.clone();
+
+    for v in 0..Footer::HEIGHT {
+        for u in 0..Footer::WIDTH {
+            let coords = point!(u, v);
+            let colors = (
+                base.footer.pixel(coords),
+                ours.footer.pixel(coords),
+                theirs.footer.pixel(coords),
+            );
+
+            match pick(colors.0, colors.1, colors.2) {
+                Some(color) => {
+                    if let Some(pixel) = footer.get_mut(coords) {
+                        *pixel = color;
+                    }
+                }
+                None => conflicts.push(MergeConflict::Pixel {
+                    coords,
+                    base: colors.0,
+                    ours: colors.1,
+                    theirs: colors.2,
+                }),
+            }
+        }
+    }
+
+    if conflicts.is_empty() {
+        Ok(Model {
+            header,
+            meshes,
+            footer,
+        })
+    } else {
+        Err(conflicts)
+    }
+}
+
+/// Returns the version that should be kept after merging, or [`None`] if both sides changed
+/// `base` in different ways.
+fn pick<T: PartialEq>(base: T, ours: T, theirs: T) -> Option<T> {
+    if ours == base {
+        Some(theirs)
+    } else if theirs == base || ours == theirs {
+        Some(ours)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::Point3D;
+
+    fn base() -> Model {
+        let mut model = Model::default();
+        model.meshes.push(Mesh::new("body".to_string()));
+        model.meshes.push(Mesh::new("arm".to_string()));
+        model.meshes.push(Mesh::new("leg".to_string()));
+
+        model
+    }
+
+    #[test]
+    fn merge_non_conflicting() {
+        let base = base();
+
+        let mut ours = base.clone();
+        ours.meshes[0].position = point!(1.0, 0.0, 0.0);
+        ours.meshes.remove(2);
+        ours.meshes.push(Mesh::new("head".to_string()));
+        ours.footer.set(point!(1, 1), Color::Red).unwrap();
+
+        let mut theirs = base.clone();
+        theirs.meshes[1].vertices.push(point!(0.0, 1.0, 0.0));
+        theirs.meshes.push(Mesh::new("head".to_string()));
+        theirs.meshes.push(Mesh::new("tail".to_string()));
+        theirs.footer.set(point!(1, 1), Color::Red).unwrap();
+        theirs.footer.set(point!(2, 2), Color::Blue).unwrap();
+        theirs.header.name = "creature".to_string();
+
+        let merged = Model::merge3(&base, &ours, &theirs).unwrap();
+
+        let names: Vec<&str> = merged.meshes.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["body", "arm", "head", "tail"]);
+        assert_eq!(merged.meshes[0].position, point!(1.0, 0.0, 0.0));
+        assert_eq!(merged.meshes[1].vertices.len(), 1);
+        assert_eq!(merged.footer.pixel(point!(1, 1)), Color::Red);
+        assert_eq!(merged.footer.pixel(point!(2, 2)), Color::Blue);
+        assert_eq!(merged.header.name, "creature");
+    }
+
+    #[test]
+    fn merge_conflicts() {
+        let base = base();
+
+        let mut ours = base.clone();
+        ours.meshes[0].position = point!(1.0, 0.0, 0.0);
+        ours.meshes.remove(1);
+        ours.footer.set(point!(3, 4), Color::Red).unwrap();
+        ours.header.zoom = 8;
+
+        let mut theirs = base.clone();
+        theirs.meshes[0].position = point!(2.0, 0.0, 0.0);
+        theirs.meshes[1].position = point!(0.0, 1.0, 0.0);
+        theirs.footer.set(point!(3, 4), Color::Blue).unwrap();
+        theirs.header.zoom = 4;
+
+        let conflicts = Model::merge3(&base, &ours, &theirs).unwrap_err();
+
+        assert_eq!(conflicts.len(), 4);
+        assert!(matches!(conflicts[0], MergeConflict::Header { .. }));
+        assert_eq!(
+            conflicts[1],
+            MergeConflict::Mesh {
+                name: "body".to_string(),
+                base: 0,
+                ours: Some(0),
+                theirs: Some(0),
+            }
+        );
+        assert_eq!(
+            conflicts[2].to_string(),
+            "mesh 1 \"arm\": ours removed, theirs changed (mesh 1)"
+        );
+        assert_eq!(
+            conflicts[3].to_string(),
+            "pixel 3,4: 0 changed to 8 by ours and 12 by theirs"
+        );
+    }
+}
@@ -0,0 +1,464 @@
+//! For attaching information about where a project comes from to the project file itself.
+//!
+//! picoCAD has no place for things like the author or the license of a model, but it ignores
+//! fields at the end of the [`Header`](crate::assets::Header) line.
+//! [`Metadata`] is stored there as [`extensions`](crate::assets::Header::extensions) starting with
+//! `meta.`, like `picocad;house;16;1;0;meta.author=mzntori;meta.tags=props,low-poly`.
+//! Other extensions are left untouched.
+//!
+//! Characters that would break the header line (`;`, `,`, `%`, line breaks and whitespace at the
+//! end of a value) are written as `%` followed by their hex code, so any text survives a round
+//! trip.
+//! Saving the project in picoCAD itself drops the metadata.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::Model;
+//! use picocadrs::metadata::Metadata;
+//!
+//! let mut model = Model::new_named("house".to_string());
+//! model.set_metadata(&Metadata {
+//!     author: Some("mzntori".to_string()),
+//!     tags: vec!["props".to_string(), "low-poly".to_string()],
+//!     ..Metadata::default()
+//! });
+//!
+//! assert!(model
+//!     .to_string()
+//!     .starts_with("picocad;house;16;1;0;meta.author=mzntori;meta.tags=props,low-poly\n"));
+//!
+//! let loaded: Model = model.to_string().parse().unwrap();
+//! assert_eq!(loaded.metadata().author.as_deref(), Some("mzntori"));
+//! assert_eq!(loaded.metadata().tags, vec!["props", "low-poly"]);
+//! ```
+//!
+//! # Face tags
+//!
+//! Faces can be tagged with any text, like `window` or `emissive`, to mark them for later
+//! processing without keeping a separate file next to the project.
+//! The tags of a face are stored in its [`extras`](crate::assets::Face::extras) as a list of
+//! strings, like `tags={'window','glass'}`, so they stay with the face when meshes or faces are
+//! removed or reordered.
+//! Like the rest of the metadata, saving the project in picoCAD itself drops them.
+//!
+//! ```
+//! use picocadrs::assets::{MeshFaceRef, Model, ProjectPreset};
+//!
+//! let mut model = Model::new_project("house".to_string(), ProjectPreset::Plane);
+//! model.tag_face(0, 0, "window").unwrap();
+//!
+//! assert!(model.to_string().contains("tags={'window'}"));
+//!
+//! let loaded: Model = model.to_string().parse().unwrap();
+//! assert_eq!(loaded.faces_with_tag("window"), vec![MeshFaceRef::new(0, 0)]);
+//! ```
+
+use crate::{
+    assets::{Face, MeshFaceRef, Model},
+    error::PicoError,
+    lua_table::{LuaTable, LuaValue},
+};
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+
+/// Key of the author in the header extensions.
+const AUTHOR: &str = "meta.author";
+/// Key of the license in the header extensions.
+const LICENSE: &str = "meta.license";
+/// Key of the tags in the header extensions.
+const TAGS: &str = "meta.tags";
+/// Key of the tool version in the header extensions.
+const TOOL_VERSION: &str = "meta.tool";
+/// Key of the tags in the extras of a face.
+const FACE_TAGS: &str = "tags";
+
+/// Information about a project that picoCAD doesn't store on its own.
+///
+/// Everything is optional, fields that are [`None`] or empty aren't written to the file.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Metadata {
+    /// Who made the project.
+    pub author: Option<String>,
+    /// License the project is shared under, like `CC-BY-4.0`.
+    pub license: Option<String>,
+    /// Tags for sorting the project in galleries.
+    pub tags: Vec<String>,
+    /// Name and version of the tool that last wrote the project, like `picocadrs 1.0.3`.
+    pub tool_version: Option<String>,
+}
+
+impl Metadata {
+    /// Checks if no field is set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::metadata::Metadata;
+    ///
+    /// assert!(Metadata::default().is_empty());
+    /// assert!(!Metadata {
+    ///     tags: vec!["props".to_string()],
+    ///     ..Metadata::default()
+    /// }
+    /// .is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self == &Metadata::default()
+    }
+
+    /// Reads the metadata out of the extensions of a header.
+    /// Extensions that aren't metadata are ignored.
+    fn from_extensions(extensions: &[String]) -> Metadata {
+        let mut metadata = Metadata::default();
+
+        for extension in extensions {
+            let Some((key, value)) = extension.split_once('=') else {
+                continue;
+            };
+
+            match key {
+                AUTHOR => metadata.author = Some(decode(value)),
+                LICENSE => metadata.license = Some(decode(value)),
+                TAGS => metadata.tags = value.split(',').map(decode).collect(),
+                TOOL_VERSION => metadata.tool_version = Some(decode(value)),
+                _ => {}
+            }
+        }
+
+        metadata
+    }
+
+    /// Returns the header extensions the metadata is written as.
+    fn to_extensions(&self) -> Vec<String> {
+        let mut extensions: Vec<String> = Vec::new();
+
+        if let Some(author) = &self.author {
+            extensions.push(format!("{}={}", AUTHOR, encode(author)));
+        }
+        if let Some(license) = &self.license {
+            extensions.push(format!("{}={}", LICENSE, encode(license)));
+        }
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|tag| encode(tag)).collect();
+            extensions.push(format!("{}={}", TAGS, tags.join(",")));
+        }
+        if let Some(tool_version) = &self.tool_version {
+            extensions.push(format!("{}={}", TOOL_VERSION, encode(tool_version)));
+        }
+
+        extensions
+    }
+}
+
+/// Reads the metadata stored in the header of the model.
+///
+/// It's recommended to use [`Model::metadata`].
+pub fn metadata(model: &Model) -> Metadata {
+    Metadata::from_extensions(&model.header.extensions)
+}
+
+/// Replaces the metadata stored in the header of the model.
+/// Header extensions that aren't metadata keep their place, the metadata is appended after them.
+///
+/// It's recommended to use [`Model::set_metadata`].
+pub fn set_metadata(model: &mut Model, metadata: &Metadata) {
+    model.header.extensions.retain(|extension| {
+        !matches!(
+            extension.split_once('=').map(|(key, _)| key),
+            Some(AUTHOR | LICENSE | TAGS | TOOL_VERSION)
+        )
+    });
+
+    model.header.extensions.extend(metadata.to_extensions());
+}
+
+/// Returns every face tag stored in the model and the faces tagged with it, sorted by mesh and
+/// face index.
+///
+/// Entries of the tag lists that aren't strings are ignored.
+pub fn face_tags(model: &Model) -> BTreeMap<String, Vec<MeshFaceRef>> {
+    let mut tags: BTreeMap<String, Vec<MeshFaceRef>> = BTreeMap::new();
+
+    for (m, mesh) in model.meshes.iter().enumerate() {
+        for (f, face) in mesh.faces.iter().enumerate() {
+            let face_ref = MeshFaceRef::new(m, f);
+
+            for tag in tags_of(face) {
+                let faces = tags.entry(String::from(tag)).or_default();
+                if faces.last() != Some(&face_ref) {
+                    faces.push(face_ref);
+                }
+            }
+        }
+    }
+
+    tags
+}
+
+/// Returns the faces tagged with `tag`, sorted by mesh and face index.
+///
+/// It's recommended to use [`Model::faces_with_tag`].
+pub fn faces_with_tag(model: &Model, tag: &str) -> Vec<MeshFaceRef> {
+    face_tags(model).remove(tag).unwrap_or_default()
+}
+
+/// Tags the face with index `face` of the mesh with index `mesh`.
+/// Tagging a face twice with the same tag does nothing.
+///
+/// Returns [`PicoError::MeshIndex`] or [`PicoError::FaceIndex`] if the face doesn't exist.
+///
+/// It's recommended to use [`Model::tag_face`].
+pub fn tag_face(model: &mut Model, mesh: usize, face: usize, tag: &str) -> Result<(), PicoError> {
+    let meshes = model.meshes.len();
+    let faces = &mut model
+        .meshes
+        .get_mut(mesh)
+        .ok_or(PicoError::MeshIndex(mesh, meshes))?
+        .faces;
+    let count = faces.len();
+    let face = faces
+        .get_mut(face)
+        .ok_or(PicoError::FaceIndex(face, count))?;
+
+    if tags_of(face).contains(&tag) {
+        return Ok(());
+    }
+
+    let entry = face
+        .extras
+        .entry(String::from(FACE_TAGS))
+        .or_insert_with(|| LuaValue::Table(LuaTable::default()));
+    // Anything else stored at the key can't hold tags.
+    if !matches!(entry, LuaValue::Table(_)) {
+        *entry = LuaValue::Table(LuaTable::default());
+    }
+    if let LuaValue::Table(table) = entry {
+        table.sequence.push(LuaValue::String(String::from(tag)));
+    }
+
+    Ok(())
+}
+
+/// Removes `tag` from the face with index `face` of the mesh with index `mesh`.
+///
+/// Returns `true` if the face was tagged with it.
+///
+/// It's recommended to use [`Model::untag_face`].
+pub fn untag_face(model: &mut Model, mesh: usize, face: usize, tag: &str) -> bool {
+    let Some(face) = model
+        .meshes
+        .get_mut(mesh)
+        .and_then(|mesh| mesh.faces.get_mut(face))
+    else {
+        return false;
+    };
+    let Some(LuaValue::Table(table)) = face.extras.get_mut(FACE_TAGS) else {
+        return false;
+    };
+
+    let length = table.sequence.len();
+    table.sequence.retain(|value| value.as_str() != Some(tag));
+    let removed = table.sequence.len() != length;
+
+    if table.sequence.is_empty() && table.fields.is_empty() && table.other.is_empty() {
+        face.extras.remove(FACE_TAGS);
+    }
+
+    removed
+}
+
+/// Returns the tags stored in the extras of the face, in the order they were added.
+fn tags_of(face: &Face) -> Vec<&str> {
+    face.extras
+        .get(FACE_TAGS)
+        .and_then(LuaValue::as_table)
+        .map(|table| table.sequence.iter().filter_map(LuaValue::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Escapes the characters that would end a value early, as well as trailing whitespace which
+/// would be trimmed away when reading the header.
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for (i, c) in value.char_indices() {
+        let trailing = c.is_whitespace() && value[i..].trim_end().is_empty();
+
+        if trailing || matches!(c, '%' | ';' | ',' | '\n' | '\r') {
+            for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+                encoded.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            encoded.push(c);
+        }
+    }
+
+    encoded
+}
+
+/// Reverses [`encode`].
+/// A `%` that isn't followed by two hex digits is kept as it is.
+fn decode(value: &str) -> String {
+    let mut bytes: Vec<u8> = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+
+    while let Some((first, tail)) = rest.split_first() {
+        let escaped = tail
+            .get(..2)
+            .and_then(|hex| core::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(byte) if *first == b'%' => {
+                bytes.push(byte);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(*first);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::ProjectPreset;
+    use crate::lua_table::parse;
+    use alloc::{string::ToString, vec};
+
+    #[test]
+    fn metadata_round_trip() {
+        let metadata = Metadata {
+            author: Some("someone; else".to_string()),
+            license: Some("100% free\nfor all".to_string()),
+            tags: vec!["a,b".to_string(), "".to_string(), "c".to_string()],
+            tool_version: Some("picocadrs 1.0.3 ".to_string()),
+        };
+
+        let mut model = Model::default();
+        model.header.extensions.push("other=1".to_string());
+        model.set_metadata(&metadata);
+
+        let loaded: Model = model.to_string().parse().unwrap();
+
+        assert_eq!(loaded.metadata(), metadata);
+        assert_eq!(loaded.header.extensions[0], "other=1");
+        assert_eq!(loaded.header.extensions.len(), 5);
+    }
+
+    #[test]
+    fn metadata_replaced() {
+        let mut model = Model::default();
+        model.set_metadata(&Metadata {
+            author: Some("first".to_string()),
+            ..Metadata::default()
+        });
+        model.set_metadata(&Metadata {
+            license: Some("MIT".to_string()),
+            ..Metadata::default()
+        });
+
+        assert_eq!(model.header.extensions, vec!["meta.license=MIT"]);
+
+        model.set_metadata(&Metadata::default());
+        assert!(model.header.extensions.is_empty());
+        assert!(model.metadata().is_empty());
+    }
+
+    #[test]
+    fn metadata_escaping() {
+        assert_eq!(decode("50%"), "50%");
+        assert_eq!(decode("%zz%3B"), "%zz;");
+        assert_eq!(decode("%C3%A4"), "ä");
+        assert_eq!(decode("ä%25"), "ä%");
+        assert_eq!(encode("tool 1.0 \u{3000}"), "tool 1.0%20%E3%80%80");
+    }
+
+    #[test]
+    fn metadata_face_tags() {
+        let mut model = Model::new_project("tags".to_string(), ProjectPreset::Plane);
+        model.meshes.push(model.meshes[0].clone());
+        let face = model.meshes[1].faces[0].clone();
+        model.meshes[1].faces.push(face);
+
+        model.tag_face(1, 1, "glass; a='b'").unwrap();
+        model.tag_face(0, 0, "glass; a='b'").unwrap();
+        model.tag_face(0, 0, "glass; a='b'").unwrap();
+        model.tag_face(1, 1, "emissive").unwrap();
+        model.set_metadata(&Metadata {
+            author: Some("me".to_string()),
+            ..Metadata::default()
+        });
+
+        assert_eq!(model.header.extensions, vec!["meta.author=me"]);
+        assert_eq!(
+            model.meshes[1].faces[1].extras[FACE_TAGS].to_string(),
+            "{'glass; a=\\'b\\'','emissive'}"
+        );
+
+        let loaded: Model = model.to_string().parse().unwrap();
+        assert_eq!(
+            loaded.faces_with_tag("glass; a='b'"),
+            vec![MeshFaceRef::new(0, 0), MeshFaceRef::new(1, 1)]
+        );
+        assert_eq!(loaded.face_tags().len(), 2);
+        assert!(loaded.faces_with_tag("missing").is_empty());
+
+        assert!(matches!(
+            model.tag_face(2, 0, "x"),
+            Err(PicoError::MeshIndex(2, 2))
+        ));
+        assert!(matches!(
+            model.tag_face(0, 1, "x"),
+            Err(PicoError::FaceIndex(1, 1))
+        ));
+
+        assert!(model.untag_face(0, 0, "glass; a='b'"));
+        assert!(!model.untag_face(0, 0, "glass; a='b'"));
+        assert!(!model.untag_face(5, 0, "emissive"));
+        assert!(model.untag_face(1, 1, "emissive"));
+        assert!(model.meshes[0].faces[0].extras.is_empty());
+        assert_eq!(
+            model.face_tags()["glass; a='b'"],
+            vec![MeshFaceRef::new(1, 1)]
+        );
+    }
+
+    #[test]
+    fn metadata_face_tags_lenient() {
+        let mut model = Model::new_project("tags".to_string(), ProjectPreset::Plane);
+        let extras = &mut model.meshes[0].faces[0].extras;
+        extras.insert(FACE_TAGS.to_string(), parse("{'a',1,'a',x='b'}").unwrap());
+
+        assert_eq!(model.face_tags()["a"], vec![MeshFaceRef::new(0, 0)]);
+        assert_eq!(model.face_tags().len(), 1);
+
+        model.meshes[0].faces[0]
+            .extras
+            .insert(FACE_TAGS.to_string(), LuaValue::Integer(3));
+        assert!(model.face_tags().is_empty());
+
+        model.tag_face(0, 0, "b").unwrap();
+        assert_eq!(model.faces_with_tag("b"), vec![MeshFaceRef::new(0, 0)]);
+    }
+
+    #[test]
+    fn metadata_face_tags_follow_faces() {
+        let mut model = Model::new_project("tags".to_string(), ProjectPreset::Plane);
+        let mut first = model.meshes[0].clone();
+        first.name = "a_first".to_string();
+        model.meshes.push(first);
+        model.meshes[0].faces.insert(0, Face::default());
+
+        model.tag_face(0, 1, "window").unwrap();
+        model.meshes[0].remove_face(0);
+        model.normalize_for_sharing().unwrap();
+
+        assert_eq!(model.meshes[1].name, "plane");
+        assert_eq!(model.faces_with_tag("window"), vec![MeshFaceRef::new(1, 0)]);
+    }
+}
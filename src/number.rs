@@ -0,0 +1,170 @@
+//! For writing numbers the same way picoCAD does.
+//!
+//! picoCAD runs on pico-8, which stores numbers as 16.16 fixed point values.
+//! When saving, numbers are written with at most 4 digits behind the comma, without trailing
+//! zeros and never in scientific notation.
+//! Whole numbers don't get a trailing `.0`.
+//!
+//! Rusts default formatting of [`f64`] writes every digit needed to tell the value apart from
+//! other floats, so results of calculations like `0.1 + 0.2` end up as `0.30000000000000004`,
+//! which only bloats the file since picoCAD can't store that precision anyway.
+//!
+//! The fields picoCAD writes itself, like positions, rotations, vertices and uv-coordinates, are
+//! formatted through [`PicoNumber`] when saving a model.
+//! Values picoCAD doesn't know about are kept in the extras of a mesh or face and written exactly
+//! as they were read, see [`LuaValue`].
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::number::PicoNumber;
+//!
+//! assert_eq!(PicoNumber(1.0).to_string(), "1");
+//! assert_eq!(PicoNumber(-0.25).to_string(), "-0.25");
+//! assert_eq!(PicoNumber(0.1 + 0.2).to_string(), "0.3");
+//! assert_eq!(PicoNumber(1.0 / 3.0).to_string(), "0.3333");
+//! assert_eq!(PicoNumber(1e-7).to_string(), "0");
+//! ```
+
+use crate::lua_table::LuaValue;
+use alloc::{format, string::ToString};
+use core::fmt::{Display, Formatter};
+
+/// Amount of digits behind the comma picoCAD writes.
+pub const DECIMALS: usize = 4;
+/// Smallest number pico-8 can store.
+pub const MIN: f64 = -32768.0;
+/// Biggest number pico-8 can store, with [`DECIMALS`] digits behind the comma.
+pub const MAX: f64 = 32767.9999;
+
+/// Wraps a number to format it like picoCAD does.
+///
+/// Numbers are rounded to [`DECIMALS`] digits behind the comma and trailing zeros are removed.
+/// Numbers outside of the range pico-8 can store are clamped to [`MIN`] and [`MAX`], `NaN` is
+/// written as `0`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PicoNumber(pub f64);
+
+impl Display for PicoNumber {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let number = if self.0.is_nan() {
+            0.0
+        } else {
+            self.0.clamp(MIN, MAX)
+        };
+
+        let formatted = format!("{:.*}", DECIMALS, number);
+        let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+
+        if trimmed == "-0" {
+            write!(f, "0")
+        } else {
+            write!(f, "{}", trimmed)
+        }
+    }
+}
+
+impl From<PicoNumber> for LuaValue {
+    /// The value picoCAD reads back after writing the number.
+    ///
+    /// Whole numbers become [`LuaValue::Integer`], like they are when parsing a saved file.
+    fn from(number: PicoNumber) -> Self {
+        let formatted = number.to_string();
+
+        match formatted.parse::<i64>() {
+            Ok(integer) => LuaValue::Integer(integer),
+            Err(_) => LuaValue::Number(formatted.parse().unwrap_or_default()),
+        }
+    }
+}
+
+/// Numbers that can be written into a picoCAD file.
+///
+/// Floats are written using [`PicoNumber`], integers are written as they are and points are
+/// written component by component, separated by commas.
+pub trait FormatNumber {
+    /// Writes the number the way picoCAD does.
+    fn fmt_number(&self, f: &mut Formatter<'_>) -> core::fmt::Result;
+}
+
+impl FormatNumber for f64 {
+    fn fmt_number(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        PicoNumber(*self).fmt(f)
+    }
+}
+
+impl FormatNumber for f32 {
+    fn fmt_number(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        PicoNumber(*self as f64).fmt(f)
+    }
+}
+
+/// Displays a value using its [`FormatNumber`] implementation.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Point3D;
+/// use picocadrs::number::PicoFormat;
+/// use picocadrs::point;
+///
+/// let point = point!(0.1 + 0.2, 1.0, -1e-9);
+///
+/// assert_eq!(point.to_string(), "0.30000000000000004,1,-0.000000001");
+/// assert_eq!(PicoFormat(&point).to_string(), "0.3,1,0");
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct PicoFormat<'a, T: ?Sized>(pub &'a T);
+
+impl<T: FormatNumber + ?Sized> Display for PicoFormat<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.0.fmt_number(f)
+    }
+}
+
+macro_rules! impl_format_number_for_integers {
+    ($($t:ty),*) => {
+        $(
+            impl FormatNumber for $t {
+                fn fmt_number(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                    Display::fmt(self, f)
+                }
+            }
+        )*
+    };
+}
+
+impl_format_number_for_integers!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn number_trailing_zeros() {
+        assert_eq!(PicoNumber(0.0).to_string(), "0");
+        assert_eq!(PicoNumber(-0.0).to_string(), "0");
+        assert_eq!(PicoNumber(16.0).to_string(), "16");
+        assert_eq!(PicoNumber(1.5).to_string(), "1.5");
+        assert_eq!(PicoNumber(-0.75).to_string(), "-0.75");
+        assert_eq!(PicoNumber(0.0625).to_string(), "0.0625");
+    }
+
+    #[test]
+    fn number_precision_and_range() {
+        assert_eq!(PicoNumber(0.12346).to_string(), "0.1235");
+        assert_eq!(PicoNumber(-0.00004).to_string(), "0");
+        assert_eq!(PicoNumber(2.99999).to_string(), "3");
+        assert_eq!(PicoNumber(1e20).to_string(), "32767.9999");
+        assert_eq!(PicoNumber(-1e20).to_string(), "-32768");
+        assert_eq!(PicoNumber(f64::NAN).to_string(), "0");
+    }
+
+    #[test]
+    fn number_lua_value() {
+        assert_eq!(LuaValue::from(PicoNumber(2.0)), LuaValue::Integer(2));
+        assert_eq!(LuaValue::from(PicoNumber(-0.0)), LuaValue::Integer(0));
+        assert_eq!(LuaValue::from(PicoNumber(0.1 + 0.2)), LuaValue::Number(0.3));
+        assert_eq!(LuaValue::from(PicoNumber(1e20)), LuaValue::Number(MAX));
+    }
+}
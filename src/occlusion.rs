@@ -0,0 +1,459 @@
+//! For darkening the parts of a model that are hard for light to reach, also known as ambient
+//! occlusion.
+//!
+//! For every point that is shaded, rays are cast in evenly spread directions across the half
+//! of the sphere the face is looking at.
+//! The more of these rays hit another face within [`AoOptions::max_distance`], the darker the point
+//! gets: the share of rays that don't hit anything is used as intensity for
+//! [`Shade::from_intensity`], so colors are swapped for their
+//! [`shadow_transition`](crate::assets::Color::shadow_transition) or
+//! [`shadow`](crate::assets::Color::shadow) like picoCAD does when shading.
+//!
+//! Which points are shaded is decided by [`AoTarget`]:
+//!
+//! - [`AoTarget::Texture`] shades every pixel of the texture at the point of the face it is
+//!   sampled by.
+//!   Pixels used by multiple faces are shaded like the face that comes first in the model (see
+//!   [`TextureUsage::owner`]), unused pixels and pixels in the alpha color are left as they are.
+//! - [`AoTarget::FaceColors`] shades the color of each face at its center.
+//!
+//! Rays can hit both sides of every face, so single-sided faces still block light from behind.
+//! The rotation of meshes is ignored, the same way [`raycast`](crate::raycast) ignores it.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Color, Mesh, Model, Point3D};
+//! use picocadrs::occlusion::{AoOptions, AoTarget};
+//! use picocadrs::point;
+//!
+//! let mut model = Model::default();
+//! model.meshes.push(Mesh::plane());
+//! model.meshes.push(Mesh::plane());
+//! // a low ceiling above the floor, the y-axis points down in picoCAD.
+//! model.meshes[1].position = point!(0.0, -0.25, 0.0);
+//!
+//! let options = AoOptions {
+//!     target: AoTarget::FaceColors,
+//!     ..AoOptions::default()
+//! };
+//!
+//! assert_eq!(model.bake_ao(16, options), 1);
+//! assert_eq!(model.meshes[0].faces[0].color, Color::DarkGrey);
+//! assert_eq!(model.meshes[1].faces[0].color, Color::LightGrey);
+//! ```
+
+use crate::{
+    analysis::usage::TextureUsage,
+    assets::{Face, Footer, MeshFaceRef, Model, Point2D, Point3D},
+    point,
+    shading::Shade,
+    spatial::Bvh,
+};
+
+/// Distance rays start away from the face they are cast from, so they don't hit it themselves.
+const RAY_OFFSET: f64 = 1e-4;
+
+/// What [`bake_ao`] darkens.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub enum AoTarget {
+    /// Pixels of the texture.
+    #[default]
+    Texture,
+    /// Colors of the faces.
+    FaceColors,
+}
+
+/// Settings for [`bake_ao`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AoOptions {
+    /// Faces further away than this don't block light.
+    ///
+    /// Defaults to `1.0`, half the size of a new plane.
+    pub max_distance: f64,
+    /// What is darkened.
+    pub target: AoTarget,
+}
+
+impl Default for AoOptions {
+    fn default() -> Self {
+        AoOptions {
+            max_distance: 1.0,
+            target: AoTarget::default(),
+        }
+    }
+}
+
+/// Darkens the texture or the face colors of the model according to how much of the light around
+/// them is blocked and returns the amount of pixels or faces that changed.
+///
+/// Each point is tested with `samples` rays, more rays give smoother results but take longer.
+/// Nothing changes if `samples` is `0`.
+///
+/// It's recommended to use [`Model::bake_ao`].
+pub fn bake_ao(model: &mut Model, samples: usize, options: AoOptions) -> usize {
+    if samples == 0 {
+        return 0;
+    }
+
+    let directions = hemisphere(samples);
+
+    match options.target {
+        AoTarget::Texture => bake_texture(model, &directions, options),
+        AoTarget::FaceColors => bake_face_colors(model, &directions, options),
+    }
+}
+
+/// Shades the pixels of the texture at the points they are sampled at.
+fn bake_texture(model: &mut Model, directions: &[Point3D<f64>], options: AoOptions) -> usize {
+    let usage = TextureUsage::from_model(model);
+    let bvh = Bvh::from_model(model);
+    let mut shades: Vec<(Point2D<usize>, Shade)> = vec![];
+
+    for v in 0..Footer::HEIGHT {
+        for u in 0..Footer::WIDTH {
+            let coords = point!(u, v);
+            let Some(owner) = usage.owner(coords) else {
+                continue;
+            };
+
+            if model.footer.pixel(coords) == model.header.alpha {
+                continue;
+            }
+
+            let mesh = &model.meshes[owner.mesh];
+            let face = &mesh.faces[owner.face];
+            let Some(position) = texel_position(face, &mesh.vertices, coords) else {
+                continue;
+            };
+
+            let normal = face.normal(&mesh.vertices);
+            let shade = shade_at(
+                model,
+                &bvh,
+                owner,
+                position + mesh.position,
+                normal,
+                directions,
+                options,
+            );
+            shades.push((coords, shade));
+        }
+    }
+
+    let mut changed = 0;
+
+    for (coords, shade) in shades {
+        let Some(pixel) = model.footer.get_mut(coords) else {
+            continue;
+        };
+
+        let color = shade.apply(*pixel);
+        if color != *pixel {
+            *pixel = color;
+            changed += 1;
+        }
+    }
+
+    changed
+}
+
+/// Shades the colors of the faces at their centers.
+fn bake_face_colors(model: &mut Model, directions: &[Point3D<f64>], options: AoOptions) -> usize {
+    let bvh = Bvh::from_model(model);
+    let mut shades: Vec<(MeshFaceRef, Shade)> = vec![];
+
+    for (mesh_index, mesh) in model.meshes.iter().enumerate() {
+        for (face_index, face) in mesh.faces.iter().enumerate() {
+            let Some(corners) = face.corners(&mesh.vertices) else {
+                continue;
+            };
+            if corners.len() < 3 {
+                continue;
+            }
+
+            let center = corners
+                .iter()
+                .fold(point!(0.0, 0.0, 0.0), |sum, corner| sum + *corner)
+                * (1.0 / corners.len() as f64);
+
+            let face_ref = MeshFaceRef::new(mesh_index, face_index);
+            let shade = shade_at(
+                model,
+                &bvh,
+                face_ref,
+                center + mesh.position,
+                face.normal(&mesh.vertices),
+                directions,
+                options,
+            );
+            shades.push((face_ref, shade));
+        }
+    }
+
+    let mut changed = 0;
+
+    for (face_ref, shade) in shades {
+        let face = &mut model.meshes[face_ref.mesh].faces[face_ref.face];
+        let color = shade.apply(face.color);
+        if color != face.color {
+            face.color = color;
+            changed += 1;
+        }
+    }
+
+    changed
+}
+
+/// Returns the shade of the point `position` in world space on the face `face`, which looks in
+/// the direction `normal`.
+fn shade_at(
+    model: &Model,
+    bvh: &Bvh<MeshFaceRef>,
+    face: MeshFaceRef,
+    position: Point3D<f64>,
+    normal: Point3D<f64>,
+    directions: &[Point3D<f64>],
+    options: AoOptions,
+) -> Shade {
+    if normal == point!(0.0, 0.0, 0.0) {
+        return Shade::Lit;
+    }
+
+    let (tangent, bitangent) = basis(normal);
+    let origin = position + normal * RAY_OFFSET;
+
+    let blocked = directions
+        .iter()
+        .map(|d| tangent * d.x + bitangent * d.y + normal * d.z)
+        .filter(|direction| is_blocked(model, bvh, face, origin, *direction, options.max_distance))
+        .count();
+
+    Shade::from_intensity(1.0 - blocked as f64 / directions.len() as f64)
+}
+
+/// Checks if a ray hits any face other than `skip` within `max_distance`.
+fn is_blocked(
+    model: &Model,
+    bvh: &Bvh<MeshFaceRef>,
+    skip: MeshFaceRef,
+    origin: Point3D<f64>,
+    direction: Point3D<f64>,
+    max_distance: f64,
+) -> bool {
+    bvh.cast_ray(origin, direction, max_distance, |face_ref| {
+        if *face_ref == skip {
+            return None;
+        }
+
+        let mesh = &model.meshes[face_ref.mesh];
+        mesh.faces[face_ref.face]
+            .intersect_ray_sides(&mesh.vertices, origin - mesh.position, direction, true)
+            .map(|hit| (hit.distance, ()))
+    })
+    .is_some()
+}
+
+/// Returns `count` directions spread evenly across the half of the unit sphere around the z-axis.
+///
+/// Directions close to the z-axis are picked more often, since light coming straight at a face
+/// matters more than light grazing it.
+fn hemisphere(count: usize) -> Vec<Point3D<f64>> {
+    let golden_angle = core::f64::consts::PI * (3.0 - 5f64.sqrt());
+
+    (0..count)
+        .map(|i| {
+            let radius = ((i as f64 + 0.5) / count as f64).sqrt();
+            let angle = i as f64 * golden_angle;
+
+            point!(
+                radius * angle.cos(),
+                radius * angle.sin(),
+                (1.0 - radius * radius).sqrt()
+            )
+        })
+        .collect()
+}
+
+/// Returns two directions at right angles to each other and to `normal`.
+fn basis(normal: Point3D<f64>) -> (Point3D<f64>, Point3D<f64>) {
+    let helper = if normal.x.abs() < 0.9 {
+        point!(1.0, 0.0, 0.0)
+    } else {
+        point!(0.0, 1.0, 0.0)
+    };
+
+    let tangent = normal.cross(helper).normalized();
+    (tangent, normal.cross(tangent))
+}
+
+/// Returns the point of the face the center of the pixel at `coords` is drawn at, relative to the
+/// position of the mesh.
+///
+/// Pixels at the border of the face whose center lies just outside of it are moved onto the
+/// closest triangle of the face.
+fn texel_position(
+    face: &Face,
+    vertices: &[Point3D<f64>],
+    coords: Point2D<usize>,
+) -> Option<Point3D<f64>> {
+    let corners = face.corners(vertices)?;
+    let uv = point!((coords.u as f64 + 0.5) / 8.0, (coords.v as f64 + 0.5) / 8.0);
+
+    let mut best: Option<(f64, Point3D<f64>)> = None;
+
+    for i in 1..corners.len().saturating_sub(1) {
+        let triangle = [0, i, i + 1];
+        let Some(weights) = barycentric(triangle.map(|corner| face.uv_maps[corner].coords), uv)
+        else {
+            continue;
+        };
+
+        let fit = weights.iter().copied().fold(f64::INFINITY, f64::min);
+        if best.is_some_and(|(best_fit, _)| best_fit >= fit) {
+            continue;
+        }
+
+        let weights = weights.map(|weight| weight.max(0.0));
+        let total: f64 = weights.iter().sum();
+        let position = triangle
+            .iter()
+            .zip(weights)
+            .fold(point!(0.0, 0.0, 0.0), |sum, (corner, weight)| {
+                sum + corners[*corner] * (weight / total)
+            });
+
+        best = Some((fit, position));
+    }
+
+    best.map(|(_, position)| position)
+}
+
+/// Returns the weights of the corners of a triangle in uv-space at `point`.
+///
+/// Returns [`None`] if the triangle has no area.
+fn barycentric(triangle: [Point2D<f64>; 3], point: Point2D<f64>) -> Option<[f64; 3]> {
+    let [a, b, c] = triangle;
+    let area = (b.u - a.u) * (c.v - a.v) - (c.u - a.u) * (b.v - a.v);
+
+    if area.abs() < f64::EPSILON {
+        return None;
+    }
+
+    let wb = ((point.u - a.u) * (c.v - a.v) - (c.u - a.u) * (point.v - a.v)) / area;
+    let wc = ((b.u - a.u) * (point.v - a.v) - (point.u - a.u) * (b.v - a.v)) / area;
+
+    Some([1.0 - wb - wc, wb, wc])
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Color, Mesh};
+
+    /// A floor with a low ceiling over its `x > 0` half, the texture of the floor is white.
+    fn covered_floor() -> Model {
+        let mut model = Model::default();
+        model.header.alpha = Color::Invalid;
+        model.meshes.push(Mesh::plane());
+
+        let mut ceiling = Mesh::plane();
+        ceiling.vertices = vec![
+            point!(0.0, 0.0, -1.0),
+            point!(2.0, 0.0, -1.0),
+            point!(2.0, 0.0, 1.0),
+            point!(0.0, 0.0, 1.0),
+        ];
+        ceiling.position = point!(0.0, -0.1, 0.0);
+        ceiling.faces[0].no_texture = true;
+        model.meshes.push(ceiling);
+
+        for v in 0..16 {
+            for u in 0..16 {
+                model.footer.set(point!(u, v), Color::White).unwrap();
+            }
+        }
+
+        model
+    }
+
+    #[test]
+    fn ao_hemisphere() {
+        let directions = hemisphere(32);
+
+        assert_eq!(directions.len(), 32);
+        for direction in directions {
+            assert!((direction.length() - 1.0).abs() < 1e-9);
+            assert!(direction.z > 0.0);
+        }
+
+        let (tangent, bitangent) = basis(point!(0.0, -1.0, 0.0));
+        assert!(tangent.dot(point!(0.0, -1.0, 0.0)).abs() < 1e-9);
+        assert!(bitangent.dot(tangent).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ao_texel_position() {
+        let mesh = Mesh::plane();
+        let face = &mesh.faces[0];
+
+        // the plane maps 16x16 pixels onto 2x2 units.
+        let corner = texel_position(face, &mesh.vertices, point!(0, 0)).unwrap();
+        let other = texel_position(face, &mesh.vertices, point!(15, 15)).unwrap();
+
+        assert!((corner - other).length() > 2.5);
+        assert!(corner.x.abs() < 1.0 && corner.z.abs() < 1.0);
+        assert_eq!(corner.y, 0.0);
+    }
+
+    #[test]
+    fn ao_texture() {
+        let mut model = covered_floor();
+        let changed = model.bake_ao(16, AoOptions::default());
+
+        assert!(changed > 0);
+        assert!(changed < 16 * 16);
+
+        // texels under the ceiling are darkened, the open half is left alone.
+        let under = texel_position(
+            &model.meshes[0].faces[0],
+            &model.meshes[0].vertices,
+            point!(2, 8),
+        )
+        .unwrap();
+        let (covered, open) = if under.x > 0.0 {
+            (point!(2, 8), point!(13, 8))
+        } else {
+            (point!(13, 8), point!(2, 8))
+        };
+
+        assert_eq!(model.footer.pixel(covered), Color::White.shadow());
+        assert_eq!(model.footer.pixel(open), Color::White);
+        assert_eq!(model.footer.pixel(point!(20, 8)), Color::Black);
+    }
+
+    #[test]
+    fn ao_face_colors() {
+        let mut model = covered_floor();
+        model.meshes[0].vertices = vec![
+            point!(0.25, 0.0, -0.25),
+            point!(0.75, 0.0, -0.25),
+            point!(0.75, 0.0, 0.25),
+            point!(0.25, 0.0, 0.25),
+        ];
+        let options = AoOptions {
+            target: AoTarget::FaceColors,
+            ..AoOptions::default()
+        };
+
+        assert_eq!(model.bake_ao(0, options), 0);
+        assert_eq!(model.bake_ao(8, options), 1);
+        assert_eq!(model.meshes[0].faces[0].color, Color::LightGrey.shadow());
+        assert_eq!(model.meshes[1].faces[0].color, Color::LightGrey);
+
+        // nothing blocks the light once the ceiling is out of reach.
+        let mut model = covered_floor();
+        model.meshes[1].position = point!(0.0, -5.0, 0.0);
+        assert_eq!(model.bake_ao(8, options), 0);
+    }
+}
@@ -0,0 +1,446 @@
+//! For shrinking the file size of models.
+//!
+//! picoCAD projects are often packed into carts, which leaves very little room.
+//! [`optimize`] applies a couple of changes that keep the look of a model (mostly) the same while
+//! making the written file smaller.
+//! Each change can be turned off with [`OptimizeOptions`].
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Mesh, Model, Point3D};
+//! use picocadrs::optimize::OptimizeOptions;
+//! use picocadrs::point;
+//!
+//! let mut model = Model::default();
+//! let mut mesh = Mesh::new("a_very_long_name".to_string());
+//! mesh.vertices.push(point!(0.33333333, 1.0, 0.0));
+//! model.meshes.push(mesh);
+//!
+//! let report = model.optimize(OptimizeOptions::default());
+//!
+//! assert_eq!(model.meshes[0].name, "a");
+//! assert_eq!(model.meshes[0].vertices[0], point!(0.333, 1.0, 0.0));
+//! assert!(report.bytes_saved() > 0);
+//! ```
+//!
+//! [`normalize_for_sharing`] doesn't care about size but brings a model into a canonical form,
+//! so saving the same model twice produces files that diff cleanly.
+
+use crate::assets::{Face, Header, Model, Point3D};
+use crate::error::PicoError;
+
+/// Characters used for shortened mesh names, in the order they are handed out.
+const NAME_CHARACTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Decides which changes [`optimize`] applies to a model.
+///
+/// By default every change is applied and coordinates are rounded to 3 digits behind the comma,
+/// which keeps everything placed on picoCAD's grid intact.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct OptimizeOptions {
+    /// Renames meshes to single characters (`a`, `b`, ...).
+    /// After 62 meshes names grow to 2 characters.
+    pub shorten_names: bool,
+    /// Amount of digits behind the comma positions, vertices and uv-coordinates are rounded to.
+    /// [`None`] leaves them untouched.
+    pub precision: Option<u32>,
+    /// Removes faces that don't show up in picoCAD.
+    /// These are faces with less than 3 vertices, faces using vertices that don't exist and
+    /// faces that use the same vertices in the same order as an earlier face of the mesh.
+    pub strip_redundant_faces: bool,
+    /// Turns textured faces that only show a single color of the texture into faces without
+    /// texture using that color.
+    /// Faces showing the alpha color of the model are kept, since they are invisible.
+    pub flatten_single_color_faces: bool,
+}
+
+impl Default for OptimizeOptions {
+    /// Enables every optimization and rounds to 3 digits behind the comma.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::optimize::OptimizeOptions;
+    ///
+    /// let options = OptimizeOptions::default();
+    ///
+    /// assert!(options.shorten_names);
+    /// assert_eq!(options.precision, Some(3));
+    /// assert!(options.strip_redundant_faces);
+    /// assert!(options.flatten_single_color_faces);
+    /// ```
+    fn default() -> Self {
+        OptimizeOptions {
+            shorten_names: true,
+            precision: Some(3),
+            strip_redundant_faces: true,
+            flatten_single_color_faces: true,
+        }
+    }
+}
+
+/// Summary of the changes made by [`optimize`].
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct OptimizeReport {
+    /// Size of the serialized model before optimizing in bytes.
+    pub bytes_before: usize,
+    /// Size of the serialized model after optimizing in bytes.
+    pub bytes_after: usize,
+    /// Amount of meshes that got a new name.
+    pub renamed_meshes: usize,
+    /// Amount of faces that were removed.
+    pub removed_faces: usize,
+    /// Amount of faces that were turned into faces without texture.
+    pub flattened_faces: usize,
+}
+
+impl OptimizeReport {
+    /// Returns how many bytes the serialized model got smaller.
+    pub fn bytes_saved(&self) -> usize {
+        self.bytes_before.saturating_sub(self.bytes_after)
+    }
+}
+
+/// Optimizes the model for file size as configured by `options`.
+///
+/// It's recommended to use [`Model::optimize`].
+pub fn optimize(model: &mut Model, options: OptimizeOptions) -> OptimizeReport {
+    let mut report = OptimizeReport {
+        bytes_before: model.to_string().len(),
+        ..OptimizeReport::default()
+    };
+
+    if options.shorten_names {
+        for (index, mesh) in model.meshes.iter_mut().enumerate() {
+            let name = short_name(index);
+
+            if mesh.name != name {
+                mesh.name = name;
+                report.renamed_meshes += 1;
+            }
+        }
+    }
+
+    if options.flatten_single_color_faces {
+        let alpha = model.header.alpha;
+
+        for face in model
+            .meshes
+            .iter_mut()
+            .flat_map(|mesh| mesh.faces.iter_mut())
+        {
+            if face.no_texture {
+                continue;
+            }
+
+            let colors = face.texture_colors(&model.footer);
+
+            if let (1, Some(&color)) = (colors.len(), colors.keys().next()) {
+                if color != alpha {
+                    face.no_texture = true;
+                    face.color = color;
+                    report.flattened_faces += 1;
+                }
+            }
+        }
+    }
+
+    if options.strip_redundant_faces {
+        for mesh in model.meshes.iter_mut() {
+            let vertex_count = mesh.vertices.len();
+            let mut kept: Vec<Vec<usize>> = vec![];
+            let faces_before = mesh.faces.len();
+
+            mesh.faces.retain(|face| {
+                if face.uv_maps.len() < 3
+                    || face
+                        .uv_maps
+                        .iter()
+                        .any(|uv_map| uv_map.vertex_index >= vertex_count)
+                {
+                    return false;
+                }
+
+                let key = winding_key(face);
+
+                if kept.contains(&key) {
+                    false
+                } else {
+                    kept.push(key);
+                    true
+                }
+            });
+
+            report.removed_faces += faces_before - mesh.faces.len();
+        }
+    }
+
+    if let Some(precision) = options.precision {
+        let factor = 10f64.powi(precision as i32);
+
+        for mesh in model.meshes.iter_mut() {
+            round_point(&mut mesh.position, factor);
+
+            for vertex in mesh.vertices.iter_mut() {
+                round_point(vertex, factor);
+            }
+
+            for uv_map in mesh
+                .faces
+                .iter_mut()
+                .flat_map(|face| face.uv_maps.iter_mut())
+            {
+                uv_map.coords.u = round_value(uv_map.coords.u, factor);
+                uv_map.coords.v = round_value(uv_map.coords.v, factor);
+            }
+        }
+    }
+
+    report.bytes_after = model.to_string().len();
+
+    report
+}
+
+/// Brings the model into a canonical form for sharing it on forums or in version control.
+///
+/// - the zoom level is reset to the one of [`Header::default`], since it only depends on the
+///   last session in picoCAD
+/// - leading and trailing whitespace is trimmed from the model name and mesh names
+/// - positions, vertices and uv-coordinates are rounded to 3 digits behind the comma and
+///   rotations are normalized and rounded, negative zeros are written as `0`
+/// - meshes are sorted by name, meshes with the same name by their serialized form
+///
+/// Returns [`PicoError::Identifier`] without changing anything if the identifier of the header
+/// is not `picocad`.
+///
+/// It's recommended to use [`Model::normalize_for_sharing`].
+pub fn normalize_for_sharing(model: &mut Model) -> Result<(), PicoError> {
+    if model.header.identifier() != Header::default().identifier() {
+        return Err(PicoError::Identifier);
+    }
+
+    model.header.zoom = Header::default().zoom;
+    model.header.name = model.header.name.trim().to_string();
+
+    let factor = 10f64.powi(3);
+
+    for mesh in model.meshes.iter_mut() {
+        mesh.name = mesh.name.trim().to_string();
+
+        round_point(&mut mesh.position, factor);
+
+        mesh.rotation.normalize();
+        round_point(&mut mesh.rotation.0, factor);
+
+        for vertex in mesh.vertices.iter_mut() {
+            round_point(vertex, factor);
+        }
+
+        for uv_map in mesh
+            .faces
+            .iter_mut()
+            .flat_map(|face| face.uv_maps.iter_mut())
+        {
+            uv_map.coords.u = round_value(uv_map.coords.u, factor);
+            uv_map.coords.v = round_value(uv_map.coords.v, factor);
+        }
+    }
+
+    model
+        .meshes
+        .sort_by_cached_key(|mesh| (mesh.name.clone(), mesh.to_string()));
+
+    Ok(())
+}
+
+/// Rounds every component of `point` to a multiple of `1 / factor`.
+fn round_point(point: &mut Point3D<f64>, factor: f64) {
+    point.x = round_value(point.x, factor);
+    point.y = round_value(point.y, factor);
+    point.z = round_value(point.z, factor);
+}
+
+/// Rounds `value` to a multiple of `1 / factor`.
+/// Adding `0.0` turns a negative zero into a positive one, so it isn't written as `-0`.
+fn round_value(value: f64, factor: f64) -> f64 {
+    (value * factor).round() / factor + 0.0
+}
+
+/// Returns the shortened name of the mesh at `index`.
+fn short_name(index: usize) -> String {
+    let characters: Vec<char> = NAME_CHARACTERS.chars().collect();
+    let mut name = String::new();
+    let mut index = index;
+
+    loop {
+        name.insert(0, characters[index % characters.len()]);
+
+        if index < characters.len() {
+            break;
+        }
+
+        index = index / characters.len() - 1;
+    }
+
+    name
+}
+
+/// Returns the vertex indices of the face rotated so the smallest index comes first.
+/// Faces with equal keys cover the same vertices in the same direction.
+fn winding_key(face: &Face) -> Vec<usize> {
+    let indices: Vec<usize> = face
+        .uv_maps
+        .iter()
+        .map(|uv_map| uv_map.vertex_index)
+        .collect();
+
+    let start = indices
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, index)| **index)
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+
+    indices[start..]
+        .iter()
+        .chain(indices[..start].iter())
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Color, Mesh, Point2D};
+    use crate::fixtures::face;
+    use crate::point;
+
+    #[test]
+    fn optimize_short_name() {
+        assert_eq!(short_name(0), "a");
+        assert_eq!(short_name(25), "z");
+        assert_eq!(short_name(26), "A");
+        assert_eq!(short_name(61), "9");
+        assert_eq!(short_name(62), "aa");
+        assert_eq!(short_name(63), "ab");
+        assert_eq!(short_name(62 + 62), "ba");
+    }
+
+    #[test]
+    fn optimize_strip_redundant_faces() {
+        let mut mesh = Mesh::new("a".to_string());
+        mesh.vertices = vec![point!(0.0, 0.0, 0.0); 4];
+        mesh.faces = vec![
+            face(&[0, 1, 2, 3]),
+            face(&[2, 3, 0, 1]),
+            face(&[3, 2, 1, 0]),
+            face(&[0, 1]),
+            face(&[0, 1, 4]),
+        ];
+
+        let mut model = Model {
+            meshes: vec![mesh],
+            ..Model::default()
+        };
+
+        let report = model.optimize(OptimizeOptions {
+            shorten_names: false,
+            precision: None,
+            strip_redundant_faces: true,
+            flatten_single_color_faces: false,
+        });
+
+        assert_eq!(report.removed_faces, 3);
+        assert_eq!(report.renamed_meshes, 0);
+        assert_eq!(
+            model.meshes[0].faces,
+            vec![face(&[0, 1, 2, 3]), face(&[3, 2, 1, 0])]
+        );
+        assert!(report.bytes_saved() > 0);
+    }
+
+    #[test]
+    fn optimize_flatten_single_color_faces() {
+        let mut textured = face(&[0, 1, 2]);
+        textured.uv_maps[1].coords = point!(1.0, 0.0);
+        textured.uv_maps[2].coords = point!(1.0, 1.0);
+
+        let mut model = Model::default();
+        model.header.alpha = Color::Black;
+
+        let mut mesh = Mesh::new("a".to_string());
+        mesh.vertices = vec![point!(0.0, 0.0, 0.0); 3];
+        mesh.faces = vec![textured.clone()];
+        model.meshes.push(mesh);
+
+        // only shows the alpha color
+        let report = model.optimize(OptimizeOptions::default());
+        assert_eq!(report.flattened_faces, 0);
+        assert!(!model.meshes[0].faces[0].no_texture);
+
+        for u in 0..8 {
+            for v in 0..8 {
+                model.footer.set(point!(u, v), Color::Green).unwrap();
+            }
+        }
+
+        let report = model.optimize(OptimizeOptions::default());
+        assert_eq!(report.flattened_faces, 1);
+        assert!(model.meshes[0].faces[0].no_texture);
+        assert_eq!(model.meshes[0].faces[0].color, Color::Green);
+    }
+
+    #[test]
+    fn optimize_precision() {
+        let mut mesh = Mesh::new("a".to_string());
+        mesh.position = point!(0.123456, -1.0, 2.5);
+        mesh.vertices = vec![point!(1.0 / 3.0, 2.0 / 3.0, 0.0)];
+        mesh.faces = vec![face(&[0, 0, 0])];
+        mesh.faces[0].uv_maps[0].coords = point!(0.06, 14.96);
+
+        let mut model = Model {
+            meshes: vec![mesh],
+            ..Model::default()
+        };
+
+        model.optimize(OptimizeOptions {
+            precision: Some(1),
+            ..OptimizeOptions::default()
+        });
+
+        let mesh = &model.meshes[0];
+        assert_eq!(mesh.position, point!(0.1, -1.0, 2.5));
+        assert_eq!(mesh.vertices[0], point!(0.3, 0.7, 0.0));
+        assert_eq!(mesh.faces[0].uv_maps[0].coords, point!(0.1, 15.0));
+    }
+
+    #[test]
+    fn normalize_for_sharing() {
+        let mut first = Mesh::new(" b ".to_string());
+        first.position = point!(-0.0001, 1.23456, 0.0);
+        first.rotation.0 = point!(1.25, -0.25, 0.0);
+
+        let second = Mesh::new("a".to_string());
+
+        let mut model = Model {
+            meshes: vec![first, second],
+            ..Model::default()
+        };
+        model.header.zoom = 3;
+        model.header.name = "  shared\t".to_string();
+
+        model.normalize_for_sharing().unwrap();
+
+        assert_eq!(model.header.zoom, 16);
+        assert_eq!(model.header.name, "shared");
+        assert_eq!(model.meshes[0].name, "a");
+        assert_eq!(model.meshes[1].name, "b");
+        assert_eq!(model.meshes[1].position.to_string(), "0,1.235,0");
+        assert_eq!(model.meshes[1].rotation.0, point!(0.25, 0.75, 0.0));
+
+        let once = model.to_string();
+        model.normalize_for_sharing().unwrap();
+        assert_eq!(model.to_string(), once);
+    }
+}
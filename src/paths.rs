@@ -1,9 +1,36 @@
 //! Important paths across different platforms.
 //!
 //! Mainly the paths of where picoCAD will store project files.
+//!
+//! Where that is depends on how picoCAD was installed, so [`Paths`] checks multiple locations in
+//! this order:
+//!
+//! 1. A folder set with [`Paths::with_projects_dir`], for portable installs or tests.
+//! 2. The folder in the [`PICOCAD_PATH`](ENV_VAR) environment variable.
+//! 3. The folders picoCAD uses on the current system, see [`Paths::candidates`].
+//!    This includes the folder used when picoCAD runs through Proton, like on the Steam Deck, and
+//!    the folder the itch app installs picoCAD into.
+//!    The first folder that exists is used.
+//! 4. The default folder of the current system, even if it doesn't exist yet.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::paths::Paths;
+//! use std::path::Path;
+//!
+//! let paths = Paths::with_projects_dir("portable/projects");
+//!
+//! assert_eq!(paths.projects_dir(), Some(Path::new("portable/projects").to_path_buf()));
+//! assert_eq!(
+//!     paths.project_file("house"),
+//!     Some(Path::new("portable/projects/house.txt").to_path_buf())
+//! );
+//! ```
 
 use std::env::consts::OS;
 use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 
 /// File path where a picoCAD project files are located on Windows systems relative to home directory.
 pub const WINDOWS: &str = r#"\AppData\Roaming\pico-8\appdata\picocad\"#;
@@ -12,32 +39,192 @@ pub const OSX: &str = "/Library/Application Support/pico-8/appdata/picocad/";
 /// File path where a picoCAD project files are located on Linux systems relative to home directory.
 pub const LINUX: &str = "/.lexaloffle/pico-8/appdata/picocad/";
 
+/// Environment variable that can point to the folder picoCAD project files are located in.
+pub const ENV_VAR: &str = "PICOCAD_PATH";
+
+/// Folder holding the prefixes of games run through Proton, relative to the home directory.
+const PROTON_PREFIXES: &str = ".local/share/Steam/steamapps/compatdata";
+/// Project folder inside of a Proton prefix.
+const PROTON_PROJECTS: &str = "pfx/drive_c/users/steamuser/AppData/Roaming/pico-8/appdata/picocad";
+/// Project folder inside of the folder the itch app installs picoCAD into.
+const ITCH_PROJECTS: &str = "picocad/appdata/picocad";
+
+/// Decides where picoCAD project files are looked for.
+///
+/// [`Paths::default`] looks in the same places picoCAD does, more information can be found in the
+/// [module](self) documentation.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Paths {
+    /// Folder used instead of any other location if set.
+    pub projects_dir: Option<PathBuf>,
+}
+
+impl Paths {
+    /// Creates paths that always use `dir` as the project folder.
+    pub fn with_projects_dir<P: AsRef<Path>>(dir: P) -> Paths {
+        Paths {
+            projects_dir: Some(dir.as_ref().to_path_buf()),
+        }
+    }
+
+    /// Returns the folder picoCAD project files are located in.
+    ///
+    /// Returns [`None`] if no folder is set and the home directory of the user can't be found.
+    pub fn projects_dir(&self) -> Option<PathBuf> {
+        self.resolve(std::env::var_os(ENV_VAR), home_dir().as_deref(), |path| {
+            path.is_dir()
+        })
+    }
+
+    /// Returns the path of the file the project called `name` is stored in.
+    pub fn project_file(&self, name: &str) -> Option<PathBuf> {
+        let mut file_name = OsString::from(name);
+        file_name.push(".txt");
+
+        self.projects_dir().map(|dir| dir.join(file_name))
+    }
+
+    /// Returns every folder picoCAD project files are looked for in on the current system, in
+    /// the order they are checked.
+    ///
+    /// The folder set in `self` and the environment variable are not included.
+    pub fn candidates(&self) -> Vec<PathBuf> {
+        match home_dir() {
+            Some(home) => system_candidates(OS, &home),
+            None => vec![],
+        }
+    }
+
+    /// Picks the project folder given the value of the environment variable, the home directory
+    /// and a way to check if a folder exists.
+    fn resolve(
+        &self,
+        env: Option<OsString>,
+        home: Option<&Path>,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Option<PathBuf> {
+        if let Some(dir) = &self.projects_dir {
+            return Some(dir.clone());
+        }
+
+        if let Some(env) = env.filter(|env| !env.is_empty()) {
+            return Some(PathBuf::from(env));
+        }
+
+        let candidates = system_candidates(OS, home?);
+
+        candidates
+            .iter()
+            .find(|candidate| exists(candidate))
+            .or(candidates.first())
+            .cloned()
+    }
+}
+
+/// Returns the home directory of the user.
+fn home_dir() -> Option<PathBuf> {
+    directories::UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf())
+}
+
+/// Returns the folders picoCAD stores projects in on the given system, starting with the default.
+fn system_candidates(os: &str, home: &Path) -> Vec<PathBuf> {
+    let relative = |path: &str| home.join(path.trim_matches(|c| c == '/' || c == '\\'));
+
+    match os {
+        "windows" => vec![
+            relative(WINDOWS),
+            relative(r#"AppData\Roaming\itch\apps"#).join(ITCH_PROJECTS),
+        ],
+        "macos" => vec![
+            relative(OSX),
+            relative("Library/Application Support/itch/apps").join(ITCH_PROJECTS),
+        ],
+        "linux" => {
+            let mut candidates = vec![
+                relative(LINUX),
+                relative(".config/itch/apps").join(ITCH_PROJECTS),
+            ];
+
+            // games added to Steam get a prefix named after a random id, so every prefix is checked.
+            if let Ok(prefixes) = std::fs::read_dir(home.join(PROTON_PREFIXES)) {
+                let mut prefixes: Vec<PathBuf> = prefixes
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path().join(PROTON_PROJECTS))
+                    .collect();
+                prefixes.sort();
+                candidates.extend(prefixes);
+            }
+
+            candidates
+        }
+        _ => vec![home.to_path_buf()],
+    }
+}
+
 /// Returns the file path where picoCAD project files are located on the system as an [`OsString`](OsString).
 /// If there is no home directory found this returns [`None`].
 /// If this returns [`None`] when it shouldn't check
 /// [`this`](https://docs.rs/directories/latest/directories/struct.BaseDirs.html#method.new)
 /// methods documentation, which this function relies on.
 ///
-/// I could verify that this works on windows, but I don't see why it shouldn't on macOS or linux.
+/// The folder is picked by [`Paths::default`], so it respects the [`PICOCAD_PATH`](ENV_VAR)
+/// environment variable.
 pub fn projects_path() -> Option<OsString> {
-    return if let Some(user_dirs) = directories::UserDirs::new() {
-        let mut path = user_dirs.home_dir().as_os_str().to_os_string();
-        path.push(match OS {
-            "windows" => WINDOWS,
-            "linux" => LINUX,
-            "macos" => OSX,
-            &_ => "",
-        });
-        Some(path)
-    } else {
-        None
-    };
+    Paths::default()
+        .projects_dir()
+        .map(|path| path.into_os_string())
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
 
+    #[test]
+    fn paths_resolve_order() {
+        let home = Path::new("home");
+        let default = system_candidates(OS, home)[0].clone();
+
+        let paths = Paths::with_projects_dir("custom");
+        assert_eq!(
+            paths.resolve(Some(OsString::from("env")), Some(home), |_| true),
+            Some(PathBuf::from("custom"))
+        );
+
+        let paths = Paths::default();
+        assert_eq!(
+            paths.resolve(Some(OsString::from("env")), Some(home), |_| true),
+            Some(PathBuf::from("env"))
+        );
+        assert_eq!(
+            paths.resolve(Some(OsString::new()), Some(home), |_| false),
+            Some(default.clone())
+        );
+        assert_eq!(paths.resolve(None, None, |_| true), None);
+    }
+
+    #[test]
+    fn paths_existing_candidate() {
+        let home = Path::new("home");
+        let candidates = system_candidates("linux", home);
+
+        assert_eq!(
+            candidates[0],
+            Path::new("home/.lexaloffle/pico-8/appdata/picocad")
+        );
+        assert_eq!(
+            candidates[1],
+            Path::new("home/.config/itch/apps/picocad/appdata/picocad")
+        );
+
+        if OS == "linux" {
+            let itch = candidates[1].clone();
+            assert_eq!(
+                Paths::default().resolve(None, Some(home), |path| path == itch),
+                Some(itch.clone())
+            );
+        }
+    }
+
     #[test]
     #[ignore]
     fn path_test_windows() {
@@ -45,7 +232,7 @@ pub mod tests {
         let mut path = user_dirs.home_dir().as_os_str().to_os_string();
         path.push(WINDOWS);
 
-        assert_eq!(path, projects_path().unwrap())
+        assert_eq!(PathBuf::from(path), PathBuf::from(projects_path().unwrap()))
     }
 
     #[test]
@@ -55,7 +242,7 @@ pub mod tests {
         let mut path = user_dirs.home_dir().as_os_str().to_os_string();
         path.push(LINUX);
 
-        assert_eq!(path, projects_path().unwrap())
+        assert_eq!(PathBuf::from(path), PathBuf::from(projects_path().unwrap()))
     }
 
     #[test]
@@ -65,6 +252,6 @@ pub mod tests {
         let mut path = user_dirs.home_dir().as_os_str().to_os_string();
         path.push(OSX);
 
-        assert_eq!(path, projects_path().unwrap())
+        assert_eq!(PathBuf::from(path), PathBuf::from(projects_path().unwrap()))
     }
 }
@@ -0,0 +1,401 @@
+//! For exchanging textures with [PICO-8](https://www.lexaloffle.com/pico-8.php) carts.
+//!
+//! The sprite sheet of a `.p8` cart is stored in its `__gfx__` section, as 128 lines of 128 hex
+//! digits, one for each pixel.
+//! That is the same format picoCAD uses for its texture, which only has 120 lines instead.
+//! So a texture always shows 120 rows of the sprite sheet, starting at a row of choice
+//! (`0 - 8` to stay inside of the sheet).
+//!
+//! Rows missing from a cart, like in carts that never used the bottom of their sprite sheet, are
+//! read as black.
+//!
+//! The other way around, [`Footer::to_p8_gfx`] writes a texture as a whole `__gfx__` section and
+//! [`patch_cart`] copies it into the sprite sheet of an existing cart, so textures made for
+//! picoCAD can be used as sprites in a game.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Color, Footer, Point2D};
+//! use picocadrs::point;
+//!
+//! let cart = "pico-8 cartridge // http://www.pico-8.com
+//! version 41
+//! __lua__
+//! print(\"hi\")
+//! __gfx__
+//! 00000000
+//! 0880000a
+//! __map__
+//! 0101
+//! ";
+//!
+//! let footer = Footer::from_p8_cart(cart, 0).unwrap();
+//! assert_eq!(footer.pixel(point!(1, 1)), Color::Red);
+//! assert_eq!(footer.pixel(point!(7, 1)), Color::Yellow);
+//!
+//! // starts one row further down the sprite sheet.
+//! let footer = Footer::from_p8_cart(cart, 1).unwrap();
+//! assert_eq!(footer.pixel(point!(1, 0)), Color::Red);
+//! ```
+
+use crate::{
+    assets::{Color, Footer},
+    error::PicoError,
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+/// Width of the sprite sheet in pixels.
+pub const SHEET_WIDTH: usize = 128;
+/// Height of the sprite sheet in pixels.
+pub const SHEET_HEIGHT: usize = 128;
+
+/// Returns the content of the `__gfx__` section of a `.p8` cart, without its header.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::pico8::gfx_section;
+///
+/// let cart = "__lua__\nx=1\n__gfx__\n0123\n__sfx__\n";
+///
+/// assert_eq!(gfx_section(cart), Some("0123\n"));
+/// assert_eq!(gfx_section("__lua__\n"), None);
+/// ```
+pub fn gfx_section(cart: &str) -> Option<&str> {
+    section_range(cart, "__gfx__").map(|(_, start, end)| &cart[start..end])
+}
+
+/// Reads the content of a `__gfx__` section into a texture, starting at `start_row` of the sprite
+/// sheet.
+///
+/// Fails with [`PicoError::Pico8`] if a pixel isn't a hex digit.
+///
+/// It's recommended to use [`Footer::from_p8_gfx`].
+pub fn footer_from_gfx(gfx: &str, start_row: usize) -> Result<Footer, PicoError> {
+    let mut footer = Footer::default();
+    let pixels = footer.as_flat_mut_slice();
+
+    let rows = gfx
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .skip(start_row)
+        .take(Footer::HEIGHT);
+
+    for (v, (row, line)) in rows.enumerate() {
+        for (u, c) in line.chars().take(SHEET_WIDTH).enumerate() {
+            let color = Color::from(c.to_ascii_lowercase());
+
+            if color == Color::Invalid {
+                return Err(PicoError::Pico8(format!(
+                    "invalid pixel {:?} in row {} of the sprite sheet",
+                    c, row
+                )));
+            }
+
+            pixels[v * SHEET_WIDTH + u] = color;
+        }
+    }
+
+    Ok(footer)
+}
+
+/// Reads the sprite sheet of a `.p8` cart into a texture, starting at `start_row` of the sprite
+/// sheet.
+///
+/// Fails with [`PicoError::Pico8`] if the cart has no `__gfx__` section or a pixel isn't a hex
+/// digit.
+///
+/// It's recommended to use [`Footer::from_p8_cart`].
+pub fn footer_from_cart(cart: &str, start_row: usize) -> Result<Footer, PicoError> {
+    let gfx = gfx_section(cart)
+        .ok_or_else(|| PicoError::Pico8("cart has no __gfx__ section".to_string()))?;
+
+    footer_from_gfx(gfx, start_row)
+}
+
+/// Reads the sprite sheet of the `.p8` cart at `path` into a texture, starting at `start_row` of
+/// the sprite sheet.
+///
+/// Fails like [`footer_from_cart`] or with [`PicoError::IO`] if the file can't be read.
+///
+/// It's recommended to use [`Footer::load_p8_cart`].
+#[cfg(feature = "std")]
+pub fn load_cart_footer<P: AsRef<Path>>(path: P, start_row: usize) -> Result<Footer, PicoError> {
+    footer_from_cart(&std::fs::read_to_string(path)?, start_row)
+}
+
+/// Writes the texture as a `__gfx__` section, header included.
+///
+/// The texture fills the top 120 rows of the sprite sheet, the 8 rows below it are black.
+///
+/// It's recommended to use [`Footer::to_p8_gfx`].
+pub fn footer_to_gfx(footer: &Footer) -> String {
+    let mut rows = vec![blank_row(); SHEET_HEIGHT];
+    write_rows(&mut rows, footer, 0);
+
+    gfx_block(&rows)
+}
+
+/// Copies the texture into the sprite sheet of a `.p8` cart, replacing 120 rows starting at
+/// `start_row` and returns the changed cart.
+///
+/// Everything else in the cart is kept as it is, including the rest of the sprite sheet.
+/// Rows of the texture that would end up below the sprite sheet are dropped.
+/// Carts without a `__gfx__` section get one, in the place PICO-8 puts it.
+///
+/// It's recommended to use [`Footer::patch_p8_cart`].
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Color, Footer, Point2D};
+/// use picocadrs::pico8::patch_cart;
+/// use picocadrs::point;
+///
+/// let cart = "__lua__\nprint(1)\n__gfx__\n7777\n7777\n__map__\n0101\n";
+///
+/// let mut footer = Footer::default();
+/// footer.set(point!(0, 0), Color::Red).unwrap();
+///
+/// let patched = patch_cart(cart, &footer, 1);
+/// let sheet = Footer::from_p8_cart(&patched, 0).unwrap();
+///
+/// assert_eq!(sheet.pixel(point!(0, 0)), Color::White);
+/// assert_eq!(sheet.pixel(point!(0, 1)), Color::Red);
+/// assert!(patched.starts_with("__lua__\nprint(1)\n__gfx__\n7777"));
+/// assert!(patched.ends_with("__map__\n0101\n"));
+/// ```
+pub fn patch_cart(cart: &str, footer: &Footer, start_row: usize) -> String {
+    let range = section_range(cart, "__gfx__");
+
+    let mut rows: Vec<String> = vec![];
+    if let Some((_, start, end)) = range {
+        rows = cart[start..end]
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .take(SHEET_HEIGHT)
+            .map(|line| {
+                let mut row: String = line.chars().take(SHEET_WIDTH).collect();
+                while row.len() < SHEET_WIDTH {
+                    row.push('0');
+                }
+                row
+            })
+            .collect();
+    }
+    rows.resize(SHEET_HEIGHT, blank_row());
+    write_rows(&mut rows, footer, start_row);
+
+    let block = gfx_block(&rows);
+
+    let (before, after) = match range {
+        Some((header, _, end)) => (&cart[..header], &cart[end..]),
+        None => {
+            // PICO-8 writes the sprite sheet in front of all of these sections.
+            let next = ["__gff__", "__label__", "__map__", "__sfx__", "__music__"]
+                .iter()
+                .filter_map(|name| section_range(cart, name).map(|(header, _, _)| header))
+                .min()
+                .unwrap_or(cart.len());
+
+            (&cart[..next], &cart[next..])
+        }
+    };
+
+    let mut patched = before.to_string();
+    if !patched.is_empty() && !patched.ends_with('\n') {
+        patched.push('\n');
+    }
+    patched.push_str(&block);
+    patched.push_str(after);
+
+    patched
+}
+
+/// Patches the sprite sheet of the `.p8` cart at `path` like [`patch_cart`] and writes it back.
+///
+/// Fails with [`PicoError::IO`] if the file can't be read or written.
+///
+/// It's recommended to use [`Footer::patch_p8_cart`].
+#[cfg(feature = "std")]
+pub fn patch_cart_file<P: AsRef<Path>>(
+    path: P,
+    footer: &Footer,
+    start_row: usize,
+) -> Result<(), PicoError> {
+    let cart = std::fs::read_to_string(&path)?;
+    std::fs::write(path, patch_cart(&cart, footer, start_row))?;
+
+    Ok(())
+}
+
+/// Returns where the section `name` starts, where its content starts and where it ends.
+fn section_range(cart: &str, name: &str) -> Option<(usize, usize, usize)> {
+    let mut start: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    for line in cart.split_inclusive('\n') {
+        let trimmed = line.trim();
+
+        if is_section_header(trimmed) {
+            if let Some((header, content)) = start {
+                return Some((header, content, offset));
+            }
+            if trimmed == name {
+                start = Some((offset, offset + line.len()));
+            }
+        }
+
+        offset += line.len();
+    }
+
+    start.map(|(header, content)| (header, content, cart.len()))
+}
+
+/// Returns a row of the sprite sheet filled with black.
+fn blank_row() -> String {
+    "0".repeat(SHEET_WIDTH)
+}
+
+/// Overwrites the rows of the sprite sheet starting at `start_row` with the rows of the texture.
+fn write_rows(rows: &mut [String], footer: &Footer, start_row: usize) {
+    for (row, pixels) in rows.iter_mut().skip(start_row).zip(footer.rows()) {
+        *row = pixels.iter().map(|color| color.as_char()).collect();
+    }
+}
+
+/// Writes the rows of the sprite sheet as a `__gfx__` section.
+fn gfx_block(rows: &[String]) -> String {
+    let mut block = String::from("__gfx__\n");
+
+    for row in rows {
+        block.push_str(row);
+        block.push('\n');
+    }
+
+    block
+}
+
+/// Checks if a line starts a new section of a cart, like `__gfx__`.
+fn is_section_header(line: &str) -> bool {
+    line.len() > 4
+        && line.starts_with("__")
+        && line.ends_with("__")
+        && line[2..line.len() - 2]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::Point2D;
+    use crate::point;
+
+    fn sheet() -> String {
+        let rows: Vec<String> = (0..SHEET_HEIGHT)
+            .map(|row| {
+                let color = Color::from((row % 16) as i32).as_char();
+                color.to_string().repeat(SHEET_WIDTH)
+            })
+            .collect();
+
+        rows.join("\n")
+    }
+
+    #[test]
+    fn pico8_import_gfx() {
+        let gfx = sheet();
+
+        let footer = footer_from_gfx(&gfx, 0).unwrap();
+        assert_eq!(footer.pixel(point!(0, 0)), Color::Black);
+        assert_eq!(footer.pixel(point!(127, 17)), Color::DarkBlue);
+        assert_eq!(footer.pixel(point!(5, 119)), Color::White);
+
+        let footer = footer_from_gfx(&gfx, 8).unwrap();
+        assert_eq!(footer.pixel(point!(0, 0)), Color::Red);
+        assert_eq!(footer.pixel(point!(5, 119)), Color::LightPeach);
+
+        // rows after the end of the sheet are black.
+        let footer = footer_from_gfx(&gfx, 100).unwrap();
+        assert_eq!(footer.pixel(point!(0, 27)), Color::LightPeach);
+        assert_eq!(footer.pixel(point!(0, 28)), Color::Black);
+
+        let footer = footer_from_gfx("0A\r\n\r\nF", 0).unwrap();
+        assert_eq!(footer.pixel(point!(1, 0)), Color::Yellow);
+        assert_eq!(footer.pixel(point!(0, 1)), Color::LightPeach);
+
+        assert!(footer_from_gfx("00g0", 0).is_err());
+    }
+
+    #[test]
+    fn pico8_import_cart() {
+        let cart = format!(
+            "pico-8 cartridge // http://www.pico-8.com\nversion 41\n__lua__\n-- __gfx__ in a comment\n__gfx__\n{}\n__label__\n7777\n",
+            sheet()
+        );
+
+        assert_eq!(gfx_section(&cart).map(|gfx| gfx.lines().count()), Some(128));
+        assert_eq!(
+            footer_from_cart(&cart, 0).unwrap(),
+            footer_from_gfx(&sheet(), 0).unwrap()
+        );
+
+        assert_eq!(gfx_section("__gfx__"), Some(""));
+        assert!(footer_from_cart("__lua__\n__map__\n", 0).is_err());
+    }
+
+    #[test]
+    fn pico8_export_gfx() {
+        let footer = footer_from_gfx(&sheet(), 3).unwrap();
+        let gfx = footer_to_gfx(&footer);
+
+        assert!(gfx.starts_with("__gfx__\n333"));
+        assert_eq!(gfx.lines().count(), 1 + SHEET_HEIGHT);
+        assert!(gfx
+            .lines()
+            .all(|line| line == "__gfx__" || line.len() == SHEET_WIDTH));
+        assert_eq!(gfx.lines().last(), Some(blank_row().as_str()));
+        assert_eq!(footer_from_cart(&gfx, 0).unwrap(), footer);
+    }
+
+    #[test]
+    fn pico8_patch_cart() {
+        let mut footer = Footer::default();
+        footer.replace_color(Color::Black, Color::Pink, None);
+
+        // replaces the existing section, keeping the rows the texture doesn't cover.
+        let cart = format!("__lua__\n-- code\n__gfx__\n{}\n__sfx__\n0001\n", sheet());
+        let patched = patch_cart(&cart, &footer, 4);
+        let gfx = gfx_section(&patched).unwrap();
+
+        assert!(patched.starts_with("__lua__\n-- code\n__gfx__\n000"));
+        assert!(patched.ends_with("\n__sfx__\n0001\n"));
+        assert_eq!(gfx.lines().count(), SHEET_HEIGHT);
+        assert_eq!(gfx.lines().nth(3), Some("3".repeat(SHEET_WIDTH).as_str()));
+        assert_eq!(gfx.lines().nth(4), Some("e".repeat(SHEET_WIDTH).as_str()));
+        assert_eq!(gfx.lines().nth(123), Some("e".repeat(SHEET_WIDTH).as_str()));
+        assert_eq!(gfx.lines().nth(124), Some("c".repeat(SHEET_WIDTH).as_str()));
+        assert_eq!(footer_from_cart(&patched, 4).unwrap(), footer);
+
+        // adds the section in front of the map.
+        let patched = patch_cart("__lua__\nx=1\n__map__\n01", &footer, 0);
+        assert!(patched.starts_with("__lua__\nx=1\n__gfx__\neee"));
+        assert!(patched.ends_with("\n__map__\n01"));
+
+        let patched = patch_cart("__lua__\nx=1", &footer, 0);
+        assert!(patched.starts_with("__lua__\nx=1\n__gfx__\n"));
+        assert_eq!(footer_from_cart(&patched, 0).unwrap(), footer);
+    }
+}
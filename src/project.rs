@@ -0,0 +1,147 @@
+//! For working with projects without knowing which version of picoCAD created them.
+//!
+//! Right now [`Project`] can only hold a project of the original picoCAD ([`Model`]).
+//! picoCAD 2 projects will be added once their file format is known from real files.
+//! The version is decided by the identifier at the start of the header.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::Model;
+//! use picocadrs::project::{Project, Version};
+//!
+//! let file = Model::default().to_string();
+//! let project: Project = file.parse().unwrap();
+//!
+//! assert_eq!(project.version(), Version::V1);
+//! assert_eq!(project.save_as(Version::V1).unwrap(), file);
+//! ```
+
+use crate::{assets::Model, error::PicoError};
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// Versions of picoCAD that write different project files.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Version {
+    /// The original picoCAD.
+    V1,
+}
+
+impl Version {
+    /// Finds out which version wrote the project file `s` by looking at its header.
+    ///
+    /// Returns [`None`] if the file doesn't start with a known identifier.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::project::Version;
+    ///
+    /// assert_eq!(Version::detect("picocad;house;16;1;0\n{}%"), Some(Version::V1));
+    /// assert_eq!(Version::detect("house"), None);
+    /// ```
+    pub fn detect(s: &str) -> Option<Version> {
+        let identifier = s.trim_start().split([';', '\n']).next()?;
+
+        match identifier.trim() {
+            "picocad" => Some(Version::V1),
+            _ => None,
+        }
+    }
+}
+
+/// A project of any version of picoCAD.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Project {
+    /// Project of the original picoCAD.
+    V1(Model),
+}
+
+impl Project {
+    /// Returns the version of picoCAD the project belongs to.
+    pub fn version(&self) -> Version {
+        match self {
+            Project::V1(_) => Version::V1,
+        }
+    }
+
+    /// Returns the project as it would be stored by the given version of picoCAD.
+    pub fn save_as(&self, version: Version) -> Result<String, PicoError> {
+        Ok(match (self, version) {
+            (Project::V1(model), Version::V1) => model.to_string(),
+        })
+    }
+
+    /// Returns the project as a [`Model`] of the original picoCAD.
+    pub fn into_model(self) -> Model {
+        match self {
+            Project::V1(model) => model,
+        }
+    }
+}
+
+impl From<Model> for Project {
+    fn from(model: Model) -> Self {
+        Project::V1(model)
+    }
+}
+
+impl FromStr for Project {
+    type Err = PicoError;
+
+    /// Parses a project of any version.
+    ///
+    /// Fails with [`PicoError::Identifier`] if the version can't be detected (see
+    /// [`Version::detect`]).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Version::detect(s) {
+            Some(Version::V1) => Ok(Project::V1(s.parse()?)),
+            None => Err(PicoError::Identifier),
+        }
+    }
+}
+
+impl Display for Project {
+    /// Writes the project in the format of the version it belongs to.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Project::V1(model) => write!(f, "{}", model),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    fn project_detect_and_parse() {
+        let v1 = Model::default().to_string();
+
+        let project: Project = v1.parse().unwrap();
+        assert_eq!(project.version(), Version::V1);
+        assert_eq!(project.to_string(), v1);
+
+        assert!(matches!(
+            "picocad2;a;16;1;0\n{}%".parse::<Project>(),
+            Err(PicoError::Identifier)
+        ));
+    }
+
+    #[test]
+    fn project_save_as() {
+        let mut model = Model::default();
+        model.header.name = "house".to_string();
+        let project = Project::from(model.clone());
+
+        let v1 = project.save_as(Version::V1).unwrap();
+        assert_eq!(Version::detect(&v1), Some(Version::V1));
+        assert_eq!(v1, model.to_string());
+        assert_eq!(project.into_model(), model);
+    }
+}
@@ -0,0 +1,96 @@
+//! For finding what a ray hits first, like the face under the cursor of an editor.
+//!
+//! Rays are tested against every face of every mesh using [`Face::intersect_ray`], so the backs
+//! of faces that aren't [`double_sided`](crate::assets::Face::double_sided) can't be hit, the
+//! same way picoCAD doesn't draw them.
+//!
+//! Every call builds a [`Bvh`] over the model first, to cast many rays against the same model
+//! build one with [`Model::bvh`] and use [`Bvh::raycast`] instead.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{MeshFaceRef, Model, Point3D, ProjectPreset};
+//! use picocadrs::point;
+//!
+//! let model = Model::new_project("pick".to_string(), ProjectPreset::Plane);
+//!
+//! // looking straight down onto the plane.
+//! let hit = model
+//!     .raycast(point!(0.0, -5.0, 0.0), point!(0.0, 1.0, 0.0))
+//!     .unwrap();
+//!
+//! assert_eq!(hit.face, MeshFaceRef::new(0, 0));
+//! assert_eq!(hit.hit.point, point!(0.0, 0.0, 0.0));
+//! ```
+
+use crate::{
+    assets::{FaceHit, MeshFaceRef, Model, Point3D},
+    spatial::Bvh,
+};
+
+/// The face a ray hits first, returned by [`raycast`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RayHit {
+    /// The face that was hit.
+    pub face: MeshFaceRef,
+    /// Where the face was hit.
+    /// Unlike the result of [`Face::intersect_ray`](crate::assets::Face::intersect_ray) the
+    /// point is given in world space, the position of the mesh is already added.
+    pub hit: FaceHit,
+}
+
+/// Returns the closest face a ray starting at `origin` and travelling in `direction` hits.
+///
+/// `origin` is given in world space.
+/// Returns [`None`] if the ray doesn't hit any face.
+/// If multiple faces are hit at the same distance, any of them may be returned.
+///
+/// It's recommended to use [`Model::raycast`].
+pub fn raycast(model: &Model, origin: Point3D<f64>, direction: Point3D<f64>) -> Option<RayHit> {
+    Bvh::from_model(model).raycast(model, origin, direction)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Mesh, ProjectPreset};
+    use crate::point;
+
+    #[test]
+    fn raycast_closest() {
+        let mut model = Model::default();
+        model.meshes.push(Mesh::plane());
+        model.meshes.push(Mesh::plane());
+        model.meshes[1].position = point!(0.0, -1.0, 0.0);
+
+        let hit = model
+            .raycast(point!(0.25, -5.0, 0.5), point!(0.0, 2.0, 0.0))
+            .unwrap();
+
+        assert_eq!(hit.face, MeshFaceRef::new(1, 0));
+        assert_eq!(hit.hit.distance, 4.0);
+        assert_eq!(hit.hit.point, point!(0.25, -1.0, 0.5));
+
+        let weights: f64 = hit.hit.barycentric.iter().sum();
+        assert!((weights - 1.0).abs() < 1e-9);
+
+        assert!(model
+            .raycast(point!(10.0, -5.0, 0.0), point!(0.0, 1.0, 0.0))
+            .is_none());
+    }
+
+    #[test]
+    fn raycast_backfaces() {
+        let mut model = Model::new_project("back".to_string(), ProjectPreset::Plane);
+        let from_below = (point!(0.5, 5.0, 0.5), point!(0.0, -1.0, 0.0));
+
+        assert!(model.raycast(from_below.0, from_below.1).is_some());
+
+        model.meshes[0].faces[0].double_sided = false;
+        assert!(model.raycast(from_below.0, from_below.1).is_none());
+        assert!(model
+            .raycast(point!(0.5, -5.0, 0.5), point!(0.0, 1.0, 0.0))
+            .is_some());
+    }
+}
@@ -0,0 +1,194 @@
+//! For swapping the colors of a model.
+//!
+//! Colors show up in three places of a model: the [`color`](crate::assets::Face::color) of each
+//! face, the background and alpha color of the [`Header`](crate::assets::Header) and every pixel of
+//! the [`Footer`](crate::assets::Footer).
+//! [`remap_colors`] replaces colors in all of them at once using a mapping from old to new colors,
+//! which makes creating variants of a model (like a blue version of a red enemy) a single call.
+//!
+//! Colors missing from the mapping are left untouched.
+//! Every place that changed is counted in a [`RemapReport`], which can also be created without
+//! changing the model using [`count_remap`] to see what a mapping would do.
+//!
+//! # Example
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use picocadrs::assets::{Color, Model, Point2D};
+//! use picocadrs::point;
+//!
+//! let mut model = Model::default();
+//! model.footer.set(point!(0, 0), Color::Red).unwrap();
+//! model.footer.set(point!(1, 0), Color::Red).unwrap();
+//!
+//! let mapping = HashMap::from([(Color::Red, Color::Blue)]);
+//!
+//! let report = model.remap_colors_dry_run(&mapping);
+//! assert_eq!(report.pixels[&Color::Red], 2);
+//! assert_eq!(model.footer.pixel(point!(0, 0)), Color::Red);
+//!
+//! assert_eq!(model.remap_colors(&mapping), report);
+//! assert_eq!(model.footer.pixel(point!(0, 0)), Color::Blue);
+//! ```
+
+use crate::{
+    assets::{Color, Footer, Model, Point2D},
+    point,
+};
+use std::collections::HashMap;
+
+/// Amount of places [`remap_colors`] changed, counted by their old color.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RemapReport {
+    /// Faces whose color changed.
+    pub faces: HashMap<Color, usize>,
+    /// Colors of the header that changed, background and alpha color are counted separately.
+    pub header: HashMap<Color, usize>,
+    /// Pixels of the texture that changed.
+    pub pixels: HashMap<Color, usize>,
+}
+
+impl RemapReport {
+    /// Returns the amount of places using `color` that changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use picocadrs::assets::{Color, Model};
+    ///
+    /// let model = Model::default();
+    /// let report = model.remap_colors_dry_run(&HashMap::from([(Color::Black, Color::Red)]));
+    ///
+    /// // every pixel of the texture and the alpha color.
+    /// assert_eq!(report.total(Color::Black), 128 * 120 + 1);
+    /// assert_eq!(report.total(Color::Red), 0);
+    /// ```
+    pub fn total(&self, color: Color) -> usize {
+        [&self.faces, &self.header, &self.pixels]
+            .iter()
+            .filter_map(|counts| counts.get(&color))
+            .sum()
+    }
+
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty() && self.header.is_empty() && self.pixels.is_empty()
+    }
+}
+
+/// Replaces every color of the model found in `mapping` and reports what changed.
+///
+/// It's recommended to use [`Model::remap_colors`].
+pub fn remap_colors(model: &mut Model, mapping: &HashMap<Color, Color>) -> RemapReport {
+    let report = count_remap(model, mapping);
+    let remap = |color: &mut Color| {
+        if let Some(new) = mapping.get(color) {
+            *color = *new;
+        }
+    };
+
+    for face in model
+        .meshes
+        .iter_mut()
+        .flat_map(|mesh| mesh.faces.iter_mut())
+    {
+        remap(&mut face.color);
+    }
+
+    remap(&mut model.header.background);
+    remap(&mut model.header.alpha);
+
+    for v in 0..Footer::HEIGHT {
+        for u in 0..Footer::WIDTH {
+            if let Some(pixel) = model.footer.get_mut(point!(u, v)) {
+                remap(pixel);
+            }
+        }
+    }
+
+    report
+}
+
+/// Reports what [`remap_colors`] would change without changing the model.
+///
+/// It's recommended to use [`Model::remap_colors_dry_run`].
+pub fn count_remap(model: &Model, mapping: &HashMap<Color, Color>) -> RemapReport {
+    let mut report = RemapReport::default();
+    let count = |counts: &mut HashMap<Color, usize>, color: Color| {
+        if mapping.get(&color).is_some_and(|new| *new != color) {
+            *counts.entry(color).or_insert(0) += 1;
+        }
+    };
+
+    for face in model.meshes.iter().flat_map(|mesh| mesh.faces.iter()) {
+        count(&mut report.faces, face.color);
+    }
+
+    count(&mut report.header, model.header.background);
+    count(&mut report.header, model.header.alpha);
+
+    for v in 0..Footer::HEIGHT {
+        for u in 0..Footer::WIDTH {
+            count(&mut report.pixels, model.footer.pixel(point!(u, v)));
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Face, Mesh};
+
+    #[test]
+    fn remap_every_place() {
+        let mut mesh = Mesh::new("enemy".to_string());
+        mesh.faces.push(Face {
+            color: Color::Red,
+            ..Face::default()
+        });
+        mesh.faces.push(Face {
+            color: Color::Green,
+            ..Face::default()
+        });
+
+        let mut model = Model::default();
+        model.meshes.push(mesh);
+        model.header.background = Color::Red;
+        model.footer.set(point!(5, 5), Color::Red).unwrap();
+
+        let mapping = HashMap::from([
+            (Color::Red, Color::Blue),
+            (Color::Blue, Color::Red),
+            (Color::Green, Color::Green),
+        ]);
+        let report = model.remap_colors(&mapping);
+
+        assert_eq!(report.faces, HashMap::from([(Color::Red, 1)]));
+        assert_eq!(report.header, HashMap::from([(Color::Red, 1)]));
+        assert_eq!(report.pixels, HashMap::from([(Color::Red, 1)]));
+        assert_eq!(report.total(Color::Red), 3);
+
+        assert_eq!(model.meshes[0].faces[0].color, Color::Blue);
+        assert_eq!(model.meshes[0].faces[1].color, Color::Green);
+        assert_eq!(model.header.background, Color::Blue);
+        assert_eq!(model.header.alpha, Color::Black);
+        assert_eq!(model.footer.pixel(point!(5, 5)), Color::Blue);
+        assert_eq!(model.footer.pixel(point!(5, 6)), Color::Black);
+
+        // swapping back restores the model.
+        model.remap_colors(&mapping);
+        assert_eq!(model.meshes[0].faces[0].color, Color::Red);
+        assert_eq!(model.footer.pixel(point!(5, 5)), Color::Red);
+    }
+
+    #[test]
+    fn remap_empty_mapping() {
+        let mut model = Model::default();
+
+        assert!(model.remap_colors(&HashMap::new()).is_empty());
+        assert_eq!(model, Model::default());
+    }
+}
@@ -0,0 +1,431 @@
+//! Keeping track of multiple loaded projects at once.
+//!
+//! A [`ProjectRegistry`] owns any amount of [`Model`]s keyed by their name and remembers which
+//! file they were loaded from.
+//! Other parts of a program can [`subscribe`](ProjectRegistry::subscribe) to the registry to get
+//! notified whenever a project is added, saved, reloaded or removed.
+//! This is meant to back multi-document editors or daemons that keep files in sync.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::Model;
+//! use picocadrs::registry::{ProjectRegistry, RegistryEvent};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let events = Arc::new(Mutex::new(vec![]));
+//! let events_clone = Arc::clone(&events);
+//!
+//! let mut registry = ProjectRegistry::new();
+//! registry.subscribe(move |event| events_clone.lock().unwrap().push(event.clone()));
+//!
+//! registry.insert("house".to_string(), Model::default());
+//! registry.remove("house");
+//!
+//! assert_eq!(
+//!     *events.lock().unwrap(),
+//!     vec![
+//!         RegistryEvent::Added("house".to_string()),
+//!         RegistryEvent::Removed("house".to_string())
+//!     ]
+//! );
+//! ```
+
+use crate::{assets::Model, error::PicoError, paths::projects_path};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Things that happened to a project inside a [`ProjectRegistry`].
+/// Each variant holds the name the project is registered under.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RegistryEvent {
+    /// A project was added to the registry.
+    /// Also emitted if a project replaced another one with the same name.
+    Added(String),
+    /// A project was written to its file.
+    Saved(String),
+    /// A project was read from its file again, discarding unsaved changes.
+    Reloaded(String),
+    /// A project was removed from the registry.
+    Removed(String),
+}
+
+impl RegistryEvent {
+    /// Returns the name of the project the event is about.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::registry::RegistryEvent;
+    ///
+    /// assert_eq!(RegistryEvent::Saved("house".to_string()).name(), "house");
+    /// ```
+    pub fn name(&self) -> &str {
+        match self {
+            RegistryEvent::Added(name)
+            | RegistryEvent::Saved(name)
+            | RegistryEvent::Reloaded(name)
+            | RegistryEvent::Removed(name) => name.as_str(),
+        }
+    }
+}
+
+/// Callback that gets notified about [`RegistryEvent`]s.
+type Subscriber = Box<dyn FnMut(&RegistryEvent) + Send>;
+
+/// Identifies a subscription to a [`ProjectRegistry`].
+/// Returned by [`subscribe`](ProjectRegistry::subscribe) and used to
+/// [`unsubscribe`](ProjectRegistry::unsubscribe) again.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SubscriptionId(usize);
+
+/// A project held by the registry and the file it belongs to.
+#[derive(Debug)]
+struct Entry {
+    model: Model,
+    path: Option<PathBuf>,
+}
+
+/// Manages multiple loaded models keyed by name.
+///
+/// Every project can be associated with a file path which is used when saving or reloading it.
+/// Projects without a path are saved into the picoCAD project folder the same way
+/// [`Model::write`] does.
+#[derive(Default)]
+pub struct ProjectRegistry {
+    projects: BTreeMap<String, Entry>,
+    subscribers: Vec<(SubscriptionId, Subscriber)>,
+    next_subscription: usize,
+}
+
+impl ProjectRegistry {
+    /// Creates a registry with no projects and no subscribers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::registry::ProjectRegistry;
+    ///
+    /// let registry = ProjectRegistry::new();
+    /// assert!(registry.is_empty());
+    /// ```
+    pub fn new() -> ProjectRegistry {
+        ProjectRegistry::default()
+    }
+
+    /// Registers a callback that is called with every event that happens in this registry.
+    ///
+    /// Returns an id that can be used to [`unsubscribe`](ProjectRegistry::unsubscribe) again.
+    pub fn subscribe<F>(&mut self, callback: F) -> SubscriptionId
+    where
+        F: FnMut(&RegistryEvent) + Send + 'static,
+    {
+        let id = SubscriptionId(self.next_subscription);
+        self.next_subscription += 1;
+        self.subscribers.push((id, Box::new(callback)));
+
+        id
+    }
+
+    /// Removes a callback registered with [`subscribe`](ProjectRegistry::subscribe).
+    ///
+    /// Returns `false` if there was no subscription with the given id.
+    pub fn unsubscribe(&mut self, id: SubscriptionId) -> bool {
+        let len = self.subscribers.len();
+        self.subscribers
+            .retain(|(subscription, _)| *subscription != id);
+
+        len != self.subscribers.len()
+    }
+
+    /// Adds a model under the given name that isn't associated with any file.
+    ///
+    /// If there already was a project with that name it is replaced and returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Model;
+    /// use picocadrs::registry::ProjectRegistry;
+    ///
+    /// let mut registry = ProjectRegistry::new();
+    ///
+    /// assert!(registry.insert("house".to_string(), Model::default()).is_none());
+    /// assert!(registry.insert("house".to_string(), Model::default()).is_some());
+    /// assert_eq!(registry.len(), 1);
+    /// ```
+    pub fn insert(&mut self, name: String, model: Model) -> Option<Model> {
+        self.insert_entry(name, Entry { model, path: None })
+    }
+
+    /// Loads a model from the given path and adds it to the registry.
+    ///
+    /// The project is registered under the file name without its extension, which is returned.
+    /// Any project that was registered under the same name will be replaced.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<String, PicoError> {
+        let path = path.as_ref().to_path_buf();
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let model = Model::load_from_path(path.clone().into_os_string())?;
+
+        self.insert_entry(
+            name.clone(),
+            Entry {
+                model,
+                path: Some(path),
+            },
+        );
+
+        Ok(name)
+    }
+
+    /// Removes the project with the given name from the registry and returns it.
+    pub fn remove(&mut self, name: &str) -> Option<Model> {
+        let entry = self.projects.remove(name)?;
+        self.notify(RegistryEvent::Removed(name.to_string()));
+
+        Some(entry.model)
+    }
+
+    /// Returns a reference to the project with the given name.
+    pub fn get(&self, name: &str) -> Option<&Model> {
+        self.projects.get(name).map(|entry| &entry.model)
+    }
+
+    /// Returns a mutable reference to the project with the given name.
+    ///
+    /// Changes are only written to the file once the project is
+    /// [`save`](ProjectRegistry::save)d.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Model> {
+        self.projects.get_mut(name).map(|entry| &mut entry.model)
+    }
+
+    /// Returns the path of the file the project with the given name belongs to.
+    ///
+    /// Returns [`None`] if there is no such project or it isn't associated with a file.
+    pub fn path(&self, name: &str) -> Option<&Path> {
+        self.projects.get(name)?.path.as_deref()
+    }
+
+    /// Associates the project with the given name with a file.
+    /// The next [`save`](ProjectRegistry::save) will write to that file.
+    ///
+    /// Returns [`PicoError::UnknownProject`] if no project with that name is registered.
+    pub fn set_path<P: AsRef<Path>>(&mut self, name: &str, path: P) -> Result<(), PicoError> {
+        let entry = self.entry_mut(name)?;
+        entry.path = Some(path.as_ref().to_path_buf());
+
+        Ok(())
+    }
+
+    /// Writes the project with the given name to its file.
+    ///
+    /// If the project isn't associated with a file it is written into the picoCAD project folder
+    /// named after [`header.name`](crate::assets::Header) and associated with that file from now
+    /// on.
+    ///
+    /// Returns [`PicoError::UnknownProject`] if no project with that name is registered.
+    pub fn save(&mut self, name: &str) -> Result<(), PicoError> {
+        let entry = self.entry_mut(name)?;
+
+        let path = match &entry.path {
+            Some(path) => path.clone(),
+            None => {
+                let mut path = PathBuf::from(projects_path().ok_or(PicoError::NoHomeDirectory)?);
+                path.push(entry.model.header.name.clone());
+                path.set_extension("txt");
+                path
+            }
+        };
+
+        std::fs::write(&path, entry.model.to_string())?;
+        entry.path = Some(path);
+
+        self.notify(RegistryEvent::Saved(name.to_string()));
+
+        Ok(())
+    }
+
+    /// Writes every project in the registry to its file.
+    ///
+    /// Stops at the first project that can't be saved.
+    pub fn save_all(&mut self) -> Result<(), PicoError> {
+        let names: Vec<String> = self.projects.keys().cloned().collect();
+
+        for name in names {
+            self.save(name.as_str())?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads the project with the given name from its file again.
+    /// Any changes that have not been saved are lost.
+    ///
+    /// Returns [`PicoError::UnknownProject`] if no project with that name is registered and
+    /// [`PicoError::NoProjectPath`] if it isn't associated with a file.
+    pub fn reload(&mut self, name: &str) -> Result<(), PicoError> {
+        let entry = self.entry_mut(name)?;
+        let path = entry
+            .path
+            .clone()
+            .ok_or_else(|| PicoError::NoProjectPath(name.to_string()))?;
+
+        entry.model = Model::load_from_path(path.into_os_string())?;
+
+        self.notify(RegistryEvent::Reloaded(name.to_string()));
+
+        Ok(())
+    }
+
+    /// Returns an iterator over the names of all registered projects in alphabetical order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.projects.keys().map(|name| name.as_str())
+    }
+
+    /// Returns an iterator over all registered projects and their names in alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Model)> {
+        self.projects
+            .iter()
+            .map(|(name, entry)| (name.as_str(), &entry.model))
+    }
+
+    /// Returns the amount of registered projects.
+    pub fn len(&self) -> usize {
+        self.projects.len()
+    }
+
+    /// Returns `true` if there are no registered projects.
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty()
+    }
+
+    fn insert_entry(&mut self, name: String, entry: Entry) -> Option<Model> {
+        let previous = self.projects.insert(name.clone(), entry);
+        self.notify(RegistryEvent::Added(name));
+
+        previous.map(|entry| entry.model)
+    }
+
+    fn entry_mut(&mut self, name: &str) -> Result<&mut Entry, PicoError> {
+        self.projects
+            .get_mut(name)
+            .ok_or_else(|| PicoError::UnknownProject(name.to_string()))
+    }
+
+    fn notify(&mut self, event: RegistryEvent) {
+        for (_, subscriber) in self.subscribers.iter_mut() {
+            subscriber(&event);
+        }
+    }
+}
+
+impl std::fmt::Debug for ProjectRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProjectRegistry")
+            .field("projects", &self.projects)
+            .field("subscribers", &self.subscribers.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn recording_registry() -> (ProjectRegistry, Arc<Mutex<Vec<RegistryEvent>>>) {
+        let events = Arc::new(Mutex::new(vec![]));
+        let events_clone = Arc::clone(&events);
+
+        let mut registry = ProjectRegistry::new();
+        registry.subscribe(move |event| events_clone.lock().unwrap().push(event.clone()));
+
+        (registry, events)
+    }
+
+    #[test]
+    fn registry_insert_remove() {
+        let (mut registry, events) = recording_registry();
+
+        assert!(registry.insert("a".to_string(), Model::default()).is_none());
+        assert!(registry.insert("b".to_string(), Model::default()).is_none());
+        assert_eq!(registry.names().collect::<Vec<&str>>(), vec!["a", "b"]);
+        assert!(registry.path("a").is_none());
+
+        assert!(registry.remove("a").is_some());
+        assert!(registry.remove("a").is_none());
+        assert_eq!(registry.len(), 1);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                RegistryEvent::Added("a".to_string()),
+                RegistryEvent::Added("b".to_string()),
+                RegistryEvent::Removed("a".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn registry_unsubscribe() {
+        let (mut registry, events) = recording_registry();
+        let id = registry.subscribe(|_| {});
+
+        assert!(registry.unsubscribe(id));
+        assert!(!registry.unsubscribe(id));
+        assert!(registry.unsubscribe(SubscriptionId(0)));
+
+        registry.insert("a".to_string(), Model::default());
+        assert!(events.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn registry_save_reload() {
+        let dir = std::env::temp_dir().join("picocadrs_registry_save_reload");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("house.txt");
+
+        let (mut registry, events) = recording_registry();
+        registry.insert("house".to_string(), Model::default());
+        registry.set_path("house", &path).unwrap();
+        registry.save("house").unwrap();
+
+        registry.get_mut("house").unwrap().header.name = "changed".to_string();
+        registry.reload("house").unwrap();
+        assert_eq!(registry.get("house").unwrap().header.name, "unnamed");
+
+        assert_eq!(registry.load(&path).unwrap(), "house");
+        assert_eq!(registry.path("house"), Some(path.as_path()));
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                RegistryEvent::Added("house".to_string()),
+                RegistryEvent::Saved("house".to_string()),
+                RegistryEvent::Reloaded("house".to_string()),
+                RegistryEvent::Added("house".to_string()),
+            ]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn registry_errors() {
+        let mut registry = ProjectRegistry::new();
+        registry.insert("house".to_string(), Model::default());
+
+        assert!(matches!(
+            registry.save("tree"),
+            Err(PicoError::UnknownProject(_))
+        ));
+        assert!(matches!(
+            registry.reload("house"),
+            Err(PicoError::NoProjectPath(_))
+        ));
+    }
+}
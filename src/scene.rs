@@ -0,0 +1,357 @@
+//! For building models out of nested groups of meshes.
+//!
+//! picoCAD only knows a flat list of meshes, which gets hard to manage for models made of many
+//! parts, like a character with arms and legs that each consist of multiple meshes.
+//! A [`Scene`] instead holds a tree of [`Group`]s.
+//! Each group has its own [`Transform`] that moves, rotates and scales everything inside of it,
+//! including other groups.
+//!
+//! Once done, [`Scene::flatten`] bakes all transforms into the vertices of the meshes and returns
+//! a [`Model`] picoCAD can open.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Mesh, Point3D};
+//! use picocadrs::point;
+//! use picocadrs::scene::{Group, Node, Scene, Transform};
+//!
+//! let mut hand = Mesh::new("hand".to_string());
+//! hand.vertices.push(point!(0.0, 0.0, 0.0));
+//!
+//! let arm = Group {
+//!     name: "arm".to_string(),
+//!     transform: Transform::translation(point!(1.0, 0.0, 0.0)),
+//!     children: vec![Node::Mesh(hand)],
+//! };
+//!
+//! let mut scene = Scene::default();
+//! scene.root.transform = Transform::translation(point!(0.0, -1.0, 0.0));
+//! scene.root.children.push(Node::Group(arm));
+//!
+//! let model = scene.flatten();
+//!
+//! assert_eq!(model.meshes[0].position, point!(1.0, -1.0, 0.0));
+//! ```
+
+use crate::{
+    assets::{Footer, Header, Mesh, Model, Point3D},
+    point,
+};
+use std::f64::consts::TAU;
+
+/// Moves, rotates and scales everything it is applied to.
+///
+/// Points are scaled first, then rotated around the `x`, `y` and `z` axis in that order and
+/// moved last.
+/// Like [`Rotation`](crate::assets::Rotation), rotations are given in turns, so `1.0` is a full
+/// rotation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transform {
+    /// Distance points are moved by.
+    pub translation: Point3D<f64>,
+    /// Rotation around each axis in turns.
+    pub rotation: Point3D<f64>,
+    /// Factor points are scaled by on each axis.
+    pub scale: Point3D<f64>,
+}
+
+impl Transform {
+    /// Creates a transform that only moves points by `translation`.
+    pub fn translation(translation: Point3D<f64>) -> Transform {
+        Transform {
+            translation,
+            ..Transform::default()
+        }
+    }
+
+    /// Creates a transform that only rotates points by `rotation`, given in turns.
+    pub fn rotation(rotation: Point3D<f64>) -> Transform {
+        Transform {
+            rotation,
+            ..Transform::default()
+        }
+    }
+
+    /// Creates a transform that only scales points by `scale`.
+    pub fn scale(scale: Point3D<f64>) -> Transform {
+        Transform {
+            scale,
+            ..Transform::default()
+        }
+    }
+
+    /// Applies the transform to a point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    /// use picocadrs::point;
+    /// use picocadrs::scene::Transform;
+    ///
+    /// let transform = Transform {
+    ///     translation: point!(1.0, 0.0, 0.0),
+    ///     rotation: point!(0.0, 0.0, 0.25),
+    ///     scale: point!(2.0, 2.0, 2.0),
+    /// };
+    ///
+    /// let moved = transform.apply(point!(1.0, 0.0, 0.0));
+    /// assert!((moved - point!(1.0, 2.0, 0.0)).length() < 1e-9);
+    /// ```
+    pub fn apply(&self, point: Point3D<f64>) -> Point3D<f64> {
+        let mut p = point!(
+            point.x * self.scale.x,
+            point.y * self.scale.y,
+            point.z * self.scale.z
+        );
+
+        let (sin, cos) = (self.rotation.x * TAU).sin_cos();
+        p = point!(p.x, p.y * cos - p.z * sin, p.y * sin + p.z * cos);
+
+        let (sin, cos) = (self.rotation.y * TAU).sin_cos();
+        p = point!(p.x * cos + p.z * sin, p.y, -p.x * sin + p.z * cos);
+
+        let (sin, cos) = (self.rotation.z * TAU).sin_cos();
+        p = point!(p.x * cos - p.y * sin, p.x * sin + p.y * cos, p.z);
+
+        p + self.translation
+    }
+
+    /// Checks if the transform mirrors points, which turns faces inside out.
+    pub fn mirrors(&self) -> bool {
+        self.scale.x * self.scale.y * self.scale.z < 0.0
+    }
+}
+
+impl Default for Transform {
+    /// Creates a transform that leaves points where they are.
+    fn default() -> Self {
+        Transform {
+            translation: point!(0.0, 0.0, 0.0),
+            rotation: point!(0.0, 0.0, 0.0),
+            scale: point!(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+/// Part of a [`Scene`], either a mesh or a group of other nodes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    /// A single mesh.
+    Mesh(Mesh),
+    /// A group of nodes sharing a transform.
+    Group(Group),
+}
+
+/// Nodes sharing a transform.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    /// Name of the group.
+    /// Only used to find groups again, it isn't stored in the flattened model.
+    pub name: String,
+    /// Transform applied to every node inside the group.
+    pub transform: Transform,
+    /// Nodes inside the group.
+    pub children: Vec<Node>,
+}
+
+impl Group {
+    /// Creates a new empty group with the given name that doesn't change its children.
+    pub fn new(name: String) -> Group {
+        Group {
+            name,
+            transform: Transform::default(),
+            children: vec![],
+        }
+    }
+
+    /// Finds the first group with the given name inside this group, including itself.
+    /// Groups are searched depth first.
+    pub fn find_group_mut(&mut self, name: &str) -> Option<&mut Group> {
+        if self.name == name {
+            return Some(self);
+        }
+
+        self.children.iter_mut().find_map(|child| match child {
+            Node::Group(group) => group.find_group_mut(name),
+            Node::Mesh(_) => None,
+        })
+    }
+
+    /// Adds the meshes inside this group with all transforms applied to `meshes`.
+    /// `parents` are the transforms of the groups above this one, starting with the closest.
+    fn flatten_into(&self, parents: &mut Vec<Transform>, meshes: &mut Vec<Mesh>) {
+        parents.insert(0, self.transform);
+
+        for child in self.children.iter() {
+            match child {
+                Node::Mesh(mesh) => meshes.push(transform_mesh(mesh, parents)),
+                Node::Group(group) => group.flatten_into(parents, meshes),
+            }
+        }
+
+        parents.remove(0);
+    }
+}
+
+/// A tree of meshes that can be turned into a [`Model`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scene {
+    /// Header of the flattened model.
+    pub header: Header,
+    /// Group holding every node of the scene.
+    pub root: Group,
+    /// Texture of the flattened model.
+    pub footer: Footer,
+}
+
+impl Scene {
+    /// Bakes the transforms of all groups into their meshes and returns them as a model.
+    ///
+    /// Meshes are added in the order they appear in the tree, depth first.
+    /// The position of each mesh is transformed along with its vertices, so meshes keep their
+    /// anchor point.
+    pub fn flatten(&self) -> Model {
+        let mut meshes: Vec<Mesh> = vec![];
+        self.root.flatten_into(&mut vec![], &mut meshes);
+
+        Model {
+            header: self.header.clone(),
+            meshes,
+            footer: self.footer.clone(),
+        }
+    }
+}
+
+impl Default for Scene {
+    /// Creates an empty scene with a default header and footer.
+    fn default() -> Self {
+        Scene {
+            header: Header::default(),
+            root: Group::new("root".to_string()),
+            footer: Footer::default(),
+        }
+    }
+}
+
+impl From<Model> for Scene {
+    /// Puts all meshes of the model into the root group of a new scene.
+    fn from(model: Model) -> Self {
+        let mut root = Group::new("root".to_string());
+        root.children = model.meshes.into_iter().map(Node::Mesh).collect();
+
+        Scene {
+            header: model.header,
+            root,
+            footer: model.footer,
+        }
+    }
+}
+
+/// Applies the transforms to the mesh, starting with the first one.
+fn transform_mesh(mesh: &Mesh, transforms: &[Transform]) -> Mesh {
+    let apply = |point: Point3D<f64>| {
+        transforms
+            .iter()
+            .fold(point, |point, transform| transform.apply(point))
+    };
+
+    let mut result = mesh.clone();
+    result.position = apply(mesh.position);
+    result.vertices = mesh
+        .vertices
+        .iter()
+        .map(|vertex| apply(*vertex + mesh.position) - result.position)
+        .collect();
+
+    if transforms.iter().filter(|t| t.mirrors()).count() % 2 == 1 {
+        for face in result.faces.iter_mut() {
+            face.uv_maps.reverse();
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::{Face, Point2D, UVMap};
+
+    fn triangle() -> Mesh {
+        let mut mesh = Mesh::new("triangle".to_string());
+        mesh.position = point!(1.0, 0.0, 0.0);
+        mesh.vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(1.0, 0.0, 0.0),
+            point!(0.0, 0.0, 1.0),
+        ];
+        mesh.faces.push(Face {
+            uv_maps: (0..3).map(|i| UVMap::new(i, point!(0.0, 0.0))).collect(),
+            ..Face::default()
+        });
+
+        mesh
+    }
+
+    fn close(a: Point3D<f64>, b: Point3D<f64>) -> bool {
+        (a - b).length() < 1e-9
+    }
+
+    #[test]
+    fn scene_nested_groups() {
+        let mut inner = Group::new("inner".to_string());
+        inner.transform = Transform::rotation(point!(0.0, 0.25, 0.0));
+        inner.children.push(Node::Mesh(triangle()));
+
+        let mut scene = Scene::default();
+        scene.root.transform = Transform::translation(point!(0.0, 0.0, 5.0));
+        scene.root.children.push(Node::Group(inner));
+        scene.root.children.push(Node::Mesh(triangle()));
+
+        let model = scene.flatten();
+        assert_eq!(model.meshes.len(), 2);
+
+        // rotated a quarter around y, then moved.
+        let mesh = &model.meshes[0];
+        assert!(close(mesh.position, point!(0.0, 0.0, 4.0)));
+        assert!(close(mesh.vertices[1], point!(0.0, 0.0, -1.0)));
+        assert!(close(mesh.vertices[2], point!(1.0, 0.0, 0.0)));
+
+        let mesh = &model.meshes[1];
+        assert!(close(mesh.position, point!(1.0, 0.0, 5.0)));
+        assert_eq!(mesh.vertices, triangle().vertices);
+    }
+
+    #[test]
+    fn scene_mirroring_keeps_faces_outside() {
+        let normal = triangle().faces[0].normal(&triangle().vertices);
+
+        let mut scene = Scene::from(Model {
+            meshes: vec![triangle()],
+            ..Model::default()
+        });
+        scene.root.transform = Transform::scale(point!(1.0, -1.0, 1.0));
+
+        let model = scene.flatten();
+        let mesh = &model.meshes[0];
+
+        assert!(close(mesh.faces[0].normal(&mesh.vertices), -normal));
+    }
+
+    #[test]
+    fn scene_find_group() {
+        let mut scene = Scene::default();
+        scene
+            .root
+            .children
+            .push(Node::Group(Group::new("arm".to_string())));
+
+        scene.root.find_group_mut("arm").unwrap().transform =
+            Transform::translation(point!(1.0, 0.0, 0.0));
+
+        assert!(scene.root.find_group_mut("leg").is_none());
+        assert!(scene.root.find_group_mut("root").is_some());
+    }
+}
@@ -0,0 +1,305 @@
+//! For selecting meshes and faces of a model to edit them in bulk.
+//!
+//! A selection starts with every mesh of a model (see [`Model::select`]) and is narrowed down by
+//! chaining filters.
+//! [`MeshSelection::faces`] turns a selection of meshes into a selection of all their faces.
+//! Selections hold mutable references, which can be iterated over to edit the selected parts.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Color, Face, Mesh, Model};
+//!
+//! let mut model = Model::default();
+//! for name in ["leg_left", "leg_right", "body"] {
+//!     let mut mesh = Mesh::new(name.to_string());
+//!     mesh.faces.push(Face {
+//!         color: Color::Red,
+//!         ..Face::default()
+//!     });
+//!     model.meshes.push(mesh);
+//! }
+//!
+//! // make every red face of the legs ignore shading.
+//! for face in model.select().meshes_named("leg*").faces().with_color(Color::Red) {
+//!     face.no_shading = true;
+//! }
+//!
+//! assert!(model.meshes[0].faces[0].no_shading);
+//! assert!(model.meshes[1].faces[0].no_shading);
+//! assert!(!model.meshes[2].faces[0].no_shading);
+//! ```
+
+use crate::assets::{Color, Face, Mesh, Model};
+
+/// A selection of meshes.
+///
+/// Created using [`Model::select`].
+#[derive(Debug)]
+pub struct MeshSelection<'a> {
+    meshes: Vec<&'a mut Mesh>,
+}
+
+impl<'a> MeshSelection<'a> {
+    /// Selects every mesh of the model.
+    ///
+    /// It's recommended to use [`Model::select`].
+    pub fn new(model: &'a mut Model) -> MeshSelection<'a> {
+        MeshSelection {
+            meshes: model.meshes.iter_mut().collect(),
+        }
+    }
+
+    /// Keeps the meshes for which `predicate` returns true.
+    pub fn filter<F: FnMut(&Mesh) -> bool>(mut self, mut predicate: F) -> MeshSelection<'a> {
+        self.meshes.retain(|mesh| predicate(mesh));
+        self
+    }
+
+    /// Keeps the meshes whose name matches `pattern`.
+    ///
+    /// In the pattern `*` matches any amount of characters and `?` matches exactly one character.
+    /// Every other character has to match exactly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Mesh, Model};
+    ///
+    /// let mut model = Model::default();
+    /// for name in ["leg_1", "leg_2", "leg_10", "arm_1"] {
+    ///     model.meshes.push(Mesh::new(name.to_string()));
+    /// }
+    ///
+    /// assert_eq!(model.select().meshes_named("leg*").len(), 3);
+    /// assert_eq!(model.select().meshes_named("leg_?").len(), 2);
+    /// assert_eq!(model.select().meshes_named("*_1").len(), 2);
+    /// ```
+    pub fn meshes_named(self, pattern: &str) -> MeshSelection<'a> {
+        let pattern: Vec<char> = pattern.chars().collect();
+
+        self.filter(|mesh| {
+            let name: Vec<char> = mesh.name.chars().collect();
+            matches_pattern(&pattern, &name)
+        })
+    }
+
+    /// Selects every face of the selected meshes.
+    pub fn faces(self) -> FaceSelection<'a> {
+        FaceSelection {
+            faces: self
+                .meshes
+                .into_iter()
+                .flat_map(|mesh| mesh.faces.iter_mut())
+                .collect(),
+        }
+    }
+
+    /// Returns the amount of selected meshes.
+    pub fn len(&self) -> usize {
+        self.meshes.len()
+    }
+
+    /// Checks if no mesh is selected.
+    pub fn is_empty(&self) -> bool {
+        self.meshes.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for MeshSelection<'a> {
+    type Item = &'a mut Mesh;
+    type IntoIter = std::vec::IntoIter<&'a mut Mesh>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.meshes.into_iter()
+    }
+}
+
+/// A selection of faces.
+///
+/// Created using [`MeshSelection::faces`].
+#[derive(Debug)]
+pub struct FaceSelection<'a> {
+    faces: Vec<&'a mut Face>,
+}
+
+impl<'a> FaceSelection<'a> {
+    /// Keeps the faces for which `predicate` returns true.
+    pub fn filter<F: FnMut(&Face) -> bool>(mut self, mut predicate: F) -> FaceSelection<'a> {
+        self.faces.retain(|face| predicate(face));
+        self
+    }
+
+    /// Keeps the faces that have the given color.
+    pub fn with_color(self, color: Color) -> FaceSelection<'a> {
+        self.filter(|face| face.color == color)
+    }
+
+    /// Keeps the faces whose [`double_sided`](Face::double_sided) flag is `value`.
+    pub fn double_sided(self, value: bool) -> FaceSelection<'a> {
+        self.filter(|face| face.double_sided == value)
+    }
+
+    /// Keeps the faces whose [`no_shading`](Face::no_shading) flag is `value`.
+    pub fn no_shading(self, value: bool) -> FaceSelection<'a> {
+        self.filter(|face| face.no_shading == value)
+    }
+
+    /// Keeps the faces whose [`no_texture`](Face::no_texture) flag is `value`.
+    pub fn no_texture(self, value: bool) -> FaceSelection<'a> {
+        self.filter(|face| face.no_texture == value)
+    }
+
+    /// Keeps the faces whose [`render_priority`](Face::render_priority) flag is `value`.
+    pub fn render_priority(self, value: bool) -> FaceSelection<'a> {
+        self.filter(|face| face.render_priority == value)
+    }
+
+    /// Returns the amount of selected faces.
+    pub fn len(&self) -> usize {
+        self.faces.len()
+    }
+
+    /// Checks if no face is selected.
+    pub fn is_empty(&self) -> bool {
+        self.faces.is_empty()
+    }
+}
+
+impl<'a> IntoIterator for FaceSelection<'a> {
+    type Item = &'a mut Face;
+    type IntoIter = std::vec::IntoIter<&'a mut Face>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.faces.into_iter()
+    }
+}
+
+/// Checks if `name` matches `pattern`, where `*` matches any amount of characters and `?` exactly
+/// one.
+///
+/// Only the last `*` is ever backtracked to, so this takes at most
+/// `pattern.len() * name.len()` steps.
+fn matches_pattern(pattern: &[char], name: &[char]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // position of the last `*` in the pattern and of the character in the name it matched up to.
+    let mut star: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                // let the last `*` match one more character and try again.
+                Some((star_p, star_n)) => {
+                    star = Some((star_p, star_n + 1));
+                    p = star_p + 1;
+                    n = star_n + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, name: &str) -> bool {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+
+        matches_pattern(&pattern, &name)
+    }
+
+    #[test]
+    fn select_pattern() {
+        assert!(matches("leg*", "leg"));
+        assert!(matches("leg*", "leg_left"));
+        assert!(matches("*", ""));
+        assert!(matches("*left", "leg_left"));
+        assert!(matches("l?g*t", "leg_left"));
+        assert!(matches("body", "body"));
+
+        assert!(!matches("leg*", "arm_leg"));
+        assert!(!matches("leg?", "leg"));
+        assert!(!matches("body", "body_2"));
+        assert!(!matches("", "body"));
+    }
+
+    #[test]
+    fn select_pattern_backtracking() {
+        let name = "a".repeat(200);
+
+        assert!(!matches("*a*a*a*a*a*a*a*b", &name));
+        assert!(matches("*a*a*a*a*a*a*a*", &name));
+        assert!(matches("*?*b*", "aaab_a"));
+        assert!(!matches("a*?", "a"));
+    }
+
+    #[test]
+    fn select_faces() {
+        let mut model = Model::default();
+
+        for name in ["cube", "plane"] {
+            let mut mesh = Mesh::new(name.to_string());
+            mesh.faces.push(Face {
+                color: Color::Red,
+                double_sided: true,
+                ..Face::default()
+            });
+            mesh.faces.push(Face {
+                color: Color::Red,
+                double_sided: false,
+                ..Face::default()
+            });
+            mesh.faces.push(Face {
+                color: Color::Blue,
+                double_sided: true,
+                ..Face::default()
+            });
+            model.meshes.push(mesh);
+        }
+
+        assert_eq!(model.select().len(), 2);
+        assert_eq!(model.select().faces().len(), 6);
+        assert_eq!(model.select().faces().with_color(Color::Red).len(), 4);
+        assert!(model.select().faces().with_color(Color::Green).is_empty());
+
+        for face in model
+            .select()
+            .meshes_named("plane")
+            .faces()
+            .with_color(Color::Red)
+            .double_sided(true)
+        {
+            face.render_priority = true;
+        }
+
+        assert_eq!(model.select().faces().render_priority(true).len(), 1);
+        assert!(model.meshes[1].faces[0].render_priority);
+    }
+
+    #[test]
+    fn select_meshes() {
+        let mut model = Model::default();
+        model.meshes.push(Mesh::new("a".to_string()));
+        model.meshes.push(Mesh::new("b".to_string()));
+
+        for mesh in model.select().filter(|mesh| mesh.name == "b") {
+            mesh.name = "c".to_string();
+        }
+
+        assert_eq!(model.meshes[1].name, "c");
+        assert!(model.select().meshes_named("b").is_empty());
+    }
+}
@@ -0,0 +1,477 @@
+//! For darkening faces the way picoCAD does.
+//!
+//! picoCAD doesn't blend colors to shade faces, it swaps them for darker colors of the palette.
+//! Faces facing the light keep their color, faces at an angle get the color returned by
+//! [`Color::shadow_transition`] and faces facing away get the color returned by
+//! [`Color::shadow`].
+//! Which of these a face gets is decided by [`Shade::from_intensity`], using lambert shading:
+//! the dot product of the face's [`normal`](Face::normal) and the direction towards the light.
+//!
+//! The shading can be baked into a model in two ways:
+//!
+//! - [`bake_shading`] changes the color of each face.
+//! - [`shaded_texture`] returns a copy of the texture with the pixels of each face darkened.
+//!
+//! Faces with [`no_shading`](Face::no_shading) are never shaded.
+//! Double-sided faces are lit the same from both sides.
+//!
+//! [`bake_shading`] and [`shaded_texture`] take the direction of the light in model space, so the
+//! result doesn't depend on the camera.
+//! Inside picoCAD, the light a mesh is shaded with shines relative to the camera instead and is
+//! turned by the [`rotation`](crate::assets::Mesh::rotation) of the mesh (see [`Rotation`]).
+//! Meshes copied from other projects or rotated on their own end up with different rotations, so
+//! their shadows don't agree.
+//! [`unify_lighting`] gives every mesh the same rotation, taken from a reference mesh or made to
+//! point the light in a given direction relative to the camera (see [`light_direction`]).
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Color, Model, Point3D, ProjectPreset};
+//! use picocadrs::point;
+//!
+//! // the plane faces up, so a light shining down lights it fully.
+//! let mut model = Model::new_project("lit".to_string(), ProjectPreset::Plane);
+//! model.bake_shading(point!(0.0, 1.0, 0.0));
+//! assert_eq!(model.meshes[0].faces[0].color, Color::LightGrey);
+//!
+//! // light shining from the side only grazes it.
+//! let mut model = Model::new_project("shaded".to_string(), ProjectPreset::Plane);
+//! model.bake_shading(point!(1.0, 0.0, 0.0));
+//! assert_eq!(model.meshes[0].faces[0].color, Color::DarkGrey);
+//! ```
+
+use crate::{
+    analysis::usage::TextureUsage,
+    assets::{Color, Face, Footer, Mesh, Model, Point2D, Point3D, Rotation},
+    error::PicoError,
+    point,
+};
+use std::f64::consts::TAU;
+
+/// Intensity below which a face is drawn with [`Color::shadow`].
+pub const SHADOW_THRESHOLD: f64 = 0.35;
+/// Intensity below which a face is drawn with [`Color::shadow_transition`].
+pub const SHADOW_TRANSITION_THRESHOLD: f64 = 0.7;
+
+/// Direction the light travels in for meshes without rotation, relative to the camera: away from
+/// the camera, so it shines on the side of the mesh the camera looks at.
+///
+/// Directions relative to the camera have `x` pointing right, `y` pointing down like in picoCAD
+/// and `z` pointing away from the camera.
+pub const BASE_LIGHT_DIR: Point3D<f64> = Point3D {
+    x: 0.0,
+    y: 0.0,
+    z: 1.0,
+};
+
+/// What [`unify_lighting`] aligns the lighting of every mesh with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LightingReference {
+    /// The rotation of the first mesh with this name.
+    Mesh(String),
+    /// This rotation.
+    Rotation(Rotation),
+    /// The rotation that turns [`BASE_LIGHT_DIR`] into this direction relative to the camera, see
+    /// [`rotation_towards`].
+    Direction(Point3D<f64>),
+}
+
+/// Meshes changed by [`unify_lighting`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LightingReport {
+    /// The rotation every mesh has now.
+    pub rotation: Rotation,
+    /// Indices of the meshes whose rotation changed, in ascending order.
+    pub changed: Vec<usize>,
+}
+
+/// How much light a face gets.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Shade {
+    /// The face keeps its color.
+    #[default]
+    Lit,
+    /// The face is drawn with [`Color::shadow_transition`].
+    Transition,
+    /// The face is drawn with [`Color::shadow`].
+    Shadow,
+}
+
+impl Shade {
+    /// Returns the shade of a face given the dot product of its normal and the direction towards
+    /// the light (or camera).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::shading::Shade;
+    ///
+    /// assert_eq!(Shade::from_intensity(1.0), Shade::Lit);
+    /// assert_eq!(Shade::from_intensity(0.5), Shade::Transition);
+    /// assert_eq!(Shade::from_intensity(-1.0), Shade::Shadow);
+    /// ```
+    pub fn from_intensity(intensity: f64) -> Shade {
+        if intensity < SHADOW_THRESHOLD {
+            Shade::Shadow
+        } else if intensity < SHADOW_TRANSITION_THRESHOLD {
+            Shade::Transition
+        } else {
+            Shade::Lit
+        }
+    }
+
+    /// Returns the color `color` is drawn with under this shade.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Color;
+    /// use picocadrs::shading::Shade;
+    ///
+    /// assert_eq!(Shade::Lit.apply(Color::Orange), Color::Orange);
+    /// assert_eq!(Shade::Transition.apply(Color::Orange), Color::Brown);
+    /// assert_eq!(Shade::Shadow.apply(Color::Orange), Color::DarkPurple);
+    /// ```
+    pub fn apply(&self, color: Color) -> Color {
+        match self {
+            Shade::Lit => color,
+            Shade::Transition => color.shadow_transition(),
+            Shade::Shadow => color.shadow(),
+        }
+    }
+}
+
+/// Returns the direction light travels in for a mesh with the given rotation, relative to the
+/// camera.
+///
+/// This is [`BASE_LIGHT_DIR`] turned by the rotation, given in turns: first around the `x`-axis,
+/// then around the `y`-axis and last around the `z`-axis.
+/// A rotation of `0.5` around the `x`- or `y`-axis makes the light travel towards the camera, so
+/// it shines from the side opposite the camera.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Point3D, Rotation};
+/// use picocadrs::point;
+/// use picocadrs::shading::light_direction;
+///
+/// let light = light_direction(Rotation(point!(0.5, 0.0, 0.0)));
+/// assert!((light - point!(0.0, 0.0, -1.0)).length() < 1e-9);
+///
+/// // a quarter turn around the x-axis makes the light travel up.
+/// let light = light_direction(Rotation(point!(0.25, 0.0, 0.0)));
+/// assert!((light - point!(0.0, -1.0, 0.0)).length() < 1e-9);
+/// ```
+pub fn light_direction(rotation: Rotation) -> Point3D<f64> {
+    let mut light = BASE_LIGHT_DIR;
+
+    let (sin, cos) = (rotation.0.x * TAU).sin_cos();
+    light = point!(
+        light.x,
+        light.y * cos - light.z * sin,
+        light.y * sin + light.z * cos
+    );
+
+    let (sin, cos) = (rotation.0.y * TAU).sin_cos();
+    light = point!(
+        light.x * cos + light.z * sin,
+        light.y,
+        -light.x * sin + light.z * cos
+    );
+
+    let (sin, cos) = (rotation.0.z * TAU).sin_cos();
+    point!(
+        light.x * cos - light.y * sin,
+        light.x * sin + light.y * cos,
+        light.z
+    )
+}
+
+/// Returns a rotation for which [`light_direction`] points in `direction`, relative to the camera.
+///
+/// Only the `x` and `y` components of the rotation are used, both are
+/// [normalized](Rotation::normalize).
+/// Returns no rotation if `direction` has no length.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Point3D;
+/// use picocadrs::point;
+/// use picocadrs::shading::{light_direction, rotation_towards};
+///
+/// let direction = point!(1.0, 1.0, -1.0).normalized();
+/// let light = light_direction(rotation_towards(direction));
+///
+/// assert!((light - direction).length() < 1e-9);
+/// ```
+pub fn rotation_towards(direction: Point3D<f64>) -> Rotation {
+    let direction = direction.normalized();
+    if direction.length() == 0.0 {
+        return Rotation(point!(0.0, 0.0, 0.0));
+    }
+
+    // turning the light around the x-axis tilts it towards -y, then around the y-axis towards x.
+    let x = (-direction.y).clamp(-1.0, 1.0).asin();
+    let y = direction.x.atan2(direction.z);
+
+    let mut rotation = Rotation(point!(x / TAU, y / TAU, 0.0));
+    rotation.normalize();
+    rotation
+}
+
+/// Gives every mesh the same rotation, so picoCAD lights them all from the same direction
+/// relative to the camera.
+///
+/// Meshes that already have the same rotation (see [`Rotation::equal_rotation`]) are left as
+/// they are.
+/// Only the lighting changes, vertices are not moved.
+///
+/// Returns [`PicoError::MeshNotFound`] if the reference is a mesh that doesn't exist.
+///
+/// It's recommended to use [`Model::unify_lighting`].
+pub fn unify_lighting(
+    model: &mut Model,
+    reference: &LightingReference,
+) -> Result<LightingReport, PicoError> {
+    let rotation = match reference {
+        LightingReference::Mesh(name) => {
+            model
+                .meshes
+                .iter()
+                .find(|mesh| mesh.name == *name)
+                .ok_or_else(|| PicoError::MeshNotFound(name.clone()))?
+                .rotation
+        }
+        LightingReference::Rotation(rotation) => *rotation,
+        LightingReference::Direction(direction) => rotation_towards(*direction),
+    };
+
+    let mut changed = vec![];
+    for (index, mesh) in model.meshes.iter_mut().enumerate() {
+        if !mesh.rotation.equal_rotation(&rotation) {
+            mesh.rotation = rotation;
+            changed.push(index);
+        }
+    }
+
+    Ok(LightingReport { rotation, changed })
+}
+
+/// Returns the shade of a face of `mesh` lit by light travelling in the direction `light_dir`.
+pub fn face_shade(mesh: &Mesh, face: &Face, light_dir: Point3D<f64>) -> Shade {
+    if face.no_shading {
+        return Shade::Lit;
+    }
+
+    let mut intensity = face.normal(&mesh.vertices).dot(-light_dir.normalized());
+
+    // the back of double-sided faces is lit like its front.
+    if face.double_sided {
+        intensity = intensity.abs();
+    }
+
+    Shade::from_intensity(intensity)
+}
+
+/// Darkens the color of every face according to the light travelling in the direction
+/// `light_dir` and returns the amount of faces that changed.
+///
+/// Shaded faces are marked with [`no_shading`](Face::no_shading), so picoCAD doesn't shade them a
+/// second time.
+///
+/// It's recommended to use [`Model::bake_shading`].
+pub fn bake_shading(model: &mut Model, light_dir: Point3D<f64>) -> usize {
+    let mut changed = 0;
+
+    for mesh in model.meshes.iter_mut() {
+        let shades: Vec<Shade> = mesh
+            .faces
+            .iter()
+            .map(|face| face_shade(mesh, face, light_dir))
+            .collect();
+
+        for (face, shade) in mesh.faces.iter_mut().zip(shades) {
+            if face.no_shading {
+                continue;
+            }
+
+            let color = shade.apply(face.color);
+            if color != face.color {
+                changed += 1;
+            }
+
+            face.color = color;
+            face.no_shading = true;
+        }
+    }
+
+    changed
+}
+
+/// Returns a copy of the texture where the pixels of each face are darkened according to the
+/// light travelling in the direction `light_dir`.
+///
+/// Pixels used by multiple faces are shaded like the face that comes first in the model (see
+/// [`TextureUsage::owner`]), unused pixels and pixels in the alpha color are left as they are.
+///
+/// It's recommended to use [`Model::shaded_texture`].
+pub fn shaded_texture(model: &Model, light_dir: Point3D<f64>) -> Footer {
+    let usage = TextureUsage::from_model(model);
+    let mut footer = model.footer.clone();
+
+    for v in 0..Footer::HEIGHT {
+        for u in 0..Footer::WIDTH {
+            let coords = point!(u, v);
+            let Some(owner) = usage.owner(coords) else {
+                continue;
+            };
+
+            let mesh = &model.meshes[owner.mesh];
+            let shade = face_shade(mesh, &mesh.faces[owner.face], light_dir);
+
+            if let Some(pixel) = footer
+                .get_mut(coords)
+                .filter(|pixel| **pixel != model.header.alpha)
+            {
+                *pixel = shade.apply(*pixel);
+            }
+        }
+    }
+
+    footer
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::UVMap;
+
+    /// A mesh with a face looking up and a face looking towards -x, each using 2x2 pixels.
+    fn corner_model() -> Model {
+        let mut mesh = Mesh::new("corner".to_string());
+        mesh.vertices = vec![
+            point!(0.0, 0.0, 0.0),
+            point!(0.0, 0.0, 1.0),
+            point!(1.0, 0.0, 1.0),
+            point!(1.0, 0.0, 0.0),
+            point!(0.0, 1.0, 0.0),
+            point!(0.0, 1.0, 1.0),
+        ];
+
+        let uv_maps = |indices: [usize; 4], u: f64| {
+            indices
+                .iter()
+                .zip([(0.0, 0.0), (0.0, 0.25), (0.25, 0.25), (0.25, 0.0)])
+                .map(|(i, (du, dv))| UVMap::new(*i, point!(u + du, dv)))
+                .collect()
+        };
+
+        mesh.faces.push(Face {
+            color: Color::Orange,
+            uv_maps: uv_maps([0, 1, 2, 3], 0.0),
+            ..Face::default()
+        });
+        mesh.faces.push(Face {
+            color: Color::Orange,
+            uv_maps: uv_maps([4, 5, 1, 0], 1.0),
+            ..Face::default()
+        });
+
+        let mut model = Model::default();
+        model.header.alpha = Color::Invalid;
+        model.meshes.push(mesh);
+        for v in 0..4 {
+            for u in 0..16 {
+                model.footer.set(point!(u, v), Color::White).unwrap();
+            }
+        }
+
+        model
+    }
+
+    #[test]
+    fn shading_face_colors() {
+        let mut model = corner_model();
+        assert_eq!(
+            model.meshes[0].faces[0].normal(&model.meshes[0].vertices),
+            point!(0.0, -1.0, 0.0)
+        );
+
+        // shining down and a bit along +x.
+        let changed = model.bake_shading(point!(0.3, 1.0, 0.0));
+
+        assert_eq!(changed, 1);
+        assert_eq!(model.meshes[0].faces[0].color, Color::Orange);
+        assert_eq!(model.meshes[0].faces[1].color, Color::DarkPurple);
+        assert!(model.meshes[0].faces.iter().all(|face| face.no_shading));
+
+        // baked faces aren't shaded again.
+        assert_eq!(model.bake_shading(point!(-1.0, 0.0, 0.0)), 0);
+    }
+
+    #[test]
+    fn shading_unify_lighting() {
+        let mut model = corner_model();
+        model.meshes[0].name = "reference".to_string();
+        model.meshes[0].rotation = Rotation(point!(0.25, 0.0, 0.0));
+        model.meshes.push(model.meshes[0].clone());
+        model.meshes[1].name = "copy".to_string();
+        model.meshes[1].rotation = Rotation(point!(1.25, 0.5, 0.0));
+        model.meshes.push(model.meshes[1].clone());
+        model.meshes[2].rotation = Rotation(point!(-0.75, 0.0, 1.0));
+
+        let report = model
+            .unify_lighting(&LightingReference::Mesh("reference".to_string()))
+            .unwrap();
+
+        assert_eq!(report.changed, vec![1]);
+        assert_eq!(model.meshes[1].rotation, Rotation(point!(0.25, 0.0, 0.0)));
+        // already the same rotation, so it keeps its numbers.
+        assert_eq!(model.meshes[2].rotation, Rotation(point!(-0.75, 0.0, 1.0)));
+
+        let report = model
+            .unify_lighting(&LightingReference::Direction(point!(0.0, 1.0, 0.0)))
+            .unwrap();
+        assert_eq!(report.changed, vec![0, 1, 2]);
+        // light travelling down is light travelling away from the camera turned upwards.
+        assert_eq!(report.rotation, Rotation(point!(0.75, 0.0, 0.0)));
+
+        assert!(matches!(
+            model.unify_lighting(&LightingReference::Mesh("missing".to_string())),
+            Err(PicoError::MeshNotFound(name)) if name == "missing"
+        ));
+    }
+
+    #[test]
+    fn shading_rotation_towards() {
+        for direction in [
+            point!(0.0, -1.0, 0.0),
+            point!(0.0, 0.0, -3.0),
+            point!(-1.0, 0.5, 0.2),
+        ] {
+            let light = light_direction(rotation_towards(direction));
+            assert!((light - direction.normalized()).length() < 1e-9);
+        }
+
+        assert_eq!(
+            rotation_towards(point!(0.0, 0.0, 0.0)),
+            Rotation(point!(0.0, 0.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn shading_texture() {
+        let mut model = corner_model();
+        model.meshes[0].faces[0].double_sided = true;
+
+        let footer = model.shaded_texture(point!(0.0, -1.0, 0.0));
+
+        assert_eq!(footer.pixel(point!(1, 1)), Color::White);
+        assert_eq!(footer.pixel(point!(9, 1)), Color::Lavender);
+        assert_eq!(footer.pixel(point!(12, 1)), Color::White);
+        assert_eq!(model.footer.pixel(point!(9, 1)), Color::White);
+    }
+}
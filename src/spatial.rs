@@ -0,0 +1,697 @@
+//! For answering questions about where faces are without testing every single one of them.
+//!
+//! [`Bvh`] is a bounding volume hierarchy: a tree of boxes where each box surrounds the boxes or
+//! items below it.
+//! Queries skip every branch whose box can't contain an answer, so they only test a few items
+//! instead of all of them.
+//! Building the tree takes about as long as testing every item a few times, so it pays off when
+//! many queries are made against the same geometry, like when [baking ambient
+//! occlusion](crate::occlusion) or matching points of an imported mesh against a model.
+//!
+//! A [`Bvh`] can hold any items, as long as each has an [`Aabb`] around it.
+//! [`Bvh::from_model`] builds one over the faces of a model, which also offers ray casting and
+//! nearest point queries against the faces themselves.
+//!
+//! The tree doesn't update when the model changes, it has to be built again.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{MeshFaceRef, Model, Point3D, ProjectPreset};
+//! use picocadrs::spatial::Aabb;
+//! use picocadrs::point;
+//!
+//! let model = Model::new_project("lookup".to_string(), ProjectPreset::Plane);
+//! let bvh = model.bvh();
+//!
+//! let hit = bvh
+//!     .raycast(&model, point!(0.5, -2.0, 0.5), point!(0.0, 1.0, 0.0))
+//!     .unwrap();
+//! assert_eq!(hit.hit.point, point!(0.5, 0.0, 0.5));
+//!
+//! let nearest = bvh.nearest_point(&model, point!(3.0, -1.0, 0.0)).unwrap();
+//! assert_eq!(nearest.point, point!(1.0, 0.0, 0.0));
+//!
+//! let area = Aabb::new(point!(0.5, -0.5, 0.5), point!(2.0, 0.5, 2.0));
+//! assert_eq!(bvh.query_box(&area), vec![&MeshFaceRef::new(0, 0)]);
+//! ```
+
+use crate::{
+    assets::{MeshFaceRef, Model, Point3D},
+    point,
+    raycast::RayHit,
+};
+
+/// Maximum amount of items in a leaf of a [`Bvh`].
+const LEAF_SIZE: usize = 4;
+
+/// An axis aligned box, given by its smallest and largest corner.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Aabb {
+    /// Corner with the smallest coordinates.
+    pub min: Point3D<f64>,
+    /// Corner with the largest coordinates.
+    pub max: Point3D<f64>,
+}
+
+impl Aabb {
+    /// Creates a box spanning between 2 corners, in any order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    /// use picocadrs::spatial::Aabb;
+    /// use picocadrs::point;
+    ///
+    /// let aabb = Aabb::new(point!(1.0, 0.0, 2.0), point!(0.0, 1.0, 0.0));
+    ///
+    /// assert_eq!(aabb.min, point!(0.0, 0.0, 0.0));
+    /// assert_eq!(aabb.max, point!(1.0, 1.0, 2.0));
+    /// ```
+    pub fn new(a: Point3D<f64>, b: Point3D<f64>) -> Aabb {
+        Aabb {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    /// Returns the smallest box around all points, or [`None`] if there are none.
+    pub fn from_points<I: IntoIterator<Item = Point3D<f64>>>(points: I) -> Option<Aabb> {
+        points.into_iter().fold(None, |aabb: Option<Aabb>, point| {
+            Some(match aabb {
+                Some(aabb) => aabb.union(&Aabb::new(point, point)),
+                None => Aabb::new(point, point),
+            })
+        })
+    }
+
+    /// Returns the smallest box around both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    /// Returns the point in the middle of the box.
+    pub fn center(&self) -> Point3D<f64> {
+        (self.min + self.max) * 0.5
+    }
+
+    /// Checks if the point is inside of the box or on its surface.
+    pub fn contains(&self, point: Point3D<f64>) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+            && self.min.z <= point.z
+            && point.z <= self.max.z
+    }
+
+    /// Checks if both boxes overlap, touching counts as overlapping.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::Point3D;
+    /// use picocadrs::spatial::Aabb;
+    /// use picocadrs::point;
+    ///
+    /// let a = Aabb::new(point!(0.0, 0.0, 0.0), point!(1.0, 1.0, 1.0));
+    ///
+    /// assert!(a.intersects(&Aabb::new(point!(1.0, 1.0, 1.0), point!(2.0, 2.0, 2.0))));
+    /// assert!(!a.intersects(&Aabb::new(point!(1.5, 0.0, 0.0), point!(2.0, 1.0, 1.0))));
+    /// ```
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+            && self.min.z <= other.max.z
+            && other.min.z <= self.max.z
+    }
+
+    /// Returns the distance from the point to the closest point of the box, `0.0` if the point is
+    /// inside.
+    pub fn distance_to(&self, point: Point3D<f64>) -> f64 {
+        let closest = point.max(self.min).min(self.max);
+        (point - closest).length()
+    }
+
+    /// Returns the distance along a ray at which it enters the box, `0.0` if it starts inside.
+    ///
+    /// `direction` has to be normalized.
+    /// Returns [`None`] if the ray misses the box.
+    pub fn ray_distance(&self, origin: Point3D<f64>, direction: Point3D<f64>) -> Option<f64> {
+        let mut near: f64 = 0.0;
+        let mut far = f64::INFINITY;
+
+        for (o, d, min, max) in [
+            (origin.x, direction.x, self.min.x, self.max.x),
+            (origin.y, direction.y, self.min.y, self.max.y),
+            (origin.z, direction.z, self.min.z, self.max.z),
+        ] {
+            if d == 0.0 {
+                if o < min || o > max {
+                    return None;
+                }
+                continue;
+            }
+
+            let a = (min - o) / d;
+            let b = (max - o) / d;
+            near = near.max(a.min(b));
+            far = far.min(a.max(b));
+
+            if near > far {
+                return None;
+            }
+        }
+
+        Some(near)
+    }
+}
+
+/// Where [`Bvh::nearest_point`] found the point on a face closest to another point.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct NearestPoint {
+    /// The face the point lies on.
+    pub face: MeshFaceRef,
+    /// The closest point, in world space.
+    pub point: Point3D<f64>,
+    /// Distance between the closest point and the point that was looked for.
+    pub distance: f64,
+}
+
+/// A node of a [`Bvh`].
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// Holds the items from `start` up to `end`.
+    Leaf {
+        bounds: Aabb,
+        start: usize,
+        end: usize,
+    },
+    /// Holds 2 other nodes.
+    Branch {
+        bounds: Aabb,
+        left: usize,
+        right: usize,
+    },
+}
+
+impl Node {
+    fn bounds(&self) -> &Aabb {
+        match self {
+            Node::Leaf { bounds, .. } | Node::Branch { bounds, .. } => bounds,
+        }
+    }
+}
+
+/// A bounding volume hierarchy over items of type `T`.
+///
+/// More information can be found in the [module](self) documentation.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Point3D;
+/// use picocadrs::spatial::{Aabb, Bvh};
+/// use picocadrs::point;
+///
+/// let points = [point!(0.0, 0.0, 0.0), point!(5.0, 0.0, 0.0), point!(9.0, 0.0, 0.0)];
+/// let bvh = Bvh::new(points.iter().map(|p| (*p, Aabb::new(*p, *p))).collect());
+///
+/// let nearest = bvh.nearest(point!(6.0, 1.0, 0.0), |p| Some(((*p - point!(6.0, 1.0, 0.0)).length(), *p)));
+/// assert_eq!(nearest, Some(point!(5.0, 0.0, 0.0)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bvh<T> {
+    nodes: Vec<Node>,
+    items: Vec<(T, Aabb)>,
+}
+
+impl<T> Bvh<T> {
+    /// Builds the tree over the items, each paired with the box around it.
+    pub fn new(mut items: Vec<(T, Aabb)>) -> Bvh<T> {
+        let mut nodes = vec![];
+
+        if !items.is_empty() {
+            let len = items.len();
+            build(&mut nodes, &mut items, 0, len);
+        }
+
+        Bvh { nodes, items }
+    }
+
+    /// Amount of items in the tree.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Checks if the tree holds no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the box around all items, or [`None`] if there are none.
+    pub fn bounds(&self) -> Option<&Aabb> {
+        self.nodes.first().map(Node::bounds)
+    }
+
+    /// Returns all items whose box overlaps with `aabb`.
+    pub fn query_box(&self, aabb: &Aabb) -> Vec<&T> {
+        let mut found = vec![];
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop() {
+            let Some(node) = self.nodes.get(index) else {
+                continue;
+            };
+            if !node.bounds().intersects(aabb) {
+                continue;
+            }
+
+            match node {
+                Node::Leaf { start, end, .. } => found.extend(
+                    self.items[*start..*end]
+                        .iter()
+                        .filter(|(_, bounds)| bounds.intersects(aabb))
+                        .map(|(item, _)| item),
+                ),
+                Node::Branch { left, right, .. } => stack.extend([*right, *left]),
+            }
+        }
+
+        found
+    }
+
+    /// Returns the result of the closest item hit by a ray starting at `origin` and travelling
+    /// in `direction`, no further away than `max_distance`.
+    ///
+    /// `hit` tests the ray against an item and returns the distance to the hit together with the
+    /// result, it is only called for items whose box the ray passes through.
+    /// Distances are measured along the normalized direction.
+    pub fn cast_ray<R, F>(
+        &self,
+        origin: Point3D<f64>,
+        direction: Point3D<f64>,
+        max_distance: f64,
+        mut hit: F,
+    ) -> Option<R>
+    where
+        F: FnMut(&T) -> Option<(f64, R)>,
+    {
+        let direction = direction.normalized();
+        if direction == point!(0.0, 0.0, 0.0) {
+            return None;
+        }
+
+        let mut closest: Option<(f64, R)> = None;
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop() {
+            let Some(node) = self.nodes.get(index) else {
+                continue;
+            };
+
+            let limit = closest.as_ref().map_or(max_distance, |(d, _)| *d);
+            match node.bounds().ray_distance(origin, direction) {
+                Some(distance) if distance <= limit => {}
+                _ => continue,
+            }
+
+            match node {
+                Node::Leaf { start, end, .. } => {
+                    for (item, _) in self.items[*start..*end].iter() {
+                        let Some((distance, result)) = hit(item) else {
+                            continue;
+                        };
+
+                        let better = match &closest {
+                            Some((best, _)) => distance < *best,
+                            None => distance <= max_distance,
+                        };
+                        if better {
+                            closest = Some((distance, result));
+                        }
+                    }
+                }
+                Node::Branch { left, right, .. } => stack.extend([*right, *left]),
+            }
+        }
+
+        closest.map(|(_, result)| result)
+    }
+
+    /// Returns the result of the item closest to `point`.
+    ///
+    /// `distance` returns the distance from `point` to an item together with the result, it is
+    /// only called for items whose box is closer than the closest item found so far.
+    pub fn nearest<R, F>(&self, point: Point3D<f64>, mut distance: F) -> Option<R>
+    where
+        F: FnMut(&T) -> Option<(f64, R)>,
+    {
+        let mut closest: Option<(f64, R)> = None;
+        let mut stack = vec![0];
+
+        while let Some(index) = stack.pop() {
+            let Some(node) = self.nodes.get(index) else {
+                continue;
+            };
+
+            if closest
+                .as_ref()
+                .is_some_and(|(d, _)| node.bounds().distance_to(point) >= *d)
+            {
+                continue;
+            }
+
+            match node {
+                Node::Leaf { start, end, .. } => {
+                    for (item, _) in self.items[*start..*end].iter() {
+                        let Some((d, result)) = distance(item) else {
+                            continue;
+                        };
+
+                        if !closest.as_ref().is_some_and(|(best, _)| *best <= d) {
+                            closest = Some((d, result));
+                        }
+                    }
+                }
+                Node::Branch { left, right, .. } => {
+                    // the closer child is visited first, so the other one is skipped more often.
+                    let (near, far) = if self.nodes[*left].bounds().distance_to(point)
+                        <= self.nodes[*right].bounds().distance_to(point)
+                    {
+                        (*left, *right)
+                    } else {
+                        (*right, *left)
+                    };
+                    stack.extend([far, near]);
+                }
+            }
+        }
+
+        closest.map(|(_, result)| result)
+    }
+}
+
+impl Bvh<MeshFaceRef> {
+    /// Builds the tree over every face of the model, in world space.
+    ///
+    /// Faces referencing vertices that don't exist are left out.
+    /// The rotation of meshes is ignored, like in [`raycast`](crate::raycast).
+    ///
+    /// It's recommended to use [`Model::bvh`].
+    pub fn from_model(model: &Model) -> Bvh<MeshFaceRef> {
+        let mut items = vec![];
+
+        for (mesh_index, mesh) in model.meshes.iter().enumerate() {
+            for (face_index, face) in mesh.faces.iter().enumerate() {
+                let Some(corners) = face.corners(&mesh.vertices) else {
+                    continue;
+                };
+
+                if let Some(bounds) =
+                    Aabb::from_points(corners.into_iter().map(|corner| corner + mesh.position))
+                {
+                    items.push((MeshFaceRef::new(mesh_index, face_index), bounds));
+                }
+            }
+        }
+
+        Bvh::new(items)
+    }
+
+    /// Returns the closest face of `model` hit by a ray starting at `origin` and travelling in
+    /// `direction`, both given in world space.
+    ///
+    /// Gives the same result as [`raycast`](crate::raycast::raycast), but faster if the tree is
+    /// used for many rays.
+    /// `model` has to be the model the tree was built from.
+    pub fn raycast(
+        &self,
+        model: &Model,
+        origin: Point3D<f64>,
+        direction: Point3D<f64>,
+    ) -> Option<RayHit> {
+        self.cast_ray(origin, direction, f64::INFINITY, |face_ref| {
+            let mesh = &model.meshes[face_ref.mesh];
+            let mut hit = mesh.faces[face_ref.face].intersect_ray(
+                &mesh.vertices,
+                origin - mesh.position,
+                direction,
+            )?;
+            hit.point += mesh.position;
+
+            Some((
+                hit.distance,
+                RayHit {
+                    face: *face_ref,
+                    hit,
+                },
+            ))
+        })
+    }
+
+    /// Returns the point on the faces of `model` closest to `point`, given in world space.
+    ///
+    /// `model` has to be the model the tree was built from.
+    /// Returns [`None`] if the model has no faces.
+    pub fn nearest_point(&self, model: &Model, point: Point3D<f64>) -> Option<NearestPoint> {
+        self.nearest(point, |face_ref| {
+            let mesh = &model.meshes[face_ref.mesh];
+            let corners = mesh.faces[face_ref.face].corners(&mesh.vertices)?;
+            let closest = closest_on_polygon(&corners, point - mesh.position)? + mesh.position;
+            let distance = (closest - point).length();
+
+            Some((
+                distance,
+                NearestPoint {
+                    face: *face_ref,
+                    point: closest,
+                    distance,
+                },
+            ))
+        })
+    }
+}
+
+/// Builds the node for the items from `start` up to `end` and returns its index.
+fn build<T>(nodes: &mut Vec<Node>, items: &mut [(T, Aabb)], start: usize, end: usize) -> usize {
+    let bounds = items[start..end]
+        .iter()
+        .skip(1)
+        .fold(items[start].1, |bounds, (_, item)| bounds.union(item));
+
+    let index = nodes.len();
+
+    if end - start <= LEAF_SIZE {
+        nodes.push(Node::Leaf { bounds, start, end });
+        return index;
+    }
+
+    // split along the longest side of the box around the centers, at the median.
+    let centers =
+        Aabb::from_points(items[start..end].iter().map(|(_, b)| b.center())).unwrap_or(bounds);
+    let size = centers.max - centers.min;
+    let axis = if size.x >= size.y && size.x >= size.z {
+        |p: Point3D<f64>| p.x
+    } else if size.y >= size.z {
+        |p: Point3D<f64>| p.y
+    } else {
+        |p: Point3D<f64>| p.z
+    };
+
+    items[start..end].sort_by(|(_, a), (_, b)| axis(a.center()).total_cmp(&axis(b.center())));
+    let middle = start + (end - start) / 2;
+
+    // placeholder until both children are built.
+    nodes.push(Node::Leaf { bounds, start, end });
+    let left = build(nodes, items, start, middle);
+    let right = build(nodes, items, middle, end);
+    nodes[index] = Node::Branch {
+        bounds,
+        left,
+        right,
+    };
+
+    index
+}
+
+/// Returns the point of a polygon closest to `point`, treating the polygon as a fan of triangles.
+///
+/// Returns [`None`] if the polygon has no corners.
+fn closest_on_polygon(corners: &[Point3D<f64>], point: Point3D<f64>) -> Option<Point3D<f64>> {
+    match corners.len() {
+        0 => None,
+        1 => Some(corners[0]),
+        2 => Some(closest_on_triangle(
+            point, corners[0], corners[1], corners[1],
+        )),
+        _ => (1..corners.len() - 1)
+            .map(|i| closest_on_triangle(point, corners[0], corners[i], corners[i + 1]))
+            .min_by(|a, b| (*a - point).length().total_cmp(&(*b - point).length())),
+    }
+}
+
+/// Returns the point of the triangle `a`, `b`, `c` closest to `p`.
+fn closest_on_triangle(
+    p: Point3D<f64>,
+    a: Point3D<f64>,
+    b: Point3D<f64>,
+    c: Point3D<f64>,
+) -> Point3D<f64> {
+    let ab = b - a;
+    let ac = c - a;
+
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    let total = va + vb + vc;
+    if total == 0.0 {
+        return a;
+    }
+
+    a + ab * (vb / total) + ac * (vc / total)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::Mesh;
+
+    /// A row of `count` planes along the x-axis, 3 units apart.
+    fn row(count: usize) -> Model {
+        let mut model = Model::default();
+
+        for i in 0..count {
+            let mut mesh = Mesh::plane();
+            mesh.position = point!(i as f64 * 3.0, 0.0, 0.0);
+            model.meshes.push(mesh);
+        }
+
+        model
+    }
+
+    #[test]
+    fn spatial_aabb() {
+        let aabb = Aabb::from_points([point!(0.0, 0.0, 0.0), point!(2.0, -1.0, 1.0)]).unwrap();
+
+        assert_eq!(aabb.min, point!(0.0, -1.0, 0.0));
+        assert_eq!(aabb.center(), point!(1.0, -0.5, 0.5));
+        assert!(aabb.contains(point!(1.0, 0.0, 1.0)));
+        assert_eq!(aabb.distance_to(point!(5.0, 0.0, 0.5)), 3.0);
+        assert_eq!(
+            aabb.ray_distance(point!(-1.0, -0.5, 0.5), point!(1.0, 0.0, 0.0)),
+            Some(1.0)
+        );
+        assert_eq!(
+            aabb.ray_distance(point!(1.0, -0.5, 0.5), point!(0.0, 1.0, 0.0)),
+            Some(0.0)
+        );
+        assert!(aabb
+            .ray_distance(point!(-1.0, -0.5, 0.5), point!(-1.0, 0.0, 0.0))
+            .is_none());
+        assert!(Aabb::from_points([]).is_none());
+    }
+
+    #[test]
+    fn spatial_matches_brute_force() {
+        let model = row(20);
+        let bvh = model.bvh();
+
+        assert_eq!(bvh.len(), 20);
+        assert_eq!(bvh.bounds().unwrap().max, point!(58.0, 0.0, 1.0));
+
+        for x in [-0.5, 14.25, 30.0, 57.5, 70.0] {
+            let origin = point!(x, -3.0, 0.25);
+            let direction = point!(0.1, 1.0, 0.0);
+
+            assert_eq!(
+                bvh.raycast(&model, origin, direction),
+                crate::raycast::raycast(&model, origin, direction)
+            );
+        }
+
+        let nearest = bvh.nearest_point(&model, point!(10.5, -2.0, 3.0)).unwrap();
+        assert_eq!(nearest.face, MeshFaceRef::new(3, 0));
+        assert_eq!(nearest.point, point!(10.0, 0.0, 1.0));
+
+        let found = bvh.query_box(&Aabb::new(point!(4.5, -1.0, 0.0), point!(9.5, 1.0, 0.0)));
+        let mut found: Vec<usize> = found.iter().map(|face| face.mesh).collect();
+        found.sort();
+        assert_eq!(found, vec![2, 3]);
+    }
+
+    #[test]
+    fn spatial_closest_on_triangle() {
+        let (a, b, c) = (
+            point!(0.0, 0.0, 0.0),
+            point!(2.0, 0.0, 0.0),
+            point!(0.0, 0.0, 2.0),
+        );
+
+        assert_eq!(
+            closest_on_triangle(point!(0.5, 3.0, 0.5), a, b, c),
+            point!(0.5, 0.0, 0.5)
+        );
+        assert_eq!(closest_on_triangle(point!(-1.0, 0.0, -1.0), a, b, c), a);
+        assert_eq!(
+            closest_on_triangle(point!(3.0, 0.0, 3.0), a, b, c),
+            point!(1.0, 0.0, 1.0)
+        );
+        assert_eq!(
+            closest_on_triangle(point!(1.0, 1.0, -1.0), a, b, c),
+            point!(1.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn spatial_empty() {
+        let model = Model::default();
+        let bvh = model.bvh();
+
+        assert!(bvh.is_empty());
+        assert!(bvh.bounds().is_none());
+        assert!(bvh
+            .raycast(&model, point!(0.0, 0.0, 0.0), point!(0.0, 1.0, 0.0))
+            .is_none());
+        assert!(bvh.nearest_point(&model, point!(0.0, 0.0, 0.0)).is_none());
+    }
+}
@@ -0,0 +1,470 @@
+//! For describing models in a few lines of code.
+//!
+//! The [`model!`](crate::model) macro builds a whole [`Model`](crate::assets::Model) out of a list
+//! of meshes, the [`mesh!`](crate::mesh) macro a single [`Mesh`].
+//! The body of each mesh is a list of statements, each calling the method of the same name on a
+//! [`MeshTemplate`]:
+//!
+//! - _Shapes_ add geometry to the mesh, centered around its position:
+//!   [`cube`](MeshTemplate::cube), [`cuboid`](MeshTemplate::cuboid),
+//!   [`plane`](MeshTemplate::plane) and [`cylinder`](MeshTemplate::cylinder).
+//! - _Placement_ sets where the mesh is: [`at`](MeshTemplate::at) and
+//!   [`rotation`](MeshTemplate::rotation).
+//! - _Attributes_ apply to every face of the mesh, no matter if they come before or after the
+//!   shapes: [`color`](MeshTemplate::color), [`double_sided`](MeshTemplate::double_sided),
+//!   [`no_shading`](MeshTemplate::no_shading), [`no_texture`](MeshTemplate::no_texture) and
+//!   [`texture`](MeshTemplate::texture).
+//!
+//! Colors can be written by their name alone, like `color(Red)`.
+//! Numbers can be written with or without decimals.
+//! Names of meshes need to be literals or wrapped in parentheses, like `mesh (format!("{}", i))`.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::Color;
+//! use picocadrs::model;
+//!
+//! let model = model! {
+//!     name "house";
+//!     mesh "walls" {
+//!         cuboid(2, 1.5, 2);
+//!         at(0, -0.75, 0);
+//!         color(Brown);
+//!     }
+//!     mesh "ground" {
+//!         plane(6);
+//!         color(Color::Green);
+//!         no_texture(true);
+//!     }
+//! };
+//!
+//! assert_eq!(model.header.name, "house");
+//! assert_eq!(model.meshes[0].faces.len(), 6);
+//! assert_eq!(model.meshes[0].faces[0].color, Color::Brown);
+//! assert!(model.meshes[1].faces[0].no_texture);
+//! ```
+
+use crate::{
+    assets::{Color, Face, Mesh, Point2D, Point3D, Rect},
+    point,
+};
+
+/// Pixels each face is mapped onto, if not set with [`MeshTemplate::texture`].
+const DEFAULT_TEXTURE: Rect = Rect {
+    position: Point2D { u: 0, v: 0 },
+    size: Point2D { u: 16, v: 16 },
+};
+
+/// A mesh being built up by statements of the [`model!`](crate::model) and
+/// [`mesh!`](crate::mesh) macros.
+///
+/// Every statement returns the template again, so it can be used without the macros too.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Color;
+/// use picocadrs::template::MeshTemplate;
+///
+/// let mesh = MeshTemplate::new("pillar".to_string())
+///     .cylinder(0.5, 3, 8)
+///     .color(Color::LightGrey)
+///     .build();
+///
+/// assert_eq!(mesh.name, "pillar");
+/// assert_eq!(mesh.faces.len(), 24);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshTemplate {
+    mesh: Mesh,
+    color: Option<Color>,
+    double_sided: Option<bool>,
+    no_shading: Option<bool>,
+    no_texture: Option<bool>,
+    texture: Rect,
+    /// Indices of the faces mapped onto `texture`.
+    textured: Vec<usize>,
+}
+
+impl MeshTemplate {
+    /// Creates an empty template for a mesh with the given name at the origin.
+    pub fn new(name: String) -> MeshTemplate {
+        MeshTemplate {
+            mesh: Mesh::new(name),
+            color: None,
+            double_sided: None,
+            no_shading: None,
+            no_texture: None,
+            texture: DEFAULT_TEXTURE,
+            textured: vec![],
+        }
+    }
+
+    /// Adds a cube with edges `size` units long.
+    pub fn cube<S: Into<f64>>(&mut self, size: S) -> &mut Self {
+        let size = size.into();
+        self.cuboid(size, size, size)
+    }
+
+    /// Adds a box `width` units along the `x`-axis, `height` along the `y`-axis and `depth` along
+    /// the `z`-axis, with all faces pointing outwards.
+    pub fn cuboid<W: Into<f64>, H: Into<f64>, D: Into<f64>>(
+        &mut self,
+        width: W,
+        height: H,
+        depth: D,
+    ) -> &mut Self {
+        let (x, y, z) = (width.into() / 2.0, height.into() / 2.0, depth.into() / 2.0);
+
+        let mut cuboid = Mesh::new(String::new());
+        cuboid.vertices = vec![
+            point!(-x, -y, -z),
+            point!(x, -y, -z),
+            point!(x, -y, z),
+            point!(-x, -y, z),
+            point!(-x, y, -z),
+            point!(x, y, -z),
+            point!(x, y, z),
+            point!(-x, y, z),
+        ];
+
+        for vertices in [
+            [3, 2, 1, 0],
+            [4, 5, 6, 7],
+            [0, 1, 5, 4],
+            [1, 2, 6, 5],
+            [2, 3, 7, 6],
+            [3, 0, 4, 7],
+        ] {
+            cuboid.faces.push(self.face(&vertices));
+        }
+
+        self.append(cuboid, true);
+        self
+    }
+
+    /// Adds a double sided square lying flat on the `x` and `z` axis, like the one picoCAD adds to
+    /// new projects, with edges `size` units long.
+    pub fn plane<S: Into<f64>>(&mut self, size: S) -> &mut Self {
+        let half = size.into() / 2.0;
+
+        let mut plane = Mesh::new(String::new());
+        plane.vertices = vec![
+            point!(-half, 0.0, -half),
+            point!(half, 0.0, -half),
+            point!(half, 0.0, half),
+            point!(-half, 0.0, half),
+        ];
+
+        let mut face = self.face(&[3, 2, 1, 0]);
+        face.double_sided = true;
+        plane.faces.push(face);
+
+        self.append(plane, true);
+        self
+    }
+
+    /// Adds a closed cylinder standing on the `y`-axis, with `segments` faces around it.
+    ///
+    /// The texture is wrapped around the sides like in [`Mesh::lathe`], ignoring
+    /// [`texture`](MeshTemplate::texture).
+    /// Nothing is added if `segments` is smaller than 3.
+    pub fn cylinder<R: Into<f64>, H: Into<f64>>(
+        &mut self,
+        radius: R,
+        height: H,
+        segments: usize,
+    ) -> &mut Self {
+        let (radius, half) = (radius.into(), height.into() / 2.0);
+
+        self.append(
+            Mesh::lathe(
+                &[
+                    point!(0.0, half),
+                    point!(radius, half),
+                    point!(radius, -half),
+                    point!(0.0, -half),
+                ],
+                segments,
+            ),
+            false,
+        );
+        self
+    }
+
+    /// Sets the position of the mesh.
+    pub fn at<X: Into<f64>, Y: Into<f64>, Z: Into<f64>>(&mut self, x: X, y: Y, z: Z) -> &mut Self {
+        self.mesh.position = point!(x.into(), y.into(), z.into());
+        self
+    }
+
+    /// Sets the shadow rotation of the mesh.
+    pub fn rotation<X: Into<f64>, Y: Into<f64>, Z: Into<f64>>(
+        &mut self,
+        x: X,
+        y: Y,
+        z: Z,
+    ) -> &mut Self {
+        self.mesh.rotation.0 = point!(x.into(), y.into(), z.into());
+        self
+    }
+
+    /// Sets the color of every face.
+    pub fn color(&mut self, color: Color) -> &mut Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets if every face is rendered from both sides.
+    pub fn double_sided(&mut self, double_sided: bool) -> &mut Self {
+        self.double_sided = Some(double_sided);
+        self
+    }
+
+    /// Sets if every face is drawn without shading.
+    pub fn no_shading(&mut self, no_shading: bool) -> &mut Self {
+        self.no_shading = Some(no_shading);
+        self
+    }
+
+    /// Sets if every face shows its color instead of the texture.
+    pub fn no_texture(&mut self, no_texture: bool) -> &mut Self {
+        self.no_texture = Some(no_texture);
+        self
+    }
+
+    /// Maps every face of boxes and planes onto the pixels `width` by `height` pixels big, starting
+    /// at pixel `u`, `v`.
+    /// By default they use the top left 16x16 pixels.
+    pub fn texture(&mut self, u: usize, v: usize, width: usize, height: usize) -> &mut Self {
+        self.texture = Rect::new(point!(u, v), point!(width, height));
+
+        for index in self.textured.clone() {
+            let uv_maps = &self.mesh.faces[index].uv_maps;
+            let vertices = [0, 1, 2, 3].map(|corner| uv_maps[corner].vertex_index);
+            self.mesh.faces[index].uv_maps = self.face(&vertices).uv_maps;
+        }
+
+        self
+    }
+
+    /// Returns the finished mesh, with the attributes applied to all of its faces.
+    pub fn build(&self) -> Mesh {
+        let mut mesh = self.mesh.clone();
+
+        for face in mesh.faces.iter_mut() {
+            if let Some(color) = self.color {
+                face.color = color;
+            }
+            if let Some(double_sided) = self.double_sided {
+                face.double_sided = double_sided;
+            }
+            if let Some(no_shading) = self.no_shading {
+                face.no_shading = no_shading;
+            }
+            if let Some(no_texture) = self.no_texture {
+                face.no_texture = no_texture;
+            }
+        }
+
+        mesh
+    }
+
+    /// Creates a quad using the given vertices mapped onto the current texture.
+    fn face(&self, vertices: &[usize; 4]) -> Face {
+        Face::builder()
+            .vertices(vertices.iter().copied())
+            .uv_rect(self.texture)
+            .color(Color::LightGrey)
+            .build()
+            .unwrap_or_default()
+    }
+
+    /// Adds the vertices and faces of `shape` to the mesh.
+    /// If `textured` is true, its faces are mapped onto `texture` again when it changes.
+    fn append(&mut self, shape: Mesh, textured: bool) {
+        let offset = self.mesh.vertices.len();
+
+        if textured {
+            let start = self.mesh.faces.len();
+            self.textured.extend(start..start + shape.faces.len());
+        }
+
+        self.mesh.vertices.extend(shape.vertices);
+        self.mesh
+            .faces
+            .extend(shape.faces.into_iter().map(|mut face| {
+                for uv_map in face.uv_maps.iter_mut() {
+                    uv_map.vertex_index += offset;
+                }
+                face
+            }));
+    }
+}
+
+#[macro_export]
+/// Builds a [`Model`](crate::assets::Model) out of a list of meshes.
+///
+/// Starts with a default model, whose name can be set with `name "...";`.
+/// Every `mesh "name" { ... }` adds a mesh built like in [`mesh!`](crate::mesh).
+/// More information can be found in the [`template`](crate::template) module.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::model;
+///
+/// let model = model! {
+///     mesh "cube" { cube(1.0); at(0, 0, 0); color(Red); }
+/// };
+///
+/// assert_eq!(model.meshes.len(), 1);
+/// assert_eq!(model.meshes[0].vertices.len(), 8);
+/// ```
+macro_rules! model {
+    ($($items:tt)*) => {{
+        let mut model = $crate::assets::Model::default();
+        $crate::__model_items!(model; $($items)*);
+        model
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __model_items {
+    ($model:ident;) => {};
+    ($model:ident; name $name:tt; $($rest:tt)*) => {
+        $model.header.name = ::std::string::ToString::to_string(&$name);
+        $crate::__model_items!($model; $($rest)*);
+    };
+    ($model:ident; mesh $name:tt { $($statements:tt)* } $($rest:tt)*) => {
+        $model.meshes.push($crate::mesh!($name { $($statements)* }));
+        $crate::__model_items!($model; $($rest)*);
+    };
+}
+
+#[macro_export]
+/// Builds a single [`Mesh`](crate::assets::Mesh) out of a list of statements, each calling the
+/// [`MeshTemplate`](crate::template::MeshTemplate) method of the same name.
+///
+/// More information can be found in the [`template`](crate::template) module.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Color;
+/// use picocadrs::mesh;
+///
+/// let mesh = mesh!("floor" {
+///     plane(4);
+///     at(0, 1, 0);
+///     color(DarkGreen);
+///     texture(16, 0, 32, 32);
+/// });
+///
+/// assert_eq!(mesh.name, "floor");
+/// assert_eq!(mesh.faces[0].color, Color::DarkGreen);
+/// ```
+macro_rules! mesh {
+    ($name:tt { $($statements:tt)* }) => {{
+        #[allow(unused_mut)]
+        let mut template =
+            $crate::template::MeshTemplate::new(::std::string::ToString::to_string(&$name));
+        $crate::__mesh_statements!(template; $($statements)*);
+        template.build()
+    }};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __mesh_statements {
+    ($template:ident;) => {};
+    ($template:ident; color($color:ident) $(; $($rest:tt)*)?) => {
+        $template.color($crate::assets::Color::$color);
+        $crate::__mesh_statements!($template; $($($rest)*)?);
+    };
+    ($template:ident; $method:ident($($argument:expr),* $(,)?) $(; $($rest:tt)*)?) => {
+        $template.$method($($argument),*);
+        $crate::__mesh_statements!($template; $($($rest)*)?);
+    };
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::UVMap;
+
+    #[test]
+    fn template_cuboid() {
+        let mesh = mesh!("box" {
+            cuboid(2, 4, 6);
+            at(1, 2.5, 3);
+            rotation(0.5, 0, 0);
+            color(Color::Red);
+            double_sided(true)
+        });
+
+        assert_eq!(mesh.name, "box");
+        assert_eq!(mesh.position, point!(1.0, 2.5, 3.0));
+        assert_eq!(mesh.rotation.0, point!(0.5, 0.0, 0.0));
+        assert_eq!(mesh.vertices[0], point!(-1.0, -2.0, -3.0));
+        assert_eq!(mesh.vertices[6], point!(1.0, 2.0, 3.0));
+        assert_eq!(mesh.faces.len(), 6);
+        assert!(mesh.is_watertight());
+
+        for face in mesh.faces.iter() {
+            assert_eq!(face.color, Color::Red);
+            assert!(face.double_sided);
+            assert!(!face.no_texture);
+
+            let center = face
+                .uv_maps
+                .iter()
+                .fold(point!(0.0, 0.0, 0.0), |sum, uv_map| {
+                    sum + mesh.vertices[uv_map.vertex_index]
+                });
+            assert!(face.normal(&mesh.vertices).dot(center) > 0.0);
+        }
+    }
+
+    #[test]
+    fn template_combined_shapes() {
+        let mut template = MeshTemplate::new("shapes".to_string());
+        template
+            .plane(2)
+            .cube(1)
+            .texture(8, 8, 8, 8)
+            .no_texture(true);
+        let mesh = template.build();
+
+        assert_eq!(mesh.vertices.len(), 12);
+        assert_eq!(mesh.faces.len(), 7);
+        assert!(mesh.faces[0].double_sided);
+        assert!(!mesh.faces[1].double_sided);
+        assert!(mesh.faces.iter().all(|face| face.no_texture));
+        assert_eq!(mesh.faces[1].uv_maps[0], UVMap::new(7, point!(1.0, 1.0)));
+        assert_eq!(mesh.faces[6].uv_maps[2], UVMap::new(8, point!(2.0, 2.0)));
+    }
+
+    #[test]
+    fn template_model() {
+        let count = 3;
+        let model = model! {
+            name "scene";
+            mesh "pillar" { cylinder(1, 2, 6) }
+            mesh (format!("cubes_{}", count)) {
+                cube(1);
+                cube(2);
+            }
+            mesh "empty" {}
+        };
+
+        assert_eq!(model.header.name, "scene");
+        assert_eq!(model.meshes.len(), 3);
+        assert_eq!(model.meshes[0].faces.len(), 18);
+        assert_eq!(model.meshes[1].name, "cubes_3");
+        assert_eq!(model.meshes[1].faces.len(), 12);
+        assert!(model.meshes[2].vertices.is_empty());
+    }
+}
@@ -0,0 +1,337 @@
+//! Helpers for testing tools built on this crate against real project files.
+//!
+//! The main use is golden-file tests: keep a folder of project files saved by picoCAD (a corpus)
+//! and check that reading and writing them, or running a transformation on them, doesn't change
+//! anything unexpected.
+//!
+//! There are two levels of strictness:
+//!
+//! - _byte-for-byte:_ [`assert_roundtrip`] checks that writing a parsed file gives back exactly
+//!   the same text.
+//!   This holds for files saved by picoCAD itself.
+//! - _canonical:_ [`assert_canonical_roundtrip`] only checks that a file settles after being
+//!   written once, so writing it again doesn't change it any further and it still holds the same
+//!   model.
+//!   This holds for hand-written or generated files with unusual number formatting or spacing.
+//!
+//! Failed assertions panic with the first line that differs, so they can be used in `#[test]`
+//! functions directly.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{Model, ProjectPreset};
+//! use picocadrs::testing;
+//!
+//! let file = Model::new_project("house".to_string(), ProjectPreset::Plane).to_string();
+//!
+//! testing::assert_roundtrip(&file);
+//! testing::assert_preserves(&file, |model| model.meshes.reverse());
+//! ```
+
+use crate::{assets::Model, error::PicoError, files::PROJECT_EXTENSION};
+use core::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// A project file of a corpus and its contents.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CorpusFile {
+    /// Path the file was read from.
+    pub path: PathBuf,
+    /// Contents of the file.
+    pub contents: String,
+}
+
+/// The first line two texts differ in, returned by [`first_difference`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Difference {
+    /// Number of the line, starting at 1.
+    pub line: usize,
+    /// The line in the expected text, [`None`] if it ended before.
+    pub expected: Option<String>,
+    /// The line in the actual text, [`None`] if it ended before.
+    pub found: Option<String>,
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "line {}: expected {}, found {}",
+            self.line,
+            self.expected
+                .as_ref()
+                .map_or("end of file".to_string(), |line| format!("{:?}", line)),
+            self.found
+                .as_ref()
+                .map_or("end of file".to_string(), |line| format!("{:?}", line)),
+        )
+    }
+}
+
+/// Parses `src` and writes the model again.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::Model;
+/// use picocadrs::testing;
+///
+/// let file = Model::default().to_string();
+///
+/// assert_eq!(testing::roundtrip(&file).unwrap(), file);
+/// assert!(testing::roundtrip("not a project").is_err());
+/// ```
+pub fn roundtrip(src: &str) -> Result<String, PicoError> {
+    Ok(src.parse::<Model>()?.to_string())
+}
+
+/// Returns the first line `found` differs from `expected` in.
+///
+/// Returns [`None`] if both texts are the same.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::testing;
+///
+/// let difference = testing::first_difference("a\nb\nc", "a\nx").unwrap();
+///
+/// assert_eq!(difference.line, 2);
+/// assert_eq!(difference.expected.as_deref(), Some("b"));
+/// assert_eq!(difference.found.as_deref(), Some("x"));
+/// assert!(testing::first_difference("a\nb", "a\nb").is_none());
+/// ```
+pub fn first_difference(expected: &str, found: &str) -> Option<Difference> {
+    if expected == found {
+        return None;
+    }
+
+    let mut expected_lines = expected.split('\n');
+    let mut found_lines = found.split('\n');
+    let mut line = 1;
+
+    loop {
+        match (expected_lines.next(), found_lines.next()) {
+            (Some(a), Some(b)) if a == b => line += 1,
+            (None, None) => return None,
+            (a, b) => {
+                return Some(Difference {
+                    line,
+                    expected: a.map(str::to_string),
+                    found: b.map(str::to_string),
+                })
+            }
+        }
+    }
+}
+
+/// Asserts that writing the parsed file gives back exactly `src`.
+///
+/// # Panics
+///
+/// Panics if `src` can't be parsed or the written file differs from it.
+pub fn assert_roundtrip(src: &str) {
+    let written = parse_or_panic(src).to_string();
+
+    if let Some(difference) = first_difference(src, &written) {
+        panic!("file changed after a round-trip, {}", difference);
+    }
+}
+
+/// Asserts that the file settles after being written once: writing it a second time gives the
+/// same text as the first time, and both hold the same model as `src`.
+///
+/// # Panics
+///
+/// Panics if `src` or the written file can't be parsed, or either check fails.
+///
+/// # Example
+///
+/// ```
+/// use picocadrs::assets::{Model, ProjectPreset};
+/// use picocadrs::testing;
+///
+/// // numbers are written shorter than they were read.
+/// let file = Model::new_project("plane".to_string(), ProjectPreset::Plane)
+///     .to_string()
+///     .replacen("pos={0,0,0}", "pos={0.0,0,0}", 1);
+///
+/// testing::assert_canonical_roundtrip(&file);
+/// ```
+pub fn assert_canonical_roundtrip(src: &str) {
+    let model = parse_or_panic(src);
+    let first = model.to_string();
+    let reparsed = parse_or_panic(&first);
+    let second = reparsed.to_string();
+
+    if let Some(difference) = first_difference(&first, &second) {
+        panic!(
+            "file didn't settle after being written once, {}",
+            difference
+        );
+    }
+    if model != reparsed {
+        panic!("model changed after being written");
+    }
+}
+
+/// Asserts that `transform` doesn't change the written file.
+///
+/// Useful for transformations that should only reorder or clean up things that aren't written,
+/// or that are expected to leave certain files alone.
+///
+/// # Panics
+///
+/// Panics if `src` can't be parsed or the file written after the transformation differs from the
+/// one written before it.
+pub fn assert_preserves<F>(src: &str, transform: F)
+where
+    F: FnOnce(&mut Model),
+{
+    let mut model = parse_or_panic(src);
+    let before = model.to_string();
+    transform(&mut model);
+    let after = model.to_string();
+
+    if let Some(difference) = first_difference(&before, &after) {
+        panic!("transformation changed the file, {}", difference);
+    }
+}
+
+/// Reads every project file (ending with `.txt`) directly inside of `dir`, sorted by path.
+///
+/// Returns [`PicoError::IO`] if the folder or one of the files can't be read.
+pub fn load_corpus<P: AsRef<Path>>(dir: P) -> Result<Vec<CorpusFile>, PicoError> {
+    let mut paths = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_file()
+            && path.extension().and_then(|extension| extension.to_str()) == Some(PROJECT_EXTENSION)
+        {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let contents = std::fs::read_to_string(&path)?;
+            Ok(CorpusFile { path, contents })
+        })
+        .collect()
+}
+
+/// Runs `check` on every file of the corpus in `dir`, see [`load_corpus`].
+///
+/// Returns the amount of files checked.
+///
+/// # Panics
+///
+/// Panics if the corpus can't be read or `check` panics, naming the file it panicked on.
+///
+/// # Example
+///
+/// ```no_run
+/// use picocadrs::testing;
+///
+/// let checked = testing::check_corpus("tests/corpus", testing::assert_roundtrip);
+/// assert!(checked > 0);
+/// ```
+pub fn check_corpus<P, F>(dir: P, check: F) -> usize
+where
+    P: AsRef<Path>,
+    F: Fn(&str),
+{
+    let dir = dir.as_ref();
+    let corpus = load_corpus(dir)
+        .unwrap_or_else(|error| panic!("could not read corpus {}: {}", dir.display(), error));
+
+    for file in corpus.iter() {
+        let result =
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| check(&file.contents)));
+
+        if let Err(cause) = result {
+            let message = cause
+                .downcast_ref::<String>()
+                .map(String::as_str)
+                .or_else(|| cause.downcast_ref::<&str>().copied())
+                .unwrap_or("unknown error");
+            panic!("{}: {}", file.path.display(), message);
+        }
+    }
+
+    corpus.len()
+}
+
+/// Parses `src`, panicking with the error if it can't be parsed.
+fn parse_or_panic(src: &str) -> Model {
+    src.parse::<Model>()
+        .unwrap_or_else(|error| panic!("could not parse file: {}", error))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::ProjectPreset;
+
+    fn plane_file() -> String {
+        Model::new_project("plane".to_string(), ProjectPreset::Plane).to_string()
+    }
+
+    #[test]
+    fn testing_first_difference() {
+        let difference = first_difference("a\nb", "a\nb\nc").unwrap();
+
+        assert_eq!(difference.line, 3);
+        assert_eq!(difference.expected, None);
+        assert_eq!(
+            difference.to_string(),
+            "line 3: expected end of file, found \"c\""
+        );
+    }
+
+    #[test]
+    fn testing_roundtrip() {
+        let file = plane_file();
+
+        assert_roundtrip(&file);
+        assert_canonical_roundtrip(&file);
+
+        let padded = file.replacen("pos={0,0,0}", "pos={0.0,0,0}", 1);
+        assert_canonical_roundtrip(&padded);
+        assert!(std::panic::catch_unwind(|| assert_roundtrip(&padded)).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "transformation changed the file, line 4")]
+    fn testing_preserves() {
+        assert_preserves(&plane_file(), |model| {
+            model.meshes[0].name = "moved".to_string()
+        });
+    }
+
+    #[test]
+    fn testing_corpus() {
+        let dir = std::env::temp_dir().join("picocadrs_testing_corpus");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(dir.join("b.txt"), plane_file()).unwrap();
+        std::fs::write(dir.join("a.txt"), Model::default().to_string()).unwrap();
+        std::fs::write(dir.join("notes.md"), "not a project").unwrap();
+
+        let corpus = load_corpus(&dir).unwrap();
+        assert_eq!(corpus.len(), 2);
+        assert!(corpus[0].path.ends_with("a.txt"));
+        assert_eq!(check_corpus(&dir, assert_roundtrip), 2);
+
+        std::fs::write(dir.join("c.txt"), "broken").unwrap();
+        let result = std::panic::catch_unwind(|| check_corpus(&dir, assert_roundtrip));
+        assert!(result.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,389 @@
+//! For turning arbitrary images into textures.
+//!
+//! Mapping every pixel of an image onto its nearest pico-8 color (see
+//! [`Palette::map_image`]) loses a lot of detail, since there are only 16 colors.
+//! A [`Quantizer`] can instead dither the image, which mixes neighbouring pixels of different
+//! colors to simulate the colors in between.
+//!
+//! Pico-8 also knows 16 secret colors (see [`ExtendedColor`](crate::assets::ExtendedColor)).
+//! They can't be stored in a texture, but [`Quantizer::quantize_extended`] can use them when
+//! preparing images for pico-8 carts.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::texture::{Dithering, Quantizer};
+//!
+//! // a grey gradient from black to white.
+//! let mut image: Vec<u8> = vec![];
+//! for _ in 0..120 {
+//!     for u in 0..128 {
+//!         let value = (u * 2) as u8;
+//!         image.extend_from_slice(&[value, value, value, 255]);
+//!     }
+//! }
+//!
+//! let quantizer = Quantizer::new(Dithering::FloydSteinberg);
+//! let footer = quantizer.quantize(128, 120, &image).unwrap();
+//! ```
+
+use crate::{
+    assets::{color::rgb_distance, Color, ExtendedColor, Footer, Palette, Point2D},
+    error::PicoError,
+    point,
+};
+
+/// 4x4 Bayer matrix used for ordered dithering.
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// The ways a [`Quantizer`] can dither an image.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub enum Dithering {
+    /// Every pixel becomes its nearest color.
+    #[default]
+    None,
+    /// Pixels are nudged by a repeating 4x4 Bayer pattern before picking the nearest color.
+    /// Gives the regular cross-hatch look known from old games.
+    Ordered,
+    /// The difference between a pixel and its nearest color is spread onto the pixels to the right
+    /// and below it.
+    /// Gives the most accurate colors but a more noisy look.
+    FloydSteinberg,
+}
+
+/// Turns rgba images into textures using a [`Palette`] and a [`Dithering`] mode.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Quantizer {
+    /// Colors the image is mapped onto.
+    pub palette: Palette,
+    /// How the image is dithered.
+    pub dithering: Dithering,
+    /// How strongly [`Dithering::Ordered`] nudges pixels, in rgb values.
+    pub spread: u8,
+    /// If true, [`quantize_extended`](Quantizer::quantize_extended) may also pick the secret
+    /// pico-8 colors (see [`ExtendedColor`]).
+    /// [`quantize`](Quantizer::quantize) never uses them.
+    pub allow_secret_colors: bool,
+}
+
+impl Quantizer {
+    /// Creates a quantizer using every pico-8 color and the given dithering mode.
+    pub fn new(dithering: Dithering) -> Quantizer {
+        Quantizer {
+            palette: Palette::default(),
+            dithering,
+            spread: 48,
+            allow_secret_colors: false,
+        }
+    }
+
+    /// Maps an rgba image onto the colors of the palette and stores it as a texture.
+    ///
+    /// The image is read the same way as in [`Palette::map_image`]: 4 bytes per pixel, only the
+    /// top left 128x120 pixels are used and the alpha channel is ignored.
+    ///
+    /// Returns [`PicoError::ImageSize`] if `rgba` doesn't hold exactly `width * height` pixels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::{Color, Palette, Point2D};
+    /// use picocadrs::point;
+    /// use picocadrs::texture::{Dithering, Quantizer};
+    ///
+    /// let quantizer = Quantizer {
+    ///     palette: Palette::new(vec![Color::Black, Color::White]),
+    ///     ..Quantizer::new(Dithering::Ordered)
+    /// };
+    ///
+    /// // a 50% grey square turns into a checkerboard-like pattern.
+    /// let image = vec![128; 4 * 4 * 4];
+    /// let footer = quantizer.quantize(4, 4, &image).unwrap();
+    ///
+    /// assert_ne!(footer.pixel(point!(0, 0)), footer.pixel(point!(1, 0)));
+    /// ```
+    pub fn quantize(&self, width: usize, height: usize, rgba: &[u8]) -> Result<Footer, PicoError> {
+        if rgba.len() != width * height * 4 {
+            return Err(PicoError::ImageSize(rgba.len(), width * height * 4));
+        }
+
+        let width_used = width.min(Footer::WIDTH);
+        let pixels = self.dither(
+            width,
+            height,
+            rgba,
+            |r, g, b| self.palette.nearest(r, g, b),
+            |color| color.as_rgb(),
+        );
+
+        let mut footer = Footer::default();
+
+        for (i, color) in pixels.into_iter().enumerate() {
+            if let Some(pixel) = footer.get_mut(point!(i % width_used, i / width_used)) {
+                *pixel = color;
+            }
+        }
+
+        Ok(footer)
+    }
+
+    /// Maps an rgba image onto the full pico-8 palette, including the secret colors if
+    /// [`allow_secret_colors`](Quantizer::allow_secret_colors) is set.
+    ///
+    /// Works like [`quantize`](Quantizer::quantize), but returns the 128x120 pixels of the texture
+    /// row by row instead of a [`Footer`], since a footer can't store secret colors.
+    /// Pixels outside of the image are black.
+    ///
+    /// Such textures can be exported using
+    /// [`write_extended_png`](crate::export::image::write_extended_png).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use picocadrs::assets::ExtendedColor;
+    /// use picocadrs::texture::Quantizer;
+    ///
+    /// let mut quantizer = Quantizer::default();
+    /// let image = [243, 239, 125, 255];
+    ///
+    /// let pixels = quantizer.quantize_extended(1, 1, &image).unwrap();
+    /// assert!(!pixels[0].is_secret());
+    ///
+    /// quantizer.allow_secret_colors = true;
+    /// let pixels = quantizer.quantize_extended(1, 1, &image).unwrap();
+    /// assert_eq!(pixels[0], ExtendedColor::Secret(7));
+    /// ```
+    pub fn quantize_extended(
+        &self,
+        width: usize,
+        height: usize,
+        rgba: &[u8],
+    ) -> Result<Vec<ExtendedColor>, PicoError> {
+        if rgba.len() != width * height * 4 {
+            return Err(PicoError::ImageSize(rgba.len(), width * height * 4));
+        }
+
+        let mut colors: Vec<ExtendedColor> = self
+            .palette
+            .colors()
+            .iter()
+            .map(|color| ExtendedColor::Base(*color))
+            .collect();
+
+        if self.allow_secret_colors {
+            colors.extend((0..16).map(ExtendedColor::Secret));
+        }
+
+        let nearest = |r: u8, g: u8, b: u8| {
+            colors
+                .iter()
+                .copied()
+                .min_by(|a, b_color| {
+                    rgb_distance(a.as_rgb(), (r, g, b))
+                        .total_cmp(&rgb_distance(b_color.as_rgb(), (r, g, b)))
+                })
+                .unwrap_or(ExtendedColor::Base(Color::Black))
+        };
+
+        let width_used = width.min(Footer::WIDTH);
+        let pixels = self.dither(width, height, rgba, nearest, |color| color.as_rgb());
+
+        let mut texture = vec![ExtendedColor::Base(Color::Black); Footer::WIDTH * Footer::HEIGHT];
+
+        for (i, color) in pixels.into_iter().enumerate() {
+            texture[(i / width_used) * Footer::WIDTH + i % width_used] = color;
+        }
+
+        Ok(texture)
+    }
+
+    /// Dithers the top left 128x120 pixels of an image, picking colors using `nearest`.
+    /// Returns the picked colors of the used part of the image row by row.
+    ///
+    /// `rgba` has to be checked to hold `width * height` pixels before.
+    fn dither<T: Copy>(
+        &self,
+        width: usize,
+        height: usize,
+        rgba: &[u8],
+        nearest: impl Fn(u8, u8, u8) -> T,
+        as_rgb: impl Fn(T) -> (u8, u8, u8),
+    ) -> Vec<T> {
+        let width_used = width.min(Footer::WIDTH);
+        let height_used = height.min(Footer::HEIGHT);
+
+        // clamps the channels into the rgb range before picking a color.
+        let nearest = |pixel: [f64; 3]| {
+            let [r, g, b] = pixel.map(|channel| channel.round().clamp(0.0, 255.0) as u8);
+            nearest(r, g, b)
+        };
+
+        // working copy of the used part of the image, so errors can be diffused.
+        let mut pixels: Vec<[f64; 3]> = Vec::with_capacity(width_used * height_used);
+        for v in 0..height_used {
+            for u in 0..width_used {
+                let i = (v * width + u) * 4;
+                pixels.push([rgba[i] as f64, rgba[i + 1] as f64, rgba[i + 2] as f64]);
+            }
+        }
+
+        let mut colors: Vec<T> = Vec::with_capacity(width_used * height_used);
+
+        for v in 0..height_used {
+            for u in 0..width_used {
+                let pixel = pixels[v * width_used + u];
+
+                let color = match self.dithering {
+                    Dithering::None => nearest(pixel),
+                    Dithering::Ordered => {
+                        let threshold = (BAYER_4X4[v % 4][u % 4] as f64 + 0.5) / 16.0 - 0.5;
+                        let offset = threshold * self.spread as f64;
+
+                        nearest(pixel.map(|channel| channel + offset))
+                    }
+                    Dithering::FloydSteinberg => {
+                        let color = nearest(pixel);
+                        let (r, g, b) = as_rgb(color);
+                        let error = [
+                            pixel[0] - r as f64,
+                            pixel[1] - g as f64,
+                            pixel[2] - b as f64,
+                        ];
+
+                        let mut diffuse = |du: isize, dv: usize, weight: f64| {
+                            let target_u = u as isize + du;
+
+                            if target_u < 0 || target_u >= width_used as isize {
+                                return;
+                            }
+                            if v + dv >= height_used {
+                                return;
+                            }
+
+                            let target = &mut pixels[(v + dv) * width_used + target_u as usize];
+                            for channel in 0..3 {
+                                target[channel] += error[channel] * weight;
+                            }
+                        };
+
+                        diffuse(1, 0, 7.0 / 16.0);
+                        diffuse(-1, 1, 3.0 / 16.0);
+                        diffuse(0, 1, 5.0 / 16.0);
+                        diffuse(1, 1, 1.0 / 16.0);
+
+                        color
+                    }
+                };
+
+                colors.push(color);
+            }
+        }
+
+        colors
+    }
+}
+
+impl Default for Quantizer {
+    /// Creates a quantizer using every pico-8 color without dithering.
+    fn default() -> Self {
+        Quantizer::new(Dithering::None)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    fn grey_image(width: usize, height: usize, value: u8) -> Vec<u8> {
+        [value, value, value, 255].repeat(width * height)
+    }
+
+    fn black_and_white() -> Palette {
+        Palette::new(vec![Color::Black, Color::White])
+    }
+
+    fn count(footer: &Footer, color: Color, width: usize, height: usize) -> usize {
+        let mut count = 0;
+
+        for v in 0..height {
+            for u in 0..width {
+                if footer.pixel(point!(u, v)) == color {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    #[test]
+    fn quantize_none() {
+        let quantizer = Quantizer {
+            palette: black_and_white(),
+            ..Quantizer::default()
+        };
+
+        let footer = quantizer.quantize(8, 8, &grey_image(8, 8, 100)).unwrap();
+        assert_eq!(count(&footer, Color::Black, 8, 8), 64);
+    }
+
+    #[test]
+    fn quantize_ordered() {
+        let quantizer = Quantizer {
+            palette: black_and_white(),
+            spread: 255,
+            ..Quantizer::new(Dithering::Ordered)
+        };
+
+        let footer = quantizer.quantize(8, 8, &grey_image(8, 8, 128)).unwrap();
+        let white = count(&footer, Color::White, 8, 8);
+
+        assert!(white > 16 && white < 48);
+        // the pattern repeats every 4 pixels.
+        assert_eq!(footer.pixel(point!(1, 2)), footer.pixel(point!(5, 6)));
+    }
+
+    #[test]
+    fn quantize_floyd_steinberg() {
+        let quantizer = Quantizer {
+            palette: black_and_white(),
+            ..Quantizer::new(Dithering::FloydSteinberg)
+        };
+
+        let footer = quantizer
+            .quantize(128, 120, &grey_image(128, 120, 128))
+            .unwrap();
+        let white = count(&footer, Color::White, 128, 120) as f64 / (128.0 * 120.0);
+
+        assert!((white - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn quantize_extended() {
+        // the light yellow secret color.
+        let image = [243, 239, 125, 255].repeat(4);
+        let mut quantizer = Quantizer::default();
+
+        let pixels = quantizer.quantize_extended(2, 2, &image).unwrap();
+        assert_eq!(pixels.len(), 128 * 120);
+        assert_eq!(pixels[0], ExtendedColor::Base(Color::LightPeach));
+
+        quantizer.allow_secret_colors = true;
+        let pixels = quantizer.quantize_extended(2, 2, &image).unwrap();
+        assert_eq!(pixels[1], ExtendedColor::Secret(7));
+        assert_eq!(pixels[128], ExtendedColor::Secret(7));
+        assert_eq!(pixels[2], ExtendedColor::Base(Color::Black));
+
+        // the footer can't store secret colors.
+        let footer = quantizer.quantize(2, 2, &image).unwrap();
+        assert_eq!(footer.pixel(point!(0, 0)), Color::LightPeach);
+    }
+
+    #[test]
+    fn quantize_size() {
+        assert!(matches!(
+            Quantizer::default().quantize(3, 3, &[0; 4]),
+            Err(PicoError::ImageSize(4, 36))
+        ));
+    }
+}
@@ -0,0 +1,231 @@
+//! For finding faces that can't be seen from any of a set of viewpoints.
+//!
+//! Models that are only ever shown from a few angles, like dioramas in a game that are always
+//! seen from the front, often contain a lot of faces that never end up on screen.
+//! [`hidden_faces`] finds them and [`remove_hidden_faces`] deletes them.
+//!
+//! A face counts as visible from a [`CameraParams`] if a ray from the camera reaches its center
+//! or a point close to one of its corners before hitting any other face.
+//! Rays can't hit the backs of faces that aren't
+//! [`double_sided`](crate::assets::Face::double_sided), so these are hidden when pointing away
+//! from every camera, just like picoCAD doesn't draw them.
+//! The edges of the image are not taken into account, faces outside of the view of a camera still
+//! count as visible if nothing is in front of them.
+//! The rotation of meshes is ignored, the same way [`raycast`](crate::raycast) ignores it.
+//!
+//! Faces with less than 3 corners or corners without a vertex can't be tested and are always
+//! visible.
+//!
+//! # Example
+//!
+//! ```
+//! use picocadrs::assets::{MeshFaceRef, Model, ProjectPreset};
+//! use picocadrs::camera::CameraParams;
+//!
+//! let mut model = Model::new_project("wall".to_string(), ProjectPreset::Plane);
+//! let mut bottom = model.meshes[0].faces[0].clone();
+//! bottom.flip();
+//! bottom.double_sided = false;
+//! model.meshes[0].faces.push(bottom);
+//!
+//! // the default camera looks down onto the plane, so its bottom is never visible.
+//! let cameras = [CameraParams::default()];
+//!
+//! assert_eq!(model.hidden_faces(&cameras), vec![MeshFaceRef::new(0, 1)]);
+//! assert_eq!(model.remove_hidden_faces(&cameras), 1);
+//! assert_eq!(model.meshes[0].faces.len(), 1);
+//! ```
+
+use crate::{
+    assets::{MeshFaceRef, Model, Point3D},
+    camera::{CameraParams, Projection},
+    point,
+    spatial::Bvh,
+};
+
+/// How far the points tested near the corners of a face are moved towards its center, as a share
+/// of the distance between them.
+const CORNER_INSET: f64 = 0.1;
+
+/// Distance by which another face may be hit before a face and still not count as covering it.
+const HIT_TOLERANCE: f64 = 1e-6;
+
+/// Returns every face of the model that is visible from none of the `cameras`, sorted by mesh and
+/// face index.
+///
+/// Without any cameras every face is hidden.
+///
+/// It's recommended to use [`Model::hidden_faces`].
+pub fn hidden_faces(model: &Model, cameras: &[CameraParams]) -> Vec<MeshFaceRef> {
+    let bvh = model.bvh();
+    let mut hidden = vec![];
+
+    for (mesh_index, mesh) in model.meshes.iter().enumerate() {
+        for (face_index, face) in mesh.faces.iter().enumerate() {
+            let face_ref = MeshFaceRef::new(mesh_index, face_index);
+
+            let Some(corners) = face
+                .uv_maps
+                .iter()
+                .map(|uv_map| mesh.vertices.get(uv_map.vertex_index).copied())
+                .collect::<Option<Vec<Point3D<f64>>>>()
+                .filter(|corners| corners.len() >= 3)
+            else {
+                continue;
+            };
+
+            let center = corners
+                .iter()
+                .fold(point!(0.0, 0.0, 0.0), |sum, corner| sum + *corner)
+                / corners.len() as f64;
+
+            let mut samples = vec![center + mesh.position];
+            for corner in corners.iter() {
+                samples.push(*corner + (center - *corner) * CORNER_INSET + mesh.position);
+            }
+
+            let visible = cameras.iter().any(|camera| {
+                samples
+                    .iter()
+                    .any(|sample| reaches(model, &bvh, camera, *sample, face_ref))
+            });
+
+            if !visible {
+                hidden.push(face_ref);
+            }
+        }
+    }
+
+    hidden
+}
+
+/// Removes every face of the model that is visible from none of the `cameras` and returns the
+/// amount of removed faces.
+///
+/// Vertices are kept, see [`Mesh::remove_unused_vertices`](crate::assets::Mesh::remove_unused_vertices)
+/// for removing the ones no face uses anymore.
+///
+/// It's recommended to use [`Model::remove_hidden_faces`].
+pub fn remove_hidden_faces(model: &mut Model, cameras: &[CameraParams]) -> usize {
+    let hidden = hidden_faces(model, cameras);
+
+    // removing from the back keeps the indices of the faces still to be removed valid.
+    for face_ref in hidden.iter().rev() {
+        model.meshes[face_ref.mesh].remove_face(face_ref.face);
+    }
+
+    hidden.len()
+}
+
+/// Checks if a ray from the camera towards `point`, given in world space, hits the face `target`
+/// before any other face.
+/// Faces hit at the same distance, like copies of the same face, don't block each other.
+fn reaches(
+    model: &Model,
+    bvh: &Bvh<MeshFaceRef>,
+    camera: &CameraParams,
+    point: Point3D<f64>,
+    target: MeshFaceRef,
+) -> bool {
+    let (origin, direction) = match camera.projection {
+        Projection::Perspective => {
+            if point.project(camera).is_none() {
+                return false;
+            }
+
+            let origin = camera.position();
+            (origin, (point - origin).normalized())
+        }
+        Projection::Orthographic => {
+            let forward = camera.forward();
+            let depth = (point - camera.position()).dot(forward);
+
+            (point - forward * depth, forward)
+        }
+    };
+
+    // the back of a single-sided face can't be hit, even if nothing is in front of it.
+    let mesh = &model.meshes[target.mesh];
+    let Some(own) =
+        mesh.faces[target.face].intersect_ray(&mesh.vertices, origin - mesh.position, direction)
+    else {
+        return false;
+    };
+
+    match bvh.raycast(model, origin, direction) {
+        Some(hit) => hit.face == target || hit.hit.distance >= own.distance - HIT_TOLERANCE,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::assets::Face;
+    use crate::fixtures;
+
+    /// A unit cube made of double-sided faces.
+    fn cube() -> Model {
+        let mut mesh = fixtures::cube();
+        for vertex in mesh.vertices.iter_mut() {
+            *vertex = *vertex * 0.5;
+        }
+        for face in mesh.faces.iter_mut() {
+            face.double_sided = true;
+        }
+
+        Model {
+            meshes: vec![mesh],
+            ..Model::default()
+        }
+    }
+
+    fn front(projection: Projection) -> CameraParams {
+        CameraParams {
+            yaw: 0.0,
+            pitch: 0.0,
+            projection,
+            ..CameraParams::default()
+        }
+    }
+
+    #[test]
+    fn visibility_occluded_faces() {
+        let model = cube();
+
+        for projection in [Projection::Perspective, Projection::Orthographic] {
+            let hidden = model.hidden_faces(&[front(projection)]);
+
+            // only the face at +z is visible from the front.
+            assert_eq!(hidden.len(), 5);
+            assert!(!hidden.contains(&MeshFaceRef::new(0, 4)));
+        }
+
+        let back = CameraParams {
+            yaw: core::f64::consts::PI,
+            ..front(Projection::Perspective)
+        };
+        assert_eq!(
+            model
+                .hidden_faces(&[front(Projection::Perspective), back])
+                .len(),
+            4
+        );
+        assert_eq!(model.hidden_faces(&[]).len(), 6);
+    }
+
+    #[test]
+    fn visibility_remove_hidden_faces() {
+        let mut model = cube();
+        let broken = Face::builder().vertices([0, 9, 1]).build().unwrap();
+        model.meshes[0].faces.insert(0, broken.clone());
+
+        assert_eq!(
+            model.remove_hidden_faces(&[front(Projection::Perspective)]),
+            5
+        );
+        assert_eq!(model.meshes[0].faces.len(), 2);
+        assert_eq!(model.meshes[0].faces[0], broken);
+        assert_eq!(model.meshes[0].vertices.len(), 8);
+    }
+}
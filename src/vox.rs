@@ -0,0 +1,515 @@
+//! For importing [MagicaVoxel](https://ephtracy.github.io/) models.
+//!
+//! [`from_vox`] reads the bytes of a `.vox` file and turns every model inside of it into a mesh.
+//! Only the outside of each model is kept: a side of a voxel becomes part of a face if there is no
+//! voxel next to it.
+//! Neighbouring sides with the same color are merged into as few quads as possible ("greedy
+//! meshing"), which keeps the amount of faces low enough for picoCAD.
+//!
+//! Colors of the palette of the file are replaced with the closest of the 16 colors picoCAD knows,
+//! see [`Color::nearest_rgb`].
+//! Files without a palette use the default palette of MagicaVoxel.
+//!
+//! By default faces show their color instead of the texture.
+//! With [`VoxOptions::bake_texture`] the palette strip is painted onto the texture instead (see
+//! [`Footer::paint_palette_strip`](crate::assets::Footer::paint_palette_strip)) and every face is
+//! mapped onto its color, like [`Mesh::bake_vertex_colors`] does.
+//!
+//! MagicaVoxel uses the `z`-axis as up, which becomes the `y`-axis in picoCAD.
+//! Each mesh stands on its position, centered on the other two axes.
+//! Where models are placed in the scene of a file is ignored, so all meshes start at the origin.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use picocadrs::assets::Model;
+//! use picocadrs::vox::VoxOptions;
+//!
+//! let bytes = std::fs::read("castle.vox").unwrap();
+//! let options = VoxOptions {
+//!     bake_texture: true,
+//!     ..VoxOptions::default()
+//! };
+//!
+//! let mut model = Model::from_vox(&bytes, options).unwrap();
+//! model.header.name = "castle".to_string();
+//! model.write().unwrap();
+//! ```
+
+use crate::{
+    assets::{
+        mesh::{UV_PIXEL_STEP, VERTEX_COLOR_STRIP},
+        Color, Face, Mesh, Model, Point2D, Point3D, UVMap,
+    },
+    error::PicoError,
+    point,
+};
+use std::collections::HashMap;
+
+/// Options for [`from_vox`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct VoxOptions {
+    /// Length of the edges of a voxel in picoCAD units.
+    pub voxel_size: f64,
+    /// Map faces onto a palette strip painted onto the texture instead of turning the texture
+    /// off.
+    pub bake_texture: bool,
+}
+
+impl Default for VoxOptions {
+    /// Returns options where each voxel is as big as a step of picoCAD's grid and faces show their
+    /// color.
+    fn default() -> Self {
+        VoxOptions {
+            voxel_size: 0.25,
+            bake_texture: false,
+        }
+    }
+}
+
+/// Voxels of a single model inside a `.vox` file.
+#[derive(Debug, Clone)]
+struct VoxelGrid {
+    size: [usize; 3],
+    /// Palette index of each voxel, `0` if it is empty.
+    voxels: Vec<u8>,
+}
+
+impl VoxelGrid {
+    /// Returns the palette index of the voxel at `position`, `0` if it is empty or outside of the
+    /// grid.
+    fn get(&self, position: [i64; 3]) -> u8 {
+        if (0..3).any(|axis| position[axis] < 0 || position[axis] >= self.size[axis] as i64) {
+            return 0;
+        }
+
+        let [x, y, z] = position.map(|value| value as usize);
+        self.voxels[x + self.size[0] * (y + self.size[1] * z)]
+    }
+}
+
+/// Converts the bytes of a `.vox` file into a model with a mesh for each model in the file.
+///
+/// Fails with [`PicoError::Vox`] if the file is broken or isn't a `.vox` file.
+///
+/// It's recommended to use [`Model::from_vox`].
+pub fn from_vox(bytes: &[u8], options: VoxOptions) -> Result<Model, PicoError> {
+    let (grids, palette) = parse(bytes)?;
+    let colors = palette.map(|[r, g, b]| Color::nearest_rgb(r, g, b));
+
+    let mut model = Model::default();
+
+    for (index, grid) in grids.iter().enumerate() {
+        let mut mesh = mesh_grid(grid, &colors, options);
+        mesh.name = if grids.len() == 1 {
+            "vox".to_string()
+        } else {
+            format!("vox_{}", index)
+        };
+        model.meshes.push(mesh);
+    }
+
+    if options.bake_texture {
+        model.footer.paint_palette_strip(VERTEX_COLOR_STRIP);
+    }
+
+    Ok(model)
+}
+
+/// Reads every model and the palette (indexed by palette index) out of a `.vox` file.
+fn parse(bytes: &[u8]) -> Result<(Vec<VoxelGrid>, [[u8; 3]; 256]), PicoError> {
+    let mut reader = Reader { bytes, position: 0 };
+
+    if reader.take(4)? != b"VOX " {
+        return Err(PicoError::Vox("missing VOX header".to_string()));
+    }
+    reader.u32()?;
+
+    if reader.take(4)? != b"MAIN" {
+        return Err(PicoError::Vox("missing MAIN chunk".to_string()));
+    }
+    let content = reader.u32()? as usize;
+    reader.u32()?;
+    reader.take(content)?;
+
+    let mut grids: Vec<VoxelGrid> = vec![];
+    let mut size: Option<[usize; 3]> = None;
+    let mut palette = default_palette();
+
+    // the children of MAIN follow each other until the end of the file.
+    while reader.position < bytes.len() {
+        let id = reader.take(4)?;
+        let content = reader.u32()? as usize;
+        let children = reader.u32()? as usize;
+        let mut chunk = Reader {
+            bytes: reader.take(content)?,
+            position: 0,
+        };
+        reader.take(children)?;
+
+        match id {
+            b"SIZE" => {
+                let size_x = chunk.u32()? as usize;
+                let size_y = chunk.u32()? as usize;
+                let size_z = chunk.u32()? as usize;
+
+                if size_x.saturating_mul(size_y).saturating_mul(size_z) > 256 * 256 * 256 {
+                    return Err(PicoError::Vox(format!(
+                        "model of size {}x{}x{} is too big",
+                        size_x, size_y, size_z
+                    )));
+                }
+                size = Some([size_x, size_y, size_z]);
+            }
+            b"XYZI" => {
+                let Some(size) = size.take() else {
+                    return Err(PicoError::Vox("voxels without a size".to_string()));
+                };
+
+                let mut grid = VoxelGrid {
+                    size,
+                    voxels: vec![0; size[0] * size[1] * size[2]],
+                };
+
+                for _ in 0..chunk.u32()? {
+                    let voxel = chunk.take(4)?;
+                    let position = [voxel[0], voxel[1], voxel[2]].map(|value| value as usize);
+
+                    if (0..3).all(|axis| position[axis] < size[axis]) {
+                        grid.voxels
+                            [position[0] + size[0] * (position[1] + size[1] * position[2])] =
+                            voxel[3];
+                    }
+                }
+
+                grids.push(grid);
+            }
+            b"RGBA" => {
+                // the color of palette index i is stored at i - 1.
+                for color in palette.iter_mut().skip(1) {
+                    let rgba = chunk.take(4)?;
+                    *color = [rgba[0], rgba[1], rgba[2]];
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok((grids, palette))
+}
+
+/// Returns the default palette of MagicaVoxel, indexed by palette index.
+fn default_palette() -> [[u8; 3]; 256] {
+    const STEPS: [u8; 6] = [0xff, 0xcc, 0x99, 0x66, 0x33, 0x00];
+    const RAMP: [u8; 10] = [0xee, 0xdd, 0xbb, 0xaa, 0x88, 0x77, 0x55, 0x44, 0x22, 0x11];
+
+    let mut palette = [[0; 3]; 256];
+    let mut index = 1;
+
+    for r in STEPS {
+        for g in STEPS {
+            for b in STEPS {
+                if index < 216 {
+                    palette[index] = [r, g, b];
+                    index += 1;
+                }
+            }
+        }
+    }
+
+    for value in RAMP {
+        palette[index] = [value, 0, 0];
+        palette[index + 10] = [0, value, 0];
+        palette[index + 20] = [0, 0, value];
+        palette[index + 30] = [value, value, value];
+        index += 1;
+    }
+
+    palette
+}
+
+/// Builds the mesh of the outside of `grid`, merging sides with the same color into quads.
+fn mesh_grid(grid: &VoxelGrid, colors: &[Color; 256], options: VoxOptions) -> Mesh {
+    let mut mesh = Mesh::new("vox".to_string());
+    // index of the vertex at each corner of the grid.
+    let mut corners: HashMap<[usize; 3], usize> = HashMap::new();
+
+    let center = [grid.size[0] as f64 / 2.0, grid.size[1] as f64 / 2.0];
+
+    for axis in 0..3 {
+        let (u_axis, v_axis) = ((axis + 1) % 3, (axis + 2) % 3);
+        let (width, height) = (grid.size[u_axis], grid.size[v_axis]);
+
+        for direction in [-1, 1] {
+            let mut normal = [0.0; 3];
+            normal[axis] = direction as f64;
+            let normal = to_picocad(normal);
+
+            for layer in 0..grid.size[axis] {
+                // color of each visible side in this layer.
+                let mut mask: Vec<Option<Color>> = vec![None; width * height];
+
+                for v in 0..height {
+                    for u in 0..width {
+                        let mut position = [0i64; 3];
+                        position[axis] = layer as i64;
+                        position[u_axis] = u as i64;
+                        position[v_axis] = v as i64;
+
+                        let mut neighbour = position;
+                        neighbour[axis] += direction;
+
+                        let voxel = grid.get(position);
+                        if voxel != 0 && grid.get(neighbour) == 0 {
+                            mask[u + v * width] = Some(colors[voxel as usize]);
+                        }
+                    }
+                }
+
+                for v in 0..height {
+                    let mut u = 0;
+                    while u < width {
+                        let Some(color) = mask[u + v * width] else {
+                            u += 1;
+                            continue;
+                        };
+
+                        let mut quad_width = 1;
+                        while u + quad_width < width
+                            && mask[u + quad_width + v * width] == Some(color)
+                        {
+                            quad_width += 1;
+                        }
+
+                        let mut quad_height = 1;
+                        while v + quad_height < height
+                            && (u..u + quad_width)
+                                .all(|cell| mask[cell + (v + quad_height) * width] == Some(color))
+                        {
+                            quad_height += 1;
+                        }
+
+                        for row in v..v + quad_height {
+                            for cell in u..u + quad_width {
+                                mask[cell + row * width] = None;
+                            }
+                        }
+
+                        let plane = layer + usize::from(direction > 0);
+                        let indices = [
+                            (u, v),
+                            (u + quad_width, v),
+                            (u + quad_width, v + quad_height),
+                            (u, v + quad_height),
+                        ]
+                        .map(|(corner_u, corner_v)| {
+                            let mut corner = [0; 3];
+                            corner[axis] = plane;
+                            corner[u_axis] = corner_u;
+                            corner[v_axis] = corner_v;
+
+                            *corners.entry(corner).or_insert_with(|| {
+                                let [x, y, z] = corner.map(|value| value as f64);
+                                mesh.vertices.push(
+                                    to_picocad([x - center[0], y - center[1], z])
+                                        * options.voxel_size,
+                                );
+                                mesh.vertices.len() - 1
+                            })
+                        });
+
+                        let mut face = quad(indices, color, options.bake_texture);
+                        if face.normal(&mesh.vertices).dot(normal) < 0.0 {
+                            face.flip();
+                        }
+                        mesh.faces.push(face);
+
+                        u += quad_width;
+                    }
+                }
+            }
+        }
+    }
+
+    mesh
+}
+
+/// Creates a quad in the given color, mapped onto its color in the palette strip if
+/// `bake_texture` is set.
+fn quad(indices: [usize; 4], color: Color, bake_texture: bool) -> Face {
+    // corners of the 2x2 pixels of a color in the strip, in pixels.
+    const BLOCK: [(f64, f64); 4] = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+
+    let origin: Point2D<f64> = point!(
+        (VERTEX_COLOR_STRIP.u + 2 * color.as_i32() as usize) as f64,
+        VERTEX_COLOR_STRIP.v as f64
+    );
+
+    Face {
+        uv_maps: indices
+            .iter()
+            .zip(BLOCK.iter())
+            .map(|(index, (du, dv))| {
+                let coords = if bake_texture {
+                    point!(origin.u + du, origin.v + dv) * UV_PIXEL_STEP
+                } else {
+                    point!(0.0, 0.0)
+                };
+
+                UVMap::new(*index, coords)
+            })
+            .collect(),
+        color,
+        no_texture: !bake_texture,
+        ..Face::default()
+    }
+}
+
+/// Converts a point from MagicaVoxel's axes (`z` up) to picoCAD's (`y` down).
+fn to_picocad([x, y, z]: [f64; 3]) -> Point3D<f64> {
+    point!(x, -z, y)
+}
+
+/// Reads little endian values out of bytes, failing if they run out.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], PicoError> {
+        let end = self
+            .position
+            .checked_add(count)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| PicoError::Vox("file ends too early".to_string()))?;
+
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, PicoError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::fixtures::points_outward;
+
+    fn chunk(id: &[u8; 4], content: &[u8]) -> Vec<u8> {
+        let mut bytes = id.to_vec();
+        bytes.extend((content.len() as u32).to_le_bytes());
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend(content);
+        bytes
+    }
+
+    /// Creates a `.vox` file with a single model of the given size and voxels.
+    fn vox_file(size: [u32; 3], voxels: &[[u8; 4]], palette: Option<&[[u8; 4]]>) -> Vec<u8> {
+        let mut children = chunk(b"SIZE", &size.map(u32::to_le_bytes).concat());
+
+        let mut xyzi = (voxels.len() as u32).to_le_bytes().to_vec();
+        xyzi.extend(voxels.concat());
+        children.extend(chunk(b"XYZI", &xyzi));
+
+        if let Some(palette) = palette {
+            let mut rgba = vec![0; 256 * 4];
+            rgba[..palette.len() * 4].copy_from_slice(&palette.concat());
+            children.extend(chunk(b"RGBA", &rgba));
+        }
+
+        let mut bytes = b"VOX ".to_vec();
+        bytes.extend(150u32.to_le_bytes());
+        bytes.extend(b"MAIN");
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend((children.len() as u32).to_le_bytes());
+        bytes.extend(children);
+        bytes
+    }
+
+    #[test]
+    fn vox_greedy_box() {
+        let bytes = vox_file(
+            [3, 2, 2],
+            &[[0, 0, 0, 1], [1, 0, 0, 1], [0, 0, 1, 1], [1, 0, 1, 1]],
+            Some(&[[255, 0, 77, 255]]),
+        );
+        let model = Model::from_vox(&bytes, VoxOptions::default()).unwrap();
+
+        assert_eq!(model.meshes.len(), 1);
+        let mesh = &model.meshes[0];
+
+        assert_eq!(mesh.name, "vox");
+        assert_eq!(mesh.faces.len(), 6);
+        assert_eq!(mesh.vertices.len(), 8);
+        assert!(mesh.is_watertight());
+        assert!(points_outward(mesh));
+        assert!(mesh
+            .faces
+            .iter()
+            .all(|face| face.color == Color::Red && face.no_texture));
+
+        // 2 voxels wide, 1 deep and 2 high, standing on the position of the mesh.
+        let min = mesh
+            .vertices
+            .iter()
+            .fold(point!(0.0, 0.0, 0.0), |min, v| min.min(*v));
+        let max = mesh
+            .vertices
+            .iter()
+            .fold(point!(0.0, 0.0, 0.0), |max, v| max.max(*v));
+        assert_eq!(min, point!(-0.375, -0.5, -0.25));
+        assert_eq!(max, point!(0.125, 0.0, 0.0));
+    }
+
+    #[test]
+    fn vox_colors_and_baking() {
+        // palette index 1 is white in the default palette, 216 a bright red.
+        let bytes = vox_file([2, 1, 1], &[[0, 0, 0, 1], [1, 0, 0, 216]], None);
+        let options = VoxOptions {
+            voxel_size: 1.0,
+            bake_texture: true,
+        };
+        let model = Model::from_vox(&bytes, options).unwrap();
+        let mesh = &model.meshes[0];
+
+        // both ends, and 4 sides of each color.
+        assert_eq!(mesh.faces.len(), 10);
+        assert_eq!(mesh.vertices.len(), 12);
+        assert!(mesh.is_watertight());
+        assert!(points_outward(mesh));
+
+        for face in mesh.faces.iter() {
+            assert!(!face.no_texture);
+            assert!(face
+                .uv_texels()
+                .iter()
+                .all(|texel| model.footer.pixel(*texel) == face.color));
+        }
+        assert!(mesh.faces.iter().any(|face| face.color == Color::White));
+        assert!(mesh.faces.iter().any(|face| face.color == Color::Red));
+    }
+
+    #[test]
+    fn vox_errors() {
+        let bytes = vox_file([1, 1, 1], &[[0, 0, 0, 1]], None);
+
+        assert!(Model::from_vox(&bytes, VoxOptions::default()).is_ok());
+        assert!(Model::from_vox(b"picocad;", VoxOptions::default()).is_err());
+        assert!(Model::from_vox(&bytes[..bytes.len() - 2], VoxOptions::default()).is_err());
+
+        // voxels outside of the model are skipped.
+        let bytes = vox_file([1, 1, 1], &[[0, 0, 0, 1], [5, 0, 0, 1]], None);
+        let model = Model::from_vox(&bytes, VoxOptions::default()).unwrap();
+        assert_eq!(model.meshes[0].faces.len(), 6);
+
+        assert_eq!(default_palette()[1], [0xff, 0xff, 0xff]);
+        assert_eq!(default_palette()[215], [0, 0, 0x33]);
+        assert_eq!(default_palette()[255], [0x11, 0x11, 0x11]);
+    }
+}
@@ -0,0 +1,185 @@
+#![cfg(feature = "std")]
+
+//! Feeds randomly corrupted project files to everything that reads them.
+//! None of it is allowed to panic, a broken file has to end in an error at worst.
+
+use picocadrs::assets::{
+    Color, Face, Footer, Header, Mesh, Model, Point2D, Point3D, ProjectPreset,
+};
+use picocadrs::error::PicoError;
+use picocadrs::export::obj;
+use picocadrs::point;
+
+/// Characters that are likely to confuse the parsers when inserted into a file.
+const INTERESTING: &[u8] = b"{}[](),;=%'\"\\-.0123456789eEx \n\tabcnil";
+
+/// Small xorshift generator, so every run corrupts the files the same way.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, max: usize) -> usize {
+        (self.next() % max.max(1) as u64) as usize
+    }
+}
+
+fn base_file() -> String {
+    let mut model = Model::new_project("fuzz".to_string(), ProjectPreset::Plane);
+    model.meshes.push(Mesh::lathe(
+        &[point!(0.5, 0.0), point!(1.0, 1.0), point!(0.25, 2.0)],
+        5,
+    ));
+    model.meshes[0].faces[0].color = Color::Red;
+    model.to_string()
+}
+
+/// Corrupts `source` with a few random insertions, deletions and duplications.
+fn mutate(rng: &mut Rng, source: &str) -> String {
+    let mut bytes = source.as_bytes().to_vec();
+
+    for _ in 0..1 + rng.below(4) {
+        if bytes.is_empty() {
+            bytes.push(INTERESTING[rng.below(INTERESTING.len())]);
+        }
+
+        let at = rng.below(bytes.len());
+
+        match rng.below(4) {
+            0 => bytes[at] = INTERESTING[rng.below(INTERESTING.len())],
+            1 => bytes.insert(at, INTERESTING[rng.below(INTERESTING.len())]),
+            2 => {
+                let end = (at + rng.below(16)).min(bytes.len());
+                bytes.drain(at..end);
+            }
+            _ => {
+                let end = (at + rng.below(32)).min(bytes.len());
+                let copy = bytes[at..end].to_vec();
+                let to = rng.below(bytes.len());
+                bytes.splice(to..to, copy);
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Runs everything that reads from a model, the results don't matter.
+fn exercise(model: &Model) {
+    let _ = model.to_string();
+    let _ = model.stats();
+    let _ = model.texture_usage_map();
+    let _ = model.uv_overlaps();
+    let _ = model.bvh();
+    let _ = model.raycast(point!(0.0, -10.0, 0.0), point!(0.0, 1.0, 0.0));
+    let _ = model.shaded_texture(point!(1.0, -1.0, 0.5));
+    let _ = model.texture_template_image(1);
+    let _ = model.render_svg(&Default::default());
+    let _ = model.wireframe_svg(&Default::default());
+    let _ = model.uv_layout_svg();
+    let _ = obj::material_library(model, "texture.png");
+
+    for mesh in model.meshes.iter() {
+        let _ = obj::mesh_to_obj(mesh, "model.mtl");
+        let _ = mesh.edges();
+        let _ = mesh.is_watertight();
+        let _ = mesh.content_hash();
+
+        for face in mesh.faces.iter() {
+            let _ = face.normal(&mesh.vertices);
+            let _ = face.texture_colors(&model.footer);
+        }
+    }
+
+    let mut model = model.clone();
+    let _ = model.optimize(Default::default());
+    let _ = model.normalize_for_sharing();
+}
+
+#[test]
+fn corrupted_files_dont_panic() {
+    let base = base_file();
+    let mut rng = Rng(0x5eed_1234_abcd_9876);
+
+    for _ in 0..1000 {
+        let file = mutate(&mut rng, &base);
+
+        if let Ok(model) = file.parse::<Model>() {
+            exercise(&model);
+        }
+
+        let (model, _) = Model::parse_lenient(&file);
+        exercise(&model);
+    }
+}
+
+#[test]
+fn corrupted_parts_dont_panic() {
+    let model = base_file().parse::<Model>().unwrap();
+    let header = model.header.to_string();
+    let mesh = model.meshes[1].to_string();
+    let face = model.meshes[1].faces[0].to_string();
+    let mut rng = Rng(0x0dd_ba11_cafe);
+
+    for _ in 0..1000 {
+        let _ = mutate(&mut rng, &header).parse::<Header>();
+        let _ = mutate(&mut rng, &mesh).parse::<Mesh>();
+        let _ = mutate(&mut rng, &face).parse::<Face>();
+        let _ = mutate(&mut rng, "00112233").parse::<Footer>();
+    }
+}
+
+#[test]
+fn hostile_files_dont_panic() {
+    let footer = Footer::default();
+    let files = [
+        // vertex indices that don't exist
+        format!("picocad;a;16;1;0\n{{\n{{ name='a', pos={{0,0,0}}, rot={{0,0,0}}, v={{ {{0,0,0}} }}, f={{ {{1,0,7, c=99, uv={{0,0,1,1,2,2}} }} }} }}\n}}%\n{}", footer),
+        // huge and invalid numbers
+        format!("picocad;a;16;1;0\n{{\n{{ name='a', pos={{1e308,-1e308,0}}, rot={{-1e999,1e999,0}}, v={{ {{-1e999,0,0}},{{1e308,1,0}},{{0,1e999,0}} }}, f={{ {{1,2,3, uv={{1e300,-1e300,-1e999,0,0,1e999}} }} }} }}\n}}%\n{}", footer),
+        // deeply nested tables
+        format!("picocad;a;16;1;0\n{}{}%\n{}", "{".repeat(100_000), "}".repeat(100_000), footer),
+    ];
+
+    for file in files.iter() {
+        if let Ok(model) = file.parse::<Model>() {
+            exercise(&model);
+        }
+
+        let (model, _) = Model::parse_lenient(file);
+        exercise(&model);
+    }
+}
+
+#[test]
+fn hostile_code_is_not_run() {
+    let footer = Footer::default();
+    let marker = std::env::temp_dir().join(format!("picocadrs_hostile_{}", std::process::id()));
+    let command = format!("os.execute('touch {}')", marker.display());
+    let files = [
+        // commands that would run on the machine parsing the file
+        format!("picocad;a;16;1;0\n{{ {} }}%\n{}", command, footer),
+        format!("picocad;a;16;1;0\n{{\n{{ name=({}), pos={{0,0,0}}, rot={{0,0,0}}, v={{}}, f={{}} }}\n}}%\n{}", command, footer),
+        // loops that would never end
+        format!("picocad;a;16;1;0\n{{ (function() while true do end end)() }}%\n{}", footer),
+        format!("picocad;a;16;1;0\nlocal t = {{}} t[1] = t return {{ t }}%\n{}", footer),
+    ];
+
+    for file in files.iter() {
+        assert!(
+            matches!(file.parse::<Model>(), Err(PicoError::LuaSyntax(_))),
+            "{}",
+            file
+        );
+
+        let (model, _) = Model::parse_lenient(file);
+        exercise(&model);
+    }
+
+    assert!(!marker.exists());
+}
@@ -1,9 +1,14 @@
-use std::ffi::OsString;
+#![cfg(feature = "std")]
+// The readme example is kept exactly as it appears in the readme.
+#![allow(clippy::get_first, clippy::print_with_newline)]
+
 use picocadrs::assets::{Color, Model, Point3D}; // Point3D required for point macro
 use picocadrs::point;
+use std::ffi::OsString;
 
 #[test]
 #[ignore]
+#[rustfmt::skip]
 fn readme() {
     // Loads the file "test.txt" located in the picoCAD project folder as a model.
     // This model now can access any part of that project.